@@ -0,0 +1,334 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Section automation lanes and recording.
+//!
+//! Each song section can carry automation lanes for named parameters.
+//! With automation record armed, parameter moves from mapped controllers are
+//! captured relative to the section start, thinned, and written into the
+//! section's lanes so the arrangement replays them.
+
+use std::collections::BTreeMap;
+
+use crate::control::ControlAction;
+
+use super::song::{SongMode, SongPlayer};
+
+/// A single automation point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutomationPoint {
+    /// Tick relative to the section start
+    pub tick: u64,
+    /// Parameter value
+    pub value: f64,
+}
+
+impl AutomationPoint {
+    /// Create a new point
+    pub fn new(tick: u64, value: f64) -> Self {
+        Self { tick, value }
+    }
+}
+
+/// Automation for one parameter within a section
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutomationLane {
+    /// Target parameter name
+    target: String,
+    /// Points sorted by tick
+    points: Vec<AutomationPoint>,
+}
+
+impl AutomationLane {
+    /// Create an empty lane
+    pub fn new(target: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            points: Vec::new(),
+        }
+    }
+
+    /// Get target parameter name
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Get points
+    pub fn points(&self) -> &[AutomationPoint] {
+        &self.points
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Add a point, replacing any point at the same tick
+    pub fn add_point(&mut self, point: AutomationPoint) {
+        match self.points.binary_search_by_key(&point.tick, |p| p.tick) {
+            Ok(i) => self.points[i] = point,
+            Err(i) => self.points.insert(i, point),
+        }
+    }
+
+    /// Replace everything between the first and last of `points` with them
+    pub fn overwrite(&mut self, points: &[AutomationPoint]) {
+        let (Some(first), Some(last)) = (points.first(), points.last()) else {
+            return;
+        };
+        self.points.retain(|p| p.tick < first.tick || p.tick > last.tick);
+        for &point in points {
+            self.add_point(point);
+        }
+    }
+
+    /// Get the value at a tick (linear between points, held at the ends)
+    pub fn value_at(&self, tick: u64) -> Option<f64> {
+        let first = self.points.first()?;
+        if tick <= first.tick {
+            return Some(first.value);
+        }
+        match self.points.binary_search_by_key(&tick, |p| p.tick) {
+            Ok(i) => Some(self.points[i].value),
+            Err(i) if i >= self.points.len() => self.points.last().map(|p| p.value),
+            Err(i) => Some(interpolate(&self.points[i - 1], &self.points[i], tick)),
+        }
+    }
+
+    /// Clear all points
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+}
+
+/// Linear interpolation between two points
+fn interpolate(a: &AutomationPoint, b: &AutomationPoint, tick: u64) -> f64 {
+    if b.tick == a.tick {
+        return b.value;
+    }
+    let t = (tick - a.tick) as f64 / (b.tick - a.tick) as f64;
+    a.value + (b.value - a.value) * t
+}
+
+/// Drop points that lie on the line between their neighbours (within tolerance)
+/// or closer than `min_spacing` ticks to the previously kept point.
+pub fn thin_points(points: &[AutomationPoint], tolerance: f64, min_spacing: u64) -> Vec<AutomationPoint> {
+    if points.len() <= 2 {
+        return points.to_vec();
+    }
+
+    let mut kept = vec![points[0]];
+    for i in 1..points.len() - 1 {
+        let prev = *kept.last().unwrap();
+        let point = points[i];
+        let next = points[i + 1];
+
+        let too_close = point.tick - prev.tick < min_spacing;
+        let on_line = (interpolate(&prev, &next, point.tick) - point.value).abs() <= tolerance;
+        if !too_close && !on_line {
+            kept.push(point);
+        }
+    }
+    kept.push(points[points.len() - 1]);
+    kept
+}
+
+/// Captures parameter moves into section automation lanes
+#[derive(Debug, Clone)]
+pub struct AutomationRecorder {
+    /// Whether recording is armed
+    armed: bool,
+    /// Thinning tolerance in parameter units
+    tolerance: f64,
+    /// Minimum ticks between recorded points
+    min_spacing: u64,
+    /// Current pass: section index and raw points per parameter
+    pass: Option<(usize, BTreeMap<String, Vec<AutomationPoint>>)>,
+}
+
+impl AutomationRecorder {
+    /// Create a new (disarmed) recorder
+    pub fn new() -> Self {
+        Self {
+            armed: false,
+            tolerance: 0.01,
+            min_spacing: 1,
+            pass: None,
+        }
+    }
+
+    /// Check if armed
+    pub fn is_armed(&self) -> bool {
+        self.armed
+    }
+
+    /// Arm or disarm. Disarming writes the pending pass.
+    pub fn set_armed(&mut self, armed: bool, player: &mut SongPlayer) {
+        if !armed {
+            self.commit(player);
+        }
+        self.armed = armed;
+    }
+
+    /// Toggle armed state
+    pub fn toggle(&mut self, player: &mut SongPlayer) {
+        self.set_armed(!self.armed, player);
+    }
+
+    /// Set thinning tolerance
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance.max(0.0);
+    }
+
+    /// Set minimum ticks between points
+    pub fn set_min_spacing(&mut self, ticks: u64) {
+        self.min_spacing = ticks;
+    }
+
+    /// Number of raw points captured in the current pass
+    pub fn pending_points(&self) -> usize {
+        self.pass
+            .as_ref()
+            .map(|(_, lanes)| lanes.values().map(Vec::len).sum())
+            .unwrap_or(0)
+    }
+
+    /// Capture a parameter move at the player's position. Returns true if recorded.
+    pub fn capture(&mut self, action: &ControlAction, player: &mut SongPlayer) -> bool {
+        let Some((name, value)) = action.automation_target() else {
+            return false;
+        };
+        if !self.armed || player.mode() == SongMode::Stopped || player.is_recording() {
+            return false;
+        }
+
+        // Crossing into a new section finishes the previous pass
+        let section = player.current_section();
+        if matches!(self.pass, Some((s, _)) if s != section) {
+            self.commit(player);
+        }
+
+        let tick = player.section_tick();
+        let (_, lanes) = self.pass.get_or_insert_with(|| (section, BTreeMap::new()));
+        lanes
+            .entry(name)
+            .or_default()
+            .push(AutomationPoint::new(tick, value));
+        true
+    }
+
+    /// Thin the current pass and write it into its section
+    pub fn commit(&mut self, player: &mut SongPlayer) {
+        let Some((index, lanes)) = self.pass.take() else {
+            return;
+        };
+        let Some(section) = player.get_section_mut(index) else {
+            return;
+        };
+        for (target, points) in lanes {
+            let thinned = thin_points(&points, self.tolerance, self.min_spacing);
+            section.lane_mut(&target).overwrite(&thinned);
+        }
+    }
+}
+
+impl Default for AutomationRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrangement::{Song, SongSection};
+
+    #[test]
+    fn test_lane_value_at() {
+        let mut lane = AutomationLane::new("cutoff");
+        assert_eq!(lane.value_at(0), None);
+
+        lane.add_point(AutomationPoint::new(0, 0.0));
+        lane.add_point(AutomationPoint::new(96, 1.0));
+        assert_eq!(lane.value_at(48), Some(0.5));
+        assert_eq!(lane.value_at(200), Some(1.0));
+    }
+
+    #[test]
+    fn test_thin_points() {
+        // A straight ramp collapses to its end points
+        let ramp: Vec<_> = (0..=10)
+            .map(|i| AutomationPoint::new(i * 4, i as f64 * 0.1))
+            .collect();
+        let thinned = thin_points(&ramp, 0.01, 1);
+        assert_eq!(thinned, vec![ramp[0], ramp[10]]);
+
+        // A corner survives
+        let corner = vec![
+            AutomationPoint::new(0, 0.0),
+            AutomationPoint::new(10, 1.0),
+            AutomationPoint::new(20, 0.0),
+        ];
+        assert_eq!(thin_points(&corner, 0.01, 1).len(), 3);
+    }
+
+    #[test]
+    fn test_overwrite_range() {
+        let mut lane = AutomationLane::new("cutoff");
+        for tick in [0, 24, 48, 72] {
+            lane.add_point(AutomationPoint::new(tick, 0.2));
+        }
+        lane.overwrite(&[AutomationPoint::new(20, 0.9), AutomationPoint::new(50, 0.8)]);
+        let ticks: Vec<u64> = lane.points().iter().map(|p| p.tick).collect();
+        assert_eq!(ticks, vec![0, 20, 50, 72]);
+    }
+
+    #[test]
+    fn test_record_into_section() {
+        let song = Song::new("Test")
+            .with_section(SongSection::new("Intro", 1))
+            .with_section(SongSection::new("Verse", 1));
+        let mut player = SongPlayer::new(24);
+        player.load(song);
+        player.play();
+
+        let mut recorder = AutomationRecorder::new();
+        let sweep = |v: f64| ControlAction::SetParameter("cutoff".to_string(), v);
+
+        // Not armed: nothing recorded
+        assert!(!recorder.capture(&sweep(0.1), &mut player));
+
+        recorder.toggle(&mut player);
+        player.update(12);
+        assert!(recorder.capture(&sweep(0.0), &mut player));
+        player.update(12);
+        assert!(recorder.capture(&sweep(0.5), &mut player));
+        player.update(12);
+        assert!(recorder.capture(&sweep(1.0), &mut player));
+
+        // Moving into the next section commits the intro pass
+        player.update(72);
+        assert!(recorder.capture(&sweep(0.3), &mut player));
+        let intro = player.get_section(0).unwrap().lane("cutoff").unwrap();
+        assert_eq!(
+            intro.points(),
+            &[AutomationPoint::new(12, 0.0), AutomationPoint::new(36, 1.0)]
+        );
+
+        // Track probability moves record into their own lane
+        assert!(recorder.capture(&ControlAction::SetTrackProbability(2, 0.25), &mut player));
+
+        recorder.toggle(&mut player);
+        let verse = player.get_section(1).unwrap().lane("cutoff").unwrap();
+        assert_eq!(verse.points(), &[AutomationPoint::new(12, 0.3)]);
+        let probability = player.get_section(1).unwrap().lane("track3.play_probability").unwrap();
+        assert_eq!(probability.points(), &[AutomationPoint::new(12, 0.25)]);
+        assert_eq!(
+            player.automation_values(),
+            vec![
+                ("cutoff".to_string(), 0.3),
+                ("track3.play_probability".to_string(), 0.25)
+            ]
+        );
+    }
+}
@@ -0,0 +1,580 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Two songs playing side by side, DJ style.
+//!
+//! Each deck is a song of its own (tracks, parts and a generator context)
+//! with its own transport, but both follow one master clock: a deck that
+//! starts does so on a bar line of the master, so the two stay in phase.
+//! The crossfader sets how loud each deck is, by scaling note velocities
+//! or by sending channel volume, so a set can blend from one song into the
+//! next.
+
+use std::f64::consts::FRAC_PI_2;
+
+use super::part::{PartManager, TrackClipState};
+use crate::generators::GeneratorContext;
+use crate::sequencer::scheduler::MidiMessageType;
+use crate::sequencer::track::TrackManager;
+use crate::sequencer::{FillState, ScheduledEvent, SequencerTiming};
+
+/// Channel volume controller
+const CC_VOLUME: u8 = 7;
+
+/// One side of the mixer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeckSide {
+    /// Left deck (crossfader at 0.0)
+    A,
+    /// Right deck (crossfader at 1.0)
+    B,
+}
+
+impl DeckSide {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "a" | "left" | "1" => Some(DeckSide::A),
+            "b" | "right" | "2" => Some(DeckSide::B),
+            _ => None,
+        }
+    }
+
+    /// The other deck
+    pub fn other(&self) -> Self {
+        match self {
+            DeckSide::A => DeckSide::B,
+            DeckSide::B => DeckSide::A,
+        }
+    }
+}
+
+/// How the crossfader position maps to each deck's level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossfadeCurve {
+    /// Straight line: each deck at half level in the middle
+    Linear,
+    /// Constant loudness through the fade (about 0.7 each in the middle)
+    EqualPower,
+    /// Both decks full until the fader hits an end
+    Cut,
+}
+
+impl Default for CrossfadeCurve {
+    fn default() -> Self {
+        CrossfadeCurve::EqualPower
+    }
+}
+
+impl CrossfadeCurve {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "linear" => Some(CrossfadeCurve::Linear),
+            "equal_power" | "equal-power" | "power" => Some(CrossfadeCurve::EqualPower),
+            "cut" | "scratch" => Some(CrossfadeCurve::Cut),
+            _ => None,
+        }
+    }
+}
+
+/// What the crossfader changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossfadeMode {
+    /// Scale the velocity of each deck's notes
+    Velocity,
+    /// Send channel volume (CC7) on each deck's channels
+    Volume,
+}
+
+impl Default for CrossfadeMode {
+    fn default() -> Self {
+        CrossfadeMode::Velocity
+    }
+}
+
+impl CrossfadeMode {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "velocity" | "vel" => Some(CrossfadeMode::Velocity),
+            "volume" | "cc7" => Some(CrossfadeMode::Volume),
+            _ => None,
+        }
+    }
+}
+
+/// Crossfader between deck A (0.0) and deck B (1.0)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Crossfader {
+    /// Position (0.0 = all A, 1.0 = all B)
+    position: f64,
+    /// Level curve
+    curve: CrossfadeCurve,
+    /// What the levels are applied to
+    mode: CrossfadeMode,
+}
+
+impl Default for Crossfader {
+    fn default() -> Self {
+        Self {
+            position: 0.0,
+            curve: CrossfadeCurve::default(),
+            mode: CrossfadeMode::default(),
+        }
+    }
+}
+
+impl Crossfader {
+    /// Create a crossfader all the way to deck A
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the level curve
+    pub fn with_curve(mut self, curve: CrossfadeCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Set what the levels are applied to
+    pub fn with_mode(mut self, mode: CrossfadeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get position
+    pub fn position(&self) -> f64 {
+        self.position
+    }
+
+    /// Set position (0.0 = all A, 1.0 = all B)
+    pub fn set_position(&mut self, position: f64) {
+        self.position = position.clamp(0.0, 1.0);
+    }
+
+    /// Get the curve
+    pub fn curve(&self) -> CrossfadeCurve {
+        self.curve
+    }
+
+    /// Get the mode
+    pub fn mode(&self) -> CrossfadeMode {
+        self.mode
+    }
+
+    /// Level of a deck (0.0 to 1.0)
+    pub fn gain(&self, side: DeckSide) -> f64 {
+        let p = match side {
+            DeckSide::A => 1.0 - self.position,
+            DeckSide::B => self.position,
+        };
+        match self.curve {
+            CrossfadeCurve::Linear => p,
+            CrossfadeCurve::EqualPower => (p * FRAC_PI_2).sin(),
+            CrossfadeCurve::Cut => {
+                if p > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// A song with its own transport
+pub struct Deck {
+    /// Display name (usually the song name)
+    name: String,
+    tracks: TrackManager,
+    parts: PartManager,
+    context: GeneratorContext,
+    /// Master tick the deck's tick 0 fell on (None = stopped)
+    started_at: Option<u64>,
+    /// Master tick a queued start happens on
+    pending_start: Option<u64>,
+    /// Part whose track states were last applied
+    applied: Option<String>,
+    /// Requested fill, in the deck's own bars
+    fill: FillState,
+}
+
+impl Deck {
+    /// Create a stopped deck
+    pub fn new(name: impl Into<String>, tracks: TrackManager, parts: PartManager) -> Self {
+        Self {
+            name: name.into(),
+            tracks,
+            parts,
+            context: GeneratorContext::default(),
+            started_at: None,
+            pending_start: None,
+            applied: None,
+            fill: FillState::new(),
+        }
+    }
+
+    /// Set the generator context (tempo, key, meter)
+    pub fn with_context(mut self, context: GeneratorContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Get name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the tracks
+    pub fn tracks(&self) -> &TrackManager {
+        &self.tracks
+    }
+
+    /// Get the mutable tracks
+    pub fn tracks_mut(&mut self) -> &mut TrackManager {
+        &mut self.tracks
+    }
+
+    /// Get the parts
+    pub fn parts(&self) -> &PartManager {
+        &self.parts
+    }
+
+    /// Get the generator context
+    pub fn context(&self) -> &GeneratorContext {
+        &self.context
+    }
+
+    /// Check if playing
+    pub fn is_playing(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    /// Master tick a queued start happens on
+    pub fn pending_start(&self) -> Option<u64> {
+        self.pending_start
+    }
+
+    /// Deck position in its own ticks at a master tick
+    pub fn position(&self, master_tick: u64) -> Option<u64> {
+        self.started_at.map(|start| master_tick.saturating_sub(start))
+    }
+
+    /// Start on the next bar line of the master clock (now, if on one)
+    pub fn start(&mut self, master_tick: u64) -> u64 {
+        let bar = self.context.ticks_per_bar().max(1);
+        let at = master_tick.div_ceil(bar) * bar;
+        self.pending_start = Some(at);
+        at
+    }
+
+    /// Stop and rewind
+    pub fn stop(&mut self) {
+        self.started_at = None;
+        self.pending_start = None;
+        self.applied = None;
+        self.fill.cancel();
+        self.tracks.reset_all();
+    }
+
+    /// Timing at a position in the deck's own ticks
+    fn timing(&self, local: u64) -> SequencerTiming {
+        SequencerTiming {
+            tempo: self.context.tempo,
+            ppqn: self.context.ppqn.max(1),
+            position_ticks: local,
+            beats_per_bar: self.context.beats_per_bar.max(1),
+            ..Default::default()
+        }
+    }
+
+    /// Play a fill in the current bar (the next one if already in its last beat)
+    pub fn request_fill(&mut self, master_tick: u64) {
+        let local = self.position(master_tick).unwrap_or(0);
+        self.fill.request(&self.timing(local));
+    }
+
+    /// Queue a part on the deck's own bar lines
+    pub fn trigger_part(&mut self, name: &str, master_tick: u64) -> bool {
+        let local = self.position(master_tick).unwrap_or(0);
+        let ppqn = self.context.ppqn;
+        let beats = self.context.beats_per_bar as u32;
+        self.parts.trigger_part(name, local, ppqn, beats)
+    }
+
+    /// Launch the clips of a part that just became current
+    fn apply_part(&mut self) {
+        self.parts.revert_expired(&mut self.tracks);
+        let Some(part) = self.parts.current() else {
+            return;
+        };
+        if self.applied.as_deref() == Some(part.name()) {
+            return;
+        }
+        self.applied = Some(part.name().to_string());
+        for (&index, state) in part.track_states() {
+            let Some(track) = self.tracks.track_mut(index) else {
+                continue;
+            };
+            match state {
+                TrackClipState::Clip(clip) => {
+                    track.set_active_clip(Some(*clip));
+                    if let Some(clip) = track.active_clip_mut() {
+                        clip.reset();
+                        clip.play();
+                    }
+                }
+                TrackClipState::Stop => track.set_active_clip(None),
+                _ => {}
+            }
+        }
+    }
+
+    /// Advance to a master tick, returning the events generated on it.
+    /// Events are placed on the master timeline.
+    pub fn tick(&mut self, master_tick: u64) -> Vec<ScheduledEvent> {
+        if let Some(at) = self.pending_start.filter(|&at| at <= master_tick) {
+            self.pending_start = None;
+            self.started_at = Some(at);
+        }
+        let Some(local) = self.position(master_tick) else {
+            return Vec::new();
+        };
+
+        self.parts.update(local);
+        self.apply_part();
+
+        let ppqn = self.context.ppqn.max(1) as u64;
+        if local % ppqn != 0 {
+            return Vec::new();
+        }
+        let beat = local / ppqn;
+        let beats_per_bar = self.context.beats_per_bar.max(1) as u64;
+        self.context.bar = beat / beats_per_bar;
+        self.context.beat = beat % beats_per_bar;
+        self.context.ticks_to_generate = ppqn;
+        self.fill.update(&self.timing(local));
+        self.context.fill = self.fill.is_active(self.context.bar);
+        self.tracks.generate_all(&self.context, master_tick)
+    }
+
+    /// Channels the deck plays on, with their destinations
+    pub fn channels(&self) -> Vec<(u8, Option<usize>)> {
+        let mut channels = Vec::new();
+        for index in 0..self.tracks.track_count() {
+            if let Some(track) = self.tracks.track(index) {
+                let channel = (track.channel(), track.destination());
+                if !channels.contains(&channel) {
+                    channels.push(channel);
+                }
+            }
+        }
+        channels
+    }
+}
+
+/// Two decks and a crossfader on one clock
+pub struct DeckMixer {
+    a: Deck,
+    b: Deck,
+    crossfader: Crossfader,
+}
+
+impl DeckMixer {
+    /// Create a mixer with the fader on deck A
+    pub fn new(a: Deck, b: Deck) -> Self {
+        Self {
+            a,
+            b,
+            crossfader: Crossfader::new(),
+        }
+    }
+
+    /// Set the crossfader
+    pub fn with_crossfader(mut self, crossfader: Crossfader) -> Self {
+        self.crossfader = crossfader;
+        self
+    }
+
+    /// Get a deck
+    pub fn deck(&self, side: DeckSide) -> &Deck {
+        match side {
+            DeckSide::A => &self.a,
+            DeckSide::B => &self.b,
+        }
+    }
+
+    /// Get a mutable deck
+    pub fn deck_mut(&mut self, side: DeckSide) -> &mut Deck {
+        match side {
+            DeckSide::A => &mut self.a,
+            DeckSide::B => &mut self.b,
+        }
+    }
+
+    /// Swap the song on one side (e.g. loading the next song on the deck
+    /// that has been faded out). Returns the old deck.
+    pub fn load(&mut self, side: DeckSide, deck: Deck) -> Deck {
+        std::mem::replace(self.deck_mut(side), deck)
+    }
+
+    /// Get the crossfader
+    pub fn crossfader(&self) -> &Crossfader {
+        &self.crossfader
+    }
+
+    /// Move the crossfader, returning the volume changes to send (none in
+    /// velocity mode)
+    pub fn set_crossfade(&mut self, position: f64, tick: u64) -> Vec<ScheduledEvent> {
+        self.crossfader.set_position(position);
+        self.volume_events(tick)
+    }
+
+    /// Channel volume for both decks at the current fader position
+    pub fn volume_events(&self, tick: u64) -> Vec<ScheduledEvent> {
+        if self.crossfader.mode() != CrossfadeMode::Volume {
+            return Vec::new();
+        }
+        [DeckSide::A, DeckSide::B]
+            .into_iter()
+            .flat_map(|side| {
+                let volume = (self.crossfader.gain(side) * 127.0).round() as u8;
+                self.deck(side).channels().into_iter().map(move |(channel, destination)| {
+                    ScheduledEvent::control_change(tick, channel, CC_VOLUME, volume).with_destination(destination)
+                })
+            })
+            .collect()
+    }
+
+    /// Advance both decks to a master tick, returning their events with
+    /// the crossfader applied
+    pub fn tick(&mut self, master_tick: u64) -> Vec<ScheduledEvent> {
+        let mut events = Vec::new();
+        for side in [DeckSide::A, DeckSide::B] {
+            let gain = self.crossfader.gain(side);
+            let scale = self.crossfader.mode() == CrossfadeMode::Velocity;
+            for mut event in self.deck_mut(side).tick(master_tick) {
+                if scale && event.message_type == MidiMessageType::NoteOn {
+                    // A faded-out note is dropped; velocity 0 would be a note off
+                    let velocity = (event.data2 as f64 * gain).round() as u8;
+                    if velocity == 0 {
+                        continue;
+                    }
+                    event.data2 = velocity;
+                }
+                events.push(event);
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrangement::Part;
+    use crate::sequencer::track::TrackConfig;
+    use crate::sequencer::{Clip, ClipNote};
+
+    /// A deck with one track on a channel playing a note every beat
+    fn deck(name: &str, channel: u8, note: u8) -> Deck {
+        let mut tracks = TrackManager::new();
+        let index = tracks.add_track(TrackConfig {
+            name: name.to_string(),
+            channel,
+            ..TrackConfig::default()
+        });
+        let mut clip = Clip::new(name, 96);
+        clip.add_notes((0..4).map(|beat| ClipNote::new(beat * 24, 12, note, 100)));
+        tracks.track_mut(index).unwrap().add_clip(clip);
+        let mut parts = PartManager::new(1);
+        parts.add_part(Part::new("Main").with_track(index, TrackClipState::Clip(0)));
+        let mut deck = Deck::new(name, tracks, parts);
+        deck.trigger_part("Main", 0);
+        deck
+    }
+
+    fn note_ons(events: &[ScheduledEvent]) -> Vec<(u64, u8, u8)> {
+        events
+            .iter()
+            .filter(|e| e.message_type == MidiMessageType::NoteOn)
+            .map(|e| (e.time_ticks, e.channel, e.data2))
+            .collect()
+    }
+
+    #[test]
+    fn test_crossfader_curves() {
+        let mut fader = Crossfader::new().with_curve(CrossfadeCurve::Linear);
+        assert_eq!((fader.gain(DeckSide::A), fader.gain(DeckSide::B)), (1.0, 0.0));
+        fader.set_position(0.25);
+        assert_eq!((fader.gain(DeckSide::A), fader.gain(DeckSide::B)), (0.75, 0.25));
+
+        let mut fader = Crossfader::new();
+        fader.set_position(0.5);
+        assert!((fader.gain(DeckSide::A) - fader.gain(DeckSide::B)).abs() < 1e-9);
+        assert!((fader.gain(DeckSide::A).powi(2) + fader.gain(DeckSide::B).powi(2) - 1.0).abs() < 1e-9);
+
+        let mut fader = Crossfader::new().with_curve(CrossfadeCurve::Cut);
+        fader.set_position(0.9);
+        assert_eq!((fader.gain(DeckSide::A), fader.gain(DeckSide::B)), (1.0, 1.0));
+        assert_eq!(CrossfadeCurve::from_str("equal-power"), Some(CrossfadeCurve::EqualPower));
+        assert_eq!(DeckSide::from_str("B").map(|s| s.other()), Some(DeckSide::A));
+    }
+
+    #[test]
+    fn test_decks_share_the_clock() {
+        let crossfader = Crossfader::new().with_curve(CrossfadeCurve::Linear);
+        let mut mixer = DeckMixer::new(deck("A", 0, 60), deck("B", 1, 72)).with_crossfader(crossfader);
+        mixer.deck_mut(DeckSide::A).start(0);
+        mixer.set_crossfade(0.5, 0);
+
+        let mut events = Vec::new();
+        for tick in 0..30 {
+            events.extend(mixer.tick(tick));
+        }
+        // Deck B starts on the next bar line of the master clock
+        assert_eq!(mixer.deck_mut(DeckSide::B).start(30), 96);
+        for tick in 30..120 {
+            events.extend(mixer.tick(tick));
+        }
+        assert_eq!(mixer.deck(DeckSide::B).position(120), Some(24));
+
+        let ons = note_ons(&events);
+        assert_eq!(&ons[..2], &[(0, 0, 50), (24, 0, 50)]);
+        assert!(ons.contains(&(96, 1, 50)));
+        assert!(ons.contains(&(96, 0, 50)));
+
+        // All the way to B silences A's notes
+        mixer.set_crossfade(1.0, 120);
+        let ons = note_ons(&mixer.tick(192));
+        assert_eq!(ons.iter().map(|&(_, channel, velocity)| (channel, velocity)).collect::<Vec<_>>(), vec![(1, 100)]);
+    }
+
+    #[test]
+    fn test_volume_mode() {
+        let crossfader = Crossfader::new().with_mode(CrossfadeMode::Volume);
+        let mut mixer = DeckMixer::new(deck("A", 0, 60), deck("B", 3, 72)).with_crossfader(crossfader);
+        let sent: Vec<Vec<u8>> = mixer.set_crossfade(1.0, 0).iter().map(|e| e.to_midi_bytes()).collect();
+        assert_eq!(sent, vec![vec![0xB0, 7, 0], vec![0xB3, 7, 127]]);
+
+        // Notes play at full velocity; the volume does the fading
+        mixer.deck_mut(DeckSide::A).start(0);
+        assert_eq!(note_ons(&mixer.tick(0)), vec![(0, 0, 100)]);
+    }
+
+    #[test]
+    fn test_fill_reaches_the_context() {
+        let mut deck = deck("A", 0, 60);
+        deck.start(0);
+        deck.tick(0);
+        deck.request_fill(30);
+
+        deck.tick(48);
+        assert!(deck.context().fill);
+
+        // Over once the bar is done
+        deck.tick(96);
+        assert!(!deck.context().fill);
+    }
+}
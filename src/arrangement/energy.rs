@@ -0,0 +1,261 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! One-knob set energy.
+//!
+//! Energy is a single control from 0.0 to 1.0 that moves several things at
+//! once, so a generative set can be pushed up or brought down from one
+//! fader: the tempo within bounds, how many layers play, and the intensity
+//! parameters of the generators (drum hits, melody rests and so on). An
+//! energy policy says what moves and how far. Built-in heuristics cover the
+//! stock generators, and a song can add or replace mappings of its own.
+//!
+//! Layers enter in their listed order as energy rises and drop out in
+//! reverse. A layer only switches when the energy crosses its threshold,
+//! so a track muted or unmuted by hand stays that way until the knob
+//! passes its point again.
+
+use crate::sequencer::track::TrackManager;
+use crate::sequencer::TrackState;
+
+/// Parameters moved by the built-in heuristics: generator, parameter, and
+/// the values at no energy and at full energy
+pub const ENERGY_HEURISTICS: &[(&str, &str, f64, f64)] = &[
+    ("drums", "kick_euclidean_hits", 2.0, 6.0),
+    ("drums", "snare_euclidean_hits", 1.0, 4.0),
+    ("drums", "hat_euclidean_hits", 4.0, 16.0),
+    ("drums", "fill_probability", 0.1, 0.8),
+    ("melody", "rest_probability", 0.4, 0.05),
+    ("melody", "rhythmic_complexity", 0.2, 0.8),
+    ("arpeggio", "probability", 0.5, 1.0),
+];
+
+/// A generator parameter that follows the energy
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnergyParam {
+    /// Track index
+    pub track: usize,
+    /// Generator parameter name
+    pub param: String,
+    /// Value at no energy
+    pub low: f64,
+    /// Value at full energy
+    pub high: f64,
+}
+
+impl EnergyParam {
+    /// Create a mapping
+    pub fn new(track: usize, param: impl Into<String>, low: f64, high: f64) -> Self {
+        Self {
+            track,
+            param: param.into(),
+            low,
+            high,
+        }
+    }
+
+    /// Value at an energy level
+    pub fn value(&self, energy: f64) -> f64 {
+        self.low + (self.high - self.low) * energy.clamp(0.0, 1.0)
+    }
+}
+
+/// What the energy changes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnergyPolicy {
+    /// Tempo at no energy and at full energy
+    tempo: Option<(f64, f64)>,
+    /// Tracks in the order they enter
+    layers: Vec<usize>,
+    /// Generator parameters to move
+    params: Vec<EnergyParam>,
+}
+
+impl EnergyPolicy {
+    /// Create a policy that changes nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the tempo between two bounds
+    pub fn with_tempo(mut self, low: f64, high: f64) -> Self {
+        self.tempo = Some((low.clamp(20.0, 300.0), high.clamp(20.0, 300.0)));
+        self
+    }
+
+    /// Set the tracks that enter as energy rises, first to last
+    pub fn with_layers(mut self, layers: Vec<usize>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    /// Add a parameter mapping, replacing any for the same parameter
+    pub fn with_param(mut self, param: EnergyParam) -> Self {
+        self.params.retain(|p| p.track != param.track || p.param != param.param);
+        self.params.push(param);
+        self
+    }
+
+    /// Add the built-in mappings for a track's generator, keeping any the
+    /// policy already has
+    pub fn with_heuristics(mut self, track: usize, generator: &str) -> Self {
+        for &(name, param, low, high) in ENERGY_HEURISTICS {
+            if name == generator && !self.params.iter().any(|p| p.track == track && p.param == param) {
+                self.params.push(EnergyParam::new(track, param, low, high));
+            }
+        }
+        self
+    }
+
+    /// Get the tempo bounds
+    pub fn tempo_range(&self) -> Option<(f64, f64)> {
+        self.tempo
+    }
+
+    /// Get the layers in entry order
+    pub fn layers(&self) -> &[usize] {
+        &self.layers
+    }
+
+    /// Get the parameter mappings
+    pub fn params(&self) -> &[EnergyParam] {
+        &self.params
+    }
+
+    /// Energy at which the layer at a position enters: the first always
+    /// plays, the rest are spread evenly
+    pub fn threshold(&self, position: usize) -> f64 {
+        position as f64 / self.layers.len().max(1) as f64
+    }
+
+    /// Tempo at an energy level
+    pub fn tempo_at(&self, energy: f64) -> Option<f64> {
+        self.tempo.map(|(low, high)| low + (high - low) * energy.clamp(0.0, 1.0))
+    }
+}
+
+/// What a change of energy did
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnergyChange {
+    /// Tempo to set
+    pub tempo: Option<f64>,
+    /// Tracks unmuted
+    pub entered: Vec<usize>,
+    /// Tracks muted
+    pub dropped: Vec<usize>,
+}
+
+/// The energy control of a song
+#[derive(Debug, Clone, Default)]
+pub struct Energy {
+    policy: EnergyPolicy,
+    /// Current level (None until first set)
+    level: Option<f64>,
+}
+
+impl Energy {
+    /// Create an energy control with a policy
+    pub fn new(policy: EnergyPolicy) -> Self {
+        Self { policy, level: None }
+    }
+
+    /// Get the policy
+    pub fn policy(&self) -> &EnergyPolicy {
+        &self.policy
+    }
+
+    /// Get the current level
+    pub fn level(&self) -> Option<f64> {
+        self.level
+    }
+
+    /// Move to an energy level (0.0 to 1.0), switching layers and setting
+    /// generator parameters. The tempo is returned for the caller to set.
+    pub fn set(&mut self, level: f64, tracks: &mut TrackManager) -> EnergyChange {
+        let level = level.clamp(0.0, 1.0);
+        let previous = self.level.replace(level);
+        let mut change = EnergyChange {
+            tempo: self.policy.tempo_at(level),
+            ..Default::default()
+        };
+
+        for (position, &index) in self.policy.layers.iter().enumerate() {
+            let threshold = self.policy.threshold(position);
+            let on = level >= threshold;
+            if previous.map(|p| p >= threshold) == Some(on) {
+                continue;
+            }
+            let Some(track) = tracks.track_mut(index) else {
+                continue;
+            };
+            if on && track.is_muted() {
+                track.set_state(TrackState::Active);
+                change.entered.push(index);
+            } else if !on && !track.is_muted() {
+                track.set_state(TrackState::Muted);
+                change.dropped.push(index);
+            }
+        }
+
+        for param in &self.policy.params {
+            if let Some(generator) = tracks.track_mut(param.track).and_then(|t| t.generator_mut()) {
+                generator.set_param(&param.param, param.value(level));
+            }
+        }
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::drums::DrumGenerator;
+    use crate::sequencer::track::TrackConfig;
+
+    fn tracks(count: usize) -> TrackManager {
+        let mut tracks = TrackManager::new();
+        for i in 0..count {
+            tracks.add_track(TrackConfig::new(format!("Track {}", i + 1)));
+        }
+        tracks
+    }
+
+    #[test]
+    fn test_energy_moves_tempo_layers_and_params() {
+        let mut tracks = tracks(4);
+        tracks.track_mut(0).unwrap().set_generator(DrumGenerator::create());
+        let policy = EnergyPolicy::new()
+            .with_tempo(110.0, 130.0)
+            .with_layers(vec![0, 1, 2, 3])
+            .with_heuristics(0, "drums")
+            .with_param(EnergyParam::new(0, "hat_euclidean_hits", 8.0, 12.0));
+        let mut energy = Energy::new(policy);
+
+        let change = energy.set(0.3, &mut tracks);
+        assert_eq!(change.tempo, Some(116.0));
+        assert_eq!(change.dropped, vec![2, 3]);
+        let muted: Vec<bool> = (0..4).map(|i| tracks.track(i).unwrap().is_muted()).collect();
+        assert_eq!(muted, vec![false, false, true, true]);
+        let drums = tracks.track(0).unwrap().generator().unwrap();
+        assert_eq!(drums.get_param("kick_euclidean_hits"), Some(3.0));
+        assert_eq!(drums.get_param("hat_euclidean_hits"), Some(9.0));
+
+        let change = energy.set(1.0, &mut tracks);
+        assert_eq!((change.tempo, change.entered), (Some(130.0), vec![2, 3]));
+    }
+
+    #[test]
+    fn test_layers_switch_only_on_crossing() {
+        let mut tracks = tracks(2);
+        let mut energy = Energy::new(EnergyPolicy::new().with_layers(vec![0, 1]));
+        energy.set(0.2, &mut tracks);
+        assert!(tracks.track(1).unwrap().is_muted());
+
+        // Unmuted by hand, the layer stays until the knob crosses 0.5
+        tracks.track_mut(1).unwrap().set_state(TrackState::Active);
+        assert_eq!(energy.set(0.4, &mut tracks), EnergyChange::default());
+        assert!(!tracks.track(1).unwrap().is_muted());
+        energy.set(0.6, &mut tracks);
+        assert_eq!(energy.set(0.1, &mut tracks).dropped, vec![1]);
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Tempo-synced one-shot FX (risers and impacts).
+//!
+//! An FX event is a short scripted sequence - a CC ramp, a pitch-bend rise,
+//! an accelerating noise-sweep roll or a single impact hit - that runs for a
+//! number of beats and ends on a section boundary. Parts list the FX that
+//! lead into them, so queuing a part transition launches its risers in
+//! time; FX can also be launched by hand from a controller.
+
+use std::collections::HashMap;
+
+use crate::sequencer::ScheduledEvent;
+
+/// Shape of a one-shot FX
+#[derive(Debug, Clone, PartialEq)]
+pub enum FxShape {
+    /// Sweep a CC from one value to another
+    CcRamp { cc: u8, from: u8, to: u8 },
+    /// Bend the pitch up by a fraction of the full bend range,
+    /// snapping back to center on the boundary
+    PitchRise { amount: f64 },
+    /// Repeat a note faster and louder toward the boundary
+    Sweep { note: u8, velocity: u8 },
+    /// A single hit on the boundary
+    Impact { note: u8, velocity: u8 },
+}
+
+impl FxShape {
+    /// Get the shape name
+    pub fn name(&self) -> &'static str {
+        match self {
+            FxShape::CcRamp { .. } => "cc_ramp",
+            FxShape::PitchRise { .. } => "pitch_rise",
+            FxShape::Sweep { .. } => "sweep",
+            FxShape::Impact { .. } => "impact",
+        }
+    }
+}
+
+/// A named one-shot FX
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxEvent {
+    /// FX name
+    name: String,
+    /// What the FX plays
+    shape: FxShape,
+    /// MIDI channel (0-15)
+    channel: u8,
+    /// Length in beats
+    beats: u32,
+}
+
+impl FxEvent {
+    /// Create a four-beat FX on channel 1
+    pub fn new(name: impl Into<String>, shape: FxShape) -> Self {
+        Self {
+            name: name.into(),
+            shape,
+            channel: 0,
+            beats: 4,
+        }
+    }
+
+    /// Set MIDI channel
+    pub fn with_channel(mut self, channel: u8) -> Self {
+        self.channel = channel.min(15);
+        self
+    }
+
+    /// Set length in beats
+    pub fn with_beats(mut self, beats: u32) -> Self {
+        self.beats = beats.max(1);
+        self
+    }
+
+    /// Get FX name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get FX shape
+    pub fn shape(&self) -> &FxShape {
+        &self.shape
+    }
+
+    /// Get MIDI channel
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Get length in beats
+    pub fn beats(&self) -> u32 {
+        self.beats
+    }
+
+    /// Get length in ticks
+    pub fn length_ticks(&self, ppqn: u32) -> u64 {
+        self.beats as u64 * ppqn.max(1) as u64
+    }
+
+    /// Events for a run from `start` to the boundary at `end` (absolute ticks)
+    pub fn render(&self, start: u64, end: u64, ppqn: u32) -> Vec<ScheduledEvent> {
+        let ppqn = ppqn.max(1) as u64;
+        let span = end.saturating_sub(start);
+        let progress = |tick: u64| {
+            if span == 0 {
+                1.0
+            } else {
+                (tick - start) as f64 / span as f64
+            }
+        };
+        // Ramps update on a 32nd-note grid
+        let step = (ppqn / 8).max(1);
+        let ch = self.channel;
+        let mut events = Vec::new();
+
+        match self.shape {
+            FxShape::CcRamp { cc, from, to } => {
+                let mut last = None;
+                let mut tick = start;
+                while tick < end {
+                    let value = lerp(from as f64, to as f64, progress(tick)).round() as u8;
+                    if last != Some(value) {
+                        events.push(ScheduledEvent::control_change(tick, ch, cc, value));
+                        last = Some(value);
+                    }
+                    tick += step;
+                }
+                events.push(ScheduledEvent::control_change(end, ch, cc, to));
+            }
+            FxShape::PitchRise { amount } => {
+                let top = 8191.0 * amount.clamp(0.0, 1.0);
+                let mut tick = start;
+                while tick < end {
+                    let bend = (top * progress(tick)).round() as i16;
+                    events.push(ScheduledEvent::pitch_bend(tick, ch, bend));
+                    tick += step;
+                }
+                events.push(ScheduledEvent::pitch_bend(end, ch, 0));
+            }
+            FxShape::Sweep { note, velocity } => {
+                // The gap shrinks from a beat to a 32nd as the boundary nears
+                let mut tick = start;
+                while tick < end {
+                    let p = progress(tick);
+                    let vel = lerp(velocity as f64 * 0.4, velocity as f64, p).round();
+                    let vel = vel.clamp(1.0, 127.0) as u8;
+                    let gap = (lerp(ppqn as f64, (ppqn / 8) as f64, p).round() as u64).max(1);
+                    let duration = (gap / 2).clamp(1, end - tick);
+                    events.push(ScheduledEvent::note_on(tick, ch, note, vel));
+                    events.push(ScheduledEvent::note_off(tick + duration, ch, note));
+                    tick += gap;
+                }
+            }
+            FxShape::Impact { note, velocity } => {
+                events.push(ScheduledEvent::note_on(end, ch, note, velocity.max(1)));
+                events.push(ScheduledEvent::note_off(end + ppqn, ch, note));
+            }
+        }
+        events
+    }
+}
+
+/// Linear interpolation
+fn lerp(from: f64, to: f64, t: f64) -> f64 {
+    from + (to - from) * t
+}
+
+/// Named FX available for launching
+#[derive(Debug, Clone, Default)]
+pub struct FxLibrary {
+    /// FX by name
+    events: HashMap<String, FxEvent>,
+}
+
+impl FxLibrary {
+    /// Create an empty library
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an FX (replacing one with the same name)
+    pub fn add(&mut self, event: FxEvent) {
+        self.events.insert(event.name().to_string(), event);
+    }
+
+    /// Get an FX by name
+    pub fn get(&self, name: &str) -> Option<&FxEvent> {
+        self.events.get(name)
+    }
+
+    /// Get FX names in sorted order
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.events.keys().map(|n| n.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    /// Launch an FX by hand, running its full length from `current_tick`
+    pub fn launch(&self, name: &str, current_tick: u64, ppqn: u32) -> Option<Vec<ScheduledEvent>> {
+        let event = self.get(name)?;
+        Some(event.render(current_tick, current_tick + event.length_ticks(ppqn), ppqn))
+    }
+
+    /// Launch an FX so it ends on `boundary`. When there is less time left
+    /// than the FX is long, it starts now and is compressed to fit.
+    pub fn lead_into(
+        &self,
+        name: &str,
+        boundary: u64,
+        current_tick: u64,
+        ppqn: u32,
+    ) -> Option<Vec<ScheduledEvent>> {
+        let event = self.get(name)?;
+        let start = boundary
+            .saturating_sub(event.length_ticks(ppqn))
+            .max(current_tick)
+            .min(boundary);
+        Some(event.render(start, boundary, ppqn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer::scheduler::MidiMessageType;
+
+    #[test]
+    fn test_fx_shapes() {
+        let ramp = FxEvent::new("open", FxShape::CcRamp { cc: 74, from: 0, to: 127 }).with_beats(2);
+        let events = ramp.render(0, 48, 24);
+        assert_eq!(events.first().unwrap().data2, 0);
+        assert!(events.windows(2).all(|w| w[0].data2 < w[1].data2));
+        assert_eq!(events.last().unwrap().time_ticks, 48);
+        assert_eq!(events.last().unwrap().data2, 127);
+
+        // The rise snaps back to center on the boundary
+        let rise = FxEvent::new("rise", FxShape::PitchRise { amount: 1.0 }).with_channel(2);
+        let events = rise.render(0, 96, 24);
+        let last = events.last().unwrap();
+        assert_eq!((last.time_ticks, last.channel), (96, 2));
+        assert_eq!(last.to_midi_bytes(), vec![0xE2, 0, 64]);
+
+        // The sweep speeds up toward the boundary
+        let sweep = FxEvent::new("sweep", FxShape::Sweep { note: 39, velocity: 100 });
+        let ons: Vec<u64> = sweep
+            .render(0, 96, 24)
+            .iter()
+            .filter(|e| e.message_type == MidiMessageType::NoteOn)
+            .map(|e| e.time_ticks)
+            .collect();
+        assert_eq!(ons[0], 0);
+        assert!(ons.windows(3).all(|w| w[2] - w[1] <= w[1] - w[0]));
+        assert!(*ons.last().unwrap() < 96);
+
+        let impact = FxEvent::new("hit", FxShape::Impact { note: 49, velocity: 127 });
+        assert_eq!(impact.render(0, 96, 24)[0].time_ticks, 96);
+    }
+
+    #[test]
+    fn test_lead_into_boundary() {
+        let mut library = FxLibrary::new();
+        library.add(FxEvent::new("hit", FxShape::Impact { note: 49, velocity: 127 }));
+        library.add(FxEvent::new("open", FxShape::CcRamp { cc: 74, from: 0, to: 127 }));
+        assert_eq!(library.names(), vec!["hit", "open"]);
+
+        // Plenty of time: the ramp starts four beats before the boundary
+        let events = library.lead_into("open", 384, 0, 24).unwrap();
+        assert_eq!(events[0].time_ticks, 288);
+
+        // Late: it starts now and still lands on the boundary
+        let events = library.lead_into("open", 384, 360, 24).unwrap();
+        assert_eq!(events[0].time_ticks, 360);
+        assert_eq!(events.last().unwrap().time_ticks, 384);
+
+        let manual = library.launch("open", 100, 24).unwrap();
+        assert_eq!(manual.last().unwrap().time_ticks, 196);
+        assert!(library.launch("missing", 0, 24).is_none());
+    }
+}
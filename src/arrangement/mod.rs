@@ -4,17 +4,38 @@
 //! Song and arrangement system.
 //!
 //! This module provides:
-//! - Parts: Collections of track clip/generator states
+//! - Parts: Collections of track clip/generator states, with overrides
+//!   that revert when the part changes
 //! - Scenes: Track state snapshots with matrix triggering
 //! - Song mode: Ordered arrangement playback
+//! - Automation: Per-section parameter lanes with recording
+//! - Performance capture: Live mutes and triggers recorded as a song
+//! - FX: Tempo-synced one-shot risers and impacts
+//! - Snapshots: External device patch recall per song and part
+//! - Decks: Two songs on one clock with a crossfader
+//! - Energy: One knob moving tempo, layers and generator intensity
 
+pub mod automation;
+pub mod deck;
+pub mod energy;
+pub mod fx;
 pub mod part;
+pub mod performance;
 pub mod scene;
+pub mod snapshot;
 pub mod song;
 
-pub use part::{Part, PartManager, PartTransition, TrackClipState};
+pub use automation::{AutomationLane, AutomationPoint, AutomationRecorder};
+pub use deck::{CrossfadeCurve, CrossfadeMode, Crossfader, Deck, DeckMixer, DeckSide};
+pub use energy::{Energy, EnergyChange, EnergyParam, EnergyPolicy};
+pub use fx::{FxEvent, FxLibrary, FxShape};
+pub use part::{
+    Part, PartGuard, PartManager, PartTransition, ScopedOverride, TrackClipState, TrackOverride, TriggerResult,
+};
+pub use performance::{PerformanceEvent, PerformanceRecorder};
 pub use scene::{Scene, SceneManager, SceneSlot};
-pub use song::{Song, SongMode, SongPosition, SongSection};
+pub use snapshot::{DeviceSnapshot, SnapshotValue};
+pub use song::{SectionCondition, Song, SongMode, SongPlayer, SongPosition, SongSection};
 
 #[cfg(test)]
 mod tests {
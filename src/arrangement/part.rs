@@ -5,11 +5,33 @@
 //!
 //! A Part represents a collection of track states that can be
 //! triggered together, enabling quick arrangement changes.
+//!
+//! Destructive parts can be guarded: they launch only when armed first, or
+//! when triggered twice within a beat. Parts can also require others to have
+//! played first (no Drop before the Intro).
+//!
+//! A part can name FX (risers, impacts) that lead into it; they are handed
+//! out once when a transition to the part is queued.
+//!
+//! Parts can also carry device snapshots: CC, NRPN and program values sent
+//! to external synths and mixers when the part starts.
+//!
+//! Optional layers give tracks a chance of playing, rolled each time the
+//! part is triggered, so repeats of a section vary a little.
+//!
+//! Track overrides are scoped to the current part: a solo, a mute or doubled
+//! density set during a part reverts by itself when the next part starts.
+
+use std::collections::{HashMap, HashSet};
 
-use std::collections::HashMap;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
+use crate::sequencer::track::TrackManager;
 use crate::sequencer::TrackState;
 
+use super::snapshot::DeviceSnapshot;
+
 /// State of a clip on a track within a part
 #[derive(Debug, Clone, PartialEq)]
 pub enum TrackClipState {
@@ -56,6 +78,103 @@ impl Default for PartTransition {
     }
 }
 
+/// What a part needs before a trigger launches it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartGuard {
+    /// Launch on the first trigger
+    None,
+    /// Launch only after the part has been armed
+    Arm,
+    /// Launch on a second trigger within a beat of the first
+    Confirm,
+}
+
+impl Default for PartGuard {
+    fn default() -> Self {
+        PartGuard::None
+    }
+}
+
+impl PartGuard {
+    /// Parse from a string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "none" | "off" => Some(PartGuard::None),
+            "arm" | "armed" => Some(PartGuard::Arm),
+            "confirm" | "double" => Some(PartGuard::Confirm),
+            _ => None,
+        }
+    }
+}
+
+/// Result of triggering a part
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TriggerResult {
+    /// Switched now or queued for the transition point
+    Scheduled,
+    /// No part with that name
+    Unknown,
+    /// Prerequisite parts that have not played yet
+    Locked(Vec<String>),
+    /// The part must be armed first
+    NotArmed,
+    /// First trigger of a confirm part; trigger again within a beat
+    AwaitingConfirm,
+}
+
+/// Generator parameters doubled by a density override
+const DENSITY_PARAMS: &[&str] = &["density", "kick_euclidean_hits", "snare_euclidean_hits", "hat_euclidean_hits"];
+
+/// Temporary track change that lasts until the part changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackOverride {
+    /// Solo the track
+    Solo,
+    /// Mute the track
+    Mute,
+    /// Double the generator's density (hits for drums)
+    DoubleDensity,
+}
+
+impl TrackOverride {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "solo" => Some(TrackOverride::Solo),
+            "mute" => Some(TrackOverride::Mute),
+            "double" | "double_density" | "x2" => Some(TrackOverride::DoubleDensity),
+            _ => None,
+        }
+    }
+
+    /// Short label for the display
+    pub fn label(self) -> &'static str {
+        match self {
+            TrackOverride::Solo => "solo",
+            TrackOverride::Mute => "mute",
+            TrackOverride::DoubleDensity => "x2",
+        }
+    }
+
+    /// Solo and mute replace each other; density stacks with either
+    fn replaces(self, other: TrackOverride) -> bool {
+        self == other || (self != TrackOverride::DoubleDensity && other != TrackOverride::DoubleDensity)
+    }
+}
+
+/// An override in force, with what to put back when it ends
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopedOverride {
+    /// Track index
+    pub track: usize,
+    /// The change
+    pub kind: TrackOverride,
+    /// Playback state before a solo or mute
+    pub previous_state: TrackState,
+    /// Generator parameters before a density change
+    pub previous_params: Vec<(String, f64)>,
+}
+
 /// Macro action that can be triggered
 #[derive(Debug, Clone)]
 pub enum MacroAction {
@@ -100,6 +219,16 @@ pub struct Part {
     follow_part: Option<String>,
     /// Color for UI display
     color: (u8, u8, u8),
+    /// Arming or confirmation needed to launch
+    guard: PartGuard,
+    /// Parts that must have played before this one can launch
+    requires: Vec<String>,
+    /// FX that lead into this part
+    fx: Vec<String>,
+    /// External device values recalled when the part starts
+    snapshots: Vec<DeviceSnapshot>,
+    /// Optional layers: track index -> chance (0.0-1.0) it plays
+    layers: HashMap<usize, f64>,
 }
 
 impl Part {
@@ -114,6 +243,11 @@ impl Part {
             duration_bars: None,
             follow_part: None,
             color: (128, 128, 128),
+            guard: PartGuard::None,
+            requires: Vec::new(),
+            fx: Vec::new(),
+            snapshots: Vec::new(),
+            layers: HashMap::new(),
         }
     }
 
@@ -197,6 +331,61 @@ impl Part {
         self.color
     }
 
+    /// Get launch guard
+    pub fn guard(&self) -> PartGuard {
+        self.guard
+    }
+
+    /// Set launch guard
+    pub fn set_guard(&mut self, guard: PartGuard) {
+        self.guard = guard;
+    }
+
+    /// Get prerequisite parts
+    pub fn requires(&self) -> &[String] {
+        &self.requires
+    }
+
+    /// Set prerequisite parts
+    pub fn set_requires(&mut self, parts: Vec<String>) {
+        self.requires = parts;
+    }
+
+    /// Get lead-in FX names
+    pub fn fx(&self) -> &[String] {
+        &self.fx
+    }
+
+    /// Set lead-in FX names
+    pub fn set_fx(&mut self, fx: Vec<String>) {
+        self.fx = fx;
+    }
+
+    /// Get device snapshots
+    pub fn snapshots(&self) -> &[DeviceSnapshot] {
+        &self.snapshots
+    }
+
+    /// Set device snapshots
+    pub fn set_snapshots(&mut self, snapshots: Vec<DeviceSnapshot>) {
+        self.snapshots = snapshots;
+    }
+
+    /// Make a track an optional layer that plays with some chance (0.0-1.0)
+    pub fn set_layer(&mut self, track: usize, chance: f64) {
+        self.layers.insert(track, chance.clamp(0.0, 1.0));
+    }
+
+    /// Get the chance an optional layer plays
+    pub fn layer_chance(&self, track: usize) -> Option<f64> {
+        self.layers.get(&track).copied()
+    }
+
+    /// Get all optional layers
+    pub fn layers(&self) -> &HashMap<usize, f64> {
+        &self.layers
+    }
+
     /// Builder: set track clip state
     pub fn with_track(mut self, track: usize, state: TrackClipState) -> Self {
         self.set_track_state(track, state);
@@ -226,6 +415,36 @@ impl Part {
         self.macros.push(action);
         self
     }
+
+    /// Builder: set launch guard
+    pub fn with_guard(mut self, guard: PartGuard) -> Self {
+        self.guard = guard;
+        self
+    }
+
+    /// Builder: add a prerequisite part
+    pub fn with_requirement(mut self, part: impl Into<String>) -> Self {
+        self.requires.push(part.into());
+        self
+    }
+
+    /// Builder: add a lead-in FX
+    pub fn with_fx(mut self, fx: impl Into<String>) -> Self {
+        self.fx.push(fx.into());
+        self
+    }
+
+    /// Builder: add a device snapshot
+    pub fn with_snapshot(mut self, snapshot: DeviceSnapshot) -> Self {
+        self.snapshots.push(snapshot);
+        self
+    }
+
+    /// Builder: add an optional layer
+    pub fn with_layer(mut self, track: usize, chance: f64) -> Self {
+        self.set_layer(track, chance);
+        self
+    }
 }
 
 /// Pending part transition
@@ -237,6 +456,10 @@ pub struct PendingTransition {
     pub scheduled_tick: u64,
     /// Original transition mode
     pub transition: PartTransition,
+    /// Whether the target's generators have been pre-rolled
+    pub prerolled: bool,
+    /// Whether the target's lead-in FX have been handed out
+    pub fx_launched: bool,
 }
 
 /// Manages parts and transitions
@@ -251,6 +474,20 @@ pub struct PartManager {
     pending: Option<PendingTransition>,
     /// Number of tracks
     track_count: usize,
+    /// Parts that have become current since the last history reset
+    played: HashSet<String>,
+    /// Part armed for launch
+    armed: Option<String>,
+    /// First trigger of a confirm part: (name, tick)
+    confirming: Option<(String, u64)>,
+    /// Random source for the optional layers
+    rng: StdRng,
+    /// Optional layers of the last triggered part: (track, plays)
+    layer_rolls: Vec<(usize, bool)>,
+    /// Overrides in force for the current part
+    overrides: Vec<ScopedOverride>,
+    /// Overrides whose part has ended, waiting to be reverted
+    expired: Vec<ScopedOverride>,
 }
 
 impl PartManager {
@@ -262,9 +499,21 @@ impl PartManager {
             current_part: None,
             pending: None,
             track_count,
+            played: HashSet::new(),
+            armed: None,
+            confirming: None,
+            rng: StdRng::from_entropy(),
+            layer_rolls: Vec::new(),
+            overrides: Vec::new(),
+            expired: Vec::new(),
         }
     }
 
+    /// Seed the layer rolls, for repeatable variation
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
     /// Add a part
     pub fn add_part(&mut self, part: Part) {
         let name = part.name().to_string();
@@ -305,32 +554,131 @@ impl PartManager {
         self.current_part.as_ref().and_then(|n| self.parts.get(n))
     }
 
-    /// Trigger a part transition
+    /// Arm a part so its next trigger launches it
+    pub fn arm_part(&mut self, name: &str) -> bool {
+        if !self.parts.contains_key(name) {
+            return false;
+        }
+        self.armed = Some(name.to_string());
+        true
+    }
+
+    /// Disarm the armed part
+    pub fn disarm(&mut self) {
+        self.armed = None;
+    }
+
+    /// Get the armed part
+    pub fn armed_part(&self) -> Option<&str> {
+        self.armed.as_deref()
+    }
+
+    /// Check if a part has played since the last history reset
+    pub fn has_played(&self, name: &str) -> bool {
+        self.played.contains(name)
+    }
+
+    /// Prerequisites of a part that have not played yet
+    pub fn missing_requirements(&self, name: &str) -> Vec<String> {
+        self.parts
+            .get(name)
+            .map(|part| {
+                part.requires()
+                    .iter()
+                    .filter(|r| !self.played.contains(*r))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Check if a part can launch (its prerequisites have played)
+    pub fn is_available(&self, name: &str) -> bool {
+        self.parts.contains_key(name) && self.missing_requirements(name).is_empty()
+    }
+
+    /// Forget which parts have played (e.g. when the song restarts)
+    pub fn reset_history(&mut self) {
+        self.played.clear();
+        self.armed = None;
+        self.confirming = None;
+    }
+
+    /// Trigger a part transition. Returns true if it was scheduled.
     pub fn trigger_part(&mut self, name: &str, current_tick: u64, ppqn: u32, beats_per_bar: u32) -> bool {
-        if let Some(part) = self.parts.get(name) {
-            let scheduled_tick = self.calculate_transition_tick(
-                current_tick,
-                part.transition(),
-                ppqn,
-                beats_per_bar,
-            );
-
-            if scheduled_tick == current_tick {
-                // Immediate transition
-                self.current_part = Some(name.to_string());
-                self.pending = None;
-            } else {
-                // Queue transition
-                self.pending = Some(PendingTransition {
-                    target: name.to_string(),
-                    scheduled_tick,
-                    transition: part.transition(),
-                });
+        self.try_trigger_part(name, current_tick, ppqn, beats_per_bar) == TriggerResult::Scheduled
+    }
+
+    /// Trigger a part transition, enforcing prerequisites and guards
+    pub fn try_trigger_part(
+        &mut self,
+        name: &str,
+        current_tick: u64,
+        ppqn: u32,
+        beats_per_bar: u32,
+    ) -> TriggerResult {
+        let Some(part) = self.parts.get(name) else {
+            return TriggerResult::Unknown;
+        };
+
+        let missing = self.missing_requirements(name);
+        if !missing.is_empty() {
+            return TriggerResult::Locked(missing);
+        }
+
+        match part.guard() {
+            PartGuard::None => {}
+            PartGuard::Arm => {
+                if self.armed.as_deref() != Some(name) {
+                    return TriggerResult::NotArmed;
+                }
+                self.armed = None;
+            }
+            PartGuard::Confirm => {
+                let confirmed = matches!(
+                    &self.confirming,
+                    Some((first, tick)) if first == name && current_tick.saturating_sub(*tick) <= ppqn as u64
+                );
+                if !confirmed {
+                    self.confirming = Some((name.to_string(), current_tick));
+                    return TriggerResult::AwaitingConfirm;
+                }
+                self.confirming = None;
             }
-            true
+        }
+
+        let scheduled_tick = self.calculate_transition_tick(
+            current_tick,
+            part.transition(),
+            ppqn,
+            beats_per_bar,
+        );
+
+        // Roll the optional layers afresh on every trigger
+        let mut layers: Vec<(usize, f64)> = part.layers().iter().map(|(&t, &c)| (t, c)).collect();
+        layers.sort_by_key(|&(track, _)| track);
+        self.layer_rolls = layers
+            .into_iter()
+            .map(|(track, chance)| (track, self.rng.gen::<f64>() < chance))
+            .collect();
+
+        if scheduled_tick == current_tick {
+            // Immediate transition
+            self.current_part = Some(name.to_string());
+            self.played.insert(name.to_string());
+            self.pending = None;
+            self.expire_overrides();
         } else {
-            false
+            // Queue transition
+            self.pending = Some(PendingTransition {
+                target: name.to_string(),
+                scheduled_tick,
+                transition: part.transition(),
+                prerolled: false,
+                fx_launched: false,
+            });
         }
+        TriggerResult::Scheduled
     }
 
     /// Calculate when transition should occur
@@ -385,6 +733,15 @@ impl PartManager {
         }
     }
 
+    /// Playback states for the optional layers of the last triggered part:
+    /// Active for layers that came up this time, Muted for the rest
+    pub fn layer_states(&self) -> Vec<(usize, TrackState)> {
+        self.layer_rolls
+            .iter()
+            .map(|&(track, plays)| (track, if plays { TrackState::Active } else { TrackState::Muted }))
+            .collect()
+    }
+
     /// Check for pending transitions
     pub fn update(&mut self, current_tick: u64) -> Option<&Part> {
         if let Some(pending) = &self.pending {
@@ -392,12 +749,152 @@ impl PartManager {
                 let target = pending.target.clone();
                 self.pending = None;
                 self.current_part = Some(target.clone());
+                self.played.insert(target.clone());
+                self.expire_overrides();
                 return self.parts.get(&target);
             }
         }
         None
     }
 
+    /// Apply an override to a track until the part changes. A solo
+    /// replaces a mute on the same track and vice versa. Returns false for
+    /// an unknown track or a density override on a track without one.
+    pub fn set_override(&mut self, tracks: &mut TrackManager, track: usize, kind: TrackOverride) -> bool {
+        let Some(current) = tracks.track(track) else {
+            return false;
+        };
+        let previous_params: Vec<(String, f64)> = match kind {
+            TrackOverride::DoubleDensity => current
+                .generator()
+                .map(|generator| {
+                    DENSITY_PARAMS
+                        .iter()
+                        .filter_map(|&name| Some((name.to_string(), generator.get_param(name)?)))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        if kind == TrackOverride::DoubleDensity && previous_params.is_empty() {
+            return false;
+        }
+
+        // Put back what a replaced override changed first
+        let (replaced, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.overrides)
+            .into_iter()
+            .partition(|o| o.track == track && kind.replaces(o.kind));
+        self.overrides = kept;
+        for scoped in replaced.iter().rev() {
+            revert(tracks, scoped);
+        }
+
+        let Some(current) = tracks.track(track) else {
+            return false;
+        };
+        let scoped = ScopedOverride {
+            track,
+            kind,
+            previous_state: current.state(),
+            previous_params,
+        };
+        match kind {
+            TrackOverride::Solo => tracks.set_track_state(track, TrackState::Soloed),
+            TrackOverride::Mute => tracks.set_track_state(track, TrackState::Muted),
+            TrackOverride::DoubleDensity => {
+                if let Some(generator) = tracks.track_mut(track).and_then(|t| t.generator_mut()) {
+                    for (name, value) in &scoped.previous_params {
+                        generator.set_param(name, value * 2.0);
+                    }
+                }
+            }
+        }
+        self.overrides.push(scoped);
+        true
+    }
+
+    /// End a track's overrides now, restoring it
+    pub fn clear_overrides(&mut self, tracks: &mut TrackManager, track: usize) {
+        let (cleared, kept): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.overrides).into_iter().partition(|o| o.track == track);
+        self.overrides = kept;
+        for scoped in cleared.iter().rev() {
+            revert(tracks, scoped);
+        }
+    }
+
+    /// Overrides in force
+    pub fn overrides(&self) -> &[ScopedOverride] {
+        &self.overrides
+    }
+
+    /// Overrides in force on a track
+    pub fn track_overrides(&self, track: usize) -> Vec<TrackOverride> {
+        self.overrides.iter().filter(|o| o.track == track).map(|o| o.kind).collect()
+    }
+
+    /// Restore tracks whose overrides ended with the last part change.
+    /// Call after `update` or a trigger switches parts; returns how many
+    /// overrides were reverted.
+    pub fn revert_expired(&mut self, tracks: &mut TrackManager) -> usize {
+        let expired = std::mem::take(&mut self.expired);
+        for scoped in expired.iter().rev() {
+            revert(tracks, scoped);
+        }
+        expired.len()
+    }
+
+    /// Move the current overrides out of scope
+    fn expire_overrides(&mut self) {
+        let ended = std::mem::take(&mut self.overrides);
+        self.expired.extend(ended);
+    }
+
+    /// Generators the pending part switches tracks to, as (track, generator name).
+    ///
+    /// Returned once, when the transition is at most a bar away, so the
+    /// caller can queue and pre-roll them before the downbeat.
+    pub fn take_preroll(&mut self, current_tick: u64, ticks_per_bar: u64) -> Vec<(usize, String)> {
+        let Some(pending) = self.pending.as_mut() else {
+            return Vec::new();
+        };
+        if pending.prerolled || pending.scheduled_tick.saturating_sub(current_tick) > ticks_per_bar {
+            return Vec::new();
+        }
+        pending.prerolled = true;
+
+        let Some(part) = self.parts.get(&pending.target) else {
+            return Vec::new();
+        };
+        let mut generators: Vec<(usize, String)> = part
+            .track_states()
+            .iter()
+            .filter_map(|(&track, state)| match state {
+                TrackClipState::Generator(name) => Some((track, name.clone())),
+                _ => None,
+            })
+            .collect();
+        generators.sort();
+        generators
+    }
+
+    /// Take the boundary tick and lead-in FX of the pending transition.
+    ///
+    /// Returned once per transition, as soon as it is queued, so the caller
+    /// can schedule each FX to end on the boundary.
+    pub fn take_fx(&mut self) -> Option<(u64, Vec<String>)> {
+        let pending = self.pending.as_mut()?;
+        if pending.fx_launched {
+            return None;
+        }
+        pending.fx_launched = true;
+        let fx = self.parts.get(&pending.target)?.fx();
+        if fx.is_empty() {
+            return None;
+        }
+        Some((pending.scheduled_tick, fx.to_vec()))
+    }
+
     /// Get pending transition
     pub fn pending_transition(&self) -> Option<&PendingTransition> {
         self.pending.as_ref()
@@ -451,6 +948,20 @@ impl Default for PartManager {
     }
 }
 
+/// Put a track back the way it was before an override
+fn revert(tracks: &mut TrackManager, scoped: &ScopedOverride) {
+    match scoped.kind {
+        TrackOverride::Solo | TrackOverride::Mute => tracks.set_track_state(scoped.track, scoped.previous_state),
+        TrackOverride::DoubleDensity => {
+            if let Some(generator) = tracks.track_mut(scoped.track).and_then(|t| t.generator_mut()) {
+                for (name, value) in &scoped.previous_params {
+                    generator.set_param(name, *value);
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -554,6 +1065,94 @@ mod tests {
         assert!(manager.pending_transition().is_none());
     }
 
+    #[test]
+    fn test_part_guards() {
+        let mut manager = PartManager::new(4);
+        manager.add_part(Part::new("Intro").with_transition(PartTransition::Immediate));
+        manager.add_part(
+            Part::new("Drop")
+                .with_transition(PartTransition::Immediate)
+                .with_guard(PartGuard::Arm)
+                .with_requirement("Intro"),
+        );
+        manager.add_part(
+            Part::new("Break")
+                .with_transition(PartTransition::Immediate)
+                .with_guard(PartGuard::Confirm),
+        );
+
+        // Locked until the intro has played
+        assert_eq!(
+            manager.try_trigger_part("Drop", 0, 24, 4),
+            TriggerResult::Locked(vec!["Intro".to_string()])
+        );
+        assert!(manager.trigger_part("Intro", 0, 24, 4));
+        assert!(manager.is_available("Drop"));
+
+        // Needs arming, and arming is used up by the launch
+        assert_eq!(manager.try_trigger_part("Drop", 10, 24, 4), TriggerResult::NotArmed);
+        assert!(manager.arm_part("Drop"));
+        assert_eq!(manager.try_trigger_part("Drop", 10, 24, 4), TriggerResult::Scheduled);
+        assert_eq!(manager.current_part(), Some("Drop"));
+        assert!(manager.armed_part().is_none());
+
+        // Confirm: the second press must come within a beat
+        assert_eq!(manager.try_trigger_part("Break", 100, 24, 4), TriggerResult::AwaitingConfirm);
+        assert_eq!(manager.try_trigger_part("Break", 130, 24, 4), TriggerResult::AwaitingConfirm);
+        assert_eq!(manager.try_trigger_part("Break", 140, 24, 4), TriggerResult::Scheduled);
+        assert_eq!(manager.current_part(), Some("Break"));
+
+        manager.reset_history();
+        assert!(!manager.has_played("Intro"));
+        assert_eq!(manager.try_trigger_part("Verse", 0, 24, 4), TriggerResult::Unknown);
+    }
+
+    #[test]
+    fn test_take_preroll() {
+        let mut manager = PartManager::new(4);
+        manager.add_part(
+            Part::new("Chorus")
+                .with_transition(PartTransition::Bars(2))
+                .with_track(2, TrackClipState::Generator("euclid".to_string()))
+                .with_track(0, TrackClipState::Generator("melody".to_string()))
+                .with_track(1, TrackClipState::Clip(0)),
+        );
+        assert!(manager.trigger_part("Chorus", 0, 24, 4));
+
+        // More than a bar before the switch: nothing yet
+        assert!(manager.take_preroll(50, 96).is_empty());
+
+        // Within the last bar: generators, once
+        assert_eq!(
+            manager.take_preroll(96, 96),
+            vec![(0, "melody".to_string()), (2, "euclid".to_string())]
+        );
+        assert!(manager.take_preroll(120, 96).is_empty());
+    }
+
+    #[test]
+    fn test_take_fx() {
+        let mut manager = PartManager::new(4);
+        manager.add_part(Part::new("Verse").with_transition(PartTransition::Bars(2)));
+        manager.add_part(
+            Part::new("Drop")
+                .with_transition(PartTransition::Bars(2))
+                .with_fx("riser")
+                .with_fx("impact"),
+        );
+        assert_eq!(manager.take_fx(), None);
+
+        assert!(manager.trigger_part("Drop", 0, 24, 4));
+        let (boundary, fx) = manager.take_fx().unwrap();
+        assert_eq!(boundary, manager.pending_transition().unwrap().scheduled_tick);
+        assert_eq!(fx, vec!["riser".to_string(), "impact".to_string()]);
+        assert_eq!(manager.take_fx(), None);
+
+        // Parts without FX hand out nothing
+        assert!(manager.trigger_part("Verse", 0, 24, 4));
+        assert_eq!(manager.take_fx(), None);
+    }
+
     #[test]
     fn test_part_navigation() {
         let mut manager = PartManager::new(4);
@@ -633,6 +1232,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_chance_layers() {
+        let mut manager = PartManager::new(4);
+        manager.set_seed(7);
+        manager.add_part(
+            Part::new("Chorus")
+                .with_transition(PartTransition::Immediate)
+                .with_layer(1, 1.0)
+                .with_layer(2, 0.3)
+                .with_layer(3, 0.0),
+        );
+        assert!(manager.layer_states().is_empty());
+
+        // Each trigger rolls again: the 30% layer sometimes plays
+        let mut percussion = 0;
+        for _ in 0..200 {
+            assert!(manager.trigger_part("Chorus", 0, 24, 4));
+            let states = manager.layer_states();
+            assert_eq!(states[0], (1, TrackState::Active));
+            assert_eq!(states[2], (3, TrackState::Muted));
+            if states[1] == (2, TrackState::Active) {
+                percussion += 1;
+            }
+        }
+        assert!((30..90).contains(&percussion));
+        assert_eq!(Part::new("Verse").with_layer(0, 1.5).layer_chance(0), Some(1.0));
+    }
+
     #[test]
     fn test_track_playback_states() {
         let mut part = Part::new("Test");
@@ -644,4 +1271,58 @@ mod tests {
         assert_eq!(part.playback_state(1), Some(TrackState::Soloed));
         assert_eq!(part.playback_state(2), None);
     }
+
+    #[test]
+    fn test_overrides_revert_on_part_change() {
+        use crate::sequencer::track::TrackConfig;
+
+        let mut tracks = TrackManager::new();
+        for _ in 0..3 {
+            tracks.add_track(TrackConfig::default());
+        }
+        let drums = tracks.track_mut(2).unwrap();
+        drums.set_generator(crate::generators::drums::DrumGenerator::create());
+        drums.generator_mut().unwrap().set_param("kick_euclidean_hits", 3.0);
+
+        let mut manager = PartManager::new(3);
+        manager.add_part(Part::new("Verse").with_transition(PartTransition::NextBar));
+        manager.add_part(Part::new("Chorus").with_transition(PartTransition::NextBar));
+        manager.trigger_part("Verse", 0, 24, 4);
+
+        // A mute is replaced by a solo; density stacks
+        assert!(manager.set_override(&mut tracks, 0, TrackOverride::Mute));
+        assert!(manager.set_override(&mut tracks, 0, TrackOverride::Solo));
+        assert!(manager.set_override(&mut tracks, 2, TrackOverride::DoubleDensity));
+        assert!(!manager.set_override(&mut tracks, 1, TrackOverride::DoubleDensity));
+        assert_eq!(manager.track_overrides(0), vec![TrackOverride::Solo]);
+        assert_eq!(tracks.track(0).unwrap().state(), TrackState::Soloed);
+        let hits = |tracks: &TrackManager| tracks.track(2).unwrap().generator().unwrap().get_param("kick_euclidean_hits");
+        assert_eq!(hits(&tracks), Some(6.0));
+
+        // Still in force while the next part is queued
+        manager.trigger_part("Chorus", 10, 24, 4);
+        assert!(manager.update(50).is_none());
+        assert_eq!(manager.revert_expired(&mut tracks), 0);
+
+        manager.update(96);
+        assert!(manager.overrides().is_empty());
+        assert_eq!(manager.revert_expired(&mut tracks), 2);
+        assert_eq!(tracks.track(0).unwrap().state(), TrackState::Active);
+        assert_eq!(hits(&tracks), Some(3.0));
+    }
+
+    #[test]
+    fn test_clear_overrides() {
+        use crate::sequencer::track::TrackConfig;
+
+        let mut tracks = TrackManager::new();
+        tracks.add_track(TrackConfig::default());
+        tracks.set_track_state(0, TrackState::Soloed);
+        let mut manager = PartManager::new(1);
+        manager.set_override(&mut tracks, 0, TrackOverride::from_str("mute").unwrap());
+        assert_eq!(tracks.track(0).unwrap().state(), TrackState::Muted);
+        manager.clear_overrides(&mut tracks, 0);
+        assert_eq!(tracks.track(0).unwrap().state(), TrackState::Soloed);
+        assert_eq!(TrackOverride::DoubleDensity.label(), "x2");
+    }
 }
@@ -0,0 +1,273 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Performance capture into an arrangement.
+//!
+//! While the song player is recording, live mute and solo toggles and part
+//! and scene triggers are captured with their timing. Finishing the take
+//! turns it into a [`Song`]: each part or scene change starts a section on
+//! the bar it launched on, and mutes and solos become stepped automation
+//! lanes ("track2.mute") that replay through `ControlAction::from_automation`.
+
+use std::collections::BTreeSet;
+
+use crate::control::ControlAction;
+
+use super::automation::AutomationPoint;
+use super::song::{Song, SongPlayer, SongSection};
+
+/// One captured performance move
+#[derive(Debug, Clone, PartialEq)]
+pub enum PerformanceEvent {
+    /// Track muted (true) or unmuted
+    Mute(usize, bool),
+    /// Track soloed (true) or unsoloed
+    Solo(usize, bool),
+    /// Part triggered
+    Part(String),
+    /// Scene launched
+    Scene(usize),
+}
+
+/// Captures a live performance for replay as an arrangement
+#[derive(Debug, Clone)]
+pub struct PerformanceRecorder {
+    /// Ticks per bar, for section boundaries
+    ticks_per_bar: u64,
+    /// Part playing when the take started
+    initial_part: Option<String>,
+    /// Mute state when the take started
+    initial_mutes: Vec<bool>,
+    /// Solo state when the take started
+    initial_solos: Vec<bool>,
+    /// Mute state as of the last captured move
+    mutes: Vec<bool>,
+    /// Solo state as of the last captured move
+    solos: Vec<bool>,
+    /// Captured moves with their ticks from the start of the take
+    events: Vec<(u64, PerformanceEvent)>,
+}
+
+impl PerformanceRecorder {
+    /// Create a recorder for a bar length in ticks
+    pub fn new(ticks_per_bar: u64) -> Self {
+        Self {
+            ticks_per_bar: ticks_per_bar.max(1),
+            initial_part: None,
+            initial_mutes: Vec::new(),
+            initial_solos: Vec::new(),
+            mutes: Vec::new(),
+            solos: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Set the part playing when the take starts
+    pub fn with_part(mut self, part: impl Into<String>) -> Self {
+        self.initial_part = Some(part.into());
+        self
+    }
+
+    /// Set the track mutes when the take starts
+    pub fn with_mutes(mut self, mutes: Vec<bool>) -> Self {
+        self.mutes = mutes.clone();
+        self.initial_mutes = mutes;
+        self
+    }
+
+    /// Set the track solos when the take starts
+    pub fn with_solos(mut self, solos: Vec<bool>) -> Self {
+        self.solos = solos.clone();
+        self.initial_solos = solos;
+        self
+    }
+
+    /// Get the captured moves
+    pub fn events(&self) -> &[(u64, PerformanceEvent)] {
+        &self.events
+    }
+
+    /// Capture an action at the player's position. Returns true if it was
+    /// a performance move recorded into the take.
+    pub fn capture(&mut self, action: &ControlAction, player: &SongPlayer) -> bool {
+        if !player.is_recording() {
+            return false;
+        }
+        let event = match *action {
+            ControlAction::ToggleMute(track) => PerformanceEvent::Mute(track, !state(&self.mutes, track)),
+            ControlAction::SetMute(track, muted) => PerformanceEvent::Mute(track, muted),
+            ControlAction::ToggleSolo(track) => PerformanceEvent::Solo(track, !state(&self.solos, track)),
+            ControlAction::SetSolo(track, soloed) => PerformanceEvent::Solo(track, soloed),
+            ControlAction::TriggerPart(ref name) => PerformanceEvent::Part(name.clone()),
+            ControlAction::TriggerScene(index) => PerformanceEvent::Scene(index),
+            _ => return false,
+        };
+        match event {
+            PerformanceEvent::Mute(track, on) => set_state(&mut self.mutes, track, on),
+            PerformanceEvent::Solo(track, on) => set_state(&mut self.solos, track, on),
+            _ => {}
+        }
+        self.events.push((player.position_ticks(), event));
+        true
+    }
+
+    /// Build the arrangement for a take that ended at `end_tick`
+    pub fn to_song(&self, name: impl Into<String>, end_tick: u64) -> Song {
+        // Section starts: (bar, part, scene). Triggers land on the next bar.
+        let mut starts: Vec<(u64, String, Option<usize>)> =
+            vec![(0, self.initial_part.clone().unwrap_or_default(), None)];
+        for (tick, event) in &self.events {
+            let bar = tick.div_ceil(self.ticks_per_bar);
+            let (last_bar, last_part, _) = starts.last().cloned().unwrap_or_default();
+            let (part, scene) = match event {
+                PerformanceEvent::Part(part) => (part.clone(), None),
+                PerformanceEvent::Scene(index) => (last_part, Some(*index)),
+                _ => continue,
+            };
+            if bar == last_bar {
+                let last = starts.last_mut().unwrap();
+                last.1 = part;
+                last.2 = scene.or(last.2);
+            } else {
+                starts.push((bar, part, scene));
+            }
+        }
+
+        let last_start = starts.last().map_or(0, |s| s.0);
+        let end_bar = end_tick.div_ceil(self.ticks_per_bar).max(last_start + 1);
+        let mut song = Song::new(name);
+        for (i, (bar, part, scene)) in starts.iter().enumerate() {
+            let next = starts.get(i + 1).map_or(end_bar, |s| s.0);
+            let mut section = SongSection::new(part.clone(), (next - bar) as u32);
+            section.set_scene(*scene);
+            self.write_lanes(&mut section, bar * self.ticks_per_bar, next * self.ticks_per_bar);
+            song.add_section(section);
+        }
+        song
+    }
+
+    /// Finish the take: build its arrangement and stop the player
+    pub fn finish(&mut self, name: impl Into<String>, player: &mut SongPlayer) -> Song {
+        let song = self.to_song(name, player.position_ticks());
+        player.stop();
+        self.initial_mutes = self.mutes.clone();
+        self.initial_solos = self.solos.clone();
+        self.events.clear();
+        song
+    }
+
+    /// Write stepped mute and solo lanes for the ticks from `start` to `end`
+    fn write_lanes(&self, section: &mut SongSection, start: u64, end: u64) {
+        for (field, initial, solo) in [("mute", &self.initial_mutes, false), ("solo", &self.initial_solos, true)] {
+            // Tracks that moved during the take, or started switched on
+            let tracks: BTreeSet<usize> = (0..initial.len())
+                .filter(|&t| initial[t])
+                .chain(self.switches(solo).map(|(_, track, _)| track))
+                .collect();
+
+            for track in tracks {
+                let moves = move || {
+                    self.switches(solo)
+                        .filter(move |&(_, t, _)| t == track)
+                        .map(|(tick, _, on)| (tick, on))
+                };
+                let mut on = moves()
+                    .take_while(|&(tick, _)| tick <= start)
+                    .last()
+                    .map_or(state(initial, track), |(_, on)| on);
+                let lane = section.lane_mut(&format!("track{}.{}", track + 1, field));
+                lane.add_point(AutomationPoint::new(0, value(on)));
+                // Hold the old value up to the tick before each move so the
+                // lane steps instead of ramping
+                for (tick, next) in moves().filter(|&(tick, _)| tick > start && tick < end) {
+                    let offset = tick - start;
+                    lane.add_point(AutomationPoint::new(offset - 1, value(on)));
+                    lane.add_point(AutomationPoint::new(offset, value(next)));
+                    on = next;
+                }
+            }
+        }
+    }
+}
+
+impl PerformanceRecorder {
+    /// Captured mute (or solo) moves as (tick, track, on)
+    fn switches(&self, solo: bool) -> impl Iterator<Item = (u64, usize, bool)> + '_ {
+        self.events.iter().filter_map(move |(tick, event)| match *event {
+            PerformanceEvent::Mute(track, on) if !solo => Some((*tick, track, on)),
+            PerformanceEvent::Solo(track, on) if solo => Some((*tick, track, on)),
+            _ => None,
+        })
+    }
+}
+
+/// State of a track, off if never set
+fn state(states: &[bool], track: usize) -> bool {
+    states.get(track).copied().unwrap_or(false)
+}
+
+/// Set the state of a track, growing the list as needed
+fn set_state(states: &mut Vec<bool>, track: usize, on: bool) {
+    if states.len() <= track {
+        states.resize(track + 1, false);
+    }
+    states[track] = on;
+}
+
+/// Lane value for a switch
+fn value(on: bool) -> f64 {
+    if on {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_performance_into_song() {
+        let mut player = SongPlayer::new(24);
+        let mut recorder = PerformanceRecorder::new(96).with_part("Intro").with_mutes(vec![false, true]);
+
+        // Not recording: nothing captured
+        assert!(!recorder.capture(&ControlAction::ToggleMute(0), &player));
+
+        player.record();
+        player.update(48);
+        assert!(recorder.capture(&ControlAction::ToggleMute(1), &player));
+        player.update(120);
+        assert!(recorder.capture(&ControlAction::TriggerPart("Verse".to_string()), &player));
+        assert!(!recorder.capture(&ControlAction::SetTempo(120.0), &player));
+        player.update(60);
+        assert!(recorder.capture(&ControlAction::ToggleSolo(0), &player));
+        player.update(156);
+        assert_eq!(recorder.events().len(), 3);
+
+        // Verse was triggered during bar 2, so it starts on bar 3
+        let song = recorder.finish("Take 1", &mut player);
+        assert_eq!(player.mode(), crate::arrangement::SongMode::Stopped);
+        let sections: Vec<(&str, u32)> = song.sections().iter().map(|s| (s.part_name(), s.length_bars())).collect();
+        assert_eq!(sections, vec![("Intro", 2), ("Verse", 2)]);
+
+        let intro = &song.sections()[0];
+        let mute = intro.lane("track2.mute").unwrap();
+        assert_eq!(mute.value_at(47), Some(1.0));
+        assert_eq!(mute.value_at(48), Some(0.0));
+        assert!(intro.lane("track1.mute").is_none());
+
+        // The solo landed 36 ticks into the verse
+        let verse = &song.sections()[1];
+        let solo = verse.lane("track1.solo").unwrap();
+        assert_eq!(solo.value_at(35), Some(0.0));
+        assert_eq!(solo.value_at(36), Some(1.0));
+        assert_eq!(verse.lane("track2.mute").unwrap().value_at(0), Some(0.0));
+
+        assert_eq!(
+            ControlAction::from_automation("track1.solo", solo.value_at(40).unwrap()),
+            ControlAction::SetSolo(0, true)
+        );
+    }
+}
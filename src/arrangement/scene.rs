@@ -4,14 +4,19 @@
 //! Scene system for track state snapshots.
 //!
 //! Scenes represent a horizontal row in a track × scene matrix,
-//! enabling coordinated triggering of multiple track clips.
+//! enabling coordinated triggering of multiple track clips. Scenes are
+//! saved in the song file's `scenes:` section, so the F1-F8 slots survive
+//! a restart.
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
 
 use crate::sequencer::trigger::FollowAction;
 
 /// A slot in the scene matrix (track × scene intersection)
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SceneSlot {
     /// Empty slot
     Empty,
@@ -32,7 +37,8 @@ impl Default for SceneSlot {
 }
 
 /// Scene launch quantization
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SceneLaunchMode {
     /// Launch immediately
     Immediate,
@@ -53,35 +59,49 @@ impl Default for SceneLaunchMode {
 }
 
 /// A scene (horizontal row of clips/slots)
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Scene {
     /// Scene name
     name: String,
     /// Slots indexed by track
-    slots: HashMap<usize, SceneSlot>,
+    #[serde(default)]
+    slots: BTreeMap<usize, SceneSlot>,
+    /// Mute states indexed by track (tracks not listed keep their state)
+    #[serde(default)]
+    mutes: BTreeMap<usize, bool>,
     /// Launch mode for this scene
+    #[serde(default)]
     launch_mode: SceneLaunchMode,
     /// Follow action after scene plays
+    #[serde(default)]
     follow_action: FollowAction,
     /// Duration in bars before follow action (None = loop indefinitely)
+    #[serde(default, rename = "follow_after")]
     follow_after_bars: Option<u32>,
     /// Tempo for this scene (None = keep current)
+    #[serde(default)]
     tempo: Option<f64>,
     /// Color for UI
+    #[serde(default = "default_scene_color")]
     color: (u8, u8, u8),
 }
 
+fn default_scene_color() -> (u8, u8, u8) {
+    (100, 100, 100)
+}
+
 impl Scene {
     /// Create a new scene
     pub fn new(name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
-            slots: HashMap::new(),
+            slots: BTreeMap::new(),
+            mutes: BTreeMap::new(),
             launch_mode: SceneLaunchMode::default(),
             follow_action: FollowAction::None,
             follow_after_bars: None,
             tempo: None,
-            color: (100, 100, 100),
+            color: default_scene_color(),
         }
     }
 
@@ -106,10 +126,25 @@ impl Scene {
     }
 
     /// Get all slots
-    pub fn slots(&self) -> &HashMap<usize, SceneSlot> {
+    pub fn slots(&self) -> &BTreeMap<usize, SceneSlot> {
         &self.slots
     }
 
+    /// Set whether a track is muted when the scene launches
+    pub fn set_mute(&mut self, track: usize, muted: bool) {
+        self.mutes.insert(track, muted);
+    }
+
+    /// Mute state for a track (None = left as it is)
+    pub fn mute(&self, track: usize) -> Option<bool> {
+        self.mutes.get(&track).copied()
+    }
+
+    /// Get all mute states
+    pub fn mutes(&self) -> &BTreeMap<usize, bool> {
+        &self.mutes
+    }
+
     /// Set launch mode
     pub fn set_launch_mode(&mut self, mode: SceneLaunchMode) {
         self.launch_mode = mode;
@@ -162,6 +197,12 @@ impl Scene {
         self
     }
 
+    /// Builder: set mute state
+    pub fn with_mute(mut self, track: usize, muted: bool) -> Self {
+        self.set_mute(track, muted);
+        self
+    }
+
     /// Builder: set launch mode
     pub fn with_launch_mode(mut self, mode: SceneLaunchMode) -> Self {
         self.launch_mode = mode;
@@ -227,6 +268,13 @@ impl SceneManager {
         }
     }
 
+    /// Create a manager holding saved scenes
+    pub fn with_scenes(track_count: usize, scenes: Vec<Scene>) -> Self {
+        let mut manager = Self::new(track_count);
+        manager.scenes = scenes;
+        manager
+    }
+
     /// Add a scene
     pub fn add_scene(&mut self, scene: Scene) {
         self.scenes.push(scene);
@@ -479,6 +527,28 @@ mod tests {
         assert_eq!(scene.slot_count(), 3);
     }
 
+    #[test]
+    fn test_scene_yaml_round_trip() {
+        let scene = Scene::new("Drop")
+            .with_slot(0, SceneSlot::Clip(2))
+            .with_slot(1, SceneSlot::Generator("arp".into()))
+            .with_slot(2, SceneSlot::Stop)
+            .with_mute(3, true)
+            .with_launch_mode(SceneLaunchMode::Bars(2))
+            .with_follow(FollowAction::Specific(0), Some(8));
+
+        let yaml = serde_yaml::to_string(&scene).unwrap();
+        let parsed: Scene = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, scene);
+        assert_eq!(parsed.mute(3), Some(true));
+        assert_eq!(parsed.mute(0), None);
+
+        let sparse: Scene = serde_yaml::from_str("name: Intro\nslots:\n  0: hold\n").unwrap();
+        assert_eq!(sparse.slot(0), &SceneSlot::Hold);
+        assert_eq!(sparse.color(), (100, 100, 100));
+        assert_eq!(sparse.launch_mode(), SceneLaunchMode::Bar);
+    }
+
     #[test]
     fn test_scene_follow_action() {
         let scene = Scene::new("Verse")
@@ -0,0 +1,158 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! External device snapshots (patch recall).
+//!
+//! A snapshot is a list of parameter values for one external synth or mixer:
+//! CCs, NRPNs and a program change. A song can send snapshots when it loads
+//! and each part can carry its own, so triggering a section puts the outboard
+//! gear back into a known state.
+
+use crate::sequencer::ScheduledEvent;
+
+/// NRPN parameter number MSB / LSB
+const CC_NRPN_MSB: u8 = 99;
+const CC_NRPN_LSB: u8 = 98;
+
+/// Data entry MSB / LSB
+const CC_DATA_MSB: u8 = 6;
+const CC_DATA_LSB: u8 = 38;
+
+/// RPN parameter number MSB / LSB (set to 127 to close the parameter)
+const CC_RPN_MSB: u8 = 101;
+const CC_RPN_LSB: u8 = 100;
+
+/// A single value recalled by a snapshot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotValue {
+    /// Control change (channel 0-15)
+    Cc { channel: u8, cc: u8, value: u8 },
+    /// 14-bit NRPN (parameter and value 0-16383)
+    Nrpn { channel: u8, param: u16, value: u16 },
+    /// Program change
+    Program { channel: u8, program: u8 },
+}
+
+impl SnapshotValue {
+    /// Events that send this value at a tick
+    pub fn to_events(&self, tick: u64) -> Vec<ScheduledEvent> {
+        match *self {
+            SnapshotValue::Cc { channel, cc, value } => {
+                vec![ScheduledEvent::control_change(tick, channel, cc, value.min(127))]
+            }
+            SnapshotValue::Nrpn { channel, param, value } => {
+                let param = param.min(0x3FFF);
+                let value = value.min(0x3FFF);
+                [
+                    (CC_NRPN_MSB, (param >> 7) as u8),
+                    (CC_NRPN_LSB, (param & 0x7F) as u8),
+                    (CC_DATA_MSB, (value >> 7) as u8),
+                    (CC_DATA_LSB, (value & 0x7F) as u8),
+                    // Close the parameter so stray data entry does not change it
+                    (CC_RPN_MSB, 127),
+                    (CC_RPN_LSB, 127),
+                ]
+                .iter()
+                .map(|&(cc, v)| ScheduledEvent::control_change(tick, channel, cc, v))
+                .collect()
+            }
+            SnapshotValue::Program { channel, program } => {
+                vec![ScheduledEvent::program_change(tick, channel, program.min(127))]
+            }
+        }
+    }
+}
+
+/// Parameter values for one external device
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DeviceSnapshot {
+    /// MIDI destination name (None = default output)
+    device: Option<String>,
+    /// Values in send order
+    values: Vec<SnapshotValue>,
+}
+
+impl DeviceSnapshot {
+    /// Create an empty snapshot for a destination
+    pub fn new(device: Option<String>) -> Self {
+        Self {
+            device,
+            values: Vec::new(),
+        }
+    }
+
+    /// Add a value
+    pub fn with_value(mut self, value: SnapshotValue) -> Self {
+        self.values.push(value);
+        self
+    }
+
+    /// Add a value
+    pub fn push(&mut self, value: SnapshotValue) {
+        self.values.push(value);
+    }
+
+    /// Get the destination name
+    pub fn device(&self) -> Option<&str> {
+        self.device.as_deref()
+    }
+
+    /// Get the values
+    pub fn values(&self) -> &[SnapshotValue] {
+        &self.values
+    }
+
+    /// Check if the snapshot sends nothing
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Events that recall the snapshot at a tick. Program changes go first
+    /// so the CCs land on the newly loaded patch.
+    pub fn to_events(&self, tick: u64, destination: Option<usize>) -> Vec<ScheduledEvent> {
+        let (programs, rest): (Vec<&SnapshotValue>, Vec<&SnapshotValue>) = self
+            .values
+            .iter()
+            .partition(|v| matches!(v, SnapshotValue::Program { .. }));
+        programs
+            .into_iter()
+            .chain(rest)
+            .flat_map(|value| value.to_events(tick))
+            .map(|event| event.with_destination(destination))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_events() {
+        let snapshot = DeviceSnapshot::new(Some("Mixer".to_string()))
+            .with_value(SnapshotValue::Cc { channel: 0, cc: 7, value: 100 })
+            .with_value(SnapshotValue::Nrpn { channel: 1, param: 1025, value: 8192 })
+            .with_value(SnapshotValue::Program { channel: 0, program: 5 });
+        assert_eq!(snapshot.device(), Some("Mixer"));
+
+        let events = snapshot.to_events(96, Some(2));
+        assert_eq!(events.len(), 8);
+        assert!(events.iter().all(|e| e.time_ticks == 96 && e.destination == Some(2)));
+
+        // Program first, then the CC, then the NRPN sequence
+        assert_eq!(events[0].to_midi_bytes(), vec![0xC0, 5]);
+        assert_eq!(events[1].to_midi_bytes(), vec![0xB0, 7, 100]);
+        let nrpn: Vec<Vec<u8>> = events[2..].iter().map(|e| e.to_midi_bytes()).collect();
+        assert_eq!(
+            nrpn,
+            vec![
+                vec![0xB1, 99, 8],
+                vec![0xB1, 98, 1],
+                vec![0xB1, 6, 64],
+                vec![0xB1, 38, 0],
+                vec![0xB1, 101, 127],
+                vec![0xB1, 100, 127],
+            ]
+        );
+    }
+}
@@ -4,9 +4,14 @@
 //! Song mode for linear arrangement playback.
 //!
 //! Provides ordered arrangement of parts with auto-advance,
-//! loop regions, and position tracking.
+//! conditional section advancement, loop regions, and position tracking.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Result};
+
+use super::automation::{AutomationLane, AutomationPoint};
 
 /// Song playback mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -92,6 +97,57 @@ impl SongPosition {
     }
 }
 
+/// Condition controlling how the player leaves or enters a section
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SectionCondition {
+    /// Repeat the section until the given pad is pressed
+    RepeatUntilPad(u8),
+    /// Skip the section once the elapsed set time exceeds the limit
+    SkipIfElapsedOver(Duration),
+}
+
+impl SectionCondition {
+    /// Parse a condition such as "repeat until pad 5" or "skip if elapsed > 40min"
+    pub fn parse(text: &str) -> Result<Self> {
+        let lower = text.trim().to_lowercase();
+        let words: Vec<&str> = lower.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["repeat", "until", "pad", pad, ..] => {
+                let pad = pad
+                    .parse::<u8>()
+                    .map_err(|_| anyhow!("Invalid pad number: {}", pad))?;
+                Ok(SectionCondition::RepeatUntilPad(pad))
+            }
+            ["skip", "if", "elapsed", rest @ ..] => {
+                let limit = rest.join("");
+                let limit = limit.trim_start_matches('>');
+                Ok(SectionCondition::SkipIfElapsedOver(parse_duration(limit)?))
+            }
+            _ => bail!("Unknown section condition: {}", text),
+        }
+    }
+}
+
+/// Parse a duration like "40min", "90s" or "1h" (bare numbers are minutes)
+fn parse_duration(text: &str) -> Result<Duration> {
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (value, unit) = text.split_at(split);
+    let value: f64 = value
+        .parse()
+        .map_err(|_| anyhow!("Invalid duration: {}", text))?;
+
+    let seconds = match unit {
+        "s" | "sec" => value,
+        "" | "m" | "min" => value * 60.0,
+        "h" | "hr" => value * 3600.0,
+        _ => bail!("Unknown duration unit: {}", unit),
+    };
+    Ok(Duration::from_secs_f64(seconds))
+}
+
 /// A section in the song arrangement
 #[derive(Debug, Clone)]
 pub struct SongSection {
@@ -113,6 +169,10 @@ pub struct SongSection {
     color: (u8, u8, u8),
     /// Notes/comments
     notes: String,
+    /// Advance/skip conditions
+    conditions: Vec<SectionCondition>,
+    /// Parameter automation lanes
+    automation: Vec<AutomationLane>,
 }
 
 impl SongSection {
@@ -128,6 +188,8 @@ impl SongSection {
             is_loop_point: false,
             color: (100, 100, 100),
             notes: String::new(),
+            conditions: Vec::new(),
+            automation: Vec::new(),
         }
     }
 
@@ -207,6 +269,71 @@ impl SongSection {
         self.notes = notes.into();
     }
 
+    /// Get conditions
+    pub fn conditions(&self) -> &[SectionCondition] {
+        &self.conditions
+    }
+
+    /// Add a condition
+    pub fn add_condition(&mut self, condition: SectionCondition) {
+        self.conditions.push(condition);
+    }
+
+    /// Remove all conditions
+    pub fn clear_conditions(&mut self) {
+        self.conditions.clear();
+    }
+
+    /// Pad that must be pressed before leaving this section (if any)
+    pub fn repeat_until_pad(&self) -> Option<u8> {
+        self.conditions.iter().find_map(|c| match c {
+            SectionCondition::RepeatUntilPad(pad) => Some(*pad),
+            _ => None,
+        })
+    }
+
+    /// Check whether this section should be skipped at the given elapsed set time
+    pub fn should_skip(&self, elapsed: Duration) -> bool {
+        self.conditions.iter().any(|c| match c {
+            SectionCondition::SkipIfElapsedOver(limit) => elapsed > *limit,
+            _ => false,
+        })
+    }
+
+    /// Get automation lanes
+    pub fn automation(&self) -> &[AutomationLane] {
+        &self.automation
+    }
+
+    /// Get automation lane for a parameter
+    pub fn lane(&self, target: &str) -> Option<&AutomationLane> {
+        self.automation.iter().find(|lane| lane.target() == target)
+    }
+
+    /// Get automation lane for a parameter, creating it if missing
+    pub fn lane_mut(&mut self, target: &str) -> &mut AutomationLane {
+        let index = match self.automation.iter().position(|lane| lane.target() == target) {
+            Some(index) => index,
+            None => {
+                self.automation.push(AutomationLane::new(target));
+                self.automation.len() - 1
+            }
+        };
+        &mut self.automation[index]
+    }
+
+    /// Remove an automation lane
+    pub fn remove_lane(&mut self, target: &str) -> Option<AutomationLane> {
+        let index = self.automation.iter().position(|lane| lane.target() == target)?;
+        Some(self.automation.remove(index))
+    }
+
+    /// Builder: add an automation point
+    pub fn with_automation(mut self, target: &str, tick: u64, value: f64) -> Self {
+        self.lane_mut(target).add_point(AutomationPoint::new(tick, value));
+        self
+    }
+
     /// Builder: set scene
     pub fn with_scene(mut self, index: usize) -> Self {
         self.scene_index = Some(index);
@@ -231,6 +358,12 @@ impl SongSection {
         self.is_loop_point = true;
         self
     }
+
+    /// Builder: add condition
+    pub fn with_condition(mut self, condition: SectionCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
 }
 
 /// Loop region for song
@@ -474,6 +607,10 @@ pub struct SongPlayer {
     ppqn: u32,
     /// Beats per bar (default time sig)
     beats_per_bar: u32,
+    /// Pads pressed since they were last consumed by a condition
+    pressed_pads: HashSet<u8>,
+    /// Elapsed set time
+    elapsed: Duration,
 }
 
 impl SongPlayer {
@@ -487,6 +624,8 @@ impl SongPlayer {
             loop_region: None,
             ppqn,
             beats_per_bar: 4,
+            pressed_pads: HashSet::new(),
+            elapsed: Duration::ZERO,
         }
     }
 
@@ -520,6 +659,18 @@ impl SongPlayer {
         }
     }
 
+    /// Start recording a performance from the top. The position runs
+    /// without a song; sections are built when the recording finishes.
+    pub fn record(&mut self) {
+        self.stop();
+        self.mode = SongMode::Recording;
+    }
+
+    /// Check if recording a performance
+    pub fn is_recording(&self) -> bool {
+        self.mode == SongMode::Recording
+    }
+
     /// Stop playback
     pub fn stop(&mut self) {
         self.mode = SongMode::Stopped;
@@ -528,6 +679,25 @@ impl SongPlayer {
         if let Some(loop_region) = &mut self.loop_region {
             loop_region.current_repeat = 0;
         }
+        self.pressed_pads.clear();
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Register a pad press for section conditions
+    pub fn press_pad(&mut self, pad: u8) {
+        self.pressed_pads.insert(pad);
+    }
+
+    /// Add wall-clock time to the elapsed set time
+    pub fn add_elapsed(&mut self, time: Duration) {
+        if self.mode != SongMode::Stopped {
+            self.elapsed += time;
+        }
+    }
+
+    /// Get elapsed set time
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
     }
 
     /// Pause playback
@@ -563,6 +733,29 @@ impl SongPlayer {
         self.current_section
     }
 
+    /// Get position in ticks from the start of the current section
+    pub fn section_tick(&self) -> u64 {
+        let Some(song) = &self.song else {
+            return 0;
+        };
+        let start = SongPosition::at_section(self.current_section)
+            .to_ticks(self.ppqn, self.beats_per_bar, &song.section_lengths());
+        self.position_ticks.saturating_sub(start)
+    }
+
+    /// Get current automation values for the current section
+    pub fn automation_values(&self) -> Vec<(String, f64)> {
+        let Some(section) = self.get_section(self.current_section) else {
+            return Vec::new();
+        };
+        let tick = self.section_tick();
+        section
+            .automation()
+            .iter()
+            .filter_map(|lane| Some((lane.target().to_string(), lane.value_at(tick)?)))
+            .collect()
+    }
+
     /// Jump to section
     pub fn goto_section(&mut self, index: usize) {
         if let Some(song) = &self.song {
@@ -603,24 +796,44 @@ impl SongPlayer {
         if self.mode == SongMode::Stopped {
             return None;
         }
+        if self.mode == SongMode::Recording {
+            self.position_ticks += ticks;
+            return None;
+        }
 
         let song = self.song.as_ref()?;
         if song.sections.is_empty() {
             return None;
         }
 
+        // The first section is entered on the first update from the top
+        let starting = self.position_ticks == 0;
         self.position_ticks += ticks;
 
         // Calculate what section we should be in
         let new_position = song.position_from_tick(self.position_ticks, self.ppqn);
         let section_count = song.section_count();
         let section_lengths = song.section_lengths();
+        let section_start = |section: usize| {
+            SongPosition::at_section(section).to_ticks(self.ppqn, self.beats_per_bar, &section_lengths)
+        };
 
-        if new_position.section != self.current_section {
-            // Section changed
-            let old_section = self.current_section;
+        let old_section = self.current_section;
+        if new_position.section != old_section || starting {
             self.current_section = new_position.section;
 
+            // Repeat the old section until its pad has been pressed
+            if new_position.section > old_section {
+                if let Some(pad) = song.sections[old_section].repeat_until_pad() {
+                    if !self.pressed_pads.remove(&pad) {
+                        let overshoot = self.position_ticks - section_start(new_position.section);
+                        self.current_section = old_section;
+                        self.position_ticks = section_start(old_section) + overshoot;
+                        return Some(old_section);
+                    }
+                }
+            }
+
             // Check for loop
             if let Some(loop_region) = &mut self.loop_region {
                 if old_section == loop_region.end_section &&
@@ -631,8 +844,7 @@ impl SongPlayer {
                     if !loop_region.is_done() {
                         // Jump back to loop start
                         self.current_section = loop_region.start_section;
-                        self.position_ticks = SongPosition::at_section(loop_region.start_section)
-                            .to_ticks(self.ppqn, self.beats_per_bar, &section_lengths);
+                        self.position_ticks = section_start(loop_region.start_section);
                     } else {
                         // Loop finished, continue or stop
                         self.mode = SongMode::Playing;
@@ -640,6 +852,17 @@ impl SongPlayer {
                 }
             }
 
+            // Skip sections whose conditions say so, carrying the overshoot along
+            let overshoot = self.position_ticks.saturating_sub(section_start(self.current_section));
+            while self.current_section < section_count
+                && song.sections[self.current_section].should_skip(self.elapsed)
+            {
+                self.current_section += 1;
+                if self.current_section < section_count {
+                    self.position_ticks = section_start(self.current_section) + overshoot;
+                }
+            }
+
             // Check for end of song
             if self.current_section >= section_count {
                 // Inline stop to avoid borrow issues
@@ -652,6 +875,12 @@ impl SongPlayer {
                 return None;
             }
 
+            if starting && self.current_section == old_section {
+                return None;
+            }
+
+            // Presses only count toward the section they were made in
+            self.pressed_pads.clear();
             return Some(self.current_section);
         }
 
@@ -663,6 +892,11 @@ impl SongPlayer {
         self.song.as_ref().and_then(|s| s.get_section(index))
     }
 
+    /// Get mutable section at index from current song
+    pub fn get_section_mut(&mut self, index: usize) -> Option<&mut SongSection> {
+        self.song.as_mut().and_then(|s| s.get_section_mut(index))
+    }
+
     /// Check if at end of song
     pub fn is_at_end(&self) -> bool {
         if let Some(song) = &self.song {
@@ -838,6 +1072,111 @@ mod tests {
         assert_eq!(player.mode(), SongMode::Playing);
     }
 
+    #[test]
+    fn test_parse_section_conditions() {
+        assert_eq!(
+            SectionCondition::parse("repeat until pad 5").unwrap(),
+            SectionCondition::RepeatUntilPad(5)
+        );
+        assert_eq!(
+            SectionCondition::parse("skip if elapsed > 40min").unwrap(),
+            SectionCondition::SkipIfElapsedOver(Duration::from_secs(40 * 60))
+        );
+        assert_eq!(
+            SectionCondition::parse("Skip if elapsed >90s").unwrap(),
+            SectionCondition::SkipIfElapsedOver(Duration::from_secs(90))
+        );
+        assert!(SectionCondition::parse("repeat until pad x").is_err());
+        assert!(SectionCondition::parse("jump around").is_err());
+    }
+
+    #[test]
+    fn test_repeat_until_pad() {
+        let mut player = SongPlayer::new(24);
+
+        let song = Song::new("Test")
+            .with_section(SongSection::new("Verse", 1))
+            .with_section(
+                SongSection::new("Chorus", 1).with_condition(SectionCondition::RepeatUntilPad(5)),
+            )
+            .with_section(SongSection::new("Outro", 1));
+
+        player.load(song);
+        player.play();
+
+        assert_eq!(player.update(96), Some(1));
+
+        // No pad pressed: chorus repeats
+        assert_eq!(player.update(96), Some(1));
+        assert_eq!(player.current_section(), 1);
+        assert_eq!(player.position_ticks(), 96);
+
+        // Pad pressed: advance at the end of the chorus
+        player.press_pad(5);
+        assert_eq!(player.update(48), None);
+        assert_eq!(player.update(48), Some(2));
+    }
+
+    #[test]
+    fn test_skip_if_elapsed() {
+        let mut player = SongPlayer::new(24);
+
+        let song = Song::new("Test")
+            .with_section(SongSection::new("Verse", 1))
+            .with_section(SongSection::new("Bridge", 1).with_condition(
+                SectionCondition::SkipIfElapsedOver(Duration::from_secs(40 * 60)),
+            ))
+            .with_section(SongSection::new("Outro", 1));
+
+        player.load(song);
+        player.play();
+        player.add_elapsed(Duration::from_secs(41 * 60));
+
+        // The ticks past the end of the verse carry over the skipped bridge
+        assert_eq!(player.update(100), Some(2));
+        assert_eq!(player.position_ticks(), 196);
+        assert_eq!(player.get_section(2).unwrap().part_name(), "Outro");
+    }
+
+    #[test]
+    fn test_skip_first_section() {
+        let mut player = SongPlayer::new(24);
+
+        let song = Song::new("Test")
+            .with_section(SongSection::new("Intro", 1).with_condition(
+                SectionCondition::SkipIfElapsedOver(Duration::from_secs(60)),
+            ))
+            .with_section(SongSection::new("Verse", 1));
+
+        player.load(song);
+        player.play();
+        player.add_elapsed(Duration::from_secs(90));
+
+        assert_eq!(player.update(12), Some(1));
+        assert_eq!(player.position_ticks(), 108);
+    }
+
+    #[test]
+    fn test_pads_cleared_on_section_entry() {
+        let mut player = SongPlayer::new(24);
+
+        let song = Song::new("Test")
+            .with_section(SongSection::new("Verse", 1))
+            .with_section(
+                SongSection::new("Chorus", 1).with_condition(SectionCondition::RepeatUntilPad(5)),
+            )
+            .with_section(SongSection::new("Outro", 1));
+
+        player.load(song);
+        player.play();
+
+        // Pressed during the verse: does not release the chorus
+        player.press_pad(5);
+        assert_eq!(player.update(96), Some(1));
+        assert_eq!(player.update(96), Some(1));
+        assert_eq!(player.current_section(), 1);
+    }
+
     #[test]
     fn test_song_metadata() {
         let song = Song::new("Test")
@@ -0,0 +1,306 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Bouncing the built-in synth to WAV.
+//!
+//! A bounce captures the stereo output of the audio callback into a 16-bit
+//! WAV file. Armed with a path, it starts writing when the transport starts
+//! and closes the file when it stops. Offline rendering drives the synth
+//! from a list of scheduled events instead, as fast as it can render,
+//! without an audio device.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::sequencer::scheduler::MidiMessageType;
+use crate::sequencer::ScheduledEvent;
+
+use super::FluidSynth;
+
+/// Frames rendered per block when rendering offline
+const RENDER_BLOCK: usize = 512;
+
+/// Size of the RIFF header before the sample data
+const HEADER_LEN: u32 = 44;
+
+/// Writes 16-bit PCM WAV data, filling in the sizes on finish
+pub struct WavWriter<W: Write + Seek> {
+    writer: W,
+    channels: u16,
+    /// Frames written so far
+    frames: u64,
+}
+
+impl WavWriter<BufWriter<File>> {
+    /// Create a WAV file
+    pub fn create<P: AsRef<Path>>(path: P, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?), sample_rate, channels)
+    }
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// Start a WAV stream, writing the header with empty sizes
+    pub fn new(mut writer: W, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        let channels = channels.max(1);
+        let block_align = channels * 2;
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&(HEADER_LEN - 8).to_le_bytes())?;
+        writer.write_all(b"WAVEfmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        writer.write_all(&1u16.to_le_bytes())?; // PCM
+        writer.write_all(&channels.to_le_bytes())?;
+        writer.write_all(&sample_rate.to_le_bytes())?;
+        writer.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+        writer.write_all(&block_align.to_le_bytes())?;
+        writer.write_all(&16u16.to_le_bytes())?; // Bits per sample
+        writer.write_all(b"data")?;
+        writer.write_all(&0u32.to_le_bytes())?;
+        Ok(Self {
+            writer,
+            channels,
+            frames: 0,
+        })
+    }
+
+    /// Number of channels
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Frames written so far
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Append interleaved samples (-1.0 to 1.0)
+    pub fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+        let mut bytes = Vec::with_capacity(samples.len() * 2);
+        for sample in samples {
+            let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        self.writer.write_all(&bytes)?;
+        self.frames += (samples.len() / self.channels as usize) as u64;
+        Ok(())
+    }
+
+    /// Fill in the chunk sizes and hand back the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        let data_len = (self.frames * self.channels as u64 * 2) as u32;
+        self.writer.seek(SeekFrom::Start(4))?;
+        self.writer.write_all(&(HEADER_LEN - 8 + data_len).to_le_bytes())?;
+        self.writer.seek(SeekFrom::Start(40))?;
+        self.writer.write_all(&data_len.to_le_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Captures the audio callback to a WAV file while the transport runs
+pub struct Bounce {
+    /// File to write on the next transport start
+    armed: Option<PathBuf>,
+    /// File being written
+    recording: Option<(PathBuf, WavWriter<BufWriter<File>>)>,
+    sample_rate: u32,
+    /// First write error since the bounce started
+    error: Option<String>,
+}
+
+impl Bounce {
+    /// Create an idle bounce at a sample rate
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            armed: None,
+            recording: None,
+            sample_rate,
+            error: None,
+        }
+    }
+
+    /// Write to a file from the next transport start
+    pub fn arm(&mut self, path: impl Into<PathBuf>) {
+        self.armed = Some(path.into());
+    }
+
+    /// Cancel an armed bounce
+    pub fn disarm(&mut self) {
+        self.armed = None;
+    }
+
+    /// Check if armed
+    pub fn is_armed(&self) -> bool {
+        self.armed.is_some()
+    }
+
+    /// Check if writing
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Seconds written to the current file
+    pub fn seconds(&self) -> f64 {
+        self.recording
+            .as_ref()
+            .map_or(0.0, |(_, wav)| wav.frames() as f64 / self.sample_rate.max(1) as f64)
+    }
+
+    /// Open the armed file when the transport starts
+    pub fn transport_started(&mut self) -> io::Result<()> {
+        if self.recording.is_some() {
+            return Ok(());
+        }
+        if let Some(path) = self.armed.take() {
+            let wav = WavWriter::create(&path, self.sample_rate, 2)?;
+            self.recording = Some((path, wav));
+            self.error = None;
+        }
+        Ok(())
+    }
+
+    /// Close the file when the transport stops, returning its path
+    pub fn transport_stopped(&mut self) -> io::Result<Option<PathBuf>> {
+        let Some((path, wav)) = self.recording.take() else {
+            return Ok(None);
+        };
+        wav.finish()?;
+        match self.error.take() {
+            Some(error) => Err(io::Error::other(error)),
+            None => Ok(Some(path)),
+        }
+    }
+
+    /// Take one rendered buffer from the audio callback (mono is doubled,
+    /// channels past the second are dropped)
+    pub fn capture(&mut self, buffer: &[f32], channels: usize) {
+        let Some((_, wav)) = self.recording.as_mut() else {
+            return;
+        };
+        let result = if channels == 2 {
+            wav.write_samples(buffer)
+        } else {
+            let stereo: Vec<f32> = buffer
+                .chunks(channels.max(1))
+                .flat_map(|frame| [frame[0], *frame.get(1).unwrap_or(&frame[0])])
+                .collect();
+            wav.write_samples(&stereo)
+        };
+        if let Err(e) = result {
+            self.error.get_or_insert_with(|| e.to_string());
+        }
+    }
+}
+
+/// Play a scheduled event on the synth
+pub fn play_event(synth: &mut FluidSynth, event: &ScheduledEvent) {
+    match event.message_type {
+        MidiMessageType::NoteOn if event.data2 > 0 => synth.note_on(event.channel, event.data1, event.data2),
+        MidiMessageType::NoteOn | MidiMessageType::NoteOff => synth.note_off(event.channel, event.data1),
+        MidiMessageType::ControlChange => synth.control_change(event.channel, event.data1, event.data2),
+        MidiMessageType::ProgramChange => synth.program_change(event.channel, event.data1),
+        MidiMessageType::PitchBend => {
+            let raw = event.data1 as i16 | (event.data2 as i16) << 7;
+            synth.pitch_bend(event.channel, raw - 8192);
+        }
+        MidiMessageType::ChannelPressure => {}
+    }
+}
+
+/// Frame an event tick falls on at a tempo
+pub fn tick_to_frame(tick: u64, tempo: f64, ppqn: u32, sample_rate: u32) -> u64 {
+    let seconds = tick as f64 * 60.0 / (tempo.max(1.0) * ppqn.max(1) as f64);
+    (seconds * sample_rate as f64).round() as u64
+}
+
+/// Render events through the synth into a WAV stream, faster than real
+/// time, then let the sound ring out for `tail_seconds`. Returns the
+/// frames written.
+pub fn render_offline<W: Write + Seek>(
+    synth: &mut FluidSynth,
+    events: &[ScheduledEvent],
+    tempo: f64,
+    ppqn: u32,
+    tail_seconds: f64,
+    wav: &mut WavWriter<W>,
+) -> io::Result<u64> {
+    let sample_rate = synth.sample_rate() as u32;
+    let mut events: Vec<&ScheduledEvent> = events.iter().collect();
+    events.sort_by_key(|event| event.time_ticks);
+
+    let mut buffer = vec![0.0f32; RENDER_BLOCK * 2];
+    let mut frame = 0u64;
+    let mut render_to = |synth: &mut FluidSynth, wav: &mut WavWriter<W>, end: u64| -> io::Result<()> {
+        while frame < end {
+            let frames = (end - frame).min(RENDER_BLOCK as u64) as usize;
+            let block = &mut buffer[..frames * 2];
+            block.fill(0.0);
+            synth.render(block, 2);
+            wav.write_samples(block)?;
+            frame += frames as u64;
+        }
+        Ok(())
+    };
+
+    for event in &events {
+        render_to(synth, wav, tick_to_frame(event.time_ticks, tempo, ppqn, sample_rate))?;
+        play_event(synth, event);
+    }
+    let last = events.last().map_or(0, |e| tick_to_frame(e.time_ticks, tempo, ppqn, sample_rate));
+    render_to(synth, wav, last + (tail_seconds.max(0.0) * sample_rate as f64) as u64)?;
+    Ok(wav.frames())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_wav_header_sizes() {
+        let mut wav = WavWriter::new(Cursor::new(Vec::new()), 44100, 2).unwrap();
+        wav.write_samples(&[0.0, 0.5, -1.0, 2.0]).unwrap();
+        assert_eq!(wav.frames(), 2);
+        let bytes = wav.finish().unwrap().into_inner();
+
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 36 + 8);
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 44100);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 8);
+        let samples: Vec<i16> = bytes[44..]
+            .chunks(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(samples, vec![0, 16383, -32767, 32767]);
+    }
+
+    #[test]
+    fn test_bounce_follows_transport() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("take.wav");
+        let mut bounce = Bounce::new(100);
+
+        // Nothing is written before the transport starts
+        bounce.arm(&path);
+        bounce.capture(&[0.1; 8], 2);
+        assert!(!path.exists());
+
+        bounce.transport_started().unwrap();
+        assert!(bounce.is_recording() && !bounce.is_armed());
+        bounce.capture(&[0.1; 100], 2);
+        bounce.capture(&[0.2; 50], 1);
+        assert_eq!(bounce.seconds(), 1.0);
+
+        assert_eq!(bounce.transport_stopped().unwrap(), Some(path.clone()));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 44 + 100 * 2 * 2);
+        assert_eq!(bounce.transport_stopped().unwrap(), None);
+    }
+
+    #[test]
+    fn test_tick_to_frame() {
+        // One beat at 120 BPM is half a second
+        assert_eq!(tick_to_frame(24, 120.0, 24, 44100), 22050);
+        assert_eq!(tick_to_frame(6, 90.0, 24, 48000), 8000);
+    }
+}
@@ -7,13 +7,18 @@
 //! - FluidSynth integration for software synthesis
 //! - Audio output via cpal (Core Audio on macOS)
 //! - Buffer management and latency control
+//! - Bouncing the synth output to WAV, live or offline
 
+pub mod bounce;
 pub mod fluidsynth;
 pub mod output;
 
+pub use bounce::{render_offline, Bounce, WavWriter};
 pub use fluidsynth::FluidSynth;
 pub use output::{AudioConfig, AudioOutput};
 
+use std::io;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 /// Audio engine combining synth and output
@@ -22,6 +27,8 @@ pub struct AudioEngine {
     synth: Arc<Mutex<FluidSynth>>,
     /// Audio output
     output: Option<AudioOutput>,
+    /// WAV capture of the output
+    bounce: Arc<Mutex<Bounce>>,
     /// Whether audio is running
     running: bool,
     /// Sample rate
@@ -36,6 +43,7 @@ impl AudioEngine {
         Self {
             synth: Arc::new(Mutex::new(FluidSynth::new())),
             output: None,
+            bounce: Arc::new(Mutex::new(Bounce::new(44100))),
             running: false,
             sample_rate: 44100,
             buffer_size: 512,
@@ -46,6 +54,7 @@ impl AudioEngine {
     pub fn with_sample_rate(sample_rate: u32) -> Self {
         let mut engine = Self::new();
         engine.sample_rate = sample_rate;
+        engine.bounce = Arc::new(Mutex::new(Bounce::new(sample_rate)));
         engine
     }
 
@@ -73,10 +82,14 @@ impl AudioEngine {
         };
 
         let synth = Arc::clone(&self.synth);
+        let bounce = Arc::clone(&self.bounce);
         let output = AudioOutput::new(config, move |buffer, channels| {
             if let Ok(mut synth) = synth.lock() {
                 synth.render(buffer, channels);
             }
+            if let Ok(mut bounce) = bounce.lock() {
+                bounce.capture(buffer, channels);
+            }
         })?;
 
         self.output = Some(output);
@@ -95,6 +108,34 @@ impl AudioEngine {
         self.running
     }
 
+    /// Get bounce reference
+    pub fn bounce(&self) -> Arc<Mutex<Bounce>> {
+        Arc::clone(&self.bounce)
+    }
+
+    /// Bounce the output to a WAV file from the next transport start
+    pub fn arm_bounce(&self, path: impl Into<PathBuf>) {
+        if let Ok(mut bounce) = self.bounce.lock() {
+            bounce.arm(path);
+        }
+    }
+
+    /// Tell the bounce the transport started
+    pub fn transport_started(&self) -> io::Result<()> {
+        match self.bounce.lock() {
+            Ok(mut bounce) => bounce.transport_started(),
+            Err(_) => Err(io::Error::other(AudioError::LockFailed)),
+        }
+    }
+
+    /// Tell the bounce the transport stopped, returning the file written
+    pub fn transport_stopped(&self) -> io::Result<Option<PathBuf>> {
+        match self.bounce.lock() {
+            Ok(mut bounce) => bounce.transport_stopped(),
+            Err(_) => Err(io::Error::other(AudioError::LockFailed)),
+        }
+    }
+
     /// Send note on
     pub fn note_on(&self, channel: u8, note: u8, velocity: u8) {
         if let Ok(mut synth) = self.synth.lock() {
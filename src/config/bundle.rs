@@ -0,0 +1,239 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Song file paths and portable project bundles.
+//!
+//! Files a song refers to (clips, the soundfont, SysEx dumps) are written
+//! relative to the song file, so a project folder can move as a whole. A
+//! bundle goes further: one zip holding the song and copies of everything
+//! it refers to, with the paths rewritten to point inside the archive.
+//! Opening a bundle unpacks it into a temporary folder and loads the song
+//! from there; the folder is removed when the loaded song lets go of it.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use tempfile::TempDir;
+use zip::write::FileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use super::SongFile;
+
+/// Name of the song inside a bundle
+pub const BUNDLE_SONG: &str = "song.yaml";
+
+/// Folder the assets are stored under inside a bundle
+pub const BUNDLE_ASSETS: &str = "assets";
+
+/// Resolve a path from a song file: relative paths are taken from the
+/// song file's folder, absolute ones are left alone
+pub fn resolve_path(song_path: &Path, asset: &str) -> PathBuf {
+    let asset = Path::new(asset);
+    if asset.is_absolute() {
+        return asset.to_path_buf();
+    }
+    song_path.parent().unwrap_or(Path::new("")).join(asset)
+}
+
+/// Check if a path names a bundle
+pub fn is_bundle(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Write a song and every file it refers to into a zip. Returns the names
+/// of the stored assets.
+pub fn write_bundle(song_path: &Path, out: &Path) -> Result<Vec<String>> {
+    let mut song = SongFile::load(song_path)?;
+
+    // Give each referenced file a unique name under assets/
+    let mut entries: HashMap<String, String> = HashMap::new();
+    let mut files: Vec<(String, PathBuf)> = Vec::new();
+    for asset in song.asset_paths() {
+        let source = resolve_path(song_path, asset);
+        if !source.is_file() {
+            return Err(anyhow!("Song refers to a missing file: {:?}", source));
+        }
+        let entry = unique_entry(BUNDLE_ASSETS, &source, files.iter().map(|(e, _)| e.as_str()));
+        entries.insert(asset.to_string(), entry.clone());
+        files.push((entry, source));
+    }
+    song.map_asset_paths(|path| entries.get(path).cloned().unwrap_or_else(|| path.to_string()));
+
+    let file = File::create(out).with_context(|| format!("Failed to create bundle: {:?}", out))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default();
+    zip.start_file(BUNDLE_SONG, options)?;
+    zip.write_all(song.to_yaml()?.as_bytes())?;
+    for (entry, source) in &files {
+        let bytes = fs::read(source).with_context(|| format!("Failed to read {:?}", source))?;
+        zip.start_file(entry.as_str(), options)?;
+        zip.write_all(&bytes)?;
+    }
+    zip.finish()?;
+    Ok(files.into_iter().map(|(entry, _)| entry).collect())
+}
+
+/// Name for a file stored under a folder, numbered past names already taken
+pub(crate) fn unique_entry<'a>(folder: &str, source: &Path, taken: impl Iterator<Item = &'a str> + Clone) -> String {
+    let name = source.file_name().and_then(|n| n.to_str()).unwrap_or("asset");
+    let mut entry = format!("{}/{}", folder, name);
+    let mut n = 2;
+    while taken.clone().any(|e| e == entry) {
+        let stem = Path::new(name).file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+        entry = match Path::new(name).extension().and_then(|e| e.to_str()) {
+            Some(ext) => format!("{}/{}-{}.{}", folder, stem, n, ext),
+            None => format!("{}/{}-{}", folder, stem, n),
+        };
+        n += 1;
+    }
+    entry
+}
+
+/// Unpack a bundle into a folder. Returns the path of the song in it.
+pub fn open_bundle(bundle: &Path, dir: &Path) -> Result<PathBuf> {
+    let file = File::open(bundle).with_context(|| format!("Failed to open bundle: {:?}", bundle))?;
+    let mut archive = ZipArchive::new(file).with_context(|| format!("Not a bundle: {:?}", bundle))?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let name = entry
+            .enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| anyhow!("Bundle entry '{}' points outside the bundle", entry.name()))?;
+        let target = dir.join(name);
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        io::copy(&mut entry, &mut File::create(&target)?)?;
+    }
+    let song = dir.join(BUNDLE_SONG);
+    if !song.is_file() {
+        return Err(anyhow!("Bundle {:?} has no {}", bundle, BUNDLE_SONG));
+    }
+    Ok(song)
+}
+
+/// A song file ready to load. An unpacked bundle's folder stays on disk
+/// until the last copy of this is dropped.
+#[derive(Debug, Clone)]
+pub struct UnpackedSong {
+    /// Song file
+    path: PathBuf,
+    /// Temporary folder a bundle was unpacked into
+    dir: Option<Arc<TempDir>>,
+}
+
+impl UnpackedSong {
+    /// Path of the song file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check if the song was unpacked from a bundle
+    pub fn is_unpacked(&self) -> bool {
+        self.dir.is_some()
+    }
+}
+
+/// Song to load: a bundle is unpacked into a temporary folder, anything
+/// else is used as is
+pub fn unpack_if_bundle(path: &Path) -> Result<UnpackedSong> {
+    if !is_bundle(path) {
+        return Ok(UnpackedSong {
+            path: path.to_path_buf(),
+            dir: None,
+        });
+    }
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("bundle");
+    let dir = tempfile::Builder::new().prefix(&format!("seq-{}-", stem)).tempdir()?;
+    let song = open_bundle(path, dir.path())?;
+    Ok(UnpackedSong {
+        path: song,
+        dir: Some(Arc::new(dir)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_path() {
+        let song = Path::new("/music/live/song.yaml");
+        assert_eq!(resolve_path(song, "clips/bass.yaml"), PathBuf::from("/music/live/clips/bass.yaml"));
+        assert_eq!(resolve_path(song, "/sounds/gm.sf2"), PathBuf::from("/sounds/gm.sf2"));
+        assert_eq!(resolve_path(Path::new("song.yaml"), "a.mid"), PathBuf::from("a.mid"));
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("project");
+        fs::create_dir_all(project.join("clips")).unwrap();
+        fs::create_dir_all(dir.path().join("sounds")).unwrap();
+        fs::write(project.join("clips/bass.yaml"), "notes: []").unwrap();
+        fs::write(project.join("bass.yaml"), "notes: [1]").unwrap();
+        fs::write(dir.path().join("sounds/gm.sf2"), [1u8, 2, 3]).unwrap();
+        fs::write(project.join("patch.syx"), [0xF0u8, 0x41, 0x10, 0xF7, 0xF0, 0x7E, 0xF7]).unwrap();
+        let song_path = project.join("song.yaml");
+        fs::write(
+            &song_path,
+            r#"
+song:
+  name: "Portable"
+  soundfont: "../sounds/gm.sf2"
+tracks:
+  - name: "Bass"
+    clips:
+      - file: "clips/bass.yaml"
+      - file: "bass.yaml"
+snapshots:
+  - sysex: ["patch.syx"]
+"#,
+        )
+        .unwrap();
+        let song = SongFile::load(&song_path).unwrap();
+        assert!(song.missing_assets(&song_path).is_empty());
+        assert_eq!(song.snapshots[0].sysex_messages(&song_path).unwrap().len(), 2);
+
+        let bundle = dir.path().join("portable.zip");
+        let stored = write_bundle(&song_path, &bundle).unwrap();
+        assert_eq!(stored, vec!["assets/bass.yaml", "assets/bass-2.yaml", "assets/gm.sf2", "assets/patch.syx"]);
+
+        let opened = open_bundle(&bundle, &dir.path().join("elsewhere")).unwrap();
+        let song = SongFile::load(&opened).unwrap();
+        assert!(song.missing_assets(&opened).is_empty());
+        assert_eq!(song.song.soundfont.as_deref(), Some("assets/gm.sf2"));
+        let clip = resolve_path(&opened, song.tracks[0].clips[1].file.as_deref().unwrap());
+        assert_eq!(fs::read_to_string(clip).unwrap(), "notes: [1]");
+
+        fs::remove_file(project.join("patch.syx")).unwrap();
+        assert!(write_bundle(&song_path, &bundle).is_err());
+    }
+
+    #[test]
+    fn test_unpacked_bundle_is_removed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let song_path = dir.path().join("song.yaml");
+        fs::write(&song_path, "song:\n  name: \"Temp\"\n").unwrap();
+        let bundle = dir.path().join("temp.zip");
+        write_bundle(&song_path, &bundle).unwrap();
+
+        let unpacked = unpack_if_bundle(&bundle).unwrap();
+        let copy = unpacked.clone();
+        let song = unpacked.path().to_path_buf();
+        assert!(unpacked.is_unpacked() && song.is_file());
+        drop(unpacked);
+        assert!(song.is_file());
+        drop(copy);
+        assert!(!song.exists());
+        assert!(!unpack_if_bundle(&song_path).unwrap().is_unpacked());
+    }
+}
@@ -6,19 +6,54 @@
 //! This module provides data structures for loading and managing
 //! song configurations, track settings, parts, and controller mappings.
 
+pub mod bundle;
+pub mod profile;
+pub mod project;
+pub mod reload;
 pub mod watcher;
 
+pub use bundle::{open_bundle, resolve_path, unpack_if_bundle, write_bundle, UnpackedSong};
+pub use profile::PerformanceProfile;
+pub use project::{load_scales, save_project, Project, ProjectManifest};
+pub use reload::{HotReload, ReloadPlan, ReloadPolicy, SongChange};
 pub use watcher::{ConfigEvent, ConfigWatcher, validate_config};
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::arrangement::{
+    DeviceSnapshot, EnergyParam, EnergyPolicy, FxEvent, FxLibrary, FxShape, Part, PartGuard, Scene, SceneManager,
+    SceneSlot, SnapshotValue, TrackClipState,
+};
+use crate::control::auto_layout::DEFAULT_KNOB_COUNT;
+use crate::control::modulation::{LfoShape, ModMatrix, ModSource, Modulator};
+use crate::control::osc::{OscMapper, OscServer, DEFAULT_OSC_PORT, DEFAULT_OSC_PREFIX};
+use crate::midi::rtp::DEFAULT_RTP_PORT;
+use crate::midi::{BeatPulse, ExternalClockSync, MidiInput, PulseFormat, PulseSender, RtpMidiSession};
+use crate::generators::arpeggio::{ArpPresetLibrary, ArpStep, ArpeggioGenerator};
+use crate::generators::{Generator, GeneratorContext, GeneratorRegistry, ParamSpec};
+use crate::music::parse_midi_note;
+use crate::recording::RecordInput;
+use crate::sequencer::TrackState as PlaybackState;
+use crate::sequencer::track::resolve_destination;
+use crate::sequencer::groove::{MAX_GROOVE_OFFSET, MAX_GROOVE_VELOCITY};
+use crate::sequencer::processor::chord_shape;
+use crate::sequencer::step_mod::{StepLane, MAX_LANE_STEPS, MAX_STEPS_PER_BAR};
+use crate::sequencer::{
+    ArmedInput, CcThinner, ChainEntry, Clip, ClipCc, ClipShuffle, GainMeter, GrooveTemplate, KeyZone, KeyZoneMode,
+    Metronome, MpeZone, PatternChain, PedalMode, Processor, ProcessorChain, QuantizeMode, TransposeMode, TriggerSource,
+};
+use crate::timing::calibration::MAX_SYNC_OFFSET_MS;
+use crate::timing::{is_valid_ppqn, PositionMode, SyncOffsets, TempoHumanizer, TempoProfile, PPQN};
+use crate::ui::BeatFlash;
+
 /// Root configuration for a song
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct SongFile {
     /// Song metadata and settings
     pub song: SongConfig,
@@ -28,6 +63,27 @@ pub struct SongFile {
     /// Part definitions (named collections of track states)
     #[serde(default)]
     pub parts: HashMap<String, PartConfig>,
+    /// User arpeggio step-order presets (name -> order, e.g. "1-3-r-5^")
+    #[serde(default)]
+    pub arp_presets: HashMap<String, String>,
+    /// One-shot FX definitions (risers, sweeps, impacts)
+    #[serde(default)]
+    pub fx: HashMap<String, FxConfig>,
+    /// External device snapshots sent when the song loads
+    #[serde(default)]
+    pub snapshots: Vec<SnapshotConfig>,
+    /// Modulation sources bound to parameters
+    #[serde(default)]
+    pub modulation: Vec<ModulationConfig>,
+    /// Saved scenes, in launch order (the first eight are on F1-F8)
+    #[serde(default)]
+    pub scenes: Vec<Scene>,
+    /// User groove templates (name -> per-sixteenth offsets and velocities)
+    #[serde(default)]
+    pub grooves: HashMap<String, GrooveConfig>,
+    /// What the one-knob energy control moves
+    #[serde(default)]
+    pub energy: Option<EnergyConfig>,
 }
 
 impl SongFile {
@@ -38,6 +94,13 @@ impl SongFile {
         Self::from_yaml(&contents)
     }
 
+    /// Load a project (a folder, zip, manifest or song file). Its `song` has
+    /// every file path resolved against the project, and an unpacked zip
+    /// stays on disk while the project is kept.
+    pub fn load_project<P: AsRef<Path>>(path: P) -> Result<Project> {
+        Project::open(path.as_ref())
+    }
+
     /// Parse a song configuration from YAML string
     pub fn from_yaml(yaml: &str) -> Result<Self> {
         serde_yaml::from_str(yaml).context("Failed to parse YAML configuration")
@@ -48,6 +111,366 @@ impl SongFile {
         serde_yaml::to_string(self).context("Failed to serialize configuration to YAML")
     }
 
+    /// Build the FX library from the FX definitions
+    pub fn fx_library(&self) -> Result<FxLibrary> {
+        let mut library = FxLibrary::new();
+        for (name, fx) in &self.fx {
+            library.add(fx.to_event(name)?);
+        }
+        Ok(library)
+    }
+
+    /// Build the modulators bound to parameters
+    pub fn mod_matrix(&self) -> Result<ModMatrix> {
+        let mut matrix = ModMatrix::new();
+        for modulation in &self.modulation {
+            matrix.add(modulation.to_modulator()?);
+        }
+        Ok(matrix)
+    }
+
+    /// Build the energy policy: the song's own mappings, then the built-in
+    /// ones for each generator unless turned off
+    pub fn energy_policy(&self) -> Result<EnergyPolicy> {
+        let Some(energy) = &self.energy else {
+            return Ok(EnergyPolicy::new());
+        };
+        let track_index = |name: &str| {
+            self.tracks
+                .iter()
+                .position(|t| t.name == name)
+                .ok_or_else(|| anyhow!("Energy refers to unknown track '{}'", name))
+        };
+
+        let mut policy = EnergyPolicy::new();
+        match (energy.tempo_min, energy.tempo_max) {
+            (Some(low), Some(high)) => {
+                if let Some(tempo) = [low, high].into_iter().find(|t| !(20.0..=300.0).contains(t)) {
+                    return Err(anyhow!("Energy tempo {} is out of range (use 20-300)", tempo));
+                }
+                policy = policy.with_tempo(low, high);
+            }
+            (None, None) => {}
+            _ => return Err(anyhow!("Energy needs both tempo_min and tempo_max")),
+        }
+        let layers = energy.layers.iter().map(|name| track_index(name)).collect::<Result<_>>()?;
+        policy = policy.with_layers(layers);
+
+        let registry = GeneratorRegistry::with_builtins();
+        for param in &energy.params {
+            let index = track_index(&param.track)?;
+            let track = &self.tracks[index];
+            if let Some(generator) = track.generator.as_deref() {
+                let specs = registry.param_specs(generator);
+                if !specs.is_empty() && !specs.iter().any(|spec| spec.name == param.param) {
+                    return Err(anyhow!(
+                        "Energy sets unknown {} parameter '{}' on track '{}'",
+                        generator,
+                        param.param,
+                        track.name
+                    ));
+                }
+                check_param_range(&specs, &track.name, generator, &param.param, param.low)?;
+                check_param_range(&specs, &track.name, generator, &param.param, param.high)?;
+            }
+            policy = policy.with_param(EnergyParam::new(index, param.param.clone(), param.low, param.high));
+        }
+        if energy.heuristics {
+            for (index, track) in self.tracks.iter().enumerate() {
+                if let Some(generator) = track.generator.as_deref() {
+                    policy = policy.with_heuristics(index, generator);
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Scene manager holding the saved scenes
+    pub fn scene_manager(&self) -> SceneManager {
+        SceneManager::with_scenes(self.tracks.len(), self.scenes.clone())
+    }
+
+    /// Arpeggio presets: the built-ins plus the song's `arp_presets`
+    pub fn arp_library(&self) -> Result<ArpPresetLibrary> {
+        let mut library = ArpPresetLibrary::with_builtins();
+        library.load_strings(&self.arp_presets)?;
+        Ok(library)
+    }
+
+    /// Save an arpeggio step order (e.g. from the editor) as a song preset
+    pub fn save_arp_preset(&mut self, name: &str, steps: &[ArpStep]) -> Result<()> {
+        if name.trim().is_empty() || steps.is_empty() {
+            return Err(anyhow!("Arpeggio preset needs a name and at least one step"));
+        }
+        self.arp_presets.insert(name.trim().to_string(), ArpStep::format_order(steps));
+        Ok(())
+    }
+
+    /// Build a track's generator with its parameters. An arpeggio takes a
+    /// custom `order` or a named `preset` from its config.
+    pub fn build_generator(
+        &self,
+        track: &TrackConfig,
+        registry: &GeneratorRegistry,
+    ) -> Result<Option<Box<dyn Generator>>> {
+        let Some(name) = track.generator.as_deref() else {
+            return Ok(None);
+        };
+        let mut generator = registry
+            .create(name)
+            .ok_or_else(|| anyhow!("Track '{}' has unknown generator '{}'", track.name, name))?;
+        for (param, value) in &track.config.params {
+            match value {
+                GeneratorValue::Int(v) => generator.set_param(param, *v as f64),
+                GeneratorValue::Float(v) => generator.set_param(param, *v),
+                GeneratorValue::Bool(v) => generator.set_param(param, if *v { 1.0 } else { 0.0 }),
+                _ => {}
+            }
+        }
+        let order = track.config.get_string("order", "");
+        let preset = track.config.get_string("preset", "");
+        if name != "arpeggio" || (order.is_empty() && preset.is_empty()) {
+            return Ok(Some(generator));
+        }
+
+        let mut arpeggio = ArpeggioGenerator::new();
+        for (param, value) in generator.params() {
+            arpeggio.set_param(&param, value);
+        }
+        if !order.is_empty() {
+            arpeggio
+                .set_step_order(&order)
+                .map_err(|e| anyhow!("Track '{}' has invalid arpeggio order: {}", track.name, e))?;
+        } else {
+            arpeggio
+                .load_preset(&self.arp_library()?, &preset)
+                .map_err(|e| anyhow!("Track '{}' has {}", track.name, e))?;
+        }
+        Ok(Some(Box::new(arpeggio)))
+    }
+
+    /// Groove template of a track: a user groove by that name, else a
+    /// built-in one
+    pub fn track_groove(&self, track: &TrackConfig) -> Result<Option<GrooveTemplate>> {
+        let Some(name) = track.groove.as_deref() else {
+            return Ok(None);
+        };
+        if let Some((name, groove)) = self.grooves.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            return groove.to_template(name).map(Some);
+        }
+        GrooveTemplate::builtin(name)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Track '{}' has unknown groove '{}'", track.name, name))
+    }
+
+    /// Swing of a track: its own, or the song's
+    pub fn track_swing(&self, track: &TrackConfig) -> f64 {
+        track.swing.unwrap_or(self.song.swing).clamp(0.0, 1.0)
+    }
+
+    /// Keep the manager's scenes, to be written with the song
+    pub fn store_scenes(&mut self, manager: &SceneManager) {
+        self.scenes = manager.scenes().to_vec();
+    }
+
+    /// Check settings that parse but cannot be used
+    pub fn validate(&self) -> Result<()> {
+        self.song.resolution()?;
+        self.song.tempo_humanizer()?;
+        self.fx_library()?;
+        self.mod_matrix()?;
+        self.energy_policy()?;
+        self.arp_library()?;
+        for (name, groove) in &self.grooves {
+            groove.to_template(name)?;
+        }
+        for track in &self.tracks {
+            if !(1..=16).contains(&track.channel) {
+                return Err(anyhow!("Track '{}' has invalid channel {} (use 1-16)", track.name, track.channel));
+            }
+            if let Some(round_robin) = &track.round_robin {
+                if round_robin.voices.is_empty() {
+                    return Err(anyhow!("Track '{}' has a round robin with no channels", track.name));
+                }
+                if let Some(voice) = round_robin.voices.iter().find(|v| !(1..=16).contains(&v.channel)) {
+                    return Err(anyhow!(
+                        "Track '{}' has invalid round robin channel {} (use 1-16)",
+                        track.name,
+                        voice.channel
+                    ));
+                }
+            }
+            if let Some(thin) = &track.cc_thin {
+                thin.to_thinner().map_err(|e| anyhow!("Track '{}' has {}", track.name, e))?;
+            }
+            track.processor_chain()?;
+            track.step_lanes()?;
+            if let Some(mpe) = &track.mpe {
+                mpe.zone().map_err(|e| anyhow!("Track '{}' has {}", track.name, e))?;
+                if !(1..=15).contains(&mpe.members) {
+                    return Err(anyhow!("Track '{}' has {} MPE channels (use 1-15)", track.name, mpe.members));
+                }
+            }
+            if let Some(channel) = track.input_channel.filter(|c| !(1..=16).contains(c)) {
+                return Err(anyhow!("Track '{}' has invalid input channel {} (use 1-16)", track.name, channel));
+            }
+            track.armed_input(0)?;
+            track.pedal_mode()?;
+            track.transpose_mode()?;
+            self.track_groove(track)?;
+            track.pattern_chain()?;
+            let registry = GeneratorRegistry::with_builtins();
+            if track.generator.as_deref() == Some("arpeggio") {
+                self.build_generator(track, &registry)?;
+            }
+            for generator in track.clips.iter().filter_map(|c| c.generator.as_deref()) {
+                if registry.create(generator).is_none() {
+                    return Err(anyhow!("Track '{}' has a clip with unknown generator '{}'", track.name, generator));
+                }
+            }
+            if let Some(generator) = track.generator.as_deref() {
+                let specs = registry.param_specs(generator);
+                for (key, value) in &track.config.params {
+                    let value = match value {
+                        GeneratorValue::Int(v) => *v as f64,
+                        GeneratorValue::Float(v) => *v,
+                        _ => continue,
+                    };
+                    check_param_range(&specs, &track.name, generator, key, value)?;
+                }
+                for lane in &track.step_lanes {
+                    if !specs.is_empty() && !specs.iter().any(|spec| spec.name == lane.param) {
+                        return Err(anyhow!(
+                            "Track '{}' has a step lane for unknown {} parameter '{}'",
+                            track.name,
+                            generator,
+                            lane.param
+                        ));
+                    }
+                    for &value in &lane.steps {
+                        check_param_range(&specs, &track.name, generator, &lane.param, value)?;
+                    }
+                }
+            }
+            for clip in &track.clips {
+                for cc in &clip.ccs {
+                    cc.to_cc().map_err(|e| anyhow!("Track '{}' has a clip with {}", track.name, e))?;
+                }
+                if let Some(generator) = clip.generator.as_deref() {
+                    let specs = registry.param_specs(generator);
+                    for (key, &value) in &clip.params {
+                        check_param_range(&specs, &track.name, generator, key, value)?;
+                    }
+                }
+            }
+        }
+        for (name, part) in &self.parts {
+            for (track, &chance) in &part.layers {
+                if !self.tracks.iter().any(|t| &t.name == track) {
+                    return Err(anyhow!("Part '{}' has a layer on unknown track '{}'", name, track));
+                }
+                if !(0.0..=1.0).contains(&chance) {
+                    return Err(anyhow!(
+                        "Part '{}' has invalid chance {} for layer '{}' (use 0-1)",
+                        name,
+                        chance,
+                        track
+                    ));
+                }
+            }
+        }
+        for scene in &self.scenes {
+            let tracks = scene.slots().keys().chain(scene.mutes().keys());
+            if let Some(track) = tracks.copied().find(|&t| t >= self.tracks.len()) {
+                return Err(anyhow!(
+                    "Scene '{}' refers to track {}, but the song has {} track(s)",
+                    scene.name(),
+                    track,
+                    self.tracks.len()
+                ));
+            }
+            for (&track, slot) in scene.slots() {
+                let clips = self.tracks[track].clips.len();
+                if let SceneSlot::Clip(clip) = *slot {
+                    if clip >= clips {
+                        return Err(anyhow!(
+                            "Scene '{}' plays clip {} on track '{}', which has {} clip(s)",
+                            scene.name(),
+                            clip,
+                            self.tracks[track].name,
+                            clips
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every MIDI destination named by the tracks, to open before playing
+    pub fn destination_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in self.tracks.iter().flat_map(|t| t.destination_names()) {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Gain meter with each track's target level
+    pub fn gain_meter(&self) -> GainMeter {
+        let mut meter = GainMeter::new();
+        for (index, track) in self.tracks.iter().enumerate() {
+            if let Some(level) = track.target_level {
+                meter.set_target(index, level);
+            }
+        }
+        meter
+    }
+
+    /// Files the song refers to (clips, soundfont, SysEx dumps), as written
+    pub fn asset_paths(&self) -> Vec<&str> {
+        let clips = self.tracks.iter().flat_map(|t| &t.clips).filter_map(|c| c.file.as_deref());
+        let snapshots = self.snapshots.iter().chain(self.parts.values().flat_map(|p| &p.snapshots));
+        let mut paths: Vec<&str> = Vec::new();
+        let all = clips
+            .chain(self.song.soundfont.as_deref())
+            .chain(snapshots.flat_map(|s| s.sysex.iter().map(String::as_str)));
+        for path in all {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Referenced files that don't exist, resolved against the song file
+    pub fn missing_assets(&self, song_path: &Path) -> Vec<PathBuf> {
+        self.asset_paths()
+            .into_iter()
+            .map(|asset| resolve_path(song_path, asset))
+            .filter(|path| !path.is_file())
+            .collect()
+    }
+
+    /// Rewrite every referenced file path
+    pub fn map_asset_paths(&mut self, mut f: impl FnMut(&str) -> String) {
+        let clips = self.tracks.iter_mut().flat_map(|t| &mut t.clips).filter_map(|c| c.file.as_mut());
+        let snapshots = self.snapshots.iter_mut().chain(self.parts.values_mut().flat_map(|p| &mut p.snapshots));
+        for path in clips
+            .chain(self.song.soundfont.as_mut())
+            .chain(snapshots.flat_map(|s| s.sysex.iter_mut()))
+        {
+            *path = f(path);
+        }
+    }
+
+    /// Instrument range problems across all tracks
+    pub fn range_warnings(&self) -> Vec<String> {
+        self.tracks.iter().flat_map(TrackConfig::range_warnings).collect()
+    }
+
     /// Save configuration to a YAML file
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let yaml = self.to_yaml()?;
@@ -56,6 +479,68 @@ impl SongFile {
     }
 }
 
+/// Check a generator parameter from the YAML against its documented range
+fn check_param_range(specs: &[ParamSpec], track: &str, generator: &str, key: &str, value: f64) -> Result<()> {
+    match specs.iter().find(|spec| spec.name == key) {
+        Some(spec) if !spec.contains(value) => Err(anyhow!(
+            "Track '{}': {} parameter '{}' is {}, outside {}-{}",
+            track,
+            generator,
+            key,
+            value,
+            spec.min,
+            spec.max
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Song and controls loaded at startup.
+///
+/// A file that fails to load or validate is replaced by an empty default
+/// and its error kept for display, so a live rig still comes up (in safe
+/// mode) and the file can be fixed on the spot.
+#[derive(Debug, Clone, Default)]
+pub struct StartupConfig {
+    /// Song (empty if it failed)
+    pub song: SongFile,
+    /// Controls (defaults if they failed)
+    pub controls: ControlsFile,
+    /// Load errors with their context, one per failed file
+    pub errors: Vec<String>,
+    /// Bundle the song was unpacked from, kept on disk while this lives
+    pub bundle: Option<UnpackedSong>,
+}
+
+impl StartupConfig {
+    /// Load whichever files are given, falling back per file
+    pub fn load(song_path: Option<&Path>, controls_path: Option<&Path>) -> Self {
+        let mut config = Self::default();
+        if let Some(path) = song_path {
+            let loaded = unpack_if_bundle(path).and_then(|unpacked| Ok((validate_config(unpacked.path())?, unpacked)));
+            match loaded {
+                Ok((song, unpacked)) => {
+                    config.song = song;
+                    config.bundle = unpacked.is_unpacked().then_some(unpacked);
+                }
+                Err(e) => config.errors.push(format!("{}: {:#}", path.display(), e)),
+            }
+        }
+        if let Some(path) = controls_path {
+            match ControlsFile::load(path) {
+                Ok(controls) => config.controls = controls,
+                Err(e) => config.errors.push(format!("{}: {:#}", path.display(), e)),
+            }
+        }
+        config
+    }
+
+    /// Check if any file fell back to defaults
+    pub fn is_safe_mode(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
 /// Song-level configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SongConfig {
@@ -79,6 +564,54 @@ pub struct SongConfig {
     /// Global swing amount (0.0 - 1.0)
     #[serde(default)]
     pub swing: f64,
+    /// Clip length rounding on record stop ("bar", "pow2", "off")
+    #[serde(default)]
+    pub record_rounding: Option<String>,
+    /// Random tempo drift as a fraction of the tempo (0.0 - 0.1)
+    #[serde(default)]
+    pub tempo_drift: f64,
+    /// Bar-level tempo profile ("push", "drag", "breathe", optionally ":depth")
+    #[serde(default)]
+    pub tempo_profile: Option<String>,
+    /// Global note length multiplier (0.1 - 2.0, default 1.0)
+    #[serde(default = "default_gate_scale")]
+    pub gate_scale: f64,
+    /// Internal resolution in ticks per quarter note (a multiple of 24, up to 960)
+    #[serde(default = "default_ppqn")]
+    pub ppqn: u32,
+    /// SF2 soundfont for the built-in synth (relative to the song file)
+    #[serde(default)]
+    pub soundfont: Option<String>,
+}
+
+impl SongConfig {
+    /// Get the internal resolution, checking it is supported
+    pub fn resolution(&self) -> Result<u32> {
+        if is_valid_ppqn(self.ppqn) {
+            Ok(self.ppqn)
+        } else {
+            Err(anyhow!("Invalid ppqn {} (use a multiple of 24, up to 960)", self.ppqn))
+        }
+    }
+
+    /// Build the tempo humanizer, if drift or a profile is set
+    pub fn tempo_humanizer(&self) -> Result<Option<TempoHumanizer>> {
+        let profile = match self.tempo_profile.as_deref() {
+            Some(spec) => Some(
+                TempoProfile::parse(spec).ok_or_else(|| anyhow!("Invalid tempo profile '{}'", spec))?,
+            ),
+            None => None,
+        };
+        if self.tempo_drift <= 0.0 && profile.is_none() {
+            return Ok(None);
+        }
+        let mut humanizer = TempoHumanizer::new(self.tempo_drift);
+        if let Some((profile, depth)) = profile {
+            humanizer.set_profile(profile, depth);
+        }
+        humanizer.set_beats_per_bar(self.time_signature_num as u32);
+        Ok(Some(humanizer))
+    }
 }
 
 fn default_tempo() -> f64 {
@@ -96,6 +629,9 @@ fn default_time_sig_num() -> u8 {
 fn default_time_sig_den() -> u8 {
     4
 }
+fn default_ppqn() -> u32 {
+    PPQN
+}
 
 impl Default for SongConfig {
     fn default() -> Self {
@@ -107,6 +643,12 @@ impl Default for SongConfig {
             time_signature_num: default_time_sig_num(),
             time_signature_den: default_time_sig_den(),
             swing: 0.0,
+            record_rounding: None,
+            tempo_drift: 0.0,
+            tempo_profile: None,
+            gate_scale: default_gate_scale(),
+            ppqn: default_ppqn(),
+            soundfont: None,
         }
     }
 }
@@ -116,9 +658,18 @@ impl Default for SongConfig {
 pub struct TrackConfig {
     /// Track name (used for reference in parts)
     pub name: String,
+    /// Short display name for the track list
+    #[serde(default)]
+    pub short_name: Option<String>,
+    /// Icon shown before the track name
+    #[serde(default)]
+    pub icon: Option<String>,
     /// MIDI channel (1-16)
     #[serde(default = "default_channel")]
     pub channel: u8,
+    /// MIDI destination name for `channel` (None = default output)
+    #[serde(default)]
+    pub destination: Option<String>,
     /// Generator type (if using algorithmic generation)
     #[serde(default)]
     pub generator: Option<String>,
@@ -128,15 +679,98 @@ pub struct TrackConfig {
     /// Static clips for this track
     #[serde(default)]
     pub clips: Vec<ClipReference>,
-    /// Track transpose in semitones
+    /// Track transpose, in semitones or scale degrees (see `transpose_mode`)
     #[serde(default)]
     pub transpose: i8,
+    /// What the transpose counts: "semitones" (default) or "degrees" in the
+    /// song key, so layered parts harmonize in key
+    #[serde(default)]
+    pub transpose_mode: Option<String>,
     /// Track-specific swing override
     #[serde(default)]
     pub swing: Option<f64>,
     /// Velocity scaling (0.0 - 2.0, default 1.0)
     #[serde(default = "default_velocity_scale")]
     pub velocity_scale: f64,
+    /// Additional output layers (empty = single output on `channel`)
+    #[serde(default)]
+    pub outputs: Vec<OutputConfig>,
+    /// Latch incoming notes until pressed again
+    #[serde(default)]
+    pub latch: bool,
+    /// Per-voice routing for drum tracks
+    #[serde(default)]
+    pub voices: Vec<VoiceRouteConfig>,
+    /// Rotate successive notes across channels
+    #[serde(default)]
+    pub round_robin: Option<RoundRobinConfig>,
+    /// Play each note on its own channel as MPE, with per-note expression
+    #[serde(default)]
+    pub mpe: Option<MpeConfig>,
+    /// Rate limit on CC and channel pressure output, for slow hardware
+    #[serde(default)]
+    pub cc_thin: Option<CcThinConfig>,
+    /// Post-processing of the track's notes, applied in order
+    #[serde(default)]
+    pub processors: Vec<ProcessorConfig>,
+    /// Step-sequenced generator parameters
+    #[serde(default)]
+    pub step_lanes: Vec<StepLaneConfig>,
+    /// Program to select on load: GM name ("Warm Pad") or number (0-127)
+    #[serde(default)]
+    pub program: Option<String>,
+    /// Chance that the track's output plays (0.0 - 1.0, default 1.0)
+    #[serde(default = "default_play_probability")]
+    pub play_probability: f64,
+    /// When the play probability is rolled: "event" (default) or "bar"
+    #[serde(default)]
+    pub probability_mode: Option<String>,
+    /// Playable range of the instrument, e.g. "E1-E4" or "28-64"
+    #[serde(default)]
+    pub range: Option<String>,
+    /// Fold notes outside the range in by octaves instead of dropping them
+    #[serde(default)]
+    pub fold_range: bool,
+    /// Mute group: launching a clip stops the other tracks in the group
+    #[serde(default)]
+    pub mute_group: Option<String>,
+    /// Note length multiplier (0.1 - 2.0, default 1.0)
+    #[serde(default = "default_gate_scale")]
+    pub gate_scale: f64,
+    /// Overlapping identical notes: "retrigger" (default), "extend" or "ignore"
+    #[serde(default)]
+    pub overlap: Option<String>,
+    /// Pick a random clip every N bars ("2 bars") or each loop ("loop")
+    #[serde(default)]
+    pub shuffle: Option<String>,
+    /// Play clips in a fixed order, by name or number with optional repeat
+    /// counts ("A A B C", "Verse x3, Fill")
+    #[serde(default)]
+    pub chain: Option<String>,
+    /// MIDI input recorded and played thru on this track (name substring,
+    /// None = any input)
+    #[serde(default)]
+    pub input: Option<String>,
+    /// Input channel recorded on this track (1-16, None = any channel)
+    #[serde(default)]
+    pub input_channel: Option<u8>,
+    /// Keys played thru this track ("C2-C5" or "36-72", None = all)
+    #[serde(default)]
+    pub input_range: Option<String>,
+    /// Snap notes played thru into the song key
+    #[serde(default)]
+    pub input_quantize: bool,
+    /// Target average velocity for gain staging (1-127, default 90)
+    #[serde(default)]
+    pub target_level: Option<u8>,
+    /// Sustain pedal behavior: "thru" (default), "sustain", "sostenuto",
+    /// "latch" or "hold"
+    #[serde(default)]
+    pub pedal: Option<String>,
+    /// Groove template: a built-in ("swing", "shuffle", "push", "lazy") or
+    /// one from the song's `grooves`
+    #[serde(default)]
+    pub groove: Option<String>,
 }
 
 fn default_channel() -> u8 {
@@ -145,396 +779,2742 @@ fn default_channel() -> u8 {
 fn default_velocity_scale() -> f64 {
     1.0
 }
+fn default_play_probability() -> f64 {
+    1.0
+}
+fn default_gate_scale() -> f64 {
+    1.0
+}
 
 impl Default for TrackConfig {
     fn default() -> Self {
         Self {
             name: "Track".to_string(),
+            short_name: None,
+            icon: None,
             channel: default_channel(),
+            destination: None,
             generator: None,
             config: GeneratorConfig::default(),
             clips: Vec::new(),
             transpose: 0,
+            transpose_mode: None,
             swing: None,
             velocity_scale: default_velocity_scale(),
+            outputs: Vec::new(),
+            latch: false,
+            voices: Vec::new(),
+            round_robin: None,
+            mpe: None,
+            cc_thin: None,
+            processors: Vec::new(),
+            step_lanes: Vec::new(),
+            program: None,
+            play_probability: default_play_probability(),
+            probability_mode: None,
+            range: None,
+            fold_range: false,
+            mute_group: None,
+            gate_scale: default_gate_scale(),
+            overlap: None,
+            shuffle: None,
+            chain: None,
+            input: None,
+            input_channel: None,
+            input_range: None,
+            input_quantize: false,
+            target_level: None,
+            pedal: None,
+            groove: None,
         }
     }
 }
 
-/// Reference to a clip file or inline clip
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ClipReference {
-    /// File path to clip (relative to song file)
-    #[serde(default)]
-    pub file: Option<String>,
-    /// Clip name/identifier
-    #[serde(default)]
-    pub name: Option<String>,
-}
+impl TrackConfig {
+    /// Resolve the program to a number, by GM name or number
+    pub fn program_number(&self) -> Option<u8> {
+        crate::midi::gm::program_number(self.program.as_deref()?)
+    }
 
-/// Generator-specific configuration (flexible key-value pairs)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
-#[serde(transparent)]
-pub struct GeneratorConfig {
-    /// Configuration parameters as key-value pairs
-    pub params: HashMap<String, GeneratorValue>,
-}
+    /// Index of a clip by name, or by position as "clip_1", "clip_2", ...
+    pub fn clip_index(&self, name: &str) -> Option<usize> {
+        if let Some(index) = self.clips.iter().position(|c| c.name.as_deref() == Some(name)) {
+            return Some(index);
+        }
+        let number: usize = name.strip_prefix("clip_")?.parse().ok()?;
+        (1..=self.clips.len()).contains(&number).then(|| number - 1)
+    }
 
-/// Value types supported in generator configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(untagged)]
-pub enum GeneratorValue {
-    /// Boolean value
-    Bool(bool),
-    /// Integer value
-    Int(i64),
-    /// Float value
-    Float(f64),
-    /// String value
-    String(String),
-    /// Array of values
-    Array(Vec<GeneratorValue>),
-}
+    /// MIDI destinations the track plays on: its own, its output layers',
+    /// its voice routes' and its round-robin voices'
+    pub fn destination_names(&self) -> Vec<&str> {
+        let round_robin = self.round_robin.iter().flat_map(|rr| rr.voices.iter());
+        let mut names: Vec<&str> = Vec::new();
+        let named = std::iter::once(self.destination.as_deref())
+            .chain(self.outputs.iter().map(|o| o.destination.as_deref()))
+            .chain(self.voices.iter().map(|v| v.destination.as_deref()))
+            .chain(round_robin.map(|v| v.destination.as_deref()))
+            .flatten();
+        for name in named {
+            if !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name);
+            }
+        }
+        names
+    }
 
-impl GeneratorConfig {
-    /// Get a float parameter with default
-    pub fn get_float(&self, key: &str, default: f64) -> f64 {
-        match self.params.get(key) {
-            Some(GeneratorValue::Float(v)) => *v,
-            Some(GeneratorValue::Int(v)) => *v as f64,
-            _ => default,
+    /// Input source and channel (0-15) feeding this track's recorder
+    pub fn record_input(&self) -> RecordInput {
+        RecordInput::new(
+            self.input.clone(),
+            self.input_channel.map(|c| c.clamp(1, 16) - 1),
+        )
+    }
+
+    /// Arm this track's input to play thru it
+    pub fn armed_input(&self, index: usize) -> Result<ArmedInput> {
+        let armed = ArmedInput::new(index, self.record_input()).with_quantize(self.input_quantize);
+        let Some(range) = self.input_range.as_deref() else {
+            return Ok(armed);
+        };
+        let (low, high) = parse_note_range(range)
+            .ok_or_else(|| anyhow!("Track '{}' has invalid input range '{}'", self.name, range))?;
+        Ok(armed.with_range(low, high))
+    }
+
+    /// Resolve what the transpose counts
+    pub fn transpose_mode(&self) -> Result<TransposeMode> {
+        match self.transpose_mode.as_deref() {
+            None => Ok(TransposeMode::default()),
+            Some(spec) => TransposeMode::from_str(spec)
+                .ok_or_else(|| anyhow!("Track '{}' has unknown transpose mode '{}'", self.name, spec)),
         }
     }
 
-    /// Get an integer parameter with default
-    pub fn get_int(&self, key: &str, default: i64) -> i64 {
-        match self.params.get(key) {
-            Some(GeneratorValue::Int(v)) => *v,
-            Some(GeneratorValue::Float(v)) => *v as i64,
-            _ => default,
+    /// Resolve the sustain pedal behavior
+    pub fn pedal_mode(&self) -> Result<PedalMode> {
+        match self.pedal.as_deref() {
+            None => Ok(PedalMode::default()),
+            Some(spec) => PedalMode::from_str(spec)
+                .ok_or_else(|| anyhow!("Track '{}' has unknown pedal mode '{}'", self.name, spec)),
         }
     }
 
-    /// Get a string parameter with default
-    pub fn get_string(&self, key: &str, default: &str) -> String {
-        match self.params.get(key) {
-            Some(GeneratorValue::String(v)) => v.clone(),
-            _ => default.to_string(),
+    /// Build the clip shuffle from `shuffle` and the clips' weights
+    pub fn clip_shuffle(&self) -> Result<Option<ClipShuffle>> {
+        let Some(spec) = self.shuffle.as_deref() else {
+            return Ok(None);
+        };
+        let spec = spec.trim().to_lowercase();
+        let every_bars = match spec.as_str() {
+            "loop" => 0,
+            _ => {
+                let count = spec.trim_end_matches("bars").trim_end_matches("bar").trim();
+                count
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&bars| bars > 0)
+                    .ok_or_else(|| anyhow!("Invalid shuffle '{}' on track '{}'", spec, self.name))?
+            }
+        };
+        let weights = self.clips.iter().map(|c| c.weight.unwrap_or(1.0)).collect();
+        Ok(Some(ClipShuffle::new(every_bars).with_weights(weights)))
+    }
+
+    /// Build the pattern chain from `chain`. Steps are separated by commas,
+    /// or by spaces when there are no commas; each names a clip (or its
+    /// 1-based number) with an optional "xN" repeat count.
+    pub fn pattern_chain(&self) -> Result<Option<PatternChain>> {
+        let Some(spec) = self.chain.as_deref() else {
+            return Ok(None);
+        };
+        let steps: Vec<&str> = if spec.contains(',') {
+            spec.split(',').map(str::trim).filter(|s| !s.is_empty()).collect()
+        } else {
+            spec.split_whitespace().collect()
+        };
+        let mut entries: Vec<ChainEntry> = Vec::new();
+        for step in steps {
+            let (name, repeats) = match step.rsplit_once(['x', 'X', '*']) {
+                Some((name, count)) if !name.trim().is_empty() && count.trim().parse::<u32>().is_ok() => {
+                    (name.trim(), count.trim().parse::<u32>().unwrap_or(1))
+                }
+                _ => (step, 1),
+            };
+            let clip = self
+                .clips
+                .iter()
+                .position(|c| c.name.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(name)))
+                .or_else(|| name.parse::<usize>().ok().filter(|&n| n >= 1 && n <= self.clips.len()).map(|n| n - 1))
+                .ok_or_else(|| anyhow!("Track '{}' chains unknown clip '{}'", self.name, name))?;
+            if repeats == 0 {
+                return Err(anyhow!("Track '{}' chains clip '{}' zero times", self.name, name));
+            }
+            entries.push(ChainEntry::new(clip, repeats));
         }
+        if entries.is_empty() {
+            return Err(anyhow!("Track '{}' has an empty chain", self.name));
+        }
+        Ok(Some(PatternChain::new(entries)))
     }
 
-    /// Get a boolean parameter with default
-    pub fn get_bool(&self, key: &str, default: bool) -> bool {
-        match self.params.get(key) {
-            Some(GeneratorValue::Bool(v)) => *v,
-            _ => default,
+    /// Parse the instrument range as (low, high)
+    pub fn note_range(&self) -> Result<Option<(u8, u8)>> {
+        let Some(range) = self.range.as_deref() else {
+            return Ok(None);
+        };
+        parse_note_range(range)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Invalid range '{}' on track '{}'", range, self.name))
+    }
+
+    /// Build the processor chain
+    pub fn processor_chain(&self) -> Result<ProcessorChain> {
+        let mut chain = ProcessorChain::new();
+        for processor in &self.processors {
+            chain.push(processor.to_processor().map_err(|e| anyhow!("Track '{}' has {}", self.name, e))?);
+        }
+        Ok(chain)
+    }
+
+    /// Build the step-sequenced parameter lanes
+    pub fn step_lanes(&self) -> Result<Vec<StepLane>> {
+        self.step_lanes
+            .iter()
+            .map(|lane| lane.to_lane().map_err(|e| anyhow!("Track '{}' has {}", self.name, e)))
+            .collect()
+    }
+
+    /// Problems with the instrument range: an unreadable range, or generator
+    /// octave settings that reach outside it
+    pub fn range_warnings(&self) -> Vec<String> {
+        let (low, high) = match self.note_range() {
+            Ok(Some(range)) => range,
+            Ok(None) => return Vec::new(),
+            Err(e) => return vec![e.to_string()],
+        };
+
+        let mut warnings = Vec::new();
+        if let Some(generator) = &self.generator {
+            let base = self.config.get_int("base_octave", -2);
+            if base >= -1 {
+                let octaves = ["octaves", "octave_range", "octave_spread"]
+                    .iter()
+                    .map(|key| self.config.get_int(key, 1))
+                    .max()
+                    .unwrap_or(1)
+                    .max(1);
+                // Estimate scale degrees as sevenths of an octave
+                let shift = match self.transpose_mode() {
+                    Ok(TransposeMode::Degrees) => (self.transpose as i64 * 12).div_euclid(7),
+                    _ => self.transpose as i64,
+                };
+                let lowest = (base + 1) * 12 + shift;
+                let highest = lowest + octaves * 12 - 1;
+                if lowest < low as i64 || highest > high as i64 {
+                    warnings.push(format!(
+                        "Track '{}': {} generator plays notes {}-{}, outside range {}-{}",
+                        self.name, generator, lowest, highest, low, high
+                    ));
+                }
+            }
         }
+        warnings
     }
 }
 
-/// Part configuration (a named state for all tracks)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
-pub struct PartConfig {
-    /// Track states within this part
+/// A single output layer for a track
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutputConfig {
+    /// MIDI destination name (None = default output)
     #[serde(default)]
-    pub tracks: HashMap<String, TrackState>,
-    /// Tempo override for this part (if any)
+    pub destination: Option<String>,
+    /// MIDI channel (1-16)
+    #[serde(default = "default_channel")]
+    pub channel: u8,
+    /// Layer transpose in semitones
     #[serde(default)]
-    pub tempo: Option<f64>,
-    /// Key override for this part (if any)
+    pub transpose: i8,
+    /// Layer velocity scaling (0.0 - 2.0, default 1.0)
+    #[serde(default = "default_velocity_scale")]
+    pub velocity_scale: f64,
+}
+
+/// Routing for a single drum voice
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoiceRouteConfig {
+    /// Voice name (e.g. "kick", "snare", "hat") or note number
+    pub voice: String,
+    /// MIDI channel (1-16)
+    #[serde(default = "default_channel")]
+    pub channel: u8,
+    /// MIDI destination name (None = default output)
     #[serde(default)]
-    pub key: Option<String>,
-    /// Scale override for this part (if any)
+    pub destination: Option<String>,
+    /// Note sent to the destination (None = unchanged)
     #[serde(default)]
-    pub scale: Option<String>,
+    pub note: Option<u8>,
 }
 
-/// State of a track within a part
+/// Round-robin rotation of notes across channels
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(untagged)]
-pub enum TrackState {
-    /// Simple state: "active", "muted", "solo"
-    Simple(String),
-    /// Clip selection: "clip_1", "clip_2", etc.
-    Clip(String),
-    /// Detailed state configuration
-    Detailed(TrackStateConfig),
+pub struct RoundRobinConfig {
+    /// Channels in rotation order
+    #[serde(default)]
+    pub voices: Vec<RoundRobinVoiceConfig>,
+    /// Pitch bend range of the receiving synths in semitones (default 2)
+    #[serde(default = "default_bend_range")]
+    pub bend_range: u8,
 }
 
-impl TrackState {
-    /// Check if this state means the track is active
-    pub fn is_active(&self) -> bool {
-        match self {
-            TrackState::Simple(s) => s == "active" || s == "solo",
-            TrackState::Clip(_) => true,
-            TrackState::Detailed(config) => !config.muted,
-        }
-    }
+fn default_bend_range() -> u8 {
+    crate::sequencer::round_robin::DEFAULT_BEND_RANGE
+}
 
-    /// Check if this state means the track is muted
-    pub fn is_muted(&self) -> bool {
-        match self {
-            TrackState::Simple(s) => s == "muted",
-            TrackState::Clip(_) => false,
-            TrackState::Detailed(config) => config.muted,
+/// MPE output zone
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MpeConfig {
+    /// "lower" (default, manager on channel 1) or "upper" (channel 16)
+    #[serde(default)]
+    pub zone: Option<String>,
+    /// Number of member channels (1-15, default 15)
+    #[serde(default = "default_mpe_members")]
+    pub members: u8,
+    /// Pitch bend range of the member channels in semitones (default 48)
+    #[serde(default = "default_mpe_bend_range")]
+    pub bend_range: u8,
+}
+
+fn default_mpe_members() -> u8 {
+    15
+}
+
+fn default_mpe_bend_range() -> u8 {
+    crate::sequencer::mpe::DEFAULT_MPE_BEND_RANGE
+}
+
+impl MpeConfig {
+    /// Resolve the zone
+    pub fn zone(&self) -> Result<MpeZone> {
+        match self.zone.as_deref() {
+            None => Ok(MpeZone::default()),
+            Some(spec) => MpeZone::from_str(spec).ok_or_else(|| anyhow!("unknown MPE zone '{}'", spec)),
         }
     }
+}
 
-    /// Get the clip name if this state specifies one
-    pub fn clip_name(&self) -> Option<&str> {
-        match self {
-            TrackState::Simple(_) => None,
-            TrackState::Clip(s) => Some(s),
-            TrackState::Detailed(config) => config.clip.as_deref(),
+/// CC and channel pressure thinning
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CcThinConfig {
+    /// Fewest ticks between values of a controller (default 2)
+    #[serde(default = "default_thin_interval")]
+    pub interval: u64,
+    /// Smallest value change worth sending (1-127, default 1)
+    #[serde(default = "default_thin_delta")]
+    pub delta: u8,
+}
+
+fn default_thin_interval() -> u64 {
+    2
+}
+
+fn default_thin_delta() -> u8 {
+    1
+}
+
+impl CcThinConfig {
+    /// Build the thinner
+    pub fn to_thinner(&self) -> Result<CcThinner> {
+        if !(1..=127).contains(&self.delta) {
+            return Err(anyhow!("invalid CC thinning delta {} (use 1-127)", self.delta));
         }
+        Ok(CcThinner::new(self.interval, self.delta))
     }
 }
 
-/// Detailed track state configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
-pub struct TrackStateConfig {
-    /// Whether the track is muted
+/// One step of a track's processor chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProcessorConfig {
+    /// "velocity_curve", "humanize", "note_range", "channel_remap", "echo",
+    /// "harmonize" or "chordify"
+    pub kind: String,
+    /// Curve exponent: below 1.0 lifts soft notes (velocity_curve, default 1.0)
     #[serde(default)]
-    pub muted: bool,
-    /// Whether the track is soloed
+    pub curve: Option<f64>,
+    /// Lowest output velocity (velocity_curve, default 1)
     #[serde(default)]
-    pub solo: bool,
-    /// Active clip name
+    pub min: Option<u8>,
+    /// Highest output velocity (velocity_curve, default 127)
     #[serde(default)]
-    pub clip: Option<String>,
-    /// Generator to use (overrides track default)
+    pub max: Option<u8>,
+    /// Timing spread in ticks (humanize, default 0)
+    #[serde(default)]
+    pub timing: Option<u64>,
+    /// Velocity spread (humanize, default 0)
+    #[serde(default)]
+    pub velocity: Option<u8>,
+    /// Kept notes, e.g. "C2-C5" or "36-84" (note_range)
+    #[serde(default)]
+    pub range: Option<String>,
+    /// Fold notes outside the range in by octaves instead of dropping them
+    #[serde(default)]
+    pub fold: bool,
+    /// Channel to move (1-16, channel_remap, default any)
+    #[serde(default)]
+    pub from: Option<u8>,
+    /// Channel to move to (1-16, channel_remap)
+    #[serde(default)]
+    pub to: Option<u8>,
+    /// Ticks between repeats (echo, default 12)
+    #[serde(default)]
+    pub delay: Option<u64>,
+    /// Number of repeats (echo, default 3)
+    #[serde(default)]
+    pub repeats: Option<u8>,
+    /// Velocity kept by each repeat, 0.0-1.0 (echo, default 0.6)
+    #[serde(default)]
+    pub decay: Option<f64>,
+    /// Scale degrees to add, e.g. [2, 4] for a third and fifth (harmonize)
+    #[serde(default)]
+    pub degrees: Vec<i32>,
+    /// Chord shape, e.g. "minor", "maj7" or "0,7,12" (chordify)
+    #[serde(default)]
+    pub chord: Option<String>,
+}
+
+impl ProcessorConfig {
+    /// Build the processor
+    pub fn to_processor(&self) -> Result<Processor> {
+        let channel = |c: u8| {
+            if (1..=16).contains(&c) {
+                Ok(c - 1)
+            } else {
+                Err(anyhow!("invalid channel {} in a channel_remap (use 1-16)", c))
+            }
+        };
+        match self.kind.trim().to_lowercase().as_str() {
+            "velocity_curve" | "velocity" => {
+                let curve = self.curve.unwrap_or(1.0);
+                if curve <= 0.0 {
+                    return Err(anyhow!("velocity curve {} (use a value above 0)", curve));
+                }
+                Ok(Processor::velocity_curve(curve, self.min.unwrap_or(1), self.max.unwrap_or(127)))
+            }
+            "humanize" => Ok(Processor::humanize(self.timing.unwrap_or(0), self.velocity.unwrap_or(0))),
+            "note_range" | "range" => {
+                let range = self.range.as_deref().ok_or_else(|| anyhow!("a note_range processor without a range"))?;
+                let (low, high) =
+                    parse_note_range(range).ok_or_else(|| anyhow!("invalid processor range '{}'", range))?;
+                Ok(Processor::note_range(low, high, self.fold))
+            }
+            "channel_remap" | "channel" => {
+                let to = self.to.ok_or_else(|| anyhow!("a channel_remap processor without a 'to' channel"))?;
+                Ok(Processor::channel_remap(self.from.map(channel).transpose()?, channel(to)?))
+            }
+            "echo" => {
+                let decay = self.decay.unwrap_or(0.6);
+                if !(0.0..=1.0).contains(&decay) {
+                    return Err(anyhow!("echo decay {} (use 0.0-1.0)", decay));
+                }
+                Ok(Processor::echo(self.delay.unwrap_or(12), self.repeats.unwrap_or(3), decay))
+            }
+            "harmonize" | "harmonizer" => {
+                if self.degrees.iter().all(|d| *d == 0) {
+                    return Err(anyhow!("a harmonize processor without degrees"));
+                }
+                Ok(Processor::harmonize(&self.degrees))
+            }
+            "chordify" => {
+                let chord = self.chord.as_deref().ok_or_else(|| anyhow!("a chordify processor without a chord"))?;
+                let shape = chord_shape(chord).ok_or_else(|| anyhow!("unknown chord shape '{}'", chord))?;
+                Ok(Processor::chordify(&shape))
+            }
+            other => Err(anyhow!("unknown processor '{}'", other)),
+        }
+    }
+}
+
+/// A looping row of values for one generator parameter
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepLaneConfig {
+    /// Generator parameter to set, e.g. "octaves"
+    pub param: String,
+    /// Value for each step, e.g. [1, 1, 2, 3]
+    pub steps: Vec<f64>,
+    /// Steps in one bar (default 4)
+    #[serde(default = "default_steps_per_bar")]
+    pub steps_per_bar: u32,
+}
+
+fn default_steps_per_bar() -> u32 {
+    4
+}
+
+impl StepLaneConfig {
+    /// Build the lane
+    pub fn to_lane(&self) -> Result<StepLane> {
+        if self.steps.is_empty() {
+            return Err(anyhow!("a step lane for '{}' with no steps", self.param));
+        }
+        if self.steps.len() > MAX_LANE_STEPS {
+            return Err(anyhow!(
+                "a step lane for '{}' with {} steps (use up to {})",
+                self.param,
+                self.steps.len(),
+                MAX_LANE_STEPS
+            ));
+        }
+        if !(1..=MAX_STEPS_PER_BAR).contains(&self.steps_per_bar) {
+            return Err(anyhow!(
+                "a step lane for '{}' at {} steps per bar (use 1-{})",
+                self.param,
+                self.steps_per_bar,
+                MAX_STEPS_PER_BAR
+            ));
+        }
+        Ok(StepLane::new(self.param.clone(), self.steps.clone(), self.steps_per_bar))
+    }
+}
+
+/// Parse a note range like "E1-E4" or "28-64" as (low, high)
+fn parse_note_range(range: &str) -> Option<(u8, u8)> {
+    // Try each dash as the separator, since octave -1 has one of its own
+    range.char_indices().filter(|&(_, c)| c == '-').find_map(|(i, _)| {
+        let low = parse_midi_note(&range[..i])?;
+        let high = parse_midi_note(&range[i + 1..])?;
+        Some((low.min(high), low.max(high)))
+    })
+}
+
+/// One channel in a round-robin rotation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RoundRobinVoiceConfig {
+    /// MIDI channel (1-16)
+    #[serde(default = "default_channel")]
+    pub channel: u8,
+    /// MIDI destination name (None = default output)
+    #[serde(default)]
+    pub destination: Option<String>,
+    /// Transpose in semitones
+    #[serde(default)]
+    pub transpose: i8,
+    /// Detune in cents (-100 to 100)
+    #[serde(default)]
+    pub detune: i16,
+}
+
+/// Reference to a clip file or inline clip
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClipReference {
+    /// File path to clip (relative to song file)
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Clip name/identifier
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Exclusive group: launching this clip stops other clips in the group
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Relative chance of being picked in shuffle mode (default 1.0)
+    #[serde(default)]
+    pub weight: Option<f64>,
+    /// Generator that renders the clip at load time (instead of a file)
     #[serde(default)]
     pub generator: Option<String>,
+    /// Random seed for the generator (default 0)
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Length of a rendered clip in bars (default 1)
+    #[serde(default)]
+    pub bars: Option<u32>,
+    /// Generator parameters for a rendered clip
+    #[serde(default)]
+    pub params: BTreeMap<String, f64>,
+    /// Control changes played with the clip
+    #[serde(default)]
+    pub ccs: Vec<ClipCcConfig>,
 }
 
-/// Controller mapping configuration
+/// A control change in a clip's lane (`{ tick: 0, cc: 74, value: 40 }`)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ControlsFile {
-    /// MIDI device configuration
+pub struct ClipCcConfig {
+    /// Position in ticks from the clip start
     #[serde(default)]
-    pub midi: MidiDeviceConfig,
-    /// Controller mappings
+    pub tick: u64,
+    /// Controller number (0-127)
+    pub cc: u8,
+    /// Value (0-127)
+    pub value: u8,
+}
+
+impl ClipCcConfig {
+    /// Build the clip control change
+    pub fn to_cc(&self) -> Result<ClipCc> {
+        if self.cc > 127 || self.value > 127 {
+            return Err(anyhow!("invalid CC {} value {} (use 0-127)", self.cc, self.value));
+        }
+        Ok(ClipCc::new(self.tick, self.cc, self.value))
+    }
+}
+
+impl ClipReference {
+    /// Check if the clip is rendered from a generator
+    pub fn is_generated(&self) -> bool {
+        self.generator.is_some()
+    }
+
+    /// Render a generator clip with its fixed seed (None for file clips).
+    /// The same seed, parameters and context always give the same notes.
+    pub fn render(&self, registry: &GeneratorRegistry, context: &GeneratorContext) -> Result<Option<Clip>> {
+        let Some(generator_name) = self.generator.as_deref() else {
+            return Ok(None);
+        };
+        let mut generator = registry
+            .create(generator_name)
+            .ok_or_else(|| anyhow!("Unknown clip generator: {}", generator_name))?;
+        let seed = self.seed.unwrap_or(0);
+        generator.set_seed(seed);
+        for (param, value) in &self.params {
+            generator.set_param(param, *value);
+        }
+
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("{} #{}", generator_name, seed));
+        let mut clip = Clip::render(name, generator.as_mut(), context, self.bars.unwrap_or(1));
+        clip.set_group(self.group.clone());
+        clip.add_ccs(self.ccs.iter().map(ClipCcConfig::to_cc).collect::<Result<Vec<_>>>()?);
+        Ok(Some(clip))
+    }
+}
+
+/// Generator-specific configuration (flexible key-value pairs)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(transparent)]
+pub struct GeneratorConfig {
+    /// Configuration parameters as key-value pairs
+    pub params: HashMap<String, GeneratorValue>,
+}
+
+/// Value types supported in generator configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum GeneratorValue {
+    /// Boolean value
+    Bool(bool),
+    /// Integer value
+    Int(i64),
+    /// Float value
+    Float(f64),
+    /// String value
+    String(String),
+    /// Array of values
+    Array(Vec<GeneratorValue>),
+}
+
+impl GeneratorConfig {
+    /// Get a float parameter with default
+    pub fn get_float(&self, key: &str, default: f64) -> f64 {
+        match self.params.get(key) {
+            Some(GeneratorValue::Float(v)) => *v,
+            Some(GeneratorValue::Int(v)) => *v as f64,
+            _ => default,
+        }
+    }
+
+    /// Get an integer parameter with default
+    pub fn get_int(&self, key: &str, default: i64) -> i64 {
+        match self.params.get(key) {
+            Some(GeneratorValue::Int(v)) => *v,
+            Some(GeneratorValue::Float(v)) => *v as i64,
+            _ => default,
+        }
+    }
+
+    /// Get a string parameter with default
+    pub fn get_string(&self, key: &str, default: &str) -> String {
+        match self.params.get(key) {
+            Some(GeneratorValue::String(v)) => v.clone(),
+            _ => default.to_string(),
+        }
+    }
+
+    /// Get a boolean parameter with default
+    pub fn get_bool(&self, key: &str, default: bool) -> bool {
+        match self.params.get(key) {
+            Some(GeneratorValue::Bool(v)) => *v,
+            _ => default,
+        }
+    }
+}
+
+/// Part configuration (a named state for all tracks)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PartConfig {
+    /// Track states within this part
     #[serde(default)]
-    pub mappings: Vec<ControlMapping>,
-    /// Keyboard shortcuts
+    pub tracks: HashMap<String, TrackState>,
+    /// Tempo override for this part (if any)
     #[serde(default)]
-    pub keyboard: HashMap<String, String>,
+    pub tempo: Option<f64>,
+    /// Key override for this part (if any)
+    #[serde(default)]
+    pub key: Option<String>,
+    /// Scale override for this part (if any)
+    #[serde(default)]
+    pub scale: Option<String>,
+    /// UI color as a hex string (e.g. "#e07020")
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Launch guard: "arm" (must be armed first) or "confirm" (trigger twice within a beat)
+    #[serde(default)]
+    pub guard: Option<String>,
+    /// Parts that must have played before this one can launch
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// FX that lead into this part, ending on its first downbeat
+    #[serde(default)]
+    pub fx: Vec<String>,
+    /// External device snapshots sent when the part starts
+    #[serde(default)]
+    pub snapshots: Vec<SnapshotConfig>,
+    /// Optional layers: track name -> chance (0.0-1.0) it plays, rolled
+    /// each time the part is triggered
+    #[serde(default)]
+    pub layers: HashMap<String, f64>,
 }
 
-impl ControlsFile {
-    /// Load controls configuration from a YAML file
-    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let contents = fs::read_to_string(path.as_ref())
-            .with_context(|| format!("Failed to read controls file: {:?}", path.as_ref()))?;
-        Self::from_yaml(&contents)
+impl PartConfig {
+    /// Get the part color as RGB, if set and valid
+    pub fn rgb(&self) -> Option<(u8, u8, u8)> {
+        let hex = self.color.as_deref()?.trim().trim_start_matches('#');
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some((channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Build the engine part, resolving track and clip names against the
+    /// song's tracks (unknown names are skipped)
+    pub fn to_part(&self, name: &str, tracks: &[TrackConfig]) -> Part {
+        let mut part = Part::new(name);
+        if let Some((r, g, b)) = self.rgb() {
+            part.set_color(r, g, b);
+        }
+        if let Some(guard) = self.guard.as_deref().and_then(PartGuard::from_str) {
+            part = part.with_guard(guard);
+        }
+        for required in &self.requires {
+            part = part.with_requirement(required.clone());
+        }
+        for fx in &self.fx {
+            part = part.with_fx(fx.clone());
+        }
+        for snapshot in &self.snapshots {
+            part = part.with_snapshot(snapshot.to_snapshot());
+        }
+        for (track_name, state) in &self.tracks {
+            let Some(index) = tracks.iter().position(|t| &t.name == track_name) else {
+                continue;
+            };
+            let playback = match state {
+                _ if state.is_muted() => PlaybackState::Muted,
+                TrackState::Simple(s) if s == "solo" => PlaybackState::Soloed,
+                TrackState::Detailed(config) if config.solo => PlaybackState::Soloed,
+                _ => PlaybackState::Active,
+            };
+            part.set_playback_state(index, playback);
+            let clip = match state {
+                TrackState::Simple(s) if !matches!(s.as_str(), "active" | "muted" | "solo") => Some(s.as_str()),
+                _ => state.clip_name(),
+            };
+            if let Some(clip) = clip.and_then(|clip| tracks[index].clip_index(clip)) {
+                part.set_track_state(index, TrackClipState::Clip(clip));
+            } else if let TrackState::Detailed(TrackStateConfig { generator: Some(generator), .. }) = state {
+                part.set_track_state(index, TrackClipState::Generator(generator.clone()));
+            }
+        }
+        for (track_name, &chance) in &self.layers {
+            if let Some(index) = tracks.iter().position(|t| &t.name == track_name) {
+                part.set_layer(index, chance);
+            }
+        }
+        part
+    }
+}
+
+/// One-shot FX definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FxConfig {
+    /// Shape: "cc_ramp", "pitch_rise", "sweep" or "impact"
+    pub kind: String,
+    /// Length in beats
+    #[serde(default = "default_fx_beats")]
+    pub beats: u32,
+    /// MIDI channel (1-16)
+    #[serde(default = "default_channel")]
+    pub channel: u8,
+    /// CC number (cc_ramp)
+    #[serde(default)]
+    pub cc: Option<u8>,
+    /// Ramp start value (cc_ramp, default 0)
+    #[serde(default)]
+    pub from: Option<u8>,
+    /// Ramp end value (cc_ramp, default 127)
+    #[serde(default)]
+    pub to: Option<u8>,
+    /// Note number (sweep, impact)
+    #[serde(default)]
+    pub note: Option<u8>,
+    /// Note velocity (sweep, impact, default 127)
+    #[serde(default)]
+    pub velocity: Option<u8>,
+    /// Fraction of the bend range to rise (pitch_rise, default 1.0)
+    #[serde(default)]
+    pub amount: Option<f64>,
+}
+
+fn default_fx_beats() -> u32 {
+    4
+}
+
+impl FxConfig {
+    /// Build the FX event
+    pub fn to_event(&self, name: &str) -> Result<FxEvent> {
+        let note = || self.note.ok_or_else(|| anyhow!("FX '{}' needs a note", name));
+        let velocity = self.velocity.unwrap_or(127).min(127);
+        let shape = match self.kind.trim().to_lowercase().as_str() {
+            "cc_ramp" | "ramp" => FxShape::CcRamp {
+                cc: self.cc.ok_or_else(|| anyhow!("FX '{}' needs a cc", name))?.min(127),
+                from: self.from.unwrap_or(0).min(127),
+                to: self.to.unwrap_or(127).min(127),
+            },
+            "pitch_rise" | "rise" => FxShape::PitchRise {
+                amount: self.amount.unwrap_or(1.0),
+            },
+            "sweep" => FxShape::Sweep { note: note()?.min(127), velocity },
+            "impact" => FxShape::Impact { note: note()?.min(127), velocity },
+            other => return Err(anyhow!("Unknown FX kind '{}' for '{}'", other, name)),
+        };
+        Ok(FxEvent::new(name, shape)
+            .with_channel(self.channel.saturating_sub(1))
+            .with_beats(self.beats))
+    }
+}
+
+/// What the energy control moves
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnergyConfig {
+    /// Tempo at no energy
+    #[serde(default)]
+    pub tempo_min: Option<f64>,
+    /// Tempo at full energy
+    #[serde(default)]
+    pub tempo_max: Option<f64>,
+    /// Tracks that enter as energy rises, first to last
+    #[serde(default)]
+    pub layers: Vec<String>,
+    /// Generator parameters to move
+    #[serde(default)]
+    pub params: Vec<EnergyParamConfig>,
+    /// Add the built-in mappings for the stock generators (default true)
+    #[serde(default = "default_true")]
+    pub heuristics: bool,
+}
+
+/// A generator parameter that follows the energy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnergyParamConfig {
+    /// Track name
+    pub track: String,
+    /// Generator parameter name
+    pub param: String,
+    /// Value at no energy
+    pub low: f64,
+    /// Value at full energy
+    pub high: f64,
+}
+
+/// User groove template
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GrooveConfig {
+    /// Offset of each sixteenth, in sixteenths (-0.5 to 0.5, positive = late)
+    #[serde(default)]
+    pub offsets: Vec<f64>,
+    /// Velocity scaling of each sixteenth (0.0 to 2.0)
+    #[serde(default)]
+    pub velocities: Vec<f64>,
+}
+
+impl GrooveConfig {
+    /// Build the groove template
+    pub fn to_template(&self, name: &str) -> Result<GrooveTemplate> {
+        if self.offsets.is_empty() && self.velocities.is_empty() {
+            return Err(anyhow!("Groove '{}' has no offsets or velocities", name));
+        }
+        if let Some(offset) = self.offsets.iter().find(|o| o.abs() > MAX_GROOVE_OFFSET) {
+            return Err(anyhow!("Groove '{}' has offset {} (use -0.5 to 0.5)", name, offset));
+        }
+        if let Some(velocity) = self.velocities.iter().find(|v| !(0.0..=MAX_GROOVE_VELOCITY).contains(*v)) {
+            return Err(anyhow!("Groove '{}' has velocity scale {} (use 0-2)", name, velocity));
+        }
+        Ok(GrooveTemplate::new(name, self.offsets.clone(), self.velocities.clone()))
+    }
+}
+
+/// Modulation source bound to a parameter
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModulationConfig {
+    /// Parameter name (e.g. "melody.rest_probability")
+    pub target: String,
+    /// Source: "lfo", "random" (random walk) or "envelope"
+    pub source: String,
+    /// LFO waveform: "sine" (default), "triangle", "saw" or "square"
+    #[serde(default)]
+    pub shape: Option<String>,
+    /// LFO period, or beats between random steps (default 4)
+    #[serde(default = "default_mod_beats")]
+    pub beats: f64,
+    /// LFO phase offset (0-1)
+    #[serde(default)]
+    pub phase: f64,
+    /// Largest move as a fraction of the parameter range (default 0.5)
+    #[serde(default = "default_mod_depth")]
+    pub depth: f64,
+    /// Largest random step (-1 to 1 scale, default 0.25)
+    #[serde(default)]
+    pub step: Option<f64>,
+    /// Envelope attack in beats (default 1)
+    #[serde(default)]
+    pub attack: Option<f64>,
+    /// Envelope decay in beats (default 1)
+    #[serde(default)]
+    pub decay: Option<f64>,
+    /// Envelope sustain level (0-1, default 0.5)
+    #[serde(default)]
+    pub sustain: Option<f64>,
+    /// Random walk seed, for a repeatable walk
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+fn default_mod_beats() -> f64 {
+    4.0
+}
+
+fn default_mod_depth() -> f64 {
+    0.5
+}
+
+impl ModulationConfig {
+    /// Build the modulator
+    pub fn to_modulator(&self) -> Result<Modulator> {
+        if self.beats <= 0.0 {
+            return Err(anyhow!("Modulation of '{}' needs a positive beats value", self.target));
+        }
+        let source = match self.source.trim().to_lowercase().as_str() {
+            "lfo" => {
+                let shape = match self.shape.as_deref() {
+                    None => LfoShape::default(),
+                    Some(spec) => LfoShape::from_str(spec)
+                        .ok_or_else(|| anyhow!("Unknown LFO shape '{}' for '{}'", spec, self.target))?,
+                };
+                ModSource::Lfo { shape, beats: self.beats, phase: self.phase }
+            }
+            "random" | "random_walk" | "walk" => ModSource::RandomWalk {
+                beats: self.beats,
+                step: self.step.unwrap_or(0.25).clamp(0.0, 1.0),
+            },
+            "envelope" | "env" => ModSource::Envelope {
+                attack: self.attack.unwrap_or(1.0).max(0.0),
+                decay: self.decay.unwrap_or(1.0).max(0.0),
+                sustain: self.sustain.unwrap_or(0.5).clamp(0.0, 1.0),
+            },
+            other => return Err(anyhow!("Unknown modulation source '{}' for '{}'", other, self.target)),
+        };
+        let modulator = Modulator::new(&self.target, source).with_depth(self.depth);
+        Ok(match self.seed {
+            Some(seed) => modulator.with_seed(seed),
+            None => modulator,
+        })
+    }
+}
+
+/// External device snapshot (values recalled on song load or part start)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotConfig {
+    /// MIDI destination name (None = default output)
+    #[serde(default)]
+    pub device: Option<String>,
+    /// MIDI channel (1-16)
+    #[serde(default = "default_channel")]
+    pub channel: u8,
+    /// Program change sent before the other values
+    #[serde(default)]
+    pub program: Option<u8>,
+    /// CC number -> value
+    #[serde(default)]
+    pub cc: BTreeMap<u8, u8>,
+    /// NRPN parameter -> 14-bit value
+    #[serde(default)]
+    pub nrpn: BTreeMap<u16, u16>,
+    /// SysEx dump files (.syx, relative to the song file) sent with the snapshot
+    #[serde(default)]
+    pub sysex: Vec<String>,
+}
+
+impl SnapshotConfig {
+    /// Build the device snapshot
+    pub fn to_snapshot(&self) -> DeviceSnapshot {
+        let channel = self.channel.clamp(1, 16) - 1;
+        let mut snapshot = DeviceSnapshot::new(self.device.clone());
+        if let Some(program) = self.program {
+            snapshot.push(SnapshotValue::Program { channel, program });
+        }
+        for (&cc, &value) in &self.cc {
+            snapshot.push(SnapshotValue::Cc { channel, cc, value });
+        }
+        for (&param, &value) in &self.nrpn {
+            snapshot.push(SnapshotValue::Nrpn { channel, param, value });
+        }
+        snapshot
+    }
+
+    /// Read the SysEx dumps, split into messages (F0 ... F7)
+    pub fn sysex_messages(&self, song_path: &Path) -> Result<Vec<Vec<u8>>> {
+        let mut messages = Vec::new();
+        for file in &self.sysex {
+            let path = resolve_path(song_path, file);
+            let bytes = fs::read(&path).with_context(|| format!("Failed to read SysEx file: {:?}", path))?;
+            let mut start = None;
+            for (i, &byte) in bytes.iter().enumerate() {
+                match byte {
+                    0xF0 => start = Some(i),
+                    0xF7 => {
+                        if let Some(s) = start.take() {
+                            messages.push(bytes[s..=i].to_vec());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(messages)
+    }
+}
+
+/// State of a track within a part
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum TrackState {
+    /// Simple state: "active", "muted", "solo"
+    Simple(String),
+    /// Clip selection: "clip_1", "clip_2", etc.
+    Clip(String),
+    /// Detailed state configuration
+    Detailed(TrackStateConfig),
+}
+
+impl TrackState {
+    /// Check if this state means the track is active
+    pub fn is_active(&self) -> bool {
+        match self {
+            TrackState::Simple(s) => s == "active" || s == "solo",
+            TrackState::Clip(_) => true,
+            TrackState::Detailed(config) => !config.muted,
+        }
+    }
+
+    /// Check if this state means the track is muted
+    pub fn is_muted(&self) -> bool {
+        match self {
+            TrackState::Simple(s) => s == "muted",
+            TrackState::Clip(_) => false,
+            TrackState::Detailed(config) => config.muted,
+        }
+    }
+
+    /// Get the clip name if this state specifies one
+    pub fn clip_name(&self) -> Option<&str> {
+        match self {
+            TrackState::Simple(_) => None,
+            TrackState::Clip(s) => Some(s),
+            TrackState::Detailed(config) => config.clip.as_deref(),
+        }
+    }
+}
+
+/// Detailed track state configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TrackStateConfig {
+    /// Whether the track is muted
+    #[serde(default)]
+    pub muted: bool,
+    /// Whether the track is soloed
+    #[serde(default)]
+    pub solo: bool,
+    /// Active clip name
+    #[serde(default)]
+    pub clip: Option<String>,
+    /// Generator to use (overrides track default)
+    #[serde(default)]
+    pub generator: Option<String>,
+}
+
+/// Controller mapping configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ControlsFile {
+    /// MIDI device configuration
+    #[serde(default)]
+    pub midi: MidiDeviceConfig,
+    /// Controller mappings
+    #[serde(default)]
+    pub mappings: Vec<ControlMapping>,
+    /// Keyboard shortcuts
+    #[serde(default)]
+    pub keyboard: HashMap<String, String>,
+    /// Per-device input settings
+    #[serde(default)]
+    pub inputs: Vec<InputDeviceConfig>,
+    /// Patchbay routes between MIDI ports
+    #[serde(default)]
+    pub routes: Vec<RouteConfig>,
+    /// Named macros triggered with `action: macro`
+    #[serde(default)]
+    pub macros: HashMap<String, MacroConfig>,
+    /// Temporary tempo nudge depth in percent
+    #[serde(default = "default_nudge_depth")]
+    pub nudge_depth: f64,
+    /// Per-device output settings
+    #[serde(default)]
+    pub outputs: Vec<OutputPortConfig>,
+    /// Named controller mapping pages, in switching order
+    #[serde(default)]
+    pub pages: Vec<PageConfig>,
+    /// RTP-MIDI network sessions
+    #[serde(default)]
+    pub network: Vec<NetworkSessionConfig>,
+    /// Knobs laid across the selected track's generator parameters
+    #[serde(default)]
+    pub auto_layout: Option<AutoLayoutConfig>,
+    /// Beat pulses for lighting controllers
+    #[serde(default)]
+    pub lighting: Option<LightingConfig>,
+    /// Position readout: "bars" (default), "time", "remaining" or "smpte"
+    #[serde(default)]
+    pub position_display: Option<String>,
+    /// Beat shown outside the TUI: "title", "bell" or "title+bell"
+    #[serde(default)]
+    pub beat_flash: Option<String>,
+    /// OSC remote control server
+    #[serde(default)]
+    pub osc: Option<OscConfig>,
+    /// When song file edits take effect: "immediate", "next_bar" (default) or "manual"
+    #[serde(default)]
+    pub reload: Option<String>,
+    /// Input keyboard zone that steers the song key
+    #[serde(default)]
+    pub key_zone: Option<KeyZoneConfig>,
+    /// Metronome click output
+    #[serde(default)]
+    pub metronome: Option<MetronomeConfig>,
+    /// Launch quantization per control source ("keyboard", "midi", "grid",
+    /// "osc", "repl" -> "bar", "beat", "immediate", "2 bars", ...)
+    #[serde(default)]
+    pub launch_quantize: HashMap<String, String>,
+    /// Measured output delays for latency compensation
+    #[serde(default)]
+    pub latency: Option<LatencyConfig>,
+}
+
+/// Metronome click output
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetronomeConfig {
+    /// Click from the start
+    #[serde(default)]
+    pub enabled: bool,
+    /// MIDI destination name (e.g. "FluidSynth" for internal drum hits)
+    #[serde(default)]
+    pub destination: Option<String>,
+    /// MIDI channel (1-16, default 10)
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// Note on the first beat of the bar (default 76, hi wood block)
+    #[serde(default)]
+    pub accent_note: Option<u8>,
+    /// Note on other beats (default 77, low wood block)
+    #[serde(default)]
+    pub note: Option<u8>,
+    /// Beat click velocity (default 100)
+    #[serde(default)]
+    pub velocity: Option<u8>,
+    /// Click level, 0.0-1.0 (default 1.0)
+    #[serde(default)]
+    pub volume: Option<f64>,
+}
+
+impl MetronomeConfig {
+    /// Build the metronome, playing on a named destination
+    pub fn to_metronome(&self, destinations: &[String]) -> Result<Metronome> {
+        let mut metronome = Metronome::new();
+        if let Some(channel) = self.channel {
+            if !(1..=16).contains(&channel) {
+                return Err(anyhow!("Invalid metronome channel {} (use 1-16)", channel));
+            }
+            metronome.set_channel(channel - 1);
+        }
+        let defaults = (76, 77);
+        metronome.set_notes(self.accent_note.unwrap_or(defaults.0), self.note.unwrap_or(defaults.1));
+        if let Some(velocity) = self.velocity {
+            metronome.set_velocity(velocity);
+        }
+        if let Some(volume) = self.volume {
+            if !(0.0..=1.0).contains(&volume) {
+                return Err(anyhow!("Invalid metronome volume {} (use 0-1)", volume));
+            }
+            metronome.set_volume(volume);
+        }
+        metronome.set_destination(resolve_destination(self.destination.as_deref(), destinations));
+        metronome.set_enabled(self.enabled);
+        Ok(metronome)
+    }
+}
+
+/// Output delays measured with `seq calibrate`, in milliseconds
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LatencyConfig {
+    /// External MIDI synths
+    #[serde(default)]
+    pub midi_ms: f64,
+    /// Software synth audio
+    #[serde(default)]
+    pub audio_ms: f64,
+    /// Lighting
+    #[serde(default)]
+    pub lighting_ms: f64,
+}
+
+impl LatencyConfig {
+    /// Build the offsets the outputs are compensated by
+    pub fn to_offsets(&self) -> Result<SyncOffsets> {
+        for (name, ms) in [("midi", self.midi_ms), ("audio", self.audio_ms), ("lighting", self.lighting_ms)] {
+            if !(0.0..=MAX_SYNC_OFFSET_MS).contains(&ms) {
+                return Err(anyhow!("Invalid {} latency {} ms (use 0-{})", name, ms, MAX_SYNC_OFFSET_MS));
+            }
+        }
+        Ok(SyncOffsets::new(self.midi_ms, self.audio_ms, self.lighting_ms))
+    }
+}
+
+/// Keyboard zone whose notes set the song key instead of sounding
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyZoneConfig {
+    /// Note range, e.g. "C1-B2"
+    pub range: String,
+    /// Input channel (1-16, default any)
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// How notes are read: "chord" (default) or "bass"
+    #[serde(default)]
+    pub mode: Option<String>,
+}
+
+impl KeyZoneConfig {
+    /// Build the zone
+    pub fn to_zone(&self) -> Result<KeyZone> {
+        // Try each dash as the separator, since octave -1 has one of its own
+        let (low, high) = self
+            .range
+            .char_indices()
+            .filter(|&(_, c)| c == '-')
+            .find_map(|(i, _)| Some((parse_midi_note(&self.range[..i])?, parse_midi_note(&self.range[i + 1..])?)))
+            .ok_or_else(|| anyhow!("Invalid key zone range '{}'", self.range))?;
+        let channel = match self.channel {
+            None => None,
+            Some(c @ 1..=16) => Some(c - 1),
+            Some(c) => return Err(anyhow!("Invalid key zone channel {} (use 1-16)", c)),
+        };
+        let mode = match self.mode.as_deref() {
+            None => KeyZoneMode::default(),
+            Some(s) => KeyZoneMode::from_str(s).ok_or_else(|| anyhow!("Unknown key zone mode: {}", s))?,
+        };
+        Ok(KeyZone::new(low, high).with_channel(channel).with_mode(mode))
+    }
+}
+
+/// OSC remote control server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OscConfig {
+    /// UDP port to listen on (default 9000)
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Address prefix (default "/seq")
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+impl OscConfig {
+    /// Start listening
+    pub fn open_server(&self) -> Result<OscServer> {
+        let mapper = OscMapper::new(self.prefix.as_deref().unwrap_or(DEFAULT_OSC_PREFIX));
+        OscServer::bind(self.port.unwrap_or(DEFAULT_OSC_PORT), mapper)
+    }
+}
+
+/// Beat pulse output for lighting
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LightingConfig {
+    /// Pulse format: "midi" (default), "osc" or "serial"
+    #[serde(default)]
+    pub format: Option<String>,
+    /// MIDI destination name, OSC "host:port", or serial device path
+    #[serde(default)]
+    pub target: Option<String>,
+    /// MIDI channel (1-16, default 16)
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// Note sent on bar downbeats
+    #[serde(default)]
+    pub bar_note: Option<u8>,
+    /// Note sent on other beats
+    #[serde(default)]
+    pub beat_note: Option<u8>,
+    /// Velocity of downbeat pulses
+    #[serde(default)]
+    pub accent_velocity: Option<u8>,
+    /// Velocity of other pulses
+    #[serde(default)]
+    pub velocity: Option<u8>,
+    /// OSC address (default "/seq/beat")
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+impl LightingConfig {
+    /// Parse the pulse format
+    pub fn pulse_format(&self) -> Result<PulseFormat> {
+        match self.format.as_deref() {
+            None => Ok(PulseFormat::default()),
+            Some(s) => PulseFormat::from_str(s).ok_or_else(|| anyhow!("Unknown lighting format: {}", s)),
+        }
+    }
+
+    /// Build the pulse generator with this mapping
+    pub fn to_pulse(&self) -> BeatPulse {
+        let mut pulse = BeatPulse::new();
+        if let Some(channel) = self.channel {
+            pulse.set_channel(channel.clamp(1, 16) - 1);
+        }
+        pulse.set_notes(self.bar_note.unwrap_or(61), self.beat_note.unwrap_or(60));
+        pulse.set_velocities(self.accent_velocity.unwrap_or(127), self.velocity.unwrap_or(64));
+        if let Some(address) = &self.address {
+            pulse.set_address(address.clone());
+        }
+        pulse
+    }
+
+    /// Open the OSC or serial target (None for MIDI pulses)
+    pub fn open_sender(&self) -> Result<Option<PulseSender>> {
+        let target = || self.target.as_deref().ok_or_else(|| anyhow!("Lighting output needs a target"));
+        match self.pulse_format()? {
+            PulseFormat::MidiNote => Ok(None),
+            PulseFormat::Osc => {
+                let target = target()?;
+                let addr = target
+                    .to_socket_addrs()
+                    .with_context(|| format!("Invalid OSC target: {}", target))?
+                    .next()
+                    .with_context(|| format!("OSC target not found: {}", target))?;
+                PulseSender::osc(addr).map(Some)
+            }
+            PulseFormat::Serial => PulseSender::serial(target()?).map(Some),
+        }
+    }
+}
+
+/// Generator knob auto-layout
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AutoLayoutConfig {
+    /// CC of the first knob (the rest follow consecutively)
+    pub first_cc: u8,
+    /// Number of knobs
+    #[serde(default = "default_knob_count")]
+    pub knobs: usize,
+    /// MIDI channel of the knobs (1-16, any if unset)
+    #[serde(default)]
+    pub channel: Option<u8>,
+}
+
+fn default_knob_count() -> usize {
+    DEFAULT_KNOB_COUNT
+}
+
+/// An RTP-MIDI (AppleMIDI) network session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NetworkSessionConfig {
+    /// Session name shown to peers
+    pub name: String,
+    /// Local control port (data uses the next port)
+    #[serde(default = "default_rtp_port")]
+    pub port: u16,
+    /// Peer control address ("host:port") to invite; wait for an
+    /// invitation when unset
+    #[serde(default)]
+    pub peer: Option<String>,
+}
+
+fn default_rtp_port() -> u16 {
+    DEFAULT_RTP_PORT
+}
+
+impl NetworkSessionConfig {
+    /// Open the session (inviting the peer if one is set)
+    pub fn open(&self) -> Result<RtpMidiSession> {
+        match &self.peer {
+            Some(peer) => {
+                let remote = peer
+                    .to_socket_addrs()
+                    .with_context(|| format!("Invalid RTP-MIDI peer: {}", peer))?
+                    .next()
+                    .with_context(|| format!("RTP-MIDI peer not found: {}", peer))?;
+                RtpMidiSession::initiator(&self.name, self.port, remote)
+            }
+            None => RtpMidiSession::participant(&self.name, self.port),
+        }
+    }
+}
+
+/// A controller mapping page
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PageConfig {
+    /// Page name (referenced by mappings' `page`)
+    pub name: String,
+    /// Note lit while the page is active
+    #[serde(default)]
+    pub led_note: Option<u8>,
+    /// CC set to 127 while the page is active (if no LED note)
+    #[serde(default)]
+    pub led_cc: Option<u8>,
+    /// MIDI channel of the LED (1-16, default 1)
+    #[serde(default)]
+    pub channel: Option<u8>,
+}
+
+fn default_nudge_depth() -> f64 {
+    4.0
+}
+
+impl Default for ControlsFile {
+    fn default() -> Self {
+        Self {
+            midi: MidiDeviceConfig::default(),
+            mappings: Vec::new(),
+            keyboard: HashMap::new(),
+            inputs: Vec::new(),
+            routes: Vec::new(),
+            macros: HashMap::new(),
+            nudge_depth: default_nudge_depth(),
+            outputs: Vec::new(),
+            pages: Vec::new(),
+            network: Vec::new(),
+            auto_layout: None,
+            lighting: None,
+            position_display: None,
+            beat_flash: None,
+            osc: None,
+            reload: None,
+            key_zone: None,
+            metronome: None,
+            launch_quantize: HashMap::new(),
+            latency: None,
+        }
+    }
+}
+
+impl ControlsFile {
+    /// Load controls configuration from a YAML file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = fs::read_to_string(path.as_ref())
+            .with_context(|| format!("Failed to read controls file: {:?}", path.as_ref()))?;
+        Self::from_yaml(&contents)
+    }
+
+    /// Parse controls configuration from YAML string
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        serde_yaml::from_str(yaml).context("Failed to parse controls YAML")
+    }
+
+    /// Resolve the position readout mode
+    pub fn position_mode(&self) -> Result<PositionMode> {
+        match self.position_display.as_deref() {
+            None => Ok(PositionMode::default()),
+            Some(s) => PositionMode::from_str(s).ok_or_else(|| anyhow!("Unknown position display: {}", s)),
+        }
+    }
+
+    /// Resolve where the beat is signalled outside the TUI
+    pub fn beat_flash(&self) -> Result<BeatFlash> {
+        match self.beat_flash.as_deref() {
+            None => Ok(BeatFlash::default()),
+            Some(s) => BeatFlash::from_str(s).ok_or_else(|| anyhow!("Unknown beat flash: {}", s)),
+        }
+    }
+
+    /// Resolve when song file edits take effect
+    pub fn reload_policy(&self) -> Result<ReloadPolicy> {
+        match self.reload.as_deref() {
+            None => Ok(ReloadPolicy::default()),
+            Some(s) => ReloadPolicy::from_str(s).ok_or_else(|| anyhow!("Unknown reload policy: {}", s)),
+        }
+    }
+
+    /// Resolve the launch quantization of each control source
+    pub fn launch_quantize(&self) -> Result<Vec<(TriggerSource, QuantizeMode)>> {
+        self.launch_quantize
+            .iter()
+            .map(|(source, mode)| {
+                let source =
+                    TriggerSource::from_str(source).ok_or_else(|| anyhow!("Unknown trigger source: {}", source))?;
+                let mode = QuantizeMode::from_str(mode)
+                    .ok_or_else(|| anyhow!("Invalid launch quantize '{}' for {:?}", mode, source))?;
+                Ok((source, mode))
+            })
+            .collect()
+    }
+
+    /// Find output settings for a device (case-insensitive substring match)
+    pub fn output_for(&self, device_name: &str) -> Option<&OutputPortConfig> {
+        let name = device_name.to_lowercase();
+        self.outputs
+            .iter()
+            .find(|output| name.contains(&output.device.to_lowercase()))
+    }
+
+    /// Find input settings for a device (case-insensitive substring match)
+    pub fn input_for(&self, device_name: &str) -> Option<&InputDeviceConfig> {
+        let name = device_name.to_lowercase();
+        self.inputs
+            .iter()
+            .find(|input| name.contains(&input.device.to_lowercase()))
+    }
+}
+
+/// Input settings for a single MIDI source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InputDeviceConfig {
+    /// Device name (matched against source names)
+    pub device: String,
+    /// Velocity curve: "linear", "soft", "hard", "exp:<n>" or "fixed:<n>"
+    #[serde(default)]
+    pub velocity_curve: Option<String>,
+    /// Fixed velocity for every note (overrides the curve)
+    #[serde(default)]
+    pub fixed_velocity: Option<u8>,
+    /// Follow MMC transport commands for this device ID (127 = any)
+    #[serde(default)]
+    pub mmc: Option<u8>,
+}
+
+impl InputDeviceConfig {
+    /// Velocity curve spec with the fixed-velocity override folded in
+    pub fn curve_spec(&self) -> String {
+        match self.fixed_velocity {
+            Some(v) => format!("fixed:{}", v),
+            None => self.velocity_curve.clone().unwrap_or_else(|| "linear".to_string()),
+        }
+    }
+}
+
+/// A patchbay route between MIDI ports
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RouteConfig {
+    /// Input port name (substring match)
+    pub from: String,
+    /// Output port name (substring match)
+    pub to: String,
+    /// Only pass this channel (1-16)
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// Move messages to this channel (1-16)
+    #[serde(default)]
+    pub remap_channel: Option<u8>,
+    /// Only pass notes in this range [low, high]
+    #[serde(default)]
+    pub notes: Option<[u8; 2]>,
+    /// Message kinds to drop ("notes", "cc", "program", "bend", "aftertouch", "clock")
+    #[serde(default)]
+    pub block: Vec<String>,
+    /// Transpose notes by semitones
+    #[serde(default)]
+    pub transpose: i8,
+}
+
+/// MIDI device configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MidiDeviceConfig {
+    /// Device name to connect to
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Input channel filter (if any)
+    #[serde(default)]
+    pub input_channel: Option<u8>,
+    /// Control surface protocol ("mackie" for Mackie Control)
+    #[serde(default)]
+    pub surface: Option<String>,
+    /// Start immediately when slaved to external clock instead of waiting for the bar
+    #[serde(default)]
+    pub start_immediately: bool,
+}
+
+impl MidiDeviceConfig {
+    /// External clock sync with the configured start behavior
+    pub fn clock_sync(&self) -> ExternalClockSync {
+        let mut sync = ExternalClockSync::new();
+        sync.quantize_start = !self.start_immediately;
+        sync
+    }
+
+    /// Apply the settings to a connected input's clock sync
+    pub fn apply_to(&self, input: &MidiInput) {
+        input.set_quantize_start(!self.start_immediately);
+    }
+}
+
+/// Output settings for a single MIDI destination
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutputPortConfig {
+    /// Device name (substring match)
+    pub device: String,
+    /// Use running status (disable for devices that mishandle it)
+    #[serde(default = "default_true")]
+    pub running_status: bool,
+    /// Merge consecutive CCs and drop repeated values
+    #[serde(default = "default_true")]
+    pub coalesce_cc: bool,
+    /// Send MMC transport commands with this device ID (127 = all devices)
+    #[serde(default)]
+    pub mmc: Option<u8>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A single controller mapping
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ControlMapping {
+    /// MIDI note number (for note-based triggers)
+    #[serde(default)]
+    pub note: Option<u8>,
+    /// MIDI CC number (for continuous controls)
+    #[serde(default)]
+    pub cc: Option<u8>,
+    /// Notes held together to trigger (chord triggers)
+    #[serde(default)]
+    pub chord: Vec<u8>,
+    /// Action to perform
+    pub action: String,
+    /// Target of the action (part name, parameter path, etc.)
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Value range for CC mappings [min, max]
+    #[serde(default)]
+    pub range: Option<[f64; 2]>,
+    /// MIDI channel filter (if any)
+    #[serde(default)]
+    pub channel: Option<u8>,
+    /// Mapping page this control belongs to (first page if unset)
+    #[serde(default)]
+    pub page: Option<String>,
+    /// Ignore an absolute CC until it reaches the parameter's current value
+    #[serde(default)]
+    pub soft_takeover: bool,
+}
+
+/// A named sequence of control actions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MacroConfig {
+    /// Steps run in order
+    #[serde(default)]
+    pub steps: Vec<MacroStepConfig>,
+}
+
+/// A single macro step
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MacroStepConfig {
+    /// Action to perform (same names as mappings)
+    pub action: String,
+    /// Target of the action (track, part, parameter path, etc.)
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Value for tempo/parameter actions
+    #[serde(default)]
+    pub value: Option<f64>,
+    /// Delay after the previous step in beats
+    #[serde(default)]
+    pub delay: f64,
+    /// Quantize the step ("beat", "bar", "2 bars", ...)
+    #[serde(default)]
+    pub quantize: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_song_config() {
+        let yaml = r#"
+song:
+  name: "Test Song"
+  tempo: 120
+  key: "C"
+  scale: "major"
+
+tracks:
+  - name: "Pad"
+    channel: 1
+    generator: drone
+    config:
+      density: 0.5
+      voices: 3
+
+  - name: "Bass"
+    channel: 2
+    clips:
+      - file: "clips/bass_1.yaml"
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        assert_eq!(config.song.name, "Test Song");
+        assert_eq!(config.song.tempo, 120.0);
+        assert_eq!(config.song.key, "C");
+        assert_eq!(config.tracks.len(), 2);
+        assert_eq!(config.tracks[0].name, "Pad");
+        assert_eq!(config.tracks[0].generator, Some("drone".to_string()));
+        assert_eq!(config.tracks[1].name, "Bass");
+        assert_eq!(config.tracks[1].clips.len(), 1);
+        assert!(!config.tracks[1].clips[0].is_generated());
+    }
+
+    #[test]
+    fn test_generated_clips() {
+        let yaml = r#"
+song:
+  name: "Factory"
+
+tracks:
+  - name: "Lead"
+    clips:
+      - generator: melody
+        seed: 42
+        bars: 4
+      - name: "Beat"
+        generator: drums
+        params:
+          humanize_velocity: 0
+        ccs:
+          - { tick: 0, cc: 74, value: 40 }
+          - { tick: 48, cc: 74, value: 90 }
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        config.validate().unwrap();
+        let registry = GeneratorRegistry::with_builtins();
+        let context = GeneratorContext::default();
+        let clips = &config.tracks[0].clips;
+
+        let first = clips[0].render(&registry, &context).unwrap().unwrap();
+        let again = clips[0].render(&registry, &context).unwrap().unwrap();
+        assert_eq!(first.name(), "melody #42");
+        assert_eq!(first.length(), 4 * 96);
+        assert_eq!(first.notes(), again.notes());
+
+        let beat = clips[1].render(&registry, &context).unwrap().unwrap();
+        assert_eq!(beat.name(), "Beat");
+        assert!(beat.note_count() > 0);
+        let beat_again = clips[1].render(&registry, &context).unwrap().unwrap();
+        assert_eq!(beat.notes(), beat_again.notes());
+        assert_eq!(beat.ccs()[1], ClipCc::new(48, 74, 90));
+
+        let mut bad = config.clone();
+        bad.tracks[0].clips[0].generator = Some("kazoo".to_string());
+        assert!(bad.validate().is_err());
+        let mut bad = config.clone();
+        bad.tracks[0].clips[1].ccs[0].value = 128;
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_parts() {
+        let yaml = r#"
+song:
+  name: "Test"
+  tempo: 100
+  key: "D"
+  scale: "minor"
+
+tracks:
+  - name: "Pad"
+    channel: 1
+  - name: "Arp"
+    channel: 2
+
+parts:
+  intro:
+    color: '#e07020'
+    tracks:
+      Pad: active
+      Arp: muted
+  main:
+    guard: arm
+    requires: [intro]
+    fx: [riser, crash]
+    tracks:
+      Pad: active
+      Arp: active
+    layers:
+      Arp: 0.3
+
+fx:
+  riser:
+    kind: cc_ramp
+    beats: 8
+    channel: 2
+    cc: 74
+  crash:
+    kind: impact
+    note: 49
+  broken:
+    kind: sweep
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        assert_eq!(config.parts.len(), 2);
+
+        let intro = config.parts.get("intro").unwrap();
+        assert!(intro.tracks.get("Pad").unwrap().is_active());
+        assert!(intro.tracks.get("Arp").unwrap().is_muted());
+        assert_eq!(intro.rgb(), Some((0xe0, 0x70, 0x20)));
+
+        let main = config.parts.get("main").unwrap();
+        assert!(main.tracks.get("Pad").unwrap().is_active());
+        assert!(main.tracks.get("Arp").unwrap().is_active());
+        assert_eq!(main.rgb(), None);
+        assert_eq!(main.guard.as_deref(), Some("arm"));
+        assert_eq!(main.requires, vec!["intro".to_string()]);
+        assert_eq!(main.layers.get("Arp"), Some(&0.3));
+        assert_eq!(main.fx, vec!["riser".to_string(), "crash".to_string()]);
+
+        let riser = config.fx["riser"].to_event("riser").unwrap();
+        assert_eq!(riser.shape(), &FxShape::CcRamp { cc: 74, from: 0, to: 127 });
+        assert_eq!((riser.beats(), riser.channel()), (8, 1));
+        assert_eq!(config.fx["crash"].to_event("crash").unwrap().beats(), 4);
+
+        // A sweep without a note is rejected
+        assert!(config.fx_library().is_err());
+    }
+
+    #[test]
+    fn test_parse_snapshots() {
+        let yaml = r#"
+song:
+  name: "Test"
+  tempo: 120
+  key: "C"
+  scale: "major"
+
+snapshots:
+  - device: "Mixer"
+    cc: {7: 100, 10: 64}
+
+parts:
+  chorus:
+    snapshots:
+      - device: "Synth"
+        channel: 3
+        program: 12
+        nrpn: {1025: 8192}
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let mixer = config.snapshots[0].to_snapshot();
+        assert_eq!(mixer.device(), Some("Mixer"));
+        assert_eq!(
+            mixer.values(),
+            &[
+                SnapshotValue::Cc { channel: 0, cc: 7, value: 100 },
+                SnapshotValue::Cc { channel: 0, cc: 10, value: 64 },
+            ]
+        );
+
+        let synth = config.parts["chorus"].snapshots[0].to_snapshot();
+        assert_eq!(
+            synth.values(),
+            &[
+                SnapshotValue::Program { channel: 2, program: 12 },
+                SnapshotValue::Nrpn { channel: 2, param: 1025, value: 8192 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_modulation() {
+        let yaml = r#"
+song:
+  name: "Test"
+  tempo: 120
+  key: "C"
+  scale: "major"
+
+modulation:
+  - target: melody.rest_probability
+    source: lfo
+    shape: triangle
+    beats: 16
+    depth: 0.2
+  - target: arp.gate
+    source: random
+    step: 0.1
+    seed: 4
+  - target: drums.density
+    source: envelope
+    attack: 2
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let matrix = config.mod_matrix().unwrap();
+        assert_eq!(matrix.len(), 3);
+        let lfo = &matrix.modulators()[0];
+        assert_eq!(lfo.target(), "melody.rest_probability");
+        assert_eq!(lfo.source(), &ModSource::Lfo { shape: LfoShape::Triangle, beats: 16.0, phase: 0.0 });
+        assert_eq!(lfo.depth(), 0.2);
+        assert_eq!(
+            matrix.modulators()[2].source(),
+            &ModSource::Envelope { attack: 2.0, decay: 1.0, sustain: 0.5 }
+        );
+
+        let mut broken = config.clone();
+        broken.modulation[0].shape = Some("wobble".to_string());
+        assert!(broken.validate().is_err());
+    }
+
+    #[test]
+    fn test_generator_config() {
+        let yaml = r#"
+song:
+  name: "Test"
+  tempo: 120
+  key: "C"
+  scale: "major"
+
+tracks:
+  - name: "Arp"
+    channel: 1
+    generator: arpeggio
+    config:
+      pattern: "up-down"
+      octaves: 2
+      rate: "1/16"
+      density: 0.8
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let track = &config.tracks[0];
+        assert_eq!(track.config.get_string("pattern", "up"), "up-down");
+        assert_eq!(track.config.get_int("octaves", 1), 2);
+        assert_eq!(track.config.get_float("density", 0.5), 0.8);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_generator_param_ranges() {
+        let yaml = r#"
+song:
+  name: "Test"
+
+tracks:
+  - name: "Pad"
+    channel: 1
+    generator: chord
+    config:
+      base_octave: 9
+      voicing: "open"
+    clips:
+      - generator: melody
+        params:
+          rest_probability: 0.9
+"#;
+
+        let mut config = SongFile::from_yaml(yaml).unwrap();
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(err, "Track 'Pad': chord parameter 'base_octave' is 9, outside 1-6");
+
+        config.tracks[0].config.params.insert("base_octave".to_string(), GeneratorValue::Int(3));
+        let err = config.validate().unwrap_err().to_string();
+        assert_eq!(err, "Track 'Pad': melody parameter 'rest_probability' is 0.9, outside 0-0.5");
+    }
+
+    #[test]
+    fn test_parse_controls() {
+        let yaml = r#"
+midi:
+  device: "Launchpad Mini"
+
+mappings:
+  - note: 36
+    action: trigger_part
+    target: intro
+
+  - cc: 1
+    action: set_param
+    target: Arp.density
+    range: [0.1, 1.0]
+
+keyboard:
+  space: toggle_play
+  q: trigger_part:intro
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        assert_eq!(controls.midi.device, Some("Launchpad Mini".to_string()));
+        assert_eq!(controls.mappings.len(), 2);
+        assert_eq!(controls.mappings[0].note, Some(36));
+        assert_eq!(controls.mappings[0].action, "trigger_part");
+        assert_eq!(controls.mappings[1].cc, Some(1));
+        assert_eq!(controls.mappings[1].range, Some([0.1, 1.0]));
+        assert_eq!(controls.keyboard.get("space"), Some(&"toggle_play".to_string()));
+        assert_eq!(controls.nudge_depth, 4.0);
+    }
+
+    #[test]
+    fn test_parse_input_devices() {
+        let yaml = r#"
+inputs:
+  - device: "KeyStep"
+    velocity_curve: soft
+  - device: "MPD"
+    velocity_curve: hard
+    fixed_velocity: 100
+  - device: "Tape Deck"
+    mmc: 127
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        assert_eq!(controls.inputs.len(), 3);
+        assert_eq!(controls.input_for("Arturia KeyStep 37").unwrap().curve_spec(), "soft");
+        assert_eq!(controls.input_for("Akai MPD218").unwrap().curve_spec(), "fixed:100");
+        assert!(controls.input_for("Launchpad").is_none());
+        assert_eq!(controls.input_for("Tape Deck").unwrap().mmc, Some(127));
+        assert_eq!(controls.input_for("MPD").unwrap().mmc, None);
+    }
+
+    #[test]
+    fn test_parse_output_ports() {
+        let yaml = r#"
+outputs:
+  - device: "Old Synth"
+    running_status: false
+  - device: "Expander"
+  - device: "Recorder"
+    mmc: 16
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        let old = controls.output_for("Vintage Old Synth MIDI 1").unwrap();
+        assert!(!old.running_status);
+        assert!(old.coalesce_cc);
+        assert!(controls.output_for("Expander").unwrap().running_status);
+        assert!(controls.output_for("Drum Machine").is_none());
+        assert_eq!(controls.output_for("Recorder").unwrap().mmc, Some(16));
+    }
+
+    #[test]
+    fn test_parse_routes() {
+        let yaml = r#"
+routes:
+  - from: "KeyStep"
+    to: "Prophet"
+    remap_channel: 2
+  - from: "MPD"
+    to: "TR-8"
+    channel: 10
+    notes: [36, 51]
+    block: [aftertouch]
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        assert_eq!(controls.routes.len(), 2);
+        assert_eq!(controls.routes[0].remap_channel, Some(2));
+        assert_eq!(controls.routes[1].notes, Some([36, 51]));
+        assert_eq!(controls.routes[1].block, vec!["aftertouch".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_control_surface() {
+        let yaml = r#"
+midi:
+  device: "X-Touch"
+  surface: mackie
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        assert_eq!(controls.midi.surface, Some("mackie".to_string()));
+        assert!(!controls.midi.start_immediately);
+        assert!(controls.midi.clock_sync().quantize_start);
+        assert!(controls.mappings.is_empty());
+
+        let controls = ControlsFile::from_yaml("midi:\n  start_immediately: true\n").unwrap();
+        assert!(!controls.midi.clock_sync().quantize_start);
+    }
+
+    #[test]
+    fn test_parse_network_sessions() {
+        let yaml = r#"
+network:
+  - name: "SEQ"
+  - name: "SEQ to iPad"
+    port: 5006
+    peer: "192.168.1.20:5004"
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        assert_eq!(controls.network.len(), 2);
+        assert_eq!(controls.network[0].port, DEFAULT_RTP_PORT);
+        assert_eq!(controls.network[0].peer, None);
+        assert_eq!(controls.network[1].port, 5006);
+        assert_eq!(controls.network[1].peer.as_deref(), Some("192.168.1.20:5004"));
+    }
+
+    #[test]
+    fn test_parse_lighting() {
+        let yaml = r#"
+lighting:
+  format: osc
+  target: "127.0.0.1:7700"
+  channel: 10
+  velocity: 40
+position_display: remaining
+beat_flash: title+bell
+reload: manual
+osc:
+  port: 0
+  prefix: /touch
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        assert_eq!(controls.position_mode().unwrap(), PositionMode::Remaining);
+        assert!(controls.beat_flash().unwrap().bell);
+        assert_eq!(controls.reload_policy().unwrap(), ReloadPolicy::Manual);
+        assert_eq!(controls.osc.as_ref().unwrap().open_server().unwrap().mapper().prefix(), "/touch");
+        let lighting = controls.lighting.unwrap();
+        assert_eq!(lighting.pulse_format().unwrap(), PulseFormat::Osc);
+        assert!(matches!(lighting.open_sender().unwrap(), Some(PulseSender::Osc { .. })));
+
+        let pulse = lighting.to_pulse();
+        let beat = pulse.pulses(24, 48, 24, 4)[0];
+        assert_eq!(pulse.midi_events(&beat, None)[0].to_midi_bytes(), vec![0x99, 60, 40]);
+    }
+
+    #[test]
+    fn test_parse_key_zone() {
+        let yaml = r#"
+key_zone:
+  range: "C1-B2"
+  channel: 2
+  mode: bass
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        let zone = controls.key_zone.as_ref().unwrap().to_zone().unwrap();
+        assert_eq!(zone.range(), (24, 47));
+        assert_eq!(zone.mode(), KeyZoneMode::Bass);
+        assert!(zone.contains(1, 30));
+        assert!(!zone.contains(0, 30));
+
+        let bad = KeyZoneConfig { range: "C1".to_string(), channel: None, mode: None };
+        assert!(bad.to_zone().is_err());
+    }
+
+    #[test]
+    fn test_parse_launch_quantize() {
+        let yaml = r#"
+launch_quantize:
+  grid: bar
+  keyboard: beat
+  osc: immediate
+"#;
+
+        let mut controls = ControlsFile::from_yaml(yaml).unwrap();
+        let mut modes = controls.launch_quantize().unwrap();
+        modes.sort_by_key(|(source, _)| format!("{:?}", source));
+        assert_eq!(
+            modes,
+            vec![
+                (TriggerSource::Grid, QuantizeMode::Bar),
+                (TriggerSource::Keyboard, QuantizeMode::Beat),
+                (TriggerSource::Osc, QuantizeMode::Immediate),
+            ]
+        );
+
+        controls.launch_quantize.insert("theremin".to_string(), "bar".to_string());
+        assert!(controls.launch_quantize().is_err());
+    }
+
+    #[test]
+    fn test_parse_metronome() {
+        let yaml = r#"
+metronome:
+  enabled: true
+  destination: fluid
+  volume: 0.5
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        let config = controls.metronome.unwrap();
+        let destinations = vec!["IAC Bus 1".to_string(), "FluidSynth".to_string()];
+        let metronome = config.to_metronome(&destinations).unwrap();
+        assert!(metronome.is_enabled());
+        assert_eq!(metronome.channel(), 9);
+        assert_eq!(metronome.volume(), 0.5);
+        assert_eq!(metronome.destination(), Some(1));
+
+        let loud = MetronomeConfig { volume: Some(2.0), ..config };
+        assert!(loud.to_metronome(&destinations).is_err());
+    }
+
+    #[test]
+    fn test_parse_latency() {
+        let yaml = r#"
+latency:
+  midi_ms: 8
+  audio_ms: 23.5
+"#;
+
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        let config = controls.latency.unwrap();
+        let offsets = config.to_offsets().unwrap();
+        assert_eq!(offsets.lighting_ms, 0.0);
+        assert_eq!(offsets.delay_ms(crate::timing::SyncOutput::Midi), 15.5);
+
+        let late = LatencyConfig { lighting_ms: 900.0, ..config };
+        assert!(late.to_offsets().is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = SongFile {
+            song: SongConfig {
+                name: "Round Trip Test".to_string(),
+                tempo: 140.0,
+                key: "G".to_string(),
+                scale: "dorian".to_string(),
+                time_signature_num: 4,
+                time_signature_den: 4,
+                swing: 0.2,
+                record_rounding: Some("pow2".to_string()),
+                tempo_drift: 0.01,
+                tempo_profile: Some("push:0.03".to_string()),
+                gate_scale: 1.2,
+                ppqn: 96,
+                soundfont: Some("sounds/gm.sf2".to_string()),
+            },
+            tracks: vec![TrackConfig {
+                name: "Lead".to_string(),
+                short_name: Some("Ld".to_string()),
+                icon: None,
+                channel: 3,
+                destination: Some("Prophet".to_string()),
+                generator: Some("melody".to_string()),
+                config: GeneratorConfig::default(),
+                clips: Vec::new(),
+                transpose: 2,
+                transpose_mode: Some("degrees".to_string()),
+                swing: None,
+                velocity_scale: 1.0,
+                outputs: Vec::new(),
+                latch: false,
+                voices: Vec::new(),
+                round_robin: None,
+                mpe: Some(MpeConfig {
+                    zone: Some("upper".to_string()),
+                    members: 7,
+                    bend_range: 24,
+                }),
+                cc_thin: Some(CcThinConfig { interval: 6, delta: 2 }),
+                processors: Vec::new(),
+                step_lanes: vec![StepLaneConfig {
+                    param: "octaves".to_string(),
+                    steps: vec![1.0, 1.0, 2.0, 3.0],
+                    steps_per_bar: 4,
+                }],
+                program: Some("Pad 2 (warm)".to_string()),
+                play_probability: 0.75,
+                probability_mode: Some("bar".to_string()),
+                range: Some("E1-E4".to_string()),
+                fold_range: true,
+                mute_group: Some("bass".to_string()),
+                gate_scale: 0.5,
+                overlap: Some("extend".to_string()),
+                shuffle: Some("4 bars".to_string()),
+                chain: Some("Intro x2, Main".to_string()),
+                input: Some("KeyStep".to_string()),
+                input_channel: Some(2),
+                input_range: Some("C2-C5".to_string()),
+                input_quantize: true,
+                target_level: Some(80),
+                pedal: Some("sostenuto".to_string()),
+                groove: Some("shuffle".to_string()),
+            }],
+            parts: HashMap::new(),
+            arp_presets: HashMap::new(),
+            fx: HashMap::new(),
+            snapshots: Vec::new(),
+            modulation: Vec::new(),
+            scenes: Vec::new(),
+            grooves: HashMap::new(),
+            energy: None,
+        };
+
+        let yaml = original.to_yaml().unwrap();
+        let parsed = SongFile::from_yaml(&yaml).unwrap();
+
+        assert_eq!(original.song.name, parsed.song.name);
+        assert_eq!(original.song.tempo, parsed.song.tempo);
+        assert_eq!(original.song.key, parsed.song.key);
+        assert_eq!(original.tracks.len(), parsed.tracks.len());
+        assert_eq!(original.tracks[0].name, parsed.tracks[0].name);
+        assert_eq!(parsed.tracks[0].short_name.as_deref(), Some("Ld"));
+        assert_eq!(
+            parsed.tracks[0].record_input(),
+            RecordInput::new(Some("KeyStep".to_string()), Some(1))
+        );
+        let armed = parsed.tracks[0].armed_input(2).unwrap();
+        assert_eq!((armed.track, armed.range, armed.quantize), (2, (36, 72), true));
+        assert_eq!(parsed.gain_meter().target(0), 80);
+        assert_eq!(parsed.tracks[0].pedal_mode().unwrap(), PedalMode::Sostenuto);
+        assert_eq!(parsed.tracks[0].transpose_mode().unwrap(), TransposeMode::Degrees);
+        let thinner = parsed.tracks[0].cc_thin.as_ref().unwrap().to_thinner().unwrap();
+        assert_eq!((thinner.min_interval(), thinner.min_delta()), (6, 2));
+        assert!(CcThinConfig { interval: 1, delta: 0 }.to_thinner().is_err());
+    }
+
+    #[test]
+    fn test_pattern_chain() {
+        let yaml = r#"
+song:
+  name: "Chains"
+
+tracks:
+  - name: "Drums"
+    chain: "A A B C"
+    clips:
+      - name: "A"
+      - name: "B"
+      - name: "C"
+  - name: "Bass"
+    chain: "Verse x3, 2"
+    clips:
+      - name: "Verse"
+      - name: "Turnaround"
+  - name: "Keys"
+    chain: "Verse"
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let drums = config.tracks[0].pattern_chain().unwrap().unwrap();
+        assert_eq!(drums.entries().len(), 4);
+        assert_eq!(drums.total_loops(), 4);
+        let bass = config.tracks[1].pattern_chain().unwrap().unwrap();
+        assert_eq!(bass.entries(), &[ChainEntry::new(0, 3), ChainEntry::new(1, 1)]);
+        assert!(config.tracks[2].pattern_chain().is_err());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_output_layers() {
+        let yaml = r#"
+song:
+  name: "Layers"
+
+tracks:
+  - name: "Pad"
+    channel: 1
+    latch: true
+    outputs:
+      - destination: "Prophet"
+        channel: 2
+      - destination: "FluidSynth"
+        channel: 5
+        transpose: 12
+        velocity_scale: 0.7
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let outputs = &config.tracks[0].outputs;
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].destination, Some("Prophet".to_string()));
+        assert_eq!(outputs[0].velocity_scale, 1.0);
+        assert_eq!(outputs[1].channel, 5);
+        assert_eq!(outputs[1].transpose, 12);
+        assert!(config.tracks[0].latch);
+    }
+
+    #[test]
+    fn test_parse_voice_routes() {
+        let yaml = r#"
+song:
+  name: "Split Kit"
+
+tracks:
+  - name: "Drums"
+    generator: drums
+    channel: 10
+    voices:
+      - voice: kick
+        destination: "Volca"
+        channel: 1
+      - voice: hat
+        channel: 11
+        note: 60
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let voices = &config.tracks[0].voices;
+        assert_eq!(voices.len(), 2);
+        assert_eq!(voices[0].destination, Some("Volca".to_string()));
+        assert_eq!(voices[1].channel, 11);
+        assert_eq!(voices[1].note, Some(60));
     }
 
-    /// Parse controls configuration from YAML string
-    pub fn from_yaml(yaml: &str) -> Result<Self> {
-        serde_yaml::from_str(yaml).context("Failed to parse controls YAML")
+    #[test]
+    fn test_parse_mpe() {
+        let yaml = r#"
+song:
+  name: "Expressive"
+
+tracks:
+  - name: "Pad"
+    generator: drone
+    channel: 1
+    mpe:
+      zone: upper
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let mpe = config.tracks[0].mpe.as_ref().unwrap();
+        assert_eq!(mpe.zone().unwrap(), MpeZone::Upper);
+        assert_eq!((mpe.members, mpe.bend_range), (15, 48));
+        assert!(config.validate().is_ok());
+
+        let bad = yaml.replace("zone: upper", "members: 16");
+        assert!(SongFile::from_yaml(&bad).unwrap().validate().is_err());
     }
-}
 
-/// MIDI device configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
-pub struct MidiDeviceConfig {
-    /// Device name to connect to
-    #[serde(default)]
-    pub device: Option<String>,
-    /// Input channel filter (if any)
-    #[serde(default)]
-    pub input_channel: Option<u8>,
-}
+    #[test]
+    fn test_track_destinations() {
+        let yaml = r#"
+song:
+  name: "Rig"
 
-/// A single controller mapping
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ControlMapping {
-    /// MIDI note number (for note-based triggers)
-    #[serde(default)]
-    pub note: Option<u8>,
-    /// MIDI CC number (for continuous controls)
-    #[serde(default)]
-    pub cc: Option<u8>,
-    /// Action to perform
-    pub action: String,
-    /// Target of the action (part name, parameter path, etc.)
-    #[serde(default)]
-    pub target: Option<String>,
-    /// Value range for CC mappings [min, max]
-    #[serde(default)]
-    pub range: Option<[f64; 2]>,
-    /// MIDI channel filter (if any)
-    #[serde(default)]
-    pub channel: Option<u8>,
-}
+tracks:
+  - name: "Drums"
+    destination: "Volca"
+    channel: 10
+    voices:
+      - voice: kick
+        destination: "volca"
+  - name: "Pad"
+    destination: "Prophet"
+    outputs:
+      - destination: "IAC"
+"#;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let config = SongFile::from_yaml(yaml).unwrap();
+        assert_eq!(config.tracks[0].destination.as_deref(), Some("Volca"));
+        assert_eq!(config.tracks[0].destination_names(), vec!["Volca"]);
+        assert_eq!(config.destination_names(), vec!["Volca", "Prophet", "IAC"]);
+    }
 
     #[test]
-    fn test_parse_song_config() {
+    fn test_track_program_names() {
         let yaml = r#"
 song:
-  name: "Test Song"
-  tempo: 120
-  key: "C"
-  scale: "major"
+  name: "Programs"
 
 tracks:
   - name: "Pad"
-    channel: 1
-    generator: drone
-    config:
-      density: 0.5
-      voices: 3
+    program: "Warm Pad"
+  - name: "Keys"
+    program: "4"
+  - name: "Lead"
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        assert_eq!(config.tracks[0].program_number(), Some(89));
+        assert_eq!(config.tracks[1].program_number(), Some(4));
+        assert_eq!(config.tracks[2].program_number(), None);
+    }
+
+    #[test]
+    fn test_track_ranges() {
+        let yaml = r#"
+song:
+  name: "Ranges"
 
+tracks:
   - name: "Bass"
-    channel: 2
-    clips:
-      - file: "clips/bass_1.yaml"
+    range: "E1-E4"
+    fold_range: true
+    generator: "melody"
+    config:
+      base_octave: 4
+      octave_range: 2
+  - name: "Sub"
+    range: "C-1-B1"
+  - name: "Broken"
+    range: "low to high"
+  - name: "Free"
 "#;
 
         let config = SongFile::from_yaml(yaml).unwrap();
-        assert_eq!(config.song.name, "Test Song");
-        assert_eq!(config.song.tempo, 120.0);
-        assert_eq!(config.song.key, "C");
-        assert_eq!(config.tracks.len(), 2);
-        assert_eq!(config.tracks[0].name, "Pad");
-        assert_eq!(config.tracks[0].generator, Some("drone".to_string()));
-        assert_eq!(config.tracks[1].name, "Bass");
-        assert_eq!(config.tracks[1].clips.len(), 1);
+        assert_eq!(config.tracks[0].note_range().unwrap(), Some((28, 64)));
+        assert!(config.tracks[0].fold_range);
+        assert_eq!(config.tracks[1].note_range().unwrap(), Some((0, 35)));
+        assert!(config.tracks[2].note_range().is_err());
+        assert_eq!(config.tracks[3].note_range().unwrap(), None);
+
+        // Octaves 4-5 reach past E4; the broken range is reported too
+        let warnings = config.range_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("Bass"));
+        assert!(warnings[1].contains("Broken"));
     }
 
     #[test]
-    fn test_parse_parts() {
+    fn test_track_processors() {
         let yaml = r#"
 song:
-  name: "Test"
-  tempo: 100
-  key: "D"
-  scale: "minor"
+  name: "Processors"
+
+tracks:
+  - name: "Keys"
+    processors:
+      - kind: velocity_curve
+        curve: 0.6
+        min: 30
+      - kind: humanize
+        timing: 2
+        velocity: 8
+      - kind: note_range
+        range: "C2-C5"
+        fold: true
+      - kind: channel_remap
+        from: 1
+        to: 10
+      - kind: echo
+        delay: 6
+        decay: 0.5
+      - kind: harmonize
+        degrees: [2, 4]
+      - kind: chordify
+        chord: power
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let chain = config.tracks[0].processor_chain().unwrap();
+        assert_eq!(
+            chain.processors(),
+            &[
+                Processor::velocity_curve(0.6, 30, 127),
+                Processor::humanize(2, 8),
+                Processor::note_range(36, 72, true),
+                Processor::channel_remap(Some(0), 9),
+                Processor::echo(6, 3, 0.5),
+                Processor::harmonize(&[2, 4]),
+                Processor::chordify(&[7, 12]),
+            ]
+        );
+
+        let bad = yaml.replace("to: 10", "to: 17");
+        let error = SongFile::from_yaml(&bad).unwrap().validate().unwrap_err().to_string();
+        assert!(error.contains("Keys") && error.contains("17"));
+        let bad = yaml.replace("kind: humanize", "kind: wobble");
+        assert!(SongFile::from_yaml(&bad).unwrap().validate().is_err());
+        let bad = yaml.replace("chord: power", "chord: wide");
+        assert!(SongFile::from_yaml(&bad).unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn test_energy_policy() {
+        let yaml = r#"
+song:
+  name: "Energy"
 
 tracks:
   - name: "Pad"
-    channel: 1
-  - name: "Arp"
-    channel: 2
+  - name: "Drums"
+    generator: drums
+  - name: "Lead"
+    generator: melody
 
-parts:
-  intro:
-    tracks:
-      Pad: active
-      Arp: muted
-  main:
-    tracks:
-      Pad: active
-      Arp: active
+energy:
+  tempo_min: 118
+  tempo_max: 126
+  layers: [Pad, Drums, Lead]
+  params:
+    - { track: Lead, param: rest_probability, low: 0.5, high: 0.0 }
 "#;
 
         let config = SongFile::from_yaml(yaml).unwrap();
-        assert_eq!(config.parts.len(), 2);
-
-        let intro = config.parts.get("intro").unwrap();
-        assert!(intro.tracks.get("Pad").unwrap().is_active());
-        assert!(intro.tracks.get("Arp").unwrap().is_muted());
+        config.validate().unwrap();
+        let policy = config.energy_policy().unwrap();
+        assert_eq!(policy.tempo_range(), Some((118.0, 126.0)));
+        assert_eq!(policy.layers(), &[0, 1, 2]);
+        // The song's own mapping wins over the built-in one
+        let rests: Vec<&EnergyParam> = policy.params().iter().filter(|p| p.param == "rest_probability").collect();
+        assert_eq!(rests, vec![&EnergyParam::new(2, "rest_probability", 0.5, 0.0)]);
+        assert!(policy.params().iter().any(|p| p.track == 1 && p.param == "kick_euclidean_hits"));
 
-        let main = config.parts.get("main").unwrap();
-        assert!(main.tracks.get("Pad").unwrap().is_active());
-        assert!(main.tracks.get("Arp").unwrap().is_active());
+        let manual = SongFile::from_yaml(&yaml.replace("energy:", "energy:\n  heuristics: false")).unwrap();
+        assert_eq!(manual.energy_policy().unwrap().params().len(), 1);
+        let bad = yaml.replace("[Pad, Drums, Lead]", "[Pad, Bass]");
+        assert!(SongFile::from_yaml(&bad).unwrap().validate().unwrap_err().to_string().contains("Bass"));
+        let bad = yaml.replace("tempo_max: 126\n", "");
+        assert!(SongFile::from_yaml(&bad).unwrap().validate().is_err());
     }
 
     #[test]
-    fn test_generator_config() {
+    fn test_step_lanes() {
         let yaml = r#"
 song:
-  name: "Test"
-  tempo: 120
-  key: "C"
-  scale: "major"
+  name: "Steps"
 
 tracks:
   - name: "Arp"
-    channel: 1
     generator: arpeggio
-    config:
-      pattern: "up-down"
-      octaves: 2
-      rate: "1/16"
-      density: 0.8
+    step_lanes:
+      - param: octaves
+        steps: [1, 1, 2, 3]
+      - param: gate
+        steps: [0.9, 0.4]
+        steps_per_bar: 8
 "#;
 
         let config = SongFile::from_yaml(yaml).unwrap();
-        let track = &config.tracks[0];
-        assert_eq!(track.config.get_string("pattern", "up"), "up-down");
-        assert_eq!(track.config.get_int("octaves", 1), 2);
-        assert_eq!(track.config.get_float("density", 0.5), 0.8);
+        config.validate().unwrap();
+        let lanes = config.tracks[0].step_lanes().unwrap();
+        assert_eq!(lanes[0], StepLane::new("octaves", vec![1.0, 1.0, 2.0, 3.0], 4));
+        assert_eq!(lanes[1].steps_per_bar(), 8);
+
+        let bad = yaml.replace("[1, 1, 2, 3]", "[1, 9]");
+        let error = SongFile::from_yaml(&bad).unwrap().validate().unwrap_err().to_string();
+        assert!(error.contains("octaves") && error.contains("9"));
+        let bad = yaml.replace("param: gate", "param: wobble");
+        assert!(SongFile::from_yaml(&bad).unwrap().validate().is_err());
     }
 
     #[test]
-    fn test_parse_controls() {
+    fn test_clip_shuffle() {
         let yaml = r#"
-midi:
-  device: "Launchpad Mini"
+song:
+  name: "Shuffle"
 
-mappings:
-  - note: 36
-    action: trigger_part
-    target: intro
+tracks:
+  - name: "Drums"
+    shuffle: "2 bars"
+    clips:
+      - name: "Beat A"
+        weight: 3.0
+      - name: "Beat B"
+  - name: "Bass"
+    shuffle: "loop"
+  - name: "Keys"
+    shuffle: "sometimes"
+"#;
 
-  - cc: 1
-    action: set_param
-    target: Arp.density
-    range: [0.1, 1.0]
+        let config = SongFile::from_yaml(yaml).unwrap();
+        let shuffle = config.tracks[0].clip_shuffle().unwrap().unwrap();
+        assert_eq!(shuffle.every_bars(), 2);
+        assert_eq!(shuffle.weight(0), 3.0);
+        assert_eq!(shuffle.weight(1), 1.0);
+        assert_eq!(config.tracks[1].clip_shuffle().unwrap().unwrap().every_bars(), 0);
+        assert!(config.tracks[2].clip_shuffle().is_err());
+    }
 
-keyboard:
-  space: toggle_play
-  q: trigger_part:intro
-"#;
+    #[test]
+    fn test_tempo_humanizer() {
+        let mut song = SongConfig::default();
+        assert!(song.tempo_humanizer().unwrap().is_none());
 
-        let controls = ControlsFile::from_yaml(yaml).unwrap();
-        assert_eq!(controls.midi.device, Some("Launchpad Mini".to_string()));
-        assert_eq!(controls.mappings.len(), 2);
-        assert_eq!(controls.mappings[0].note, Some(36));
-        assert_eq!(controls.mappings[0].action, "trigger_part");
-        assert_eq!(controls.mappings[1].cc, Some(1));
-        assert_eq!(controls.mappings[1].range, Some([0.1, 1.0]));
-        assert_eq!(controls.keyboard.get("space"), Some(&"toggle_play".to_string()));
+        song.tempo_drift = 0.02;
+        song.tempo_profile = Some("drag:0.03".to_string());
+        let humanizer = song.tempo_humanizer().unwrap().unwrap();
+        assert_eq!(humanizer.drift(), 0.02);
+        assert_eq!(humanizer.profile(), TempoProfile::Drag);
+
+        song.tempo_profile = Some("sideways".to_string());
+        assert!(song.tempo_humanizer().is_err());
     }
 
     #[test]
-    fn test_round_trip() {
-        let original = SongFile {
-            song: SongConfig {
-                name: "Round Trip Test".to_string(),
-                tempo: 140.0,
-                key: "G".to_string(),
-                scale: "dorian".to_string(),
-                time_signature_num: 4,
-                time_signature_den: 4,
-                swing: 0.2,
-            },
-            tracks: vec![TrackConfig {
-                name: "Lead".to_string(),
-                channel: 3,
-                generator: Some("melody".to_string()),
-                config: GeneratorConfig::default(),
-                clips: Vec::new(),
-                transpose: 0,
-                swing: None,
-                velocity_scale: 1.0,
-            }],
-            parts: HashMap::new(),
-        };
+    fn test_resolution() {
+        let config = SongFile::from_yaml("song:\n  name: Fine\n  ppqn: 480\n").unwrap();
+        assert_eq!(config.song.resolution().unwrap(), 480);
 
-        let yaml = original.to_yaml().unwrap();
-        let parsed = SongFile::from_yaml(&yaml).unwrap();
+        let mut song = SongConfig::default();
+        assert_eq!(song.resolution().unwrap(), 24);
+        song.ppqn = 100;
+        assert!(song.resolution().is_err());
+    }
 
-        assert_eq!(original.song.name, parsed.song.name);
-        assert_eq!(original.song.tempo, parsed.song.tempo);
-        assert_eq!(original.song.key, parsed.song.key);
-        assert_eq!(original.tracks.len(), parsed.tracks.len());
-        assert_eq!(original.tracks[0].name, parsed.tracks[0].name);
+    #[test]
+    fn test_parse_arp_presets() {
+        let yaml = r#"
+song:
+  name: "Presets"
+
+arp_presets:
+  rolling: "1-3-5-3^"
+  sparse: "1 r r 5"
+
+tracks:
+  - name: "Arp"
+    generator: arpeggio
+    config:
+      preset: rolling
+"#;
+
+        let config = SongFile::from_yaml(yaml).unwrap();
+        assert_eq!(config.arp_presets.len(), 2);
+        assert_eq!(config.arp_presets.get("rolling"), Some(&"1-3-5-3^".to_string()));
+        assert_eq!(config.tracks[0].config.get_string("preset", ""), "rolling");
+
+        // The track's arpeggio plays the song preset
+        let registry = GeneratorRegistry::with_builtins();
+        let generator = config.build_generator(&config.tracks[0], &registry).unwrap().unwrap();
+        let mut custom = ArpeggioGenerator::new();
+        custom.set_step_order("1-3-5-3^").unwrap();
+        assert_eq!(generator.get_param("pattern"), custom.get_param("pattern"));
+        assert_ne!(generator.get_param("pattern"), ArpeggioGenerator::new().get_param("pattern"));
+        assert!(config.validate().is_ok());
+
+        let mut missing = config.clone();
+        missing.tracks[0].config.params.insert("preset".to_string(), GeneratorValue::String("gone".to_string()));
+        assert!(missing.validate().is_err());
+    }
+
+    #[test]
+    fn test_save_arp_preset() {
+        let mut config = SongFile::from_yaml("song:\n  name: \"Presets\"\n").unwrap();
+        let steps = ArpStep::parse_order("1-5^-r").unwrap();
+        config.save_arp_preset("climb", &steps).unwrap();
+        assert!(config.save_arp_preset(" ", &steps).is_err());
+
+        let parsed = SongFile::from_yaml(&config.to_yaml().unwrap()).unwrap();
+        assert_eq!(parsed.arp_library().unwrap().get("climb"), Some(steps.as_slice()));
     }
 
     #[test]
@@ -552,6 +3532,86 @@ song:
         assert_eq!(config.song.time_signature_den, 4);
     }
 
+    #[test]
+    fn test_parse_scenes() {
+        let yaml = r#"
+song:
+  name: "Scenes"
+tracks:
+  - name: "Lead"
+    channel: 1
+    clips:
+      - generator: arpeggio
+  - name: "Pad"
+    channel: 2
+scenes:
+  - name: "Verse"
+    slots:
+      0: { clip: 0 }
+      1: { generator: drone }
+    mutes:
+      1: false
+  - name: "Break"
+    slots:
+      0: stop
+    mutes:
+      1: true
+    launch_mode: { bars: 2 }
+"#;
+
+        let mut config = SongFile::from_yaml(yaml).unwrap();
+        config.validate().unwrap();
+        let mut scenes = config.scene_manager();
+        assert_eq!(scenes.scene_count(), 2);
+        assert_eq!(scenes.get_scene(0).unwrap().slot(1), &SceneSlot::Generator("drone".to_string()));
+        assert_eq!(scenes.get_scene(1).unwrap().mute(1), Some(true));
+
+        // Edited scenes are written back with the song
+        scenes.get_scene_mut(1).unwrap().set_mute(0, true);
+        config.store_scenes(&scenes);
+        let reloaded = SongFile::from_yaml(&config.to_yaml().unwrap()).unwrap();
+        assert_eq!(reloaded.scenes, config.scenes);
+        assert_eq!(reloaded.scenes[1].mute(0), Some(true));
+
+        config.scenes[0].set_slot(0, SceneSlot::Clip(3));
+        assert!(config.validate().unwrap_err().to_string().contains("plays clip 3"));
+        config.scenes[0].set_slot(5, SceneSlot::Stop);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_grooves() {
+        let yaml = r#"
+song:
+  name: "Grooves"
+  swing: 0.2
+tracks:
+  - name: "Drums"
+    groove: dilla
+  - name: "Bass"
+    groove: Shuffle
+    swing: 0.0
+grooves:
+  dilla:
+    offsets: [0.0, 0.3, 0.1, 0.4]
+    velocities: [1.0, 0.7]
+"#;
+
+        let mut config = SongFile::from_yaml(yaml).unwrap();
+        config.validate().unwrap();
+        let drums = config.track_groove(&config.tracks[0]).unwrap().unwrap();
+        assert_eq!(drums.apply(18, 24), (2, 0.7));
+        assert_eq!(config.track_groove(&config.tracks[1]).unwrap().unwrap().name(), "shuffle");
+        assert_eq!(config.track_swing(&config.tracks[0]), 0.2);
+        assert_eq!(config.track_swing(&config.tracks[1]), 0.0);
+
+        config.tracks[1].groove = Some("polka".to_string());
+        assert!(config.validate().unwrap_err().to_string().contains("unknown groove"));
+        config.tracks[1].groove = None;
+        config.grooves.get_mut("dilla").unwrap().offsets[0] = 0.9;
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_track_state() {
         let active = TrackState::Simple("active".to_string());
@@ -567,4 +3627,25 @@ song:
         assert!(clip.is_active());
         assert_eq!(clip.clip_name(), Some("clip_1"));
     }
+
+    #[test]
+    fn test_startup_safe_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let song_path = dir.path().join("song.yaml");
+        let controls_path = dir.path().join("controls.yaml");
+        fs::write(&song_path, "song:\n  name: \"Broken\"\n  ppqn: 100\n").unwrap();
+        fs::write(&controls_path, "nudge_depth: 2.0\n").unwrap();
+
+        let startup = StartupConfig::load(Some(&song_path), Some(&controls_path));
+        assert!(startup.is_safe_mode());
+        assert_eq!(startup.errors.len(), 1);
+        assert!(startup.errors[0].contains("Invalid ppqn 100"));
+        assert_eq!(startup.song, SongFile::default());
+        assert_eq!(startup.controls.nudge_depth, 2.0);
+
+        let startup = StartupConfig::load(None, Some(&dir.path().join("missing.yaml")));
+        assert!(startup.errors[0].contains("Failed to read controls file"));
+        assert_eq!(startup.controls, ControlsFile::default());
+        assert!(!StartupConfig::load(None, None).is_safe_mode());
+    }
 }
@@ -0,0 +1,185 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Portable performance profiles.
+//!
+//! A profile carries a performer's control layout (mappings, pages,
+//! keyboard bindings and macros) together with a snapshot of parameter
+//! values and locks, so the layout can follow them from one song to the
+//! next. Importing merges the profile over the song's own controls: a
+//! profile mapping replaces the song's mapping on the same control, and
+//! macros and keys replace those of the same name.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::control::ParameterRegistry;
+
+use super::{ControlMapping, ControlsFile, MacroConfig, PageConfig};
+
+/// A performer's control layout and parameter snapshot
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PerformanceProfile {
+    /// Profile name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Controller mappings
+    #[serde(default)]
+    pub mappings: Vec<ControlMapping>,
+    /// Mapping pages
+    #[serde(default)]
+    pub pages: Vec<PageConfig>,
+    /// Keyboard bindings (key -> action)
+    #[serde(default)]
+    pub keyboard: HashMap<String, String>,
+    /// Macros by name
+    #[serde(default)]
+    pub macros: HashMap<String, MacroConfig>,
+    /// Parameter values by name
+    #[serde(default)]
+    pub params: BTreeMap<String, f64>,
+    /// Locked parameters
+    #[serde(default)]
+    pub locked: Vec<String>,
+}
+
+impl PerformanceProfile {
+    /// Capture the controls and current parameter values
+    pub fn capture(name: impl Into<String>, controls: &ControlsFile, params: &ParameterRegistry) -> Self {
+        let mut locked: Vec<String> = params.iter().filter(|p| p.locked).map(|p| p.name.clone()).collect();
+        locked.sort();
+        Self {
+            name: Some(name.into()),
+            mappings: controls.mappings.clone(),
+            pages: controls.pages.clone(),
+            keyboard: controls.keyboard.clone(),
+            macros: controls.macros.clone(),
+            // Targets, so a parameter mid-glide is saved where it is heading
+            params: params.iter().map(|p| (p.name.clone(), p.value.target())).collect(),
+            locked,
+        }
+    }
+
+    /// Load a profile from a YAML file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let contents = fs::read_to_string(path.as_ref())
+            .with_context(|| format!("Failed to read profile: {:?}", path.as_ref()))?;
+        Self::from_yaml(&contents)
+    }
+
+    /// Parse a profile from a YAML string
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        serde_yaml::from_str(yaml).context("Failed to parse profile YAML")
+    }
+
+    /// Serialize to a YAML string
+    pub fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self).context("Failed to serialize profile to YAML")
+    }
+
+    /// Save the profile to a YAML file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let yaml = self.to_yaml()?;
+        fs::write(path.as_ref(), yaml)
+            .with_context(|| format!("Failed to write profile: {:?}", path.as_ref()))
+    }
+
+    /// Merge the control layout into a song's controls
+    pub fn merge_into(&self, controls: &mut ControlsFile) {
+        for page in &self.pages {
+            match controls.pages.iter_mut().find(|p| p.name.eq_ignore_ascii_case(&page.name)) {
+                Some(existing) => *existing = page.clone(),
+                None => controls.pages.push(page.clone()),
+            }
+        }
+        controls
+            .mappings
+            .retain(|mapping| !self.mappings.iter().any(|m| same_control(m, mapping)));
+        controls.mappings.extend(self.mappings.iter().cloned());
+        controls.keyboard.extend(self.keyboard.clone());
+        controls.macros.extend(self.macros.clone());
+    }
+
+    /// Restore parameter values and locks. Returns the names of parameters
+    /// this song doesn't have.
+    pub fn apply_params(&self, params: &mut ParameterRegistry) -> Vec<String> {
+        let mut missing = Vec::new();
+        for (name, value) in &self.params {
+            if !params.set(name, *value) {
+                missing.push(name.clone());
+            }
+        }
+        for name in &self.locked {
+            params.set_locked(name, true);
+        }
+        missing
+    }
+}
+
+/// Check if two mappings sit on the same physical control
+fn same_control(a: &ControlMapping, b: &ControlMapping) -> bool {
+    let page = |m: &ControlMapping| m.page.as_deref().map(str::to_lowercase);
+    a.note == b.note && a.cc == b.cc && a.chord == b.chord && a.channel == b.channel && page(a) == page(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::Parameter;
+
+    #[test]
+    fn test_profile_round_trip_and_import() {
+        let performer = ControlsFile::from_yaml(
+            r#"
+mappings:
+  - cc: 74
+    action: set_param
+    target: Arp.density
+  - note: 36
+    action: trigger_part
+    target: Chorus
+macros:
+  drop:
+    steps:
+      - action: stop_all
+"#,
+        )
+        .unwrap();
+        let mut params = ParameterRegistry::new();
+        params.register(Parameter::new("Arp.density", 0.0, 1.0, 0.5));
+        params.register(Parameter::new("Arp.swing", 0.0, 1.0, 0.0));
+        params.set("Arp.density", 0.8);
+        params.set_locked("Arp.swing", true);
+
+        let profile = PerformanceProfile::capture("Live", &performer, &params);
+        let profile = PerformanceProfile::from_yaml(&profile.to_yaml().unwrap()).unwrap();
+        assert_eq!(profile.locked, vec!["Arp.swing".to_string()]);
+
+        // The song maps CC 74 elsewhere and has a mapping of its own
+        let mut song = ControlsFile::from_yaml(
+            r#"
+mappings:
+  - cc: 74
+    action: set_tempo
+  - cc: 1
+    action: fill
+"#,
+        )
+        .unwrap();
+        profile.merge_into(&mut song);
+        assert_eq!(song.mappings.len(), 3);
+        assert_eq!(song.mappings.iter().filter(|m| m.cc == Some(74)).count(), 1);
+        assert!(song.mappings.iter().any(|m| m.action == "set_param"));
+        assert!(song.macros.contains_key("drop"));
+
+        let mut song_params = ParameterRegistry::new();
+        song_params.register(Parameter::new("Arp.density", 0.0, 1.0, 0.5));
+        let missing = profile.apply_params(&mut song_params);
+        assert_eq!(song_params.get("Arp.density").unwrap().get(), 0.8);
+        assert_eq!(missing, vec!["Arp.swing".to_string()]);
+    }
+}
@@ -0,0 +1,403 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Project folders.
+//!
+//! A project keeps everything a set needs in one folder (or one zip of
+//! that folder), described by a `project.yaml` manifest:
+//!
+//! ```text
+//! project.yaml    manifest: name, song, controls, custom scales, file list
+//! song.yaml       the song, with paths pointing inside the project
+//! controls.yaml   controller mappings (optional)
+//! clips/          clip files
+//! assets/         SysEx dumps and other files the song refers to
+//! ```
+//!
+//! Soundfonts are large and usually shared between projects, so they are
+//! referenced where they live rather than copied: the manifest lists them
+//! relative to the project folder (or to the folder a zip sits in), so a
+//! project and its sounds can move together to another machine. Opening a
+//! project resolves every path the song refers to against the project and
+//! notes any file that can't be found.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use crate::music::scale::{CustomScaleDefinition, ScaleRegistry};
+
+use super::bundle::{
+    is_bundle, resolve_path, unique_entry, unpack_if_bundle, UnpackedSong, BUNDLE_ASSETS, BUNDLE_SONG,
+};
+use super::{ControlsFile, SongFile};
+
+/// Name of the manifest in a project
+pub const PROJECT_MANIFEST: &str = "project.yaml";
+
+/// Name of the controls in a project
+pub const PROJECT_CONTROLS: &str = "controls.yaml";
+
+/// Folder clip files are stored under in a project
+pub const PROJECT_CLIPS: &str = "clips";
+
+fn default_song() -> String {
+    BUNDLE_SONG.to_string()
+}
+
+/// What a project holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectManifest {
+    /// Project name
+    pub name: String,
+    /// Song file, relative to the project
+    #[serde(default = "default_song")]
+    pub song: String,
+    /// Controls file, relative to the project
+    #[serde(default)]
+    pub controls: Option<String>,
+    /// Custom scales the song can use
+    #[serde(default)]
+    pub scales: Vec<CustomScaleDefinition>,
+    /// Soundfonts the song plays through, relative to the project folder
+    /// (or to the folder a zip sits in)
+    #[serde(default)]
+    pub soundfonts: Vec<String>,
+    /// Files stored in the project, relative to it
+    #[serde(default)]
+    pub files: Vec<String>,
+}
+
+impl ProjectManifest {
+    /// Create a manifest for a project holding only a song
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            song: default_song(),
+            controls: None,
+            scales: Vec::new(),
+            soundfonts: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+}
+
+/// A project opened from a folder, a zip, a manifest or a lone song file
+#[derive(Debug, Clone)]
+pub struct Project {
+    /// Folder the project files are in
+    pub dir: PathBuf,
+    /// The manifest (made up from the song when there is none)
+    pub manifest: ProjectManifest,
+    /// The song, with every file path resolved against the project
+    pub song: SongFile,
+    /// The controls, if the project has them
+    pub controls: Option<ControlsFile>,
+    /// Files the song refers to that weren't found on opening
+    missing: Vec<PathBuf>,
+    /// Where the song was loaded from (keeps an unpacked zip on disk)
+    source: UnpackedSong,
+}
+
+impl Project {
+    /// Open a project. A zip is unpacked into a temporary folder that is
+    /// removed when the project is dropped.
+    pub fn open(location: &Path) -> Result<Self> {
+        let source = unpack_if_bundle(location)?;
+        let path = source.path().to_path_buf();
+        let (dir, song_file) = if path.is_dir() {
+            (path.clone(), None)
+        } else {
+            let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let is_manifest = path.file_name().is_some_and(|n| n == PROJECT_MANIFEST);
+            (dir, (!is_manifest).then_some(path.clone()))
+        };
+
+        // A song beside a manifest that names it opens the whole project
+        let manifest_path = dir.join(PROJECT_MANIFEST);
+        let stored: Option<ProjectManifest> = if manifest_path.is_file() {
+            let text = fs::read_to_string(&manifest_path)
+                .with_context(|| format!("Failed to read project manifest: {:?}", manifest_path))?;
+            Some(serde_yaml::from_str(&text).context("Failed to parse project manifest")?)
+        } else {
+            None
+        };
+        let file_name = song_file.as_ref().and_then(|f| f.file_name()).and_then(|n| n.to_str());
+        let manifest = match (stored, &song_file) {
+            (Some(manifest), None) => manifest,
+            (Some(manifest), Some(_)) if file_name == Some(manifest.song.as_str()) => manifest,
+            (None, None) => ProjectManifest::new(dir.file_name().and_then(|n| n.to_str()).unwrap_or("Project")),
+            (_, Some(file)) => ProjectManifest {
+                song: file.file_name().and_then(|n| n.to_str()).unwrap_or(BUNDLE_SONG).to_string(),
+                ..ProjectManifest::new(file.file_stem().and_then(|n| n.to_str()).unwrap_or("Project"))
+            },
+        };
+
+        // Soundfonts are found from where the project sits, not the unpacked copy
+        let song_path = dir.join(&manifest.song);
+        let anchor = if source.is_unpacked() { location.to_path_buf() } else { song_path.clone() };
+        let mut song = SongFile::load(&song_path)?;
+        song.map_asset_paths(|asset| {
+            let from = if manifest.soundfonts.iter().any(|s| s == asset) { &anchor } else { &song_path };
+            resolve_path(from, asset).to_string_lossy().into_owned()
+        });
+        let missing = song.missing_assets(&song_path);
+        let controls = match &manifest.controls {
+            Some(controls) => Some(ControlsFile::load(dir.join(controls))?),
+            None => None,
+        };
+        Ok(Self {
+            dir,
+            manifest,
+            song,
+            controls,
+            missing,
+            source,
+        })
+    }
+
+    /// Path of the song file
+    pub fn song_path(&self) -> PathBuf {
+        self.dir.join(&self.manifest.song)
+    }
+
+    /// Check if the project was unpacked from a zip
+    pub fn is_unpacked(&self) -> bool {
+        self.source.is_unpacked()
+    }
+
+    /// Scale registry holding the project's custom scales
+    pub fn scale_registry(&self) -> ScaleRegistry {
+        let mut registry = ScaleRegistry::new();
+        for scale in &self.manifest.scales {
+            registry.register(scale.clone());
+        }
+        registry
+    }
+
+    /// Files the song refers to that weren't found on opening, soundfonts
+    /// included
+    pub fn missing_files(&self) -> &[PathBuf] {
+        &self.missing
+    }
+}
+
+/// Load custom scales from a YAML list of `name` and `intervals`
+pub fn load_scales(path: &Path) -> Result<Vec<CustomScaleDefinition>> {
+    let text = fs::read_to_string(path).with_context(|| format!("Failed to read scales file: {:?}", path))?;
+    let scales: Vec<CustomScaleDefinition> = serde_yaml::from_str(&text).context("Failed to parse scales")?;
+    for scale in &scales {
+        if scale.name.trim().is_empty() || scale.intervals.is_empty() || scale.intervals.iter().any(|&i| i > 11) {
+            return Err(anyhow!("Scale '{}' needs a name and intervals from 0 to 11", scale.name));
+        }
+    }
+    Ok(scales)
+}
+
+/// Path from a folder to a file, both absolute: up to the folder they
+/// share, then down to the file (the file as is if they share nothing)
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to_parts: Vec<Component> = to.components().collect();
+    let shared = from.iter().zip(&to_parts).take_while(|(a, b)| a == b).count();
+    if shared == 0 {
+        return to.to_path_buf();
+    }
+    let mut path = PathBuf::new();
+    for _ in shared..from.len() {
+        path.push("..");
+    }
+    for part in &to_parts[shared..] {
+        path.push(part);
+    }
+    path
+}
+
+/// Save a song, its controls and custom scales as a project, into a folder
+/// or (for a `.zip` path) one archive. Returns the manifest written.
+pub fn save_project(
+    song_path: &Path,
+    controls_path: Option<&Path>,
+    scales: &[CustomScaleDefinition],
+    out: &Path,
+) -> Result<ProjectManifest> {
+    let mut song = SongFile::load(song_path)?;
+    let clip_files: Vec<String> =
+        song.tracks.iter().flat_map(|t| &t.clips).filter_map(|c| c.file.clone()).collect();
+
+    // Clips and other files are copied in; the soundfont stays where it is,
+    // referenced from the folder the project will sit in
+    let anchor = match out.parent() {
+        Some(parent) if is_bundle(out) && !parent.as_os_str().is_empty() => parent,
+        _ if is_bundle(out) => Path::new("."),
+        _ => out,
+    };
+    fs::create_dir_all(anchor).with_context(|| format!("Failed to create {:?}", anchor))?;
+    let anchor = fs::canonicalize(anchor)?;
+    let mut manifest = ProjectManifest::new(song.song.name.clone());
+    let mut paths: HashMap<String, String> = HashMap::new();
+    let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+    for asset in song.asset_paths() {
+        let source = resolve_path(song_path, asset);
+        if !source.is_file() {
+            return Err(anyhow!("Song refers to a missing file: {:?}", source));
+        }
+        if song.song.soundfont.as_deref() == Some(asset) {
+            let reference = relative_path(&anchor, &fs::canonicalize(&source)?).to_string_lossy().into_owned();
+            manifest.soundfonts.push(reference.clone());
+            paths.insert(asset.to_string(), reference);
+            continue;
+        }
+        let folder = if clip_files.iter().any(|c| c == asset) { PROJECT_CLIPS } else { BUNDLE_ASSETS };
+        let entry = unique_entry(folder, &source, files.iter().map(|(e, _)| e.as_str()));
+        let bytes = fs::read(&source).with_context(|| format!("Failed to read {:?}", source))?;
+        paths.insert(asset.to_string(), entry.clone());
+        files.push((entry, bytes));
+    }
+    song.map_asset_paths(|path| paths.get(path).cloned().unwrap_or_else(|| path.to_string()));
+    files.insert(0, (BUNDLE_SONG.to_string(), song.to_yaml()?.into_bytes()));
+
+    if let Some(controls_path) = controls_path {
+        ControlsFile::load(controls_path)?;
+        let bytes = fs::read(controls_path).with_context(|| format!("Failed to read {:?}", controls_path))?;
+        files.insert(1, (PROJECT_CONTROLS.to_string(), bytes));
+        manifest.controls = Some(PROJECT_CONTROLS.to_string());
+    }
+    manifest.scales = scales.to_vec();
+    manifest.files = files.iter().map(|(entry, _)| entry.clone()).collect();
+    let manifest_yaml = serde_yaml::to_string(&manifest).context("Failed to serialize project manifest")?;
+    files.push((PROJECT_MANIFEST.to_string(), manifest_yaml.into_bytes()));
+
+    if is_bundle(out) {
+        let file = File::create(out).with_context(|| format!("Failed to create project: {:?}", out))?;
+        let mut zip = ZipWriter::new(file);
+        for (entry, bytes) in &files {
+            zip.start_file(entry.as_str(), FileOptions::default())?;
+            zip.write_all(bytes)?;
+        }
+        zip.finish()?;
+    } else {
+        for (entry, bytes) in &files {
+            let target = out.join(entry);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, bytes).with_context(|| format!("Failed to write {:?}", target))?;
+        }
+    }
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A song in one folder with a clip beside it, a soundfont elsewhere
+    fn loose_song(dir: &Path) -> PathBuf {
+        let song_dir = dir.join("work");
+        fs::create_dir_all(song_dir.join("parts")).unwrap();
+        fs::create_dir_all(dir.join("sounds")).unwrap();
+        fs::write(song_dir.join("parts/bass.yaml"), "notes: []").unwrap();
+        fs::write(song_dir.join("patch.syx"), [0xF0u8, 0x7E, 0xF7]).unwrap();
+        fs::write(dir.join("sounds/gm.sf2"), [1u8, 2, 3]).unwrap();
+        fs::write(song_dir.join("controls.yaml"), "nudge_depth: 0.1\n").unwrap();
+        let song_path = song_dir.join("live.yaml");
+        fs::write(
+            &song_path,
+            r#"
+song:
+  name: "Night Set"
+  soundfont: "../sounds/gm.sf2"
+tracks:
+  - name: "Bass"
+    clips:
+      - file: "parts/bass.yaml"
+snapshots:
+  - sysex: ["patch.syx"]
+"#,
+        )
+        .unwrap();
+        song_path
+    }
+
+    #[test]
+    fn test_project_folder_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let song_path = loose_song(dir.path());
+        let scales = [CustomScaleDefinition {
+            name: "hirajoshi".to_string(),
+            intervals: vec![0, 2, 3, 7, 8],
+        }];
+        let out = dir.path().join("night");
+        let controls = song_path.with_file_name("controls.yaml");
+        let manifest = save_project(&song_path, Some(&controls), &scales, &out).unwrap();
+        assert_eq!(manifest.name, "Night Set");
+        assert_eq!(manifest.files, vec!["song.yaml", "controls.yaml", "clips/bass.yaml", "assets/patch.syx"]);
+        assert_eq!(manifest.soundfonts, vec!["../sounds/gm.sf2"]);
+        assert!(!out.join("assets/gm.sf2").exists());
+
+        // Clip paths come back absolute, wherever the project is opened from
+        let project = Project::open(&out).unwrap();
+        let clip = project.song.tracks[0].clips[0].file.clone().unwrap();
+        assert_eq!(PathBuf::from(&clip), out.join("clips/bass.yaml"));
+        assert_eq!(fs::read_to_string(clip).unwrap(), "notes: []");
+        assert_eq!(project.controls.unwrap().nudge_depth, 0.1);
+        assert!(Project::open(&out.join(PROJECT_MANIFEST)).unwrap().missing_files().is_empty());
+        let root = crate::music::Note::C;
+        assert!(Project::open(&out).unwrap().scale_registry().get_scale(root, "hirajoshi").is_some());
+
+        // The project and its sounds move together
+        let moved = dir.path().join("elsewhere");
+        fs::create_dir_all(&moved).unwrap();
+        fs::rename(&out, moved.join("night")).unwrap();
+        fs::rename(dir.path().join("sounds"), moved.join("sounds")).unwrap();
+        let project = Project::open(&moved.join("night")).unwrap();
+        assert!(project.missing_files().is_empty());
+        fs::remove_dir_all(moved.join("sounds")).unwrap();
+        assert_eq!(Project::open(&moved.join("night")).unwrap().missing_files().len(), 1);
+    }
+
+    #[test]
+    fn test_load_project_from_zip_or_song() {
+        let dir = tempfile::tempdir().unwrap();
+        let song_path = loose_song(dir.path());
+        let zip = dir.path().join("night.zip");
+        save_project(&song_path, None, &[], &zip).unwrap();
+
+        let project = SongFile::load_project(&zip).unwrap();
+        assert_eq!(project.song.song.name, "Night Set");
+        assert_eq!(project.manifest.soundfonts, vec!["sounds/gm.sf2"]);
+        assert!(project.missing_files().is_empty());
+        let clip = project.song.tracks[0].clips[0].file.clone().unwrap();
+        assert!(Path::new(&clip).is_absolute());
+        assert_eq!(fs::read_to_string(&clip).unwrap(), "notes: []");
+
+        // The unpacked files go when the project does
+        assert!(project.is_unpacked());
+        drop(project);
+        assert!(!Path::new(&clip).exists());
+
+        // A lone song file opens as a project of its own
+        let project = Project::open(&song_path).unwrap();
+        assert_eq!((project.manifest.name.as_str(), project.manifest.song.as_str()), ("live", "live.yaml"));
+        assert!(project.missing_files().is_empty());
+        assert!(Path::new(project.song.song.soundfont.as_deref().unwrap()).is_file());
+    }
+
+    #[test]
+    fn test_load_scales() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scales.yaml");
+        fs::write(&path, "- name: hirajoshi\n  intervals: [0, 2, 3, 7, 8]\n").unwrap();
+        assert_eq!(load_scales(&path).unwrap()[0].intervals, vec![0, 2, 3, 7, 8]);
+
+        fs::write(&path, "- name: wide\n  intervals: [0, 14]\n").unwrap();
+        assert!(load_scales(&path).is_err());
+    }
+}
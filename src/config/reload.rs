@@ -0,0 +1,442 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Applying song file edits to a running sequencer.
+//!
+//! When the watcher reloads the song, the new file is diffed against the
+//! one playing. Tempo, swing, key, per-track generator parameters, track
+//! transpose/swing/velocity and parts are changed in place without
+//! stopping; anything else (new tracks, channels, generator types, clips)
+//! is listed as needing a restart. The policy decides when the changes
+//! land: at once, on the next bar, or when the performer applies them.
+
+use crate::arrangement::PartManager;
+use crate::generators::{GeneratorContext, GeneratorRegistry};
+use crate::music::Key;
+use crate::sequencer::track::TrackManager;
+
+use super::{GeneratorValue, SongConfig, SongFile, TrackConfig};
+
+/// When a reloaded song takes effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReloadPolicy {
+    /// As soon as the file is reloaded
+    Immediate,
+    /// On the next bar line
+    NextBar,
+    /// When the performer applies it
+    Manual,
+}
+
+impl Default for ReloadPolicy {
+    fn default() -> Self {
+        ReloadPolicy::NextBar
+    }
+}
+
+impl ReloadPolicy {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().replace('-', "_").as_str() {
+            "immediate" | "now" => Some(ReloadPolicy::Immediate),
+            "next_bar" | "bar" | "quantized" => Some(ReloadPolicy::NextBar),
+            "manual" => Some(ReloadPolicy::Manual),
+            _ => None,
+        }
+    }
+}
+
+/// A change that can be made while playing
+#[derive(Debug, Clone, PartialEq)]
+pub enum SongChange {
+    /// New tempo in BPM
+    Tempo(f64),
+    /// New global swing
+    Swing(f64),
+    /// New key
+    Key(Key),
+    /// Generator parameter of a track
+    TrackParam { track: usize, name: String, value: f64 },
+    /// Track transpose
+    TrackTranspose { track: usize, transpose: i8 },
+    /// Track swing
+    TrackSwing { track: usize, swing: f64 },
+    /// Track velocity scale
+    TrackVelocityScale { track: usize, scale: f64 },
+    /// A part was added or edited
+    Part(String),
+    /// A part was removed
+    PartRemoved(String),
+}
+
+/// Differences between the playing song and a reloaded one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReloadPlan {
+    /// Changes applied in place
+    pub changes: Vec<SongChange>,
+    /// Edits that only take effect after a restart
+    pub restart: Vec<String>,
+}
+
+impl ReloadPlan {
+    /// Diff a reloaded song against the one playing
+    pub fn diff(old: &SongFile, new: &SongFile) -> Self {
+        let mut plan = Self::default();
+        plan.diff_song(&old.song, &new.song);
+
+        let same_tracks = old.tracks.len() == new.tracks.len()
+            && old.tracks.iter().zip(&new.tracks).all(|(a, b)| a.name == b.name);
+        if same_tracks {
+            let registry = GeneratorRegistry::with_builtins();
+            for (index, (a, b)) in old.tracks.iter().zip(&new.tracks).enumerate() {
+                plan.diff_track(index, a, b, &registry);
+            }
+        } else {
+            plan.restart.push("Tracks were added, removed or renamed".to_string());
+        }
+
+        let mut names: Vec<&String> = new.parts.keys().collect();
+        names.sort();
+        for name in names {
+            if old.parts.get(name) != new.parts.get(name) || !same_tracks {
+                plan.changes.push(SongChange::Part(name.clone()));
+            }
+        }
+        let mut removed: Vec<&String> = old.parts.keys().filter(|n| !new.parts.contains_key(*n)).collect();
+        removed.sort();
+        plan.changes.extend(removed.into_iter().map(|n| SongChange::PartRemoved(n.clone())));
+
+        if old.arp_presets != new.arp_presets || old.fx != new.fx {
+            plan.restart.push("Arp presets or FX changed".to_string());
+        }
+        if old.snapshots != new.snapshots || old.modulation != new.modulation {
+            plan.restart.push("Snapshots or modulation changed".to_string());
+        }
+        plan
+    }
+
+    fn diff_song(&mut self, old: &SongConfig, new: &SongConfig) {
+        if old.tempo != new.tempo {
+            self.changes.push(SongChange::Tempo(new.tempo));
+        }
+        if old.swing != new.swing {
+            self.changes.push(SongChange::Swing(new.swing));
+        }
+        if old.key != new.key || old.scale != new.scale {
+            match Key::parse(&new.key, &new.scale) {
+                Some(key) => self.changes.push(SongChange::Key(key)),
+                None => self.restart.push(format!("Key '{} {}' is not valid", new.key, new.scale)),
+            }
+        }
+        let rest = SongConfig {
+            name: old.name.clone(),
+            tempo: old.tempo,
+            swing: old.swing,
+            key: old.key.clone(),
+            scale: old.scale.clone(),
+            ..new.clone()
+        };
+        if rest != *old {
+            self.restart.push("Song settings changed".to_string());
+        }
+    }
+
+    fn diff_track(&mut self, track: usize, old: &TrackConfig, new: &TrackConfig, registry: &GeneratorRegistry) {
+        if old.generator != new.generator {
+            self.restart.push(format!("Track '{}' generator changed", new.name));
+        } else if let Some(generator) = new.generator.as_deref() {
+            let specs = registry.param_specs(generator);
+            let mut keys: Vec<&String> = old.config.params.keys().chain(new.config.params.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let (before, after) = (old.config.params.get(key), new.config.params.get(key));
+                if before == after {
+                    continue;
+                }
+                // A removed key goes back to the generator's default
+                let value = match after {
+                    Some(value) => numeric(value),
+                    None => specs.iter().find(|s| s.name == key.as_str()).map(|s| s.default),
+                };
+                match value {
+                    Some(value) => self.changes.push(SongChange::TrackParam { track, name: key.clone(), value }),
+                    None => self.restart.push(format!("Track '{}' setting '{}' changed", new.name, key)),
+                }
+            }
+        }
+        if old.transpose != new.transpose {
+            self.changes.push(SongChange::TrackTranspose { track, transpose: new.transpose });
+        }
+        if old.swing != new.swing {
+            let swing = new.swing.unwrap_or(0.0);
+            self.changes.push(SongChange::TrackSwing { track, swing });
+        }
+        if old.velocity_scale != new.velocity_scale {
+            self.changes.push(SongChange::TrackVelocityScale { track, scale: new.velocity_scale });
+        }
+        let rest = TrackConfig {
+            generator: old.generator.clone(),
+            config: old.config.clone(),
+            transpose: old.transpose,
+            swing: old.swing,
+            velocity_scale: old.velocity_scale,
+            ..new.clone()
+        };
+        if rest != *old {
+            self.restart.push(format!("Track '{}' settings changed", new.name));
+        }
+    }
+
+    /// Check if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.restart.is_empty()
+    }
+
+    /// Apply the changes to the engine. Tempo and key go to the generator
+    /// context; the caller moves the clock to `context.tempo`.
+    pub fn apply(
+        &self,
+        song: &SongFile,
+        tracks: &mut TrackManager,
+        parts: &mut PartManager,
+        context: &mut GeneratorContext,
+    ) {
+        for change in &self.changes {
+            match change {
+                SongChange::Tempo(tempo) => context.tempo = *tempo,
+                SongChange::Swing(swing) => context.swing = *swing,
+                SongChange::Key(key) => context.key = key.clone(),
+                SongChange::TrackParam { track, name, value } => {
+                    if let Some(generator) = tracks.track_mut(*track).and_then(|t| t.generator_mut()) {
+                        generator.set_param(name, *value);
+                    }
+                }
+                SongChange::TrackTranspose { track, transpose } => {
+                    if let Some(track) = tracks.track_mut(*track) {
+                        track.set_transpose(*transpose);
+                    }
+                }
+                SongChange::TrackSwing { track, swing } => {
+                    if let Some(track) = tracks.track_mut(*track) {
+                        track.set_swing(*swing);
+                    }
+                }
+                SongChange::TrackVelocityScale { track, scale } => {
+                    if let Some(track) = tracks.track_mut(*track) {
+                        track.set_velocity_scale(*scale);
+                    }
+                }
+                SongChange::Part(name) => {
+                    if let Some(part) = song.parts.get(name) {
+                        parts.add_part(part.to_part(name, &song.tracks));
+                    }
+                }
+                SongChange::PartRemoved(name) => {
+                    parts.remove_part(name);
+                }
+            }
+        }
+    }
+}
+
+/// A generator value as a number (booleans as 0/1)
+fn numeric(value: &GeneratorValue) -> Option<f64> {
+    match value {
+        GeneratorValue::Int(v) => Some(*v as f64),
+        GeneratorValue::Float(v) => Some(*v),
+        GeneratorValue::Bool(v) => Some(if *v { 1.0 } else { 0.0 }),
+        _ => None,
+    }
+}
+
+/// A reloaded song waiting to take effect
+#[derive(Debug, Clone)]
+struct PendingReload {
+    song: SongFile,
+    plan: ReloadPlan,
+    /// Tick the reload arrived on
+    received: u64,
+}
+
+/// Holds reloaded songs until the policy lets them in
+#[derive(Debug, Clone)]
+pub struct HotReload {
+    policy: ReloadPolicy,
+    /// The song playing now
+    running: SongFile,
+    /// Latest reload not yet applied
+    pending: Option<PendingReload>,
+    /// Set when the performer applies a manual reload
+    apply_requested: bool,
+}
+
+impl HotReload {
+    /// Start from the song playing
+    pub fn new(running: SongFile, policy: ReloadPolicy) -> Self {
+        Self {
+            policy,
+            running,
+            pending: None,
+            apply_requested: false,
+        }
+    }
+
+    /// Get the policy
+    pub fn policy(&self) -> ReloadPolicy {
+        self.policy
+    }
+
+    /// Set the policy
+    pub fn set_policy(&mut self, policy: ReloadPolicy) {
+        self.policy = policy;
+    }
+
+    /// Get the song playing
+    pub fn running(&self) -> &SongFile {
+        &self.running
+    }
+
+    /// Take in a reloaded song; replaces any reload still waiting.
+    /// Returns the plan (empty if nothing changed).
+    pub fn receive(&mut self, song: SongFile, tick: u64) -> &ReloadPlan {
+        let plan = ReloadPlan::diff(&self.running, &song);
+        self.apply_requested = false;
+        let pending = self.pending.insert(PendingReload { song, plan, received: tick });
+        &pending.plan
+    }
+
+    /// Plan of the reload waiting, if any
+    pub fn pending(&self) -> Option<&ReloadPlan> {
+        self.pending.as_ref().map(|p| &p.plan)
+    }
+
+    /// Let a waiting manual reload in on the next poll
+    pub fn apply(&mut self) -> bool {
+        self.apply_requested = self.pending.is_some();
+        self.apply_requested
+    }
+
+    /// Drop the waiting reload
+    pub fn discard(&mut self) {
+        self.pending = None;
+        self.apply_requested = false;
+    }
+
+    /// Called each tick: returns the reload once it is due, making its
+    /// song the running one
+    pub fn poll(&mut self, tick: u64, ticks_per_bar: u64) -> Option<(SongFile, ReloadPlan)> {
+        let pending = self.pending.as_ref()?;
+        let due = match self.policy {
+            ReloadPolicy::Immediate => true,
+            ReloadPolicy::NextBar => {
+                let bar = ticks_per_bar.max(1);
+                tick >= pending.received.div_ceil(bar) * bar
+            }
+            ReloadPolicy::Manual => self.apply_requested,
+        };
+        if !due {
+            return None;
+        }
+        let pending = self.pending.take()?;
+        self.apply_requested = false;
+        self.running = pending.song.clone();
+        Some((pending.song, pending.plan))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrangement::TrackClipState;
+    use crate::sequencer::track::TrackConfig as EngineTrack;
+
+    const SONG: &str = r#"
+song:
+  name: "Live"
+  tempo: 120
+
+tracks:
+  - name: "Lead"
+    channel: 1
+    generator: melody
+    config:
+      rest_probability: 0.2
+      base_octave: 4
+    clips:
+      - name: "Hook"
+        file: "hook.mid"
+
+parts:
+  verse:
+    tracks:
+      Lead: "active"
+"#;
+
+    #[test]
+    fn test_diff_reloaded_song() {
+        let old = SongFile::from_yaml(SONG).unwrap();
+        let edited = SONG
+            .replace("tempo: 120", "tempo: 126")
+            .replace("rest_probability: 0.2\n", "")
+            .replace("base_octave: 4", "base_octave: 5\n      pattern: \"up\"")
+            .replace("Lead: \"active\"", "Lead: \"Hook\"");
+        let new = SongFile::from_yaml(&edited).unwrap();
+
+        let plan = ReloadPlan::diff(&old, &new);
+        assert_eq!(
+            plan.changes,
+            vec![
+                SongChange::Tempo(126.0),
+                SongChange::TrackParam { track: 0, name: "base_octave".to_string(), value: 5.0 },
+                SongChange::TrackParam { track: 0, name: "rest_probability".to_string(), value: 0.15 },
+                SongChange::Part("verse".to_string()),
+            ]
+        );
+        assert_eq!(plan.restart, vec!["Track 'Lead' setting 'pattern' changed".to_string()]);
+
+        let mut channel = new.clone();
+        channel.tracks[0].channel = 2;
+        assert_eq!(ReloadPlan::diff(&new, &channel).restart, vec!["Track 'Lead' settings changed".to_string()]);
+        assert!(ReloadPlan::diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn test_reload_policies() {
+        let old = SongFile::from_yaml(SONG).unwrap();
+        let new = SongFile::from_yaml(&SONG.replace("base_octave: 4", "base_octave: 6")).unwrap();
+
+        // Quantized: waits for the next bar line
+        let mut reload = HotReload::new(old.clone(), ReloadPolicy::default());
+        assert_eq!(reload.receive(new.clone(), 130).changes.len(), 1);
+        assert!(reload.poll(191, 96).is_none());
+        let (song, plan) = reload.poll(192, 96).unwrap();
+        assert_eq!(reload.running(), &song);
+        assert!(reload.pending().is_none());
+
+        let mut tracks = TrackManager::new();
+        let lead = tracks.add_track(EngineTrack::default());
+        let melody = GeneratorRegistry::with_builtins().create("melody").unwrap();
+        tracks.track_mut(lead).unwrap().set_generator(melody);
+        let mut parts = PartManager::new(1);
+        let mut context = GeneratorContext::default();
+        plan.apply(&song, &mut tracks, &mut parts, &mut context);
+        let generator = tracks.track_mut(lead).unwrap().generator().unwrap();
+        assert_eq!(generator.get_param("base_octave"), Some(6.0));
+
+        // Manual: only when applied
+        let mut reload = HotReload::new(old.clone(), ReloadPolicy::Manual);
+        reload.receive(new.clone(), 0);
+        assert!(reload.poll(1000, 96).is_none());
+        assert!(reload.apply());
+        assert!(reload.poll(1001, 96).is_some());
+
+        // Immediate, and new parts resolve track and clip names
+        let mut reload = HotReload::new(old, ReloadPolicy::from_str("now").unwrap());
+        let hooked = SongFile::from_yaml(&SONG.replace("Lead: \"active\"", "Lead: \"Hook\"")).unwrap();
+        reload.receive(hooked, 50);
+        let (song, plan) = reload.poll(50, 96).unwrap();
+        plan.apply(&song, &mut tracks, &mut parts, &mut context);
+        assert_eq!(parts.get_part("verse").unwrap().track_state(lead), &TrackClipState::Clip(0));
+    }
+}
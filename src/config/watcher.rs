@@ -11,7 +11,7 @@ use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender};
 use std::time::{Duration, Instant};
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 
 use super::SongFile;
@@ -198,7 +198,9 @@ impl ConfigWatcher {
 
 /// Validate a configuration without applying it
 pub fn validate_config<P: AsRef<Path>>(path: P) -> Result<SongFile> {
-    SongFile::load(path)
+    let song = SongFile::load(path)?;
+    song.validate().context("Invalid song configuration")?;
+    Ok(song)
 }
 
 #[cfg(test)]
@@ -246,6 +248,13 @@ song:
             song: super::super::SongConfig::default(),
             tracks: Vec::new(),
             parts: std::collections::HashMap::new(),
+            arp_presets: std::collections::HashMap::new(),
+            fx: std::collections::HashMap::new(),
+            snapshots: Vec::new(),
+            modulation: Vec::new(),
+            scenes: Vec::new(),
+            grooves: std::collections::HashMap::new(),
+            energy: None,
         };
 
         let _reloaded = ConfigEvent::Reloaded(Box::new(song));
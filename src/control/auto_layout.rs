@@ -0,0 +1,148 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Automatic knob layout for the selected track's generator.
+//!
+//! A bank of knobs (usually eight CCs) is laid across the selected track's
+//! generator parameters, a bank's worth at a time with paging. Selecting
+//! another track re-lays the knobs, so any generator is hands-on without
+//! writing mappings. Explicit mappings on the same controls take priority.
+
+use super::midi_map::MidiBinding;
+use super::ControlAction;
+
+/// Knobs in a default bank
+pub const DEFAULT_KNOB_COUNT: usize = 8;
+
+/// Knob bank laid across generator parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoLayout {
+    /// Controls in knob order
+    knobs: Vec<MidiBinding>,
+    /// Track whose parameters are laid out
+    track: Option<usize>,
+    /// Parameter names of that track's generator, in display order
+    params: Vec<String>,
+    /// Current page
+    page: usize,
+}
+
+impl AutoLayout {
+    /// Create a layout over a bank of controls
+    pub fn new(knobs: Vec<MidiBinding>) -> Self {
+        Self {
+            knobs,
+            track: None,
+            params: Vec::new(),
+            page: 0,
+        }
+    }
+
+    /// Create a layout over consecutive CCs starting at `first_cc`
+    pub fn cc_bank(channel: Option<u8>, first_cc: u8, count: usize) -> Self {
+        let knobs = (0..count)
+            .map(|i| first_cc.saturating_add(i as u8).min(127))
+            .map(|cc| match channel {
+                Some(channel) => MidiBinding::cc(channel, cc),
+                None => MidiBinding::cc_any(cc),
+            })
+            .collect();
+        Self::new(knobs)
+    }
+
+    /// Lay the knobs across a track's parameters, starting at the first page.
+    /// Names are sorted so the layout is the same every time.
+    pub fn set_track(&mut self, track: usize, mut params: Vec<String>) {
+        params.sort();
+        self.track = Some(track);
+        self.params = params;
+        self.page = 0;
+    }
+
+    /// Clear the layout (no generator on the selected track)
+    pub fn clear(&mut self) {
+        self.track = None;
+        self.params.clear();
+        self.page = 0;
+    }
+
+    /// Get the laid-out track
+    pub fn track(&self) -> Option<usize> {
+        self.track
+    }
+
+    /// Get the current page
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Number of pages
+    pub fn page_count(&self) -> usize {
+        self.params.len().div_ceil(self.knobs.len().max(1)).max(1)
+    }
+
+    /// Move through pages (wrapping)
+    pub fn step_page(&mut self, delta: i32) {
+        let pages = self.page_count() as i64;
+        self.page = (self.page as i64 + delta as i64).rem_euclid(pages) as usize;
+    }
+
+    /// Parameters on the current page, in knob order (None = unused knob)
+    pub fn labels(&self) -> Vec<Option<&str>> {
+        let start = self.page * self.knobs.len();
+        (0..self.knobs.len())
+            .map(|i| self.params.get(start + i).map(String::as_str))
+            .collect()
+    }
+
+    /// Full parameter key for a knob on the current page
+    fn knob_param(&self, knob: usize) -> Option<String> {
+        let track = self.track?;
+        let name = self.params.get(self.page * self.knobs.len() + knob)?;
+        Some(format!("track{}.{}", track + 1, name))
+    }
+
+    /// Action for a control message, if it is one of the knobs and the knob
+    /// has a parameter on this page
+    pub fn process(&self, channel: u8, status: u8, data1: u8, data2: u8) -> Option<ControlAction> {
+        let knob = self.knobs.iter().position(|k| k.matches(channel, status, data1))?;
+        let param = self.knob_param(knob)?;
+        Some(ControlAction::SetParameter(param, data2 as f64 / 127.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(count: usize) -> Vec<String> {
+        (0..count).map(|i| format!("p{:02}", i)).collect()
+    }
+
+    #[test]
+    fn test_knob_pages() {
+        let mut layout = AutoLayout::cc_bank(None, 20, DEFAULT_KNOB_COUNT);
+        assert_eq!(layout.process(0, 0xB0, 20, 127), None);
+
+        layout.set_track(1, names(10));
+        assert_eq!(layout.page_count(), 2);
+        assert_eq!(
+            layout.process(3, 0xB0, 21, 127),
+            Some(ControlAction::SetParameter("track2.p01".to_string(), 1.0))
+        );
+
+        // The second page holds the last two parameters
+        layout.step_page(1);
+        assert_eq!(layout.labels()[..3], [Some("p08"), Some("p09"), None]);
+        assert_eq!(layout.process(0, 0xB0, 22, 64), None);
+        layout.step_page(1);
+        assert_eq!(layout.page(), 0);
+
+        // Selecting another track starts over on its first page
+        layout.step_page(1);
+        layout.set_track(0, vec!["rate".to_string(), "density".to_string()]);
+        assert_eq!(layout.page(), 0);
+        assert_eq!(layout.labels()[0], Some("density"));
+        assert_eq!(layout.process(0, 0xB0, 27, 64), None);
+    }
+}
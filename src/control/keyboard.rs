@@ -12,6 +12,9 @@ use crossterm::event::{KeyCode, KeyModifiers};
 
 use super::ControlAction;
 
+/// Number of tracks reachable from the number keys at once
+pub const TRACKS_PER_PAGE: usize = 8;
+
 /// A keyboard shortcut definition
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Shortcut {
@@ -102,6 +105,10 @@ pub struct KeyboardController {
     repeat_rate: u32,
     /// Currently held keys with repeat counters
     held_keys: HashMap<Shortcut, u32>,
+    /// Current track bank page
+    track_page: usize,
+    /// Number of tracks (limits paging)
+    track_count: usize,
 }
 
 impl KeyboardController {
@@ -112,6 +119,8 @@ impl KeyboardController {
             repeat_delay: 30, // ~500ms at 60fps
             repeat_rate: 6,   // ~100ms at 60fps
             held_keys: HashMap::new(),
+            track_page: 0,
+            track_count: TRACKS_PER_PAGE,
         }
     }
 
@@ -143,6 +152,18 @@ impl KeyboardController {
             "Toggle Record",
         ).category("Transport"));
 
+        self.add(KeyBinding::new(
+            Shortcut::shift(KeyCode::Char('R')),
+            ControlAction::ToggleAutomationRecord,
+            "Toggle Automation Record",
+        ).category("Transport"));
+
+        self.add(KeyBinding::new(
+            Shortcut::key(KeyCode::Char('m')),
+            ControlAction::ToggleMetronome,
+            "Toggle Metronome",
+        ).category("Transport"));
+
         self.add(KeyBinding::new(
             Shortcut::key(KeyCode::Enter),
             ControlAction::Play,
@@ -180,6 +201,30 @@ impl KeyboardController {
             "Tap Tempo",
         ).category("Tempo"));
 
+        self.add(KeyBinding::new(
+            Shortcut::alt(KeyCode::Up),
+            ControlAction::NudgeTempo(1.0),
+            "Nudge Faster (hold)",
+        ).category("Tempo"));
+
+        self.add(KeyBinding::new(
+            Shortcut::alt(KeyCode::Down),
+            ControlAction::NudgeTempo(-1.0),
+            "Nudge Slower (hold)",
+        ).category("Tempo"));
+
+        self.add(KeyBinding::new(
+            Shortcut::alt(KeyCode::Right),
+            ControlAction::ShiftPhase(1),
+            "Advance Phase",
+        ).category("Tempo"));
+
+        self.add(KeyBinding::new(
+            Shortcut::alt(KeyCode::Left),
+            ControlAction::ShiftPhase(-1),
+            "Retard Phase",
+        ).category("Tempo"));
+
         // Track mute (1-8)
         for i in 0..8 {
             let c = char::from_digit(i + 1, 10).unwrap();
@@ -200,6 +245,44 @@ impl KeyboardController {
             ).category("Tracks"));
         }
 
+        // Track pages
+        self.add(KeyBinding::new(
+            Shortcut::key(KeyCode::PageDown),
+            ControlAction::TrackPage(1),
+            "Next Track Page",
+        ).category("Tracks"));
+
+        self.add(KeyBinding::new(
+            Shortcut::key(KeyCode::PageUp),
+            ControlAction::TrackPage(-1),
+            "Previous Track Page",
+        ).category("Tracks"));
+
+        self.add(KeyBinding::new(
+            Shortcut::shift(KeyCode::PageDown),
+            ControlAction::TrackPage(1),
+            "Next Track Page",
+        ).category("Tracks"));
+
+        self.add(KeyBinding::new(
+            Shortcut::shift(KeyCode::PageUp),
+            ControlAction::TrackPage(-1),
+            "Previous Track Page",
+        ).category("Tracks"));
+
+        // Performance
+        self.add(KeyBinding::new(
+            Shortcut::key(KeyCode::Char('f')),
+            ControlAction::Fill,
+            "Fill Next Bar",
+        ).category("Performance"));
+
+        self.add(KeyBinding::new(
+            Shortcut::key(KeyCode::Char('n')),
+            ControlAction::ToggleNoteRepeat,
+            "Toggle Note Repeat",
+        ).category("Performance"));
+
         // Scene triggers (F1-F8)
         for i in 1..=8 {
             self.add(KeyBinding::new(
@@ -253,6 +336,12 @@ impl KeyboardController {
             "Toggle MIDI Learn",
         ).category("UI"));
 
+        self.add(KeyBinding::new(
+            Shortcut::key(KeyCode::Char('p')),
+            ControlAction::CyclePositionDisplay,
+            "Cycle Position Display",
+        ).category("UI"));
+
         self.add(KeyBinding::new(
             Shortcut::key(KeyCode::Char('q')),
             ControlAction::Quit,
@@ -289,15 +378,37 @@ impl KeyboardController {
         self.bindings.get(&shortcut).map(|b| &b.action)
     }
 
-    /// Process a key event and return the action
+    /// Process a key event and return the action (track actions follow the current page)
     pub fn process_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<ControlAction> {
         let shortcut = Shortcut::new(code, modifiers);
+        let action = self.bindings.get(&shortcut)?.action.clone();
 
-        if let Some(binding) = self.bindings.get(&shortcut) {
-            Some(binding.action.clone())
-        } else {
-            None
+        if let ControlAction::TrackPage(delta) = action {
+            self.shift_page(delta);
         }
+        Some(action.offset_track(self.track_page * TRACKS_PER_PAGE))
+    }
+
+    /// Get current track page
+    pub fn track_page(&self) -> usize {
+        self.track_page
+    }
+
+    /// Get number of track pages
+    pub fn page_count(&self) -> usize {
+        self.track_count.max(1).div_ceil(TRACKS_PER_PAGE)
+    }
+
+    /// Set number of tracks
+    pub fn set_track_count(&mut self, count: usize) {
+        self.track_count = count;
+        self.track_page = self.track_page.min(self.page_count() - 1);
+    }
+
+    /// Move the track page
+    pub fn shift_page(&mut self, delta: i32) {
+        let page = (self.track_page as i64 + delta as i64).max(0) as usize;
+        self.track_page = page.min(self.page_count() - 1);
     }
 
     /// Handle key press (for repeat handling)
@@ -307,9 +418,15 @@ impl KeyboardController {
     }
 
     /// Handle key release
-    pub fn key_up(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+    pub fn key_up(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<ControlAction> {
         let shortcut = Shortcut::new(code, modifiers);
         self.held_keys.remove(&shortcut);
+
+        // Releasing a nudge key returns to the base tempo
+        match self.bindings.get(&shortcut)?.action {
+            ControlAction::NudgeTempo(_) => Some(ControlAction::ReleaseNudge),
+            _ => None,
+        }
     }
 
     /// Update repeat timers and return actions for repeated keys
@@ -326,6 +443,7 @@ impl KeyboardController {
                         // Only repeat certain action types
                         match &binding.action {
                             ControlAction::AdjustTempo(_)
+                            | ControlAction::NudgeTempo(_)
                             | ControlAction::NavigateUp
                             | ControlAction::NavigateDown
                             | ControlAction::NavigateLeft
@@ -458,6 +576,14 @@ mod tests {
         // Up should adjust tempo
         let action = controller.get_action(KeyCode::Up, KeyModifiers::NONE);
         assert_eq!(action, Some(&ControlAction::AdjustTempo(1.0)));
+
+        // PageDown should page the track bank
+        let action = controller.get_action(KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(action, Some(&ControlAction::TrackPage(1)));
+
+        // f should request a fill
+        let action = controller.get_action(KeyCode::Char('f'), KeyModifiers::NONE);
+        assert_eq!(action, Some(&ControlAction::Fill));
     }
 
     #[test]
@@ -533,4 +659,36 @@ mod tests {
         let action = controller.process_key(KeyCode::Char('z'), KeyModifiers::NONE);
         assert_eq!(action, None);
     }
+
+    #[test]
+    fn test_nudge_release() {
+        let mut controller = KeyboardController::with_defaults();
+
+        let action = controller.process_key(KeyCode::Up, KeyModifiers::ALT);
+        assert_eq!(action, Some(ControlAction::NudgeTempo(1.0)));
+        assert_eq!(
+            controller.key_up(KeyCode::Up, KeyModifiers::ALT),
+            Some(ControlAction::ReleaseNudge)
+        );
+        assert_eq!(controller.key_up(KeyCode::Up, KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn test_track_paging() {
+        let mut controller = KeyboardController::with_defaults();
+        controller.set_track_count(20);
+
+        controller.process_key(KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(controller.track_page(), 1);
+        let action = controller.process_key(KeyCode::Char('3'), KeyModifiers::NONE);
+        assert_eq!(action, Some(ControlAction::ToggleMute(10)));
+
+        // Paging stops at the last bank
+        controller.process_key(KeyCode::PageDown, KeyModifiers::SHIFT);
+        controller.process_key(KeyCode::PageDown, KeyModifiers::SHIFT);
+        assert_eq!(controller.track_page(), 2);
+
+        controller.set_track_count(8);
+        assert_eq!(controller.track_page(), 0);
+    }
 }
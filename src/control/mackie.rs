@@ -0,0 +1,257 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Mackie Control protocol support (subset).
+//!
+//! Handles the parts of the Mackie Control Universal protocol that most
+//! DAW-style surfaces share: transport buttons, the jog wheel, a bank of
+//! eight faders with mute/solo/select buttons, and the LCD scribble strips.
+
+use super::midi_map::status;
+use super::ControlAction;
+
+/// Number of channel strips per bank
+pub const STRIPS: usize = 8;
+
+/// Characters per strip on the LCD
+const LCD_STRIP_WIDTH: usize = 7;
+
+/// SysEx header for Mackie Control (main unit)
+const SYSEX_HEADER: [u8; 5] = [0xF0, 0x00, 0x00, 0x66, 0x14];
+
+/// Mackie button note numbers
+pub mod buttons {
+    pub const REC_ARM: u8 = 0x00;
+    pub const SOLO: u8 = 0x08;
+    pub const MUTE: u8 = 0x10;
+    pub const SELECT: u8 = 0x18;
+    pub const BANK_LEFT: u8 = 0x2E;
+    pub const BANK_RIGHT: u8 = 0x2F;
+    pub const CHANNEL_LEFT: u8 = 0x30;
+    pub const CHANNEL_RIGHT: u8 = 0x31;
+    pub const CYCLE: u8 = 0x56;
+    pub const REWIND: u8 = 0x5B;
+    pub const FAST_FORWARD: u8 = 0x5C;
+    pub const STOP: u8 = 0x5D;
+    pub const PLAY: u8 = 0x5E;
+    pub const RECORD: u8 = 0x5F;
+}
+
+/// Jog wheel controller number
+pub const JOG_WHEEL_CC: u8 = 0x3C;
+
+/// Mackie Control surface state
+#[derive(Debug, Clone)]
+pub struct MackieControl {
+    /// First track shown on the surface
+    bank_offset: usize,
+    /// Number of tracks available
+    track_count: usize,
+}
+
+impl MackieControl {
+    /// Create a new surface for the given number of tracks
+    pub fn new(track_count: usize) -> Self {
+        Self {
+            bank_offset: 0,
+            track_count,
+        }
+    }
+
+    /// Get first track shown on the surface
+    pub fn bank_offset(&self) -> usize {
+        self.bank_offset
+    }
+
+    /// Set number of tracks available
+    pub fn set_track_count(&mut self, count: usize) {
+        self.track_count = count;
+        self.bank_offset = self.bank_offset.min(self.max_offset());
+    }
+
+    /// Shift the fader bank by a number of strips
+    pub fn shift_bank(&mut self, delta: i32) {
+        let offset = (self.bank_offset as i64 + delta as i64).max(0) as usize;
+        self.bank_offset = offset.min(self.max_offset());
+    }
+
+    fn max_offset(&self) -> usize {
+        self.track_count.saturating_sub(1) / STRIPS * STRIPS
+    }
+
+    /// Track index for a strip in the current bank
+    pub fn strip_track(&self, strip: usize) -> Option<usize> {
+        let track = self.bank_offset + strip;
+        if strip < STRIPS && track < self.track_count {
+            Some(track)
+        } else {
+            None
+        }
+    }
+
+    /// Translate an incoming message into an action (bank changes are handled internally)
+    pub fn process_message(&mut self, status_byte: u8, data1: u8, data2: u8) -> Option<ControlAction> {
+        let channel = (status_byte & 0x0F) as usize;
+
+        match status_byte & 0xF0 {
+            status::NOTE_ON if data2 > 0 => self.process_button(data1),
+            status::PITCH_BEND => {
+                let value = ((data2 as u16) << 7) | data1 as u16;
+                let track = self.strip_track(channel)?;
+                Some(ControlAction::SetTrackVolume(track, value as f64 / 16383.0))
+            }
+            status::CONTROL_CHANGE if data1 == JOG_WHEEL_CC => {
+                // Relative: 1-63 clockwise, 65-127 counter-clockwise
+                if data2 & 0x40 == 0 {
+                    Some(ControlAction::NavigateRight)
+                } else {
+                    Some(ControlAction::NavigateLeft)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn process_button(&mut self, note: u8) -> Option<ControlAction> {
+        let strip = (note & 0x07) as usize;
+
+        match note {
+            buttons::PLAY => Some(ControlAction::Play),
+            buttons::STOP => Some(ControlAction::Stop),
+            buttons::RECORD => Some(ControlAction::ToggleRecord),
+            buttons::BANK_LEFT => {
+                self.shift_bank(-(STRIPS as i32));
+                None
+            }
+            buttons::BANK_RIGHT => {
+                self.shift_bank(STRIPS as i32);
+                None
+            }
+            buttons::CHANNEL_LEFT => {
+                self.shift_bank(-1);
+                None
+            }
+            buttons::CHANNEL_RIGHT => {
+                self.shift_bank(1);
+                None
+            }
+            n if (buttons::SOLO..buttons::MUTE).contains(&n) => {
+                self.strip_track(strip).map(ControlAction::ToggleSolo)
+            }
+            n if (buttons::MUTE..buttons::SELECT).contains(&n) => {
+                self.strip_track(strip).map(ControlAction::ToggleMute)
+            }
+            n if (buttons::SELECT..buttons::SELECT + 8).contains(&n) => {
+                self.strip_track(strip).map(ControlAction::SelectTrack)
+            }
+            _ => None,
+        }
+    }
+
+    /// Build the LCD SysEx message showing track names for the current bank
+    pub fn lcd_track_names(&self, names: &[String]) -> Vec<u8> {
+        let mut text = String::new();
+        for strip in 0..STRIPS {
+            let name = self
+                .strip_track(strip)
+                .and_then(|track| names.get(track))
+                .map(|s| s.as_str())
+                .unwrap_or("");
+            let short: String = name.chars().filter(|c| c.is_ascii()).take(LCD_STRIP_WIDTH - 1).collect();
+            text.push_str(&format!("{:<width$}", short, width = LCD_STRIP_WIDTH));
+        }
+        Self::lcd_text(0, &text)
+    }
+
+    /// Build an LCD SysEx message writing text at a display offset (0-111)
+    pub fn lcd_text(offset: u8, text: &str) -> Vec<u8> {
+        let mut message = SYSEX_HEADER.to_vec();
+        message.push(0x12);
+        message.push(offset.min(111));
+        message.extend(text.bytes().filter(|b| *b < 0x80).take(112 - offset.min(111) as usize));
+        message.push(0xF7);
+        message
+    }
+
+    /// Build a fader position message for a strip (value 0.0-1.0)
+    pub fn fader_position(strip: usize, value: f64) -> [u8; 3] {
+        let raw = (value.clamp(0.0, 1.0) * 16383.0).round() as u16;
+        [
+            status::PITCH_BEND | (strip.min(STRIPS) as u8),
+            (raw & 0x7F) as u8,
+            (raw >> 7) as u8,
+        ]
+    }
+
+    /// Build a button LED message
+    pub fn button_led(note: u8, on: bool) -> [u8; 3] {
+        [status::NOTE_ON, note, if on { 0x7F } else { 0x00 }]
+    }
+}
+
+impl Default for MackieControl {
+    fn default() -> Self {
+        Self::new(STRIPS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transport_buttons() {
+        let mut mcu = MackieControl::new(8);
+        assert_eq!(mcu.process_message(0x90, buttons::PLAY, 127), Some(ControlAction::Play));
+        assert_eq!(mcu.process_message(0x90, buttons::STOP, 127), Some(ControlAction::Stop));
+        // Button release is ignored
+        assert_eq!(mcu.process_message(0x90, buttons::PLAY, 0), None);
+    }
+
+    #[test]
+    fn test_fader_bank() {
+        let mut mcu = MackieControl::new(12);
+
+        assert_eq!(
+            mcu.process_message(0xE2, 0x7F, 0x7F),
+            Some(ControlAction::SetTrackVolume(2, 1.0))
+        );
+
+        mcu.process_message(0x90, buttons::BANK_RIGHT, 127);
+        assert_eq!(mcu.bank_offset(), 8);
+        assert_eq!(
+            mcu.process_message(0x90, buttons::MUTE + 1, 127),
+            Some(ControlAction::ToggleMute(9))
+        );
+        // Strip past the last track does nothing
+        assert_eq!(mcu.process_message(0x90, buttons::SOLO + 5, 127), None);
+
+        mcu.process_message(0x90, buttons::BANK_RIGHT, 127);
+        assert_eq!(mcu.bank_offset(), 8);
+    }
+
+    #[test]
+    fn test_jog_wheel() {
+        let mut mcu = MackieControl::default();
+        assert_eq!(mcu.process_message(0xB0, JOG_WHEEL_CC, 1), Some(ControlAction::NavigateRight));
+        assert_eq!(mcu.process_message(0xB0, JOG_WHEEL_CC, 65), Some(ControlAction::NavigateLeft));
+    }
+
+    #[test]
+    fn test_lcd_track_names() {
+        let mcu = MackieControl::new(2);
+        let names = vec!["Drums".to_string(), "Bassline".to_string()];
+        let msg = mcu.lcd_track_names(&names);
+
+        assert_eq!(&msg[..7], &[0xF0, 0x00, 0x00, 0x66, 0x14, 0x12, 0x00]);
+        assert_eq!(&msg[7..21], b"Drums  Bassli ");
+        assert_eq!(msg.len(), 7 + STRIPS * 7 + 1);
+        assert_eq!(*msg.last().unwrap(), 0xF7);
+    }
+
+    #[test]
+    fn test_fader_position() {
+        assert_eq!(MackieControl::fader_position(1, 1.0), [0xE1, 0x7F, 0x7F]);
+        assert_eq!(MackieControl::fader_position(0, 0.0), [0xE0, 0x00, 0x00]);
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Named control macros.
+//!
+//! A macro is a list of control actions run in order from a single trigger,
+//! each step optionally delayed and/or quantized to the beat or bar. Macros
+//! are defined in the controls file and triggered through `RunMacro`.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use crate::config::MacroConfig;
+use crate::sequencer::{QuantizeMode, SequencerTiming};
+
+use super::ControlAction;
+
+/// A single macro step
+#[derive(Debug, Clone, PartialEq)]
+pub struct MacroStep {
+    /// Action to perform
+    pub action: ControlAction,
+    /// Delay after the previous step in beats
+    pub delay_beats: f64,
+    /// Quantization applied after the delay
+    pub quantize: QuantizeMode,
+}
+
+impl MacroStep {
+    /// Create an immediate step
+    pub fn new(action: ControlAction) -> Self {
+        Self {
+            action,
+            delay_beats: 0.0,
+            quantize: QuantizeMode::Immediate,
+        }
+    }
+
+    /// Builder: delay in beats
+    pub fn with_delay(mut self, beats: f64) -> Self {
+        self.delay_beats = beats.max(0.0);
+        self
+    }
+
+    /// Builder: quantization
+    pub fn with_quantize(mut self, quantize: QuantizeMode) -> Self {
+        self.quantize = quantize;
+        self
+    }
+}
+
+/// A named sequence of control actions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlMacro {
+    /// Macro name
+    name: String,
+    /// Steps in order
+    steps: Vec<MacroStep>,
+}
+
+impl ControlMacro {
+    /// Create an empty macro
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// Build a macro from its configuration
+    pub fn from_config(name: &str, config: &MacroConfig, track_names: &[String]) -> Result<Self> {
+        let mut control_macro = Self::new(name);
+        for step in &config.steps {
+            let action = ControlAction::from_spec(&step.action, step.target.as_deref(), step.value, track_names)
+                .ok_or_else(|| anyhow!("Unknown action '{}' in macro '{}'", step.action, name))?;
+            let quantize = match step.quantize.as_deref() {
+                Some(q) => QuantizeMode::from_str(q)
+                    .ok_or_else(|| anyhow!("Unknown quantize '{}' in macro '{}'", q, name))?,
+                None => QuantizeMode::Immediate,
+            };
+            control_macro.add_step(
+                MacroStep::new(action)
+                    .with_delay(step.delay)
+                    .with_quantize(quantize),
+            );
+        }
+        Ok(control_macro)
+    }
+
+    /// Get macro name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get steps
+    pub fn steps(&self) -> &[MacroStep] {
+        &self.steps
+    }
+
+    /// Add a step
+    pub fn add_step(&mut self, step: MacroStep) {
+        self.steps.push(step);
+    }
+
+    /// Builder: add a step
+    pub fn with_step(mut self, step: MacroStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+}
+
+/// Runs macros against the sequencer clock
+#[derive(Debug, Default)]
+pub struct MacroRunner {
+    /// Macros by name
+    macros: HashMap<String, ControlMacro>,
+    /// Scheduled actions: (tick, action), in trigger order
+    scheduled: Vec<(u64, ControlAction)>,
+}
+
+impl MacroRunner {
+    /// Create an empty runner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a macro (replacing one with the same name)
+    pub fn add_macro(&mut self, control_macro: ControlMacro) {
+        self.macros.insert(control_macro.name().to_string(), control_macro);
+    }
+
+    /// Get a macro by name
+    pub fn get_macro(&self, name: &str) -> Option<&ControlMacro> {
+        self.macros.get(name)
+    }
+
+    /// Number of macros
+    pub fn len(&self) -> usize {
+        self.macros.len()
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.macros.is_empty()
+    }
+
+    /// Check if any steps are waiting
+    pub fn is_running(&self) -> bool {
+        !self.scheduled.is_empty()
+    }
+
+    /// Schedule a macro's steps from the current position
+    pub fn trigger(&mut self, name: &str, timing: &SequencerTiming) -> bool {
+        let Some(control_macro) = self.macros.get(name) else {
+            return false;
+        };
+
+        let mut step_timing = timing.clone();
+        for step in &control_macro.steps {
+            step_timing.position_ticks +=
+                (step.delay_beats * step_timing.ticks_per_beat() as f64).round() as u64;
+            step_timing.position_ticks += step.quantize.ticks_until(&step_timing);
+            self.scheduled.push((step_timing.position_ticks, step.action.clone()));
+        }
+        true
+    }
+
+    /// Take actions that are due at the current tick
+    pub fn update(&mut self, current_tick: u64) -> Vec<ControlAction> {
+        let (mut due, waiting): (Vec<_>, Vec<_>) = self
+            .scheduled
+            .drain(..)
+            .partition(|(tick, _)| *tick <= current_tick);
+        self.scheduled = waiting;
+
+        due.sort_by_key(|(tick, _)| *tick);
+        due.into_iter().map(|(_, action)| action).collect()
+    }
+
+    /// Drop all scheduled steps
+    pub fn cancel_all(&mut self) {
+        self.scheduled.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ControlsFile;
+
+    fn breakdown() -> ControlMacro {
+        ControlMacro::new("breakdown")
+            .with_step(MacroStep::new(ControlAction::SetMute(0, true)))
+            .with_step(
+                MacroStep::new(ControlAction::TriggerPart("Breakdown".to_string()))
+                    .with_quantize(QuantizeMode::Bar),
+            )
+            .with_step(
+                MacroStep::new(ControlAction::SetParameter("macro1".to_string(), 0.0))
+                    .with_delay(2.0),
+            )
+    }
+
+    #[test]
+    fn test_macro_schedule() {
+        let mut runner = MacroRunner::new();
+        runner.add_macro(breakdown());
+
+        let timing = SequencerTiming {
+            position_ticks: 10,
+            ..Default::default()
+        };
+        assert!(runner.trigger("breakdown", &timing));
+        assert!(!runner.trigger("missing", &timing));
+
+        assert_eq!(runner.update(10), vec![ControlAction::SetMute(0, true)]);
+        assert!(runner.update(95).is_empty());
+        assert_eq!(
+            runner.update(96),
+            vec![ControlAction::TriggerPart("Breakdown".to_string())]
+        );
+        assert_eq!(
+            runner.update(144),
+            vec![ControlAction::SetParameter("macro1".to_string(), 0.0)]
+        );
+        assert!(!runner.is_running());
+    }
+
+    #[test]
+    fn test_macro_from_config() {
+        let yaml = r#"
+macros:
+  breakdown:
+    steps:
+      - action: mute
+        target: Drums
+      - action: trigger_part
+        target: Breakdown
+        quantize: bar
+      - action: set_param
+        target: macro1
+        value: 0
+        delay: 2
+"#;
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        let tracks = vec!["Bass".to_string(), "Drums".to_string()];
+        let parsed =
+            ControlMacro::from_config("breakdown", &controls.macros["breakdown"], &tracks).unwrap();
+        let steps = parsed.steps();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].action, ControlAction::SetMute(1, true));
+        assert_eq!(steps[1].quantize, QuantizeMode::Bar);
+        assert_eq!(
+            steps[2].action,
+            ControlAction::SetParameter("macro1".to_string(), 0.0)
+        );
+        assert_eq!(steps[2].delay_beats, 2.0);
+
+        let tracks = vec!["Bass".to_string()];
+        assert!(ControlMacro::from_config("breakdown", &controls.macros["breakdown"], &tracks).is_err());
+    }
+}
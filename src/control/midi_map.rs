@@ -4,12 +4,23 @@
 //! MIDI controller mapping with learn mode.
 //!
 //! Provides configurable MIDI bindings for notes, CCs, and program changes
-//! with support for relative encoders and multiple mapping layers.
+//! with support for relative encoders and multiple mapping layers. Layers
+//! can be named pages, switched from a mapped button, with an LED per page
+//! lit to show which is active. Chord bindings fire when an exact set of
+//! notes is held at once.
+//!
+//! Absolute CC mappings can use soft takeover: after a page switch or a
+//! preset change moves a parameter away from where the knob sits, the knob
+//! is ignored until it reaches (or crosses) the parameter's value.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
+use super::auto_layout::AutoLayout;
 use super::ControlAction;
 
+/// How close (normalized) a knob must come to a parameter to pick it up
+const PICKUP_TOLERANCE: f64 = 1.5 / 127.0;
+
 /// MIDI message status bytes
 pub mod status {
     pub const NOTE_OFF: u8 = 0x80;
@@ -201,6 +212,8 @@ pub struct MidiMappingEntry {
     pub description: String,
     /// Mapping layer (for switching between mapping sets)
     pub layer: u8,
+    /// Ignore the control until it picks up the parameter's value
+    pub soft_takeover: bool,
 }
 
 impl MidiMappingEntry {
@@ -213,6 +226,7 @@ impl MidiMappingEntry {
             sensitivity: 1.0,
             description: String::new(),
             layer: 0,
+            soft_takeover: false,
         }
     }
 
@@ -239,6 +253,94 @@ impl MidiMappingEntry {
         self.layer = layer;
         self
     }
+
+    /// Enable soft takeover
+    pub fn soft_takeover(mut self, enabled: bool) -> Self {
+        self.soft_takeover = enabled;
+        self
+    }
+
+    /// Check if soft takeover applies (absolute controls only)
+    fn uses_takeover(&self) -> bool {
+        self.soft_takeover
+            && self.encoder_mode == EncoderMode::Absolute
+            && takeover_target(&self.action).is_some()
+    }
+}
+
+/// Map a CC value to a gate scale (0.1 to 2.0)
+fn gate_scale(value: u8) -> f64 {
+    0.1 + value as f64 / 127.0 * 1.9
+}
+
+/// Parameter key and normalized value of an absolute value action
+fn takeover_target(action: &ControlAction) -> Option<(String, f64)> {
+    match action {
+        ControlAction::SetTrackVolume(track, value) => Some((format!("track{}.volume", track + 1), *value)),
+        other => other.automation_target(),
+    }
+}
+
+/// A chord binding: fires when exactly these notes are held together
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChordBinding {
+    /// MIDI channel (0-15, or None for any channel)
+    pub channel: Option<u8>,
+    /// Notes in the chord
+    pub notes: BTreeSet<u8>,
+    /// The action to perform
+    pub action: ControlAction,
+    /// Mapping layer
+    pub layer: u8,
+}
+
+impl ChordBinding {
+    /// Create a chord binding for any channel
+    pub fn new(notes: impl IntoIterator<Item = u8>, action: ControlAction) -> Self {
+        Self {
+            channel: None,
+            notes: notes.into_iter().map(|n| n.min(127)).collect(),
+            action,
+            layer: 0,
+        }
+    }
+
+    /// Set channel
+    pub fn channel(mut self, channel: u8) -> Self {
+        self.channel = Some(channel.min(15));
+        self
+    }
+
+    /// Set layer
+    pub fn layer(mut self, layer: u8) -> Self {
+        self.layer = layer;
+        self
+    }
+}
+
+/// A named mapping page (one layer of mappings)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappingPage {
+    /// Page name
+    pub name: String,
+    /// Note or CC lit while the page is active
+    pub led: Option<MidiBinding>,
+}
+
+impl MappingPage {
+    /// Create a page without an LED
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            led: None,
+        }
+    }
+
+    /// Set the LED binding
+    pub fn led(mut self, binding: MidiBinding) -> Self {
+        self.led = Some(binding);
+        self
+    }
 }
 
 /// Configuration for MIDI mappings (serializable)
@@ -274,6 +376,22 @@ pub struct MidiController {
     last_message: Option<(MidiBinding, u8)>,
     /// Configuration
     config: MidiMapConfig,
+    /// Chord bindings
+    chords: Vec<ChordBinding>,
+    /// Notes currently held, per channel
+    held: HashMap<u8, BTreeSet<u8>>,
+    /// Channels whose held chord already fired (until a note is released)
+    chord_fired: BTreeSet<u8>,
+    /// Named pages, one per layer
+    pages: Vec<MappingPage>,
+    /// Last known value of each parameter (normalized)
+    values: HashMap<String, f64>,
+    /// Last normalized position of each mapping's control
+    positions: HashMap<usize, f64>,
+    /// Mappings waiting to pick up, with the value they must reach
+    pickups: HashMap<usize, f64>,
+    /// Knobs laid across the selected track's generator parameters
+    auto_layout: Option<AutoLayout>,
 }
 
 impl MidiController {
@@ -285,6 +403,14 @@ impl MidiController {
             learn_mode: false,
             last_message: None,
             config: MidiMapConfig::default(),
+            chords: Vec::new(),
+            held: HashMap::new(),
+            chord_fired: BTreeSet::new(),
+            pages: Vec::new(),
+            values: HashMap::new(),
+            positions: HashMap::new(),
+            pickups: HashMap::new(),
+            auto_layout: None,
         }
     }
 
@@ -314,11 +440,142 @@ impl MidiController {
     /// Clear all mappings
     pub fn clear(&mut self) {
         self.mappings.clear();
+        self.chords.clear();
+        self.positions.clear();
+        self.pickups.clear();
+    }
+
+    /// Add a chord binding
+    pub fn add_chord(&mut self, chord: ChordBinding) {
+        self.chords.push(chord);
+    }
+
+    /// Get chord bindings
+    pub fn chords(&self) -> &[ChordBinding] {
+        &self.chords
     }
 
-    /// Set current layer
+    /// Track a note and return a chord action if the held notes now form a
+    /// bound chord. A chord fires once until one of its notes is released.
+    pub fn process_chord(&mut self, channel: u8, status: u8, note: u8, velocity: u8) -> Option<ControlAction> {
+        let msg_type = status & 0xF0;
+        let held = self.held.entry(channel).or_default();
+
+        if msg_type == status::NOTE_OFF || (msg_type == status::NOTE_ON && velocity == 0) {
+            held.remove(&note);
+            self.chord_fired.remove(&channel);
+            return None;
+        }
+        if msg_type != status::NOTE_ON {
+            return None;
+        }
+
+        held.insert(note);
+        if held.len() < 2 || self.chord_fired.contains(&channel) {
+            return None;
+        }
+
+        let held = &self.held[&channel];
+        let chord = self.chords.iter().find(|c| {
+            c.layer == self.current_layer
+                && c.channel.map(|ch| ch == channel).unwrap_or(true)
+                && &c.notes == held
+        })?;
+        self.chord_fired.insert(channel);
+        Some(chord.action.clone())
+    }
+
+    /// Set current layer. Soft takeover controls on the new layer must
+    /// pick up their parameters again.
     pub fn set_layer(&mut self, layer: u8) {
-        self.current_layer = layer % self.config.num_layers;
+        let layer = layer % self.config.num_layers;
+        if layer != self.current_layer {
+            self.current_layer = layer;
+            for index in 0..self.mappings.len() {
+                if self.mappings[index].layer == layer {
+                    self.arm_pickup(index);
+                }
+            }
+        }
+    }
+
+    /// Report a parameter's value as changed elsewhere (preset load,
+    /// automation, another controller). Soft takeover controls for it must
+    /// pick it up before they move it again.
+    pub fn sync_value(&mut self, action: &ControlAction) {
+        let Some((key, value)) = takeover_target(action) else {
+            return;
+        };
+        self.values.insert(key.clone(), value);
+        for index in 0..self.mappings.len() {
+            if takeover_target(&self.mappings[index].action).is_some_and(|(k, _)| k == key) {
+                self.arm_pickup(index);
+            }
+        }
+    }
+
+    /// Check if a mapping is waiting to pick up its parameter
+    pub fn is_waiting_pickup(&self, index: usize) -> bool {
+        self.pickups.contains_key(&index)
+    }
+
+    /// Number of mappings waiting to pick up
+    pub fn pending_pickups(&self) -> usize {
+        self.pickups.len()
+    }
+
+    /// Make a soft takeover mapping wait if its control is away from the value
+    fn arm_pickup(&mut self, index: usize) {
+        let entry = &self.mappings[index];
+        if !entry.uses_takeover() {
+            return;
+        }
+        let Some((key, _)) = takeover_target(&entry.action) else {
+            return;
+        };
+        let Some(&target) = self.values.get(&key) else {
+            return;
+        };
+        match self.positions.get(&index) {
+            Some(position) if (position - target).abs() <= PICKUP_TOLERANCE => {
+                self.pickups.remove(&index);
+            }
+            _ => {
+                self.pickups.insert(index, target);
+            }
+        }
+    }
+
+    /// Record a control's new position. Returns false while it has not yet
+    /// reached or crossed the value it must pick up.
+    fn take_over(&mut self, index: usize, value: f64) -> bool {
+        let previous = self.positions.insert(index, value);
+        let Some(&target) = self.pickups.get(&index) else {
+            return true;
+        };
+        let reached = (value - target).abs() <= PICKUP_TOLERANCE;
+        let crossed = previous.is_some_and(|p| (p < target) != (value < target));
+        if reached || crossed {
+            self.pickups.remove(&index);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Set the generator knob auto-layout
+    pub fn set_auto_layout(&mut self, layout: Option<AutoLayout>) {
+        self.auto_layout = layout;
+    }
+
+    /// Get the generator knob auto-layout
+    pub fn auto_layout(&self) -> Option<&AutoLayout> {
+        self.auto_layout.as_ref()
+    }
+
+    /// Get the mutable generator knob auto-layout
+    pub fn auto_layout_mut(&mut self) -> Option<&mut AutoLayout> {
+        self.auto_layout.as_mut()
     }
 
     /// Get current layer
@@ -326,6 +583,68 @@ impl MidiController {
         self.current_layer
     }
 
+    /// Add a page as the next layer
+    pub fn add_page(&mut self, page: MappingPage) {
+        self.pages.push(page);
+        self.config.num_layers = self.config.num_layers.max(self.pages.len().min(u8::MAX as usize) as u8);
+    }
+
+    /// Get pages
+    pub fn pages(&self) -> &[MappingPage] {
+        &self.pages
+    }
+
+    /// Find a page's layer by name
+    pub fn page_index(&self, name: &str) -> Option<u8> {
+        self.pages
+            .iter()
+            .position(|p| p.name.eq_ignore_ascii_case(name))
+            .map(|i| i as u8)
+    }
+
+    /// Name of the active page (if pages are named)
+    pub fn page_name(&self) -> Option<&str> {
+        self.pages.get(self.current_layer as usize).map(|p| p.name.as_str())
+    }
+
+    /// Switch pages for a page action. Returns true if the action was one.
+    pub fn apply_page_action(&mut self, action: &ControlAction) -> bool {
+        match *action {
+            ControlAction::SelectPage(page) => self.set_layer(page),
+            ControlAction::StepPage(delta) => {
+                let layers = self.config.num_layers.max(1) as i32;
+                let layer = (self.current_layer as i32 + delta).rem_euclid(layers);
+                self.set_layer(layer as u8);
+            }
+            ControlAction::StepKnobPage(delta) => {
+                if let Some(layout) = self.auto_layout.as_mut() {
+                    layout.step_page(delta);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Messages that light the active page's LED and darken the others
+    pub fn page_feedback(&self) -> Vec<Vec<u8>> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, page)| {
+                let led = page.led.as_ref()?;
+                let value = if i == self.current_layer as usize { 127 } else { 0 };
+                let channel = led.channel.unwrap_or(0);
+                let status = match led.binding_type {
+                    MidiBindingType::Note => status::NOTE_ON,
+                    MidiBindingType::ControlChange => status::CONTROL_CHANGE,
+                    _ => return None,
+                };
+                Some(vec![status | channel, led.data1, value])
+            })
+            .collect()
+    }
+
     /// Toggle learn mode
     pub fn toggle_learn(&mut self) {
         self.learn_mode = !self.learn_mode;
@@ -352,18 +671,15 @@ impl MidiController {
         data1: u8,
         data2: u8,
     ) -> Option<ControlAction> {
-        // Find matching mapping on current layer
-        for entry in &self.mappings {
-            if entry.layer != self.current_layer {
-                continue;
-            }
-
-            if entry.binding.matches(channel, status, data1) {
-                return Some(self.apply_mapping(entry, data2));
-            }
-        }
+        let index = self.find_mapping(channel, status, data1)?;
+        Some(self.apply_mapping(&self.mappings[index], data2))
+    }
 
-        None
+    /// Find the matching mapping on the current layer
+    fn find_mapping(&self, channel: u8, status: u8, data1: u8) -> Option<usize> {
+        self.mappings
+            .iter()
+            .position(|entry| entry.layer == self.current_layer && entry.binding.matches(channel, status, data1))
     }
 
     /// Process a MIDI message and update learn state
@@ -391,7 +707,30 @@ impl MidiController {
             return None; // In learn mode, don't trigger actions
         }
 
-        self.process_message(channel, status, data1, data2)
+        // A completed chord takes priority over the single note that completed it
+        if matches!(msg_type, status::NOTE_ON | status::NOTE_OFF) && !self.chords.is_empty() {
+            if let Some(action) = self.process_chord(channel, status, data1, data2) {
+                self.apply_page_action(&action);
+                return Some(action);
+            }
+        }
+
+        let Some(index) = self.find_mapping(channel, status, data1) else {
+            // Unmapped controls fall through to the generator auto-layout
+            return self.auto_layout.as_ref()?.process(channel, status, data1, data2);
+        };
+        let action = self.apply_mapping(&self.mappings[index], data2);
+
+        if let Some((key, value)) = takeover_target(&action) {
+            if self.mappings[index].uses_takeover() && !self.take_over(index, value) {
+                return None;
+            }
+            self.values.insert(key, value);
+        }
+
+        // Page buttons switch here; the action is still returned for display
+        self.apply_page_action(&action);
+        Some(action)
     }
 
     /// Apply a mapping to get the action with value
@@ -412,6 +751,15 @@ impl MidiController {
                 let vol = value as f64 / 127.0;
                 ControlAction::SetTrackVolume(*track, vol)
             }
+            ControlAction::SetTrackProbability(track, _) => {
+                ControlAction::SetTrackProbability(*track, value as f64 / 127.0)
+            }
+            // Gate scales span 0.1 to 2.0, with 1.0 just above the middle
+            ControlAction::SetTrackGate(track, _) => ControlAction::SetTrackGate(*track, gate_scale(value)),
+            ControlAction::SetGateScale(_) => ControlAction::SetGateScale(gate_scale(value)),
+            ControlAction::SetCrossfade(_) => ControlAction::SetCrossfade(value as f64 / 127.0),
+            ControlAction::SetEnergy(_) => ControlAction::SetEnergy(value as f64 / 127.0),
+            ControlAction::SetMetronomeVolume(_) => ControlAction::SetMetronomeVolume(value as f64 / 127.0),
             ControlAction::AdjustTempo(_) => {
                 let delta = match entry.encoder_mode {
                     EncoderMode::Absolute => (value as f64 - 64.0) / 64.0 * entry.sensitivity * 10.0,
@@ -568,6 +916,99 @@ mod tests {
         assert_eq!(action, Some(ControlAction::TriggerScene(1)));
     }
 
+    #[test]
+    fn test_mapping_pages() {
+        let mut controller = MidiController::new();
+        controller.add_page(MappingPage::new("Mixer").led(MidiBinding::note(0, 90)));
+        controller.add_page(MappingPage::new("Params").led(MidiBinding::note(0, 91)));
+        controller.add_mapping(MidiMappingEntry::new(MidiBinding::note(0, 98), ControlAction::StepPage(1)));
+        controller.add_mapping(MidiMappingEntry::new(MidiBinding::note(0, 98), ControlAction::StepPage(1)).layer(1));
+        controller.add_mapping(MidiMappingEntry::new(MidiBinding::cc(0, 20), ControlAction::SetTrackVolume(0, 0.0)));
+        controller.add_mapping(
+            MidiMappingEntry::new(MidiBinding::cc(0, 20), ControlAction::SetParameter("cutoff".to_string(), 0.0))
+                .layer(1),
+        );
+
+        assert_eq!(controller.page_name(), Some("Mixer"));
+        assert_eq!(controller.page_index("params"), Some(1));
+        assert_eq!(controller.page_feedback(), vec![vec![0x90, 90, 127], vec![0x90, 91, 0]]);
+
+        // The page button flips to Params, and the same knob now moves a parameter
+        controller.process_message_learn(0, status::NOTE_ON, 98, 127);
+        assert_eq!(controller.page_name(), Some("Params"));
+        assert_eq!(controller.page_feedback(), vec![vec![0x90, 90, 0], vec![0x90, 91, 127]]);
+        assert_eq!(
+            controller.process_message_learn(0, status::CONTROL_CHANGE, 20, 127),
+            Some(ControlAction::SetParameter("cutoff".to_string(), 1.0))
+        );
+
+        // Stepping wraps around
+        controller.process_message_learn(0, status::NOTE_ON, 98, 127);
+        assert_eq!(controller.current_layer(), 0);
+        assert!(controller.apply_page_action(&ControlAction::SelectPage(1)));
+        assert_eq!(controller.current_layer(), 1);
+        assert!(!controller.apply_page_action(&ControlAction::Play));
+    }
+
+    #[test]
+    fn test_auto_layout_fallthrough() {
+        let mut controller = MidiController::new();
+        controller.add_mapping(MidiMappingEntry::new(MidiBinding::cc(0, 20), ControlAction::SetTrackVolume(0, 0.0)));
+        let mut layout = AutoLayout::cc_bank(Some(0), 20, 2);
+        layout.set_track(0, vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        controller.set_auto_layout(Some(layout));
+
+        // The explicit mapping keeps its knob; the other knob follows the layout
+        assert_eq!(
+            controller.process_message_learn(0, status::CONTROL_CHANGE, 20, 127),
+            Some(ControlAction::SetTrackVolume(0, 1.0))
+        );
+        assert_eq!(
+            controller.process_message_learn(0, status::CONTROL_CHANGE, 21, 0),
+            Some(ControlAction::SetParameter("track1.b".to_string(), 0.0))
+        );
+
+        assert!(controller.apply_page_action(&ControlAction::StepKnobPage(1)));
+        assert_eq!(controller.process_message_learn(0, status::CONTROL_CHANGE, 21, 0), None);
+        assert_eq!(controller.auto_layout().unwrap().labels(), vec![Some("c"), None]);
+    }
+
+    #[test]
+    fn test_soft_takeover() {
+        let mut controller = MidiController::with_config(MidiMapConfig {
+            num_layers: 2,
+            ..Default::default()
+        });
+        let volume = |v: f64| ControlAction::SetTrackVolume(0, v);
+        controller.add_mapping(MidiMappingEntry::new(MidiBinding::cc(0, 7), volume(0.0)).soft_takeover(true));
+        controller.add_mapping(
+            MidiMappingEntry::new(MidiBinding::cc(0, 8), ControlAction::SetParameter("cutoff".to_string(), 0.0))
+                .soft_takeover(true),
+        );
+        controller.add_mapping(MidiMappingEntry::new(MidiBinding::cc(0, 7), volume(0.0)).layer(1));
+
+        // Knob at the bottom on layer 0
+        assert_eq!(controller.process_message_learn(0, status::CONTROL_CHANGE, 7, 0), Some(volume(0.0)));
+
+        // Layer 1 pushes the volume up with a knob that jumps freely
+        controller.set_layer(1);
+        controller.process_message_learn(0, status::CONTROL_CHANGE, 7, 127);
+        controller.set_layer(0);
+        assert!(controller.is_waiting_pickup(0));
+
+        // Below the value: ignored until the knob crosses it
+        assert_eq!(controller.process_message_learn(0, status::CONTROL_CHANGE, 7, 40), None);
+        assert_eq!(controller.process_message_learn(0, status::CONTROL_CHANGE, 7, 90), None);
+        assert_eq!(controller.process_message_learn(0, status::CONTROL_CHANGE, 7, 126), Some(volume(126.0 / 127.0)));
+        assert_eq!(controller.pending_pickups(), 0);
+
+        // A preset change does the same for a parameter
+        controller.process_message_learn(0, status::CONTROL_CHANGE, 8, 100);
+        controller.sync_value(&ControlAction::SetParameter("cutoff".to_string(), 0.25));
+        assert_eq!(controller.process_message_learn(0, status::CONTROL_CHANGE, 8, 90), None);
+        assert!(controller.process_message_learn(0, status::CONTROL_CHANGE, 8, 20).is_some());
+    }
+
     #[test]
     fn test_format_binding() {
         let binding = MidiBinding::note(0, 60);
@@ -580,6 +1021,43 @@ mod tests {
         assert_eq!(format_binding(&binding), "CC 1");
     }
 
+    #[test]
+    fn test_chord_trigger() {
+        let mut controller = MidiController::new();
+        controller.add_binding(MidiBinding::note(0, 72), ControlAction::TriggerScene(2));
+        controller.add_chord(ChordBinding::new(
+            [24, 28, 31],
+            ControlAction::TriggerPart("Chorus".to_string()),
+        ));
+
+        // Building up the triad fires once when complete
+        assert_eq!(controller.process_message_learn(0, status::NOTE_ON, 24, 100), None);
+        assert_eq!(controller.process_message_learn(0, status::NOTE_ON, 28, 100), None);
+        assert_eq!(
+            controller.process_message_learn(0, status::NOTE_ON, 31, 100),
+            Some(ControlAction::TriggerPart("Chorus".to_string()))
+        );
+
+        // Single notes still map individually
+        assert_eq!(
+            controller.process_message_learn(0, status::NOTE_ON, 72, 100),
+            Some(ControlAction::TriggerScene(2))
+        );
+
+        // Release and replay one note to fire again
+        controller.process_message_learn(0, status::NOTE_OFF, 72, 0);
+        controller.process_message_learn(0, status::NOTE_ON, 31, 0);
+        assert_eq!(
+            controller.process_message_learn(0, status::NOTE_ON, 31, 90),
+            Some(ControlAction::TriggerPart("Chorus".to_string()))
+        );
+
+        // Extra held notes are not the chord
+        controller.process_message_learn(0, status::NOTE_ON, 35, 90);
+        controller.process_message_learn(0, status::NOTE_OFF, 31, 0);
+        assert_eq!(controller.process_message_learn(0, status::NOTE_ON, 31, 90), None);
+    }
+
     #[test]
     fn test_learn_mode() {
         let mut controller = MidiController::new();
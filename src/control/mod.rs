@@ -6,18 +6,42 @@
 //! This module provides:
 //! - Keyboard shortcut handling
 //! - MIDI controller mapping with learn mode
+//! - Automatic knob layout for generator parameters
+//! - Mackie Control surface support
 //! - Parameter registry with smoothing
+//! - LFO, random-walk and envelope modulation of parameters
+//! - OSC remote control over UDP
+//! - Live-coding REPL
 
+pub mod auto_layout;
 pub mod keyboard;
+pub mod mackie;
+pub mod macros;
 pub mod midi_map;
+pub mod modulation;
+pub mod osc;
 pub mod params;
-
-pub use keyboard::{KeyBinding, KeyboardController, Shortcut};
-pub use midi_map::{MidiBinding, MidiController, MidiMapConfig};
+pub mod repl;
+
+pub use auto_layout::AutoLayout;
+pub use keyboard::{KeyBinding, KeyboardController, Shortcut, TRACKS_PER_PAGE};
+pub use mackie::MackieControl;
+pub use macros::{ControlMacro, MacroRunner, MacroStep};
+pub use midi_map::{ChordBinding, MappingPage, MidiBinding, MidiController, MidiMapConfig};
+pub use modulation::{LfoShape, ModMatrix, ModSource, Modulator};
+pub use osc::{OscArg, OscMapper, OscMessage, OscServer};
 pub use params::{Parameter, ParameterRegistry, ParameterValue};
+pub use repl::{Repl, ReplCommand, ReplOutput};
 
 use std::sync::{Arc, Mutex};
 
+use anyhow::{anyhow, Result};
+
+use crate::arrangement::DeckSide;
+use crate::config::{ControlMapping, ControlsFile};
+use crate::midi::MmcCommand;
+use crate::sequencer::gain::DEFAULT_MEASURE_BARS;
+
 /// Action that can be triggered by controls
 #[derive(Debug, Clone, PartialEq)]
 pub enum ControlAction {
@@ -35,14 +59,24 @@ pub enum ControlAction {
     Pause,
     /// Toggle record
     ToggleRecord,
+    /// Start (true) or stop (false) recording
+    SetRecord(bool),
+    /// Move the transport to a time in seconds (from MMC locate)
+    Locate(f64),
+    /// Arm/disarm automation recording of controller moves
+    ToggleAutomationRecord,
 
     // Tempo
     /// Set tempo to specific value
     SetTempo(f64),
     /// Adjust tempo by delta
     AdjustTempo(f64),
-    /// Nudge tempo temporarily
+    /// Nudge tempo temporarily (-1.0 to 1.0 of the nudge depth)
     NudgeTempo(f64),
+    /// Return from a temporary nudge to the base tempo
+    ReleaseNudge,
+    /// Shift phase by pulses without changing tempo (positive = advance)
+    ShiftPhase(i32),
     /// Tap tempo
     TapTempo,
 
@@ -51,10 +85,32 @@ pub enum ControlAction {
     ToggleMute(usize),
     /// Toggle track solo
     ToggleSolo(usize),
+    /// Mute or unmute a track
+    SetMute(usize, bool),
+    /// Solo or unsolo a track
+    SetSolo(usize, bool),
     /// Set track volume
     SetTrackVolume(usize, f64),
+    /// Set track play probability (0.0 to 1.0)
+    SetTrackProbability(usize, f64),
+    /// Set track note length multiplier (0.1 to 2.0)
+    SetTrackGate(usize, f64),
+    /// Set global note length multiplier (0.1 to 2.0)
+    SetGateScale(f64),
     /// Select track
     SelectTrack(usize),
+    /// Toggle latch mode for a track's input
+    ToggleLatch(usize),
+    /// Release all latched notes on a track
+    ClearLatch(usize),
+    /// Move the track bank by a number of pages
+    TrackPage(i32),
+    /// Switch the MIDI controller to a mapping page
+    SelectPage(u8),
+    /// Move through the MIDI controller's mapping pages (wrapping)
+    StepPage(i32),
+    /// Move through the generator knob auto-layout pages (wrapping)
+    StepKnobPage(i32),
 
     // Clip/Scene
     /// Trigger clip on track
@@ -65,18 +121,60 @@ pub enum ControlAction {
     TriggerScene(usize),
     /// Stop all clips
     StopAllClips,
+    /// Play a fill for the next bar on all generators
+    Fill,
+    /// Toggle note repeat for live input
+    ToggleNoteRepeat,
+    /// Click the swing grid on its own to audition the groove
+    ToggleGrooveAudition,
+    /// Turn the metronome click on or off
+    ToggleMetronome,
+    /// Set the metronome click level (0.0 to 1.0)
+    SetMetronomeVolume(f64),
+    /// Trigger a part by name
+    TriggerPart(String),
+    /// Set the set energy (0.0 to 1.0): tempo, layers and intensity
+    SetEnergy(f64),
+    /// Run a named macro from the controls file
+    RunMacro(String),
+    /// Launch a one-shot FX by name
+    LaunchFx(String),
+    /// Capture the last bars of a track's output into the phrase library
+    CapturePhrase(usize),
+    /// Drop a phrase (by library index) onto a track as a clip
+    DropPhrase(usize, usize),
+    /// Measure each track's average velocity over a number of bars
+    MeasureGain(u32),
+    /// Apply the velocity scales suggested by the last measurement
+    ApplyGain,
+    /// Seed the selected track's generator from another track's recent output
+    InspireFrom(usize),
+
+    // Decks
+    /// Move the crossfader (0.0 = deck A, 1.0 = deck B)
+    SetCrossfade(f64),
+    /// Start a stopped deck on the next bar, or stop a playing one
+    ToggleDeck(DeckSide),
 
     // Parameters
     /// Set parameter value
     SetParameter(String, f64),
     /// Adjust parameter by delta
     AdjustParameter(String, f64),
+    /// Randomize all unlocked parameters
+    RandomizeParams,
+    /// Nudge unlocked parameters by up to this normalized amount
+    MutateParams(f64),
+    /// Lock/unlock a parameter against randomize and mutate
+    ToggleParamLock(String),
 
     // UI
     /// Toggle help display
     ToggleHelp,
     /// Toggle MIDI learn mode
     ToggleLearn,
+    /// Switch the transport position readout to the next mode
+    CyclePositionDisplay,
     /// Quit application
     Quit,
 
@@ -115,6 +213,8 @@ impl ControlAction {
             ControlAction::SetTempo(_)
                 | ControlAction::AdjustTempo(_)
                 | ControlAction::NudgeTempo(_)
+                | ControlAction::ReleaseNudge
+                | ControlAction::ShiftPhase(_)
                 | ControlAction::TapTempo
         )
     }
@@ -125,10 +225,185 @@ impl ControlAction {
             self,
             ControlAction::ToggleMute(_)
                 | ControlAction::ToggleSolo(_)
+                | ControlAction::SetMute(_, _)
+                | ControlAction::SetSolo(_, _)
                 | ControlAction::SetTrackVolume(_, _)
+                | ControlAction::SetTrackProbability(_, _)
+                | ControlAction::SetTrackGate(_, _)
                 | ControlAction::SelectTrack(_)
+                | ControlAction::ToggleLatch(_)
+                | ControlAction::ClearLatch(_)
         )
     }
+
+    /// Shift the track index of a track action (used for track bank paging)
+    pub fn offset_track(&self, offset: usize) -> ControlAction {
+        match self.clone() {
+            ControlAction::ToggleMute(t) => ControlAction::ToggleMute(t + offset),
+            ControlAction::ToggleSolo(t) => ControlAction::ToggleSolo(t + offset),
+            ControlAction::SetMute(t, m) => ControlAction::SetMute(t + offset, m),
+            ControlAction::SetSolo(t, s) => ControlAction::SetSolo(t + offset, s),
+            ControlAction::SetTrackVolume(t, v) => ControlAction::SetTrackVolume(t + offset, v),
+            ControlAction::SetTrackProbability(t, p) => {
+                ControlAction::SetTrackProbability(t + offset, p)
+            }
+            ControlAction::SetTrackGate(t, g) => ControlAction::SetTrackGate(t + offset, g),
+            ControlAction::SelectTrack(t) => ControlAction::SelectTrack(t + offset),
+            ControlAction::ToggleLatch(t) => ControlAction::ToggleLatch(t + offset),
+            ControlAction::ClearLatch(t) => ControlAction::ClearLatch(t + offset),
+            ControlAction::TriggerClip(t, c) => ControlAction::TriggerClip(t + offset, c),
+            ControlAction::StopClip(t) => ControlAction::StopClip(t + offset),
+            ControlAction::CapturePhrase(t) => ControlAction::CapturePhrase(t + offset),
+            ControlAction::DropPhrase(p, t) => ControlAction::DropPhrase(p, t + offset),
+            ControlAction::InspireFrom(t) => ControlAction::InspireFrom(t + offset),
+            other => other,
+        }
+    }
+
+    /// Transport action for a received MMC command
+    pub fn from_mmc(command: &MmcCommand) -> Option<ControlAction> {
+        match command {
+            MmcCommand::Play | MmcCommand::DeferredPlay => Some(ControlAction::Play),
+            MmcCommand::Stop => Some(ControlAction::Stop),
+            MmcCommand::Pause => Some(ControlAction::Pause),
+            MmcCommand::RecordStrobe => Some(ControlAction::SetRecord(true)),
+            MmcCommand::RecordExit => Some(ControlAction::SetRecord(false)),
+            MmcCommand::Locate(time) => Some(ControlAction::Locate(time.to_seconds())),
+            MmcCommand::FastForward | MmcCommand::Rewind => None,
+        }
+    }
+
+    /// MMC command to send for a transport action
+    pub fn to_mmc(&self) -> Option<MmcCommand> {
+        match self {
+            ControlAction::Play => Some(MmcCommand::Play),
+            ControlAction::Stop => Some(MmcCommand::Stop),
+            ControlAction::Pause => Some(MmcCommand::Pause),
+            ControlAction::SetRecord(true) => Some(MmcCommand::RecordStrobe),
+            ControlAction::SetRecord(false) => Some(MmcCommand::RecordExit),
+            _ => None,
+        }
+    }
+
+    /// Automation lane target and value for a recordable action
+    pub fn automation_target(&self) -> Option<(String, f64)> {
+        match self {
+            ControlAction::SetParameter(name, value) => Some((name.clone(), *value)),
+            ControlAction::SetTrackProbability(track, value) => {
+                Some((format!("track{}.play_probability", track + 1), *value))
+            }
+            ControlAction::SetTrackGate(track, value) => Some((format!("track{}.gate", track + 1), *value)),
+            ControlAction::SetGateScale(value) => Some(("gate".to_string(), *value)),
+            ControlAction::SetCrossfade(value) => Some(("crossfade".to_string(), *value)),
+            ControlAction::SetEnergy(value) => Some(("energy".to_string(), *value)),
+            _ => None,
+        }
+    }
+
+    /// Action that replays an automation lane value (the inverse of
+    /// `automation_target`; mute and solo lanes switch at 0.5)
+    pub fn from_automation(target: &str, value: f64) -> ControlAction {
+        let track_field = target
+            .strip_prefix("track")
+            .and_then(|rest| rest.split_once('.'))
+            .and_then(|(number, field)| Some((number.parse::<usize>().ok()?.checked_sub(1)?, field)));
+        match track_field {
+            Some((track, "mute")) => ControlAction::SetMute(track, value >= 0.5),
+            Some((track, "solo")) => ControlAction::SetSolo(track, value >= 0.5),
+            Some((track, "play_probability")) => ControlAction::SetTrackProbability(track, value),
+            Some((track, "gate")) => ControlAction::SetTrackGate(track, value),
+            _ if target == "gate" => ControlAction::SetGateScale(value),
+            _ if target == "crossfade" => ControlAction::SetCrossfade(value),
+            _ if target == "energy" => ControlAction::SetEnergy(value),
+            _ => ControlAction::SetParameter(target.to_string(), value),
+        }
+    }
+
+    /// Build an action from a controls file spec.
+    ///
+    /// `action` may carry its target inline ("trigger_part:intro"). Track
+    /// targets are a track name or 1-based track number.
+    pub fn from_spec(
+        action: &str,
+        target: Option<&str>,
+        value: Option<f64>,
+        track_names: &[String],
+    ) -> Option<ControlAction> {
+        let (name, target) = match action.split_once(':') {
+            Some((name, inline)) => (name.trim(), Some(inline.trim())),
+            None => (action.trim(), target.map(str::trim)),
+        };
+
+        let track = || {
+            let target = target?;
+            match target.parse::<usize>() {
+                Ok(number) if number >= 1 => Some(number - 1),
+                _ => track_names.iter().position(|n| n.eq_ignore_ascii_case(target)),
+            }
+        };
+        let number = || target?.parse::<usize>().ok()?.checked_sub(1);
+
+        let action = match name.to_lowercase().as_str() {
+            "none" => ControlAction::None,
+            "toggle_play" => ControlAction::TogglePlay,
+            "play" => ControlAction::Play,
+            "stop" => ControlAction::Stop,
+            "pause" => ControlAction::Pause,
+            "toggle_record" => ControlAction::ToggleRecord,
+            "automation_record" => ControlAction::ToggleAutomationRecord,
+            "set_tempo" => ControlAction::SetTempo(value?),
+            "adjust_tempo" => ControlAction::AdjustTempo(value?),
+            "tap_tempo" => ControlAction::TapTempo,
+            "nudge_tempo" => ControlAction::NudgeTempo(value.unwrap_or(1.0)),
+            "release_nudge" => ControlAction::ReleaseNudge,
+            "shift_phase" => ControlAction::ShiftPhase(value.unwrap_or(1.0) as i32),
+            "toggle_mute" => ControlAction::ToggleMute(track()?),
+            "toggle_solo" => ControlAction::ToggleSolo(track()?),
+            "mute" => ControlAction::SetMute(track()?, true),
+            "unmute" => ControlAction::SetMute(track()?, false),
+            "solo" => ControlAction::SetSolo(track()?, true),
+            "unsolo" => ControlAction::SetSolo(track()?, false),
+            "select_track" => ControlAction::SelectTrack(track()?),
+            "track_probability" => ControlAction::SetTrackProbability(track()?, value.unwrap_or(1.0)),
+            "track_gate" => ControlAction::SetTrackGate(track()?, value.unwrap_or(1.0)),
+            "gate_scale" => ControlAction::SetGateScale(value.unwrap_or(1.0)),
+            "toggle_latch" => ControlAction::ToggleLatch(track()?),
+            "clear_latch" => ControlAction::ClearLatch(track()?),
+            "stop_clip" => ControlAction::StopClip(track()?),
+            "trigger_scene" => ControlAction::TriggerScene(number()?),
+            "stop_all" => ControlAction::StopAllClips,
+            "fill" => ControlAction::Fill,
+            "note_repeat" => ControlAction::ToggleNoteRepeat,
+            "groove_audition" => ControlAction::ToggleGrooveAudition,
+            "metronome" => ControlAction::ToggleMetronome,
+            "metronome_volume" => ControlAction::SetMetronomeVolume(value.unwrap_or(1.0)),
+            "page" => ControlAction::SelectPage(number()?.min(u8::MAX as usize) as u8),
+            "next_page" => ControlAction::StepPage(1),
+            "prev_page" => ControlAction::StepPage(-1),
+            "next_knob_page" => ControlAction::StepKnobPage(1),
+            "prev_knob_page" => ControlAction::StepKnobPage(-1),
+            "trigger_part" => ControlAction::TriggerPart(target?.to_string()),
+            "energy" => ControlAction::SetEnergy(value.unwrap_or(0.5).clamp(0.0, 1.0)),
+            "macro" => ControlAction::RunMacro(target?.to_string()),
+            "fx" => ControlAction::LaunchFx(target?.to_string()),
+            "capture_phrase" => ControlAction::CapturePhrase(track()?),
+            "measure_gain" => ControlAction::MeasureGain(
+                value.map_or(DEFAULT_MEASURE_BARS, |bars| bars.max(1.0) as u32),
+            ),
+            "apply_gain" => ControlAction::ApplyGain,
+            "inspire" => ControlAction::InspireFrom(track()?),
+            "position_display" => ControlAction::CyclePositionDisplay,
+            "crossfade" => ControlAction::SetCrossfade(value.unwrap_or(0.5)),
+            "toggle_deck" => ControlAction::ToggleDeck(DeckSide::from_str(target?)?),
+            "set_param" => ControlAction::SetParameter(target?.to_string(), value?),
+            "adjust_param" => ControlAction::AdjustParameter(target?.to_string(), value?),
+            "randomize" => ControlAction::RandomizeParams,
+            "mutate" => ControlAction::MutateParams(value.unwrap_or(0.1)),
+            "lock_param" => ControlAction::ToggleParamLock(target?.to_string()),
+            _ => return None,
+        };
+        Some(action)
+    }
 }
 
 /// Controller manager combining keyboard and MIDI
@@ -136,6 +411,8 @@ pub struct ControllerManager {
     keyboard: KeyboardController,
     midi: MidiController,
     params: Arc<Mutex<ParameterRegistry>>,
+    macros: MacroRunner,
+    osc: Option<OscServer>,
     learn_mode: bool,
     pending_learn: Option<String>,
 }
@@ -147,6 +424,8 @@ impl ControllerManager {
             keyboard: KeyboardController::with_defaults(),
             midi: MidiController::new(),
             params: Arc::new(Mutex::new(ParameterRegistry::new())),
+            macros: MacroRunner::new(),
+            osc: None,
             learn_mode: false,
             pending_learn: None,
         }
@@ -177,6 +456,141 @@ impl ControllerManager {
         Arc::clone(&self.params)
     }
 
+    /// Get macro runner
+    pub fn macros(&self) -> &MacroRunner {
+        &self.macros
+    }
+
+    /// Get mutable macro runner
+    pub fn macros_mut(&mut self) -> &mut MacroRunner {
+        &mut self.macros
+    }
+
+    /// Load macros from the controls file
+    pub fn load_macros(&mut self, controls: &ControlsFile, track_names: &[String]) -> Result<()> {
+        for (name, config) in &controls.macros {
+            self.macros.add_macro(ControlMacro::from_config(name, config, track_names)?);
+        }
+        Ok(())
+    }
+
+    /// Start the OSC server if the controls file enables one
+    pub fn load_osc(&mut self, controls: &ControlsFile) -> Result<()> {
+        self.osc = match &controls.osc {
+            Some(config) => Some(config.open_server()?),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Get the OSC server (if running)
+    pub fn osc(&self) -> Option<&OscServer> {
+        self.osc.as_ref()
+    }
+
+    /// Actions from OSC messages received since the last poll
+    pub fn poll_osc(&self, track_names: &[String]) -> Vec<ControlAction> {
+        self.osc.as_ref().map_or_else(Vec::new, |server| server.poll(track_names))
+    }
+
+    /// Load chord triggers (mappings with a `chord` note list) from the controls file
+    pub fn load_chords(&mut self, controls: &ControlsFile, track_names: &[String]) -> Result<()> {
+        for mapping in controls.mappings.iter().filter(|m| !m.chord.is_empty()) {
+            let action = self.mapping_action(mapping, None, track_names)?;
+            let mut chord = ChordBinding::new(mapping.chord.iter().copied(), action)
+                .layer(self.mapping_page(mapping)?);
+            if let Some(channel) = mapping.channel {
+                chord = chord.channel(channel.saturating_sub(1));
+            }
+            self.midi.add_chord(chord);
+        }
+        Ok(())
+    }
+
+    /// Load mapping pages from the controls file
+    pub fn load_pages(&mut self, controls: &ControlsFile) {
+        for config in &controls.pages {
+            let channel = config.channel.unwrap_or(1).saturating_sub(1);
+            let led = match (config.led_note, config.led_cc) {
+                (Some(note), _) => Some(MidiBinding::note(channel, note)),
+                (None, Some(cc)) => Some(MidiBinding::cc(channel, cc)),
+                (None, None) => None,
+            };
+            let mut page = MappingPage::new(&config.name);
+            page.led = led;
+            self.midi.add_page(page);
+        }
+    }
+
+    /// Set up the generator knob auto-layout from the controls file
+    pub fn load_auto_layout(&mut self, controls: &ControlsFile) {
+        let layout = controls.auto_layout.as_ref().map(|config| {
+            let channel = config.channel.map(|c| c.saturating_sub(1));
+            AutoLayout::cc_bank(channel, config.first_cc, config.knobs)
+        });
+        self.midi.set_auto_layout(layout);
+    }
+
+    /// Lay the auto-layout knobs across a newly selected track's generator
+    /// parameters (empty = no generator)
+    pub fn select_track_params(&mut self, track: usize, params: Vec<String>) {
+        if let Some(layout) = self.midi.auto_layout_mut() {
+            if params.is_empty() {
+                layout.clear();
+            } else {
+                layout.set_track(track, params);
+            }
+        }
+    }
+
+    /// Load note and CC mappings from the controls file (load pages first)
+    pub fn load_mappings(&mut self, controls: &ControlsFile, track_names: &[String]) -> Result<()> {
+        for mapping in controls.mappings.iter().filter(|m| m.chord.is_empty()) {
+            let channel = mapping.channel.map(|c| c.saturating_sub(1));
+            let (binding, value) = match (mapping.note, mapping.cc) {
+                (Some(note), _) => match channel {
+                    Some(channel) => (MidiBinding::note(channel, note), None),
+                    None => (MidiBinding::note_any(note), None),
+                },
+                (None, Some(cc)) => match channel {
+                    Some(channel) => (MidiBinding::cc(channel, cc), Some(0.0)),
+                    None => (MidiBinding::cc_any(cc), Some(0.0)),
+                },
+                (None, None) => continue,
+            };
+            let action = self.mapping_action(mapping, value, track_names)?;
+            let layer = self.mapping_page(mapping)?;
+            self.midi.add_mapping(
+                midi_map::MidiMappingEntry::new(binding, action)
+                    .layer(layer)
+                    .soft_takeover(mapping.soft_takeover),
+            );
+        }
+        Ok(())
+    }
+
+    /// Resolve a mapping's action, accepting page names as `page` targets
+    fn mapping_action(&self, mapping: &ControlMapping, value: Option<f64>, track_names: &[String]) -> Result<ControlAction> {
+        let page_target = match (mapping.action.as_str(), mapping.target.as_deref()) {
+            ("page", Some(name)) => self.midi.page_index(name).map(|i| (i + 1).to_string()),
+            _ => None,
+        };
+        let target = page_target.as_deref().or(mapping.target.as_deref());
+        ControlAction::from_spec(&mapping.action, target, value, track_names)
+            .ok_or_else(|| anyhow!("Unknown action '{}' in mapping", mapping.action))
+    }
+
+    /// Layer of the page a mapping belongs to (the first page if unset)
+    fn mapping_page(&self, mapping: &ControlMapping) -> Result<u8> {
+        match mapping.page.as_deref() {
+            Some(name) => self
+                .midi
+                .page_index(name)
+                .ok_or_else(|| anyhow!("Unknown page '{}' in mapping", name)),
+            None => Ok(0),
+        }
+    }
+
     /// Toggle learn mode
     pub fn toggle_learn(&mut self) {
         self.learn_mode = !self.learn_mode;
@@ -213,6 +627,27 @@ impl ControllerManager {
         self.midi.process_message(channel, status, data1, data2)
     }
 
+    /// Apply a parameter action to the registry. Returns true if handled.
+    pub fn apply_param_action(&self, action: &ControlAction) -> bool {
+        let Ok(mut params) = self.params.lock() else {
+            return false;
+        };
+        match action {
+            ControlAction::SetParameter(name, value) => params.set(name, *value),
+            ControlAction::AdjustParameter(name, delta) => params.adjust(name, *delta),
+            ControlAction::RandomizeParams => {
+                params.randomize(&mut rand::thread_rng());
+                true
+            }
+            ControlAction::MutateParams(amount) => {
+                params.mutate(&mut rand::thread_rng(), *amount);
+                true
+            }
+            ControlAction::ToggleParamLock(name) => params.toggle_lock(name).is_some(),
+            _ => false,
+        }
+    }
+
     /// Update parameter smoothing (call each frame)
     pub fn update(&mut self, delta_time: f64) {
         if let Ok(mut params) = self.params.lock() {
@@ -246,6 +681,172 @@ mod tests {
         assert!(!ControlAction::Stop.is_track());
     }
 
+    #[test]
+    fn test_offset_track() {
+        assert_eq!(ControlAction::ToggleMute(2).offset_track(8), ControlAction::ToggleMute(10));
+        assert_eq!(
+            ControlAction::TriggerClip(1, 3).offset_track(16),
+            ControlAction::TriggerClip(17, 3)
+        );
+        assert_eq!(ControlAction::Stop.offset_track(8), ControlAction::Stop);
+    }
+
+    #[test]
+    fn test_apply_param_action() {
+        let manager = ControllerManager::new();
+        {
+            let params = manager.params();
+            let mut params = params.lock().unwrap();
+            params.register(Parameter::new("base_octave", 1.0, 7.0, 4.0));
+        }
+
+        assert!(manager.apply_param_action(&ControlAction::ToggleParamLock("base_octave".to_string())));
+        assert!(manager.apply_param_action(&ControlAction::RandomizeParams));
+        assert_eq!(manager.params().lock().unwrap().value("base_octave"), Some(4.0));
+        assert!(!manager.apply_param_action(&ControlAction::Stop));
+    }
+
+    #[test]
+    fn test_load_chords() {
+        let yaml = r#"
+mappings:
+  - chord: [24, 28, 31]
+    action: trigger_part
+    target: Chorus
+  - note: 36
+    action: trigger_part
+    target: Intro
+"#;
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        let mut manager = ControllerManager::new();
+        manager.load_chords(&controls, &[]).unwrap();
+
+        let chords = manager.midi().chords();
+        assert_eq!(chords.len(), 1);
+        assert_eq!(chords[0].notes.len(), 3);
+        assert_eq!(chords[0].action, ControlAction::TriggerPart("Chorus".to_string()));
+    }
+
+    #[test]
+    fn test_load_pages() {
+        let yaml = r#"
+pages:
+  - name: Mixer
+    led_note: 90
+  - name: Params
+    led_note: 91
+mappings:
+  - note: 98
+    action: next_page
+  - note: 98
+    action: next_page
+    page: Params
+  - note: 99
+    action: page
+    target: Params
+  - cc: 20
+    action: set_param
+    target: cutoff
+    page: Params
+    soft_takeover: true
+"#;
+        let controls = ControlsFile::from_yaml(yaml).unwrap();
+        let mut manager = ControllerManager::new();
+        manager.load_pages(&controls);
+        manager.load_mappings(&controls, &[]).unwrap();
+
+        let midi = manager.midi();
+        assert_eq!(midi.pages().len(), 2);
+        assert_eq!(midi.mappings().len(), 4);
+        assert_eq!(midi.mappings()[2].action, ControlAction::SelectPage(1));
+        assert_eq!(midi.mappings()[3].layer, 1);
+        assert!(midi.mappings()[3].soft_takeover);
+
+        let bad = ControlsFile::from_yaml("mappings:\n  - note: 1\n    action: play\n    page: Nope\n").unwrap();
+        assert!(manager.load_mappings(&bad, &[]).is_err());
+    }
+
+    #[test]
+    fn test_mmc_actions() {
+        use crate::midi::MmcTime;
+
+        let play = MmcCommand::parse(&[0xF0, 0x7F, 0x7F, 0x06, 0x02, 0xF7], 0x7F).unwrap();
+        assert_eq!(ControlAction::from_mmc(&play), Some(ControlAction::Play));
+        assert_eq!(
+            ControlAction::from_mmc(&MmcCommand::RecordExit),
+            Some(ControlAction::SetRecord(false))
+        );
+        let locate = MmcCommand::Locate(MmcTime::from_seconds(90.0, 25));
+        assert_eq!(ControlAction::from_mmc(&locate), Some(ControlAction::Locate(90.0)));
+
+        assert_eq!(ControlAction::Stop.to_mmc(), Some(MmcCommand::Stop));
+        assert_eq!(ControlAction::TogglePlay.to_mmc(), None);
+    }
+
+    #[test]
+    fn test_action_from_spec() {
+        let tracks = vec!["Drums".to_string(), "Bass".to_string()];
+        assert_eq!(
+            ControlAction::from_spec("mute", Some("drums"), None, &tracks),
+            Some(ControlAction::SetMute(0, true))
+        );
+        assert_eq!(
+            ControlAction::from_spec("unsolo", Some("bass"), None, &tracks),
+            Some(ControlAction::SetSolo(1, false))
+        );
+        assert_eq!(
+            ControlAction::from_spec("toggle_solo", Some("2"), None, &tracks),
+            Some(ControlAction::ToggleSolo(1))
+        );
+        assert_eq!(
+            ControlAction::from_spec("track_gate", Some("bass"), Some(0.5), &tracks),
+            Some(ControlAction::SetTrackGate(1, 0.5))
+        );
+        assert_eq!(
+            ControlAction::from_spec("trigger_part:intro", None, None, &tracks),
+            Some(ControlAction::TriggerPart("intro".to_string()))
+        );
+        assert_eq!(
+            ControlAction::from_spec("fx:riser", None, None, &tracks),
+            Some(ControlAction::LaunchFx("riser".to_string()))
+        );
+        assert_eq!(
+            ControlAction::from_spec("capture_phrase", Some("bass"), None, &tracks),
+            Some(ControlAction::CapturePhrase(1))
+        );
+        assert_eq!(
+            ControlAction::from_spec("inspire:drums", None, None, &tracks),
+            Some(ControlAction::InspireFrom(0))
+        );
+        assert_eq!(
+            ControlAction::from_spec("measure_gain", None, None, &tracks),
+            Some(ControlAction::MeasureGain(DEFAULT_MEASURE_BARS))
+        );
+        assert_eq!(
+            ControlAction::from_spec("toggle_deck:b", None, None, &tracks),
+            Some(ControlAction::ToggleDeck(DeckSide::B))
+        );
+        assert_eq!(
+            ControlAction::from_automation("crossfade", 0.25),
+            ControlAction::SetCrossfade(0.25)
+        );
+        assert_eq!(
+            ControlAction::from_spec("energy", None, Some(1.5), &tracks),
+            Some(ControlAction::SetEnergy(1.0))
+        );
+        assert_eq!(ControlAction::from_automation("energy", 0.7), ControlAction::SetEnergy(0.7));
+        assert_eq!(
+            ControlAction::from_spec("metronome_volume", None, Some(0.4), &tracks),
+            Some(ControlAction::SetMetronomeVolume(0.4))
+        );
+        assert_eq!(
+            ControlAction::from_spec("set_param", Some("macro1"), Some(0.0), &tracks),
+            Some(ControlAction::SetParameter("macro1".to_string(), 0.0))
+        );
+        assert_eq!(ControlAction::from_spec("mute", Some("Keys"), None, &tracks), None);
+        assert_eq!(ControlAction::from_spec("explode", None, None, &tracks), None);
+    }
+
     #[test]
     fn test_controller_manager() {
         let mut manager = ControllerManager::new();
@@ -0,0 +1,309 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Modulation sources for parameters.
+//!
+//! LFOs, random walks and envelopes move registered parameters over time.
+//! Each modulator is bound to one parameter by name and moves it around its
+//! set value by a depth (a fraction of the parameter's range). The tick loop
+//! calls `ModMatrix::update` with the song position, so modulation follows
+//! the tempo and lines up with the bar. Turning the parameter by hand moves
+//! the centre the modulation swings around.
+
+use std::f64::consts::TAU;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::params::{Parameter, ParameterRegistry};
+
+/// LFO waveform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LfoShape {
+    /// Smooth sine
+    Sine,
+    /// Linear up and down
+    Triangle,
+    /// Ramp up, then drop
+    Saw,
+    /// Alternating high and low
+    Square,
+}
+
+impl Default for LfoShape {
+    fn default() -> Self {
+        LfoShape::Sine
+    }
+}
+
+impl LfoShape {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "sine" | "sin" => Some(LfoShape::Sine),
+            "triangle" | "tri" => Some(LfoShape::Triangle),
+            "saw" | "ramp" => Some(LfoShape::Saw),
+            "square" | "pulse" => Some(LfoShape::Square),
+            _ => None,
+        }
+    }
+
+    /// Wave value (-1.0 to 1.0) at a phase (0.0 to 1.0)
+    pub fn value(&self, phase: f64) -> f64 {
+        match self {
+            LfoShape::Sine => (phase * TAU).sin(),
+            LfoShape::Triangle => 4.0 * ((phase - 0.25).rem_euclid(1.0) - 0.5).abs() - 1.0,
+            LfoShape::Saw => 2.0 * phase - 1.0,
+            LfoShape::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+}
+
+/// Where a modulator's movement comes from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModSource {
+    /// Periodic wave over a period in beats, offset by a phase (0.0-1.0)
+    Lfo { shape: LfoShape, beats: f64, phase: f64 },
+    /// Wanders by up to `step` every `beats` beats, staying within -1 to 1
+    RandomWalk { beats: f64, step: f64 },
+    /// Rises over `attack` beats, then falls to `sustain` over `decay`
+    /// beats; restarts on each trigger
+    Envelope { attack: f64, decay: f64, sustain: f64 },
+}
+
+/// A modulation source bound to a parameter
+#[derive(Debug, Clone)]
+pub struct Modulator {
+    /// Parameter name
+    target: String,
+    /// Movement source
+    source: ModSource,
+    /// Largest move, as a fraction of the parameter range
+    depth: f64,
+    /// Random source for the walk
+    rng: StdRng,
+    /// Current walk position (-1.0 to 1.0)
+    walk: f64,
+    /// Last walk step taken
+    walk_step: Option<u64>,
+    /// Tick the envelope was last triggered
+    triggered_at: Option<u64>,
+    /// Normalized value the modulation moves around
+    base: Option<f64>,
+    /// Normalized value last written to the parameter
+    written: Option<f64>,
+}
+
+impl Modulator {
+    /// Create a modulator at half depth
+    pub fn new(target: impl Into<String>, source: ModSource) -> Self {
+        Self {
+            target: target.into(),
+            source,
+            depth: 0.5,
+            rng: StdRng::from_entropy(),
+            walk: 0.0,
+            walk_step: None,
+            triggered_at: None,
+            base: None,
+            written: None,
+        }
+    }
+
+    /// Set depth (0.0 to 1.0 of the parameter range)
+    pub fn with_depth(mut self, depth: f64) -> Self {
+        self.depth = depth.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Seed the random walk
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Get the parameter name
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Get the source
+    pub fn source(&self) -> &ModSource {
+        &self.source
+    }
+
+    /// Get depth
+    pub fn depth(&self) -> f64 {
+        self.depth
+    }
+
+    /// Restart the envelope at a tick
+    pub fn trigger(&mut self, tick: u64) {
+        self.triggered_at = Some(tick);
+    }
+
+    /// Source output at a tick: -1.0 to 1.0 (envelopes 0.0 to 1.0)
+    pub fn value(&mut self, tick: u64, ppqn: u32) -> f64 {
+        let beats = tick as f64 / ppqn.max(1) as f64;
+        match self.source {
+            ModSource::Lfo { shape, beats: period, phase } => {
+                shape.value((beats / period.max(0.01) + phase).rem_euclid(1.0))
+            }
+            ModSource::RandomWalk { beats: period, step } => {
+                let index = (beats / period.max(0.01)) as u64;
+                let mut last = self.walk_step.filter(|&last| last <= index).unwrap_or(index);
+                while last < index {
+                    self.walk = (self.walk + self.rng.gen_range(-step..=step)).clamp(-1.0, 1.0);
+                    last += 1;
+                }
+                self.walk_step = Some(index);
+                self.walk
+            }
+            ModSource::Envelope { attack, decay, sustain } => {
+                let Some(start) = self.triggered_at.filter(|&start| start <= tick) else {
+                    return 0.0;
+                };
+                let elapsed = (tick - start) as f64 / ppqn.max(1) as f64;
+                if elapsed < attack {
+                    elapsed / attack
+                } else if elapsed < attack + decay {
+                    1.0 - (1.0 - sustain) * (elapsed - attack) / decay
+                } else {
+                    sustain
+                }
+            }
+        }
+    }
+
+    /// Move the parameter for a tick. Returns false if it isn't registered.
+    fn apply(&mut self, registry: &mut ParameterRegistry, tick: u64, ppqn: u32) -> bool {
+        let value = self.value(tick, ppqn);
+        let Some(param) = registry.get_mut(&self.target) else {
+            return false;
+        };
+        let set = normalized_target(param);
+        // A value changed by hand becomes the new centre
+        if self.written != Some(set) {
+            self.base = Some(set);
+        }
+        let base = self.base.unwrap_or(set);
+        param.set_normalized(base + value * self.depth);
+        self.written = Some(normalized_target(param));
+        true
+    }
+}
+
+/// A parameter's set value (before smoothing), normalized
+fn normalized_target(param: &Parameter) -> f64 {
+    let range = param.max - param.min;
+    if range == 0.0 {
+        0.0
+    } else {
+        (param.value.target() - param.min) / range
+    }
+}
+
+/// The modulators of a song
+#[derive(Debug, Clone, Default)]
+pub struct ModMatrix {
+    modulators: Vec<Modulator>,
+}
+
+impl ModMatrix {
+    /// Create an empty matrix
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a modulator
+    pub fn add(&mut self, modulator: Modulator) {
+        self.modulators.push(modulator);
+    }
+
+    /// Get the modulators
+    pub fn modulators(&self) -> &[Modulator] {
+        &self.modulators
+    }
+
+    /// Number of modulators
+    pub fn len(&self) -> usize {
+        self.modulators.len()
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.modulators.is_empty()
+    }
+
+    /// Restart every envelope (e.g. on a part change)
+    pub fn trigger(&mut self, tick: u64) {
+        for modulator in &mut self.modulators {
+            modulator.trigger(tick);
+        }
+    }
+
+    /// Move the bound parameters to the song position. Returns the names
+    /// of modulated parameters that aren't registered.
+    pub fn update(&mut self, tick: u64, ppqn: u32, registry: &mut ParameterRegistry) -> Vec<String> {
+        self.modulators
+            .iter_mut()
+            .filter_map(|modulator| (!modulator.apply(registry, tick, ppqn)).then(|| modulator.target.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lfo_moves_parameter_around_its_value() {
+        let mut registry = ParameterRegistry::new();
+        registry.register(Parameter::new("melody.rest_probability", 0.0, 1.0, 0.5));
+        let mut matrix = ModMatrix::new();
+        let lfo = ModSource::Lfo { shape: LfoShape::Sine, beats: 4.0, phase: 0.0 };
+        matrix.add(Modulator::new("melody.rest_probability", lfo).with_depth(0.25));
+        matrix.add(Modulator::new("missing", ModSource::RandomWalk { beats: 1.0, step: 0.1 }));
+
+        // A quarter of the way through the 4-beat cycle is the peak
+        assert_eq!(matrix.update(24, 24, &mut registry), vec!["missing".to_string()]);
+        assert!((registry.value("melody.rest_probability").unwrap() - 0.75).abs() < 1e-9);
+        matrix.update(72, 24, &mut registry);
+        assert!((registry.value("melody.rest_probability").unwrap() - 0.25).abs() < 1e-9);
+
+        // Setting the parameter by hand moves the centre
+        registry.set("melody.rest_probability", 0.6);
+        matrix.update(96, 24, &mut registry);
+        assert!((registry.value("melody.rest_probability").unwrap() - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_walk_and_envelope() {
+        assert_eq!(LfoShape::Triangle.value(0.25), 1.0);
+        assert_eq!(LfoShape::Triangle.value(0.75), -1.0);
+        assert_eq!(LfoShape::from_str("saw"), Some(LfoShape::Saw));
+
+        let mut walk = Modulator::new("p", ModSource::RandomWalk { beats: 1.0, step: 0.2 }).with_seed(3);
+        let mut last = walk.value(0, 24);
+        for beat in 1..50 {
+            let value = walk.value(beat * 24, 24);
+            assert!((value - last).abs() <= 0.2 + 1e-9);
+            assert!((-1.0..=1.0).contains(&value));
+            last = value;
+        }
+
+        let mut env = Modulator::new("p", ModSource::Envelope { attack: 1.0, decay: 2.0, sustain: 0.5 });
+        assert_eq!(env.value(0, 24), 0.0);
+        env.trigger(96);
+        assert_eq!(env.value(108, 24), 0.5);
+        assert_eq!(env.value(120, 24), 1.0);
+        assert_eq!(env.value(144, 24), 0.75);
+        assert_eq!(env.value(500, 24), 0.5);
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! OSC (Open Sound Control) remote control.
+//!
+//! Listens on a UDP port so TouchOSC, Max/MSP and the like can drive SEQ
+//! next to the MIDI mappings. Addresses sit under a prefix (`/seq` by
+//! default):
+//!
+//! ```text
+//! /seq/transport/play            play, stop, pause, toggle, record
+//! /seq/tempo 128.0               set tempo (/seq/tempo/tap to tap)
+//! /seq/track/1/mute [0|1]        mute, solo, select, latch, stop,
+//!                                volume, probability, gate (track number or name)
+//! /seq/track/1/clip/2            trigger a clip
+//! /seq/scene/3                   trigger a scene
+//! /seq/part/chorus               trigger a part (also macro, fx)
+//! /seq/param/Arp.density 0.7     set a parameter
+//! /seq/<action>[/<target>] [v]   any controls file action
+//! ```
+//!
+//! Buttons send 1 on press and 0 on release; a 0 is ignored for one-shot
+//! actions so a press fires once.
+
+use std::net::{SocketAddr, UdpSocket};
+
+use anyhow::{Context, Result};
+
+use super::ControlAction;
+
+/// Default UDP port
+pub const DEFAULT_OSC_PORT: u16 = 9000;
+
+/// Default address prefix
+pub const DEFAULT_OSC_PREFIX: &str = "/seq";
+
+/// Largest datagram read
+const MAX_PACKET: usize = 4096;
+
+/// An OSC argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscArg {
+    /// 32-bit integer ('i') or 64-bit integer ('h')
+    Int(i64),
+    /// 32-bit ('f') or 64-bit ('d') float
+    Float(f64),
+    /// String ('s')
+    String(String),
+    /// True ('T') or false ('F')
+    Bool(bool),
+}
+
+impl OscArg {
+    /// Numeric value of the argument
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            OscArg::Int(i) => Some(*i as f64),
+            OscArg::Float(f) => Some(*f),
+            OscArg::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+            OscArg::String(s) => s.trim().parse().ok(),
+        }
+    }
+}
+
+/// An OSC message
+#[derive(Debug, Clone, PartialEq)]
+pub struct OscMessage {
+    /// Address pattern
+    pub address: String,
+    /// Arguments
+    pub args: Vec<OscArg>,
+}
+
+impl OscMessage {
+    /// Create a message
+    pub fn new(address: impl Into<String>, args: Vec<OscArg>) -> Self {
+        Self {
+            address: address.into(),
+            args,
+        }
+    }
+
+    /// First numeric argument
+    pub fn value(&self) -> Option<f64> {
+        self.args.iter().find_map(OscArg::as_f64)
+    }
+
+    /// Encode as an OSC packet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        push_string(&mut buf, &self.address);
+        let tags: String = std::iter::once(',')
+            .chain(self.args.iter().map(|arg| match arg {
+                OscArg::Int(_) => 'i',
+                OscArg::Float(_) => 'f',
+                OscArg::String(_) => 's',
+                OscArg::Bool(true) => 'T',
+                OscArg::Bool(false) => 'F',
+            }))
+            .collect();
+        push_string(&mut buf, &tags);
+        for arg in &self.args {
+            match arg {
+                OscArg::Int(i) => buf.extend_from_slice(&(*i as i32).to_be_bytes()),
+                OscArg::Float(f) => buf.extend_from_slice(&(*f as f32).to_be_bytes()),
+                OscArg::String(s) => push_string(&mut buf, s),
+                OscArg::Bool(_) => {}
+            }
+        }
+        buf
+    }
+
+    /// Parse a packet: one message, or every message in a bundle
+    pub fn parse_packet(data: &[u8]) -> Vec<OscMessage> {
+        let mut messages = Vec::new();
+        parse_into(data, &mut messages, 0);
+        messages
+    }
+
+    /// Parse a single message
+    pub fn parse(data: &[u8]) -> Option<OscMessage> {
+        let mut reader = Reader { data, pos: 0 };
+        let address = reader.string()?;
+        if !address.starts_with('/') {
+            return None;
+        }
+        // Very old senders omit the type tags
+        let tags = if reader.pos < data.len() { reader.string()? } else { ",".to_string() };
+        let mut args = Vec::new();
+        for tag in tags.strip_prefix(',')?.chars() {
+            let arg = match tag {
+                'i' => OscArg::Int(i32::from_be_bytes(reader.bytes()?) as i64),
+                'h' => OscArg::Int(i64::from_be_bytes(reader.bytes()?)),
+                'f' => OscArg::Float(f32::from_be_bytes(reader.bytes()?) as f64),
+                'd' => OscArg::Float(f64::from_be_bytes(reader.bytes()?)),
+                's' | 'S' => OscArg::String(reader.string()?),
+                'T' => OscArg::Bool(true),
+                'F' => OscArg::Bool(false),
+                // Nil and impulse carry no data
+                'N' | 'I' => continue,
+                _ => return None,
+            };
+            args.push(arg);
+        }
+        Some(OscMessage { address, args })
+    }
+}
+
+/// Parse a packet, descending into (nested) bundles
+fn parse_into(data: &[u8], messages: &mut Vec<OscMessage>, depth: usize) {
+    if depth > 8 {
+        return;
+    }
+    let Some(mut rest) = data.strip_prefix(b"#bundle\0") else {
+        messages.extend(OscMessage::parse(data));
+        return;
+    };
+    // Skip the time tag: everything is applied on arrival
+    rest = rest.get(8..).unwrap_or(&[]);
+    while rest.len() >= 4 {
+        let size = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let Some(element) = rest.get(4..4 + size) else {
+            break;
+        };
+        parse_into(element, messages, depth + 1);
+        rest = &rest[4 + size..];
+    }
+}
+
+/// Cursor over packet bytes
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    /// Null-terminated string padded to four bytes
+    fn string(&mut self) -> Option<String> {
+        let rest = self.data.get(self.pos..)?;
+        let len = rest.iter().position(|&b| b == 0)?;
+        let s = std::str::from_utf8(&rest[..len]).ok()?.to_string();
+        self.pos += (len + 4) & !3;
+        Some(s)
+    }
+
+    /// Fixed-size big-endian field
+    fn bytes<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.data.get(self.pos..self.pos + N)?.try_into().ok()?;
+        self.pos += N;
+        Some(bytes)
+    }
+}
+
+/// Append a null-terminated string padded to four bytes
+fn push_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(s.as_bytes());
+    buf.push(0);
+    while buf.len() % 4 != 0 {
+        buf.push(0);
+    }
+}
+
+/// Maps OSC addresses to control actions
+#[derive(Debug, Clone)]
+pub struct OscMapper {
+    /// Address prefix
+    prefix: String,
+}
+
+impl OscMapper {
+    /// Create a mapper for addresses under a prefix ("/seq")
+    pub fn new(prefix: &str) -> Self {
+        let prefix = format!("/{}", prefix.trim().trim_matches('/'));
+        Self {
+            prefix: if prefix == "/" { String::new() } else { prefix },
+        }
+    }
+
+    /// Get the address prefix
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Action for a message (None = not ours, unknown, or a button release)
+    pub fn action(&self, message: &OscMessage, track_names: &[String]) -> Option<ControlAction> {
+        let path = message.address.strip_prefix(self.prefix.as_str())?;
+        if !path.is_empty() && !path.starts_with('/') {
+            return None;
+        }
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        let value = message.value();
+        let pressed = value.map_or(true, |v| v > 0.0);
+        let track = |target: &str| match target.parse::<usize>() {
+            Ok(number) if number >= 1 => Some(number - 1),
+            _ => track_names.iter().position(|n| n.eq_ignore_ascii_case(target)),
+        };
+
+        let action = match parts.as_slice() {
+            ["transport", command] => {
+                let action = match *command {
+                    "play" => ControlAction::Play,
+                    "stop" => ControlAction::Stop,
+                    "pause" => ControlAction::Pause,
+                    "toggle" => ControlAction::TogglePlay,
+                    "record" => match value {
+                        Some(v) => return Some(ControlAction::SetRecord(v > 0.0)),
+                        None => ControlAction::ToggleRecord,
+                    },
+                    _ => return None,
+                };
+                pressed.then_some(action)?
+            }
+            ["tempo"] => ControlAction::SetTempo(value?),
+            ["tempo", "tap"] => pressed.then_some(ControlAction::TapTempo)?,
+            ["track", target, "clip", clip] => {
+                let clip = clip.parse::<usize>().ok()?.checked_sub(1)?;
+                pressed.then_some(ControlAction::TriggerClip(track(target)?, clip))?
+            }
+            ["track", target, command] => {
+                let t = track(target)?;
+                match *command {
+                    "mute" => match value {
+                        Some(v) => ControlAction::SetMute(t, v > 0.0),
+                        None => ControlAction::ToggleMute(t),
+                    },
+                    "volume" => ControlAction::SetTrackVolume(t, value?),
+                    "probability" => ControlAction::SetTrackProbability(t, value?),
+                    "gate" => ControlAction::SetTrackGate(t, value?),
+                    "solo" => pressed.then_some(ControlAction::ToggleSolo(t))?,
+                    "select" => pressed.then_some(ControlAction::SelectTrack(t))?,
+                    "latch" => pressed.then_some(ControlAction::ToggleLatch(t))?,
+                    "stop" => pressed.then_some(ControlAction::StopClip(t))?,
+                    _ => return None,
+                }
+            }
+            ["param", name] => ControlAction::SetParameter(name.to_string(), value?),
+            ["scene", scene] => {
+                let scene = scene.parse::<usize>().ok()?.checked_sub(1)?;
+                pressed.then_some(ControlAction::TriggerScene(scene))?
+            }
+            ["part", name] => pressed.then(|| ControlAction::TriggerPart(name.to_string()))?,
+            ["macro", name] => pressed.then(|| ControlAction::RunMacro(name.to_string()))?,
+            ["fx", name] => pressed.then(|| ControlAction::LaunchFx(name.to_string()))?,
+            // Anything else is a controls file action with an optional target
+            [name, rest @ ..] => {
+                let target = (!rest.is_empty()).then(|| rest.join("/"));
+                let action = ControlAction::from_spec(name, target.as_deref(), value, track_names)?;
+                if value.is_none() || action.automation_target().is_some() || pressed {
+                    action
+                } else {
+                    return None;
+                }
+            }
+            [] => return None,
+        };
+        Some(action)
+    }
+}
+
+impl Default for OscMapper {
+    fn default() -> Self {
+        Self::new(DEFAULT_OSC_PREFIX)
+    }
+}
+
+/// UDP server receiving OSC control messages
+pub struct OscServer {
+    socket: UdpSocket,
+    mapper: OscMapper,
+}
+
+impl OscServer {
+    /// Listen on a port on every interface
+    pub fn bind(port: u16, mapper: OscMapper) -> Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))
+            .with_context(|| format!("Failed to open OSC port {}", port))?;
+        socket.set_nonblocking(true).context("Failed to set OSC socket non-blocking")?;
+        Ok(Self { socket, mapper })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Get the address mapper
+    pub fn mapper(&self) -> &OscMapper {
+        &self.mapper
+    }
+
+    /// Read every waiting message without blocking
+    pub fn receive(&self) -> Vec<OscMessage> {
+        let mut messages = Vec::new();
+        let mut buf = [0u8; MAX_PACKET];
+        while let Ok((len, _)) = self.socket.recv_from(&mut buf) {
+            messages.extend(OscMessage::parse_packet(&buf[..len]));
+        }
+        messages
+    }
+
+    /// Read waiting messages and map them to actions
+    pub fn poll(&self, track_names: &[String]) -> Vec<ControlAction> {
+        self.receive()
+            .iter()
+            .filter_map(|message| self.mapper.action(message, track_names))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_messages_and_bundles() {
+        let message = OscMessage::new(
+            "/seq/param/Arp.density",
+            vec![OscArg::Float(0.5), OscArg::String("x".to_string()), OscArg::Int(3)],
+        );
+        assert_eq!(OscMessage::parse(&message.to_bytes()), Some(message.clone()));
+
+        let play = OscMessage::new("/seq/transport/play", vec![]);
+        let mut bundle = b"#bundle\0".to_vec();
+        bundle.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 1]);
+        for element in [&message, &play] {
+            let bytes = element.to_bytes();
+            bundle.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            bundle.extend_from_slice(&bytes);
+        }
+        assert_eq!(OscMessage::parse_packet(&bundle), vec![message, play]);
+        assert!(OscMessage::parse_packet(b"junk").is_empty());
+    }
+
+    #[test]
+    fn test_address_mapping() {
+        let mapper = OscMapper::default();
+        let names = vec!["Bass".to_string(), "Lead".to_string()];
+        let action = |address: &str, args: Vec<OscArg>| mapper.action(&OscMessage::new(address, args), &names);
+
+        assert_eq!(action("/seq/transport/play", vec![]), Some(ControlAction::Play));
+        // Button release
+        assert_eq!(action("/seq/transport/play", vec![OscArg::Float(0.0)]), None);
+        assert_eq!(action("/seq/track/1/mute", vec![]), Some(ControlAction::ToggleMute(0)));
+        assert_eq!(action("/seq/track/lead/mute", vec![OscArg::Int(1)]), Some(ControlAction::SetMute(1, true)));
+        assert_eq!(action("/seq/track/2/clip/3", vec![]), Some(ControlAction::TriggerClip(1, 2)));
+        assert_eq!(
+            action("/seq/param/Arp.density", vec![OscArg::Float(0.25)]),
+            Some(ControlAction::SetParameter("Arp.density".to_string(), 0.25))
+        );
+        assert_eq!(action("/seq/tempo", vec![OscArg::Int(128)]), Some(ControlAction::SetTempo(128.0)));
+        assert_eq!(action("/seq/part/chorus", vec![]), Some(ControlAction::TriggerPart("chorus".to_string())));
+        assert_eq!(action("/seq/track_gate/2", vec![OscArg::Float(0.0)]), Some(ControlAction::SetTrackGate(1, 0.0)));
+        assert_eq!(action("/other/transport/play", vec![]), None);
+    }
+
+    #[test]
+    fn test_server_receives() {
+        let server = OscServer::bind(0, OscMapper::default()).unwrap();
+        let port = server.local_addr().unwrap().port();
+        let client = UdpSocket::bind(("127.0.0.1", 0)).unwrap();
+        client
+            .send_to(&OscMessage::new("/seq/transport/stop", vec![]).to_bytes(), ("127.0.0.1", port))
+            .unwrap();
+
+        let mut actions = Vec::new();
+        for _ in 0..100 {
+            actions = server.poll(&[]);
+            if !actions.is_empty() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(actions, vec![ControlAction::Stop]);
+    }
+}
@@ -4,10 +4,15 @@
 //! Parameter system with smoothing and automation.
 //!
 //! Provides a registry of named parameters with configurable ranges,
-//! value smoothing, and optional automation support.
+//! value smoothing, and optional automation support. Parameters can be
+//! locked so randomize/mutate actions leave them alone.
 
 use std::collections::HashMap;
 
+use rand::Rng;
+
+use crate::generators::ParamSpec;
+
 /// Parameter value with optional smoothing
 #[derive(Debug, Clone)]
 pub struct ParameterValue {
@@ -119,6 +124,8 @@ pub struct Parameter {
     pub group: String,
     /// Whether parameter is exposed for MIDI control
     pub midi_controllable: bool,
+    /// Whether randomize/mutate leave this parameter alone
+    pub locked: bool,
 }
 
 impl Parameter {
@@ -136,9 +143,18 @@ impl Parameter {
             precision: 2,
             group: "General".to_string(),
             midi_controllable: true,
+            locked: false,
         }
     }
 
+    /// Create a generator parameter from its range metadata
+    pub fn from_spec(name: impl Into<String>, spec: &ParamSpec) -> Self {
+        Self::new(name, spec.min, spec.max, spec.default)
+            .unit(spec.unit)
+            .precision(if spec.step >= 1.0 { 0 } else { 2 })
+            .group("Generator")
+    }
+
     /// Set display name
     pub fn display_name(mut self, name: impl Into<String>) -> Self {
         self.display_name = name.into();
@@ -169,6 +185,12 @@ impl Parameter {
         self
     }
 
+    /// Set locked against randomize/mutate
+    pub fn locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
     /// Set smoothing
     pub fn smoothing(mut self, smoothing: f64) -> Self {
         self.value.set_smoothing(smoothing);
@@ -363,6 +385,67 @@ impl ParameterRegistry {
             param.reset();
         }
     }
+
+    /// Lock or unlock a parameter by name
+    pub fn set_locked(&mut self, name: &str, locked: bool) -> bool {
+        if let Some(param) = self.params.get_mut(name) {
+            param.locked = locked;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Toggle a parameter's lock, returning the new state
+    pub fn toggle_lock(&mut self, name: &str) -> Option<bool> {
+        let param = self.params.get_mut(name)?;
+        param.locked = !param.locked;
+        Some(param.locked)
+    }
+
+    /// Check if a parameter is locked
+    pub fn is_locked(&self, name: &str) -> bool {
+        self.params.get(name).map(|p| p.locked).unwrap_or(false)
+    }
+
+    /// Get names of locked parameters in order
+    pub fn locked(&self) -> Vec<String> {
+        self.iter().filter(|p| p.locked).map(|p| p.name.clone()).collect()
+    }
+
+    /// Set every unlocked parameter to a random value in its range.
+    /// Returns the names of the parameters changed.
+    pub fn randomize<R: Rng>(&mut self, rng: &mut R) -> Vec<String> {
+        let mut changed = Vec::new();
+        for name in &self.order {
+            if let Some(param) = self.params.get_mut(name) {
+                if !param.locked {
+                    param.set_normalized(rng.gen::<f64>());
+                    changed.push(name.clone());
+                }
+            }
+        }
+        changed
+    }
+
+    /// Move every unlocked parameter by a random amount up to `amount`
+    /// (normalized). Returns the names of the parameters changed.
+    pub fn mutate<R: Rng>(&mut self, rng: &mut R, amount: f64) -> Vec<String> {
+        let amount = amount.clamp(0.0, 1.0);
+        let mut changed = Vec::new();
+        if amount == 0.0 {
+            return changed;
+        }
+        for name in &self.order {
+            if let Some(param) = self.params.get_mut(name) {
+                if !param.locked {
+                    param.adjust_normalized(rng.gen_range(-amount..=amount));
+                    changed.push(name.clone());
+                }
+            }
+        }
+        changed
+    }
 }
 
 impl Default for ParameterRegistry {
@@ -573,6 +656,32 @@ mod tests {
         assert_eq!(volume.group, "Mixer");
     }
 
+    #[test]
+    fn test_randomize_respects_locks() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut registry = ParameterRegistry::new();
+        registry.register(Parameter::new("base_octave", 1.0, 7.0, 4.0).locked(true));
+        registry.register(Parameter::new("rate", 0.0, 1.0, 0.5));
+        registry.register(Parameter::new("density", 0.0, 1.0, 0.5));
+
+        assert!(registry.set_locked("rate", true));
+        assert_eq!(registry.locked(), vec!["base_octave".to_string(), "rate".to_string()]);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let changed = registry.randomize(&mut rng);
+        assert_eq!(changed, vec!["density".to_string()]);
+        assert_eq!(registry.value("base_octave"), Some(4.0));
+        assert_eq!(registry.value("rate"), Some(0.5));
+
+        assert_eq!(registry.toggle_lock("rate"), Some(false));
+        let changed = registry.mutate(&mut rng, 0.1);
+        assert_eq!(changed, vec!["rate".to_string(), "density".to_string()]);
+        assert!((registry.value("rate").unwrap() - 0.5).abs() <= 0.1);
+        assert_eq!(registry.value("base_octave"), Some(4.0));
+    }
+
     #[test]
     fn test_registry_reset() {
         let mut registry = ParameterRegistry::new();
@@ -0,0 +1,433 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Live-coding REPL.
+//!
+//! A small command language typed a line at a time (`seq repl`) and
+//! evaluated against the engine's tracks:
+//!
+//! ```text
+//! track Bass arpeggio ch 2       create a track (generator and channel optional)
+//! gen Bass melody                swap a track's generator
+//! set Bass density 0.7           set a track setting or generator parameter
+//! pattern Bass riff c2 - c2 _ g1 define a clip on a sixteenth grid
+//!                                (note, '-' rest, '_' hold the last note)
+//! play Bass riff                 launch a clip (name or number)
+//! tracks                         list tracks
+//! tempo 128                      set the tempo
+//! <action> [target] [value]      any controls file action (mute Bass,
+//!                                trigger_part chorus, fill)
+//! ```
+//!
+//! Tracks are named or numbered from 1. Actions that belong to the
+//! transport or arrangement are handed back for the app to dispatch.
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::generators::{Generator, GeneratorRegistry};
+use crate::music::scale::parse_midi_note;
+use crate::sequencer::track::{TrackConfig, TrackManager};
+use crate::sequencer::{Clip, ClipNote, Track};
+
+use super::ControlAction;
+
+/// Prompt shown before each line
+pub const REPL_PROMPT: &str = "seq> ";
+
+/// Velocity of pattern notes
+const PATTERN_VELOCITY: u8 = 100;
+
+/// One step of a pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternStep {
+    /// Start a note
+    Note(u8),
+    /// Hold the previous note through the step
+    Hold,
+    /// Silence
+    Rest,
+}
+
+impl PatternStep {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "-" | "." => Some(PatternStep::Rest),
+            "_" => Some(PatternStep::Hold),
+            _ => parse_midi_note(s).map(PatternStep::Note),
+        }
+    }
+}
+
+/// A parsed REPL line
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplCommand {
+    /// Create a track
+    Track {
+        /// Track name
+        name: String,
+        /// Generator name
+        generator: Option<String>,
+        /// MIDI channel (0-15)
+        channel: Option<u8>,
+    },
+    /// Replace a track's generator
+    Generator(usize, String),
+    /// Set a track setting or generator parameter
+    Set(usize, String, f64),
+    /// Define (or redefine) a clip from steps
+    Pattern(usize, String, Vec<PatternStep>),
+    /// Launch a clip by name or number
+    Play(usize, String),
+    /// List tracks
+    Tracks,
+    /// Show the command summary
+    Help,
+    /// Leave the REPL
+    Quit,
+    /// A control action
+    Action(ControlAction),
+}
+
+impl ReplCommand {
+    /// Parse a line. Blank lines and `#` comments parse to None.
+    pub fn parse(line: &str, track_names: &[String]) -> Result<Option<Self>> {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&verb, args)) = words.split_first() else {
+            return Ok(None);
+        };
+
+        let track = |index: usize| -> Result<usize> {
+            let name = args.get(index).ok_or_else(|| anyhow!("{} needs a track", verb))?;
+            find_track(name, track_names).ok_or_else(|| anyhow!("No track '{}'", name))
+        };
+        let word = |index: usize, what: &str| -> Result<String> {
+            args.get(index)
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow!("{} needs {}", verb, what))
+        };
+
+        let command = match verb.to_lowercase().as_str() {
+            "track" => {
+                let name = word(0, "a name")?;
+                let mut generator = None;
+                let mut channel = None;
+                let mut rest = args[1..].iter();
+                while let Some(&arg) = rest.next() {
+                    if arg.eq_ignore_ascii_case("ch") || arg.eq_ignore_ascii_case("channel") {
+                        let value = rest.next().ok_or_else(|| anyhow!("ch needs a channel"))?;
+                        let number: u8 = value.parse().with_context(|| format!("Invalid channel '{}'", value))?;
+                        if !(1..=16).contains(&number) {
+                            bail!("Channel must be 1-16");
+                        }
+                        channel = Some(number - 1);
+                    } else {
+                        generator = Some(arg.to_string());
+                    }
+                }
+                ReplCommand::Track { name, generator, channel }
+            }
+            "gen" | "generator" => ReplCommand::Generator(track(0)?, word(1, "a generator")?),
+            "set" => {
+                let value = word(2, "a value")?;
+                let value = value.parse().with_context(|| format!("Invalid value '{}'", value))?;
+                ReplCommand::Set(track(0)?, word(1, "a parameter")?, value)
+            }
+            "pattern" => {
+                let steps = args
+                    .iter()
+                    .skip(2)
+                    .map(|s| PatternStep::from_str(s).ok_or_else(|| anyhow!("Invalid step '{}'", s)))
+                    .collect::<Result<Vec<_>>>()?;
+                if steps.is_empty() {
+                    bail!("pattern needs at least one step");
+                }
+                ReplCommand::Pattern(track(0)?, word(1, "a name")?, steps)
+            }
+            "play" => ReplCommand::Play(track(0)?, word(1, "a clip")?),
+            "tracks" | "ls" => ReplCommand::Tracks,
+            "help" | "?" => ReplCommand::Help,
+            "quit" | "exit" => ReplCommand::Quit,
+            "tempo" => {
+                let value = word(0, "a BPM")?;
+                ReplCommand::Action(ControlAction::SetTempo(
+                    value.parse().with_context(|| format!("Invalid tempo '{}'", value))?,
+                ))
+            }
+            _ => {
+                // Controls file actions: a target and/or a trailing number
+                let value = args.last().and_then(|s| s.parse::<f64>().ok());
+                let action = ControlAction::from_spec(verb, args.first().copied(), value, track_names)
+                    .ok_or_else(|| anyhow!("Unknown command '{}' (try help)", line))?;
+                ReplCommand::Action(action)
+            }
+        };
+        Ok(Some(command))
+    }
+}
+
+/// What evaluating a line produced
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplOutput {
+    /// Text to show
+    Message(String),
+    /// An action for the app to dispatch
+    Action(ControlAction),
+    /// The user asked to leave
+    Quit,
+}
+
+/// Evaluates REPL lines against the engine's tracks
+#[derive(Debug)]
+pub struct Repl {
+    /// Generators available to `track` and `gen`
+    registry: GeneratorRegistry,
+    /// Ticks per quarter note, for pattern steps
+    ppqn: u32,
+    /// Lines entered, oldest first
+    history: Vec<String>,
+}
+
+impl Repl {
+    /// Create a REPL with the built-in generators
+    pub fn new(ppqn: u32) -> Self {
+        Self {
+            registry: GeneratorRegistry::with_builtins(),
+            ppqn: ppqn.max(4),
+            history: Vec::new(),
+        }
+    }
+
+    /// Get the lines entered so far
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Parse and run a line
+    pub fn eval(&mut self, line: &str, tracks: &mut TrackManager) -> Result<ReplOutput> {
+        let names: Vec<String> = tracks.iter().map(|t| t.name().to_string()).collect();
+        let Some(command) = ReplCommand::parse(line, &names)? else {
+            return Ok(ReplOutput::Message(String::new()));
+        };
+        self.history.push(line.trim().to_string());
+        self.run(command, tracks)
+    }
+
+    /// Run a parsed command
+    pub fn run(&mut self, command: ReplCommand, tracks: &mut TrackManager) -> Result<ReplOutput> {
+        let message = match command {
+            ReplCommand::Track { name, generator, channel } => {
+                let config = TrackConfig::new(name.clone()).with_channel(channel.unwrap_or(0));
+                let generator = generator.map(|g| self.generator(&g)).transpose()?;
+                let index = tracks.add_track(config);
+                let track = tracks.track_mut(index).expect("track just added");
+                if let Some(generator) = generator {
+                    track.set_generator(generator);
+                }
+                format!("Track {} '{}'", index + 1, name)
+            }
+            ReplCommand::Generator(index, name) => {
+                let generator = self.generator(&name)?;
+                let track = track_mut(tracks, index)?;
+                track.set_generator(generator);
+                format!("{}: {}", track.name(), name)
+            }
+            ReplCommand::Set(index, param, value) => {
+                let track = track_mut(tracks, index)?;
+                let name = track.name().to_string();
+                match param.to_lowercase().as_str() {
+                    "transpose" => track.set_transpose(value.round().clamp(-48.0, 48.0) as i8),
+                    "swing" => track.set_swing(value),
+                    "velocity" | "velocity_scale" => track.set_velocity_scale(value),
+                    "probability" => track.set_play_probability(value),
+                    "gate" => track.set_gate_scale(value),
+                    "channel" => track.set_channel((value.round().clamp(1.0, 16.0) as u8) - 1),
+                    _ => {
+                        let generator = track
+                            .generator_mut()
+                            .ok_or_else(|| anyhow!("{} has no generator", name))?;
+                        if generator.get_param(&param).is_none() {
+                            bail!("{} has no parameter '{}'", generator.name(), param);
+                        }
+                        generator.set_param(&param, value);
+                    }
+                }
+                format!("{}.{} = {}", name, param, value)
+            }
+            ReplCommand::Pattern(index, name, steps) => {
+                let clip = self.pattern_clip(&name, &steps);
+                let track = track_mut(tracks, index)?;
+                let existing = (0..track.clip_count()).find(|&i| track.clip(i).is_some_and(|c| c.name() == name));
+                // Redefine in place so a playing clip keeps playing
+                if let Some(old) = existing.and_then(|i| track.clip_mut(i)) {
+                    old.set_length(clip.length());
+                    old.clear_notes();
+                    old.add_notes(clip.notes().iter().cloned());
+                } else {
+                    track.add_clip(clip);
+                }
+                format!("{}: pattern '{}' ({} steps)", track.name(), name, steps.len())
+            }
+            ReplCommand::Play(index, clip) => {
+                let track = track_mut(tracks, index)?;
+                let found = match clip.parse::<usize>() {
+                    Ok(number) if number >= 1 => Some(number - 1).filter(|&i| i < track.clip_count()),
+                    _ => (0..track.clip_count()).find(|&i| track.clip(i).is_some_and(|c| c.name() == clip)),
+                };
+                let found = found.ok_or_else(|| anyhow!("{} has no clip '{}'", track.name(), clip))?;
+                track.set_active_clip(Some(found));
+                if let Some(clip) = track.clip_mut(found) {
+                    clip.play();
+                }
+                format!("{}: playing '{}'", track.name(), clip)
+            }
+            ReplCommand::Tracks => tracks
+                .iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    let generator = t.generator().map_or("-", |g| g.name());
+                    format!("{:>2} {:<12} ch {:<2} {}", i + 1, t.name(), t.channel() + 1, generator)
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ReplCommand::Help => HELP.trim().to_string(),
+            ReplCommand::Quit => return Ok(ReplOutput::Quit),
+            ReplCommand::Action(action) => return Ok(ReplOutput::Action(action)),
+        };
+        Ok(ReplOutput::Message(message))
+    }
+
+    /// Create a generator by name
+    fn generator(&self, name: &str) -> Result<Box<dyn Generator>> {
+        self.registry.create(name).ok_or_else(|| {
+            let mut names = self.registry.available();
+            names.sort();
+            anyhow!("Unknown generator '{}' (have {})", name, names.join(", "))
+        })
+    }
+
+    /// Build a clip from pattern steps, a sixteenth note each
+    fn pattern_clip(&self, name: &str, steps: &[PatternStep]) -> Clip {
+        let step_ticks = self.ppqn as u64 / 4;
+        let mut clip = Clip::new(name, step_ticks * steps.len() as u64);
+        let mut notes: Vec<ClipNote> = Vec::new();
+        let mut holding = false;
+        for (i, step) in steps.iter().enumerate() {
+            match *step {
+                PatternStep::Note(note) => {
+                    notes.push(ClipNote::new(i as u64 * step_ticks, step_ticks, note, PATTERN_VELOCITY));
+                    holding = true;
+                }
+                PatternStep::Hold if holding => {
+                    if let Some(last) = notes.last_mut() {
+                        last.duration += step_ticks;
+                    }
+                }
+                PatternStep::Hold | PatternStep::Rest => holding = false,
+            }
+        }
+        clip.add_notes(notes);
+        clip
+    }
+}
+
+/// Command summary for `help`
+const HELP: &str = "
+track <name> [generator] [ch N]   create a track
+gen <track> <generator>           swap a track's generator
+set <track> <param> <value>       transpose, swing, velocity, probability,
+                                  gate, channel or a generator parameter
+pattern <track> <name> <steps>    notes (c3, 60), '-' rest, '_' hold
+play <track> <clip>               launch a clip
+tracks                            list tracks
+tempo <bpm>                       set the tempo
+<action> [target] [value]         controls file action (mute Bass, fill)
+quit                              leave
+";
+
+/// Find a track by 1-based number or name
+fn find_track(name: &str, track_names: &[String]) -> Option<usize> {
+    match name.parse::<usize>() {
+        Ok(number) if number >= 1 => Some(number - 1).filter(|&i| i < track_names.len()),
+        _ => track_names.iter().position(|n| n.eq_ignore_ascii_case(name)),
+    }
+}
+
+/// Get a track or fail
+fn track_mut(tracks: &mut TrackManager, index: usize) -> Result<&mut Track> {
+    tracks.track_mut(index).ok_or_else(|| anyhow!("No track {}", index + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        let names = vec!["Drums".to_string(), "Bass".to_string()];
+        assert_eq!(ReplCommand::parse("  # just a comment", &names).unwrap(), None);
+        assert_eq!(
+            ReplCommand::parse("track Lead melody ch 3", &names).unwrap(),
+            Some(ReplCommand::Track {
+                name: "Lead".to_string(),
+                generator: Some("melody".to_string()),
+                channel: Some(2),
+            })
+        );
+        assert_eq!(
+            ReplCommand::parse("set bass density 0.7", &names).unwrap(),
+            Some(ReplCommand::Set(1, "density".to_string(), 0.7))
+        );
+        assert_eq!(
+            ReplCommand::parse("mute 1", &names).unwrap(),
+            Some(ReplCommand::Action(ControlAction::SetMute(0, true)))
+        );
+        assert_eq!(
+            ReplCommand::parse("trigger_part chorus", &names).unwrap(),
+            Some(ReplCommand::Action(ControlAction::TriggerPart("chorus".to_string())))
+        );
+        assert_eq!(
+            ReplCommand::parse("mutate 0.2", &names).unwrap(),
+            Some(ReplCommand::Action(ControlAction::MutateParams(0.2)))
+        );
+        assert!(ReplCommand::parse("set Keys density 1", &names).is_err());
+        assert!(ReplCommand::parse("pattern Bass riff c2 zz", &names).is_err());
+        assert!(ReplCommand::parse("frobnicate", &names).is_err());
+    }
+
+    #[test]
+    fn test_live_coding_session() {
+        let mut tracks = TrackManager::new();
+        let mut repl = Repl::new(24);
+
+        repl.eval("track Bass arpeggio ch 2", &mut tracks).unwrap();
+        assert_eq!(tracks.track(0).unwrap().channel(), 1);
+        repl.eval("set bass octaves 2", &mut tracks).unwrap();
+        assert_eq!(tracks.track(0).unwrap().generator().unwrap().get_param("octaves"), Some(2.0));
+        assert!(repl.eval("set bass wobble 2", &mut tracks).is_err());
+        assert!(repl.eval("track Keys theremin", &mut tracks).is_err());
+
+        // c2 held for two steps, a rest, then g1
+        repl.eval("pattern Bass riff c2 _ - g1", &mut tracks).unwrap();
+        let clip = tracks.track(0).unwrap().clip(0).unwrap();
+        assert_eq!(clip.length(), 24);
+        assert_eq!(clip.notes(), &[ClipNote::new(0, 12, 36, 100), ClipNote::new(18, 6, 31, 100)]);
+
+        // Redefining a pattern replaces its notes in place
+        repl.eval("pattern Bass riff e2", &mut tracks).unwrap();
+        assert_eq!(tracks.track(0).unwrap().clip_count(), 1);
+        assert_eq!(tracks.track(0).unwrap().clip(0).unwrap().notes()[0].note, 40);
+        assert_eq!(tracks.track(0).unwrap().clip(0).unwrap().length(), 6);
+
+        repl.eval("play 1 riff", &mut tracks).unwrap();
+        assert_eq!(tracks.track(0).unwrap().active_clip_index(), Some(0));
+
+        assert_eq!(
+            repl.eval("tempo 128", &mut tracks).unwrap(),
+            ReplOutput::Action(ControlAction::SetTempo(128.0))
+        );
+        assert_eq!(repl.eval("quit", &mut tracks).unwrap(), ReplOutput::Quit);
+        assert_eq!(repl.history().len(), 9);
+    }
+}
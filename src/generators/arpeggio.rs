@@ -7,11 +7,32 @@
 //! patterns, octave ranges, and rhythmic options including Euclidean rhythms.
 
 use std::collections::HashMap;
+use std::fmt;
 
+use anyhow::{anyhow, Result};
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 
-use super::{Generator, GeneratorContext, MidiEvent};
+use super::{Generator, GeneratorContext, Inspiration, MidiEvent, ParamSpec, EUCLIDEAN_PARAMS};
+use crate::music::EuclideanGate;
+
+/// Parameter ranges
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::whole(
+        "pattern",
+        0.0,
+        6.0,
+        0.0,
+        "Order: 0 up, 1 down, 2 up-down, 3 down-up, 4 random, 5 as played, 6 custom",
+    ),
+    ParamSpec::whole("rate", 1.0, 64.0, 8.0, "Note division (4 = quarter, 8 = eighth, 16 = sixteenth)"),
+    ParamSpec::new("gate", 0.1, 1.0, 0.8, "Portion of each step the note sounds"),
+    ParamSpec::whole("octaves", 1.0, 4.0, 2.0, "Octaves spanned"),
+    ParamSpec::whole("base_octave", 0.0, 8.0, 4.0, "Lowest octave (middle C = 4)"),
+    ParamSpec::whole("velocity", 1.0, 127.0, 100.0, "Base velocity"),
+    ParamSpec::whole("accent_velocity", 1.0, 127.0, 120.0, "Velocity on beat 1"),
+    ParamSpec::new("probability", 0.0, 1.0, 1.0, "Chance each note plays"),
+];
 
 /// Arpeggio pattern types
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -28,6 +49,8 @@ pub enum ArpPattern {
     Random,
     /// Play in order notes were defined
     Order,
+    /// Play a user-defined step order
+    Custom,
 }
 
 impl ArpPattern {
@@ -38,6 +61,7 @@ impl ArpPattern {
             2 => ArpPattern::UpDown,
             3 => ArpPattern::DownUp,
             4 => ArpPattern::Random,
+            6 => ArpPattern::Custom,
             _ => ArpPattern::Order,
         }
     }
@@ -50,10 +74,167 @@ impl ArpPattern {
             ArpPattern::DownUp => 3,
             ArpPattern::Random => 4,
             ArpPattern::Order => 5,
+            ArpPattern::Custom => 6,
         }
     }
 }
 
+/// A single step in a custom arpeggio order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArpStep {
+    /// Play a scale degree (1-based) shifted by whole octaves
+    Note { degree: usize, octave: i8 },
+    /// Skip this step
+    Rest,
+}
+
+impl ArpStep {
+    /// Create a note step with no octave shift
+    pub fn note(degree: usize) -> Self {
+        ArpStep::Note { degree, octave: 0 }
+    }
+
+    /// Parse a single step token
+    ///
+    /// Tokens are a scale degree followed by optional octave marks
+    /// (`^` up, `v` down), or `r`/`.` for a rest. Examples: `3`, `1^`, `5vv`.
+    pub fn parse(token: &str) -> Result<Self> {
+        let token = token.trim();
+        if token.eq_ignore_ascii_case("r") || token == "." {
+            return Ok(ArpStep::Rest);
+        }
+
+        let digits: String = token.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let degree: usize = digits
+            .parse()
+            .map_err(|_| anyhow!("Invalid arpeggio step: {:?}", token))?;
+        if degree == 0 {
+            return Err(anyhow!("Arpeggio step degrees are 1-based: {:?}", token));
+        }
+
+        // Shifts stop at four octaves either way
+        let mut octave: i8 = 0;
+        for c in token[digits.len()..].chars() {
+            match c {
+                '^' => octave = (octave + 1).min(4),
+                'v' | 'V' => octave = (octave - 1).max(-4),
+                _ => return Err(anyhow!("Invalid octave mark in arpeggio step: {:?}", token)),
+            }
+        }
+
+        Ok(ArpStep::Note { degree, octave })
+    }
+
+    /// Parse a full step order such as `1-3-2-4` or `1 5 r 1^`
+    pub fn parse_order(order: &str) -> Result<Vec<Self>> {
+        let steps: Vec<Self> = order
+            .split(|c: char| c == '-' || c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .map(Self::parse)
+            .collect::<Result<_>>()?;
+
+        if steps.is_empty() {
+            return Err(anyhow!("Arpeggio step order is empty"));
+        }
+        Ok(steps)
+    }
+
+    /// Format a step order back to its text form
+    pub fn format_order(steps: &[Self]) -> String {
+        steps
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+}
+
+impl fmt::Display for ArpStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArpStep::Rest => write!(f, "r"),
+            ArpStep::Note { degree, octave } => {
+                write!(f, "{}", degree)?;
+                let mark = if *octave > 0 { "^" } else { "v" };
+                for _ in 0..octave.unsigned_abs() {
+                    write!(f, "{}", mark)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Named collection of custom arpeggio step orders
+#[derive(Debug, Clone, Default)]
+pub struct ArpPresetLibrary {
+    presets: HashMap<String, Vec<ArpStep>>,
+}
+
+impl ArpPresetLibrary {
+    /// Create an empty library
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a library with the built-in presets
+    pub fn with_builtins() -> Self {
+        let mut library = Self::new();
+        for (name, order) in [
+            ("alberti", "1-5-3-5"),
+            ("broken-thirds", "1-3-2-4-3-5-4-6"),
+            ("pedal", "1-3-1-4-1-5-1-4"),
+            ("octave-jump", "1-5-1^-5"),
+            ("gallop", "1-1-r-3-3-r-5-5"),
+        ] {
+            if let Ok(steps) = ArpStep::parse_order(order) {
+                library.insert(name, steps);
+            }
+        }
+        library
+    }
+
+    /// Add user presets from text step orders (e.g. the song file's `arp_presets`)
+    pub fn load_strings(&mut self, presets: &HashMap<String, String>) -> Result<()> {
+        for (name, order) in presets {
+            let steps = ArpStep::parse_order(order)
+                .map_err(|e| anyhow!("Arpeggio preset {:?}: {}", name, e))?;
+            self.insert(name, steps);
+        }
+        Ok(())
+    }
+
+    /// Add or replace a preset
+    pub fn insert(&mut self, name: &str, steps: Vec<ArpStep>) {
+        self.presets.insert(name.to_string(), steps);
+    }
+
+    /// Remove a preset
+    pub fn remove(&mut self, name: &str) -> Option<Vec<ArpStep>> {
+        self.presets.remove(name)
+    }
+
+    /// Get a preset by name
+    pub fn get(&self, name: &str) -> Option<&[ArpStep]> {
+        self.presets.get(name).map(|s| s.as_slice())
+    }
+
+    /// Get sorted preset names
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.presets.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Export presets as text step orders for saving to the song file
+    pub fn to_strings(&self) -> HashMap<String, String> {
+        self.presets
+            .iter()
+            .map(|(name, steps)| (name.clone(), ArpStep::format_order(steps)))
+            .collect()
+    }
+}
+
 /// Configuration for arpeggiator
 #[derive(Debug, Clone)]
 struct ArpConfig {
@@ -73,14 +254,12 @@ struct ArpConfig {
     accent_velocity: u8,
     /// Probability of playing each note (0.0 - 1.0)
     probability: f64,
-    /// Use Euclidean rhythm
-    euclidean: bool,
-    /// Euclidean pattern: number of hits
-    euclidean_hits: u8,
-    /// Euclidean pattern: number of steps
-    euclidean_steps: u8,
+    /// Euclidean rhythm over the steps
+    euclidean: EuclideanGate,
     /// Scale degrees to include (empty = all)
     degrees: Vec<usize>,
+    /// Step order used by the custom pattern
+    steps: Vec<ArpStep>,
 }
 
 impl Default for ArpConfig {
@@ -94,10 +273,9 @@ impl Default for ArpConfig {
             velocity: 100,
             accent_velocity: 120,
             probability: 1.0,
-            euclidean: false,
-            euclidean_hits: 5,
-            euclidean_steps: 8,
+            euclidean: EuclideanGate::default(),
             degrees: vec![], // All degrees
+            steps: vec![ArpStep::note(1), ArpStep::note(3), ArpStep::note(5)],
         }
     }
 }
@@ -113,8 +291,12 @@ pub struct ArpeggioGenerator {
     euclidean_pattern: Vec<bool>,
     /// Current step in euclidean pattern
     euclidean_step: usize,
+    /// Step pattern adopted from another track (replaces the euclidean one)
+    rhythm: Option<Vec<bool>>,
     /// Notes in current arpeggio
     note_sequence: Vec<u8>,
+    /// Resolved custom steps (None = rest)
+    step_sequence: Vec<Option<u8>>,
     /// Accumulated ticks for timing
     tick_accumulator: u64,
     rng: StdRng,
@@ -129,7 +311,9 @@ impl ArpeggioGenerator {
             direction_up: true,
             euclidean_pattern: Vec::new(),
             euclidean_step: 0,
+            rhythm: None,
             note_sequence: Vec::new(),
+            step_sequence: Vec::new(),
             tick_accumulator: 0,
             rng: StdRng::from_entropy(),
         }
@@ -140,41 +324,35 @@ impl ArpeggioGenerator {
         Box::new(Self::new())
     }
 
-    /// Generate Euclidean rhythm pattern
-    fn generate_euclidean(hits: usize, steps: usize) -> Vec<bool> {
-        if steps == 0 {
-            return vec![];
-        }
-        if hits >= steps {
-            return vec![true; steps];
-        }
-        if hits == 0 {
-            return vec![false; steps];
-        }
-
-        // Bjorklund's algorithm
-        let mut pattern = vec![vec![true]; hits];
-        let mut remainder = vec![vec![false]; steps - hits];
+    /// Get the custom step order
+    pub fn steps(&self) -> &[ArpStep] {
+        &self.config.steps
+    }
 
-        while remainder.len() > 1 {
-            let min_len = pattern.len().min(remainder.len());
-            for i in 0..min_len {
-                pattern[i].extend(remainder[i].clone());
-            }
-            let new_remainder: Vec<Vec<bool>> = if pattern.len() > min_len {
-                pattern.drain(min_len..).collect()
-            } else {
-                remainder.drain(min_len..).collect()
-            };
-            remainder = new_remainder;
+    /// Set a custom step order and switch to the custom pattern
+    pub fn set_steps(&mut self, steps: Vec<ArpStep>) {
+        if steps.is_empty() {
+            return;
         }
+        self.config.steps = steps;
+        self.config.pattern = ArpPattern::Custom;
+        self.position = 0;
+        self.note_sequence.clear();
+    }
 
-        // Flatten and append remainder
-        let mut result: Vec<bool> = pattern.into_iter().flatten().collect();
-        for r in remainder {
-            result.extend(r);
-        }
-        result
+    /// Set a custom step order from text (e.g. `1-3-2-4`)
+    pub fn set_step_order(&mut self, order: &str) -> Result<()> {
+        self.set_steps(ArpStep::parse_order(order)?);
+        Ok(())
+    }
+
+    /// Load a named preset from a library
+    pub fn load_preset(&mut self, library: &ArpPresetLibrary, name: &str) -> Result<()> {
+        let steps = library
+            .get(name)
+            .ok_or_else(|| anyhow!("Unknown arpeggio preset: {}", name))?;
+        self.set_steps(steps.to_vec());
+        Ok(())
     }
 
     /// Build the note sequence based on scale and configuration
@@ -187,13 +365,37 @@ impl ArpeggioGenerator {
         };
 
         self.note_sequence.clear();
+        self.step_sequence.clear();
+
+        // Resolve custom steps against the scale; degrees past the top wrap upward
+        if self.config.pattern == ArpPattern::Custom && !scale.is_empty() {
+            for step in &self.config.steps {
+                let note = match *step {
+                    ArpStep::Rest => None,
+                    ArpStep::Note { degree, octave } => {
+                        let wrapped = (degree - 1) % scale.len() + 1;
+                        let octave = i32::try_from((degree - 1) / scale.len())
+                            .unwrap_or(i32::MAX)
+                            .saturating_add(octave as i32 + self.config.base_octave as i32)
+                            .clamp(i8::MIN as i32, i8::MAX as i32) as i8;
+                        scale.midi_note_at(wrapped, octave)
+                    }
+                };
+                if let Some(n) = note {
+                    self.note_sequence.push(n);
+                }
+                self.step_sequence.push(note);
+            }
+        }
 
         // Build notes across octaves
-        for octave_offset in 0..self.config.octaves {
-            let octave = self.config.base_octave + octave_offset as i8;
-            for &degree in &degrees {
-                if let Some(note) = scale.midi_note_at(degree, octave) {
-                    self.note_sequence.push(note);
+        if self.config.pattern != ArpPattern::Custom {
+            for octave_offset in 0..self.config.octaves {
+                let octave = self.config.base_octave + octave_offset as i8;
+                for &degree in &degrees {
+                    if let Some(note) = scale.midi_note_at(degree, octave) {
+                        self.note_sequence.push(note);
+                    }
                 }
             }
         }
@@ -211,11 +413,11 @@ impl ArpeggioGenerator {
         }
 
         // Update euclidean pattern if enabled
-        if self.config.euclidean {
-            self.euclidean_pattern = Self::generate_euclidean(
-                self.config.euclidean_hits as usize,
-                self.config.euclidean_steps as usize,
-            );
+        if self.config.euclidean.enabled {
+            self.euclidean_pattern = match &self.rhythm {
+                Some(rhythm) => rhythm.clone(),
+                None => self.config.euclidean.pattern().to_vec(),
+            };
         }
     }
 
@@ -226,6 +428,14 @@ impl ArpeggioGenerator {
         }
 
         let note = match self.config.pattern {
+            ArpPattern::Custom => {
+                if self.step_sequence.is_empty() {
+                    return None;
+                }
+                let step = self.step_sequence[self.position % self.step_sequence.len()];
+                self.position = (self.position + 1) % self.step_sequence.len();
+                return step;
+            }
             ArpPattern::Random => {
                 let idx = self.rng.gen_range(0..self.note_sequence.len());
                 self.note_sequence[idx]
@@ -278,7 +488,7 @@ impl ArpeggioGenerator {
 
     /// Check if current euclidean step should play
     fn should_play_euclidean(&mut self) -> bool {
-        if !self.config.euclidean || self.euclidean_pattern.is_empty() {
+        if !self.config.euclidean.enabled || self.euclidean_pattern.is_empty() {
             return true;
         }
         let should_play = self.euclidean_pattern[self.euclidean_step];
@@ -301,7 +511,12 @@ impl Generator for ArpeggioGenerator {
         }
 
         let mut events = Vec::new();
-        let ticks_per_note = context.note_duration(self.config.rate);
+        // A fill doubles the rate
+        let ticks_per_note = if context.fill {
+            (context.note_duration(self.config.rate) / 2).max(1)
+        } else {
+            context.note_duration(self.config.rate)
+        };
         let note_duration = (ticks_per_note as f64 * self.config.gate) as u64;
 
         // Process each tick position
@@ -351,20 +566,19 @@ impl Generator for ArpeggioGenerator {
             "velocity" => self.config.velocity = (value as u8).clamp(1, 127),
             "accent_velocity" => self.config.accent_velocity = (value as u8).clamp(1, 127),
             "probability" => self.config.probability = value.clamp(0.0, 1.0),
-            "euclidean" => self.config.euclidean = value > 0.5,
-            "euclidean_hits" => self.config.euclidean_hits = (value as u8).clamp(1, 32),
-            "euclidean_steps" => self.config.euclidean_steps = (value as u8).clamp(1, 32),
+            "euclidean" => self.config.euclidean.enabled = value > 0.5,
+            "euclidean_hits" => self.config.euclidean.set_hits((value as u8).clamp(1, 32)),
+            "euclidean_steps" => self.config.euclidean.set_steps((value as u8).clamp(1, 32)),
+            "euclidean_rotation" => self.config.euclidean.set_rotation((value as u8).min(31)),
             _ => {}
         }
         // Rebuild sequence when relevant params change
         if matches!(name, "octaves" | "base_octave" | "pattern") {
             self.note_sequence.clear();
         }
-        if matches!(name, "euclidean_hits" | "euclidean_steps") {
-            self.euclidean_pattern = Self::generate_euclidean(
-                self.config.euclidean_hits as usize,
-                self.config.euclidean_steps as usize,
-            );
+        if matches!(name, "euclidean_hits" | "euclidean_steps" | "euclidean_rotation") {
+            self.rhythm = None;
+            self.euclidean_pattern = self.config.euclidean.pattern().to_vec();
         }
     }
 
@@ -378,9 +592,10 @@ impl Generator for ArpeggioGenerator {
             "velocity" => Some(self.config.velocity as f64),
             "accent_velocity" => Some(self.config.accent_velocity as f64),
             "probability" => Some(self.config.probability),
-            "euclidean" => Some(if self.config.euclidean { 1.0 } else { 0.0 }),
-            "euclidean_hits" => Some(self.config.euclidean_hits as f64),
-            "euclidean_steps" => Some(self.config.euclidean_steps as f64),
+            "euclidean" => Some(if self.config.euclidean.enabled { 1.0 } else { 0.0 }),
+            "euclidean_hits" => Some(self.config.euclidean.hits() as f64),
+            "euclidean_steps" => Some(self.config.euclidean.steps() as f64),
+            "euclidean_rotation" => Some(self.config.euclidean.rotation() as f64),
             _ => None,
         }
     }
@@ -391,12 +606,40 @@ impl Generator for ArpeggioGenerator {
         self.euclidean_step = 0;
         self.tick_accumulator = 0;
         self.note_sequence.clear();
+        self.step_sequence.clear();
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn inspire(&mut self, inspiration: &Inspiration) -> bool {
+        let mut took = false;
+        if inspiration.has_rhythm() {
+            self.config.rate = inspiration.division.clamp(1, 64);
+            self.config.euclidean.enabled = true;
+            self.rhythm = Some(inspiration.rhythm.clone());
+            self.euclidean_pattern = inspiration.rhythm.clone();
+            self.euclidean_step = 0;
+            took = true;
+        }
+        if let (Some(octave), Some(span)) = (inspiration.base_octave(), inspiration.octave_span()) {
+            self.config.base_octave = octave.clamp(0, 8);
+            self.config.octaves = span.clamp(1, 4);
+            self.note_sequence.clear();
+            took = true;
+        }
+        took
     }
 
     fn name(&self) -> &'static str {
         "arpeggio"
     }
 
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        [PARAMS, &EUCLIDEAN_PARAMS].concat()
+    }
+
     fn params(&self) -> HashMap<String, f64> {
         let mut params = HashMap::new();
         params.insert("pattern".to_string(), self.config.pattern.to_value() as f64);
@@ -407,9 +650,10 @@ impl Generator for ArpeggioGenerator {
         params.insert("velocity".to_string(), self.config.velocity as f64);
         params.insert("accent_velocity".to_string(), self.config.accent_velocity as f64);
         params.insert("probability".to_string(), self.config.probability);
-        params.insert("euclidean".to_string(), if self.config.euclidean { 1.0 } else { 0.0 });
-        params.insert("euclidean_hits".to_string(), self.config.euclidean_hits as f64);
-        params.insert("euclidean_steps".to_string(), self.config.euclidean_steps as f64);
+        params.insert("euclidean".to_string(), if self.config.euclidean.enabled { 1.0 } else { 0.0 });
+        params.insert("euclidean_hits".to_string(), self.config.euclidean.hits() as f64);
+        params.insert("euclidean_steps".to_string(), self.config.euclidean.steps() as f64);
+        params.insert("euclidean_rotation".to_string(), self.config.euclidean.rotation() as f64);
         params
     }
 }
@@ -446,22 +690,31 @@ mod tests {
     }
 
     #[test]
-    fn test_euclidean_rhythm() {
-        // Classic 3-over-8 euclidean
-        let pattern = ArpeggioGenerator::generate_euclidean(3, 8);
-        assert_eq!(pattern.len(), 8);
-        assert_eq!(pattern.iter().filter(|&&b| b).count(), 3);
-
-        // 5-over-8
-        let pattern = ArpeggioGenerator::generate_euclidean(5, 8);
-        assert_eq!(pattern.iter().filter(|&&b| b).count(), 5);
+    fn test_fill_doubles_rate() {
+        let mut arp = ArpeggioGenerator::new();
+        arp.set_param("rate", 4.0);
+        assert_eq!(arp.generate(&test_context()).len(), 4);
 
-        // Edge cases
-        let pattern = ArpeggioGenerator::generate_euclidean(0, 8);
-        assert!(pattern.iter().all(|&b| !b));
+        let fill = GeneratorContext {
+            fill: true,
+            ..test_context()
+        };
+        let starts: Vec<u64> = arp.generate(&fill).iter().map(|e| e.start_tick).collect();
+        assert_eq!(starts, vec![0, 12, 24, 36, 48, 60, 72, 84]);
+    }
 
-        let pattern = ArpeggioGenerator::generate_euclidean(8, 8);
-        assert!(pattern.iter().all(|&b| b));
+    #[test]
+    fn test_euclidean_rhythm() {
+        let mut arp = ArpeggioGenerator::new();
+        arp.set_param("euclidean", 1.0);
+        arp.set_param("euclidean_hits", 3.0);
+        arp.set_param("euclidean_rotation", 1.0);
+        arp.set_param("rate", 8.0);
+
+        // Eighth-note steps on 3-in-8 moved one step later
+        let starts: Vec<u64> = arp.generate(&test_context()).iter().map(|e| e.start_tick).collect();
+        assert_eq!(starts, vec![12, 48, 84]);
+        assert_eq!(arp.get_param("euclidean_rotation"), Some(1.0));
     }
 
     #[test]
@@ -526,4 +779,71 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_parse_step_order() {
+        let steps = ArpStep::parse_order("1-3-2-4").unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[1], ArpStep::note(3));
+
+        let steps = ArpStep::parse_order("1 r 5^ 3vv").unwrap();
+        assert_eq!(steps[1], ArpStep::Rest);
+        assert_eq!(steps[2], ArpStep::Note { degree: 5, octave: 1 });
+        assert_eq!(steps[3], ArpStep::Note { degree: 3, octave: -2 });
+        assert_eq!(ArpStep::format_order(&steps), "1-r-5^-3vv");
+
+        assert!(ArpStep::parse_order("").is_err());
+        assert!(ArpStep::parse_order("0-1").is_err());
+        assert!(ArpStep::parse_order("1-x").is_err());
+
+        let high = format!("1{}", "^".repeat(200));
+        assert_eq!(ArpStep::parse(&high).unwrap(), ArpStep::Note { degree: 1, octave: 4 });
+    }
+
+    #[test]
+    fn test_custom_step_order() {
+        let mut arp = ArpeggioGenerator::new();
+        arp.set_param("rate", 4.0);
+        arp.set_step_order("1-3-r-1^").unwrap();
+        assert_eq!(arp.get_param("pattern"), Some(6.0));
+
+        let events = arp.generate(&test_context());
+        let notes: Vec<u8> = events.iter().map(|e| e.note).collect();
+        // C4, E4, rest, C5
+        assert_eq!(notes, vec![60, 64, 72]);
+        assert_eq!(events[2].start_tick, 72);
+    }
+
+    #[test]
+    fn test_custom_degree_wraps_octave() {
+        let mut arp = ArpeggioGenerator::new();
+        arp.set_param("rate", 4.0);
+        arp.set_step_order("8").unwrap();
+
+        let events = arp.generate(&test_context());
+        assert!(events.iter().all(|e| e.note == 72));
+
+        // Far past the top of the keyboard: no notes, no overflow
+        arp.set_step_order("2000").unwrap();
+        assert!(arp.generate(&test_context()).is_empty());
+    }
+
+    #[test]
+    fn test_preset_library() {
+        let mut library = ArpPresetLibrary::with_builtins();
+        assert!(library.get("alberti").is_some());
+
+        let mut user = HashMap::new();
+        user.insert("mine".to_string(), "1-r-5-3^".to_string());
+        library.load_strings(&user).unwrap();
+        assert_eq!(library.to_strings().get("mine"), Some(&"1-r-5-3^".to_string()));
+
+        let mut arp = ArpeggioGenerator::new();
+        arp.load_preset(&library, "mine").unwrap();
+        assert_eq!(arp.steps().len(), 4);
+        assert!(arp.load_preset(&library, "missing").is_err());
+
+        user.insert("bad".to_string(), "1-?".to_string());
+        assert!(library.load_strings(&user).is_err());
+    }
 }
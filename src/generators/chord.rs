@@ -11,7 +11,23 @@ use std::collections::HashMap;
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 
-use super::{Generator, GeneratorContext, MidiEvent};
+use super::{
+    GlideConfig, Generator, GeneratorContext, MidiEvent, PitchBendEvent, ParamSpec, EUCLIDEAN_PARAMS, GLIDE_PARAMS,
+};
+use crate::music::EuclideanGate;
+
+/// Parameter ranges
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::whole("voicing", 0.0, 3.0, 0.0, "Voicing: 0 close, 1 open, 2 drop 2, 3 spread"),
+    ParamSpec::whole("inversion_mode", 0.0, 3.0, 0.0, "Inversions: 0 root, 1 random, 2 voice-led, 3 ascending"),
+    ParamSpec::whole("progression_mode", 0.0, 2.0, 0.0, "Progression: 0 functional, 1 random in key, 2 custom"),
+    ParamSpec::new("change_rate", 0.25, 64.0, 4.0, "How often the chord changes").with_unit("beats"),
+    ParamSpec::whole("base_octave", 1.0, 6.0, 3.0, "Lowest octave (middle C = 4)"),
+    ParamSpec::whole("velocity", 1.0, 127.0, 90.0, "Base velocity"),
+    ParamSpec::new("seventh_probability", 0.0, 1.0, 0.3, "Chance of adding a 7th"),
+    ParamSpec::new("ninth_probability", 0.0, 1.0, 0.1, "Chance of adding a 9th"),
+    ParamSpec::new("sus_probability", 0.0, 1.0, 0.1, "Chance of a suspended chord"),
+];
 
 /// Chord voicing types
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -131,6 +147,10 @@ struct ChordConfig {
     sus_probability: f64,
     /// Custom progression (scale degrees)
     custom_progression: Vec<u8>,
+    /// Pitch-bend glide between chord roots
+    glide: GlideConfig,
+    /// Euclidean rhythm the chord is struck on (off = held)
+    euclidean: EuclideanGate,
 }
 
 impl Default for ChordConfig {
@@ -146,6 +166,8 @@ impl Default for ChordConfig {
             ninth_probability: 0.1,
             sus_probability: 0.1,
             custom_progression: vec![1, 4, 5, 1], // I-IV-V-I
+            glide: GlideConfig::default(),
+            euclidean: EuclideanGate::default(),
         }
     }
 }
@@ -163,6 +185,10 @@ pub struct ChordGenerator {
     tick_accumulator: u64,
     /// Current inversion for ascending mode
     current_inversion: u8,
+    /// Root note of the current chord
+    root_note: Option<u8>,
+    /// Pitch bends from the last generate call
+    pending_bends: Vec<PitchBendEvent>,
     rng: StdRng,
 }
 
@@ -176,6 +202,8 @@ impl ChordGenerator {
             previous_chord: Vec::new(),
             tick_accumulator: 0,
             current_inversion: 0,
+            root_note: None,
+            pending_bends: Vec::new(),
             rng: StdRng::from_entropy(),
         }
     }
@@ -408,16 +436,47 @@ impl Generator for ChordGenerator {
             self.previous_chord = self.current_chord.clone();
             let root = self.next_root_degree();
             self.current_chord = self.build_chord(root, context);
+
+            // Glide from the previous root
+            let scale = context.scale();
+            let degree = ((root as usize - 1) % scale.len()) + 1;
+            let root_note = scale.midi_note_at(degree, self.config.base_octave);
+            if let (Some(from), Some(to)) = (self.root_note, root_note) {
+                self.pending_bends = self.config.glide.glide(from, to, context.ppqn, scale);
+            }
+            self.root_note = root_note;
         }
 
-        // Generate events for current chord
-        for &note in &self.current_chord {
-            events.push(MidiEvent::new(
-                note,
-                self.config.velocity,
-                0,
-                context.ticks_to_generate,
-            ));
+        // Generate events for current chord: restruck every eighth during a fill,
+        // otherwise held or struck on the gate's hits
+        let gate = &self.config.euclidean;
+        if context.fill {
+            let origin = context.total_ticks();
+            let eighth = (context.ppqn as u64 / 2).max(1);
+            let first = origin.div_ceil(eighth) * eighth;
+            for hit in (first..origin + context.ticks_to_generate).step_by(eighth as usize) {
+                for &note in &self.current_chord {
+                    events.push(MidiEvent::new(note, self.config.velocity, hit - origin, eighth));
+                }
+            }
+        } else if gate.enabled {
+            let origin = context.total_ticks();
+            let ticks_per_bar = context.ticks_per_bar();
+            let length = gate.step_ticks(ticks_per_bar);
+            for hit in gate.hits_between(origin, origin + context.ticks_to_generate, ticks_per_bar) {
+                for &note in &self.current_chord {
+                    events.push(MidiEvent::new(note, self.config.velocity, hit - origin, length));
+                }
+            }
+        } else {
+            for &note in &self.current_chord {
+                events.push(MidiEvent::new(
+                    note,
+                    self.config.velocity,
+                    0,
+                    context.ticks_to_generate,
+                ));
+            }
         }
 
         self.tick_accumulator += context.ticks_to_generate;
@@ -435,6 +494,13 @@ impl Generator for ChordGenerator {
             "seventh_probability" => self.config.seventh_probability = value.clamp(0.0, 1.0),
             "ninth_probability" => self.config.ninth_probability = value.clamp(0.0, 1.0),
             "sus_probability" => self.config.sus_probability = value.clamp(0.0, 1.0),
+            "glide_time" => self.config.glide.glide_time = value.clamp(0.0, 16.0),
+            "bend_range" => self.config.glide.bend_range = (value as u8).clamp(1, 48),
+            "glide_scale_lock" => self.config.glide.scale_locked = value >= 0.5,
+            "euclidean" => self.config.euclidean.enabled = value > 0.5,
+            "euclidean_hits" => self.config.euclidean.set_hits((value as u8).clamp(1, 32)),
+            "euclidean_steps" => self.config.euclidean.set_steps((value as u8).clamp(1, 32)),
+            "euclidean_rotation" => self.config.euclidean.set_rotation((value as u8).min(31)),
             _ => {}
         }
     }
@@ -450,6 +516,13 @@ impl Generator for ChordGenerator {
             "seventh_probability" => Some(self.config.seventh_probability),
             "ninth_probability" => Some(self.config.ninth_probability),
             "sus_probability" => Some(self.config.sus_probability),
+            "glide_time" => Some(self.config.glide.glide_time),
+            "bend_range" => Some(self.config.glide.bend_range as f64),
+            "glide_scale_lock" => Some(if self.config.glide.scale_locked { 1.0 } else { 0.0 }),
+            "euclidean" => Some(if self.config.euclidean.enabled { 1.0 } else { 0.0 }),
+            "euclidean_hits" => Some(self.config.euclidean.hits() as f64),
+            "euclidean_steps" => Some(self.config.euclidean.steps() as f64),
+            "euclidean_rotation" => Some(self.config.euclidean.rotation() as f64),
             _ => None,
         }
     }
@@ -460,12 +533,22 @@ impl Generator for ChordGenerator {
         self.previous_chord.clear();
         self.tick_accumulator = 0;
         self.current_inversion = 0;
+        self.root_note = None;
+        self.pending_bends.clear();
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
     }
 
     fn name(&self) -> &'static str {
         "chord"
     }
 
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        [PARAMS, &GLIDE_PARAMS, &EUCLIDEAN_PARAMS].concat()
+    }
+
     fn params(&self) -> HashMap<String, f64> {
         let mut params = HashMap::new();
         params.insert("voicing".to_string(), self.config.voicing.to_value() as f64);
@@ -477,8 +560,22 @@ impl Generator for ChordGenerator {
         params.insert("seventh_probability".to_string(), self.config.seventh_probability);
         params.insert("ninth_probability".to_string(), self.config.ninth_probability);
         params.insert("sus_probability".to_string(), self.config.sus_probability);
+        params.insert("glide_time".to_string(), self.config.glide.glide_time);
+        params.insert("bend_range".to_string(), self.config.glide.bend_range as f64);
+        params.insert(
+            "glide_scale_lock".to_string(),
+            if self.config.glide.scale_locked { 1.0 } else { 0.0 },
+        );
+        params.insert("euclidean".to_string(), if self.config.euclidean.enabled { 1.0 } else { 0.0 });
+        params.insert("euclidean_hits".to_string(), self.config.euclidean.hits() as f64);
+        params.insert("euclidean_steps".to_string(), self.config.euclidean.steps() as f64);
+        params.insert("euclidean_rotation".to_string(), self.config.euclidean.rotation() as f64);
         params
     }
+
+    fn take_pitch_bends(&mut self) -> Vec<PitchBendEvent> {
+        std::mem::take(&mut self.pending_bends)
+    }
 }
 
 #[cfg(test)]
@@ -514,6 +611,25 @@ mod tests {
         assert!(events.len() >= 3);
     }
 
+    #[test]
+    fn test_fill_restrikes_chord() {
+        let mut chord = ChordGenerator::new();
+        chord.set_param("change_rate", 8.0);
+        let held = chord.generate(&test_context());
+        assert!(held.iter().all(|e| e.start_tick == 0));
+
+        let fill = GeneratorContext {
+            fill: true,
+            bar: 1,
+            ..test_context()
+        };
+        let events = chord.generate(&fill);
+        let mut starts: Vec<u64> = events.iter().map(|e| e.start_tick).collect();
+        starts.dedup();
+        assert_eq!(starts, vec![0, 12, 24, 36, 48, 60, 72, 84]);
+        assert_eq!(events.len(), held.len() * 8);
+    }
+
     #[test]
     fn test_chord_notes_in_scale() {
         let mut chord = ChordGenerator::new();
@@ -569,6 +685,40 @@ mod tests {
         assert_eq!(chord.tick_accumulator, 0);
     }
 
+    #[test]
+    fn test_chord_root_glide() {
+        let mut chord = ChordGenerator::new();
+        chord.set_param("progression_mode", 2.0); // Custom I-IV-V-I
+        chord.set_param("glide_time", 0.5);
+        chord.set_param("bend_range", 12.0);
+
+        let ctx = test_context();
+        chord.generate(&ctx);
+        assert!(chord.take_pitch_bends().is_empty());
+
+        // I -> IV: start bent down a fourth, end centered
+        chord.generate(&ctx);
+        let bends = chord.take_pitch_bends();
+        assert_eq!(bends.first().unwrap().value, chord.config.glide.bend_value(-5.0));
+        assert_eq!(bends.last().unwrap().value, 0);
+        assert!(chord.take_pitch_bends().is_empty());
+    }
+
+    #[test]
+    fn test_chord_euclidean_gate() {
+        let mut chord = ChordGenerator::new();
+        chord.set_param("euclidean", 1.0);
+        chord.set_param("euclidean_hits", 3.0);
+        chord.set_param("euclidean_rotation", 2.0);
+
+        // Struck on the 3-in-8 hits moved two steps later, an eighth each
+        let events = chord.generate(&test_context());
+        let size = chord.current_chord.len();
+        let starts: Vec<u64> = events.iter().step_by(size).map(|e| e.start_tick).collect();
+        assert_eq!(starts, vec![0, 24, 60]);
+        assert!(events.iter().all(|e| e.duration_ticks == 12));
+    }
+
     #[test]
     fn test_inversion_modes() {
         assert_eq!(InversionMode::from_value(0), InversionMode::Root);
@@ -4,14 +4,42 @@
 //! Drone generator for sustained pad-like sounds.
 //!
 //! Generates sustained notes with slow movement between scale tones,
-//! featuring voice leading and configurable density.
+//! featuring voice leading and configurable density. One or two
+//! controllers (say CC1 and CC74) can sweep along with the drone to move
+//! a hardware synth's filter or modulation.
 
 use std::collections::HashMap;
+use std::f64::consts::TAU;
 
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 
-use super::{Generator, GeneratorContext, MidiEvent};
+use super::{
+    ControlEvent, GlideConfig, Generator, GeneratorContext, MidiEvent, NoteExpression, PitchBendEvent, ParamSpec,
+    GLIDE_PARAMS,
+};
+
+/// Ticks between swept controller values
+const SWEEP_RESOLUTION: u64 = 6;
+
+/// Parameter ranges
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::whole("voices", 1.0, 8.0, 3.0, "Number of held voices"),
+    ParamSpec::new("change_rate", 0.0, 64.0, 4.0, "How often notes change (0 = never)").with_unit("beats"),
+    ParamSpec::new("change_probability", 0.0, 1.0, 0.5, "Chance a voice changes when due"),
+    ParamSpec::whole("velocity", 1.0, 127.0, 80.0, "Base velocity"),
+    ParamSpec::whole("velocity_variation", 0.0, 64.0, 10.0, "Random velocity spread (+/-)"),
+    ParamSpec::whole("interval_preference", 0.0, 3.0, 0.0, "Preferred intervals: 0 any, 1 root, 2 fifth, 3 thirds"),
+    ParamSpec::whole("max_jump", 1.0, 7.0, 2.0, "Largest move in scale degrees"),
+    ParamSpec::whole("base_octave", 0.0, 8.0, 3.0, "Lowest octave (middle C = 4)"),
+    ParamSpec::whole("octave_spread", 0.0, 4.0, 2.0, "Octaves the voices spread over"),
+    ParamSpec::new("pitch_drift", 0.0, 50.0, 0.0, "Slow per-voice detune drift (MPE tracks)").with_unit("cents"),
+    ParamSpec::whole("sweep_cc", -1.0, 127.0, -1.0, "Controller swept with the drone (-1 = off)"),
+    ParamSpec::whole("sweep_cc2", -1.0, 127.0, -1.0, "Second controller, a quarter cycle behind (-1 = off)"),
+    ParamSpec::new("sweep_rate", 1.0, 64.0, 16.0, "Sweep cycle").with_unit("beats"),
+    ParamSpec::whole("sweep_min", 0.0, 127.0, 30.0, "Lowest swept value"),
+    ParamSpec::whole("sweep_max", 0.0, 127.0, 100.0, "Highest swept value"),
+];
 
 /// Configuration for drone behavior
 #[derive(Debug, Clone)]
@@ -34,6 +62,17 @@ struct DroneConfig {
     base_octave: i8,
     /// Octave spread for voices
     octave_spread: u8,
+    /// Largest per-voice detune drift in cents (0 = none)
+    pitch_drift: f64,
+    /// Pitch-bend glide when the root voice moves
+    glide: GlideConfig,
+    /// Swept controllers (None = off)
+    sweep_cc: [Option<u8>; 2],
+    /// Sweep cycle in beats
+    sweep_rate: f64,
+    /// Lowest and highest swept value
+    sweep_min: u8,
+    sweep_max: u8,
 }
 
 impl Default for DroneConfig {
@@ -48,6 +87,12 @@ impl Default for DroneConfig {
             max_jump: 2,
             base_octave: 3,
             octave_spread: 2,
+            pitch_drift: 0.0,
+            glide: GlideConfig::default(),
+            sweep_cc: [None, None],
+            sweep_rate: 16.0,
+            sweep_min: 30,
+            sweep_max: 100,
         }
     }
 }
@@ -63,6 +108,8 @@ struct Voice {
     active: bool,
     /// Ticks until this voice should change
     change_in: u64,
+    /// Current detune in cents
+    drift: f64,
 }
 
 /// Drone generator
@@ -70,6 +117,14 @@ pub struct DroneGenerator {
     config: DroneConfig,
     voices: Vec<Voice>,
     last_change_tick: u64,
+    /// Root voice note from the last generate call
+    last_root: Option<u8>,
+    /// Pitch bends from the last generate call
+    pending_bends: Vec<PitchBendEvent>,
+    /// Swept control changes from the last generate call
+    pending_controls: Vec<ControlEvent>,
+    /// Value last sent on each swept controller
+    last_sweep: [Option<u8>; 2],
     rng: StdRng,
 }
 
@@ -80,6 +135,10 @@ impl DroneGenerator {
             config: DroneConfig::default(),
             voices: Vec::new(),
             last_change_tick: 0,
+            last_root: None,
+            pending_bends: Vec::new(),
+            pending_controls: Vec::new(),
+            last_sweep: [None, None],
             rng: StdRng::from_entropy(),
         }
     }
@@ -121,6 +180,7 @@ impl DroneGenerator {
                     velocity,
                     active: true,
                     change_in: change_delay,
+                    drift: 0.0,
                 });
             }
         }
@@ -197,6 +257,32 @@ impl DroneGenerator {
 
         candidates[self.rng.gen_range(0..candidates.len())]
     }
+
+    /// Sweep the controllers over the ticks being generated
+    fn sweep(&mut self, context: &GeneratorContext) {
+        let start = context.total_ticks();
+        let period = ((self.config.sweep_rate * context.ppqn as f64) as u64).max(1);
+        let (low, high) = (self.config.sweep_min as f64, self.config.sweep_max as f64);
+        for offset in 0..context.ticks_to_generate {
+            let tick = start + offset;
+            if tick % SWEEP_RESOLUTION != 0 {
+                continue;
+            }
+            for (lane, cc) in self.config.sweep_cc.into_iter().enumerate() {
+                let Some(cc) = cc else {
+                    continue;
+                };
+                // The second controller runs a quarter cycle behind
+                let phase = (tick % period) as f64 / period as f64 + lane as f64 * 0.25;
+                let position = 0.5 - 0.5 * (TAU * phase).cos();
+                let value = (low + (high - low) * position).round().clamp(0.0, 127.0) as u8;
+                if self.last_sweep[lane] != Some(value) {
+                    self.last_sweep[lane] = Some(value);
+                    self.pending_controls.push(ControlEvent::new(cc, value, offset));
+                }
+            }
+        }
+    }
 }
 
 impl Default for DroneGenerator {
@@ -215,15 +301,26 @@ impl Generator for DroneGenerator {
 
         // First pass: generate events and track which voices need changes
         let mut needs_change = Vec::new();
-        for (i, voice) in self.voices.iter().enumerate() {
+        let drift = self.config.pitch_drift;
+        for (i, voice) in self.voices.iter_mut().enumerate() {
             // Generate sustained note for the duration
             if voice.active {
-                events.push(MidiEvent::new(
+                let mut event = MidiEvent::new(
                     voice.note,
                     voice.velocity,
                     0,
                     context.ticks_to_generate,
-                ));
+                );
+                // Detune wanders to a new random point over the note
+                if drift > 0.0 {
+                    let next = self.rng.gen_range(-drift..=drift);
+                    event = event.with_expression(NoteExpression {
+                        pitch: vec![(0, voice.drift / 100.0), (context.ticks_to_generate, next / 100.0)],
+                        ..NoteExpression::default()
+                    });
+                    voice.drift = next;
+                }
+                events.push(event);
             }
 
             // Check if voice should change
@@ -232,6 +329,14 @@ impl Generator for DroneGenerator {
             }
         }
 
+        // Glide when the root voice moved
+        if let Some(root) = self.voices.first().map(|v| v.note) {
+            if let Some(prev) = self.last_root {
+                self.pending_bends = self.config.glide.glide(prev, root, context.ppqn, context.scale());
+            }
+            self.last_root = Some(root);
+        }
+
         // Update change timers
         let voices_len = self.voices.len();
         for i in 0..voices_len {
@@ -251,6 +356,7 @@ impl Generator for DroneGenerator {
             }
         }
 
+        self.sweep(context);
         self.last_change_tick = current_tick;
         events
     }
@@ -266,6 +372,15 @@ impl Generator for DroneGenerator {
             "max_jump" => self.config.max_jump = (value as u8).clamp(1, 7),
             "base_octave" => self.config.base_octave = (value as i8).clamp(0, 8),
             "octave_spread" => self.config.octave_spread = (value as u8).min(4),
+            "pitch_drift" => self.config.pitch_drift = value.clamp(0.0, 50.0),
+            "glide_time" => self.config.glide.glide_time = value.clamp(0.0, 16.0),
+            "bend_range" => self.config.glide.bend_range = (value as u8).clamp(1, 48),
+            "glide_scale_lock" => self.config.glide.scale_locked = value >= 0.5,
+            "sweep_cc" => self.config.sweep_cc[0] = (value >= 0.0).then(|| value.min(127.0) as u8),
+            "sweep_cc2" => self.config.sweep_cc[1] = (value >= 0.0).then(|| value.min(127.0) as u8),
+            "sweep_rate" => self.config.sweep_rate = value.clamp(1.0, 64.0),
+            "sweep_min" => self.config.sweep_min = value.clamp(0.0, 127.0) as u8,
+            "sweep_max" => self.config.sweep_max = value.clamp(0.0, 127.0) as u8,
             _ => {}
         }
         // Reset voices when config changes significantly
@@ -285,6 +400,15 @@ impl Generator for DroneGenerator {
             "max_jump" => Some(self.config.max_jump as f64),
             "base_octave" => Some(self.config.base_octave as f64),
             "octave_spread" => Some(self.config.octave_spread as f64),
+            "pitch_drift" => Some(self.config.pitch_drift),
+            "glide_time" => Some(self.config.glide.glide_time),
+            "bend_range" => Some(self.config.glide.bend_range as f64),
+            "glide_scale_lock" => Some(if self.config.glide.scale_locked { 1.0 } else { 0.0 }),
+            "sweep_cc" => Some(self.config.sweep_cc[0].map_or(-1.0, |cc| cc as f64)),
+            "sweep_cc2" => Some(self.config.sweep_cc[1].map_or(-1.0, |cc| cc as f64)),
+            "sweep_rate" => Some(self.config.sweep_rate),
+            "sweep_min" => Some(self.config.sweep_min as f64),
+            "sweep_max" => Some(self.config.sweep_max as f64),
             _ => None,
         }
     }
@@ -292,12 +416,24 @@ impl Generator for DroneGenerator {
     fn reset(&mut self) {
         self.voices.clear();
         self.last_change_tick = 0;
+        self.last_root = None;
+        self.pending_bends.clear();
+        self.pending_controls.clear();
+        self.last_sweep = [None, None];
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
     }
 
     fn name(&self) -> &'static str {
         "drone"
     }
 
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        [PARAMS, &GLIDE_PARAMS].concat()
+    }
+
     fn params(&self) -> HashMap<String, f64> {
         let mut params = HashMap::new();
         params.insert("voices".to_string(), self.config.voices as f64);
@@ -309,8 +445,26 @@ impl Generator for DroneGenerator {
         params.insert("max_jump".to_string(), self.config.max_jump as f64);
         params.insert("base_octave".to_string(), self.config.base_octave as f64);
         params.insert("octave_spread".to_string(), self.config.octave_spread as f64);
+        params.insert("pitch_drift".to_string(), self.config.pitch_drift);
+        params.insert("glide_time".to_string(), self.config.glide.glide_time);
+        params.insert("bend_range".to_string(), self.config.glide.bend_range as f64);
+        params.insert(
+            "glide_scale_lock".to_string(),
+            if self.config.glide.scale_locked { 1.0 } else { 0.0 },
+        );
+        for name in ["sweep_cc", "sweep_cc2", "sweep_rate", "sweep_min", "sweep_max"] {
+            params.insert(name.to_string(), self.get_param(name).unwrap_or(0.0));
+        }
         params
     }
+
+    fn take_pitch_bends(&mut self) -> Vec<PitchBendEvent> {
+        std::mem::take(&mut self.pending_bends)
+    }
+
+    fn take_control_changes(&mut self) -> Vec<ControlEvent> {
+        std::mem::take(&mut self.pending_controls)
+    }
 }
 
 impl Clone for DroneGenerator {
@@ -319,6 +473,10 @@ impl Clone for DroneGenerator {
             config: self.config.clone(),
             voices: self.voices.clone(),
             last_change_tick: self.last_change_tick,
+            last_root: self.last_root,
+            pending_bends: self.pending_bends.clone(),
+            pending_controls: self.pending_controls.clone(),
+            last_sweep: self.last_sweep,
             rng: StdRng::from_entropy(),
         }
     }
@@ -410,4 +568,47 @@ mod tests {
 
         assert_eq!(events.len(), 5);
     }
+
+    #[test]
+    fn test_drone_pitch_drift() {
+        let mut drone = DroneGenerator::new();
+        drone.set_seed(7);
+        let ctx = test_context();
+        assert!(drone.generate(&ctx).iter().all(|e| e.expression.is_empty()));
+
+        drone.set_param("pitch_drift", 20.0);
+        let first = drone.generate(&ctx);
+        let second = drone.generate(&ctx);
+        for (a, b) in first.iter().zip(&second) {
+            let (_, end) = *a.expression.pitch.last().unwrap();
+            // Each note picks up where the voice's last one drifted to
+            assert_eq!(b.expression.pitch[0].1, end);
+            assert!(end.abs() <= 0.2);
+        }
+        assert_eq!(first[0].expression.pitch[0], (0, 0.0));
+    }
+
+    #[test]
+    fn test_drone_sweeps_controllers() {
+        let mut drone = DroneGenerator::new();
+        let ctx = test_context();
+        drone.generate(&ctx);
+        assert!(drone.take_control_changes().is_empty());
+
+        drone.set_param("sweep_cc", 74.0);
+        drone.set_param("sweep_cc2", 1.0);
+        drone.set_param("sweep_rate", 4.0);
+        drone.generate(&ctx);
+        let controls = drone.take_control_changes();
+        // The first controller starts at the bottom, the second a quarter cycle on
+        assert_eq!((controls[0].cc, controls[0].value, controls[0].start_tick), (74, 30, 0));
+        assert_eq!((controls[1].cc, controls[1].value), (1, 65));
+
+        // Half a cycle in, the first controller is at the top
+        let half = GeneratorContext { tick: 48, ..ctx };
+        drone.generate(&half);
+        let top = drone.take_control_changes();
+        assert_eq!(top.iter().find(|c| c.cc == 74 && c.start_tick == 0).unwrap().value, 100);
+        assert_eq!(drone.get_param("sweep_cc2"), Some(1.0));
+    }
 }
@@ -4,14 +4,36 @@
 //! Drum generator with Euclidean rhythms and style templates.
 //!
 //! Generates drum patterns using Euclidean rhythm algorithms,
-//! style templates, humanization, and fill generation.
+//! style templates, humanization, and fill generation. Each voice's steps
+//! can also carry their own velocity and a push/drag timing offset, set
+//! from the drum step editor.
 
 use std::collections::HashMap;
 
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 
-use super::{Generator, GeneratorContext, MidiEvent};
+use super::{Generator, GeneratorContext, MidiEvent, ParamSpec};
+use crate::music::euclidean;
+
+/// Parameter ranges
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::whole(
+        "style",
+        0.0,
+        5.0,
+        0.0,
+        "Style: 0 four on the floor, 1 breakbeat, 2 sparse, 3 busy, 4 euclidean, 5 random",
+    ),
+    ParamSpec::new("swing", 0.0, 1.0, 0.0, "Swing amount"),
+    ParamSpec::new("humanize_timing", 0.0, 50.0, 0.0, "Random timing spread").with_step(1.0).with_unit("ms"),
+    ParamSpec::whole("humanize_velocity", 0.0, 30.0, 5.0, "Random velocity spread"),
+    ParamSpec::new("fill_probability", 0.0, 1.0, 0.3, "Chance of a fill when one is due"),
+    ParamSpec::whole("fill_every_bars", 1.0, 16.0, 4.0, "Bars between fills").with_unit("bars"),
+    ParamSpec::whole("kick_euclidean_hits", 1.0, 16.0, 4.0, "Kick hits in euclidean style"),
+    ParamSpec::whole("snare_euclidean_hits", 1.0, 16.0, 4.0, "Snare hits in euclidean style"),
+    ParamSpec::whole("hat_euclidean_hits", 1.0, 16.0, 8.0, "Hat hits in euclidean style"),
+];
 
 /// Standard General MIDI drum notes
 pub mod gm_drums {
@@ -27,6 +49,30 @@ pub mod gm_drums {
     pub const CLAP: u8 = 39;
     pub const RIM: u8 = 37;
     pub const COWBELL: u8 = 56;
+
+    /// Look up a voice by short name ("kick", "open_hat"), GM name
+    /// ("Acoustic Snare") or note number
+    pub fn from_name(name: &str) -> Option<u8> {
+        let name = name.trim().to_lowercase().replace([' ', '-'], "_");
+        if let Ok(note) = name.parse::<u8>() {
+            return (note <= 127).then_some(note);
+        }
+        match name.as_str() {
+            "kick" | "bass_drum" => Some(KICK),
+            "snare" => Some(SNARE),
+            "hat" | "closed_hat" | "hihat" => Some(CLOSED_HAT),
+            "open_hat" => Some(OPEN_HAT),
+            "low_tom" => Some(LOW_TOM),
+            "mid_tom" => Some(MID_TOM),
+            "high_tom" => Some(HIGH_TOM),
+            "crash" => Some(CRASH),
+            "ride" => Some(RIDE),
+            "clap" => Some(CLAP),
+            "rim" => Some(RIM),
+            "cowbell" => Some(COWBELL),
+            _ => crate::midi::gm::drum_note(&name),
+        }
+    }
 }
 
 /// Drum style presets
@@ -70,6 +116,30 @@ impl DrumStyle {
     }
 }
 
+/// Largest step timing offset, in percent of a step
+pub const MAX_STEP_OFFSET: i8 = 50;
+
+/// One step of a drum voice as seen by the step editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrumStep {
+    /// Whether the step plays
+    pub hit: bool,
+    /// Velocity for this step (None = the voice's normal or accent velocity)
+    pub velocity: Option<u8>,
+    /// Timing offset in percent of a step (negative = push, positive = drag)
+    pub offset: i8,
+}
+
+impl DrumStep {
+    /// A hit with the voice's velocity and no offset
+    pub fn hit() -> Self {
+        Self {
+            hit: true,
+            ..Default::default()
+        }
+    }
+}
+
 /// Configuration for a single drum instrument
 #[derive(Debug, Clone)]
 struct DrumVoice {
@@ -89,6 +159,10 @@ struct DrumVoice {
     ghost_pattern: Vec<bool>,
     /// Ghost note velocity
     ghost_velocity: u8,
+    /// Per-step velocity lane (None = use base or accent velocity)
+    step_velocities: Vec<Option<u8>>,
+    /// Per-step timing offset lane (percent of a step)
+    step_offsets: Vec<i8>,
     /// Enabled
     enabled: bool,
 }
@@ -104,10 +178,32 @@ impl DrumVoice {
             accent_pattern: vec![false; 16],
             ghost_pattern: vec![false; 16],
             ghost_velocity: 50,
+            step_velocities: vec![None; 16],
+            step_offsets: vec![0; 16],
             enabled: true,
         }
     }
 
+    /// Get a step with its lanes
+    fn step(&self, index: usize) -> DrumStep {
+        DrumStep {
+            hit: self.pattern.get(index).copied().unwrap_or(false),
+            velocity: self.step_velocities.get(index).copied().flatten(),
+            offset: self.step_offsets.get(index).copied().unwrap_or(0),
+        }
+    }
+
+    /// Set a step and its lanes, growing the lanes if needed
+    fn set_step(&mut self, index: usize, step: DrumStep) {
+        let len = (index + 1).max(self.pattern.len());
+        self.pattern.resize(len, false);
+        self.step_velocities.resize(len, None);
+        self.step_offsets.resize(len, 0);
+        self.pattern[index] = step.hit;
+        self.step_velocities[index] = step.velocity.map(|v| v.clamp(1, 127));
+        self.step_offsets[index] = step.offset.clamp(-MAX_STEP_OFFSET, MAX_STEP_OFFSET);
+    }
+
     fn with_pattern(mut self, pattern: Vec<bool>) -> Self {
         self.pattern = pattern;
         self
@@ -197,42 +293,6 @@ impl DrumGenerator {
         Box::new(Self::new())
     }
 
-    /// Generate Euclidean rhythm pattern
-    fn generate_euclidean(hits: usize, steps: usize) -> Vec<bool> {
-        if steps == 0 {
-            return vec![];
-        }
-        if hits >= steps {
-            return vec![true; steps];
-        }
-        if hits == 0 {
-            return vec![false; steps];
-        }
-
-        // Bjorklund's algorithm
-        let mut pattern = vec![vec![true]; hits];
-        let mut remainder = vec![vec![false]; steps - hits];
-
-        while remainder.len() > 1 {
-            let min_len = pattern.len().min(remainder.len());
-            for i in 0..min_len {
-                pattern[i].extend(remainder[i].clone());
-            }
-            let new_remainder: Vec<Vec<bool>> = if pattern.len() > min_len {
-                pattern.drain(min_len..).collect()
-            } else {
-                remainder.drain(min_len..).collect()
-            };
-            remainder = new_remainder;
-        }
-
-        let mut result: Vec<bool> = pattern.into_iter().flatten().collect();
-        for r in remainder {
-            result.extend(r);
-        }
-        result
-    }
-
     /// Build pattern based on current style
     fn build_pattern(&mut self) {
         self.voices.clear();
@@ -341,15 +401,15 @@ impl DrumGenerator {
                 );
             }
             DrumStyle::Euclidean => {
-                let kick_pattern = Self::generate_euclidean(
+                let kick_pattern = euclidean(
                     self.config.kick_euclidean_hits as usize,
                     steps,
                 );
-                let snare_pattern = Self::generate_euclidean(
+                let snare_pattern = euclidean(
                     self.config.snare_euclidean_hits as usize,
                     steps,
                 );
-                let hat_pattern = Self::generate_euclidean(
+                let hat_pattern = euclidean(
                     self.config.hat_euclidean_hits as usize,
                     steps,
                 );
@@ -415,11 +475,51 @@ impl DrumGenerator {
         events
     }
 
-    /// Apply humanization to a velocity
-    fn humanize_velocity(&mut self, velocity: u8) -> u8 {
+    /// Names of the voices in the current pattern, sorted
+    pub fn voice_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.voices.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Steps per bar
+    pub fn steps_per_bar(&self) -> usize {
+        self.config.steps_per_bar as usize
+    }
+
+    /// Get a voice's steps with their velocity and offset lanes
+    pub fn voice_steps(&self, voice: &str) -> Option<Vec<DrumStep>> {
+        let voice = self.voices.get(voice)?;
+        Some((0..self.steps_per_bar()).map(|i| voice.step(i)).collect())
+    }
+
+    /// Replace a voice's steps (e.g. from the step editor)
+    pub fn set_voice_steps(&mut self, voice: &str, steps: &[DrumStep]) -> bool {
+        match self.voices.get_mut(voice) {
+            Some(voice) => {
+                for (i, step) in steps.iter().enumerate() {
+                    voice.set_step(i, *step);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add or replace a voice playing a note (e.g. from an imported
+    /// pattern). Changing the style rebuilds the preset voices.
+    pub fn set_voice(&mut self, name: &str, note: u8, steps: &[DrumStep]) {
+        let mut voice = DrumVoice::new(note.min(127));
+        for (i, step) in steps.iter().enumerate() {
+            voice.set_step(i, *step);
+        }
+        self.voices.insert(name.to_string(), voice);
+    }
+
+    /// Roll a humanization offset for a velocity
+    fn humanize_velocity(&mut self) -> i16 {
         let var = self.config.humanize_velocity as i16;
-        let offset = self.rng.gen_range(-var..=var);
-        (velocity as i16 + offset).clamp(1, 127) as u8
+        self.rng.gen_range(-var..=var)
     }
 }
 
@@ -438,8 +538,10 @@ impl Generator for DrumGenerator {
         while tick < context.ticks_to_generate {
             let step = self.current_step;
 
-            // Check for fill
-            if step == 12 && self.config.fill_every_bars > 0 {
+            // Check for fill (a requested fill always plays)
+            if step == 12 && context.fill {
+                self.in_fill = true;
+            } else if step == 12 && self.config.fill_every_bars > 0 {
                 if (self.current_bar + 1) % self.config.fill_every_bars as u64 == 0 {
                     if self.rng.gen::<f64>() < self.config.fill_probability {
                         self.in_fill = true;
@@ -455,34 +557,46 @@ impl Generator for DrumGenerator {
                     events.push(event);
                 }
             } else {
-                // Collect voice data first
-                let voice_data: Vec<_> = self.voices.values()
-                    .filter(|v| v.enabled)
-                    .map(|voice| {
+                // Collect voice data first, in name order so a seeded
+                // generator always rolls its voices in the same order
+                let mut voices: Vec<_> = self.voices.iter().filter(|(_, v)| v.enabled).collect();
+                voices.sort_by(|a, b| a.0.cmp(b.0));
+                let voice_data: Vec<_> = voices.into_iter()
+                    .map(|(_, voice)| {
                         let step_idx = step % voice.pattern.len();
-                        let should_hit = voice.pattern.get(step_idx).copied().unwrap_or(false);
+                        let lanes = voice.step(step_idx);
                         let is_accent = voice.accent_pattern.get(step_idx).copied().unwrap_or(false);
                         let should_ghost = voice.ghost_pattern.get(step_idx).copied().unwrap_or(false);
                         (voice.note, voice.velocity, voice.accent_velocity, voice.ghost_velocity,
-                         voice.probability, should_hit, is_accent, should_ghost)
+                         voice.probability, lanes, is_accent, should_ghost)
                     })
                     .collect();
 
                 // Now generate events
-                for (note, vel, accent_vel, ghost_vel, prob, should_hit, is_accent, should_ghost) in voice_data {
+                for (note, vel, accent_vel, ghost_vel, prob, lanes, is_accent, should_ghost) in voice_data {
                     // Check main hit
-                    if should_hit {
+                    if lanes.hit {
                         if self.rng.gen::<f64>() < prob {
-                            let base_vel = if is_accent { accent_vel } else { vel };
-                            let velocity = self.humanize_velocity(base_vel);
-                            events.push(MidiEvent::new(note, velocity, tick, ticks_per_step));
+                            let base_vel = match lanes.velocity {
+                                Some(step_vel) => step_vel,
+                                None if is_accent => accent_vel,
+                                None => vel,
+                            };
+                            let velocity = self.humanize_velocity();
+                            // Push/drag the hit by a share of a step
+                            let offset = ticks_per_step as i64 * lanes.offset as i64 / 100;
+                            let mut event = MidiEvent::new(note, base_vel, tick, ticks_per_step);
+                            event.humanize(offset, velocity);
+                            events.push(event);
                         }
                     }
 
                     // Check ghost note
                     if should_ghost {
-                        let velocity = self.humanize_velocity(ghost_vel);
-                        events.push(MidiEvent::new(note, velocity, tick, ticks_per_step / 2));
+                        let velocity = self.humanize_velocity();
+                        let mut event = MidiEvent::new(note, ghost_vel, tick, ticks_per_step / 2);
+                        event.humanize(0, velocity);
+                        events.push(event);
                     }
                 }
             }
@@ -569,10 +683,21 @@ impl Generator for DrumGenerator {
         self.in_fill = false;
     }
 
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        if self.config.style == DrumStyle::Random {
+            self.build_pattern();
+        }
+    }
+
     fn name(&self) -> &'static str {
         "drums"
     }
 
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        PARAMS.to_vec()
+    }
+
     fn params(&self) -> HashMap<String, f64> {
         let mut params = HashMap::new();
         params.insert("style".to_string(), self.config.style.to_value() as f64);
@@ -624,6 +749,11 @@ mod tests {
         assert_eq!(gm_drums::KICK, 36);
         assert_eq!(gm_drums::SNARE, 38);
         assert_eq!(gm_drums::CLOSED_HAT, 42);
+
+        assert_eq!(gm_drums::from_name("Open Hat"), Some(gm_drums::OPEN_HAT));
+        assert_eq!(gm_drums::from_name("40"), Some(40));
+        assert_eq!(gm_drums::from_name("Side Stick"), Some(37));
+        assert_eq!(gm_drums::from_name("kazoo"), None);
     }
 
     #[test]
@@ -639,22 +769,6 @@ mod tests {
         }
     }
 
-    #[test]
-    fn test_euclidean_patterns() {
-        // Classic 3-over-8
-        let pattern = DrumGenerator::generate_euclidean(3, 8);
-        assert_eq!(pattern.len(), 8);
-        assert_eq!(pattern.iter().filter(|&&b| b).count(), 3);
-
-        // 5-over-8
-        let pattern = DrumGenerator::generate_euclidean(5, 8);
-        assert_eq!(pattern.iter().filter(|&&b| b).count(), 5);
-
-        // 4-over-16 (four-on-floor)
-        let pattern = DrumGenerator::generate_euclidean(4, 16);
-        assert_eq!(pattern.iter().filter(|&&b| b).count(), 4);
-    }
-
     #[test]
     fn test_drums_param_changes() {
         let mut drums = DrumGenerator::new();
@@ -669,6 +783,49 @@ mod tests {
         assert_eq!(drums.get_param("fill_probability"), Some(0.5));
     }
 
+    #[test]
+    fn test_requested_fill() {
+        let mut drums = DrumGenerator::new();
+        drums.set_param("fill_probability", 0.0);
+
+        let ctx = GeneratorContext {
+            fill: true,
+            ..test_context()
+        };
+        let events = drums.generate(&ctx);
+        assert!(events.iter().any(|e| e.note == gm_drums::HIGH_TOM));
+
+        // The fill only lasts for the requested bar
+        let events = drums.generate(&test_context());
+        assert!(!events.iter().any(|e| e.note == gm_drums::HIGH_TOM));
+    }
+
+    #[test]
+    fn test_step_velocity_and_offset_lanes() {
+        let mut drums = DrumGenerator::new();
+        drums.set_param("humanize_velocity", 0.0);
+        drums.set_param("fill_probability", 0.0);
+
+        let mut steps = vec![DrumStep::default(); 16];
+        steps[0] = DrumStep { hit: true, velocity: Some(30), offset: 0 };
+        steps[4] = DrumStep { hit: true, velocity: None, offset: 25 };
+        steps[8] = DrumStep { hit: true, velocity: Some(90), offset: -90 };
+        assert!(drums.set_voice_steps("kick", &steps));
+        assert!(!drums.set_voice_steps("cowbell", &steps));
+
+        // Offsets are kept within half a step
+        assert_eq!(drums.voice_steps("kick").unwrap()[8].offset, -MAX_STEP_OFFSET);
+
+        let events = drums.generate(&test_context());
+        let kicks: Vec<(u64, u8)> = events
+            .iter()
+            .filter(|e| e.note == gm_drums::KICK)
+            .map(|e| (e.start_tick, e.velocity))
+            .collect();
+        // 6 ticks per step: +25% drags 1 tick, -50% pushes 3 ticks
+        assert_eq!(kicks, vec![(0, 30), (25, 100), (45, 90)]);
+    }
+
     #[test]
     fn test_drums_reset() {
         let mut drums = DrumGenerator::new();
@@ -0,0 +1,382 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! External process generator.
+//!
+//! Lets a sidecar process (a Python model, a script) act as a generator,
+//! registered as `external:<command>`. The command runs under `sh -c` and
+//! talks over its stdin and stdout, one JSON object per line.
+//!
+//! Each buffer SEQ writes a request:
+//!
+//! ```text
+//! {"type":"generate","id":7,"tempo":120,"ppqn":24,"bar":2,"beat":0,"tick":0,
+//!  "beats_per_bar":4,"key":"C major","ticks_to_generate":24,"swing":0,
+//!  "fill":false,"params":{"density":0.5}}
+//! ```
+//!
+//! and waits up to the timeout for the answer:
+//!
+//! ```text
+//! {"id":7,"events":[{"note":60,"velocity":100,"start":0,"duration":12}]}
+//! ```
+//!
+//! `id` is optional in the answer; answers carrying an older id (late
+//! replies to a timed-out request) are skipped. `channel` is optional per
+//! event. A reset sends `{"type":"reset"}` and expects no answer. When the
+//! process is late, fails to start or exits, the fallback generator (if
+//! any) plays that buffer instead.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use super::{Generator, GeneratorContext, MidiEvent};
+
+/// Registry prefix for external generators
+pub const EXTERNAL_PREFIX: &str = "external:";
+
+/// Default time to wait for an answer
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Answer to a generate request
+#[derive(Debug, Deserialize)]
+struct Response {
+    /// Request id being answered
+    #[serde(default)]
+    id: Option<u64>,
+    /// Events for the buffer
+    #[serde(default)]
+    events: Vec<ResponseEvent>,
+}
+
+/// An event in an answer
+#[derive(Debug, Deserialize)]
+struct ResponseEvent {
+    note: u8,
+    velocity: u8,
+    #[serde(default)]
+    start: u64,
+    duration: u64,
+    #[serde(default)]
+    channel: u8,
+}
+
+/// A running sidecar process
+struct Sidecar {
+    child: Child,
+    stdin: ChildStdin,
+    /// Lines read from the process's stdout
+    lines: Receiver<String>,
+}
+
+impl Sidecar {
+    /// Start the command under the shell
+    fn spawn(command: &str) -> Result<Self> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("Failed to start external generator: {}", command))?;
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("No stdin for external generator"))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("No stdout for external generator"))?;
+
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self { child, stdin, lines })
+    }
+
+    /// Write one request line
+    fn send(&mut self, line: &str) -> Result<()> {
+        writeln!(self.stdin, "{}", line)?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    /// Wait for the answer to a request (None = no answer in time)
+    fn receive(&self, id: u64, timeout: Duration) -> Result<Option<Vec<MidiEvent>>> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match self.lines.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => return Ok(None),
+                Err(RecvTimeoutError::Disconnected) => return Err(anyhow!("External generator exited")),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            // JSON is read with the YAML parser, which accepts it
+            let response: Response = serde_yaml::from_str(&line)
+                .with_context(|| format!("Bad answer from external generator: {}", line))?;
+            if response.id.is_some_and(|answered| answered != id) {
+                continue;
+            }
+            return Ok(Some(
+                response
+                    .events
+                    .into_iter()
+                    .map(|e| {
+                        MidiEvent::new(e.note.min(127), e.velocity.min(127), e.start, e.duration.max(1))
+                            .with_channel(e.channel.min(15))
+                    })
+                    .collect(),
+            ));
+        }
+    }
+}
+
+impl Drop for Sidecar {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Generator backed by an external process
+pub struct ExternalGenerator {
+    /// Shell command
+    command: String,
+    /// Running process (started on first use)
+    sidecar: Option<Sidecar>,
+    /// The process failed to start or exited; don't restart until reset
+    failed: bool,
+    /// Time to wait for an answer
+    timeout: Duration,
+    /// Generator used when the process doesn't answer in time
+    fallback: Option<Box<dyn Generator>>,
+    /// Parameters passed along with every request
+    params: BTreeMap<String, f64>,
+    /// Id of the last request
+    next_id: u64,
+    /// Last problem with the process
+    last_error: Option<String>,
+}
+
+impl ExternalGenerator {
+    /// Create a generator for a shell command (started on first use)
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            sidecar: None,
+            failed: false,
+            timeout: DEFAULT_TIMEOUT,
+            fallback: None,
+            params: BTreeMap::new(),
+            next_id: 0,
+            last_error: None,
+        }
+    }
+
+    /// Set the answer timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the generator that plays when the process is late or gone
+    pub fn with_fallback(mut self, fallback: Box<dyn Generator>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Get the command
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Get the last problem with the process (cleared by a good answer)
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+
+    /// Check if the process is running
+    pub fn is_running(&self) -> bool {
+        self.sidecar.is_some()
+    }
+
+    /// Request line for a context
+    fn request(&self, id: u64, context: &GeneratorContext) -> String {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|(name, value)| format!("{}:{}", json_string(name), json_number(*value)))
+            .collect();
+        format!(
+            "{{\"type\":\"generate\",\"id\":{},\"tempo\":{},\"ppqn\":{},\"bar\":{},\"beat\":{},\"tick\":{},\
+             \"beats_per_bar\":{},\"key\":{},\"ticks_to_generate\":{},\"swing\":{},\"fill\":{},\"params\":{{{}}}}}",
+            id,
+            json_number(context.tempo),
+            context.ppqn,
+            context.bar,
+            context.beat,
+            context.tick,
+            context.beats_per_bar,
+            json_string(&context.key.to_string()),
+            context.ticks_to_generate,
+            json_number(context.swing),
+            context.fill,
+            params.join(","),
+        )
+    }
+
+    /// Ask the process for a buffer of events (None = late answer)
+    fn ask(&mut self, context: &GeneratorContext) -> Result<Option<Vec<MidiEvent>>> {
+        if self.failed {
+            return Err(anyhow!("External generator is not running"));
+        }
+        if self.sidecar.is_none() {
+            self.sidecar = Some(Sidecar::spawn(&self.command)?);
+        }
+
+        self.next_id += 1;
+        let id = self.next_id;
+        let request = self.request(id, context);
+        let timeout = self.timeout;
+        let sidecar = self.sidecar.as_mut().expect("sidecar started above");
+        sidecar.send(&request)?;
+        sidecar.receive(id, timeout)
+    }
+}
+
+/// Quote a string for JSON
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Format a number for JSON (non-finite values become 0)
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "0".to_string()
+    }
+}
+
+impl Generator for ExternalGenerator {
+    fn generate(&mut self, context: &GeneratorContext) -> Vec<MidiEvent> {
+        match self.ask(context) {
+            Ok(Some(events)) => {
+                self.last_error = None;
+                return events;
+            }
+            // A late answer only costs this buffer
+            Ok(None) => self.last_error = Some("External generator timed out".to_string()),
+            // A process that fails is not restarted until reset
+            Err(err) => {
+                self.sidecar = None;
+                self.failed = true;
+                self.last_error = Some(format!("{:#}", err));
+            }
+        }
+        match self.fallback.as_mut() {
+            Some(fallback) => fallback.generate(context),
+            None => Vec::new(),
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: f64) {
+        self.params.insert(name.to_string(), value);
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.set_param(name, value);
+        }
+    }
+
+    fn get_param(&self, name: &str) -> Option<f64> {
+        self.params.get(name).copied()
+    }
+
+    fn reset(&mut self) {
+        if let Some(sidecar) = self.sidecar.as_mut() {
+            let _ = sidecar.send("{\"type\":\"reset\"}");
+        }
+        self.failed = false;
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.reset();
+        }
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.params.insert("seed".to_string(), seed as f64);
+        if let Some(fallback) = self.fallback.as_mut() {
+            fallback.set_seed(seed);
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "external"
+    }
+
+    fn params(&self) -> HashMap<String, f64> {
+        self.params.iter().map(|(k, v)| (k.clone(), *v)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::drone::DroneGenerator;
+
+    #[test]
+    fn test_external_answers() {
+        let script = r#"while read line; do echo '{"events":[{"note":60,"velocity":100,"duration":12},{"note":64,"velocity":90,"start":6,"duration":6,"channel":3}]}'; done"#;
+        let mut external = ExternalGenerator::new(script).with_timeout(Duration::from_secs(5));
+        external.set_param("density", 0.5);
+
+        let events = external.generate(&GeneratorContext::default());
+        assert!(external.is_running());
+        assert_eq!(external.last_error(), None);
+        assert_eq!(events, vec![
+            MidiEvent::new(60, 100, 0, 12),
+            MidiEvent::new(64, 90, 6, 6).with_channel(3),
+        ]);
+
+        let request = external.request(3, &GeneratorContext::default());
+        assert!(request.starts_with("{\"type\":\"generate\",\"id\":3,\"tempo\":120,"));
+        assert!(request.ends_with("\"params\":{\"density\":0.5}}"));
+    }
+
+    #[test]
+    fn test_fallback_when_process_exits() {
+        let mut external = ExternalGenerator::new("exit 0")
+            .with_timeout(Duration::from_secs(5))
+            .with_fallback(DroneGenerator::create());
+        let context = GeneratorContext {
+            ticks_to_generate: 96,
+            ..Default::default()
+        };
+
+        let events = external.generate(&context);
+        assert!(!events.is_empty());
+        assert!(external.last_error().is_some());
+        assert!(!external.is_running());
+    }
+}
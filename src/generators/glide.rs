@@ -0,0 +1,181 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Pitch-bend glides between successive roots.
+//!
+//! When a chord or drone root changes, the new notes start bent to the
+//! previous root and glide into place, either smoothly or stepping through
+//! the scale tones in between. Glides larger than the synth's bend range
+//! are skipped.
+
+use super::ParamSpec;
+use crate::music::scale::Scale;
+
+/// Ticks between pitch-bend messages in a smooth glide
+const GLIDE_RESOLUTION: u64 = 2;
+
+/// Pitch-bend event produced by a generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PitchBendEvent {
+    /// Bend value (-8192 to 8191, 0 = center)
+    pub value: i16,
+    /// Start time in ticks from current position
+    pub start_tick: u64,
+}
+
+impl PitchBendEvent {
+    /// Create a new pitch-bend event
+    pub fn new(value: i16, start_tick: u64) -> Self {
+        Self {
+            value: value.clamp(-8192, 8191),
+            start_tick,
+        }
+    }
+}
+
+/// Glide settings shared by chord and drone generators
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlideConfig {
+    /// Glide time in beats (0 = off)
+    pub glide_time: f64,
+    /// Synth pitch-bend range in semitones
+    pub bend_range: u8,
+    /// Step through scale tones instead of a smooth ramp
+    pub scale_locked: bool,
+}
+
+/// Ranges of the glide parameters
+pub const GLIDE_PARAMS: [ParamSpec; 3] = [
+    ParamSpec::new("glide_time", 0.0, 16.0, 0.0, "Pitch-bend glide length (0 = off)").with_unit("beats"),
+    ParamSpec::whole("bend_range", 1.0, 48.0, 2.0, "Synth pitch-bend range").with_unit("semitones"),
+    ParamSpec::toggle("glide_scale_lock", false, "Step through scale tones instead of a smooth ramp"),
+];
+
+impl Default for GlideConfig {
+    fn default() -> Self {
+        Self {
+            glide_time: 0.0,
+            bend_range: 2,
+            scale_locked: false,
+        }
+    }
+}
+
+impl GlideConfig {
+    /// Check if glides are enabled
+    pub fn is_enabled(&self) -> bool {
+        self.glide_time > 0.0
+    }
+
+    /// Convert a semitone offset to a bend value
+    pub fn bend_value(&self, semitones: f64) -> i16 {
+        let value = semitones / self.bend_range.max(1) as f64 * 8192.0;
+        value.round().clamp(-8192.0, 8191.0) as i16
+    }
+
+    /// Build the bends for notes that just moved from one root to another
+    pub fn glide(&self, from_note: u8, to_note: u8, ppqn: u32, scale: &Scale) -> Vec<PitchBendEvent> {
+        let interval = from_note as i32 - to_note as i32;
+        if !self.is_enabled() || interval == 0 || interval.unsigned_abs() > self.bend_range as u32 {
+            return Vec::new();
+        }
+
+        let glide_ticks = ((self.glide_time * ppqn as f64) as u64).max(1);
+
+        // Offsets (in semitones from the target) to pass through
+        let offsets: Vec<f64> = if self.scale_locked {
+            let step = if interval > 0 { -1 } else { 1 };
+            let mut offsets = Vec::new();
+            let mut offset = interval;
+            while offset != 0 {
+                let note = (to_note as i32 + offset).clamp(0, 127) as u8;
+                if offset == interval || scale.contains_midi(note) {
+                    offsets.push(offset as f64);
+                }
+                offset += step;
+            }
+            offsets
+        } else {
+            let steps = (glide_ticks / GLIDE_RESOLUTION).max(1);
+            (0..steps)
+                .map(|i| interval as f64 * (1.0 - i as f64 / steps as f64))
+                .collect()
+        };
+
+        let mut bends: Vec<PitchBendEvent> = offsets
+            .iter()
+            .enumerate()
+            .map(|(i, &offset)| {
+                let tick = glide_ticks * i as u64 / offsets.len() as u64;
+                PitchBendEvent::new(self.bend_value(offset), tick)
+            })
+            .collect();
+        bends.push(PitchBendEvent::new(0, glide_ticks));
+        bends
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::scale::{Note, ScaleType};
+
+    #[test]
+    fn test_bend_value() {
+        let glide = GlideConfig::default();
+        assert_eq!(glide.bend_value(2.0), 8191);
+        assert_eq!(glide.bend_value(-2.0), -8192);
+        assert_eq!(glide.bend_value(1.0), 4096);
+    }
+
+    #[test]
+    fn test_smooth_glide() {
+        let glide = GlideConfig {
+            glide_time: 0.5,
+            bend_range: 12,
+            scale_locked: false,
+        };
+        let scale = Scale::new(Note::C, ScaleType::Major);
+
+        // C3 to F3: start bent down a fourth and glide to center
+        let bends = glide.glide(48, 53, 24, &scale);
+        assert_eq!(bends.first().unwrap().value, glide.bend_value(-5.0));
+        assert_eq!(bends.first().unwrap().start_tick, 0);
+        assert_eq!(*bends.last().unwrap(), PitchBendEvent::new(0, 12));
+        assert!(bends.windows(2).all(|w| w[0].value <= w[1].value));
+    }
+
+    #[test]
+    fn test_scale_locked_glide() {
+        let glide = GlideConfig {
+            glide_time: 1.0,
+            bend_range: 12,
+            scale_locked: true,
+        };
+        let scale = Scale::new(Note::C, ScaleType::Major);
+
+        // C3 to F3 passes through D3 and E3
+        let bends = glide.glide(48, 53, 24, &scale);
+        let values: Vec<i16> = bends.iter().map(|b| b.value).collect();
+        assert_eq!(
+            values,
+            vec![
+                glide.bend_value(-5.0),
+                glide.bend_value(-3.0),
+                glide.bend_value(-1.0),
+                0
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glide_out_of_range() {
+        let glide = GlideConfig {
+            glide_time: 1.0,
+            ..Default::default()
+        };
+        let scale = Scale::new(Note::C, ScaleType::Major);
+        assert!(glide.glide(48, 55, 24, &scale).is_empty());
+        assert!(!glide.glide(48, 50, 24, &scale).is_empty());
+    }
+}
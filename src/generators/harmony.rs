@@ -0,0 +1,253 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Harmony generator that follows a melody track.
+//!
+//! The harmony track listens to another track (generated or played) and
+//! answers each melody note with an in-scale third or sixth below it. Notes
+//! heard during one buffer are harmonized in the next, so the harmony runs
+//! one buffer behind the melody.
+
+use std::collections::HashMap;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use super::{Generator, GeneratorContext, MidiEvent, ParamSpec};
+
+/// Parameter ranges
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::whole("interval", 0.0, 6.0, 3.0, "Interval below the melody: 3 third, 6 sixth, 0 auto").with_step(3.0),
+    ParamSpec::new("density", 0.0, 1.0, 1.0, "Chance a melody note is harmonized"),
+    ParamSpec::new("velocity_scale", 0.0, 2.0, 0.8, "Velocity relative to the melody"),
+];
+
+/// Interval choice for the harmony line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HarmonyInterval {
+    /// Third below
+    Third,
+    /// Sixth below
+    Sixth,
+    /// Whichever of the two moves least from the last harmony note
+    Auto,
+}
+
+impl Default for HarmonyInterval {
+    fn default() -> Self {
+        HarmonyInterval::Third
+    }
+}
+
+impl HarmonyInterval {
+    /// Parse from a string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "third" | "3rd" | "3" => Some(HarmonyInterval::Third),
+            "sixth" | "6th" | "6" => Some(HarmonyInterval::Sixth),
+            "auto" => Some(HarmonyInterval::Auto),
+            _ => None,
+        }
+    }
+
+    /// Parameter value (3 = third, 6 = sixth, 0 = auto)
+    fn to_param(self) -> f64 {
+        match self {
+            HarmonyInterval::Third => 3.0,
+            HarmonyInterval::Sixth => 6.0,
+            HarmonyInterval::Auto => 0.0,
+        }
+    }
+
+    /// From a parameter value (3 = third, 6 = sixth, 0 = auto)
+    fn from_param(value: f64) -> Self {
+        if value < 1.5 {
+            HarmonyInterval::Auto
+        } else if value < 4.5 {
+            HarmonyInterval::Third
+        } else {
+            HarmonyInterval::Sixth
+        }
+    }
+}
+
+/// Configuration for harmony behavior
+#[derive(Debug, Clone)]
+struct HarmonyConfig {
+    /// Interval below the melody
+    interval: HarmonyInterval,
+    /// Chance that a melody note gets a harmony note (0.0 - 1.0)
+    density: f64,
+    /// Harmony velocity relative to the melody (0.0 - 2.0)
+    velocity_scale: f64,
+}
+
+impl Default for HarmonyConfig {
+    fn default() -> Self {
+        Self {
+            interval: HarmonyInterval::Third,
+            density: 1.0,
+            velocity_scale: 0.8,
+        }
+    }
+}
+
+/// Harmony generator
+pub struct HarmonyGenerator {
+    config: HarmonyConfig,
+    /// Melody notes heard since the last generate call
+    heard: Vec<MidiEvent>,
+    /// Last harmony note (for auto interval voice leading)
+    last_note: Option<u8>,
+    rng: StdRng,
+}
+
+impl HarmonyGenerator {
+    /// Create a new harmony generator
+    pub fn new() -> Self {
+        Self {
+            config: HarmonyConfig::default(),
+            heard: Vec::new(),
+            last_note: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Factory function for registry
+    pub fn create() -> Box<dyn Generator> {
+        Box::new(Self::new())
+    }
+
+    /// Harmony note for a melody note
+    fn harmonize(&self, note: u8, context: &GeneratorContext) -> u8 {
+        let scale = context.scale();
+        let third = scale.transpose_in_scale(note, -2);
+        let sixth = scale.transpose_in_scale(note, -5);
+        match self.config.interval {
+            HarmonyInterval::Third => third,
+            HarmonyInterval::Sixth => sixth,
+            HarmonyInterval::Auto => match self.last_note {
+                Some(last) if last.abs_diff(sixth) < last.abs_diff(third) => sixth,
+                _ => third,
+            },
+        }
+    }
+}
+
+impl Default for HarmonyGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Generator for HarmonyGenerator {
+    fn generate(&mut self, context: &GeneratorContext) -> Vec<MidiEvent> {
+        let heard = std::mem::take(&mut self.heard);
+        let mut events = Vec::new();
+
+        for melody in heard {
+            if self.rng.gen::<f64>() >= self.config.density {
+                continue;
+            }
+            let note = self.harmonize(melody.note, context);
+            // Never cross above the melody
+            if note >= melody.note {
+                continue;
+            }
+            let velocity = (melody.velocity as f64 * self.config.velocity_scale).round();
+            events.push(MidiEvent::new(
+                note,
+                velocity.clamp(1.0, 127.0) as u8,
+                melody.start_tick,
+                melody.duration_ticks,
+            ));
+            self.last_note = Some(note);
+        }
+        events
+    }
+
+    fn listen(&mut self, events: &[MidiEvent]) {
+        self.heard.extend(events.iter().filter(|e| e.velocity > 0).cloned());
+    }
+
+    fn set_param(&mut self, name: &str, value: f64) {
+        match name {
+            "interval" => self.config.interval = HarmonyInterval::from_param(value),
+            "density" => self.config.density = value.clamp(0.0, 1.0),
+            "velocity_scale" => self.config.velocity_scale = value.clamp(0.0, 2.0),
+            _ => {}
+        }
+    }
+
+    fn get_param(&self, name: &str) -> Option<f64> {
+        match name {
+            "interval" => Some(self.config.interval.to_param()),
+            "density" => Some(self.config.density),
+            "velocity_scale" => Some(self.config.velocity_scale),
+            _ => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.heard.clear();
+        self.last_note = None;
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn name(&self) -> &'static str {
+        "harmony"
+    }
+
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        PARAMS.to_vec()
+    }
+
+    fn params(&self) -> HashMap<String, f64> {
+        let mut params = HashMap::new();
+        params.insert("interval".to_string(), self.config.interval.to_param());
+        params.insert("density".to_string(), self.config.density);
+        params.insert("velocity_scale".to_string(), self.config.velocity_scale);
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harmony_follows_melody() {
+        let mut harmony = HarmonyGenerator::new();
+        let ctx = GeneratorContext::default();
+
+        // Nothing heard yet: nothing to play
+        assert!(harmony.generate(&ctx).is_empty());
+
+        // C major: E -> C (third below), E -> G (sixth below)
+        harmony.listen(&[MidiEvent::new(64, 100, 6, 12), MidiEvent::new(67, 100, 12, 12)]);
+        let events = harmony.generate(&ctx);
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].note, events[0].start_tick, events[0].velocity), (60, 6, 80));
+        assert_eq!(events[1].note, 64);
+        assert!(harmony.generate(&ctx).is_empty());
+
+        harmony.set_param("interval", 6.0);
+        harmony.listen(&[MidiEvent::new(64, 100, 0, 12)]);
+        assert_eq!(harmony.generate(&ctx)[0].note, 55);
+
+        harmony.set_param("density", 0.0);
+        harmony.listen(&[MidiEvent::new(64, 100, 0, 12)]);
+        assert!(harmony.generate(&ctx).is_empty());
+    }
+
+    #[test]
+    fn test_interval_parse() {
+        assert_eq!(HarmonyInterval::from_str("6th"), Some(HarmonyInterval::Sixth));
+        assert_eq!(HarmonyInterval::from_str("auto"), Some(HarmonyInterval::Auto));
+        assert_eq!(HarmonyInterval::from_str("fifth"), None);
+        assert_eq!(HarmonyInterval::from_param(0.0), HarmonyInterval::Auto);
+    }
+}
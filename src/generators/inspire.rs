@@ -0,0 +1,175 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Cross-seeding generators from another track ("inspire from track").
+//!
+//! A track's recent output is boiled down to an [`Inspiration`]: its onset
+//! rhythm on a step grid, the register it plays in, its density and its
+//! average velocity. Generators adopt whatever parts of it make sense for
+//! them through `Generator::inspire`; a melody can take the rhythm of a
+//! drum track's hat, an arpeggio the register of a chord track.
+
+use std::collections::HashMap;
+
+use super::MidiEvent;
+
+/// Steps per bar used when none are given (sixteenths in 4/4)
+pub const DEFAULT_INSPIRE_STEPS: usize = 16;
+
+/// What a track's recent output suggests to another generator
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspiration {
+    /// Onsets folded onto one bar of steps
+    pub rhythm: Vec<bool>,
+    /// Note value of one step (16 = sixteenths)
+    pub division: u32,
+    /// Lowest and highest note heard
+    pub register: Option<(u8, u8)>,
+    /// Share of steps with an onset (0.0 to 1.0)
+    pub density: f64,
+    /// Average velocity
+    pub velocity: Option<u8>,
+}
+
+impl Inspiration {
+    /// Analyze notes starting `length_ticks` before now (start ticks relative
+    /// to that point), on a grid of `steps` steps per bar
+    pub fn analyze(events: &[MidiEvent], length_ticks: u64, ppqn: u32, beats_per_bar: u8, steps: usize) -> Self {
+        let steps = steps.max(1);
+        let ticks_per_bar = (ppqn as u64 * beats_per_bar.max(1) as u64).max(1);
+        let bars = length_ticks.div_ceil(ticks_per_bar).max(1) as usize;
+        let notes: Vec<&MidiEvent> = events.iter().filter(|e| e.velocity > 0).collect();
+
+        // A step is part of the rhythm when it sounds in at least half the bars
+        let mut heard = vec![false; steps * bars];
+        for event in &notes {
+            let step = (event.start_tick % ticks_per_bar * steps as u64 + ticks_per_bar / 2) / ticks_per_bar;
+            let bar = ((event.start_tick / ticks_per_bar) as usize).min(bars - 1);
+            heard[bar * steps + step as usize % steps] = true;
+        }
+        let rhythm: Vec<bool> = (0..steps)
+            .map(|step| {
+                let count = (0..bars).filter(|bar| heard[bar * steps + step]).count();
+                count > 0 && count * 2 >= bars
+            })
+            .collect();
+        let hits = rhythm.iter().filter(|&&hit| hit).count();
+
+        let register = notes
+            .iter()
+            .map(|e| e.note)
+            .min()
+            .zip(notes.iter().map(|e| e.note).max());
+        let velocity = (!notes.is_empty())
+            .then(|| (notes.iter().map(|e| e.velocity as u32).sum::<u32>() / notes.len() as u32) as u8);
+
+        Self {
+            rhythm,
+            division: (steps as u32 * 4 / beats_per_bar.max(1) as u32).max(1),
+            register,
+            density: hits as f64 / steps as f64,
+            velocity,
+        }
+    }
+
+    /// Analyze one drum voice (or any single note) of a track's output
+    pub fn analyze_note(
+        events: &[MidiEvent],
+        note: u8,
+        length_ticks: u64,
+        ppqn: u32,
+        beats_per_bar: u8,
+        steps: usize,
+    ) -> Self {
+        let voice: Vec<MidiEvent> = events.iter().filter(|e| e.note == note).cloned().collect();
+        Self::analyze(&voice, length_ticks, ppqn, beats_per_bar, steps)
+    }
+
+    /// Check if any rhythm was heard
+    pub fn has_rhythm(&self) -> bool {
+        self.rhythm.iter().any(|&hit| hit)
+    }
+
+    /// Octave of the lowest note (MIDI octave, middle C = 4)
+    pub fn base_octave(&self) -> Option<i8> {
+        self.register.map(|(low, _)| (low / 12) as i8 - 1)
+    }
+
+    /// Octaves spanned by the register (at least one)
+    pub fn octave_span(&self) -> Option<u8> {
+        self.register.map(|(low, high)| ((high - low) / 12 + 1).min(4))
+    }
+}
+
+/// Most frequent note in some events (a drum track's busiest voice)
+pub fn busiest_note(events: &[MidiEvent]) -> Option<u8> {
+    let mut counts: HashMap<u8, usize> = HashMap::new();
+    for event in events.iter().filter(|e| e.velocity > 0) {
+        *counts.entry(event.note).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(note, count)| (count, std::cmp::Reverse(note)))
+        .map(|(note, _)| note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::arpeggio::ArpeggioGenerator;
+    use crate::generators::melody::MelodyGenerator;
+    use crate::generators::{Generator, GeneratorContext};
+
+    /// Two bars of eighth-note hats on 42 with kicks on the beat
+    fn drum_bars() -> Vec<MidiEvent> {
+        let mut events = Vec::new();
+        for bar in 0..2u64 {
+            for eighth in 0..8u64 {
+                events.push(MidiEvent::new(42, 80, bar * 96 + eighth * 12, 6).with_channel(9));
+            }
+            events.push(MidiEvent::new(36, 120, bar * 96, 6).with_channel(9));
+        }
+        events
+    }
+
+    #[test]
+    fn test_analyze_hat_rhythm() {
+        let events = drum_bars();
+        assert_eq!(busiest_note(&events), Some(42));
+
+        let hat = Inspiration::analyze_note(&events, 42, 192, 24, 4, 16);
+        let expected: Vec<bool> = (0..16).map(|step| step % 2 == 0).collect();
+        assert_eq!(hat.rhythm, expected);
+        assert_eq!(hat.division, 16);
+        assert_eq!(hat.density, 0.5);
+        assert_eq!(hat.velocity, Some(80));
+
+        let chords = vec![MidiEvent::new(48, 90, 0, 96), MidiEvent::new(67, 90, 0, 96)];
+        let register = Inspiration::analyze(&chords, 96, 24, 4, 16);
+        assert_eq!(register.base_octave(), Some(3));
+        assert_eq!(register.octave_span(), Some(2));
+    }
+
+    #[test]
+    fn test_generators_adopt_inspiration() {
+        let hat = Inspiration::analyze_note(&drum_bars(), 42, 192, 24, 4, 16);
+        let context = GeneratorContext {
+            ticks_to_generate: 96,
+            ..Default::default()
+        };
+
+        // The melody plays on the hat's eighths
+        let mut melody = MelodyGenerator::new();
+        assert!(melody.inspire(&hat));
+        let events = melody.generate(&context);
+        assert_eq!(events.len(), 8);
+        assert!(events.iter().all(|e| e.start_tick % 12 == 0));
+
+        // The arpeggio moves to the chord register
+        let chords = vec![MidiEvent::new(48, 90, 0, 96), MidiEvent::new(67, 90, 0, 96)];
+        let mut arp = ArpeggioGenerator::new();
+        assert!(arp.inspire(&Inspiration::analyze(&chords, 96, 24, 4, 16)));
+        assert_eq!(arp.get_param("base_octave"), Some(3.0));
+        assert_eq!(arp.get_param("octaves"), Some(2.0));
+    }
+}
@@ -11,7 +11,26 @@ use std::collections::HashMap;
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
 
-use super::{Generator, GeneratorContext, MidiEvent};
+use super::{Generator, GeneratorContext, Inspiration, MidiEvent, ParamSpec, EUCLIDEAN_PARAMS};
+use crate::music::EuclideanGate;
+
+/// Parameter ranges
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::whole("base_octave", 1.0, 7.0, 4.0, "Lowest octave (middle C = 4)"),
+    ParamSpec::whole("octave_range", 1.0, 4.0, 2.0, "Octaves the melody moves over"),
+    ParamSpec::whole("velocity", 1.0, 127.0, 100.0, "Base velocity"),
+    ParamSpec::whole("velocity_variation", 0.0, 64.0, 15.0, "Random velocity spread (+/-)"),
+    ParamSpec::whole("base_rate", 1.0, 32.0, 8.0, "Note division (4 = quarter, 8 = eighth)"),
+    ParamSpec::new("gate", 0.1, 1.0, 0.85, "Portion of each step the note sounds"),
+    ParamSpec::new("step_probability", 0.0, 1.0, 0.7, "Chance of stepwise motion instead of a leap"),
+    ParamSpec::new("repeat_probability", 0.0, 1.0, 0.1, "Chance of repeating the last note"),
+    ParamSpec::new("rest_probability", 0.0, 0.5, 0.15, "Chance of a rest"),
+    ParamSpec::whole("max_jump", 1.0, 7.0, 4.0, "Largest leap in scale degrees"),
+    ParamSpec::toggle("use_motifs", true, "Develop short motifs"),
+    ParamSpec::whole("motif_length", 2.0, 8.0, 4.0, "Notes per motif"),
+    ParamSpec::new("rhythmic_complexity", 0.0, 1.0, 0.5, "Rhythm variety (0 = simple)"),
+    ParamSpec::new("fill_density", 0.0, 1.0, 0.0, "How much a fill reduces rests"),
+];
 
 /// Motif transformation types
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -103,6 +122,10 @@ struct MelodyConfig {
     motif_length: u8,
     /// Rhythmic complexity (0.0 = simple, 1.0 = complex)
     rhythmic_complexity: f64,
+    /// How much a requested fill reduces rests (0.0 = ignore fills)
+    fill_density: f64,
+    /// Euclidean rhythm the onsets must land on
+    euclidean: EuclideanGate,
 }
 
 impl Default for MelodyConfig {
@@ -121,6 +144,8 @@ impl Default for MelodyConfig {
             use_motifs: true,
             motif_length: 4,
             rhythmic_complexity: 0.5,
+            fill_density: 0.0,
+            euclidean: EuclideanGate::default(),
         }
     }
 }
@@ -177,6 +202,8 @@ pub struct MelodyGenerator {
     motif_repetitions: u8,
     /// Tick accumulator
     tick_accumulator: u64,
+    /// Onset steps over one bar adopted from another track
+    rhythm: Option<Vec<bool>>,
     rng: StdRng,
 }
 
@@ -192,6 +219,7 @@ impl MelodyGenerator {
             motif_position: 0,
             motif_repetitions: 0,
             tick_accumulator: 0,
+            rhythm: None,
             rng: StdRng::from_entropy(),
         }
     }
@@ -201,6 +229,54 @@ impl MelodyGenerator {
         Box::new(Self::new())
     }
 
+    /// Get the adopted rhythm (onset steps over one bar)
+    pub fn rhythm(&self) -> Option<&[bool]> {
+        self.rhythm.as_deref()
+    }
+
+    /// Play on a fixed rhythm instead of the generated one (None = generated)
+    pub fn set_rhythm(&mut self, rhythm: Option<Vec<bool>>) {
+        self.rhythm = rhythm.filter(|r| r.iter().any(|&hit| hit));
+    }
+
+    /// Generate notes on the adopted rhythm, each lasting to the next onset
+    fn generate_on_rhythm(&mut self, rhythm: &[bool], context: &GeneratorContext) -> Vec<MidiEvent> {
+        let steps = rhythm.len() as u64;
+        let step_ticks = (context.ticks_per_bar() / steps).max(1);
+        let origin = context.total_ticks();
+        let end = origin + context.ticks_to_generate;
+        let mut events = Vec::new();
+
+        let mut step = origin.div_ceil(step_ticks);
+        while step * step_ticks < end {
+            if rhythm[(step % steps) as usize] {
+                let gap = (1..=steps).find(|n| rhythm[((step + n) % steps) as usize]).unwrap_or(steps);
+                let interval = self.choose_interval();
+                self.move_by_interval(interval, context);
+                if let Some(note) = self.note_for_degree(self.current_degree, context) {
+                    self.current_note = Some(note);
+                    let length = ((gap * step_ticks) as f64 * self.config.gate) as u64;
+                    events.push(MidiEvent::new(
+                        note,
+                        self.random_velocity(),
+                        step * step_ticks - origin,
+                        length.max(1),
+                    ));
+                }
+            }
+            step += 1;
+        }
+        events
+    }
+
+    /// Drop onsets that miss the Euclidean gate
+    fn apply_gate(&self, mut events: Vec<MidiEvent>, context: &GeneratorContext) -> Vec<MidiEvent> {
+        let origin = context.total_ticks();
+        let ticks_per_bar = context.ticks_per_bar();
+        events.retain(|e| self.config.euclidean.allows_at(origin + e.start_tick, ticks_per_bar));
+        events
+    }
+
     /// Generate a random velocity
     fn random_velocity(&mut self) -> u8 {
         let base = self.config.velocity as i16;
@@ -335,12 +411,24 @@ impl Generator for MelodyGenerator {
             }
         }
 
+        if let Some(rhythm) = self.rhythm.take() {
+            let events = self.generate_on_rhythm(&rhythm, context);
+            self.rhythm = Some(rhythm);
+            self.tick_accumulator += context.ticks_to_generate;
+            return self.apply_gate(events, context);
+        }
+
         let base_duration = context.note_duration(self.config.base_rate);
+        let rest_probability = if context.fill {
+            self.config.rest_probability * (1.0 - self.config.fill_density)
+        } else {
+            self.config.rest_probability
+        };
         let mut tick = 0u64;
 
         while tick < context.ticks_to_generate {
             // Check for rest
-            if self.rng.gen::<f64>() < self.config.rest_probability {
+            if self.rng.gen::<f64>() < rest_probability {
                 tick += base_duration;
                 continue;
             }
@@ -396,7 +484,7 @@ impl Generator for MelodyGenerator {
         }
 
         self.tick_accumulator += context.ticks_to_generate;
-        events
+        self.apply_gate(events, context)
     }
 
     fn set_param(&mut self, name: &str, value: f64) {
@@ -414,6 +502,11 @@ impl Generator for MelodyGenerator {
             "use_motifs" => self.config.use_motifs = value > 0.5,
             "motif_length" => self.config.motif_length = (value as u8).clamp(2, 8),
             "rhythmic_complexity" => self.config.rhythmic_complexity = value.clamp(0.0, 1.0),
+            "fill_density" => self.config.fill_density = value.clamp(0.0, 1.0),
+            "euclidean" => self.config.euclidean.enabled = value > 0.5,
+            "euclidean_hits" => self.config.euclidean.set_hits((value as u8).clamp(1, 32)),
+            "euclidean_steps" => self.config.euclidean.set_steps((value as u8).clamp(1, 32)),
+            "euclidean_rotation" => self.config.euclidean.set_rotation((value as u8).min(31)),
             _ => {}
         }
     }
@@ -433,6 +526,11 @@ impl Generator for MelodyGenerator {
             "use_motifs" => Some(if self.config.use_motifs { 1.0 } else { 0.0 }),
             "motif_length" => Some(self.config.motif_length as f64),
             "rhythmic_complexity" => Some(self.config.rhythmic_complexity),
+            "fill_density" => Some(self.config.fill_density),
+            "euclidean" => Some(if self.config.euclidean.enabled { 1.0 } else { 0.0 }),
+            "euclidean_hits" => Some(self.config.euclidean.hits() as f64),
+            "euclidean_steps" => Some(self.config.euclidean.steps() as f64),
+            "euclidean_rotation" => Some(self.config.euclidean.rotation() as f64),
             _ => None,
         }
     }
@@ -446,10 +544,32 @@ impl Generator for MelodyGenerator {
         self.tick_accumulator = 0;
     }
 
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    fn inspire(&mut self, inspiration: &Inspiration) -> bool {
+        let mut took = false;
+        if inspiration.has_rhythm() {
+            self.set_rhythm(Some(inspiration.rhythm.clone()));
+            took = true;
+        }
+        if let (Some(octave), Some(span)) = (inspiration.base_octave(), inspiration.octave_span()) {
+            self.config.base_octave = octave.clamp(1, 7);
+            self.config.octave_range = span.clamp(1, 4);
+            took = true;
+        }
+        took
+    }
+
     fn name(&self) -> &'static str {
         "melody"
     }
 
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        [PARAMS, &EUCLIDEAN_PARAMS].concat()
+    }
+
     fn params(&self) -> HashMap<String, f64> {
         let mut params = HashMap::new();
         params.insert("base_octave".to_string(), self.config.base_octave as f64);
@@ -465,6 +585,11 @@ impl Generator for MelodyGenerator {
         params.insert("use_motifs".to_string(), if self.config.use_motifs { 1.0 } else { 0.0 });
         params.insert("motif_length".to_string(), self.config.motif_length as f64);
         params.insert("rhythmic_complexity".to_string(), self.config.rhythmic_complexity);
+        params.insert("fill_density".to_string(), self.config.fill_density);
+        params.insert("euclidean".to_string(), if self.config.euclidean.enabled { 1.0 } else { 0.0 });
+        params.insert("euclidean_hits".to_string(), self.config.euclidean.hits() as f64);
+        params.insert("euclidean_steps".to_string(), self.config.euclidean.steps() as f64);
+        params.insert("euclidean_rotation".to_string(), self.config.euclidean.rotation() as f64);
         params
     }
 }
@@ -548,6 +673,9 @@ mod tests {
 
         melody.set_param("use_motifs", 0.0);
         assert_eq!(melody.get_param("use_motifs"), Some(0.0));
+
+        melody.set_param("fill_density", 1.5);
+        assert_eq!(melody.get_param("fill_density"), Some(1.0));
     }
 
     #[test]
@@ -563,6 +691,21 @@ mod tests {
         assert_eq!(melody.tick_accumulator, 0);
     }
 
+    #[test]
+    fn test_melody_euclidean_gate() {
+        let mut melody = MelodyGenerator::new();
+        melody.set_param("rest_probability", 0.0);
+        melody.set_param("use_motifs", 0.0);
+        melody.set_param("base_rate", 16.0);
+        melody.set_param("euclidean", 1.0);
+        melody.set_param("euclidean_hits", 3.0);
+
+        // Sixteenths survive only in the eighth-note steps 0, 3 and 6
+        let events = melody.generate(&test_context());
+        assert!(!events.is_empty());
+        assert!(events.iter().all(|e| [0, 6, 36, 42, 72, 78].contains(&e.start_tick)));
+    }
+
     #[test]
     fn test_interval_probabilities() {
         let probs = IntervalProbabilities::default();
@@ -10,13 +10,88 @@ pub mod arpeggio;
 pub mod chord;
 pub mod drone;
 pub mod drums;
+pub mod external;
+pub mod glide;
+pub mod harmony;
+pub mod inspire;
 pub mod melody;
+pub mod texture;
 
 use std::collections::HashMap;
 use std::fmt;
 
 use crate::music::scale::{Key, Note, Scale, ScaleType};
 
+pub use glide::{GlideConfig, PitchBendEvent, GLIDE_PARAMS};
+pub use inspire::Inspiration;
+pub use texture::ControlEvent;
+
+/// Humanization and groove applied to an event, kept apart from the
+/// written ("score") timing and velocity so it can be taken back out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Feel {
+    /// Ticks the event was moved (positive = late)
+    pub ticks: i64,
+    /// Velocity added
+    pub velocity: i16,
+}
+
+impl Feel {
+    /// Check if the event is as written
+    pub fn is_none(&self) -> bool {
+        self.ticks == 0 && self.velocity == 0
+    }
+
+    /// Remove this feel from a start tick and velocity
+    pub fn remove(&self, tick: u64, velocity: u8) -> (u64, u8) {
+        let tick = (tick as i64 - self.ticks).max(0) as u64;
+        let velocity = if velocity == 0 {
+            0
+        } else {
+            (velocity as i16 - self.velocity).clamp(1, 127) as u8
+        };
+        (tick, velocity)
+    }
+}
+
+/// Per-note expression. Each curve is a list of (ticks after the note
+/// start, value) breakpoints joined by straight lines; an empty curve
+/// leaves that dimension at rest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NoteExpression {
+    /// Pitch offset in semitones
+    pub pitch: Vec<(u64, f64)>,
+    /// Pressure (0.0 - 1.0)
+    pub pressure: Vec<(u64, f64)>,
+    /// Slide, sent as CC74 (0.0 - 1.0)
+    pub slide: Vec<(u64, f64)>,
+}
+
+impl NoteExpression {
+    /// Check if no curve is set
+    pub fn is_empty(&self) -> bool {
+        self.pitch.is_empty() && self.pressure.is_empty() && self.slide.is_empty()
+    }
+
+    /// Value of a curve at a tick, holding the first and last points
+    pub fn at(curve: &[(u64, f64)], tick: u64) -> Option<f64> {
+        let first = curve.first()?;
+        if tick <= first.0 {
+            return Some(first.1);
+        }
+        for pair in curve.windows(2) {
+            let ((t0, v0), (t1, v1)) = (pair[0], pair[1]);
+            if tick <= t1 {
+                if t1 == t0 {
+                    return Some(v1);
+                }
+                return Some(v0 + (v1 - v0) * (tick - t0) as f64 / (t1 - t0) as f64);
+            }
+        }
+        curve.last().map(|&(_, value)| value)
+    }
+}
+
 /// MIDI event produced by generators
 #[derive(Debug, Clone, PartialEq)]
 pub struct MidiEvent {
@@ -30,6 +105,10 @@ pub struct MidiEvent {
     pub duration_ticks: u64,
     /// MIDI channel (0-15)
     pub channel: u8,
+    /// Humanization and groove included in the timing and velocity
+    pub feel: Feel,
+    /// Per-note pitch, pressure and slide (played on MPE tracks)
+    pub expression: NoteExpression,
 }
 
 impl MidiEvent {
@@ -41,6 +120,8 @@ impl MidiEvent {
             start_tick,
             duration_ticks,
             channel: 0,
+            feel: Feel::default(),
+            expression: NoteExpression::default(),
         }
     }
 
@@ -49,6 +130,36 @@ impl MidiEvent {
         self.channel = channel;
         self
     }
+
+    /// Set the per-note expression
+    pub fn with_expression(mut self, expression: NoteExpression) -> Self {
+        self.expression = expression;
+        self
+    }
+
+    /// Move the event by some ticks and change its velocity as feel,
+    /// recording what was actually applied
+    pub fn humanize(&mut self, ticks: i64, velocity: i16) {
+        let start = (self.start_tick as i64 + ticks).max(0) as u64;
+        self.feel.ticks += start as i64 - self.start_tick as i64;
+        self.start_tick = start;
+        if self.velocity > 0 {
+            let humanized = (self.velocity as i16 + velocity).clamp(1, 127) as u8;
+            self.feel.velocity += humanized as i16 - self.velocity as i16;
+            self.velocity = humanized;
+        }
+    }
+
+    /// The event as written, with its feel taken out
+    pub fn score(&self) -> MidiEvent {
+        let (start_tick, velocity) = self.feel.remove(self.start_tick, self.velocity);
+        MidiEvent {
+            start_tick,
+            velocity,
+            feel: Feel::default(),
+            ..self.clone()
+        }
+    }
 }
 
 /// Context provided to generators for generating events
@@ -72,6 +183,8 @@ pub struct GeneratorContext {
     pub ticks_to_generate: u64,
     /// Global swing amount (0.0 - 1.0)
     pub swing: f64,
+    /// A fill has been requested for the current bar
+    pub fill: bool,
 }
 
 impl Default for GeneratorContext {
@@ -86,6 +199,7 @@ impl Default for GeneratorContext {
             key: Key::new(Note::C, ScaleType::Major),
             ticks_to_generate: 24, // One beat
             swing: 0.0,
+            fill: false,
         }
     }
 }
@@ -120,6 +234,93 @@ impl GeneratorContext {
     }
 }
 
+/// Range and documentation of a generator parameter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParamSpec {
+    /// Parameter name, as used by `set_param`
+    pub name: &'static str,
+    /// Lowest accepted value
+    pub min: f64,
+    /// Highest accepted value
+    pub max: f64,
+    /// Value of a new generator
+    pub default: f64,
+    /// Smallest meaningful change (1.0 for counts and choices)
+    pub step: f64,
+    /// Unit for display (e.g. "beats"), empty if none
+    pub unit: &'static str,
+    /// One-line description
+    pub description: &'static str,
+}
+
+impl ParamSpec {
+    /// A continuous parameter
+    pub const fn new(name: &'static str, min: f64, max: f64, default: f64, description: &'static str) -> Self {
+        Self {
+            name,
+            min,
+            max,
+            default,
+            step: 0.01,
+            unit: "",
+            description,
+        }
+    }
+
+    /// A whole-number parameter (counts, octaves, choices)
+    pub const fn whole(name: &'static str, min: f64, max: f64, default: f64, description: &'static str) -> Self {
+        Self {
+            step: 1.0,
+            ..Self::new(name, min, max, default, description)
+        }
+    }
+
+    /// An on/off parameter (0 or 1)
+    pub const fn toggle(name: &'static str, default: bool, description: &'static str) -> Self {
+        Self::whole(name, 0.0, 1.0, if default { 1.0 } else { 0.0 }, description)
+    }
+
+    /// Set the step
+    pub const fn with_step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+
+    /// Set the unit
+    pub const fn with_unit(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Check if a value is in range
+    pub fn contains(&self, value: f64) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+
+    /// Clamp a value to the range
+    pub fn clamp(&self, value: f64) -> f64 {
+        value.clamp(self.min, self.max)
+    }
+
+    /// Map a normalized value (0.0 to 1.0) into the range, on the step
+    pub fn denormalize(&self, normalized: f64) -> f64 {
+        let value = self.min + normalized.clamp(0.0, 1.0) * (self.max - self.min);
+        if self.step >= 1.0 {
+            self.clamp(self.min + ((value - self.min) / self.step).round() * self.step)
+        } else {
+            value
+        }
+    }
+}
+
+/// Ranges of the Euclidean gate parameters shared by the melodic generators
+pub const EUCLIDEAN_PARAMS: [ParamSpec; 4] = [
+    ParamSpec::toggle("euclidean", false, "Play only onsets on a Euclidean rhythm"),
+    ParamSpec::whole("euclidean_hits", 1.0, 32.0, 5.0, "Euclidean hits"),
+    ParamSpec::whole("euclidean_steps", 1.0, 32.0, 8.0, "Euclidean steps"),
+    ParamSpec::whole("euclidean_rotation", 0.0, 31.0, 0.0, "Steps the Euclidean rhythm is moved later"),
+];
+
 /// Trait for all generator implementations
 pub trait Generator: Send {
     /// Generate MIDI events for the given context
@@ -146,6 +347,41 @@ pub trait Generator: Send {
 
     /// Get a list of available parameters with their current values
     fn params(&self) -> HashMap<String, f64>;
+
+    /// Ranges and descriptions of the parameters
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        Vec::new()
+    }
+
+    /// Range and description of one parameter
+    fn param_spec(&self, name: &str) -> Option<ParamSpec> {
+        self.param_specs().into_iter().find(|spec| spec.name == name)
+    }
+
+    /// Take pitch-bend events produced by the last `generate` call
+    fn take_pitch_bends(&mut self) -> Vec<PitchBendEvent> {
+        Vec::new()
+    }
+
+    /// Take control changes produced by the last `generate` call
+    fn take_control_changes(&mut self) -> Vec<ControlEvent> {
+        Vec::new()
+    }
+
+    /// Hear the events another track played in the last buffer
+    ///
+    /// Generators that follow a source track (set with the `source`
+    /// config key) answer in their next `generate` call.
+    fn listen(&mut self, _events: &[MidiEvent]) {}
+
+    /// Seed the random source so the output can be reproduced
+    fn set_seed(&mut self, _seed: u64) {}
+
+    /// Adopt what fits from another track's recent output.
+    /// Returns true if anything was taken.
+    fn inspire(&mut self, _inspiration: &Inspiration) -> bool {
+        false
+    }
 }
 
 /// Factory function type for creating generators
@@ -171,6 +407,8 @@ impl GeneratorRegistry {
         registry.register("chord", chord::ChordGenerator::create);
         registry.register("melody", melody::MelodyGenerator::create);
         registry.register("drums", drums::DrumGenerator::create);
+        registry.register("harmony", harmony::HarmonyGenerator::create);
+        registry.register("texture", texture::TextureGenerator::create);
         registry
     }
 
@@ -179,8 +417,14 @@ impl GeneratorRegistry {
         self.factories.insert(name.to_string(), factory);
     }
 
-    /// Create a generator by name
+    /// Create a generator by name. `external:<command>` creates a generator
+    /// backed by that command (started on first use).
     pub fn create(&self, name: &str) -> Option<Box<dyn Generator>> {
+        if let Some(command) = name.strip_prefix(external::EXTERNAL_PREFIX) {
+            let command = command.trim();
+            return (!command.is_empty())
+                .then(|| Box::new(external::ExternalGenerator::new(command)) as Box<dyn Generator>);
+        }
         self.factories.get(name).map(|factory| factory())
     }
 
@@ -188,6 +432,14 @@ impl GeneratorRegistry {
     pub fn available(&self) -> Vec<String> {
         self.factories.keys().cloned().collect()
     }
+
+    /// Parameter ranges of a generator type (empty if unknown)
+    pub fn param_specs(&self, name: &str) -> Vec<ParamSpec> {
+        self.factories
+            .get(name)
+            .map(|factory| factory().param_specs())
+            .unwrap_or_default()
+    }
 }
 
 impl fmt::Debug for GeneratorRegistry {
@@ -265,6 +517,7 @@ mod tests {
         assert_eq!(ctx.tempo, 120.0);
         assert_eq!(ctx.ppqn, 24);
         assert_eq!(ctx.beats_per_bar, 4);
+        assert!(!ctx.fill);
     }
 
     #[test]
@@ -328,5 +581,34 @@ mod tests {
 
         let missing = registry.create("nonexistent");
         assert!(missing.is_none());
+
+        let external = registry.create("external: python3 model.py").unwrap();
+        assert_eq!(external.name(), "external");
+        assert!(registry.create("external:").is_none());
+    }
+
+    #[test]
+    fn test_builtin_param_specs() {
+        let registry = GeneratorRegistry::with_builtins();
+        for name in registry.available() {
+            let mut gen = registry.create(&name).unwrap();
+            let specs = gen.param_specs();
+            assert_eq!(specs.len(), gen.params().len(), "{} has undocumented parameters", name);
+            for spec in specs {
+                let param = format!("{}.{}", name, spec.name);
+                assert_eq!(gen.get_param(spec.name), Some(spec.default), "{} default", param);
+                gen.set_param(spec.name, spec.min);
+                assert_eq!(gen.get_param(spec.name), Some(spec.min), "{} min", param);
+                gen.set_param(spec.name, spec.max);
+                assert_eq!(gen.get_param(spec.name), Some(spec.max), "{} max", param);
+            }
+        }
+
+        let melody = registry.param_specs("melody");
+        let rest = melody.iter().find(|s| s.name == "rest_probability").unwrap();
+        assert!(!rest.contains(0.9));
+        let octave = registry.param_specs("chord").into_iter().find(|s| s.name == "base_octave").unwrap();
+        assert_eq!(octave.denormalize(0.5), 4.0);
+        assert!(registry.param_specs("nonexistent").is_empty());
     }
 }
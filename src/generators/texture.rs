@@ -0,0 +1,329 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! CC texture generator.
+//!
+//! Plays no notes: it sends evolving control-change streams to animate an
+//! external synth's parameters. A sweep rises and falls once per cycle, a
+//! random walk drifts within the range, and sample & hold jumps to a new
+//! random value on each step. Cycles and steps are measured in beats from
+//! the song start, so the movement stays locked to the tempo. A second CC
+//! can run the same shape on its own random path.
+
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::{Generator, GeneratorContext, MidiEvent, ParamSpec};
+
+/// Parameter ranges
+const PARAMS: &[ParamSpec] = &[
+    ParamSpec::whole("cc", 0.0, 127.0, 74.0, "Controller to animate"),
+    ParamSpec::whole("cc2", -1.0, 127.0, -1.0, "Second controller with its own path (-1 = off)"),
+    ParamSpec::whole("shape", 0.0, 2.0, 0.0, "Movement: 0 sweep, 1 random walk, 2 sample & hold"),
+    ParamSpec::new("rate", 0.25, 64.0, 4.0, "Sweep cycle or sample & hold step").with_unit("beats"),
+    ParamSpec::whole("min", 0.0, 127.0, 20.0, "Lowest value"),
+    ParamSpec::whole("max", 0.0, 127.0, 100.0, "Highest value"),
+    ParamSpec::new("drift", 0.0, 1.0, 0.25, "Random walk distance per beat (share of the range)"),
+    ParamSpec::whole("resolution", 1.0, 24.0, 6.0, "Ticks between values").with_unit("ticks"),
+];
+
+/// Control change produced by a generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlEvent {
+    /// Controller number (0-127)
+    pub cc: u8,
+    /// Value (0-127)
+    pub value: u8,
+    /// Start time in ticks from current position
+    pub start_tick: u64,
+}
+
+impl ControlEvent {
+    /// Create a new control change
+    pub fn new(cc: u8, value: u8, start_tick: u64) -> Self {
+        Self {
+            cc: cc.min(127),
+            value: value.min(127),
+            start_tick,
+        }
+    }
+}
+
+/// How the value moves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextureShape {
+    Sweep,
+    Walk,
+    SampleHold,
+}
+
+impl TextureShape {
+    fn from_index(index: u8) -> Self {
+        match index {
+            1 => TextureShape::Walk,
+            2 => TextureShape::SampleHold,
+            _ => TextureShape::Sweep,
+        }
+    }
+
+    fn index(self) -> u8 {
+        match self {
+            TextureShape::Sweep => 0,
+            TextureShape::Walk => 1,
+            TextureShape::SampleHold => 2,
+        }
+    }
+}
+
+/// Configuration for texture behavior
+#[derive(Debug, Clone)]
+struct TextureConfig {
+    cc: u8,
+    /// Second controller (None = off)
+    cc2: Option<u8>,
+    shape: TextureShape,
+    /// Cycle or step length in beats
+    rate: f64,
+    min: u8,
+    max: u8,
+    /// Walk distance per beat as a share of the range
+    drift: f64,
+    /// Ticks between values
+    resolution: u64,
+}
+
+impl Default for TextureConfig {
+    fn default() -> Self {
+        Self {
+            cc: 74,
+            cc2: None,
+            shape: TextureShape::Sweep,
+            rate: 4.0,
+            min: 20,
+            max: 100,
+            drift: 0.25,
+            resolution: 6,
+        }
+    }
+}
+
+/// One animated controller
+#[derive(Debug, Clone, Default)]
+struct Lane {
+    /// Current position within the range (0-1)
+    position: f64,
+    /// Value last sent
+    last_sent: Option<u8>,
+    /// Sample & hold step the held value belongs to
+    held_step: Option<u64>,
+}
+
+/// CC texture generator
+pub struct TextureGenerator {
+    config: TextureConfig,
+    lanes: [Lane; 2],
+    /// Control changes from the last generate call
+    pending: Vec<ControlEvent>,
+    rng: StdRng,
+}
+
+impl TextureGenerator {
+    /// Create a new texture generator
+    pub fn new() -> Self {
+        Self {
+            config: TextureConfig::default(),
+            lanes: [Lane::default(), Lane { position: 0.5, ..Lane::default() }],
+            pending: Vec::new(),
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Factory function for registry
+    pub fn create() -> Box<dyn Generator> {
+        Box::new(Self::new())
+    }
+
+    /// Position (0-1) of a lane at a song tick
+    fn position(&mut self, lane: usize, tick: u64, ppqn: u32) -> f64 {
+        let period = ((self.config.rate * ppqn as f64) as u64).max(1);
+        let config = &self.config;
+        let state = &mut self.lanes[lane];
+        match config.shape {
+            TextureShape::Sweep => {
+                // The second lane runs a quarter cycle behind
+                let phase = (tick % period) as f64 / period as f64 + lane as f64 * 0.25;
+                0.5 - 0.5 * (TAU * phase).cos()
+            }
+            TextureShape::Walk => {
+                let reach = config.drift * config.resolution as f64 / ppqn.max(1) as f64;
+                let mut position = state.position + self.rng.gen_range(-1.0..=1.0) * reach;
+                // Bounce off the ends of the range
+                if position < 0.0 {
+                    position = -position;
+                }
+                if position > 1.0 {
+                    position = 2.0 - position;
+                }
+                state.position = position.clamp(0.0, 1.0);
+                state.position
+            }
+            TextureShape::SampleHold => {
+                let step = tick / period;
+                if state.held_step != Some(step) {
+                    state.held_step = Some(step);
+                    state.position = self.rng.gen();
+                }
+                state.position
+            }
+        }
+    }
+
+    /// Controller value for a position in the range
+    fn value(&self, position: f64) -> u8 {
+        let (low, high) = (self.config.min as f64, self.config.max as f64);
+        (low + (high - low) * position).round().clamp(0.0, 127.0) as u8
+    }
+}
+
+impl Default for TextureGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Generator for TextureGenerator {
+    fn generate(&mut self, context: &GeneratorContext) -> Vec<MidiEvent> {
+        let start = context.total_ticks();
+        let resolution = self.config.resolution;
+        let controllers: Vec<u8> = [Some(self.config.cc), self.config.cc2].into_iter().flatten().collect();
+
+        for offset in 0..context.ticks_to_generate {
+            let tick = start + offset;
+            if tick % resolution != 0 {
+                continue;
+            }
+            for (lane, &cc) in controllers.iter().enumerate() {
+                let position = self.position(lane, tick, context.ppqn);
+                let value = self.value(position);
+                if self.lanes[lane].last_sent != Some(value) {
+                    self.lanes[lane].last_sent = Some(value);
+                    self.pending.push(ControlEvent::new(cc, value, offset));
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn set_param(&mut self, name: &str, value: f64) {
+        match name {
+            "cc" => self.config.cc = value.clamp(0.0, 127.0) as u8,
+            "cc2" => self.config.cc2 = (value >= 0.0).then(|| value.min(127.0) as u8),
+            "shape" => self.config.shape = TextureShape::from_index(value.clamp(0.0, 2.0) as u8),
+            "rate" => self.config.rate = value.clamp(0.25, 64.0),
+            "min" => self.config.min = value.clamp(0.0, 127.0) as u8,
+            "max" => self.config.max = value.clamp(0.0, 127.0) as u8,
+            "drift" => self.config.drift = value.clamp(0.0, 1.0),
+            "resolution" => self.config.resolution = value.clamp(1.0, 24.0) as u64,
+            _ => {}
+        }
+    }
+
+    fn get_param(&self, name: &str) -> Option<f64> {
+        match name {
+            "cc" => Some(self.config.cc as f64),
+            "cc2" => Some(self.config.cc2.map_or(-1.0, |cc| cc as f64)),
+            "shape" => Some(self.config.shape.index() as f64),
+            "rate" => Some(self.config.rate),
+            "min" => Some(self.config.min as f64),
+            "max" => Some(self.config.max as f64),
+            "drift" => Some(self.config.drift),
+            "resolution" => Some(self.config.resolution as f64),
+            _ => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.lanes = [Lane::default(), Lane { position: 0.5, ..Lane::default() }];
+        self.pending.clear();
+    }
+
+    fn name(&self) -> &'static str {
+        "texture"
+    }
+
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        PARAMS.to_vec()
+    }
+
+    fn params(&self) -> HashMap<String, f64> {
+        PARAMS
+            .iter()
+            .filter_map(|spec| Some((spec.name.to_string(), self.get_param(spec.name)?)))
+            .collect()
+    }
+
+    fn take_control_changes(&mut self) -> Vec<ControlEvent> {
+        std::mem::take(&mut self.pending)
+    }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar_context(bar: u64) -> GeneratorContext {
+        GeneratorContext {
+            bar,
+            ticks_to_generate: 96,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sweep_follows_the_beat() {
+        let mut texture = TextureGenerator::new();
+        assert!(texture.generate(&bar_context(0)).is_empty());
+        let ccs = texture.take_control_changes();
+        assert!(texture.take_control_changes().is_empty());
+
+        // A four-beat sweep: bottom on the downbeat, top halfway through
+        assert!(ccs.iter().all(|e| e.cc == 74));
+        assert_eq!(ccs[0], ControlEvent::new(74, 20, 0));
+        let top = ccs.iter().find(|e| e.start_tick == 48).unwrap();
+        assert_eq!(top.value, 100);
+        assert!(ccs.iter().all(|e| e.start_tick % 6 == 0));
+
+        // The next bar starts the cycle again
+        texture.generate(&bar_context(1));
+        assert_eq!(texture.take_control_changes()[0].value, 20);
+    }
+
+    #[test]
+    fn test_walk_and_sample_hold_stay_in_range() {
+        let mut texture = TextureGenerator::new();
+        texture.set_seed(7);
+        texture.set_param("cc2", 71.0);
+        texture.set_param("shape", 1.0);
+        texture.set_param("drift", 1.0);
+        texture.generate(&bar_context(0));
+        let walk = texture.take_control_changes();
+        assert!(walk.iter().any(|e| e.cc == 71));
+        assert!(walk.iter().all(|e| (20..=100).contains(&e.value)));
+
+        // Sample & hold changes at most once per step
+        texture.set_param("shape", 2.0);
+        texture.set_param("rate", 1.0);
+        texture.set_param("cc2", -1.0);
+        texture.generate(&bar_context(1));
+        let held = texture.take_control_changes();
+        assert!(held.len() <= 4);
+        assert!(held.iter().all(|e| e.start_tick % 24 == 0));
+    }
+}
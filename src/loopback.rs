@@ -0,0 +1,304 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! End-to-end playback tests over a virtual MIDI loopback.
+//!
+//! The harness wires tracks, parts and the scheduler together the way the
+//! live engine does, but drives them from a tick counter instead of the
+//! wall clock, and sends everything to a virtual port that records each
+//! message with the tick it went out on. Tests play a small song for a few
+//! bars and assert on the exact message sequence: clock pulses, note on/off
+//! pairs and the program changes sent on part changes.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+
+use crate::arrangement::part::MacroAction;
+use crate::arrangement::{Part, PartManager, PartTransition, TrackClipState};
+use crate::generators::GeneratorContext;
+use crate::midi::{messages, MidiOutput};
+use crate::sequencer::track::{TrackConfig, TrackManager};
+use crate::sequencer::{Clip, ClipNote, ScheduledEvent, Scheduler};
+
+/// Messages received on the loopback, with the tick each was sent on
+type Received = Arc<Mutex<Vec<(u64, Vec<u8>)>>>;
+
+/// Output end of a virtual MIDI pair; the timestamp is the sending tick
+struct VirtualOutput {
+    received: Received,
+}
+
+impl MidiOutput for VirtualOutput {
+    fn send(&mut self, message: &[u8]) -> Result<()> {
+        self.send_at(message, 0)
+    }
+
+    fn send_at(&mut self, message: &[u8], timestamp: u64) -> Result<()> {
+        self.received.lock().unwrap().push((timestamp, message.to_vec()));
+        Ok(())
+    }
+}
+
+/// Order of messages due on the same tick: the queue keeps no order for
+/// ties, so offs go before ons, then by channel and note
+fn tie_rank(event: &ScheduledEvent) -> (u8, u8, u8) {
+    let status = event.to_midi_bytes()[0];
+    let rank = match status & 0xF0 {
+        messages::PROGRAM_CHANGE => 0,
+        messages::NOTE_OFF => 1,
+        _ => 2,
+    };
+    (rank, event.channel, event.data1)
+}
+
+/// The engine driven by a tick counter
+struct Harness {
+    tracks: TrackManager,
+    parts: PartManager,
+    scheduler: Scheduler,
+    output: Box<dyn MidiOutput>,
+    received: Received,
+    context: GeneratorContext,
+    /// Next tick to play
+    tick: u64,
+    /// Part whose track states were last applied
+    applied: Option<String>,
+}
+
+impl Harness {
+    fn new(tracks: TrackManager, parts: PartManager) -> Self {
+        let received = Received::default();
+        Self {
+            tracks,
+            parts,
+            scheduler: Scheduler::new(),
+            output: Box::new(VirtualOutput { received: received.clone() }),
+            received,
+            context: GeneratorContext::default(),
+            tick: 0,
+            applied: None,
+        }
+    }
+
+    fn ticks_per_bar(&self) -> u64 {
+        self.context.ticks_per_bar()
+    }
+
+    fn send(&mut self, message: &[u8]) {
+        self.output.send_at(message, self.tick).unwrap();
+    }
+
+    fn start(&mut self) {
+        self.scheduler.start();
+        self.send(&[messages::START]);
+    }
+
+    fn trigger(&mut self, part: &str) -> bool {
+        let ppqn = self.context.ppqn;
+        let beats = self.context.beats_per_bar as u32;
+        self.parts.trigger_part(part, self.tick, ppqn, beats)
+    }
+
+    /// Launch the clips and send the program changes of a part that just
+    /// became current
+    fn apply_part(&mut self) {
+        let Some(part) = self.parts.current() else {
+            return;
+        };
+        if self.applied.as_deref() == Some(part.name()) {
+            return;
+        }
+        self.applied = Some(part.name().to_string());
+        for (&index, state) in part.track_states() {
+            let Some(track) = self.tracks.track_mut(index) else {
+                continue;
+            };
+            match state {
+                TrackClipState::Clip(clip) => {
+                    track.set_active_clip(Some(*clip));
+                    if let Some(clip) = track.active_clip_mut() {
+                        clip.reset();
+                        clip.play();
+                    }
+                }
+                TrackClipState::Stop => track.set_active_clip(None),
+                _ => {}
+            }
+        }
+        let programs: Vec<(u8, u8)> = part
+            .macros()
+            .iter()
+            .filter_map(|action| match action {
+                MacroAction::SendProgramChange(channel, program) => Some((*channel, *program)),
+                _ => None,
+            })
+            .collect();
+        for (channel, program) in programs {
+            self.scheduler
+                .schedule(ScheduledEvent::program_change(self.tick, channel, program));
+        }
+    }
+
+    /// Play up to (not including) a tick: one clock pulse per tick, a
+    /// generate pass per beat, and every event that falls due
+    fn run_until(&mut self, end: u64) {
+        let ppqn = self.context.ppqn as u64;
+        while self.tick < end {
+            self.parts.update(self.tick);
+            self.apply_part();
+
+            if self.tick % ppqn == 0 {
+                let beat = self.tick / ppqn;
+                self.context.bar = beat / self.context.beats_per_bar as u64;
+                self.context.beat = beat % self.context.beats_per_bar as u64;
+                self.context.ticks_to_generate = ppqn;
+                let events = self.tracks.generate_all(&self.context, self.tick);
+                self.scheduler.schedule_all(events);
+            }
+
+            self.send(&[messages::TIMING_CLOCK]);
+            self.flush();
+            self.tick += 1;
+        }
+    }
+
+    /// Send the events due by the current tick
+    fn flush(&mut self) {
+        let mut due = self.scheduler.poll_ticks(self.tick);
+        due.sort_by_key(|event| (event.time_ticks, tie_rank(event)));
+        for event in due {
+            self.output.send_at(&event.to_midi_bytes(), event.time_ticks).unwrap();
+        }
+    }
+
+    fn stop(&mut self) {
+        self.flush();
+        self.scheduler.stop();
+        self.send(&[messages::STOP]);
+    }
+
+    /// Everything received except clock pulses
+    fn messages(&self) -> Vec<(u64, Vec<u8>)> {
+        self.received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, bytes)| bytes[0] != messages::TIMING_CLOCK)
+            .cloned()
+            .collect()
+    }
+
+    /// Ticks of the clock pulses received
+    fn clock_ticks(&self) -> Vec<u64> {
+        self.received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, bytes)| bytes[0] == messages::TIMING_CLOCK)
+            .map(|(tick, _)| *tick)
+            .collect()
+    }
+}
+
+/// A one-bar clip
+fn clip(name: &str, notes: &[(u64, u64, u8)]) -> Clip {
+    let mut clip = Clip::new(name, 96);
+    clip.add_notes(notes.iter().map(|&(start, duration, note)| ClipNote::new(start, duration, note, 100)));
+    clip
+}
+
+/// Bass on channel 1 and a lead on channel 2, with a Verse and a Chorus
+/// that switches the lead's clip and program on the next bar
+fn small_song() -> Harness {
+    let mut tracks = TrackManager::new();
+    let bass = tracks.add_track(TrackConfig {
+        name: "Bass".to_string(),
+        channel: 0,
+        ..TrackConfig::default()
+    });
+    let lead = tracks.add_track(TrackConfig {
+        name: "Lead".to_string(),
+        channel: 1,
+        ..TrackConfig::default()
+    });
+    tracks.track_mut(bass).unwrap().add_clip(clip("Root", &[(0, 12, 36), (48, 12, 36)]));
+    let lead_track = tracks.track_mut(lead).unwrap();
+    lead_track.add_clip(clip("Verse", &[(0, 24, 60)]));
+    lead_track.add_clip(clip("Chorus", &[(0, 24, 67)]));
+
+    let mut parts = PartManager::new(2);
+    parts.add_part(
+        Part::new("Verse")
+            .with_track(bass, TrackClipState::Clip(0))
+            .with_track(lead, TrackClipState::Clip(0))
+            .with_macro(MacroAction::SendProgramChange(1, 5)),
+    );
+    parts.add_part(
+        Part::new("Chorus")
+            .with_transition(PartTransition::NextBar)
+            .with_track(lead, TrackClipState::Clip(1))
+            .with_macro(MacroAction::SendProgramChange(1, 9)),
+    );
+    Harness::new(tracks, parts)
+}
+
+#[test]
+fn test_loopback_plays_song_with_part_change() {
+    let mut harness = small_song();
+    harness.start();
+    assert!(harness.trigger("Verse"));
+    harness.run_until(30);
+
+    // Queued mid-bar: the Chorus starts on the next downbeat
+    assert!(harness.trigger("Chorus"));
+    harness.run_until(2 * harness.ticks_per_bar());
+    harness.stop();
+
+    assert_eq!(harness.clock_ticks(), (0..192).collect::<Vec<u64>>());
+    let expected: Vec<(u64, Vec<u8>)> = vec![
+        (0, vec![messages::START]),
+        (0, vec![0xC1, 5]),
+        (0, vec![0x90, 36, 100]),
+        (0, vec![0x91, 60, 100]),
+        (12, vec![0x80, 36, 0]),
+        (24, vec![0x81, 60, 0]),
+        (48, vec![0x90, 36, 100]),
+        (60, vec![0x80, 36, 0]),
+        (96, vec![0xC1, 9]),
+        (96, vec![0x90, 36, 100]),
+        (96, vec![0x91, 67, 100]),
+        (108, vec![0x80, 36, 0]),
+        (120, vec![0x81, 67, 0]),
+        (144, vec![0x90, 36, 100]),
+        (156, vec![0x80, 36, 0]),
+        (192, vec![messages::STOP]),
+    ];
+    assert_eq!(harness.messages(), expected);
+}
+
+#[test]
+fn test_loopback_notes_are_paired() {
+    let mut harness = small_song();
+    harness.start();
+    harness.trigger("Verse");
+    harness.run_until(4 * harness.ticks_per_bar());
+    harness.stop();
+
+    // Every note on is closed by a note off on the same channel and note,
+    // and nothing is left sounding at the stop
+    let mut sounding: Vec<(u8, u8)> = Vec::new();
+    for (_, bytes) in harness.messages() {
+        match bytes[0] & 0xF0 {
+            messages::NOTE_ON => sounding.push((bytes[0] & 0x0F, bytes[1])),
+            messages::NOTE_OFF => {
+                let index = sounding.iter().position(|&n| n == (bytes[0] & 0x0F, bytes[1]));
+                assert!(index.is_some(), "note off without a note on: {:?}", bytes);
+                sounding.remove(index.unwrap());
+            }
+            _ => {}
+        }
+    }
+    assert!(sounding.is_empty());
+    assert_eq!(harness.clock_ticks().len(), 4 * 96);
+}
@@ -13,10 +13,19 @@ mod sequencer;
 mod timing;
 mod ui;
 
+#[cfg(test)]
+mod loopback;
+
 use anyhow::Result;
-use midi::{print_destinations, print_sources, CoreMidiOutput, MidiInput, MidiOutput};
+use control::{Repl, ReplOutput};
+use midi::{
+    print_destinations, print_sources, run_self_test, CoreMidiOutput, MidiInput, MidiOutput,
+};
+use timing::calibration::{CalibrationTone, SyncOffsets, SyncOutput};
 use timing::MidiClock;
 use std::env;
+use std::io::{self, BufRead, Write};
+use std::net::{ToSocketAddrs, UdpSocket};
 use std::thread;
 use std::time::{Duration, Instant};
 
@@ -31,6 +40,20 @@ fn print_usage() {
     println!("  --test-note <N>         Send a test note to MIDI destination N");
     println!("  --test-clock <N> [BPM]  Send MIDI clock to destination N at BPM (default 120)");
     println!("  --monitor <N>           Monitor MIDI input from source N");
+    println!("  self-test --out <N> --in <M>");
+    println!("                          Loop test patterns from destination N back into source M");
+    println!("  calibrate --out <N> [--bpm <B>] [--osc <HOST:PORT>] [--controls <FILE>]");
+    println!("                          Play a sync pattern on MIDI, audio and lights to measure delays");
+    println!("  --render <SONG> <OUT.wav> [--bars <N>] [--seed <S>]");
+    println!("                          Render the song's tracks through its soundfont, faster than real time");
+    println!("  repl                    Live-code tracks and patterns from the terminal");
+    println!("  bundle <SONG> <OUT.zip> Pack a song and the files it uses into one archive");
+    println!("  project <SONG> <OUT> [--controls <FILE>] [--scales <FILE>]");
+    println!("                          Save a song, its clips and controls as a project folder or .zip");
+    println!("  recover <OUT.mid>       Save the take left unfinished by a crash");
+    println!("  import-drums <FILE> <DIR>");
+    println!("                          Convert Hydrogen or step CSV drum patterns to MIDI clips");
+    println!("  analyze <FILE.mid>      Suggest generator settings that imitate a MIDI file");
     println!("  --help                  Show this help message");
 }
 
@@ -129,9 +152,277 @@ fn monitor_input(source: usize) -> Result<()> {
     Ok(())
 }
 
+fn self_test(destination: usize, source: usize) -> Result<bool> {
+    println!("Connecting to MIDI destination {} and source {}...", destination, source);
+    let mut output = CoreMidiOutput::new(destination)?;
+    let input = MidiInput::new(source)?;
+
+    // Give the input port a moment to connect
+    thread::sleep(Duration::from_millis(100));
+
+    println!("Sending test pattern (notes, CC, SysEx)...");
+    println!();
+    let report = run_self_test(
+        &mut output,
+        &input,
+        64,
+        Duration::from_millis(10),
+        Duration::from_secs(1),
+    )?;
+    report.print();
+    Ok(report.passed())
+}
+
+fn run_repl() -> Result<()> {
+    let mut repl = Repl::new(24);
+    let mut tracks = sequencer::track::TrackManager::new();
+    println!("SEQ live coding - type help for commands, quit to leave");
+
+    let stdin = io::stdin();
+    loop {
+        print!("{}", control::repl::REPL_PROMPT);
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            break;
+        }
+        match repl.eval(&line, &mut tracks) {
+            Ok(ReplOutput::Message(message)) if !message.is_empty() => println!("{}", message),
+            Ok(ReplOutput::Message(_)) => {}
+            Ok(ReplOutput::Action(action)) => println!("-> {:?}", action),
+            Ok(ReplOutput::Quit) => break,
+            Err(e) => eprintln!("Error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Find the text after a `--name` flag
+fn flag_text<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let index = args.iter().position(|a| a == name)?;
+    args.get(index + 1).map(String::as_str)
+}
+
+/// Find the value after a `--name` flag
+fn flag_value(args: &[String], name: &str) -> Option<usize> {
+    flag_text(args, name)?.parse().ok()
+}
+
+/// Render the generator tracks of a song through its soundfont into a WAV
+/// file, as fast as the synth can go
+fn render_song(song_path: &str, out: &str, bars: u64, seed: u64) -> Result<()> {
+    let project = config::SongFile::load_project(song_path)?;
+    let song = &project.song;
+    if let Some(missing) = project.missing_files().first() {
+        return Err(anyhow::anyhow!("{} refers to a missing file: {:?}", song_path, missing));
+    }
+    let Some(soundfont) = song.song.soundfont.as_deref() else {
+        return Err(anyhow::anyhow!("{} has no soundfont to render with", song_path));
+    };
+    let mut synth = audio::FluidSynth::new();
+    synth.load_soundfont(&config::resolve_path(song_path.as_ref(), soundfont).to_string_lossy())?;
+
+    let ppqn = song.song.resolution()?;
+    let key = music::Key::parse(&song.song.key, &song.song.scale)
+        .ok_or_else(|| anyhow::anyhow!("Key '{} {}' is not valid", song.song.key, song.song.scale))?;
+    let mut context = generators::GeneratorContext {
+        tempo: song.song.tempo,
+        ppqn,
+        beats_per_bar: song.song.time_signature_num.max(1),
+        key,
+        ticks_to_generate: ppqn as u64,
+        swing: song.song.swing,
+        ..Default::default()
+    };
+
+    // One engine track per song track, with its generator and parameters
+    let registry = generators::GeneratorRegistry::with_builtins();
+    let mut tracks = sequencer::track::TrackManager::new();
+    let mut events = Vec::new();
+    for (index, track) in song.tracks.iter().enumerate() {
+        let channel = track.channel.clamp(1, 16) - 1;
+        let mut engine_config = sequencer::track::TrackConfig::new(track.name.clone()).with_channel(channel);
+        engine_config.processors = track.processor_chain()?;
+        engine_config.step_lanes = track.step_lanes()?;
+        tracks.add_track(engine_config);
+        if let Some(program) = track.program_number() {
+            events.push(sequencer::ScheduledEvent::program_change(0, channel, program));
+        }
+        let Some(generator) = song.build_generator(track, &registry)? else {
+            continue;
+        };
+        if let Some(engine_track) = tracks.track_mut(index) {
+            engine_track.set_generator(generator);
+            engine_track.set_seed(seed.wrapping_add(index as u64));
+        }
+    }
+
+    let beats = bars * context.beats_per_bar as u64;
+    for beat in 0..beats {
+        context.bar = beat / context.beats_per_bar as u64;
+        context.beat = beat % context.beats_per_bar as u64;
+        events.extend(tracks.generate_all(&context, beat * ppqn as u64));
+    }
+
+    println!("Rendering {} bar(s) of {} into {}...", bars, song.song.name, out);
+    let started = Instant::now();
+    let mut wav = audio::WavWriter::create(out, synth.sample_rate() as u32, 2)?;
+    let frames = audio::render_offline(&mut synth, &events, song.song.tempo, ppqn, 2.0, &mut wav)?;
+    wav.finish()?;
+    println!(
+        "Wrote {:.1}s of audio in {:.1}s",
+        frames as f64 / synth.sample_rate(),
+        started.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
+
+/// Play the A/V sync pattern for 64 beats: a note on a MIDI destination,
+/// a click on the audio output and, if given, an OSC flash
+fn calibrate(destination: usize, bpm: f64, osc: Option<&str>, offsets: SyncOffsets) -> Result<()> {
+    let tone = CalibrationTone::new(bpm).with_offsets(offsets);
+    let mut output = CoreMidiOutput::new(destination)?;
+    let flash = match osc {
+        Some(target) => {
+            let addr = target
+                .to_socket_addrs()?
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("OSC target not found: {}", target))?;
+            Some((UdpSocket::bind(("0.0.0.0", 0))?, addr))
+        }
+        None => None,
+    };
+
+    // The click is rendered in the audio callback from the frame count
+    let config = audio::AudioConfig::default();
+    let sample_rate = config.sample_rate;
+    let click = tone.clone();
+    let mut frame = 0u64;
+    let _audio = match audio::AudioOutput::new(config, move |buffer, channels| {
+        click.render_click(buffer, channels, sample_rate, frame);
+        frame += (buffer.len() / channels.max(1)) as u64;
+    }) {
+        Ok(audio) => Some(audio),
+        Err(e) => {
+            eprintln!("No audio click: {}", e);
+            None
+        }
+    };
+
+    println!("Calibrating at {} BPM for 64 beats (press Ctrl+C to stop)...", bpm);
+    println!(
+        "Delays: MIDI {:.1} ms, audio {:.1} ms, lighting {:.1} ms",
+        offsets.midi_ms, offsets.audio_ms, offsets.lighting_ms
+    );
+    println!("Record the room and enter how late each output lands under `latency:` in the controls file");
+
+    let start = Instant::now();
+    let end_ms = tone.beat_ms() * 64.0;
+    let mut sent_ms = 0.0;
+    let mut notes_off = Vec::new();
+    while sent_ms < end_ms {
+        thread::sleep(Duration::from_millis(1));
+        let now_ms = start.elapsed().as_secs_f64() * 1000.0;
+        for pulse in tone.pulses(SyncOutput::Midi, sent_ms, now_ms) {
+            output.send(&tone.note_on(&pulse))?;
+            notes_off.push((pulse.time_ms + tone.beat_ms() / 2.0, tone.note_off(&pulse)));
+            if pulse.accent {
+                println!("Beat {}", pulse.beat + 1);
+            }
+        }
+        if let Some((socket, addr)) = &flash {
+            for pulse in tone.pulses(SyncOutput::Lighting, sent_ms, now_ms) {
+                socket.send_to(&tone.flash(&pulse).to_bytes(), addr)?;
+            }
+        }
+        for (_, message) in notes_off.iter().filter(|(at, _)| *at < now_ms) {
+            output.send(message)?;
+        }
+        notes_off.retain(|(at, _)| *at >= now_ms);
+        sent_ms = now_ms;
+    }
+    for (_, message) in notes_off {
+        output.send(&message)?;
+    }
+    println!("Calibration complete");
+    Ok(())
+}
+
+/// Save the take an unfinished recording left in its journal
+fn recover_take(out: &str) -> Result<()> {
+    let Some(path) = recording::default_journal_path() else {
+        return Err(anyhow::anyhow!("No home folder to look for a recording journal in"));
+    };
+    let Some(take) = recording::RecoveredTake::recover(&path)? else {
+        println!("No unfinished take to recover");
+        return Ok(());
+    };
+    take.to_exporter(timing::PPQN).export(out)?;
+    println!("Recovered {} note(s) and {} move(s) into {}", take.notes.len(), take.moves.len(), out);
+    std::fs::remove_file(&path)?;
+    Ok(())
+}
+
+fn import_drums(file: &str, out_dir: &str) -> Result<()> {
+    let patterns = recording::load_drum_patterns(file, timing::PPQN)?;
+    std::fs::create_dir_all(out_dir)?;
+    println!("Imported {} pattern(s) from {}", patterns.len(), file);
+    for pattern in &patterns {
+        let path = std::path::Path::new(out_dir).join(format!("{}.mid", pattern.file_stem()));
+        pattern.to_exporter().export(&path)?;
+        println!("  {} ({} hits, {} voices) -> {}", pattern.name, pattern.hits.len(), pattern.voices.len(), path.display());
+    }
+    Ok(())
+}
+
+/// Print what a MIDI file sounds like and generator settings to imitate it
+fn analyze_midi(file: &str) -> Result<()> {
+    let analysis = recording::MidiAnalysis::load(file)?;
+    let (numerator, denominator) = analysis.time_signature;
+    println!("{}: {:.1} BPM, {}/{}, {:.1} bars", file, analysis.tempo, numerator, denominator, analysis.bars);
+    if let Some(key) = &analysis.key {
+        println!("  Key guess: {}", key);
+    }
+    println!("  Density: {:.2} notes per beat", analysis.density);
+    if let (Some((soft, loud)), Some(average)) = (analysis.velocity_range, analysis.average_velocity) {
+        println!("  Velocity: {}-{} (average {})", soft, loud, average);
+    }
+    if analysis.moves() > 0 {
+        println!(
+            "  Intervals: {:.0}% repeats, {:.0}% steps, {:.0}% skips, {:.0}% leaps",
+            analysis.interval_share(0, 0) * 100.0,
+            analysis.interval_share(1, 2) * 100.0,
+            analysis.interval_share(3, 4) * 100.0,
+            analysis.interval_share(5, 12) * 100.0
+        );
+    }
+    if let Some(drums) = analysis.drums {
+        println!("  Drums per bar: {:.1} kick, {:.1} snare, {:.1} hat", drums.kick, drums.snare, drums.hat);
+    }
+
+    println!();
+    println!("tracks:");
+    for suggestion in analysis.suggestions() {
+        println!("  - name: \"{}\"", suggestion.generator);
+        println!("    generator: {}", suggestion.generator);
+        println!("    config:");
+        for (param, value) in suggestion.params {
+            println!("      {}: {}", param, value);
+        }
+    }
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
 
+    // A journal left behind means the last take never finished
+    if let Some(path) = recording::default_journal_path() {
+        if args.get(1).map(String::as_str) != Some("recover") && path.is_file() {
+            eprintln!("Found an unfinished recording in {:?}; run `seq recover out.mid` to save it", path);
+        }
+    }
+
     if args.len() < 2 {
         println!("SEQ - Algorithmic MIDI Sequencer");
         println!("Run with --help for usage information");
@@ -183,6 +474,100 @@ fn main() -> Result<()> {
             })?;
             monitor_input(source)?;
         }
+        "self-test" | "--self-test" => {
+            let (Some(destination), Some(source)) =
+                (flag_value(&args, "--out"), flag_value(&args, "--in"))
+            else {
+                eprintln!("Error: self-test requires --out <N> and --in <M>");
+                eprintln!("Use --list-midi and --list-sources to see available ports");
+                std::process::exit(1);
+            };
+            if !self_test(destination, source)? {
+                std::process::exit(1);
+            }
+        }
+        "--render" | "render" => {
+            if args.len() < 4 {
+                eprintln!("Error: --render requires a song file and an output file");
+                eprintln!("Usage: seq --render song.yaml out.wav [--bars 16] [--seed 1]");
+                std::process::exit(1);
+            }
+            let bars = flag_value(&args, "--bars").unwrap_or(16) as u64;
+            let seed = flag_value(&args, "--seed").unwrap_or(0) as u64;
+            render_song(&args[2], &args[3], bars, seed)?;
+        }
+        "calibrate" | "--calibrate" => {
+            let Some(destination) = flag_value(&args, "--out") else {
+                eprintln!("Error: calibrate requires --out <N>");
+                eprintln!("Use --list-midi to see available destinations");
+                std::process::exit(1);
+            };
+            let bpm = flag_text(&args, "--bpm").and_then(|b| b.parse().ok()).unwrap_or(120.0);
+            let offsets = match flag_text(&args, "--controls") {
+                Some(path) => match config::ControlsFile::load(path)?.latency {
+                    Some(latency) => latency.to_offsets()?,
+                    None => SyncOffsets::default(),
+                },
+                None => SyncOffsets::default(),
+            };
+            calibrate(destination, bpm, flag_text(&args, "--osc"), offsets)?;
+        }
+        "repl" | "--repl" => {
+            run_repl()?;
+        }
+        "bundle" | "--bundle" => {
+            if args.len() < 4 {
+                eprintln!("Error: bundle requires a song file and an output file");
+                eprintln!("Usage: seq bundle song.yaml out.zip");
+                std::process::exit(1);
+            }
+            let assets = config::write_bundle(args[2].as_ref(), args[3].as_ref())?;
+            println!("Bundled {} with {} file(s) into {}", args[2], assets.len(), args[3]);
+            for asset in assets {
+                println!("  {}", asset);
+            }
+        }
+        "project" | "--project" => {
+            if args.len() < 4 {
+                eprintln!("Error: project requires a song file and an output folder or file");
+                eprintln!("Usage: seq project song.yaml out/ [--controls controls.yaml] [--scales scales.yaml]");
+                std::process::exit(1);
+            }
+            let controls = flag_text(&args, "--controls").map(std::path::Path::new);
+            let scales = match flag_text(&args, "--scales") {
+                Some(path) => config::load_scales(path.as_ref())?,
+                None => Vec::new(),
+            };
+            let manifest = config::save_project(args[2].as_ref(), controls, &scales, args[3].as_ref())?;
+            println!("Saved {} with {} file(s) into {}", manifest.name, manifest.files.len(), args[3]);
+            for soundfont in &manifest.soundfonts {
+                println!("  soundfont referenced at {}", soundfont);
+            }
+        }
+        "recover" | "--recover" => {
+            if args.len() < 3 {
+                eprintln!("Error: recover requires an output file");
+                eprintln!("Usage: seq recover take.mid");
+                std::process::exit(1);
+            }
+            recover_take(&args[2])?;
+        }
+        "import-drums" | "--import-drums" => {
+            if args.len() < 4 {
+                eprintln!("Error: import-drums requires a pattern file and an output folder");
+                eprintln!("Usage: seq import-drums beats.h2song clips/");
+                std::process::exit(1);
+            }
+            import_drums(&args[2], &args[3])?;
+        }
+        "analyze" | "--analyze" => {
+            if args.len() < 3 {
+                eprintln!("Error: analyze requires a MIDI file");
+                eprintln!("Usage: seq analyze song.mid");
+                std::process::exit(1);
+            }
+            analyze_midi(&args[2])?;
+        }
         "--help" | "-h" => {
             print_usage();
         }
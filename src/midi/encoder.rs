@@ -0,0 +1,239 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Output encoding for bandwidth-limited ports.
+//!
+//! Messages are queued per destination and flushed once per clock interval
+//! as a single packet. Within a packet the encoder applies running status
+//! and merges consecutive moves of the same controller, which matters on
+//! busy 5-pin DIN ports (3125 bytes per second).
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::config::OutputPortConfig;
+
+use super::{messages, MidiOutput};
+
+/// Encoder settings for one destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncoderSettings {
+    /// Omit repeated status bytes within a packet
+    pub running_status: bool,
+    /// Merge consecutive CCs and drop CCs that repeat the last sent value
+    pub coalesce_cc: bool,
+}
+
+impl Default for EncoderSettings {
+    fn default() -> Self {
+        Self {
+            running_status: true,
+            coalesce_cc: true,
+        }
+    }
+}
+
+impl EncoderSettings {
+    /// Build settings from a controls file output entry
+    pub fn from_config(config: &OutputPortConfig) -> Self {
+        Self {
+            running_status: config.running_status,
+            coalesce_cc: config.coalesce_cc,
+        }
+    }
+}
+
+/// Batching encoder for a single output port
+#[derive(Debug, Clone, Default)]
+pub struct OutputEncoder {
+    /// Settings
+    settings: EncoderSettings,
+    /// Messages waiting for the next flush
+    pending: Vec<Vec<u8>>,
+    /// Last CC value sent per (channel, controller)
+    last_cc: HashMap<(u8, u8), u8>,
+    /// Bytes saved since creation
+    bytes_saved: usize,
+}
+
+impl OutputEncoder {
+    /// Create an encoder with the given settings
+    pub fn new(settings: EncoderSettings) -> Self {
+        Self {
+            settings,
+            ..Default::default()
+        }
+    }
+
+    /// Get settings
+    pub fn settings(&self) -> EncoderSettings {
+        self.settings
+    }
+
+    /// Enable or disable running status
+    pub fn set_running_status(&mut self, enabled: bool) {
+        self.settings.running_status = enabled;
+    }
+
+    /// Enable or disable CC coalescing
+    pub fn set_coalesce_cc(&mut self, enabled: bool) {
+        self.settings.coalesce_cc = enabled;
+    }
+
+    /// Number of queued messages
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Bytes saved by running status and coalescing
+    pub fn bytes_saved(&self) -> usize {
+        self.bytes_saved
+    }
+
+    /// Forget remembered CC values (e.g. after the device reconnects)
+    pub fn reset(&mut self) {
+        self.last_cc.clear();
+    }
+
+    /// Queue a message for the next flush
+    pub fn queue(&mut self, message: &[u8]) {
+        let Some(&status) = message.first() else {
+            return;
+        };
+
+        if self.settings.coalesce_cc && status & 0xF0 == messages::CONTROL_CHANGE && message.len() == 3 {
+            let key = (status & 0x0F, message[1]);
+
+            // A newer move of the same controller replaces the queued one
+            if let Some(last) = self.pending.last_mut() {
+                if last.len() == 3 && last[0] == status && last[1] == message[1] {
+                    last[2] = message[2];
+                    self.bytes_saved += 3;
+                    return;
+                }
+            }
+
+            // Nothing changed since the last flush
+            let queued = self
+                .pending
+                .iter()
+                .any(|m| m.len() == 3 && m[0] == status && m[1] == message[1]);
+            if !queued && self.last_cc.get(&key) == Some(&message[2]) {
+                self.bytes_saved += 3;
+                return;
+            }
+        }
+
+        self.pending.push(message.to_vec());
+    }
+
+    /// Encode queued messages into one byte stream, clearing the queue
+    pub fn encode(&mut self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut running: Option<u8> = None;
+
+        for message in self.pending.drain(..) {
+            let status = message[0];
+
+            if status >= 0xF8 {
+                // Real-time bytes may appear anywhere and keep running status
+                bytes.push(status);
+                continue;
+            }
+
+            if status >= 0xF0 {
+                // System common/exclusive cancels running status
+                running = None;
+                bytes.extend_from_slice(&message);
+                continue;
+            }
+
+            if status & 0xF0 == messages::CONTROL_CHANGE && message.len() == 3 {
+                self.last_cc.insert((status & 0x0F, message[1]), message[2]);
+            }
+
+            if self.settings.running_status && running == Some(status) {
+                bytes.extend_from_slice(&message[1..]);
+                self.bytes_saved += 1;
+            } else {
+                bytes.extend_from_slice(&message);
+                running = Some(status);
+            }
+        }
+
+        bytes
+    }
+
+    /// Send everything queued as one packet
+    pub fn flush(&mut self, output: &mut dyn MidiOutput, timestamp: u64) -> Result<usize> {
+        if self.pending.is_empty() {
+            return Ok(0);
+        }
+        let bytes = self.encode();
+        output.send_at(&bytes, timestamp)?;
+        Ok(bytes.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_status() {
+        let mut encoder = OutputEncoder::default();
+        encoder.queue(&[0x90, 60, 100]);
+        encoder.queue(&[0x90, 64, 100]);
+        encoder.queue(&[0xF8]);
+        encoder.queue(&[0x90, 67, 100]);
+        encoder.queue(&[0x80, 60, 0]);
+
+        assert_eq!(
+            encoder.encode(),
+            vec![0x90, 60, 100, 64, 100, 0xF8, 67, 100, 0x80, 60, 0]
+        );
+        assert_eq!(encoder.pending(), 0);
+    }
+
+    #[test]
+    fn test_running_status_disabled() {
+        let mut encoder = OutputEncoder::new(EncoderSettings {
+            running_status: false,
+            coalesce_cc: true,
+        });
+        encoder.queue(&[0x90, 60, 100]);
+        encoder.queue(&[0x90, 64, 100]);
+        assert_eq!(encoder.encode(), vec![0x90, 60, 100, 0x90, 64, 100]);
+    }
+
+    #[test]
+    fn test_sysex_cancels_running_status() {
+        let mut encoder = OutputEncoder::default();
+        encoder.queue(&[0x90, 60, 100]);
+        encoder.queue(&[0xF0, 0x7E, 0xF7]);
+        encoder.queue(&[0x90, 64, 100]);
+        assert_eq!(
+            encoder.encode(),
+            vec![0x90, 60, 100, 0xF0, 0x7E, 0xF7, 0x90, 64, 100]
+        );
+    }
+
+    #[test]
+    fn test_cc_coalescing() {
+        let mut encoder = OutputEncoder::default();
+        encoder.queue(&[0xB0, 74, 10]);
+        encoder.queue(&[0xB0, 74, 20]);
+        encoder.queue(&[0xB0, 74, 30]);
+        encoder.queue(&[0xB0, 71, 5]);
+        assert_eq!(encoder.encode(), vec![0xB0, 74, 30, 71, 5]);
+
+        // Same value as already sent is dropped
+        encoder.queue(&[0xB0, 74, 30]);
+        assert_eq!(encoder.pending(), 0);
+
+        encoder.reset();
+        encoder.queue(&[0xB0, 74, 30]);
+        assert_eq!(encoder.pending(), 1);
+    }
+}
@@ -0,0 +1,305 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! General MIDI name database.
+//!
+//! Program and percussion names from the GM Level 1 sound set, with
+//! number→name lookup for display and name→number lookup for config files.
+//! Name matching ignores case, spaces and punctuation, so "Acoustic Snare",
+//! "acoustic_snare" and "acoustic-snare" all resolve to the same note.
+
+/// GM program names (program 0-127)
+pub const PROGRAMS: [&str; 128] = [
+    // Piano
+    "Acoustic Grand Piano",
+    "Bright Acoustic Piano",
+    "Electric Grand Piano",
+    "Honky-tonk Piano",
+    "Electric Piano 1",
+    "Electric Piano 2",
+    "Harpsichord",
+    "Clavinet",
+    // Chromatic percussion
+    "Celesta",
+    "Glockenspiel",
+    "Music Box",
+    "Vibraphone",
+    "Marimba",
+    "Xylophone",
+    "Tubular Bells",
+    "Dulcimer",
+    // Organ
+    "Drawbar Organ",
+    "Percussive Organ",
+    "Rock Organ",
+    "Church Organ",
+    "Reed Organ",
+    "Accordion",
+    "Harmonica",
+    "Tango Accordion",
+    // Guitar
+    "Acoustic Guitar (nylon)",
+    "Acoustic Guitar (steel)",
+    "Electric Guitar (jazz)",
+    "Electric Guitar (clean)",
+    "Electric Guitar (muted)",
+    "Overdriven Guitar",
+    "Distortion Guitar",
+    "Guitar Harmonics",
+    // Bass
+    "Acoustic Bass",
+    "Electric Bass (finger)",
+    "Electric Bass (pick)",
+    "Fretless Bass",
+    "Slap Bass 1",
+    "Slap Bass 2",
+    "Synth Bass 1",
+    "Synth Bass 2",
+    // Strings
+    "Violin",
+    "Viola",
+    "Cello",
+    "Contrabass",
+    "Tremolo Strings",
+    "Pizzicato Strings",
+    "Orchestral Harp",
+    "Timpani",
+    // Ensemble
+    "String Ensemble 1",
+    "String Ensemble 2",
+    "Synth Strings 1",
+    "Synth Strings 2",
+    "Choir Aahs",
+    "Voice Oohs",
+    "Synth Voice",
+    "Orchestra Hit",
+    // Brass
+    "Trumpet",
+    "Trombone",
+    "Tuba",
+    "Muted Trumpet",
+    "French Horn",
+    "Brass Section",
+    "Synth Brass 1",
+    "Synth Brass 2",
+    // Reed
+    "Soprano Sax",
+    "Alto Sax",
+    "Tenor Sax",
+    "Baritone Sax",
+    "Oboe",
+    "English Horn",
+    "Bassoon",
+    "Clarinet",
+    // Pipe
+    "Piccolo",
+    "Flute",
+    "Recorder",
+    "Pan Flute",
+    "Blown Bottle",
+    "Shakuhachi",
+    "Whistle",
+    "Ocarina",
+    // Synth lead
+    "Lead 1 (square)",
+    "Lead 2 (sawtooth)",
+    "Lead 3 (calliope)",
+    "Lead 4 (chiff)",
+    "Lead 5 (charang)",
+    "Lead 6 (voice)",
+    "Lead 7 (fifths)",
+    "Lead 8 (bass + lead)",
+    // Synth pad
+    "Pad 1 (new age)",
+    "Pad 2 (warm)",
+    "Pad 3 (polysynth)",
+    "Pad 4 (choir)",
+    "Pad 5 (bowed)",
+    "Pad 6 (metallic)",
+    "Pad 7 (halo)",
+    "Pad 8 (sweep)",
+    // Synth effects
+    "FX 1 (rain)",
+    "FX 2 (soundtrack)",
+    "FX 3 (crystal)",
+    "FX 4 (atmosphere)",
+    "FX 5 (brightness)",
+    "FX 6 (goblins)",
+    "FX 7 (echoes)",
+    "FX 8 (sci-fi)",
+    // Ethnic
+    "Sitar",
+    "Banjo",
+    "Shamisen",
+    "Koto",
+    "Kalimba",
+    "Bagpipe",
+    "Fiddle",
+    "Shanai",
+    // Percussive
+    "Tinkle Bell",
+    "Agogo",
+    "Steel Drums",
+    "Woodblock",
+    "Taiko Drum",
+    "Melodic Tom",
+    "Synth Drum",
+    "Reverse Cymbal",
+    // Sound effects
+    "Guitar Fret Noise",
+    "Breath Noise",
+    "Seashore",
+    "Bird Tweet",
+    "Telephone Ring",
+    "Helicopter",
+    "Applause",
+    "Gunshot",
+];
+
+/// First note of the GM percussion map
+pub const FIRST_DRUM: u8 = 35;
+
+/// GM percussion names (notes 35-81 on channel 10)
+pub const DRUMS: [&str; 47] = [
+    "Acoustic Bass Drum",
+    "Bass Drum 1",
+    "Side Stick",
+    "Acoustic Snare",
+    "Hand Clap",
+    "Electric Snare",
+    "Low Floor Tom",
+    "Closed Hi-Hat",
+    "High Floor Tom",
+    "Pedal Hi-Hat",
+    "Low Tom",
+    "Open Hi-Hat",
+    "Low-Mid Tom",
+    "Hi-Mid Tom",
+    "Crash Cymbal 1",
+    "High Tom",
+    "Ride Cymbal 1",
+    "Chinese Cymbal",
+    "Ride Bell",
+    "Tambourine",
+    "Splash Cymbal",
+    "Cowbell",
+    "Crash Cymbal 2",
+    "Vibraslap",
+    "Ride Cymbal 2",
+    "Hi Bongo",
+    "Low Bongo",
+    "Mute Hi Conga",
+    "Open Hi Conga",
+    "Low Conga",
+    "High Timbale",
+    "Low Timbale",
+    "High Agogo",
+    "Low Agogo",
+    "Cabasa",
+    "Maracas",
+    "Short Whistle",
+    "Long Whistle",
+    "Short Guiro",
+    "Long Guiro",
+    "Claves",
+    "Hi Wood Block",
+    "Low Wood Block",
+    "Mute Cuica",
+    "Open Cuica",
+    "Mute Triangle",
+    "Open Triangle",
+];
+
+/// GM percussion channel (0-indexed)
+pub const DRUM_CHANNEL: u8 = 9;
+
+/// Reduce a name to lowercase letters and digits for matching
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Get the GM name of a program (0-127)
+pub fn program_name(program: u8) -> Option<&'static str> {
+    PROGRAMS.get(program as usize).copied()
+}
+
+/// Look up a program by GM name or number (0-127).
+///
+/// Names match exactly after normalization, or by the descriptive part in
+/// parentheses ("Warm Pad" finds "Pad 2 (warm)").
+pub fn program_number(name: &str) -> Option<u8> {
+    if let Ok(number) = name.trim().parse::<u8>() {
+        return (number <= 127).then_some(number);
+    }
+    let wanted = normalize(name);
+    if wanted.is_empty() {
+        return None;
+    }
+
+    if let Some(i) = PROGRAMS.iter().position(|p| normalize(p) == wanted) {
+        return Some(i as u8);
+    }
+
+    // "Warm Pad" / "Pad Warm" for "Pad 2 (warm)"
+    PROGRAMS
+        .iter()
+        .position(|p| {
+            let Some((family, detail)) = p.split_once(" (") else {
+                return false;
+            };
+            let family: String = normalize(family).chars().filter(|c| !c.is_ascii_digit()).collect();
+            let detail = normalize(detail);
+            wanted == format!("{}{}", detail, family) || wanted == format!("{}{}", family, detail)
+        })
+        .map(|i| i as u8)
+}
+
+/// Get the GM percussion name of a note
+pub fn drum_name(note: u8) -> Option<&'static str> {
+    let index = note.checked_sub(FIRST_DRUM)?;
+    DRUMS.get(index as usize).copied()
+}
+
+/// Look up a GM percussion note by name
+pub fn drum_note(name: &str) -> Option<u8> {
+    let wanted = normalize(name);
+    DRUMS
+        .iter()
+        .position(|d| normalize(d) == wanted)
+        .map(|i| FIRST_DRUM + i as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_names() {
+        assert_eq!(program_name(0), Some("Acoustic Grand Piano"));
+        assert_eq!(program_name(89), Some("Pad 2 (warm)"));
+        assert_eq!(program_name(127), Some("Gunshot"));
+        assert_eq!(program_name(128), None);
+
+        assert_eq!(program_number("acoustic grand piano"), Some(0));
+        assert_eq!(program_number("Warm Pad"), Some(89));
+        assert_eq!(program_number("Lead 2 (sawtooth)"), Some(81));
+        assert_eq!(program_number("Sawtooth Lead"), Some(81));
+        assert_eq!(program_number("42"), Some(42));
+        assert_eq!(program_number("Kazoo"), None);
+    }
+
+    #[test]
+    fn test_drum_names() {
+        assert_eq!(drum_name(38), Some("Acoustic Snare"));
+        assert_eq!(drum_name(81), Some("Open Triangle"));
+        assert_eq!(drum_name(34), None);
+        assert_eq!(drum_name(82), None);
+
+        assert_eq!(drum_note("Acoustic Snare"), Some(38));
+        assert_eq!(drum_note("closed_hi-hat"), Some(42));
+        assert_eq!(drum_note("Snare"), None);
+    }
+}
@@ -39,6 +39,8 @@ pub enum MidiMessage {
     Continue,
     /// Stop playback
     Stop,
+    /// Song Position Pointer in MIDI beats (sixteenth notes)
+    SongPosition(u16),
     /// Unknown/unparsed message
     Unknown(Vec<u8>),
 }
@@ -58,6 +60,10 @@ impl MidiMessage {
             messages::START => return Some(MidiMessage::Start),
             messages::CONTINUE => return Some(MidiMessage::Continue),
             messages::STOP => return Some(MidiMessage::Stop),
+            messages::SONG_POSITION if data.len() >= 3 => {
+                let beats = ((data[2] as u16 & 0x7F) << 7) | (data[1] as u16 & 0x7F);
+                return Some(MidiMessage::SongPosition(beats));
+            }
             _ => {}
         }
 
@@ -126,10 +132,86 @@ impl MidiMessage {
                 | MidiMessage::Start
                 | MidiMessage::Continue
                 | MidiMessage::Stop
+                | MidiMessage::SongPosition(_)
         )
     }
 }
 
+/// Velocity response curve applied to incoming notes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VelocityCurve {
+    /// Pass velocity through unchanged
+    Linear,
+    /// Boost low velocities (for stiff keybeds)
+    Soft,
+    /// Reduce low velocities (for hot pads)
+    Hard,
+    /// Custom exponent: out = 127 * (in / 127) ^ exponent
+    Exponent(f64),
+    /// Every note plays at the same velocity
+    Fixed(u8),
+}
+
+impl Default for VelocityCurve {
+    fn default() -> Self {
+        VelocityCurve::Linear
+    }
+}
+
+impl VelocityCurve {
+    /// Parse a curve name: "linear", "soft", "hard", "exp:<n>" or "fixed:<n>"
+    pub fn parse(s: &str) -> Result<Self> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "linear" => return Ok(VelocityCurve::Linear),
+            "soft" => return Ok(VelocityCurve::Soft),
+            "hard" => return Ok(VelocityCurve::Hard),
+            _ => {}
+        }
+
+        if let Some(value) = s.strip_prefix("exp:") {
+            let exponent: f64 = value
+                .parse()
+                .map_err(|_| anyhow!("Invalid velocity curve exponent: {}", value))?;
+            return Ok(VelocityCurve::Exponent(exponent.clamp(0.1, 10.0)));
+        }
+        if let Some(value) = s.strip_prefix("fixed:") {
+            let velocity: u8 = value
+                .parse()
+                .map_err(|_| anyhow!("Invalid fixed velocity: {}", value))?;
+            return Ok(VelocityCurve::Fixed(velocity.clamp(1, 127)));
+        }
+
+        Err(anyhow!("Unknown velocity curve: {}", s))
+    }
+
+    /// Apply the curve to a velocity (1-127 in, 1-127 out)
+    pub fn apply(&self, velocity: u8) -> u8 {
+        let exponent = match *self {
+            VelocityCurve::Linear => return velocity,
+            VelocityCurve::Fixed(v) => return v.clamp(1, 127),
+            VelocityCurve::Soft => 0.6,
+            VelocityCurve::Hard => 1.6,
+            VelocityCurve::Exponent(e) => e,
+        };
+        let normalized = velocity.min(127) as f64 / 127.0;
+        let curved = 127.0 * normalized.powf(exponent);
+        (curved.round() as u8).clamp(1, 127)
+    }
+
+    /// Apply the curve to a message (only Note On velocities are changed)
+    pub fn apply_message(&self, message: MidiMessage) -> MidiMessage {
+        match message {
+            MidiMessage::NoteOn { channel, note, velocity } => MidiMessage::NoteOn {
+                channel,
+                note,
+                velocity: self.apply(velocity),
+            },
+            other => other,
+        }
+    }
+}
+
 /// MIDI Learn state for capturing controller assignments
 #[derive(Debug, Clone)]
 pub struct MidiLearnCapture {
@@ -195,6 +277,14 @@ pub struct ExternalClockSync {
     pub tick_count: u64,
     /// Whether we've received a start message
     pub running: bool,
+    /// Beats per bar used to find bar boundaries
+    pub beats_per_bar: u8,
+    /// Wait for the next incoming bar boundary before starting
+    pub quantize_start: bool,
+    /// A transport start is waiting for the bar boundary
+    start_pending: bool,
+    /// The bar boundary was reached and the transport may start
+    start_ready: bool,
 }
 
 impl ExternalClockSync {
@@ -203,6 +293,10 @@ impl ExternalClockSync {
             enabled: false,
             tick_count: 0,
             running: false,
+            beats_per_bar: 4,
+            quantize_start: true,
+            start_pending: false,
+            start_ready: false,
         }
     }
 
@@ -228,22 +322,86 @@ impl ExternalClockSync {
             MidiMessage::TimingClock => {
                 if self.running {
                     self.tick_count += 1;
+                    if self.at_bar_boundary() {
+                        self.release_start();
+                    }
                 }
             }
             MidiMessage::Start => {
                 self.running = true;
                 self.tick_count = 0;
+                self.release_start();
             }
             MidiMessage::Continue => {
                 self.running = true;
+                if self.at_bar_boundary() {
+                    self.release_start();
+                }
             }
             MidiMessage::Stop => {
                 self.running = false;
             }
+            MidiMessage::SongPosition(beats) => {
+                // One MIDI beat is a sixteenth note (6 clocks)
+                self.tick_count = *beats as u64 * 6;
+            }
             _ => {}
         }
     }
 
+    /// Request a transport start. Without start quantization it is ready immediately.
+    pub fn request_start(&mut self) {
+        if self.enabled && self.quantize_start {
+            self.start_pending = true;
+            self.start_ready = false;
+        } else {
+            self.start_ready = true;
+        }
+    }
+
+    /// Cancel a pending start
+    pub fn cancel_start(&mut self) {
+        self.start_pending = false;
+        self.start_ready = false;
+    }
+
+    /// Check if a start is waiting for the bar boundary
+    pub fn is_start_pending(&self) -> bool {
+        self.start_pending
+    }
+
+    /// Take the start signal (true once when the transport should start)
+    pub fn take_start(&mut self) -> bool {
+        std::mem::take(&mut self.start_ready)
+    }
+
+    fn release_start(&mut self) {
+        if self.start_pending {
+            self.start_pending = false;
+            self.start_ready = true;
+        }
+    }
+
+    /// Clock ticks per bar (24 PPQN)
+    pub fn ticks_per_bar(&self) -> u64 {
+        24 * self.beats_per_bar.max(1) as u64
+    }
+
+    /// Check if the clock count is on a bar boundary
+    pub fn at_bar_boundary(&self) -> bool {
+        self.tick_count % self.ticks_per_bar() == 0
+    }
+
+    /// Clock ticks until the next bar boundary
+    pub fn ticks_to_next_bar(&self) -> u64 {
+        let into_bar = self.tick_count % self.ticks_per_bar();
+        if into_bar == 0 {
+            0
+        } else {
+            self.ticks_per_bar() - into_bar
+        }
+    }
+
     /// Get current beat (based on 24 PPQN)
     pub fn current_beat(&self) -> u64 {
         self.tick_count / 24
@@ -253,6 +411,11 @@ impl ExternalClockSync {
     pub fn current_pulse(&self) -> u64 {
         self.tick_count % 24
     }
+
+    /// Get the position in ticks of an internal resolution (a multiple of 24)
+    pub fn position_ticks(&self, ppqn: u32) -> u64 {
+        self.tick_count * (ppqn / 24).max(1) as u64
+    }
 }
 
 impl Default for ExternalClockSync {
@@ -268,6 +431,7 @@ pub struct MidiInput {
     receiver: Receiver<MidiMessage>,
     midi_learn: Arc<Mutex<MidiLearnCapture>>,
     clock_sync: Arc<Mutex<ExternalClockSync>>,
+    velocity_curve: Arc<Mutex<VelocityCurve>>,
 }
 
 impl MidiInput {
@@ -284,15 +448,23 @@ impl MidiInput {
         let midi_learn = Arc::new(Mutex::new(MidiLearnCapture::new()));
         let clock_sync = Arc::new(Mutex::new(ExternalClockSync::new()));
 
+        let velocity_curve = Arc::new(Mutex::new(VelocityCurve::default()));
+
         let learn_clone = midi_learn.clone();
         let sync_clone = clock_sync.clone();
+        let curve_clone = velocity_curve.clone();
 
         // Create input port with callback
         let input_port = client
             .input_port("SEQ Input Port", move |packet_list: &PacketList| {
                 for packet in packet_list.iter() {
                     let data = packet.data();
-                    if let Some(msg) = MidiMessage::parse(data) {
+                    if let Some(mut msg) = MidiMessage::parse(data) {
+                        // Apply the device velocity curve before anything else sees the note
+                        if let Ok(curve) = curve_clone.lock() {
+                            msg = curve.apply_message(msg);
+                        }
+
                         // Process MIDI learn
                         if let Ok(mut learn) = learn_clone.lock() {
                             learn.capture(&msg);
@@ -321,9 +493,22 @@ impl MidiInput {
             receiver: rx,
             midi_learn,
             clock_sync,
+            velocity_curve,
         })
     }
 
+    /// Set the velocity curve for this input device
+    pub fn set_velocity_curve(&self, curve: VelocityCurve) {
+        if let Ok(mut current) = self.velocity_curve.lock() {
+            *current = curve;
+        }
+    }
+
+    /// Get the velocity curve for this input device
+    pub fn velocity_curve(&self) -> VelocityCurve {
+        self.velocity_curve.lock().map(|c| *c).unwrap_or_default()
+    }
+
     /// Try to receive the next MIDI message (non-blocking)
     pub fn try_recv(&self) -> Option<MidiMessage> {
         self.receiver.try_recv().ok()
@@ -376,6 +561,25 @@ impl MidiInput {
         }
     }
 
+    /// Enable or disable waiting for the incoming bar boundary on start
+    pub fn set_quantize_start(&self, enabled: bool) {
+        if let Ok(mut sync) = self.clock_sync.lock() {
+            sync.quantize_start = enabled;
+        }
+    }
+
+    /// Request a transport start (see `ExternalClockSync::request_start`)
+    pub fn request_start(&self) {
+        if let Ok(mut sync) = self.clock_sync.lock() {
+            sync.request_start();
+        }
+    }
+
+    /// Take the start signal once the bar boundary has been reached
+    pub fn take_start(&self) -> bool {
+        self.clock_sync.lock().map(|mut s| s.take_start()).unwrap_or(false)
+    }
+
     /// Get current clock sync state
     pub fn clock_sync_state(&self) -> Option<ExternalClockSync> {
         self.clock_sync.lock().ok().map(|s| s.clone())
@@ -540,12 +744,82 @@ mod tests {
         }
         assert_eq!(sync.current_beat(), 1);
         assert_eq!(sync.current_pulse(), 0);
+        assert_eq!(sync.position_ticks(480), 480);
 
         // Stop
         sync.process(&MidiMessage::Stop);
         assert!(!sync.running);
     }
 
+    #[test]
+    fn test_quantized_start() {
+        let mut sync = ExternalClockSync::new();
+        sync.enable();
+        sync.process(&MidiMessage::Start);
+        for _ in 0..30 {
+            sync.process(&MidiMessage::TimingClock);
+        }
+
+        sync.request_start();
+        assert!(sync.is_start_pending());
+        assert!(!sync.take_start());
+        assert_eq!(sync.ticks_to_next_bar(), 66);
+
+        for _ in 0..65 {
+            sync.process(&MidiMessage::TimingClock);
+        }
+        assert!(!sync.take_start());
+
+        sync.process(&MidiMessage::TimingClock);
+        assert!(sync.take_start());
+        assert!(!sync.take_start());
+    }
+
+    #[test]
+    fn test_unquantized_start() {
+        let mut sync = ExternalClockSync::new();
+        sync.enable();
+        sync.quantize_start = false;
+        sync.request_start();
+        assert!(sync.take_start());
+    }
+
+    #[test]
+    fn test_song_position_pointer() {
+        // Position 8 sixteenths = half a bar
+        let msg = MidiMessage::parse(&[0xF2, 0x08, 0x00]).unwrap();
+        assert_eq!(msg, MidiMessage::SongPosition(8));
+        assert!(msg.is_clock_message());
+
+        let mut sync = ExternalClockSync::new();
+        sync.enable();
+        sync.process(&msg);
+        assert_eq!(sync.tick_count, 48);
+        assert_eq!(sync.ticks_to_next_bar(), 48);
+    }
+
+    #[test]
+    fn test_velocity_curves() {
+        assert_eq!(VelocityCurve::Linear.apply(64), 64);
+        assert_eq!(VelocityCurve::Fixed(100).apply(12), 100);
+        assert!(VelocityCurve::Soft.apply(40) > 40);
+        assert!(VelocityCurve::Hard.apply(40) < 40);
+        assert_eq!(VelocityCurve::Soft.apply(127), 127);
+        assert_eq!(VelocityCurve::Hard.apply(1), 1);
+
+        let msg = MidiMessage::NoteOff { channel: 0, note: 60, velocity: 10 };
+        assert_eq!(VelocityCurve::Fixed(100).apply_message(msg.clone()), msg);
+    }
+
+    #[test]
+    fn test_parse_velocity_curve() {
+        assert_eq!(VelocityCurve::parse("Soft").unwrap(), VelocityCurve::Soft);
+        assert_eq!(VelocityCurve::parse("exp:2").unwrap(), VelocityCurve::Exponent(2.0));
+        assert_eq!(VelocityCurve::parse("fixed:90").unwrap(), VelocityCurve::Fixed(90));
+        assert!(VelocityCurve::parse("bouncy").is_err());
+        assert!(VelocityCurve::parse("fixed:loud").is_err());
+    }
+
     #[test]
     fn test_list_sources() {
         // Just verify it doesn't panic
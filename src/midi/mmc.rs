@@ -0,0 +1,221 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! MIDI Machine Control (MMC).
+//!
+//! MMC carries transport commands (play, stop, locate, record) as universal
+//! real-time SysEx: `F0 7F <device> 06 <command> ... F7`. Recorders and DAWs
+//! use it alongside or instead of MIDI clock start/stop. Each device has an
+//! ID; 127 addresses every device.
+
+use super::messages;
+
+/// Universal real-time SysEx ID
+const UNIVERSAL_REAL_TIME: u8 = 0x7F;
+
+/// MMC command sub-ID
+const MMC_COMMAND: u8 = 0x06;
+
+/// Device ID that addresses every device
+pub const MMC_ALL_DEVICES: u8 = 0x7F;
+
+/// An SMPTE position for MMC locate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MmcTime {
+    /// Hours (0-23)
+    pub hours: u8,
+    /// Minutes (0-59)
+    pub minutes: u8,
+    /// Seconds (0-59)
+    pub seconds: u8,
+    /// Frames
+    pub frames: u8,
+    /// Hundredths of a frame
+    pub subframes: u8,
+    /// Frames per second (24, 25 or 30)
+    pub fps: u8,
+}
+
+impl MmcTime {
+    /// Position from seconds at a frame rate
+    pub fn from_seconds(seconds: f64, fps: u8) -> Self {
+        let fps = Self::supported_fps(fps);
+        let total_frames = seconds.max(0.0) * fps as f64;
+        let whole = total_frames.floor() as u64;
+        let secs = whole / fps as u64;
+        Self {
+            hours: ((secs / 3600) % 24) as u8,
+            minutes: ((secs / 60) % 60) as u8,
+            seconds: (secs % 60) as u8,
+            frames: (whole % fps as u64) as u8,
+            subframes: ((total_frames - whole as f64) * 100.0) as u8,
+            fps,
+        }
+    }
+
+    /// Position in seconds
+    pub fn to_seconds(&self) -> f64 {
+        let fps = Self::supported_fps(self.fps) as f64;
+        (self.hours as f64 * 3600.0 + self.minutes as f64 * 60.0 + self.seconds as f64)
+            + (self.frames as f64 + self.subframes as f64 / 100.0) / fps
+    }
+
+    /// Position in ticks at a tempo and resolution
+    pub fn to_ticks(&self, bpm: f64, ppqn: u32) -> u64 {
+        (self.to_seconds() * bpm / 60.0 * ppqn as f64).round() as u64
+    }
+
+    /// Frame rate rounded to one MMC can carry
+    fn supported_fps(fps: u8) -> u8 {
+        match fps {
+            0..=24 => 24,
+            25..=29 => 25,
+            _ => 30,
+        }
+    }
+
+    /// Rate code carried in the hours byte
+    fn rate_code(&self) -> u8 {
+        match Self::supported_fps(self.fps) {
+            24 => 0,
+            25 => 1,
+            _ => 3,
+        }
+    }
+
+    /// Frame rate from the rate code in the hours byte (30 drop reads as 30)
+    fn fps_from_code(code: u8) -> u8 {
+        match code {
+            0 => 24,
+            1 => 25,
+            _ => 30,
+        }
+    }
+}
+
+/// A transport command carried by MMC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmcCommand {
+    /// Stop
+    Stop,
+    /// Play
+    Play,
+    /// Play once the device is ready
+    DeferredPlay,
+    /// Fast forward
+    FastForward,
+    /// Rewind
+    Rewind,
+    /// Punch in to record
+    RecordStrobe,
+    /// Punch out of record
+    RecordExit,
+    /// Pause
+    Pause,
+    /// Move to a position
+    Locate(MmcTime),
+}
+
+impl MmcCommand {
+    /// Parse an MMC message addressed to `device_id` (or to every device)
+    pub fn parse(data: &[u8], device_id: u8) -> Option<Self> {
+        let [messages::SYSEX_START, UNIVERSAL_REAL_TIME, device, MMC_COMMAND, command, rest @ ..] = data
+        else {
+            return None;
+        };
+        if *device != device_id && *device != MMC_ALL_DEVICES && device_id != MMC_ALL_DEVICES {
+            return None;
+        }
+        let command = match command {
+            0x01 => MmcCommand::Stop,
+            0x02 => MmcCommand::Play,
+            0x03 => MmcCommand::DeferredPlay,
+            0x04 => MmcCommand::FastForward,
+            0x05 => MmcCommand::Rewind,
+            0x06 => MmcCommand::RecordStrobe,
+            0x07 => MmcCommand::RecordExit,
+            0x09 => MmcCommand::Pause,
+            0x44 => {
+                // Locate target: 06 01 hr mn sc fr sf
+                let [0x06, 0x01, hours, minutes, seconds, frames, subframes, ..] = rest else {
+                    return None;
+                };
+                MmcCommand::Locate(MmcTime {
+                    hours: hours & 0x1F,
+                    minutes: *minutes,
+                    seconds: *seconds,
+                    frames: frames & 0x1F,
+                    subframes: *subframes,
+                    fps: MmcTime::fps_from_code((hours >> 5) & 0x03),
+                })
+            }
+            _ => return None,
+        };
+        Some(command)
+    }
+
+    /// Build the SysEx message for a device
+    pub fn to_sysex(&self, device_id: u8) -> Vec<u8> {
+        let device = device_id.min(MMC_ALL_DEVICES);
+        let mut data = vec![messages::SYSEX_START, UNIVERSAL_REAL_TIME, device, MMC_COMMAND];
+        match self {
+            MmcCommand::Stop => data.push(0x01),
+            MmcCommand::Play => data.push(0x02),
+            MmcCommand::DeferredPlay => data.push(0x03),
+            MmcCommand::FastForward => data.push(0x04),
+            MmcCommand::Rewind => data.push(0x05),
+            MmcCommand::RecordStrobe => data.push(0x06),
+            MmcCommand::RecordExit => data.push(0x07),
+            MmcCommand::Pause => data.push(0x09),
+            MmcCommand::Locate(time) => data.extend_from_slice(&[
+                0x44,
+                0x06,
+                0x01,
+                (time.rate_code() << 5) | (time.hours & 0x1F),
+                time.minutes.min(59),
+                time.seconds.min(59),
+                time.frames.min(29),
+                time.subframes.min(99),
+            ]),
+        }
+        data.push(messages::SYSEX_END);
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            MmcCommand::parse(&[0xF0, 0x7F, 0x10, 0x06, 0x02, 0xF7], 0x10),
+            Some(MmcCommand::Play)
+        );
+        // All-call reaches every device; other devices' commands do not
+        assert_eq!(
+            MmcCommand::parse(&[0xF0, 0x7F, 0x7F, 0x06, 0x01, 0xF7], 0x10),
+            Some(MmcCommand::Stop)
+        );
+        assert_eq!(MmcCommand::parse(&[0xF0, 0x7F, 0x11, 0x06, 0x01, 0xF7], 0x10), None);
+        // Other universal messages are ignored
+        assert_eq!(MmcCommand::parse(&[0xF0, 0x7F, 0x10, 0x01, 0x01, 0xF7], 0x10), None);
+        assert_eq!(MmcCommand::parse(&[0x90, 60, 100], 0x10), None);
+    }
+
+    #[test]
+    fn test_locate_round_trip() {
+        let time = MmcTime::from_seconds(3725.5, 25);
+        assert_eq!((time.hours, time.minutes, time.seconds, time.frames), (1, 2, 5, 12));
+
+        let sysex = MmcCommand::Locate(time).to_sysex(0x7F);
+        assert_eq!(&sysex[..7], &[0xF0, 0x7F, 0x7F, 0x06, 0x44, 0x06, 0x01]);
+        assert_eq!(sysex[7], 0x20 | 1);
+        assert_eq!(MmcCommand::parse(&sysex, 0x05), Some(MmcCommand::Locate(time)));
+
+        // Two seconds at 120 BPM is four beats
+        let two = MmcTime::from_seconds(2.0, 30);
+        assert_eq!(two.to_ticks(120.0, 24), 96);
+    }
+}
@@ -8,14 +8,30 @@
 //! interchangeably.
 
 pub mod coremidi_backend;
+pub mod encoder;
+pub mod gm;
 pub mod input;
+pub mod mmc;
+pub mod outputs;
+pub mod pulse;
+pub mod router;
+pub mod rtp;
+pub mod selftest;
 
 use anyhow::Result;
 
 pub use coremidi_backend::{CoreMidiOutput, list_destinations, print_destinations};
+pub use encoder::{EncoderSettings, OutputEncoder};
 pub use input::{
     list_sources, print_sources, ExternalClockSync, MidiInput, MidiLearnCapture, MidiMessage,
+    VelocityCurve,
 };
+pub use mmc::{MmcCommand, MmcTime, MMC_ALL_DEVICES};
+pub use outputs::{CoreMidiConnector, OutputConnector, OutputEvent, OutputPool};
+pub use pulse::{BeatPulse, Pulse, PulseFormat, PulseSender};
+pub use router::{MessageKind, MidiRouter, Route, RouteProcessor};
+pub use rtp::{RtpMidiSession, RtpPeer, SessionPacket, SessionRole};
+pub use selftest::{run_self_test, SelfTestReport};
 
 /// Trait for MIDI output implementations.
 ///
@@ -63,6 +79,7 @@ pub mod messages {
 
     // System Common Messages
     pub const SYSEX_START: u8 = 0xF0;
+    pub const SONG_POSITION: u8 = 0xF2;
     pub const SYSEX_END: u8 = 0xF7;
 }
 
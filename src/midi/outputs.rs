@@ -0,0 +1,268 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Multiple named MIDI outputs.
+//!
+//! Tracks, layers and voices name the destination they play on. The pool
+//! opens one connection per name, hands out the index that scheduled events
+//! carry, and sends each event to its connection. When a device disappears
+//! its messages are dropped (and counted) until a poll finds it again and
+//! reconnects, so unplugging a synth mid-set doesn't stop the others.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use super::coremidi_backend::{list_destinations, CoreMidiOutput};
+use super::MidiOutput;
+
+/// How often missing destinations are looked for
+pub const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Opens outputs by name
+pub trait OutputConnector {
+    /// Names of the destinations present now
+    fn available(&self) -> Vec<String>;
+
+    /// Open a destination by (partial) name
+    fn connect(&mut self, name: &str) -> Result<Box<dyn MidiOutput>>;
+}
+
+/// Core MIDI destinations
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoreMidiConnector;
+
+impl OutputConnector for CoreMidiConnector {
+    fn available(&self) -> Vec<String> {
+        list_destinations().into_iter().map(|(_, name)| name).collect()
+    }
+
+    fn connect(&mut self, name: &str) -> Result<Box<dyn MidiOutput>> {
+        Ok(Box::new(CoreMidiOutput::new_by_name(name)?))
+    }
+}
+
+/// A change in a destination's connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputEvent {
+    /// The destination was opened (or reopened)
+    Connected(String),
+    /// The destination went away
+    Lost(String),
+}
+
+/// One named destination
+struct Connection {
+    /// Name as configured (matched as a case-insensitive substring)
+    name: String,
+    /// Open output (None while the device is missing)
+    output: Option<Box<dyn MidiOutput>>,
+    /// Messages dropped while disconnected
+    dropped: u64,
+}
+
+/// A set of named outputs with reconnection
+pub struct OutputPool<C: OutputConnector> {
+    /// Opens the outputs
+    connector: C,
+    /// Destinations by index; the first is the default output
+    connections: Vec<Connection>,
+    /// Time between looks for missing destinations
+    retry_interval: Duration,
+    /// Last time destinations were checked
+    last_poll: Option<Instant>,
+}
+
+impl<C: OutputConnector> OutputPool<C> {
+    /// Create an empty pool
+    pub fn new(connector: C) -> Self {
+        Self {
+            connector,
+            connections: Vec::new(),
+            retry_interval: DEFAULT_RETRY_INTERVAL,
+            last_poll: None,
+        }
+    }
+
+    /// Set how often missing destinations are looked for
+    pub fn with_retry_interval(mut self, interval: Duration) -> Self {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Index of a named destination, adding and opening it if new. The
+    /// index is what `ScheduledEvent::destination` carries.
+    pub fn destination(&mut self, name: &str) -> usize {
+        if let Some(index) = self.index_of(name) {
+            return index;
+        }
+        let output = self.connector.connect(name).ok();
+        self.connections.push(Connection {
+            name: name.to_string(),
+            output,
+            dropped: 0,
+        });
+        self.connections.len() - 1
+    }
+
+    /// Index of a destination already in the pool
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.connections.iter().position(|c| c.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Names of the destinations, by index
+    pub fn names(&self) -> Vec<&str> {
+        self.connections.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    /// Check if a destination is open
+    pub fn is_connected(&self, index: usize) -> bool {
+        self.connections.get(index).is_some_and(|c| c.output.is_some())
+    }
+
+    /// Messages dropped for a destination while it was missing
+    pub fn dropped(&self, index: usize) -> u64 {
+        self.connections.get(index).map_or(0, |c| c.dropped)
+    }
+
+    /// Send a message to a destination (None = the default output).
+    /// Returns false if the destination is missing and the message was
+    /// dropped; a failed send marks it missing until the next poll.
+    pub fn send(&mut self, destination: Option<usize>, message: &[u8]) -> Result<bool> {
+        let index = destination.unwrap_or(0);
+        let connection = self
+            .connections
+            .get_mut(index)
+            .ok_or_else(|| anyhow!("No MIDI destination {}", index))?;
+        let sent = match connection.output.as_mut() {
+            Some(output) => output.send(message).is_ok(),
+            None => false,
+        };
+        if !sent {
+            connection.output = None;
+            connection.dropped += 1;
+        }
+        Ok(sent)
+    }
+
+    /// Drop destinations that went away and reopen ones that came back.
+    /// Checks at most once per retry interval.
+    pub fn poll(&mut self, now: Instant) -> Vec<OutputEvent> {
+        if self.last_poll.is_some_and(|last| now.duration_since(last) < self.retry_interval) {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+        self.reconnect()
+    }
+
+    /// Check every destination against the devices present now
+    pub fn reconnect(&mut self) -> Vec<OutputEvent> {
+        let available: Vec<String> = self.connector.available().iter().map(|n| n.to_lowercase()).collect();
+        let mut events = Vec::new();
+        for connection in &mut self.connections {
+            let wanted = connection.name.to_lowercase();
+            let present = available.iter().any(|n| n.contains(&wanted));
+            match (connection.output.is_some(), present) {
+                (true, false) => {
+                    connection.output = None;
+                    events.push(OutputEvent::Lost(connection.name.clone()));
+                }
+                (false, true) => {
+                    if let Ok(output) = self.connector.connect(&connection.name) {
+                        connection.output = Some(output);
+                        events.push(OutputEvent::Connected(connection.name.clone()));
+                    }
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+impl OutputPool<CoreMidiConnector> {
+    /// Create a pool of Core MIDI destinations
+    pub fn core_midi() -> Self {
+        Self::new(CoreMidiConnector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Messages sent, by destination name
+    type Sent = Arc<Mutex<Vec<(String, Vec<u8>)>>>;
+
+    struct MockOutput {
+        name: String,
+        sent: Sent,
+    }
+
+    impl MidiOutput for MockOutput {
+        fn send(&mut self, message: &[u8]) -> Result<()> {
+            self.sent.lock().unwrap().push((self.name.clone(), message.to_vec()));
+            Ok(())
+        }
+
+        fn send_at(&mut self, message: &[u8], _timestamp: u64) -> Result<()> {
+            self.send(message)
+        }
+    }
+
+    struct MockConnector {
+        present: Arc<Mutex<Vec<String>>>,
+        sent: Sent,
+    }
+
+    impl OutputConnector for MockConnector {
+        fn available(&self) -> Vec<String> {
+            self.present.lock().unwrap().clone()
+        }
+
+        fn connect(&mut self, name: &str) -> Result<Box<dyn MidiOutput>> {
+            let lower = name.to_lowercase();
+            let found = self.available().into_iter().find(|n| n.to_lowercase().contains(&lower));
+            let name = found.ok_or_else(|| anyhow!("No MIDI destination matching '{}' found", name))?;
+            Ok(Box::new(MockOutput { name, sent: self.sent.clone() }))
+        }
+    }
+
+    #[test]
+    fn test_named_destinations_reconnect() {
+        let present = Arc::new(Mutex::new(vec!["IAC Bus 1".to_string(), "Prophet Rev2".to_string()]));
+        let sent: Sent = Arc::default();
+        let connector = MockConnector { present: present.clone(), sent: sent.clone() };
+        let mut pool = OutputPool::new(connector).with_retry_interval(Duration::from_millis(500));
+
+        let iac = pool.destination("IAC");
+        let prophet = pool.destination("prophet");
+        assert_eq!((iac, prophet), (0, 1));
+        assert_eq!(pool.destination("PROPHET"), prophet);
+
+        assert!(pool.send(None, &[0x90, 60, 100]).unwrap());
+        assert!(pool.send(Some(prophet), &[0x91, 48, 90]).unwrap());
+        assert!(pool.send(Some(7), &[0x90, 60, 0]).is_err());
+
+        // Unplug the Prophet: its notes are dropped, the IAC keeps playing
+        present.lock().unwrap().retain(|n| n != "Prophet Rev2");
+        let start = Instant::now();
+        assert_eq!(pool.poll(start), vec![OutputEvent::Lost("prophet".to_string())]);
+        assert!(!pool.send(Some(prophet), &[0x91, 48, 0]).unwrap());
+        assert_eq!(pool.dropped(prophet), 1);
+
+        // Plug it back in: found on the next poll after the interval
+        present.lock().unwrap().push("Prophet Rev2".to_string());
+        assert!(pool.poll(start + Duration::from_millis(100)).is_empty());
+        assert_eq!(
+            pool.poll(start + Duration::from_millis(600)),
+            vec![OutputEvent::Connected("prophet".to_string())]
+        );
+        assert!(pool.is_connected(prophet));
+        assert!(pool.send(Some(prophet), &[0x91, 50, 90]).unwrap());
+
+        let sent: Vec<String> = sent.lock().unwrap().iter().map(|(name, _)| name.clone()).collect();
+        assert_eq!(sent, vec!["IAC Bus 1", "Prophet Rev2", "Prophet Rev2"]);
+    }
+}
@@ -0,0 +1,290 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Beat pulse output for lighting controllers.
+//!
+//! Emits a pulse on every beat, accented on bar downbeats, so the sequencer
+//! can drive a DMX bridge or an LED strip as a visual metronome. Pulses go
+//! out as MIDI notes (for MIDI-to-DMX boxes), as OSC messages over UDP, or
+//! as one text line per pulse on a serial device.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::{SocketAddr, UdpSocket};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::sequencer::ScheduledEvent;
+
+/// Default OSC address for pulses
+pub const DEFAULT_PULSE_ADDRESS: &str = "/seq/beat";
+
+/// A beat at a tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pulse {
+    /// Absolute tick of the beat
+    pub tick: u64,
+    /// Bar number (0-based)
+    pub bar: u64,
+    /// Beat within the bar (0-based)
+    pub beat: u32,
+}
+
+impl Pulse {
+    /// Check if this is the first beat of a bar
+    pub fn is_downbeat(&self) -> bool {
+        self.beat == 0
+    }
+}
+
+/// How pulses are sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseFormat {
+    /// Note on/off per beat
+    MidiNote,
+    /// OSC message per beat over UDP
+    Osc,
+    /// Text line per beat on a serial device
+    Serial,
+}
+
+impl Default for PulseFormat {
+    fn default() -> Self {
+        PulseFormat::MidiNote
+    }
+}
+
+impl PulseFormat {
+    /// Parse from a string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "midi" | "note" | "midi_note" => Some(PulseFormat::MidiNote),
+            "osc" | "udp" => Some(PulseFormat::Osc),
+            "serial" | "text" => Some(PulseFormat::Serial),
+            _ => None,
+        }
+    }
+}
+
+/// Beat pulse generator and message mapping
+#[derive(Debug, Clone)]
+pub struct BeatPulse {
+    /// Whether pulses are produced
+    enabled: bool,
+    /// MIDI channel (0-15)
+    channel: u8,
+    /// Note for bar downbeats
+    bar_note: u8,
+    /// Note for other beats
+    beat_note: u8,
+    /// Velocity of downbeat pulses
+    accent_velocity: u8,
+    /// Velocity of other pulses
+    velocity: u8,
+    /// Pulse length in ticks
+    length_ticks: u64,
+    /// OSC address
+    address: String,
+}
+
+impl Default for BeatPulse {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            channel: 15,
+            bar_note: 61,
+            beat_note: 60,
+            accent_velocity: 127,
+            velocity: 64,
+            length_ticks: 6,
+            address: DEFAULT_PULSE_ADDRESS.to_string(),
+        }
+    }
+}
+
+impl BeatPulse {
+    /// Create a pulse generator with the default mapping
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable pulses
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Set MIDI channel
+    pub fn set_channel(&mut self, channel: u8) {
+        self.channel = channel.min(15);
+    }
+
+    /// Set the downbeat and beat notes
+    pub fn set_notes(&mut self, bar: u8, beat: u8) {
+        self.bar_note = bar.min(127);
+        self.beat_note = beat.min(127);
+    }
+
+    /// Set the downbeat and beat velocities
+    pub fn set_velocities(&mut self, accent: u8, velocity: u8) {
+        self.accent_velocity = accent.clamp(1, 127);
+        self.velocity = velocity.clamp(1, 127);
+    }
+
+    /// Set pulse length in ticks
+    pub fn set_length(&mut self, ticks: u64) {
+        self.length_ticks = ticks.max(1);
+    }
+
+    /// Set the OSC address
+    pub fn set_address(&mut self, address: impl Into<String>) {
+        self.address = address.into();
+    }
+
+    /// Beats in a tick window [start, end)
+    pub fn pulses(&self, start: u64, end: u64, ppqn: u32, beats_per_bar: u32) -> Vec<Pulse> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let ticks_per_beat = ppqn.max(1) as u64;
+        let beats_per_bar = beats_per_bar.max(1) as u64;
+        let first = start.div_ceil(ticks_per_beat);
+        (first..)
+            .map(|beat| beat * ticks_per_beat)
+            .take_while(|&tick| tick < end)
+            .map(|tick| {
+                let beat = tick / ticks_per_beat;
+                Pulse {
+                    tick,
+                    bar: beat / beats_per_bar,
+                    beat: (beat % beats_per_bar) as u32,
+                }
+            })
+            .collect()
+    }
+
+    /// Note on and off events for a pulse
+    pub fn midi_events(&self, pulse: &Pulse, destination: Option<usize>) -> Vec<ScheduledEvent> {
+        let (note, velocity) = if pulse.is_downbeat() {
+            (self.bar_note, self.accent_velocity)
+        } else {
+            (self.beat_note, self.velocity)
+        };
+        vec![
+            ScheduledEvent::note_on(pulse.tick, self.channel, note, velocity),
+            ScheduledEvent::note_off(pulse.tick + self.length_ticks, self.channel, note),
+        ]
+        .into_iter()
+        .map(|event| event.with_destination(destination))
+        .collect()
+    }
+
+    /// OSC message for a pulse: `<address> ,iii bar beat accent`
+    /// (bar and beat 1-based, accent 1 on downbeats)
+    pub fn osc_message(&self, pulse: &Pulse) -> Vec<u8> {
+        let mut message = Vec::new();
+        push_osc_string(&mut message, &self.address);
+        push_osc_string(&mut message, ",iii");
+        for value in [pulse.bar as i32 + 1, pulse.beat as i32 + 1, pulse.is_downbeat() as i32] {
+            message.extend_from_slice(&value.to_be_bytes());
+        }
+        message
+    }
+
+    /// Text line for a pulse: "BAR 3" on downbeats, "BEAT 3.2" otherwise
+    pub fn text_line(&self, pulse: &Pulse) -> String {
+        if pulse.is_downbeat() {
+            format!("BAR {}\n", pulse.bar + 1)
+        } else {
+            format!("BEAT {}.{}\n", pulse.bar + 1, pulse.beat + 1)
+        }
+    }
+}
+
+/// Append a null-terminated string padded to four bytes
+fn push_osc_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(s.as_bytes());
+    buf.push(0);
+    while buf.len() % 4 != 0 {
+        buf.push(0);
+    }
+}
+
+/// Non-MIDI pulse destination
+pub enum PulseSender {
+    /// OSC over UDP
+    Osc { socket: UdpSocket, target: SocketAddr },
+    /// Serial device (configured for baud rate outside the sequencer)
+    Serial(File),
+}
+
+impl PulseSender {
+    /// Send OSC pulses to a UDP address
+    pub fn osc(target: SocketAddr) -> Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0)).context("Failed to open pulse socket")?;
+        Ok(PulseSender::Osc { socket, target })
+    }
+
+    /// Write text pulses to a serial device
+    pub fn serial<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .open(path.as_ref())
+            .with_context(|| format!("Failed to open serial device: {:?}", path.as_ref()))?;
+        Ok(PulseSender::Serial(file))
+    }
+
+    /// Send a pulse now
+    pub fn send(&mut self, pulse: &BeatPulse, beat: &Pulse) -> Result<()> {
+        match self {
+            PulseSender::Osc { socket, target } => {
+                socket.send_to(&pulse.osc_message(beat), *target)?;
+            }
+            PulseSender::Serial(file) => {
+                file.write_all(pulse.text_line(beat).as_bytes())?;
+                file.flush()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pulses_and_accents() {
+        let mut pulse = BeatPulse::new();
+        // A window starting mid-beat skips to the next beat
+        let beats = pulse.pulses(80, 200, 24, 4);
+        assert_eq!(beats.iter().map(|p| p.tick).collect::<Vec<_>>(), vec![96, 120, 144, 168, 192]);
+        assert!(beats[0].is_downbeat());
+        assert_eq!((beats[1].bar, beats[1].beat), (1, 1));
+
+        let events = pulse.midi_events(&beats[0], Some(1));
+        assert_eq!(events[0].to_midi_bytes(), vec![0x9F, 61, 127]);
+        assert_eq!(events[1].time_ticks, 102);
+        assert_eq!(pulse.midi_events(&beats[1], None)[0].to_midi_bytes(), vec![0x9F, 60, 64]);
+
+        assert_eq!(pulse.text_line(&beats[0]), "BAR 2\n");
+        assert_eq!(pulse.text_line(&beats[1]), "BEAT 2.2\n");
+
+        pulse.set_enabled(false);
+        assert!(pulse.pulses(0, 96, 24, 4).is_empty());
+    }
+
+    #[test]
+    fn test_osc_message() {
+        let pulse = BeatPulse::new();
+        let message = pulse.osc_message(&Pulse { tick: 0, bar: 0, beat: 0 });
+        assert_eq!(&message[..12], b"/seq/beat\0\0\0");
+        assert_eq!(&message[12..20], b",iii\0\0\0\0");
+        assert_eq!(&message[20..], &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,423 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! MIDI routing matrix.
+//!
+//! Routes messages from input ports to output ports independently of
+//! tracks, with a chain of processors (channel filters and remaps, note
+//! range filters, message type filters, transpose) on each route. This lets
+//! SEQ act as the central patchbay of a hardware rig.
+
+use anyhow::{anyhow, bail, Result};
+
+use super::input::MidiMessage;
+
+/// Message category used by route filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageKind {
+    /// Note on/off
+    Notes,
+    /// Control change
+    ControlChange,
+    /// Program change
+    ProgramChange,
+    /// Pitch bend
+    PitchBend,
+    /// Channel and poly aftertouch
+    Aftertouch,
+    /// Clock, transport and song position
+    Clock,
+    /// Anything else
+    Other,
+}
+
+impl MessageKind {
+    /// Get the kind of a message
+    pub fn of(message: &MidiMessage) -> Self {
+        match message {
+            MidiMessage::NoteOn { .. } | MidiMessage::NoteOff { .. } => MessageKind::Notes,
+            MidiMessage::ControlChange { .. } => MessageKind::ControlChange,
+            MidiMessage::ProgramChange { .. } => MessageKind::ProgramChange,
+            MidiMessage::PitchBend { .. } => MessageKind::PitchBend,
+            MidiMessage::ChannelAftertouch { .. } | MidiMessage::PolyAftertouch { .. } => {
+                MessageKind::Aftertouch
+            }
+            m if m.is_clock_message() => MessageKind::Clock,
+            _ => MessageKind::Other,
+        }
+    }
+
+    /// Parse from a string like "notes" or "cc"
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "notes" | "note" => Some(MessageKind::Notes),
+            "cc" | "control_change" => Some(MessageKind::ControlChange),
+            "program" | "program_change" => Some(MessageKind::ProgramChange),
+            "pitch_bend" | "bend" => Some(MessageKind::PitchBend),
+            "aftertouch" | "pressure" => Some(MessageKind::Aftertouch),
+            "clock" => Some(MessageKind::Clock),
+            _ => None,
+        }
+    }
+}
+
+/// A processing step on a route
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteProcessor {
+    /// Only pass messages on this channel (0-15)
+    ChannelFilter(u8),
+    /// Move channel messages to this channel (0-15)
+    RemapChannel(u8),
+    /// Only pass notes within this range (inclusive)
+    NoteRange(u8, u8),
+    /// Drop messages of this kind
+    Block(MessageKind),
+    /// Transpose notes by semitones
+    Transpose(i8),
+}
+
+impl RouteProcessor {
+    /// Process a message, returning None if it is filtered out
+    pub fn process(&self, message: MidiMessage) -> Option<MidiMessage> {
+        match *self {
+            RouteProcessor::ChannelFilter(ch) => match channel_of(&message) {
+                Some(c) if c != ch => None,
+                _ => Some(message),
+            },
+            RouteProcessor::RemapChannel(ch) => Some(with_channel(message, ch.min(15))),
+            RouteProcessor::NoteRange(low, high) => match message {
+                MidiMessage::NoteOn { note, .. }
+                | MidiMessage::NoteOff { note, .. }
+                | MidiMessage::PolyAftertouch { note, .. }
+                    if note < low || note > high =>
+                {
+                    None
+                }
+                _ => Some(message),
+            },
+            RouteProcessor::Block(kind) => {
+                if MessageKind::of(&message) == kind {
+                    None
+                } else {
+                    Some(message)
+                }
+            }
+            RouteProcessor::Transpose(semitones) => {
+                let shift = |note: u8| (note as i16 + semitones as i16).clamp(0, 127) as u8;
+                Some(match message {
+                    MidiMessage::NoteOn { channel, note, velocity } => {
+                        MidiMessage::NoteOn { channel, note: shift(note), velocity }
+                    }
+                    MidiMessage::NoteOff { channel, note, velocity } => {
+                        MidiMessage::NoteOff { channel, note: shift(note), velocity }
+                    }
+                    MidiMessage::PolyAftertouch { channel, note, pressure } => {
+                        MidiMessage::PolyAftertouch { channel, note: shift(note), pressure }
+                    }
+                    other => other,
+                })
+            }
+        }
+    }
+}
+
+/// Get the channel of a channel message
+fn channel_of(message: &MidiMessage) -> Option<u8> {
+    match *message {
+        MidiMessage::NoteOn { channel, .. }
+        | MidiMessage::NoteOff { channel, .. }
+        | MidiMessage::ControlChange { channel, .. }
+        | MidiMessage::ProgramChange { channel, .. }
+        | MidiMessage::PitchBend { channel, .. }
+        | MidiMessage::ChannelAftertouch { channel, .. }
+        | MidiMessage::PolyAftertouch { channel, .. } => Some(channel),
+        _ => None,
+    }
+}
+
+/// Replace the channel of a channel message
+fn with_channel(message: MidiMessage, ch: u8) -> MidiMessage {
+    match message {
+        MidiMessage::NoteOn { note, velocity, .. } => MidiMessage::NoteOn { channel: ch, note, velocity },
+        MidiMessage::NoteOff { note, velocity, .. } => MidiMessage::NoteOff { channel: ch, note, velocity },
+        MidiMessage::ControlChange { controller, value, .. } => {
+            MidiMessage::ControlChange { channel: ch, controller, value }
+        }
+        MidiMessage::ProgramChange { program, .. } => MidiMessage::ProgramChange { channel: ch, program },
+        MidiMessage::PitchBend { value, .. } => MidiMessage::PitchBend { channel: ch, value },
+        MidiMessage::ChannelAftertouch { pressure, .. } => {
+            MidiMessage::ChannelAftertouch { channel: ch, pressure }
+        }
+        MidiMessage::PolyAftertouch { note, pressure, .. } => {
+            MidiMessage::PolyAftertouch { channel: ch, note, pressure }
+        }
+        other => other,
+    }
+}
+
+/// A route from one input port to one output port
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    /// Input port index
+    pub input: usize,
+    /// Output port index
+    pub output: usize,
+    /// Processing chain, applied in order
+    pub processors: Vec<RouteProcessor>,
+    /// Whether the route is active
+    pub enabled: bool,
+}
+
+impl Route {
+    /// Create a plain thru route
+    pub fn new(input: usize, output: usize) -> Self {
+        Self {
+            input,
+            output,
+            processors: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Create a route from configuration, matching port names case-insensitively
+    pub fn from_config(
+        config: &crate::config::RouteConfig,
+        inputs: &[String],
+        outputs: &[String],
+    ) -> Result<Self> {
+        let find = |ports: &[String], name: &str| {
+            let name = name.to_lowercase();
+            ports.iter().position(|p| p.to_lowercase().contains(&name))
+        };
+        let input = find(inputs, &config.from)
+            .ok_or_else(|| anyhow!("No MIDI input matching '{}'", config.from))?;
+        let output = find(outputs, &config.to)
+            .ok_or_else(|| anyhow!("No MIDI output matching '{}'", config.to))?;
+
+        let mut route = Self::new(input, output);
+        if let Some(ch) = config.channel {
+            route.processors.push(RouteProcessor::ChannelFilter(ch.clamp(1, 16) - 1));
+        }
+        if let Some([low, high]) = config.notes {
+            route.processors.push(RouteProcessor::NoteRange(low, high));
+        }
+        for name in &config.block {
+            let kind = MessageKind::from_str(name)
+                .ok_or_else(|| anyhow!("Unknown message kind: {}", name))?;
+            route.processors.push(RouteProcessor::Block(kind));
+        }
+        if config.transpose != 0 {
+            route.processors.push(RouteProcessor::Transpose(config.transpose));
+        }
+        if let Some(ch) = config.remap_channel {
+            route.processors.push(RouteProcessor::RemapChannel(ch.clamp(1, 16) - 1));
+        }
+        Ok(route)
+    }
+
+    /// Builder: add a processor
+    pub fn with_processor(mut self, processor: RouteProcessor) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Run a message through the processing chain
+    pub fn process(&self, message: &MidiMessage) -> Option<MidiMessage> {
+        self.processors
+            .iter()
+            .try_fold(message.clone(), |msg, processor| processor.process(msg))
+    }
+}
+
+/// Routing matrix of inputs to outputs
+#[derive(Debug, Clone, Default)]
+pub struct MidiRouter {
+    /// Input port names
+    inputs: Vec<String>,
+    /// Output port names
+    outputs: Vec<String>,
+    /// Routes
+    routes: Vec<Route>,
+}
+
+impl MidiRouter {
+    /// Create a router for the given ports
+    pub fn new(inputs: Vec<String>, outputs: Vec<String>) -> Self {
+        Self {
+            inputs,
+            outputs,
+            routes: Vec::new(),
+        }
+    }
+
+    /// Get input port names
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    /// Get output port names
+    pub fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+
+    /// Get all routes
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Add a route (replacing an existing route between the same ports)
+    pub fn add_route(&mut self, route: Route) -> Result<()> {
+        if route.input >= self.inputs.len() {
+            bail!("Input {} out of range", route.input);
+        }
+        if route.output >= self.outputs.len() {
+            bail!("Output {} out of range", route.output);
+        }
+        self.remove_route(route.input, route.output);
+        self.routes.push(route);
+        Ok(())
+    }
+
+    /// Remove the route between two ports
+    pub fn remove_route(&mut self, input: usize, output: usize) -> Option<Route> {
+        let index = self
+            .routes
+            .iter()
+            .position(|r| r.input == input && r.output == output)?;
+        Some(self.routes.remove(index))
+    }
+
+    /// Get the route between two ports
+    pub fn route(&self, input: usize, output: usize) -> Option<&Route> {
+        self.routes.iter().find(|r| r.input == input && r.output == output)
+    }
+
+    /// Get the mutable route between two ports
+    pub fn route_mut(&mut self, input: usize, output: usize) -> Option<&mut Route> {
+        self.routes
+            .iter_mut()
+            .find(|r| r.input == input && r.output == output)
+    }
+
+    /// Toggle a plain thru route between two ports
+    pub fn toggle_route(&mut self, input: usize, output: usize) -> Result<bool> {
+        if self.remove_route(input, output).is_some() {
+            Ok(false)
+        } else {
+            self.add_route(Route::new(input, output))?;
+            Ok(true)
+        }
+    }
+
+    /// Route a message from an input, returning (output index, message) pairs
+    pub fn process(&self, input: usize, message: &MidiMessage) -> Vec<(usize, MidiMessage)> {
+        self.routes
+            .iter()
+            .filter(|r| r.enabled && r.input == input)
+            .filter_map(|r| r.process(message).map(|msg| (r.output, msg)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router() -> MidiRouter {
+        MidiRouter::new(
+            vec!["Keys".to_string(), "Pads".to_string()],
+            vec!["Synth".to_string(), "Drum Machine".to_string()],
+        )
+    }
+
+    #[test]
+    fn test_thru_route() {
+        let mut router = router();
+        router.add_route(Route::new(0, 0)).unwrap();
+
+        let msg = MidiMessage::NoteOn { channel: 0, note: 60, velocity: 100 };
+        assert_eq!(router.process(0, &msg), vec![(0, msg.clone())]);
+        assert!(router.process(1, &msg).is_empty());
+    }
+
+    #[test]
+    fn test_route_processors() {
+        let mut router = router();
+        router
+            .add_route(
+                Route::new(1, 1)
+                    .with_processor(RouteProcessor::ChannelFilter(9))
+                    .with_processor(RouteProcessor::NoteRange(36, 51))
+                    .with_processor(RouteProcessor::RemapChannel(0))
+                    .with_processor(RouteProcessor::Block(MessageKind::Aftertouch)),
+            )
+            .unwrap();
+
+        let kick = MidiMessage::NoteOn { channel: 9, note: 36, velocity: 100 };
+        assert_eq!(
+            router.process(1, &kick),
+            vec![(1, MidiMessage::NoteOn { channel: 0, note: 36, velocity: 100 })]
+        );
+
+        let wrong_channel = MidiMessage::NoteOn { channel: 1, note: 36, velocity: 100 };
+        assert!(router.process(1, &wrong_channel).is_empty());
+
+        let high = MidiMessage::NoteOn { channel: 9, note: 60, velocity: 100 };
+        assert!(router.process(1, &high).is_empty());
+
+        let pressure = MidiMessage::ChannelAftertouch { channel: 9, pressure: 50 };
+        assert!(router.process(1, &pressure).is_empty());
+    }
+
+    #[test]
+    fn test_toggle_route() {
+        let mut router = router();
+        assert!(router.toggle_route(0, 1).unwrap());
+        assert!(router.route(0, 1).is_some());
+        assert!(!router.toggle_route(0, 1).unwrap());
+        assert!(router.routes().is_empty());
+        assert!(router.add_route(Route::new(5, 0)).is_err());
+    }
+
+    #[test]
+    fn test_route_from_config() {
+        let config = crate::config::RouteConfig {
+            from: "pads".to_string(),
+            to: "drum".to_string(),
+            channel: Some(10),
+            remap_channel: Some(1),
+            notes: None,
+            block: vec!["clock".to_string()],
+            transpose: 0,
+        };
+        let router = router();
+        let route = Route::from_config(&config, router.inputs(), router.outputs()).unwrap();
+        assert_eq!((route.input, route.output), (1, 1));
+        assert_eq!(
+            route.processors,
+            vec![
+                RouteProcessor::ChannelFilter(9),
+                RouteProcessor::Block(MessageKind::Clock),
+                RouteProcessor::RemapChannel(0),
+            ]
+        );
+
+        let bad = crate::config::RouteConfig {
+            to: "Nowhere".to_string(),
+            ..config
+        };
+        assert!(Route::from_config(&bad, router.inputs(), router.outputs()).is_err());
+    }
+
+    #[test]
+    fn test_transpose_and_clock() {
+        let route = Route::new(0, 0).with_processor(RouteProcessor::Transpose(12));
+        let msg = MidiMessage::NoteOff { channel: 2, note: 60, velocity: 0 };
+        assert_eq!(
+            route.process(&msg),
+            Some(MidiMessage::NoteOff { channel: 2, note: 72, velocity: 0 })
+        );
+        assert_eq!(route.process(&MidiMessage::TimingClock), Some(MidiMessage::TimingClock));
+        assert_eq!(MessageKind::of(&MidiMessage::Start), MessageKind::Clock);
+    }
+}
@@ -0,0 +1,511 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Network MIDI (RTP-MIDI / AppleMIDI) backend.
+//!
+//! An AppleMIDI session uses two UDP ports: a control port for the session
+//! handshake (invitation, accept, end) and the next port up for MIDI data
+//! and clock sync. SEQ can invite a peer (initiator) or wait for one to
+//! invite it (participant), then sends and receives MIDI as RTP packets.
+//! Packets are sent without a recovery journal, which is fine on a LAN.
+
+use std::collections::VecDeque;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::Instant;
+
+use anyhow::{anyhow, Context, Result};
+use rand::Rng;
+
+use super::input::MidiMessage;
+use super::MidiOutput;
+
+/// Default AppleMIDI control port (data is one above)
+pub const DEFAULT_RTP_PORT: u16 = 5004;
+
+/// AppleMIDI protocol version
+const PROTOCOL_VERSION: u32 = 2;
+
+/// RTP payload type used for MIDI
+const PAYLOAD_TYPE: u8 = 0x61;
+
+/// Largest datagram read
+const MAX_PACKET: usize = 1500;
+
+/// AppleMIDI session control packet
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionPacket {
+    /// Invitation to join a session
+    Invitation { token: u32, ssrc: u32, name: String },
+    /// Invitation accepted
+    Accept { token: u32, ssrc: u32, name: String },
+    /// Invitation rejected
+    Reject { token: u32, ssrc: u32 },
+    /// Session ended
+    End { token: u32, ssrc: u32 },
+    /// Clock synchronization (three-way timestamp exchange)
+    Sync { ssrc: u32, count: u8, timestamps: [u64; 3] },
+}
+
+impl SessionPacket {
+    /// Parse a session packet
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 4 || data[0] != 0xFF || data[1] != 0xFF {
+            return None;
+        }
+        let u32_at = |i: usize| Some(u32::from_be_bytes(data.get(i..i + 4)?.try_into().ok()?));
+        let command = &data[2..4];
+
+        if command == b"CK" {
+            let u64_at = |i: usize| Some(u64::from_be_bytes(data.get(i..i + 8)?.try_into().ok()?));
+            return Some(SessionPacket::Sync {
+                ssrc: u32_at(4)?,
+                count: *data.get(8)?,
+                timestamps: [u64_at(12)?, u64_at(20)?, u64_at(28)?],
+            });
+        }
+
+        let token = u32_at(8)?;
+        let ssrc = u32_at(12)?;
+        let name = || {
+            let bytes = data.get(16..).unwrap_or_default();
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            String::from_utf8_lossy(&bytes[..end]).into_owned()
+        };
+        match command {
+            b"IN" => Some(SessionPacket::Invitation { token, ssrc, name: name() }),
+            b"OK" => Some(SessionPacket::Accept { token, ssrc, name: name() }),
+            b"NO" => Some(SessionPacket::Reject { token, ssrc }),
+            b"BY" => Some(SessionPacket::End { token, ssrc }),
+            _ => None,
+        }
+    }
+
+    /// Encode the packet
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = vec![0xFF, 0xFF];
+        let mut header = |command: &[u8; 2], token: u32, ssrc: u32| {
+            data.extend_from_slice(command);
+            data.extend_from_slice(&PROTOCOL_VERSION.to_be_bytes());
+            data.extend_from_slice(&token.to_be_bytes());
+            data.extend_from_slice(&ssrc.to_be_bytes());
+        };
+        let name = match self {
+            SessionPacket::Invitation { token, ssrc, name } => {
+                header(b"IN", *token, *ssrc);
+                Some(name)
+            }
+            SessionPacket::Accept { token, ssrc, name } => {
+                header(b"OK", *token, *ssrc);
+                Some(name)
+            }
+            SessionPacket::Reject { token, ssrc } => {
+                header(b"NO", *token, *ssrc);
+                None
+            }
+            SessionPacket::End { token, ssrc } => {
+                header(b"BY", *token, *ssrc);
+                None
+            }
+            SessionPacket::Sync { ssrc, count, timestamps } => {
+                data.extend_from_slice(b"CK");
+                data.extend_from_slice(&ssrc.to_be_bytes());
+                data.extend_from_slice(&[*count, 0, 0, 0]);
+                for timestamp in timestamps {
+                    data.extend_from_slice(&timestamp.to_be_bytes());
+                }
+                None
+            }
+        };
+        if let Some(name) = name {
+            data.extend_from_slice(name.as_bytes());
+            data.push(0);
+        }
+        data
+    }
+}
+
+/// Encode MIDI messages as one RTP-MIDI packet (no journal, zero deltas)
+pub fn encode_midi(sequence: u16, timestamp: u32, ssrc: u32, messages: &[&[u8]]) -> Vec<u8> {
+    let mut list = Vec::new();
+    for (i, message) in messages.iter().enumerate() {
+        if i > 0 {
+            list.push(0); // Delta time
+        }
+        list.extend_from_slice(message);
+    }
+
+    let mut data = vec![0x80, PAYLOAD_TYPE];
+    data.extend_from_slice(&sequence.to_be_bytes());
+    data.extend_from_slice(&timestamp.to_be_bytes());
+    data.extend_from_slice(&ssrc.to_be_bytes());
+    if list.len() < 16 {
+        data.push(list.len() as u8);
+    } else {
+        // Long header: B flag and a 12-bit length
+        let len = list.len().min(0x0FFF) as u16;
+        data.extend_from_slice(&(0x8000 | len).to_be_bytes());
+        list.truncate(len as usize);
+    }
+    data.extend_from_slice(&list);
+    data
+}
+
+/// Decode the MIDI messages of an RTP-MIDI packet
+pub fn decode_midi(data: &[u8]) -> Option<Vec<Vec<u8>>> {
+    if data.len() < 13 || data[0] >> 6 != 2 || data[1] & 0x7F != PAYLOAD_TYPE {
+        return None;
+    }
+    let flags = data[12];
+    let (len, mut pos) = if flags & 0x80 != 0 {
+        (((flags as usize & 0x0F) << 8) | *data.get(13)? as usize, 14)
+    } else {
+        (flags as usize & 0x0F, 13)
+    };
+    let has_first_delta = flags & 0x20 != 0;
+    let end = (pos + len).min(data.len());
+
+    let mut messages = Vec::new();
+    let mut running_status = None;
+    while pos < end {
+        // Every command but the first carries a delta time
+        if !messages.is_empty() || has_first_delta {
+            while pos < end && data[pos] & 0x80 != 0 {
+                pos += 1;
+            }
+            pos += 1;
+            if pos >= end {
+                break;
+            }
+        }
+
+        let status = if data[pos] & 0x80 != 0 {
+            pos += 1;
+            data[pos - 1]
+        } else {
+            running_status?
+        };
+        let size = match status {
+            0xF0 => {
+                let sysex_end = data[pos..end].iter().position(|&b| b == 0xF7).map_or(end, |i| pos + i + 1);
+                sysex_end - pos
+            }
+            0xC0..=0xDF | 0xF1 | 0xF3 => 1,
+            0xF2 | 0x80..=0xBF | 0xE0..=0xEF => 2,
+            _ => 0,
+        };
+        let stop = (pos + size).min(end);
+        let mut message = vec![status];
+        message.extend_from_slice(&data[pos..stop]);
+        pos = stop;
+        if status < 0xF0 {
+            running_status = Some(status);
+        }
+        messages.push(message);
+    }
+    Some(messages)
+}
+
+/// Which side of the handshake this session plays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionRole {
+    /// Invites a peer
+    Initiator,
+    /// Waits for an invitation
+    Participant,
+}
+
+/// The connected peer
+#[derive(Debug, Clone, PartialEq)]
+pub struct RtpPeer {
+    /// Peer session name
+    pub name: String,
+    /// Peer SSRC
+    pub ssrc: u32,
+    /// Peer control address
+    pub control: SocketAddr,
+    /// Peer data address
+    pub data: SocketAddr,
+}
+
+/// An RTP-MIDI session with a single peer
+pub struct RtpMidiSession {
+    /// Our session name
+    name: String,
+    /// Handshake role
+    role: SessionRole,
+    /// Our SSRC
+    ssrc: u32,
+    /// Invitation token
+    token: u32,
+    /// Control port socket
+    control: UdpSocket,
+    /// Data port socket
+    data: UdpSocket,
+    /// Address being invited (initiator)
+    remote: Option<SocketAddr>,
+    /// Connected peer
+    peer: Option<RtpPeer>,
+    /// Peer control address accepted before the data port joins
+    pending_control: Option<SocketAddr>,
+    /// Next RTP sequence number
+    sequence: u16,
+    /// Session start (timestamps count from here)
+    started: Instant,
+    /// Received messages
+    received: VecDeque<MidiMessage>,
+}
+
+impl RtpMidiSession {
+    /// Wait for a peer to invite us on `port` (data on `port + 1`)
+    pub fn participant(name: impl Into<String>, port: u16) -> Result<Self> {
+        Self::bind(name.into(), port, SessionRole::Participant)
+    }
+
+    /// Invite a peer at `remote` (its control port) from our `port`
+    pub fn initiator(name: impl Into<String>, port: u16, remote: SocketAddr) -> Result<Self> {
+        let mut session = Self::bind(name.into(), port, SessionRole::Initiator)?;
+        session.remote = Some(remote);
+        session.invite(remote, false)?;
+        Ok(session)
+    }
+
+    fn bind(name: String, port: u16, role: SessionRole) -> Result<Self> {
+        let data_port = port
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("RTP-MIDI port {} leaves no room for the data port", port))?;
+        let control = UdpSocket::bind(("0.0.0.0", port))
+            .with_context(|| format!("Failed to bind RTP-MIDI control port {}", port))?;
+        let data = UdpSocket::bind(("0.0.0.0", data_port))
+            .with_context(|| format!("Failed to bind RTP-MIDI data port {}", data_port))?;
+        control.set_nonblocking(true)?;
+        data.set_nonblocking(true)?;
+
+        let mut rng = rand::thread_rng();
+        Ok(Self {
+            name,
+            role,
+            ssrc: rng.gen(),
+            token: rng.gen(),
+            control,
+            data,
+            remote: None,
+            peer: None,
+            pending_control: None,
+            sequence: rng.gen(),
+            started: Instant::now(),
+            received: VecDeque::new(),
+        })
+    }
+
+    /// Get the session role
+    pub fn role(&self) -> SessionRole {
+        self.role
+    }
+
+    /// Get the connected peer
+    pub fn peer(&self) -> Option<&RtpPeer> {
+        self.peer.as_ref()
+    }
+
+    /// Check if a peer is connected
+    pub fn is_connected(&self) -> bool {
+        self.peer.is_some()
+    }
+
+    /// Send an invitation to a control (or data) port
+    fn invite(&self, to: SocketAddr, data_port: bool) -> Result<()> {
+        let packet = SessionPacket::Invitation {
+            token: self.token,
+            ssrc: self.ssrc,
+            name: self.name.clone(),
+        };
+        let socket = if data_port { &self.data } else { &self.control };
+        socket.send_to(&packet.to_bytes(), to)?;
+        Ok(())
+    }
+
+    /// Timestamp in 100 microsecond units since the session started
+    fn now(&self) -> u64 {
+        (self.started.elapsed().as_micros() / 100) as u64
+    }
+
+    /// Handle everything waiting on both ports
+    pub fn poll(&mut self) -> Result<()> {
+        let mut buf = [0u8; MAX_PACKET];
+        while let Some((len, from)) = receive(&self.control, &mut buf)? {
+            if let Some(packet) = SessionPacket::parse(&buf[..len]) {
+                self.handle_session(packet, from, false)?;
+            }
+        }
+        while let Some((len, from)) = receive(&self.data, &mut buf)? {
+            let bytes = &buf[..len];
+            if let Some(packet) = SessionPacket::parse(bytes) {
+                self.handle_session(packet, from, true)?;
+            } else if self.peer.as_ref().is_some_and(|peer| peer.data == from) {
+                for message in decode_midi(bytes).unwrap_or_default() {
+                    if let Some(message) = MidiMessage::parse(&message) {
+                        self.received.push_back(message);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_session(&mut self, packet: SessionPacket, from: SocketAddr, data_port: bool) -> Result<()> {
+        let socket = if data_port { &self.data } else { &self.control };
+        match packet {
+            SessionPacket::Invitation { token, ssrc, name } => {
+                // Only one peer at a time
+                if self.peer.as_ref().is_some_and(|peer| peer.ssrc != ssrc) {
+                    let reject = SessionPacket::Reject { token, ssrc: self.ssrc };
+                    socket.send_to(&reject.to_bytes(), from)?;
+                    return Ok(());
+                }
+                let accept = SessionPacket::Accept { token, ssrc: self.ssrc, name: self.name.clone() };
+                socket.send_to(&accept.to_bytes(), from)?;
+                if data_port {
+                    let control = self.pending_control.take().unwrap_or(from);
+                    self.peer = Some(RtpPeer { name, ssrc, control, data: from });
+                } else {
+                    self.pending_control = Some(from);
+                }
+            }
+            SessionPacket::Accept { token, ssrc, name } if token == self.token => {
+                if data_port {
+                    let control = self.remote.unwrap_or(from);
+                    self.peer = Some(RtpPeer { name, ssrc, control, data: from });
+                    // Start clock sync
+                    let sync = SessionPacket::Sync { ssrc: self.ssrc, count: 0, timestamps: [self.now(), 0, 0] };
+                    self.data.send_to(&sync.to_bytes(), from)?;
+                } else {
+                    // Control port joined: invite the data port next
+                    let data = SocketAddr::new(from.ip(), from.port().wrapping_add(1));
+                    self.invite(data, true)?;
+                }
+            }
+            SessionPacket::Reject { .. } => {
+                self.remote = None;
+            }
+            SessionPacket::End { ssrc, .. } => {
+                if self.peer.as_ref().is_some_and(|peer| peer.ssrc == ssrc) {
+                    self.peer = None;
+                }
+            }
+            SessionPacket::Sync { count, mut timestamps, .. } if count < 2 => {
+                timestamps[count as usize + 1] = self.now();
+                let reply = SessionPacket::Sync { ssrc: self.ssrc, count: count + 1, timestamps };
+                socket.send_to(&reply.to_bytes(), from)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Try to receive the next MIDI message (non-blocking)
+    pub fn try_recv(&mut self) -> Option<MidiMessage> {
+        self.received.pop_front()
+    }
+
+    /// Receive all pending MIDI messages
+    pub fn recv_all(&mut self) -> Vec<MidiMessage> {
+        self.received.drain(..).collect()
+    }
+
+    /// End the session with the peer
+    pub fn close(&mut self) -> Result<()> {
+        if let Some(peer) = self.peer.take() {
+            let end = SessionPacket::End { token: self.token, ssrc: self.ssrc };
+            self.control.send_to(&end.to_bytes(), peer.control)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read one datagram without blocking
+fn receive(socket: &UdpSocket, buf: &mut [u8]) -> Result<Option<(usize, SocketAddr)>> {
+    match socket.recv_from(buf) {
+        Ok(received) => Ok(Some(received)),
+        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl MidiOutput for RtpMidiSession {
+    fn send(&mut self, message: &[u8]) -> Result<()> {
+        let peer = self
+            .peer
+            .as_ref()
+            .ok_or_else(|| anyhow!("No RTP-MIDI peer connected"))?;
+        let packet = encode_midi(self.sequence, self.now() as u32, self.ssrc, &[message]);
+        self.data.send_to(&packet, peer.data)?;
+        self.sequence = self.sequence.wrapping_add(1);
+        Ok(())
+    }
+
+    fn send_at(&mut self, message: &[u8], _timestamp: u64) -> Result<()> {
+        // Packets go out as they are sent; the peer plays them on arrival
+        self.send(message)
+    }
+}
+
+impl Drop for RtpMidiSession {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_packets() {
+        let invitation = SessionPacket::Invitation {
+            token: 0x1234,
+            ssrc: 0xCAFE,
+            name: "SEQ".to_string(),
+        };
+        let bytes = invitation.to_bytes();
+        assert_eq!(&bytes[..4], &[0xFF, 0xFF, b'I', b'N']);
+        assert_eq!(bytes.last(), Some(&0));
+        assert_eq!(SessionPacket::parse(&bytes), Some(invitation));
+
+        let sync = SessionPacket::Sync { ssrc: 7, count: 1, timestamps: [10, 20, 0] };
+        assert_eq!(sync.to_bytes().len(), 36);
+        assert_eq!(SessionPacket::parse(&sync.to_bytes()), Some(sync));
+
+        let end = SessionPacket::End { token: 1, ssrc: 2 };
+        assert_eq!(SessionPacket::parse(&end.to_bytes()), Some(end));
+        assert_eq!(SessionPacket::parse(&[0x80, 0x61, 0, 0]), None);
+    }
+
+    #[test]
+    fn test_midi_payload() {
+        let packet = encode_midi(1, 100, 42, &[&[0x90, 60, 100], &[0xB0, 7, 90]]);
+        assert_eq!(packet[1], PAYLOAD_TYPE);
+        assert_eq!(packet[12], 7);
+        assert_eq!(
+            decode_midi(&packet).unwrap(),
+            vec![vec![0x90, 60, 100], vec![0xB0, 7, 90]]
+        );
+
+        // Running status and a long header
+        let mut long = encode_midi(2, 0, 42, &[]);
+        long.truncate(12);
+        let list = [0x90, 60, 100, 0x00, 64, 100, 0x00, 67, 100, 0x00, 0x80, 60, 0, 0x00, 0xF8, 0x00];
+        long.extend_from_slice(&(0x8000u16 | list.len() as u16).to_be_bytes());
+        long.extend_from_slice(&list);
+        assert_eq!(
+            decode_midi(&long).unwrap(),
+            vec![
+                vec![0x90, 60, 100],
+                vec![0x90, 64, 100],
+                vec![0x90, 67, 100],
+                vec![0x80, 60, 0],
+                vec![0xF8],
+            ]
+        );
+        assert!(decode_midi(&[0xFF, 0xFF, b'C', b'K']).is_none());
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Loopback self-test for MIDI devices.
+//!
+//! Sends a fixed pattern of notes, controllers and SysEx to an output that
+//! is patched back into an input (a cable between two DIN ports, or a
+//! virtual bus), then checks that every probe arrives intact and measures
+//! round-trip latency and jitter. Meant as a rig check before a show.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+
+use super::{messages, MidiInput, MidiMessage, MidiOutput};
+
+/// Manufacturer ID for non-commercial SysEx
+const SYSEX_NON_COMMERCIAL: u8 = 0x7D;
+
+/// One probe message and when it was sent
+#[derive(Debug, Clone)]
+struct Probe {
+    /// Raw bytes sent
+    bytes: Vec<u8>,
+    /// How the probe should look when received
+    expected: MidiMessage,
+    /// Send time
+    sent_at: Option<Instant>,
+    /// Round-trip time once received
+    latency: Option<Duration>,
+}
+
+/// Build the probe pattern: note on/off pairs, controller moves and SysEx
+pub fn probe_messages(count: usize) -> Vec<Vec<u8>> {
+    let mut probes = Vec::new();
+    for i in 0..count {
+        let n = (i % 128) as u8;
+        match i % 4 {
+            0 => probes.push(vec![messages::NOTE_ON, 36 + n % 64, 1 + n % 127]),
+            1 => probes.push(vec![messages::NOTE_OFF, 36 + (n - 1) % 64, 0]),
+            2 => probes.push(vec![messages::CONTROL_CHANGE | 1, 20 + n % 80, n]),
+            _ => probes.push(vec![
+                messages::SYSEX_START,
+                SYSEX_NON_COMMERCIAL,
+                0x53,
+                0x51,
+                n,
+                messages::SYSEX_END,
+            ]),
+        }
+    }
+    probes
+}
+
+/// Result of a loopback run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfTestReport {
+    /// Probes sent
+    pub sent: usize,
+    /// Probes received intact
+    pub received: usize,
+    /// Received messages that matched no probe
+    pub unexpected: usize,
+    /// Round-trip latency of each received probe
+    pub latencies: Vec<Duration>,
+}
+
+impl SelfTestReport {
+    /// Probes that never came back
+    pub fn missing(&self) -> usize {
+        self.sent - self.received
+    }
+
+    /// Check if every probe arrived and nothing else did
+    pub fn passed(&self) -> bool {
+        self.sent > 0 && self.missing() == 0 && self.unexpected == 0
+    }
+
+    /// Mean round-trip latency
+    pub fn mean_latency(&self) -> Option<Duration> {
+        if self.latencies.is_empty() {
+            return None;
+        }
+        Some(self.latencies.iter().sum::<Duration>() / self.latencies.len() as u32)
+    }
+
+    /// Largest round-trip latency
+    pub fn max_latency(&self) -> Option<Duration> {
+        self.latencies.iter().max().copied()
+    }
+
+    /// Standard deviation of the round-trip latency
+    pub fn jitter(&self) -> Option<Duration> {
+        let mean = self.mean_latency()?.as_secs_f64();
+        let variance = self
+            .latencies
+            .iter()
+            .map(|l| (l.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / self.latencies.len() as f64;
+        Some(Duration::from_secs_f64(variance.sqrt()))
+    }
+
+    /// Print a summary
+    pub fn print(&self) {
+        println!("Sent:       {}", self.sent);
+        println!("Received:   {}", self.received);
+        println!("Missing:    {}", self.missing());
+        println!("Unexpected: {}", self.unexpected);
+        let ms = |d: Option<Duration>| d.map_or("-".to_string(), |d| format!("{:.3}ms", d.as_secs_f64() * 1000.0));
+        println!("Latency:    mean {}, max {}", ms(self.mean_latency()), ms(self.max_latency()));
+        println!("Jitter:     {}", ms(self.jitter()));
+        println!();
+        println!("{}", if self.passed() { "PASS" } else { "FAIL" });
+    }
+}
+
+/// Matches received messages against sent probes
+#[derive(Debug, Clone, Default)]
+pub struct ProbeTracker {
+    /// Probes in send order
+    probes: Vec<Probe>,
+    /// Received messages that matched nothing
+    unexpected: usize,
+}
+
+impl ProbeTracker {
+    /// Create a tracker for a probe pattern
+    pub fn new(pattern: &[Vec<u8>]) -> Self {
+        let probes = pattern
+            .iter()
+            .filter_map(|bytes| {
+                Some(Probe {
+                    bytes: bytes.clone(),
+                    expected: MidiMessage::parse(bytes)?,
+                    sent_at: None,
+                    latency: None,
+                })
+            })
+            .collect();
+        Self {
+            probes,
+            unexpected: 0,
+        }
+    }
+
+    /// Number of probes
+    pub fn len(&self) -> usize {
+        self.probes.len()
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.probes.is_empty()
+    }
+
+    /// Bytes of a probe
+    pub fn bytes(&self, index: usize) -> Option<&[u8]> {
+        self.probes.get(index).map(|p| p.bytes.as_slice())
+    }
+
+    /// Mark a probe as sent
+    pub fn mark_sent(&mut self, index: usize, at: Instant) {
+        if let Some(probe) = self.probes.get_mut(index) {
+            probe.sent_at = Some(at);
+        }
+    }
+
+    /// Match a received message to the oldest outstanding probe it equals
+    pub fn receive(&mut self, message: &MidiMessage, at: Instant) -> bool {
+        // Clock and other real-time traffic on the bus is not ours to judge
+        if message.is_clock_message() {
+            return false;
+        }
+        let probe = self
+            .probes
+            .iter_mut()
+            .find(|p| p.sent_at.is_some() && p.latency.is_none() && p.expected == *message);
+        match probe {
+            Some(probe) => {
+                probe.latency = probe.sent_at.map(|sent| at.saturating_duration_since(sent));
+                true
+            }
+            None => {
+                self.unexpected += 1;
+                false
+            }
+        }
+    }
+
+    /// Check if every sent probe has come back
+    pub fn is_complete(&self) -> bool {
+        self.probes
+            .iter()
+            .all(|p| p.sent_at.is_none() || p.latency.is_some())
+    }
+
+    /// Summarize the run
+    pub fn report(&self) -> SelfTestReport {
+        let latencies: Vec<Duration> = self.probes.iter().filter_map(|p| p.latency).collect();
+        SelfTestReport {
+            sent: self.probes.iter().filter(|p| p.sent_at.is_some()).count(),
+            received: latencies.len(),
+            unexpected: self.unexpected,
+            latencies,
+        }
+    }
+}
+
+/// Send the probe pattern through a loopback and wait for it to return
+pub fn run_self_test(
+    output: &mut dyn MidiOutput,
+    input: &MidiInput,
+    count: usize,
+    spacing: Duration,
+    timeout: Duration,
+) -> Result<SelfTestReport> {
+    let mut tracker = ProbeTracker::new(&probe_messages(count));
+
+    // Drop anything already waiting on the input
+    input.recv_all();
+
+    for index in 0..tracker.len() {
+        let bytes = tracker.bytes(index).unwrap_or_default().to_vec();
+        tracker.mark_sent(index, Instant::now());
+        output.send(&bytes)?;
+
+        let next = Instant::now() + spacing;
+        while Instant::now() < next {
+            for message in input.recv_all() {
+                tracker.receive(&message, Instant::now());
+            }
+            thread::sleep(Duration::from_micros(200));
+        }
+    }
+
+    // Wait for stragglers
+    let deadline = Instant::now() + timeout;
+    while !tracker.is_complete() && Instant::now() < deadline {
+        for message in input.recv_all() {
+            tracker.receive(&message, Instant::now());
+        }
+        thread::sleep(Duration::from_micros(200));
+    }
+
+    Ok(tracker.report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_pattern() {
+        let probes = probe_messages(8);
+        assert_eq!(probes.len(), 8);
+        assert_eq!(probes[0][0], messages::NOTE_ON);
+        assert_eq!(probes[1][0], messages::NOTE_OFF);
+        assert_eq!(probes[2][0], messages::CONTROL_CHANGE | 1);
+        assert_eq!(probes[3].first(), Some(&messages::SYSEX_START));
+        assert_eq!(probes[3].last(), Some(&messages::SYSEX_END));
+
+        // Every probe is distinguishable from the others
+        let parsed: Vec<MidiMessage> = probes.iter().filter_map(|p| MidiMessage::parse(p)).collect();
+        for (i, a) in parsed.iter().enumerate() {
+            assert!(parsed[i + 1..].iter().all(|b| a != b));
+        }
+    }
+
+    #[test]
+    fn test_tracker_report() {
+        let mut tracker = ProbeTracker::new(&probe_messages(4));
+        let start = Instant::now();
+        for i in 0..4 {
+            tracker.mark_sent(i, start);
+        }
+
+        for (i, ms) in [(0, 2), (2, 4), (3, 3)] {
+            let message = MidiMessage::parse(tracker.bytes(i).unwrap()).unwrap();
+            assert!(tracker.receive(&message, start + Duration::from_millis(ms)));
+        }
+        assert!(!tracker.receive(&MidiMessage::TimingClock, start));
+        assert!(!tracker.receive(&MidiMessage::ProgramChange { channel: 0, program: 5 }, start));
+        assert!(!tracker.is_complete());
+
+        let report = tracker.report();
+        assert_eq!(report.sent, 4);
+        assert_eq!(report.received, 3);
+        assert_eq!(report.missing(), 1);
+        assert_eq!(report.unexpected, 1);
+        assert!(!report.passed());
+        assert_eq!(report.mean_latency(), Some(Duration::from_millis(3)));
+        assert_eq!(report.max_latency(), Some(Duration::from_millis(4)));
+        assert!(report.jitter().unwrap() > Duration::ZERO);
+    }
+}
@@ -3,9 +3,11 @@
 
 //! Music theory utilities for SEQ.
 //!
-//! This module provides scale definitions, key management, and note
-//! manipulation utilities for algorithmic composition.
+//! This module provides scale definitions, key management, note
+//! manipulation and Euclidean rhythm utilities for algorithmic composition.
 
+pub mod rhythm;
 pub mod scale;
 
-pub use scale::{Key, Note, Scale, ScaleType};
+pub use rhythm::{euclidean, EuclideanGate};
+pub use scale::{parse_midi_note, Key, Note, Scale, ScaleType};
@@ -0,0 +1,242 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Euclidean rhythms.
+//!
+//! Bjorklund's algorithm spreads a number of hits as evenly as possible
+//! over a number of steps (3 in 8 gives the tresillo). A Euclidean gate
+//! lays such a pattern over each bar, so a generator can let through only
+//! the onsets that land on a hit.
+
+/// Spread `hits` as evenly as possible over `steps` (Bjorklund's algorithm)
+pub fn euclidean(hits: usize, steps: usize) -> Vec<bool> {
+    if steps == 0 {
+        return vec![];
+    }
+    if hits >= steps {
+        return vec![true; steps];
+    }
+    if hits == 0 {
+        return vec![false; steps];
+    }
+
+    let mut pattern = vec![vec![true]; hits];
+    let mut remainder = vec![vec![false]; steps - hits];
+
+    while remainder.len() > 1 {
+        let min_len = pattern.len().min(remainder.len());
+        for i in 0..min_len {
+            pattern[i].extend(remainder[i].clone());
+        }
+        let new_remainder: Vec<Vec<bool>> = if pattern.len() > min_len {
+            pattern.drain(min_len..).collect()
+        } else {
+            remainder.drain(min_len..).collect()
+        };
+        remainder = new_remainder;
+    }
+
+    // Flatten and append remainder
+    let mut result: Vec<bool> = pattern.into_iter().flatten().collect();
+    for r in remainder {
+        result.extend(r);
+    }
+    result
+}
+
+/// Rotate a pattern later by a number of steps
+pub fn rotate(pattern: &[bool], rotation: usize) -> Vec<bool> {
+    let mut rotated = pattern.to_vec();
+    if !rotated.is_empty() {
+        rotated.rotate_right(rotation % pattern.len());
+    }
+    rotated
+}
+
+/// Euclidean pattern over each bar that masks note onsets. Steps start at
+/// `bar start + i * ticks per bar / steps`, so they stay on the bar even
+/// when the bar doesn't divide evenly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EuclideanGate {
+    /// Whether the gate is applied
+    pub enabled: bool,
+    /// Hits per bar
+    hits: u8,
+    /// Steps per bar
+    steps: u8,
+    /// Steps the pattern is rotated later
+    rotation: u8,
+    /// The rotated pattern, rebuilt when the above change
+    pattern: Vec<bool>,
+}
+
+impl Default for EuclideanGate {
+    fn default() -> Self {
+        let mut gate = Self {
+            enabled: false,
+            hits: 5,
+            steps: 8,
+            rotation: 0,
+            pattern: Vec::new(),
+        };
+        gate.rebuild();
+        gate
+    }
+}
+
+impl EuclideanGate {
+    /// Create an enabled gate
+    pub fn new(hits: u8, steps: u8) -> Self {
+        let mut gate = Self {
+            enabled: true,
+            hits,
+            steps: steps.max(1),
+            rotation: 0,
+            pattern: Vec::new(),
+        };
+        gate.rebuild();
+        gate
+    }
+
+    /// Set rotation
+    pub fn with_rotation(mut self, rotation: u8) -> Self {
+        self.set_rotation(rotation);
+        self
+    }
+
+    /// Get hits per bar
+    pub fn hits(&self) -> u8 {
+        self.hits
+    }
+
+    /// Get steps per bar
+    pub fn steps(&self) -> u8 {
+        self.steps
+    }
+
+    /// Get rotation
+    pub fn rotation(&self) -> u8 {
+        self.rotation
+    }
+
+    /// Set hits per bar
+    pub fn set_hits(&mut self, hits: u8) {
+        self.hits = hits;
+        self.rebuild();
+    }
+
+    /// Set steps per bar (at least 1)
+    pub fn set_steps(&mut self, steps: u8) {
+        self.steps = steps.max(1);
+        self.rebuild();
+    }
+
+    /// Set rotation
+    pub fn set_rotation(&mut self, rotation: u8) {
+        self.rotation = rotation;
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        self.pattern = rotate(&euclidean(self.hits as usize, self.steps as usize), self.rotation as usize);
+    }
+
+    /// The rotated pattern
+    pub fn pattern(&self) -> &[bool] {
+        &self.pattern
+    }
+
+    /// Average length of a step in ticks
+    pub fn step_ticks(&self, ticks_per_bar: u64) -> u64 {
+        (ticks_per_bar / self.steps.max(1) as u64).max(1)
+    }
+
+    /// Tick within the bar where a step starts
+    fn step_start(&self, step: u64, ticks_per_bar: u64) -> u64 {
+        step * ticks_per_bar / self.steps.max(1) as u64
+    }
+
+    /// Check if an onset at a song tick gets through (always, when off)
+    pub fn allows_at(&self, tick: u64, ticks_per_bar: u64) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        // The last step starting at or before the tick
+        let ticks_per_bar = ticks_per_bar.max(1);
+        let offset = tick % ticks_per_bar;
+        let step = ((offset + 1) * self.steps.max(1) as u64).div_ceil(ticks_per_bar) - 1;
+        self.pattern.get(step as usize).copied().unwrap_or(false)
+    }
+
+    /// Song ticks of the hits from `start` up to (not including) `end`
+    pub fn hits_between(&self, start: u64, end: u64, ticks_per_bar: u64) -> Vec<u64> {
+        let ticks_per_bar = ticks_per_bar.max(1);
+        let mut hits: Vec<u64> = Vec::new();
+        if end <= start {
+            return hits;
+        }
+        for bar in start / ticks_per_bar..=(end - 1) / ticks_per_bar {
+            let bar_start = bar * ticks_per_bar;
+            for (step, _) in self.pattern.iter().enumerate().filter(|&(_, &hit)| hit) {
+                let tick = bar_start + self.step_start(step as u64, ticks_per_bar);
+                if tick >= start && tick < end && hits.last() != Some(&tick) {
+                    hits.push(tick);
+                }
+            }
+        }
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_euclidean_patterns() {
+        // Classic 3-over-8
+        let pattern = euclidean(3, 8);
+        assert_eq!(pattern, vec![true, false, false, true, false, false, true, false]);
+
+        // 5-over-8
+        assert_eq!(euclidean(5, 8).iter().filter(|&&b| b).count(), 5);
+
+        // 4-over-16 (four-on-floor)
+        let pattern = euclidean(4, 16);
+        assert_eq!(pattern.iter().filter(|&&b| b).count(), 4);
+
+        // Edge cases
+        assert!(euclidean(0, 8).iter().all(|&b| !b));
+        assert!(euclidean(8, 8).iter().all(|&b| b));
+        assert!(euclidean(3, 0).is_empty());
+    }
+
+    #[test]
+    fn test_gate() {
+        assert_eq!(rotate(&[true, false, false], 1), vec![false, true, false]);
+
+        // 3 in 8 over a 96-tick bar, one step later: hits on steps 1, 4, 7
+        let gate = EuclideanGate::new(3, 8).with_rotation(1);
+        assert_eq!(gate.hits_between(0, 96, 96), vec![12, 48, 84]);
+        assert_eq!(gate.hits_between(90, 200, 96), vec![108, 144, 180]);
+        assert!(gate.allows_at(50, 96));
+        assert!(!gate.allows_at(60, 96));
+        assert!(EuclideanGate::default().allows_at(60, 96));
+    }
+
+    #[test]
+    fn test_gate_uneven_bar() {
+        // 7 steps in a 96-tick bar start at 0, 13, 27, 41, 54, 68, 82
+        let gate = EuclideanGate::new(7, 7);
+        assert_eq!(gate.hits_between(0, 96, 96), vec![0, 13, 27, 41, 54, 68, 82]);
+        // Later bars start on the bar line, not where the steps drifted to
+        assert_eq!(gate.hits_between(96 * 10, 96 * 10 + 14, 96), vec![960, 973]);
+
+        // The bar's last ticks belong to the last step
+        let mut gate = EuclideanGate::new(1, 7);
+        gate.set_rotation(6);
+        assert_eq!(gate.pattern(), &[false, false, false, false, false, false, true]);
+        assert!(gate.allows_at(95, 96) && gate.allows_at(82, 96));
+        assert!(!gate.allows_at(81, 96) && !gate.allows_at(96, 96));
+    }
+}
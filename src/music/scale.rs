@@ -125,6 +125,29 @@ impl fmt::Display for Note {
     }
 }
 
+/// Parse a MIDI note from a name with octave ("E1", "C#4", "Bb-1") or a
+/// number ("40"). Octaves use the MIDI convention (middle C = C4 = 60).
+pub fn parse_midi_note(s: &str) -> Option<MidiNote> {
+    let s = s.trim();
+    if let Ok(number) = s.parse::<u8>() {
+        return (number <= 127).then_some(number);
+    }
+
+    let split = s
+        .char_indices()
+        .skip(1)
+        .find(|&(_, c)| c.is_ascii_digit() || c == '-')
+        .map(|(i, _)| i)?;
+    let (name, octave) = s.split_at(split);
+    let note = Note::from_str(name)?;
+    let octave: i16 = octave.parse().ok()?;
+
+    // Cb belongs to the octave below its letter
+    let pitch = note.pitch_class() as i16 - if name.eq_ignore_ascii_case("cb") { 12 } else { 0 };
+    let midi = (octave + 1) * 12 + pitch;
+    (0..=127).contains(&midi).then_some(midi as MidiNote)
+}
+
 /// Scale types supported by the system
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -384,7 +407,6 @@ impl Scale {
 
         let pitch_class = midi_note % 12;
         let note = Note::from_pitch_class(pitch_class);
-        let octave = (midi_note / 12) as i32 - 1;
 
         // Find current position in scale (or nearest)
         let current_degree = self
@@ -393,16 +415,23 @@ impl Scale {
             .position(|&n| n == note)
             .unwrap_or_else(|| self.nearest_degree(note));
 
-        // Calculate new position
+        // Snap onto the current degree, the short way round
+        let mut snap = (self.notes[current_degree].pitch_class() as i32 - pitch_class as i32).rem_euclid(12);
+        if snap > 6 {
+            snap -= 12;
+        }
+
+        // Calculate new position; octaves wrap at the scale root
         let scale_len = self.len() as i32;
         let new_pos = current_degree as i32 + degrees;
         let new_degree = new_pos.rem_euclid(scale_len) as usize;
         let octave_change = new_pos.div_euclid(scale_len);
 
-        // Get the new note
-        let new_note = self.notes[new_degree];
-        let new_octave = octave + octave_change;
-        let result = ((new_octave + 1) * 12 + new_note.pitch_class() as i32) as i32;
+        // Move by the interval between the degrees
+        let root = self.root.pitch_class() as i32;
+        let above_root = |degree: usize| (self.notes[degree].pitch_class() as i32 - root).rem_euclid(12);
+        let interval = above_root(new_degree) - above_root(current_degree) + octave_change * 12;
+        let result = midi_note as i32 + snap + interval;
 
         result.clamp(0, 127) as MidiNote
     }
@@ -642,6 +671,20 @@ mod tests {
         assert_eq!(Note::from_str("X"), None);
     }
 
+    #[test]
+    fn test_parse_midi_note() {
+        assert_eq!(parse_midi_note("C4"), Some(60));
+        assert_eq!(parse_midi_note("E1"), Some(28));
+        assert_eq!(parse_midi_note("f#3"), Some(54));
+        assert_eq!(parse_midi_note("C-1"), Some(0));
+        assert_eq!(parse_midi_note("Cb4"), Some(59));
+        assert_eq!(parse_midi_note("40"), Some(40));
+        assert_eq!(parse_midi_note("G9"), Some(127));
+        assert_eq!(parse_midi_note("A9"), None);
+        assert_eq!(parse_midi_note("H2"), None);
+        assert_eq!(parse_midi_note("E"), None);
+    }
+
     #[test]
     fn test_note_transpose() {
         assert_eq!(Note::C.transpose(2), Note::D);
@@ -741,6 +784,9 @@ mod tests {
         // D4 (62) up 3 scale degrees should be G4 (67)
         // D minor: D, E, F, G, A, Bb, C
         assert_eq!(d_minor.transpose_in_scale(62, 3), 67);
+        // Octaves wrap at the root, not at C
+        assert_eq!(d_minor.transpose_in_scale(72, 1), 74);
+        assert_eq!(d_minor.transpose_in_scale(74, -1), 72);
     }
 
     #[test]
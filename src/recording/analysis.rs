@@ -0,0 +1,466 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Standard MIDI file analysis.
+//!
+//! A MIDI file is read into its notes and boiled down to the things a
+//! generator can imitate: tempo and meter, a guess at the key, how busy the
+//! material is, its velocities and register, how far the melody moves from
+//! note to note, and how often the drums hit. From that come suggested
+//! settings for the melody and drum generators, meant as a starting point
+//! to tweak rather than a copy of the source.
+//!
+//! The key is guessed by matching the time spent on each pitch class
+//! against the Krumhansl-Kessler major and minor profiles. Melodic
+//! intervals follow the top note of each onset on every pitched channel.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::generators::drums::gm_drums;
+use crate::midi::gm::DRUM_CHANNEL;
+use crate::music::{Key, Note, ScaleType};
+
+/// Krumhansl-Kessler key profiles, from the tonic up
+const MAJOR_PROFILE: [f64; 12] = [6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88];
+const MINOR_PROFILE: [f64; 12] = [6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17];
+
+/// Share of melodic moves the suggested largest leap covers
+const LEAP_COVERAGE: f64 = 0.95;
+
+/// A note read from a MIDI file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileNote {
+    /// Start tick
+    pub tick: u64,
+    /// MIDI channel (0-15)
+    pub channel: u8,
+    /// Note number
+    pub note: u8,
+    /// Velocity (1-127)
+    pub velocity: u8,
+    /// Length in ticks
+    pub duration: u64,
+}
+
+/// The notes and timing of a MIDI file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MidiFileContents {
+    /// Ticks per quarter note
+    pub ppqn: u16,
+    /// First tempo in BPM, if the file sets one
+    pub tempo: Option<f64>,
+    /// First time signature, if the file sets one
+    pub time_signature: Option<(u8, u8)>,
+    /// Notes from every track, by start tick
+    pub notes: Vec<FileNote>,
+}
+
+impl MidiFileContents {
+    /// Read a MIDI file from disk
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("Failed to read MIDI file: {}", path.display()))?;
+        Self::parse(&bytes).with_context(|| format!("Failed to parse MIDI file: {}", path.display()))
+    }
+
+    /// Parse a format 0 or 1 MIDI file
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 14 || &bytes[0..4] != b"MThd" {
+            return Err(anyhow!("not a standard MIDI file"));
+        }
+        let header_length = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
+        let division = u16::from_be_bytes([bytes[12], bytes[13]]);
+        if division & 0x8000 != 0 {
+            return Err(anyhow!("SMPTE time division is not supported"));
+        }
+
+        let mut contents = MidiFileContents {
+            ppqn: division.max(1),
+            ..Default::default()
+        };
+        let mut pos = 8 + header_length;
+        while pos + 8 <= bytes.len() {
+            let length = u32::from_be_bytes([bytes[pos + 4], bytes[pos + 5], bytes[pos + 6], bytes[pos + 7]]) as usize;
+            let end = (pos + 8).saturating_add(length).min(bytes.len());
+            if &bytes[pos..pos + 4] == b"MTrk" {
+                contents.read_track(&bytes[pos + 8..end])?;
+            }
+            pos = end;
+        }
+        contents.notes.sort_by_key(|n| (n.tick, n.channel, n.note));
+        Ok(contents)
+    }
+
+    /// Read the events of one track chunk
+    fn read_track(&mut self, data: &[u8]) -> Result<()> {
+        let truncated = || anyhow!("truncated track");
+        let mut held: HashMap<(u8, u8), (u64, u8)> = HashMap::new();
+        let (mut pos, mut tick, mut running) = (0, 0u64, None);
+
+        while pos < data.len() {
+            tick += read_variable_length(data, &mut pos).ok_or_else(truncated)? as u64;
+            let mut status = *data.get(pos).ok_or_else(truncated)?;
+            if status & 0x80 != 0 {
+                pos += 1;
+            } else {
+                status = running.ok_or_else(|| anyhow!("data byte without a status"))?;
+            }
+
+            match status {
+                0xFF => {
+                    let kind = *data.get(pos).ok_or_else(truncated)?;
+                    pos += 1;
+                    let length = read_variable_length(data, &mut pos).ok_or_else(truncated)? as usize;
+                    let body = data.get(pos..pos + length).ok_or_else(truncated)?;
+                    pos += length;
+                    match (kind, body) {
+                        (0x2F, _) => break,
+                        (0x51, &[a, b, c]) if self.tempo.is_none() => {
+                            let micros = u32::from_be_bytes([0, a, b, c]).max(1);
+                            self.tempo = Some(60_000_000.0 / micros as f64);
+                        }
+                        (0x58, &[numerator, power, ..]) if self.time_signature.is_none() => {
+                            self.time_signature = Some((numerator.max(1), 1u8 << power.min(6)));
+                        }
+                        _ => {}
+                    }
+                    running = None;
+                }
+                0xF0 | 0xF7 => {
+                    let length = read_variable_length(data, &mut pos).ok_or_else(truncated)? as usize;
+                    pos += length;
+                    running = None;
+                }
+                0x80..=0xEF => {
+                    running = Some(status);
+                    let size = if matches!(status & 0xF0, 0xC0 | 0xD0) { 1 } else { 2 };
+                    let body = data.get(pos..pos + size).ok_or_else(truncated)?;
+                    pos += size;
+                    let channel = status & 0x0F;
+                    match (status & 0xF0, body) {
+                        (0x90, &[note, velocity]) if velocity > 0 => {
+                            if let Some(start) = held.insert((channel, note), (tick, velocity)) {
+                                self.push_note(channel, note, start, tick);
+                            }
+                        }
+                        (0x80 | 0x90, &[note, _]) => {
+                            if let Some(start) = held.remove(&(channel, note)) {
+                                self.push_note(channel, note, start, tick);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => return Err(anyhow!("unexpected status byte {:#04x}", status)),
+            }
+        }
+
+        // Notes never released end with the track
+        for ((channel, note), start) in held {
+            self.push_note(channel, note, start, tick);
+        }
+        Ok(())
+    }
+
+    fn push_note(&mut self, channel: u8, note: u8, (start, velocity): (u64, u8), end: u64) {
+        self.notes.push(FileNote {
+            tick: start,
+            channel,
+            note,
+            velocity,
+            duration: end.saturating_sub(start),
+        });
+    }
+}
+
+/// Read a variable-length quantity, advancing the position
+fn read_variable_length(data: &[u8], pos: &mut usize) -> Option<u32> {
+    let mut value = 0u32;
+    for _ in 0..4 {
+        let byte = *data.get(*pos)?;
+        *pos += 1;
+        value = (value << 7) | (byte & 0x7F) as u32;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Average drum hits per bar
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DrumDensity {
+    /// Kick drum hits
+    pub kick: f64,
+    /// Snare, clap and rim hits
+    pub snare: f64,
+    /// Closed, pedal and open hat hits
+    pub hat: f64,
+}
+
+/// Settings proposed for one generator
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorSuggestion {
+    /// Generator name
+    pub generator: &'static str,
+    /// Parameter values
+    pub params: Vec<(&'static str, f64)>,
+}
+
+/// What a MIDI file sounds like, in numbers
+#[derive(Debug, Clone, PartialEq)]
+pub struct MidiAnalysis {
+    /// Tempo in BPM (120 when the file sets none)
+    pub tempo: f64,
+    /// Time signature (4/4 when the file sets none)
+    pub time_signature: (u8, u8),
+    /// Length in bars
+    pub bars: f64,
+    /// Best matching major or minor key, if there are pitched notes
+    pub key: Option<Key>,
+    /// Pitched notes per beat
+    pub density: f64,
+    /// Lowest and highest pitched note
+    pub register: Option<(u8, u8)>,
+    /// Softest and loudest velocity
+    pub velocity_range: Option<(u8, u8)>,
+    /// Average velocity
+    pub average_velocity: Option<u8>,
+    /// Melodic moves by size in semitones (the last counts an octave or more)
+    pub intervals: [usize; 13],
+    /// Drum hits per bar, if the file has drums on channel 10
+    pub drums: Option<DrumDensity>,
+}
+
+impl MidiAnalysis {
+    /// Read and analyze a MIDI file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Ok(Self::analyze(&MidiFileContents::load(path)?))
+    }
+
+    /// Analyze the notes of a MIDI file
+    pub fn analyze(contents: &MidiFileContents) -> Self {
+        let ppqn = contents.ppqn.max(1) as u64;
+        let time_signature = contents.time_signature.unwrap_or((4, 4));
+        let ticks_per_bar = (ppqn * 4 * time_signature.0 as u64 / time_signature.1.max(1) as u64).max(1);
+        let length = contents.notes.iter().map(|n| n.tick + n.duration).max().unwrap_or(0).max(ppqn);
+        let bars = length as f64 / ticks_per_bar as f64;
+
+        let (drums, pitched): (Vec<&FileNote>, Vec<&FileNote>) =
+            contents.notes.iter().partition(|n| n.channel == DRUM_CHANNEL);
+        let velocities = contents.notes.iter().map(|n| n.velocity);
+        let velocity_range = velocities.clone().min().zip(velocities.clone().max());
+        let average_velocity = (!contents.notes.is_empty())
+            .then(|| (velocities.map(|v| v as usize).sum::<usize>() / contents.notes.len()) as u8);
+
+        let drums = (!drums.is_empty()).then(|| {
+            let per_bar = |voices: &[u8]| drums.iter().filter(|n| voices.contains(&n.note)).count() as f64 / bars;
+            DrumDensity {
+                kick: per_bar(&[35, gm_drums::KICK]),
+                snare: per_bar(&[gm_drums::RIM, gm_drums::SNARE, gm_drums::CLAP, 40]),
+                hat: per_bar(&[gm_drums::CLOSED_HAT, 44, gm_drums::OPEN_HAT]),
+            }
+        });
+
+        Self {
+            tempo: contents.tempo.unwrap_or(120.0),
+            time_signature,
+            bars,
+            key: guess_key(&pitched),
+            density: pitched.len() as f64 / (length as f64 / ppqn as f64),
+            register: pitched.iter().map(|n| n.note).min().zip(pitched.iter().map(|n| n.note).max()),
+            velocity_range,
+            average_velocity,
+            intervals: melodic_intervals(&pitched),
+            drums,
+        }
+    }
+
+    /// Total melodic moves
+    pub fn moves(&self) -> usize {
+        self.intervals.iter().sum()
+    }
+
+    /// Share of melodic moves within a range of sizes in semitones
+    pub fn interval_share(&self, low: usize, high: usize) -> f64 {
+        let count: usize = self.intervals[low.min(12)..=high.min(12)].iter().sum();
+        count as f64 / self.moves().max(1) as f64
+    }
+
+    /// Propose generator settings that imitate the file
+    pub fn suggestions(&self) -> Vec<GeneratorSuggestion> {
+        let mut suggestions = Vec::new();
+        if let Some((low, high)) = self.register {
+            let mut params = Vec::new();
+            if self.moves() > 0 {
+                let moving = (self.moves() - self.intervals[0]).max(1);
+                let step = (self.intervals[1] + self.intervals[2]) as f64 / moving as f64;
+                params.push(("step_probability", round(step)));
+                params.push(("repeat_probability", round(self.interval_share(0, 0))));
+                params.push(("max_jump", self.largest_leap_degrees() as f64));
+            }
+
+            // The slowest division that fits the notes, with rests for the gaps
+            let rate = [4.0, 8.0, 16.0].into_iter().find(|r| r / 4.0 >= self.density).unwrap_or(16.0);
+            params.push(("base_rate", rate));
+            params.push(("rest_probability", round((1.0 - self.density / (rate / 4.0)).clamp(0.0, 0.5))));
+            params.push(("base_octave", (low as f64 / 12.0 - 1.0).floor().clamp(1.0, 7.0)));
+            params.push(("octave_range", ((high - low) as f64 / 12.0).ceil().clamp(1.0, 4.0)));
+            if let (Some(average), Some((soft, loud))) = (self.average_velocity, self.velocity_range) {
+                params.push(("velocity", average as f64));
+                params.push(("velocity_variation", ((loud - soft) as f64 / 2.0).round().min(64.0)));
+            }
+            suggestions.push(GeneratorSuggestion { generator: "melody", params });
+        }
+        if let Some(drums) = self.drums {
+            let hits = |per_bar: f64| per_bar.round().clamp(1.0, 16.0);
+            suggestions.push(GeneratorSuggestion {
+                generator: "drums",
+                params: vec![
+                    ("kick_euclidean_hits", hits(drums.kick)),
+                    ("snare_euclidean_hits", hits(drums.snare)),
+                    ("hat_euclidean_hits", hits(drums.hat)),
+                ],
+            });
+        }
+        suggestions
+    }
+
+    /// Smallest leap, in scale degrees, covering most melodic moves
+    fn largest_leap_degrees(&self) -> u8 {
+        let total = self.moves().max(1) as f64;
+        let mut covered = 0;
+        let semitones = (0..=12)
+            .find(|&size| {
+                covered += self.intervals[size];
+                covered as f64 / total >= LEAP_COVERAGE
+            })
+            .unwrap_or(12);
+        ((semitones * 7 + 6) / 12).clamp(1, 7) as u8
+    }
+}
+
+/// Round to two decimals
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Major or minor key whose profile best matches the time on each pitch class
+fn guess_key(notes: &[&FileNote]) -> Option<Key> {
+    let mut weights = [0.0; 12];
+    for note in notes {
+        weights[(note.note % 12) as usize] += note.duration.max(1) as f64;
+    }
+    if weights.iter().all(|&w| w == 0.0) {
+        return None;
+    }
+
+    let mut best: Option<(f64, u8, ScaleType)> = None;
+    for root in 0..12u8 {
+        for (profile, scale) in [(&MAJOR_PROFILE, ScaleType::Major), (&MINOR_PROFILE, ScaleType::NaturalMinor)] {
+            let rotated: Vec<f64> = (0..12).map(|pc| profile[(pc + 12 - root as usize) % 12]).collect();
+            let score = correlation(&weights, &rotated);
+            if best.map_or(true, |(b, _, _)| score > b) {
+                best = Some((score, root, scale));
+            }
+        }
+    }
+    best.map(|(_, root, scale)| Key::new(Note::from_pitch_class(root), scale))
+}
+
+/// Pearson correlation of two equal-length series
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let mean = |s: &[f64]| s.iter().sum::<f64>() / s.len() as f64;
+    let (mean_a, mean_b) = (mean(a), mean(b));
+    let covariance: f64 = a.iter().zip(b).map(|(x, y)| (x - mean_a) * (y - mean_b)).sum();
+    let spread = |s: &[f64], m: f64| s.iter().map(|x| (x - m).powi(2)).sum::<f64>().sqrt();
+    covariance / (spread(a, mean_a) * spread(b, mean_b)).max(f64::EPSILON)
+}
+
+/// Count the moves of the top line on each pitched channel
+fn melodic_intervals(notes: &[&FileNote]) -> [usize; 13] {
+    let mut tops: HashMap<u8, Vec<(u64, u8)>> = HashMap::new();
+    for note in notes {
+        let line = tops.entry(note.channel).or_default();
+        match line.last_mut() {
+            Some((tick, top)) if *tick == note.tick => *top = (*top).max(note.note),
+            _ => line.push((note.tick, note.note)),
+        }
+    }
+
+    let mut intervals = [0; 13];
+    for line in tops.values() {
+        for pair in line.windows(2) {
+            intervals[(pair[0].1.abs_diff(pair[1].1) as usize).min(12)] += 1;
+        }
+    }
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::export::{ExportNote, ExportTrack, MidiExporter};
+
+    fn exported(tracks: Vec<ExportTrack>) -> Vec<u8> {
+        let mut exporter = MidiExporter::new();
+        exporter.set_ppqn(24);
+        exporter.set_tempo(96.0);
+        exporter.set_time_signature(3, 4);
+        for track in tracks {
+            exporter.add_track(track);
+        }
+        exporter.export_to_bytes()
+    }
+
+    #[test]
+    fn test_read_exported_file() {
+        let mut lead = ExportTrack::new("Lead", 2);
+        lead.add_note(ExportNote::new(0, 60, 90, 24));
+        lead.add_note(ExportNote::new(24, 64, 110, 48));
+        let contents = MidiFileContents::parse(&exported(vec![lead])).unwrap();
+
+        assert_eq!(contents.tempo.map(f64::round), Some(96.0));
+        assert_eq!(contents.time_signature, Some((3, 4)));
+        assert_eq!(contents.ppqn, 24);
+        assert_eq!(contents.notes.len(), 2);
+        let note = contents.notes[1];
+        assert_eq!((note.tick, note.note, note.velocity, note.duration), (24, 64, 110, 48));
+        assert!(MidiFileContents::parse(b"RIFF....").is_err());
+    }
+
+    #[test]
+    fn test_analysis_suggests_generators() {
+        // Two bars of 3/4: a stepwise A minor line in eighths over kick and hat
+        let mut lead = ExportTrack::new("Lead", 0);
+        for (i, &note) in [57, 59, 60, 62, 64, 62, 60, 59, 57, 57, 60, 64].iter().enumerate() {
+            lead.add_note(ExportNote::new(i as u64 * 12, note, 80 + (i as u8 % 3) * 10, 12));
+        }
+        let mut drums = ExportTrack::new("Drums", DRUM_CHANNEL);
+        for beat in 0..3 {
+            drums.add_note(ExportNote::new(beat * 24, gm_drums::KICK, 100, 6));
+            drums.add_note(ExportNote::new(beat * 24 + 12, gm_drums::CLOSED_HAT, 70, 6));
+        }
+        let contents = MidiFileContents::parse(&exported(vec![lead, drums])).unwrap();
+        let analysis = MidiAnalysis::analyze(&contents);
+
+        assert_eq!(analysis.key, Some(Key::new(Note::A, ScaleType::NaturalMinor)));
+        assert_eq!(analysis.time_signature, (3, 4));
+        assert_eq!(analysis.register, Some((57, 64)));
+        assert_eq!(analysis.moves(), 11);
+        assert_eq!(analysis.intervals[0], 1);
+        assert!((analysis.density - 2.0).abs() < 1e-9);
+
+        let suggestions = analysis.suggestions();
+        let melody = &suggestions[0];
+        assert_eq!(melody.generator, "melody");
+        assert!(melody.params.contains(&("base_rate", 8.0)));
+        assert!(melody.params.contains(&("base_octave", 3.0)));
+        let drums = &suggestions[1].params;
+        assert_eq!(drums[0], ("kick_euclidean_hits", 2.0));
+    }
+}
@@ -4,10 +4,14 @@
 //! MIDI recording to clips.
 //!
 //! Provides real-time MIDI input capture with quantization,
-//! overdub, and punch in/out support.
+//! overdub, and punch in/out support. Each track's recorder can take its
+//! input from one MIDI source and channel, so several performers can record
+//! onto different tracks at once.
 
 use std::collections::HashMap;
 
+use crate::timing::Grid;
+
 /// Recording mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RecordMode {
@@ -46,6 +50,53 @@ impl Default for RecordingState {
     }
 }
 
+/// How the clip length is chosen on record stop when no loop length is set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthRounding {
+    /// Keep the raw recorded length
+    Off,
+    /// Round to the nearest whole bar
+    NearestBar,
+    /// Round to the nearest power-of-two number of bars (1, 2, 4, 8...)
+    PowerOfTwoBars,
+}
+
+impl Default for LengthRounding {
+    fn default() -> Self {
+        LengthRounding::NearestBar
+    }
+}
+
+impl LengthRounding {
+    /// Parse from a config string ("off", "bar", "pow2")
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "off" | "none" => Some(LengthRounding::Off),
+            "bar" | "bars" | "nearest_bar" => Some(LengthRounding::NearestBar),
+            "pow2" | "power_of_two" | "power_of_two_bars" => Some(LengthRounding::PowerOfTwoBars),
+            _ => None,
+        }
+    }
+
+    /// Round a length in ticks to this rule (never shorter than one bar)
+    pub fn round(&self, ticks: u64, ticks_per_bar: u64) -> u64 {
+        if ticks_per_bar == 0 {
+            return ticks;
+        }
+        let bars = (ticks + ticks_per_bar / 2) / ticks_per_bar;
+        match self {
+            LengthRounding::Off => ticks,
+            LengthRounding::NearestBar => bars.max(1) * ticks_per_bar,
+            LengthRounding::PowerOfTwoBars => {
+                // Round on a log scale so 3 bars becomes 4 and 5 bars becomes 4
+                let exact = ticks as f64 / ticks_per_bar as f64;
+                let power = exact.max(1.0).log2().round().min(16.0) as u32;
+                (1u64 << power) * ticks_per_bar
+            }
+        }
+    }
+}
+
 /// Quantization settings for recording
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct QuantizeSettings {
@@ -95,6 +146,29 @@ impl QuantizeSettings {
         }
     }
 
+    /// Create with eighth note triplet grid
+    pub fn eighth_triplet(ppqn: u32) -> Self {
+        Self::from_grid(Grid::triplet(8), ppqn)
+    }
+
+    /// Create with sixteenth note triplet grid
+    pub fn sixteenth_triplet(ppqn: u32) -> Self {
+        Self::from_grid(Grid::triplet(16), ppqn)
+    }
+
+    /// Create with dotted eighth grid
+    pub fn dotted_eighth(ppqn: u32) -> Self {
+        Self::from_grid(Grid::dotted(8), ppqn)
+    }
+
+    /// Create with any note-value grid ("1/8T", "1/8.", ...)
+    pub fn from_grid(grid: Grid, ppqn: u32) -> Self {
+        Self {
+            grid: grid.ticks(ppqn),
+            ..Default::default()
+        }
+    }
+
     /// Quantize a tick value
     pub fn quantize(&self, tick: u64) -> u64 {
         if self.grid == 0 || self.strength == 0.0 {
@@ -179,6 +253,31 @@ impl PunchRegion {
     }
 }
 
+/// The MIDI source and channel feeding a track's recording and thru
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordInput {
+    /// Source name (case-insensitive substring, None = any source)
+    pub source: Option<String>,
+    /// Channel (0-15, None = any channel)
+    pub channel: Option<u8>,
+}
+
+impl RecordInput {
+    /// Input from a source and channel
+    pub fn new(source: Option<String>, channel: Option<u8>) -> Self {
+        Self { source, channel }
+    }
+
+    /// Check if a message from a source and channel belongs to this input
+    pub fn accepts(&self, source: &str, channel: u8) -> bool {
+        let source_ok = self
+            .source
+            .as_ref()
+            .map_or(true, |s| source.to_lowercase().contains(&s.to_lowercase()));
+        source_ok && self.channel.map_or(true, |c| c == channel)
+    }
+}
+
 /// MIDI recorder for capturing input to clips
 pub struct MidiRecorder {
     /// Current state
@@ -211,6 +310,10 @@ pub struct MidiRecorder {
     metronome: bool,
     /// Input channel filter (None = all channels)
     channel_filter: Option<u8>,
+    /// Input source filter (None = all sources)
+    source_filter: Option<String>,
+    /// Length rounding applied on stop when no loop length is set
+    length_rounding: LengthRounding,
 }
 
 impl MidiRecorder {
@@ -232,6 +335,8 @@ impl MidiRecorder {
             beats_per_bar: 4,
             metronome: true,
             channel_filter: None,
+            source_filter: None,
+            length_rounding: LengthRounding::default(),
         }
     }
 
@@ -286,6 +391,16 @@ impl MidiRecorder {
         self.loop_length
     }
 
+    /// Set length rounding rule
+    pub fn set_length_rounding(&mut self, rounding: LengthRounding) {
+        self.length_rounding = rounding;
+    }
+
+    /// Get length rounding rule
+    pub fn length_rounding(&self) -> LengthRounding {
+        self.length_rounding
+    }
+
     /// Set punch region
     pub fn set_punch_region(&mut self, region: Option<PunchRegion>) {
         self.punch_region = region;
@@ -326,6 +441,31 @@ impl MidiRecorder {
         self.channel_filter
     }
 
+    /// Set the source and channel this recorder listens to
+    pub fn set_input(&mut self, input: RecordInput) {
+        self.source_filter = input.source;
+        self.channel_filter = input.channel;
+    }
+
+    /// Get the source and channel this recorder listens to
+    pub fn input(&self) -> RecordInput {
+        RecordInput::new(self.source_filter.clone(), self.channel_filter)
+    }
+
+    /// Record note on from a named source
+    pub fn note_on_from(&mut self, source: &str, channel: u8, note: u8, velocity: u8) {
+        if self.input().accepts(source, channel) {
+            self.note_on(channel, note, velocity);
+        }
+    }
+
+    /// Record note off from a named source
+    pub fn note_off_from(&mut self, source: &str, channel: u8, note: u8) {
+        if self.input().accepts(source, channel) {
+            self.note_off(channel, note);
+        }
+    }
+
     /// Arm recording
     pub fn arm(&mut self) {
         if self.state == RecordingState::Idle {
@@ -377,9 +517,47 @@ impl MidiRecorder {
             }
         }
 
+        // A fresh take with no preset length decides its own loop length
+        if self.mode == RecordMode::Replace
+            && self.loop_length == 0
+            && self.length_rounding != LengthRounding::Off
+            && !self.notes.is_empty()
+        {
+            self.detect_length();
+        }
+
         self.state = RecordingState::Idle;
     }
 
+    /// Detect the intended clip length from the take and start looping it.
+    ///
+    /// Whole empty bars before the first note are dropped, the remaining
+    /// length is rounded by the length rounding rule, and notes are trimmed
+    /// to fit. Returns the detected length in ticks.
+    pub fn detect_length(&mut self) -> u64 {
+        let ticks_per_bar = self.ppqn as u64 * self.beats_per_bar as u64;
+        let first = self.notes.iter().map(|n| n.start_tick).min().unwrap_or(0);
+        let lead_in = if ticks_per_bar > 0 {
+            (first / ticks_per_bar) * ticks_per_bar
+        } else {
+            0
+        };
+
+        let raw = self.duration().max(self.notes.iter().map(|n| n.end_tick()).max().unwrap_or(0));
+        let length = self.length_rounding.round(raw.saturating_sub(lead_in), ticks_per_bar);
+
+        for note in &mut self.notes {
+            note.start_tick -= lead_in;
+        }
+        self.notes.retain(|n| n.start_tick < length);
+        for note in &mut self.notes {
+            note.duration = note.duration.min(length - note.start_tick);
+        }
+
+        self.loop_length = length;
+        length
+    }
+
     /// Pause recording
     pub fn pause(&mut self) {
         if self.state == RecordingState::Recording {
@@ -645,6 +823,18 @@ mod tests {
         assert_eq!(quantize.quantize(10), 12);
     }
 
+    #[test]
+    fn test_quantize_triplet_and_dotted() {
+        let triplet = QuantizeSettings::eighth_triplet(96);
+        assert_eq!(triplet.grid, 32);
+        assert_eq!(triplet.quantize(50), 64);
+        assert_eq!(QuantizeSettings::sixteenth_triplet(24).quantize(7), 8);
+
+        let dotted = QuantizeSettings::dotted_eighth(24);
+        assert_eq!(dotted.grid, 18);
+        assert_eq!(dotted.quantize(30), 36);
+    }
+
     #[test]
     fn test_quantize_strength() {
         let quantize = QuantizeSettings {
@@ -767,6 +957,31 @@ mod tests {
         assert_eq!(recorder.notes()[0].note, 60);
     }
 
+    #[test]
+    fn test_record_input_source() {
+        let mut keys = MidiRecorder::new(24);
+        keys.set_input(RecordInput::new(Some("keystep".to_string()), None));
+        let mut pads = MidiRecorder::new(24);
+        pads.set_input(RecordInput::new(Some("MPD".to_string()), Some(9)));
+        keys.start(0);
+        pads.start(0);
+
+        for recorder in [&mut keys, &mut pads] {
+            recorder.note_on_from("Arturia KeyStep 37", 0, 60, 100);
+            recorder.note_on_from("Akai MPD218", 9, 36, 110);
+            recorder.note_on_from("Akai MPD218", 0, 38, 110);
+            recorder.tick(12);
+            recorder.note_off_from("Arturia KeyStep 37", 0, 60);
+            recorder.note_off_from("Akai MPD218", 9, 36);
+            recorder.note_off_from("Akai MPD218", 0, 38);
+            recorder.stop();
+        }
+
+        assert_eq!(keys.notes().iter().map(|n| n.note).collect::<Vec<_>>(), vec![60]);
+        assert_eq!(pads.notes().iter().map(|n| n.note).collect::<Vec<_>>(), vec![36]);
+        assert!(RecordInput::default().accepts("Anything", 5));
+    }
+
     #[test]
     fn test_count_in() {
         let mut recorder = MidiRecorder::new(24);
@@ -796,6 +1011,52 @@ mod tests {
         assert_eq!(recorder.position() - recorder.start_position, expected_pos);
     }
 
+    #[test]
+    fn test_length_rounding() {
+        let bar = 96;
+        assert_eq!(LengthRounding::NearestBar.round(150, bar), 192);
+        assert_eq!(LengthRounding::NearestBar.round(130, bar), 96);
+        assert_eq!(LengthRounding::NearestBar.round(10, bar), 96);
+        assert_eq!(LengthRounding::PowerOfTwoBars.round(3 * bar - 10, bar), 4 * bar);
+        assert_eq!(LengthRounding::PowerOfTwoBars.round(5 * bar, bar), 4 * bar);
+        assert_eq!(LengthRounding::PowerOfTwoBars.round(7 * bar, bar), 8 * bar);
+        assert_eq!(LengthRounding::Off.round(150, bar), 150);
+        assert_eq!(LengthRounding::from_str("pow2"), Some(LengthRounding::PowerOfTwoBars));
+        assert_eq!(LengthRounding::from_str("sideways"), None);
+    }
+
+    #[test]
+    fn test_detect_length_on_stop() {
+        let mut recorder = MidiRecorder::new(24);
+        recorder.start(0);
+
+        // An empty bar, then two bars of playing stopped slightly late
+        recorder.tick(96);
+        recorder.note_on(0, 60, 100);
+        recorder.tick(48);
+        recorder.note_off(0, 60);
+        recorder.tick(138);
+        recorder.note_on(0, 62, 100);
+        recorder.tick(10);
+        recorder.stop();
+
+        assert_eq!(recorder.loop_length(), 192);
+        let notes = recorder.notes();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].start_tick, 0);
+        assert_eq!(notes[1].start_tick, 186);
+        assert_eq!(notes[1].end_tick(), 192);
+
+        // A preset loop length is left alone
+        let mut recorder = MidiRecorder::new(24);
+        recorder.set_loop_length(384);
+        recorder.start(0);
+        recorder.note_on(0, 60, 100);
+        recorder.tick(100);
+        recorder.stop();
+        assert_eq!(recorder.loop_length(), 384);
+    }
+
     #[test]
     fn test_punch_region() {
         let region = PunchRegion::new(100, 200);
@@ -3,11 +3,25 @@
 
 //! Standard MIDI file export.
 //!
-//! Exports clips and arrangements as Type 0 or Type 1 MIDI files.
+//! Exports clips and arrangements as Type 0 or Type 1 MIDI files, either
+//! whole or split into one stem file per song section or part. Each export
+//! can keep the humanization and groove that was played (the performance)
+//! or take it out and write the notes as written (the score).
+//!
+//! A whole arrangement can also be rendered: each section's part is applied
+//! to the tracks in song order and the clips and generators are played
+//! through with a fixed seed, so the same song and seed always write the
+//! same file. Section tempo and time signature changes go into the tempo map.
 
-use std::fs::File;
+use std::collections::HashSet;
+use std::fs::{self, File};
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::arrangement::{PartManager, Song, TrackClipState};
+use crate::generators::{Feel, GeneratorContext, GeneratorRegistry};
+use crate::sequencer::track::TrackManager;
+use crate::timing::PositionReadout;
 
 use super::freeze::FrozenNote;
 
@@ -26,6 +40,32 @@ impl Default for MidiFileFormat {
     }
 }
 
+/// Whether exported notes keep their humanization and groove
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFeel {
+    /// Notes as they were played, with humanization and groove
+    Performance,
+    /// Notes as written, with humanization and groove removed
+    Score,
+}
+
+impl ExportFeel {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "performance" | "humanized" | "groove" => Some(ExportFeel::Performance),
+            "score" | "quantized" | "straight" => Some(ExportFeel::Score),
+            _ => None,
+        }
+    }
+}
+
+impl Default for ExportFeel {
+    fn default() -> Self {
+        ExportFeel::Performance
+    }
+}
+
 /// A track for export
 #[derive(Debug, Clone)]
 pub struct ExportTrack {
@@ -37,6 +77,8 @@ pub struct ExportTrack {
     pub notes: Vec<ExportNote>,
     /// Program change at start (None = no change)
     pub program: Option<u8>,
+    /// Feel for this track (None = the exporter's)
+    pub feel: Option<ExportFeel>,
 }
 
 impl ExportTrack {
@@ -47,6 +89,7 @@ impl ExportTrack {
             channel,
             notes: Vec::new(),
             program: None,
+            feel: None,
         }
     }
 
@@ -63,6 +106,7 @@ impl ExportTrack {
                 note: frozen.note,
                 velocity: frozen.velocity,
                 duration: frozen.duration,
+                feel: frozen.feel,
             });
         }
     }
@@ -73,12 +117,106 @@ impl ExportTrack {
         self
     }
 
+    /// Set the feel for this track, overriding the exporter's
+    pub fn with_feel(mut self, feel: ExportFeel) -> Self {
+        self.feel = Some(feel);
+        self
+    }
+
     /// Sort notes by tick
     pub fn sort(&mut self) {
         self.notes.sort_by_key(|n| n.tick);
     }
 }
 
+/// How an arrangement is split into stem files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StemSplit {
+    /// One file per section, in song order
+    Section,
+    /// One file per part (its first appearance in the song)
+    Part,
+}
+
+impl Default for StemSplit {
+    fn default() -> Self {
+        StemSplit::Section
+    }
+}
+
+/// A span of the arrangement written to its own file
+#[derive(Debug, Clone, PartialEq)]
+pub struct StemRegion {
+    /// Stem name (section part name)
+    pub name: String,
+    /// Start tick (inclusive)
+    pub start_tick: u64,
+    /// End tick (exclusive)
+    pub end_tick: u64,
+    /// Tempo for this stem
+    pub tempo: f64,
+    /// Time signature for this stem
+    pub time_signature: (u8, u8),
+}
+
+impl StemRegion {
+    /// Compute stem regions for a song at the given PPQN
+    pub fn from_song(song: &Song, ppqn: u32, split: StemSplit) -> Vec<StemRegion> {
+        let ticks_per_bar = ppqn as u64 * song.default_time_signature().0 as u64;
+        let mut seen = HashSet::new();
+        let mut regions = Vec::new();
+        let mut start_tick = 0;
+
+        for section in song.sections() {
+            let end_tick = start_tick + section.length_bars() as u64 * ticks_per_bar;
+            let first = seen.insert(section.part_name().to_string());
+            if split == StemSplit::Section || first {
+                regions.push(StemRegion {
+                    name: section.part_name().to_string(),
+                    start_tick,
+                    end_tick,
+                    tempo: section.tempo().unwrap_or(song.default_tempo()),
+                    time_signature: section.time_signature(),
+                });
+            }
+            start_tick = end_tick;
+        }
+        regions
+    }
+
+    /// Start and end of the stem in a readout's mode, at the stem's tempo
+    pub fn span_text(&self, readout: &PositionReadout) -> String {
+        let readout = PositionReadout {
+            tempo: self.tempo,
+            beats_per_bar: self.time_signature.0,
+            section_end: Some(self.end_tick),
+            ..readout.clone()
+        };
+        format!("{} - {}", readout.format(self.start_tick), readout.format(self.end_tick))
+    }
+
+    /// File name for the stem at `index` ("02-chorus.mid")
+    pub fn file_name(&self, index: usize) -> String {
+        let name: String = self
+            .name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+            .collect();
+        format!("{:02}-{}.mid", index + 1, name)
+    }
+}
+
+/// A tempo or time signature change partway through an export
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoChange {
+    /// Tick of the change (at the notes' resolution)
+    pub tick: u64,
+    /// Tempo in BPM from here on
+    pub tempo: f64,
+    /// Time signature from here on
+    pub time_signature: (u8, u8),
+}
+
 /// A note for export
 #[derive(Debug, Clone)]
 pub struct ExportNote {
@@ -90,6 +228,8 @@ pub struct ExportNote {
     pub velocity: u8,
     /// Duration in ticks
     pub duration: u64,
+    /// Humanization and groove included in the tick and velocity
+    pub feel: Feel,
 }
 
 impl ExportNote {
@@ -100,6 +240,24 @@ impl ExportNote {
             note,
             velocity,
             duration,
+            feel: Feel::default(),
+        }
+    }
+
+    /// Set the humanization and groove included in the note
+    pub fn with_feel(mut self, feel: Feel) -> Self {
+        self.feel = feel;
+        self
+    }
+
+    /// The note with its feel taken out
+    pub fn score(&self) -> ExportNote {
+        let (tick, velocity) = self.feel.remove(self.tick, self.velocity);
+        ExportNote {
+            tick,
+            velocity,
+            feel: Feel::default(),
+            ..self.clone()
         }
     }
 
@@ -189,12 +347,18 @@ pub struct MidiExporter {
     format: MidiFileFormat,
     /// PPQN (ticks per quarter note)
     ppqn: u16,
+    /// Resolution of the notes added (None = already at `ppqn`)
+    source_ppqn: Option<u32>,
     /// Tempo in BPM
     tempo: f64,
     /// Time signature
     time_sig: (u8, u8),
+    /// Changes after the start, in tick order
+    tempo_map: Vec<TempoChange>,
     /// Tracks to export
     tracks: Vec<ExportTrack>,
+    /// Feel for tracks that don't set their own
+    feel: ExportFeel,
 }
 
 impl MidiExporter {
@@ -203,9 +367,12 @@ impl MidiExporter {
         Self {
             format: MidiFileFormat::Type0,
             ppqn: 480,
+            source_ppqn: None,
             tempo: 120.0,
             time_sig: (4, 4),
+            tempo_map: Vec::new(),
             tracks: Vec::new(),
+            feel: ExportFeel::default(),
         }
     }
 
@@ -229,6 +396,16 @@ impl MidiExporter {
         self.ppqn
     }
 
+    /// Set the resolution of the notes added, so they are rescaled on write
+    pub fn set_source_ppqn(&mut self, ppqn: u32) {
+        self.source_ppqn = Some(ppqn.max(1));
+    }
+
+    /// Get the resolution of the notes added
+    pub fn source_ppqn(&self) -> Option<u32> {
+        self.source_ppqn
+    }
+
     /// Set tempo
     pub fn set_tempo(&mut self, bpm: f64) {
         self.tempo = bpm.clamp(20.0, 300.0);
@@ -249,6 +426,32 @@ impl MidiExporter {
         self.time_sig
     }
 
+    /// Add a tempo and time signature change partway through
+    pub fn add_tempo_change(&mut self, tick: u64, tempo: f64, time_signature: (u8, u8)) {
+        let change = TempoChange {
+            tick,
+            tempo: tempo.clamp(20.0, 300.0),
+            time_signature: (time_signature.0.max(1), time_signature.1.max(1)),
+        };
+        let at = self.tempo_map.partition_point(|c| c.tick <= tick);
+        self.tempo_map.insert(at, change);
+    }
+
+    /// Get the changes after the start
+    pub fn tempo_map(&self) -> &[TempoChange] {
+        &self.tempo_map
+    }
+
+    /// Set whether notes keep their humanization and groove
+    pub fn set_feel(&mut self, feel: ExportFeel) {
+        self.feel = feel;
+    }
+
+    /// Get the feel for tracks that don't set their own
+    pub fn feel(&self) -> ExportFeel {
+        self.feel
+    }
+
     /// Add a track
     pub fn add_track(&mut self, track: ExportTrack) {
         self.tracks.push(track);
@@ -291,8 +494,7 @@ impl MidiExporter {
         let mut events = Vec::new();
 
         // Add tempo and time signature
-        events.push(MidiExportEvent::tempo(0, self.tempo));
-        events.push(MidiExportEvent::time_signature(0, self.time_sig.0, self.time_sig.1));
+        events.extend(self.tempo_events());
 
         // Add all track events
         for track in &self.tracks {
@@ -300,15 +502,15 @@ impl MidiExporter {
                 events.push(MidiExportEvent::program_change(0, track.channel, program));
             }
 
-            for note in &track.notes {
+            for note in &self.out_notes(track) {
                 events.push(MidiExportEvent::note_on(
-                    note.tick,
+                    self.out_tick(note.tick),
                     track.channel,
                     note.note,
                     note.velocity,
                 ));
                 events.push(MidiExportEvent::note_off(
-                    note.end_tick(),
+                    self.out_tick(note.end_tick()),
                     track.channel,
                     note.note,
                 ));
@@ -335,10 +537,8 @@ impl MidiExporter {
         self.write_header(writer, 1, num_tracks as u16)?;
 
         // Write tempo track
-        let mut tempo_events = Vec::new();
-        tempo_events.push(MidiExportEvent::tempo(0, self.tempo));
-        tempo_events.push(MidiExportEvent::time_signature(0, self.time_sig.0, self.time_sig.1));
-        tempo_events.push(MidiExportEvent::track_name(0, "Tempo"));
+        let mut tempo_events = vec![MidiExportEvent::track_name(0, "Tempo")];
+        tempo_events.extend(self.tempo_events());
         self.write_track(writer, &tempo_events)?;
 
         // Write each track
@@ -351,15 +551,15 @@ impl MidiExporter {
                 events.push(MidiExportEvent::program_change(0, track.channel, program));
             }
 
-            for note in &track.notes {
+            for note in &self.out_notes(track) {
                 events.push(MidiExportEvent::note_on(
-                    note.tick,
+                    self.out_tick(note.tick),
                     track.channel,
                     note.note,
                     note.velocity,
                 ));
                 events.push(MidiExportEvent::note_off(
-                    note.end_tick(),
+                    self.out_tick(note.end_tick()),
                     track.channel,
                     note.note,
                 ));
@@ -372,6 +572,29 @@ impl MidiExporter {
         Ok(())
     }
 
+    /// Tempo and time signature events: the starting values, then each
+    /// change that differs from the one before
+    fn tempo_events(&self) -> Vec<MidiExportEvent> {
+        let mut events = vec![
+            MidiExportEvent::tempo(0, self.tempo),
+            MidiExportEvent::time_signature(0, self.time_sig.0, self.time_sig.1),
+        ];
+        let (mut tempo, mut time_sig) = (self.tempo, self.time_sig);
+        for change in &self.tempo_map {
+            let tick = self.out_tick(change.tick);
+            if change.tempo != tempo {
+                events.push(MidiExportEvent::tempo(tick, change.tempo));
+                tempo = change.tempo;
+            }
+            if change.time_signature != time_sig {
+                let (numerator, denominator) = change.time_signature;
+                events.push(MidiExportEvent::time_signature(tick, numerator, denominator));
+                time_sig = change.time_signature;
+            }
+        }
+        events
+    }
+
     /// Write MIDI file header chunk
     fn write_header<W: Write>(&self, writer: &mut W, format: u16, num_tracks: u16) -> io::Result<()> {
         // MThd
@@ -432,6 +655,177 @@ impl MidiExporter {
         writer.write_all(&bytes)
     }
 
+    /// Build an exporter holding only the notes that start in a region,
+    /// shifted to start at zero and cut at the region end
+    pub fn slice(&self, region: &StemRegion) -> MidiExporter {
+        let mut exporter = MidiExporter {
+            format: self.format,
+            ppqn: self.ppqn,
+            source_ppqn: self.source_ppqn,
+            tempo: self.tempo,
+            time_sig: self.time_sig,
+            tempo_map: Vec::new(),
+            tracks: Vec::new(),
+            feel: self.feel,
+        };
+        exporter.set_tempo(region.tempo);
+        exporter.set_time_signature(region.time_signature.0, region.time_signature.1);
+
+        for track in &self.tracks {
+            let mut stem = ExportTrack::new(track.name.clone(), track.channel);
+            stem.program = track.program;
+            stem.feel = track.feel;
+            for note in &track.notes {
+                if note.tick >= region.start_tick && note.tick < region.end_tick {
+                    let duration = note.duration.min(region.end_tick - note.tick);
+                    stem.add_note(
+                        ExportNote::new(note.tick - region.start_tick, note.note, note.velocity, duration)
+                            .with_feel(note.feel),
+                    );
+                }
+            }
+            exporter.add_track(stem);
+        }
+        exporter
+    }
+
+    /// Write one file per region into a directory, returning the paths written
+    pub fn export_stems<P: AsRef<Path>>(&self, regions: &[StemRegion], dir: P) -> io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let mut paths = Vec::new();
+        for (i, region) in regions.iter().enumerate() {
+            let path = dir.join(region.file_name(i));
+            self.slice(region).export(&path)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Render a whole arrangement as a Type 1 file, one export track per
+    /// sequencer track. Each section applies its part (clips, generators
+    /// from `registry`, mutes), then plays through a beat at a time at
+    /// `ppqn`. Tracks and new generators are seeded from `seed`.
+    pub fn render_arrangement(
+        &mut self,
+        song: &Song,
+        parts: &PartManager,
+        tracks: &mut TrackManager,
+        registry: &GeneratorRegistry,
+        seed: u64,
+        ppqn: u32,
+    ) {
+        let ppqn = ppqn.max(1);
+        self.format = MidiFileFormat::Type1;
+        self.set_source_ppqn(ppqn);
+        self.set_tempo(song.default_tempo());
+        let (numerator, denominator) = song.default_time_signature();
+        self.set_time_signature(numerator, denominator);
+        self.tempo_map.clear();
+        self.tracks.clear();
+
+        tracks.reset_all();
+        for (index, track) in tracks.iter_mut().enumerate() {
+            track.set_seed(seed.wrapping_add(index as u64));
+        }
+        let mut exported: Vec<ExportTrack> = tracks
+            .iter()
+            .map(|track| ExportTrack::new(track.name(), track.channel()))
+            .collect();
+
+        let mut context = GeneratorContext {
+            ppqn,
+            ..Default::default()
+        };
+        let mut start_tick = 0;
+        let mut bar = 0;
+        for (section_index, section) in song.sections().iter().enumerate() {
+            let tempo = section.tempo().unwrap_or(song.default_tempo());
+            let time_signature = section.time_signature();
+            if section_index > 0 {
+                self.add_tempo_change(start_tick, tempo, time_signature);
+            }
+            if let Some(part) = parts.get_part(section.part_name()) {
+                for (&index, state) in part.track_states() {
+                    let Some(track) = tracks.track_mut(index) else {
+                        continue;
+                    };
+                    match state {
+                        TrackClipState::Clip(clip) => {
+                            track.set_active_clip(Some(*clip));
+                            if let Some(clip) = track.active_clip_mut() {
+                                clip.reset();
+                                clip.play();
+                            }
+                        }
+                        TrackClipState::Generator(name) => {
+                            if let Some(generator) = registry.create(name) {
+                                track.set_generator(generator);
+                                let section_seed = ((section_index as u64) << 32) | index as u64;
+                                track.set_seed(seed ^ section_seed);
+                            }
+                        }
+                        TrackClipState::Stop | TrackClipState::Empty => {
+                            track.set_active_clip(None);
+                            track.clear_generator();
+                        }
+                        TrackClipState::Hold => {}
+                    }
+                }
+                for index in 0..tracks.track_count() {
+                    if let Some(state) = part.playback_state(index) {
+                        tracks.set_track_state(index, state);
+                    }
+                }
+            }
+
+            context.tempo = tempo;
+            context.beats_per_bar = time_signature.0.max(1);
+            context.ticks_to_generate = ppqn as u64;
+            for section_bar in 0..section.length_bars() as u64 {
+                context.bar = bar + section_bar;
+                for beat in 0..context.beats_per_bar as u64 {
+                    context.beat = beat;
+                    let beat_tick = start_tick + (section_bar * context.beats_per_bar as u64 + beat) * ppqn as u64;
+                    for (index, export) in exported.iter_mut().enumerate() {
+                        if !tracks.should_output(index) {
+                            continue;
+                        }
+                        let Some(track) = tracks.track_mut(index) else {
+                            continue;
+                        };
+                        for event in track.generate(&context) {
+                            if event.velocity > 0 {
+                                export.add_note(
+                                    ExportNote::new(
+                                        beat_tick + event.start_tick,
+                                        event.note,
+                                        event.velocity,
+                                        event.duration_ticks,
+                                    )
+                                    .with_feel(event.feel),
+                                );
+                            }
+                        }
+                        // Only notes are exported
+                        if let Some(generator) = track.generator_mut() {
+                            generator.take_pitch_bends();
+                            generator.take_control_changes();
+                        }
+                    }
+                }
+            }
+            start_tick += section.length_bars() as u64 * context.ticks_per_bar();
+            bar += section.length_bars() as u64;
+        }
+
+        for mut export in exported {
+            export.sort();
+            self.tracks.push(export);
+        }
+    }
+
     /// Scale ticks from source PPQN to export PPQN
     pub fn scale_ticks(&self, tick: u64, source_ppqn: u32) -> u64 {
         if source_ppqn == self.ppqn as u32 {
@@ -440,6 +834,23 @@ impl MidiExporter {
             (tick as f64 * self.ppqn as f64 / source_ppqn as f64) as u64
         }
     }
+
+    /// A track's notes as they are written out, with the feel removed
+    /// when exporting the score
+    fn out_notes(&self, track: &ExportTrack) -> Vec<ExportNote> {
+        match track.feel.unwrap_or(self.feel) {
+            ExportFeel::Performance => track.notes.clone(),
+            ExportFeel::Score => track.notes.iter().map(ExportNote::score).collect(),
+        }
+    }
+
+    /// Convert a note tick to the file resolution
+    fn out_tick(&self, tick: u64) -> u64 {
+        match self.source_ppqn {
+            Some(source) => self.scale_ticks(tick, source),
+            None => tick,
+        }
+    }
 }
 
 impl Default for MidiExporter {
@@ -552,6 +963,19 @@ mod tests {
 
         // Same PPQN
         assert_eq!(exporter.scale_ticks(480, 480), 480);
+
+        // Notes from a 96 PPQN engine are rescaled on write
+        assert_eq!(exporter.out_tick(96), 96);
+        exporter.set_source_ppqn(96);
+        assert_eq!(exporter.out_tick(96), 480);
+        let region = StemRegion {
+            name: "A".to_string(),
+            start_tick: 0,
+            end_tick: 960,
+            tempo: 120.0,
+            time_signature: (4, 4),
+        };
+        assert_eq!(exporter.slice(&region).source_ppqn(), Some(96));
     }
 
     #[test]
@@ -563,6 +987,7 @@ mod tests {
                 velocity: 100,
                 start_tick: 0,
                 duration: 24,
+                feel: Feel::default(),
             },
             FrozenNote {
                 channel: 0,
@@ -570,6 +995,7 @@ mod tests {
                 velocity: 90,
                 start_tick: 24,
                 duration: 24,
+                feel: Feel::default(),
             },
         ];
 
@@ -600,6 +1026,156 @@ mod tests {
         assert_eq!(track.program, Some(48));
     }
 
+    #[test]
+    fn test_stem_regions() {
+        use crate::arrangement::SongSection;
+        use crate::timing::PositionMode;
+
+        let song = Song::new("Stems")
+            .with_section(SongSection::new("Verse", 2))
+            .with_section(SongSection::new("Chorus", 1).with_tempo(128.0))
+            .with_section(SongSection::new("Verse", 2));
+
+        let regions = StemRegion::from_song(&song, 480, StemSplit::Section);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(regions[1].start_tick, 3840);
+        assert_eq!(regions[1].end_tick, 5760);
+        assert_eq!(regions[1].tempo, 128.0);
+        assert_eq!(regions[2].file_name(2), "03-verse.mid");
+
+        let readout = PositionReadout::new(480, 4, 120.0);
+        assert_eq!(regions[1].span_text(&readout), "003:01:00 - 004:01:00");
+        let clock = readout.with_mode(PositionMode::Clock);
+        assert_eq!(regions[1].span_text(&clock), "0:03.7 - 0:05.6");
+
+        let parts = StemRegion::from_song(&song, 480, StemSplit::Part);
+        let names: Vec<&str> = parts.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["Verse", "Chorus"]);
+    }
+
+    #[test]
+    fn test_slice_region() {
+        let mut exporter = MidiExporter::new();
+        let mut track = ExportTrack::new("Bass", 1).with_program(33);
+        track.add_note(ExportNote::new(0, 36, 100, 480));
+        track.add_note(ExportNote::new(1900, 38, 100, 480));
+        track.add_note(ExportNote::new(2000, 40, 100, 480));
+        exporter.add_track(track);
+
+        let region = StemRegion {
+            name: "Chorus".to_string(),
+            start_tick: 1920,
+            end_tick: 2160,
+            tempo: 100.0,
+            time_signature: (3, 4),
+        };
+        let stem = exporter.slice(&region);
+        assert_eq!(stem.tempo(), 100.0);
+        assert_eq!(stem.time_signature(), (3, 4));
+
+        // Only the note starting inside the region, shifted and cut
+        let notes = &stem.tracks()[0].notes;
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].tick, 80);
+        assert_eq!(notes[0].duration, 160);
+        assert_eq!(stem.tracks()[0].program, Some(33));
+    }
+
+    #[test]
+    fn test_score_and_performance_feel() {
+        let mut exporter = MidiExporter::new();
+        assert_eq!(exporter.feel(), ExportFeel::Performance);
+        let swung = ExportNote::new(18, 60, 110, 6).with_feel(Feel { ticks: 6, velocity: 10 });
+
+        let mut drums = ExportTrack::new("Drums", 9);
+        drums.add_note(swung.clone());
+        exporter.add_track(drums);
+        let mut bass = ExportTrack::new("Bass", 1).with_feel(ExportFeel::Performance);
+        bass.add_note(swung);
+        exporter.add_track(bass);
+
+        let played = exporter.out_notes(&exporter.tracks()[0]);
+        assert_eq!((played[0].tick, played[0].velocity), (18, 110));
+
+        // The score takes the feel back out, except on the track that keeps it
+        exporter.set_feel(ExportFeel::from_str("score").unwrap());
+        let written = exporter.out_notes(&exporter.tracks()[0]);
+        assert_eq!((written[0].tick, written[0].velocity, written[0].end_tick()), (12, 100, 18));
+        assert_eq!(exporter.out_notes(&exporter.tracks()[1])[0].tick, 18);
+
+        // Stems keep the feel so they can be written either way
+        let region = StemRegion {
+            name: "A".to_string(),
+            start_tick: 0,
+            end_tick: 96,
+            tempo: 120.0,
+            time_signature: (4, 4),
+        };
+        let stem = exporter.slice(&region);
+        assert_eq!(stem.feel(), ExportFeel::Score);
+        assert_eq!(stem.tracks()[0].notes[0].feel.ticks, 6);
+    }
+
+    #[test]
+    fn test_render_arrangement() {
+        use crate::arrangement::{Part, SongSection};
+        use crate::sequencer::track::TrackConfig;
+        use crate::sequencer::{Clip, ClipNote};
+
+        let song = Song::new("Render")
+            .with_section(SongSection::new("Intro", 1))
+            .with_section(SongSection::new("Verse", 2).with_tempo(90.0).with_time_sig(3, 4));
+        let mut parts = PartManager::new(2);
+        parts.add_part(Part::new("Intro").with_track(0, TrackClipState::Clip(0)));
+        parts.add_part(
+            Part::new("Verse")
+                .with_track(0, TrackClipState::Stop)
+                .with_track(1, TrackClipState::Generator("arpeggio".to_string())),
+        );
+        let registry = GeneratorRegistry::with_builtins();
+
+        let render = |seed: u64| {
+            let mut tracks = TrackManager::new();
+            tracks.add_track(TrackConfig::new("Bass").with_channel(1));
+            tracks.add_track(TrackConfig::new("Arp").with_channel(2));
+            let mut clip = Clip::new("Riff", 48);
+            clip.add_note(ClipNote::new(0, 12, 36, 100));
+            tracks.track_mut(0).unwrap().add_clip(clip);
+
+            let mut exporter = MidiExporter::new();
+            exporter.render_arrangement(&song, &parts, &mut tracks, &registry, seed, 24);
+            exporter
+        };
+        let exporter = render(7);
+        assert_eq!(exporter.format(), MidiFileFormat::Type1);
+        assert_eq!(exporter.tracks().len(), 2);
+
+        // The clip loops through the intro and stops with it
+        let bass = &exporter.tracks()[0];
+        let ticks: Vec<u64> = bass.notes.iter().map(|n| n.tick).collect();
+        assert_eq!(ticks, vec![0, 48]);
+        assert_eq!(bass.channel, 1);
+
+        // The generator plays the verse only, the same way for the same seed
+        let arp = &exporter.tracks()[1];
+        assert!(!arp.notes.is_empty());
+        assert!(arp.notes.iter().all(|n| n.tick >= 96));
+        assert_eq!(exporter.export_to_bytes(), render(7).export_to_bytes());
+
+        // The verse changes tempo and meter where it starts
+        assert_eq!(
+            exporter.tempo_map(),
+            &[TempoChange {
+                tick: 96,
+                tempo: 90.0,
+                time_signature: (3, 4),
+            }]
+        );
+        let tempo = exporter.tempo_events();
+        assert_eq!(tempo.len(), 4);
+        assert!(tempo[2..].iter().all(|e| e.tick == 1920));
+    }
+
     #[test]
     fn test_time_signature() {
         let mut exporter = MidiExporter::new();
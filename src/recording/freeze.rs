@@ -6,7 +6,7 @@
 //! Converts real-time generator output to static clips
 //! that can be saved and edited.
 
-use crate::generators::MidiEvent;
+use crate::generators::{Feel, MidiEvent};
 
 /// Options for freezing
 #[derive(Debug, Clone)]
@@ -88,6 +88,8 @@ pub struct FrozenNote {
     pub start_tick: u64,
     /// Duration in ticks
     pub duration: u64,
+    /// Humanization and groove included in the start and velocity
+    pub feel: Feel,
 }
 
 impl FrozenNote {
@@ -99,6 +101,7 @@ impl FrozenNote {
             velocity: note_on.velocity,
             start_tick: note_on.start_tick,
             duration,
+            feel: note_on.feel,
         }
     }
 
@@ -205,6 +208,7 @@ impl ClipFreezer {
                     },
                     start_tick: active.start_tick,
                     duration,
+                    feel: self.feel(&active.event),
                 });
             }
         }
@@ -260,10 +264,12 @@ impl ClipFreezer {
                             },
                             start_tick: active.start_tick,
                             duration,
+                            feel: self.feel(&active.event),
                         };
 
-                        // Apply quantization
+                        // Apply quantization (a quantized note has no feel left)
                         if let Some(grid) = self.options.quantize_grid {
+                            frozen.feel = Feel::default();
                             frozen.start_tick = self.quantize(frozen.start_tick, grid);
                             let end = self.quantize(frozen.end_tick(), grid);
                             frozen.duration = end.saturating_sub(frozen.start_tick);
@@ -276,6 +282,18 @@ impl ClipFreezer {
         }
     }
 
+    /// Feel kept for a captured note (none on velocity when velocities are dropped)
+    fn feel(&self, event: &MidiEvent) -> Feel {
+        Feel {
+            velocity: if self.options.include_velocity {
+                event.feel.velocity
+            } else {
+                0
+            },
+            ..event.feel
+        }
+    }
+
     /// Quantize a tick value to grid
     fn quantize(&self, tick: u64, grid: u32) -> u64 {
         let grid = grid as u64;
@@ -366,6 +384,7 @@ impl ClipFreezer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generators::NoteExpression;
 
     #[test]
     fn test_freezer_creation() {
@@ -402,6 +421,8 @@ mod tests {
                 note: 60,
                 velocity: 100,
                 duration_ticks: 24,
+                feel: Feel::default(),
+                expression: NoteExpression::default(),
             },
         ];
 
@@ -416,6 +437,8 @@ mod tests {
                 note: 60,
                 velocity: 0,
                 duration_ticks: 0,
+                feel: Feel::default(),
+                expression: NoteExpression::default(),
             },
         ];
         freezer.process_events(&off_events);
@@ -467,6 +490,8 @@ mod tests {
                 note: 60,
                 velocity: 100,
                 duration_ticks: 20,
+                feel: Feel::default(),
+                expression: NoteExpression::default(),
             },
         ];
         freezer.process_events(&events);
@@ -478,6 +503,8 @@ mod tests {
                 note: 60,
                 velocity: 0,
                 duration_ticks: 0,
+                feel: Feel::default(),
+                expression: NoteExpression::default(),
             },
         ];
         freezer.process_events(&off_events);
@@ -495,6 +522,8 @@ mod tests {
             note: 60,
             velocity: 100,
             duration_ticks: 24,
+            feel: Feel::default(),
+            expression: NoteExpression::default(),
         };
 
         let frozen = FrozenNote::from_events(&event, 24);
@@ -518,6 +547,7 @@ mod tests {
                 velocity: 100,
                 start_tick: 0,
                 duration: 20,
+                feel: Feel::default(),
             },
             FrozenNote {
                 channel: 0,
@@ -525,6 +555,7 @@ mod tests {
                 velocity: 100,
                 start_tick: 15, // Overlaps with first
                 duration: 20,
+                feel: Feel::default(),
             },
         ];
 
@@ -563,6 +594,8 @@ mod tests {
                 note: 60,
                 velocity: 100,
                 duration_ticks: 5,
+                feel: Feel::default(),
+                expression: NoteExpression::default(),
             },
         ];
         freezer.process_events(&events);
@@ -574,6 +607,8 @@ mod tests {
                 note: 60,
                 velocity: 0,
                 duration_ticks: 0,
+                feel: Feel::default(),
+                expression: NoteExpression::default(),
             },
         ];
         freezer.process_events(&off_events);
@@ -0,0 +1,377 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Drum pattern import.
+//!
+//! Patterns from other drum machines come in as a list of hits on named
+//! voices, which can become a clip, a MIDI file or the voice patterns of
+//! the drum generator. Two formats are read:
+//!
+//! - Hydrogen songs and patterns (`.h2song`, `.h2pattern`). Instruments map
+//!   to their MIDI out note, or to the General MIDI drum of the same name.
+//! - Step CSV: one line per voice, the voice name first and then either a
+//!   step string (`kick,x...x...x...x...`) or one cell per step
+//!   (`snare,0,0,0,0,100,0,0,0`). In step strings `x` is a hit, `X` an
+//!   accent, `o` a ghost note and `.` or `-` a rest; `|` and spaces are
+//!   ignored so bars can be marked.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::generators::drums::{gm_drums, DrumGenerator, DrumStep, MAX_STEP_OFFSET};
+use crate::sequencer::{Clip, ClipNote};
+
+use super::export::{ExportNote, ExportTrack, MidiExporter, MidiFileFormat};
+
+/// Hydrogen's resolution in ticks per quarter note
+const HYDROGEN_PPQN: u64 = 48;
+
+/// MIDI channel imported patterns are written on (channel 10)
+const DRUM_CHANNEL: u8 = 9;
+
+/// Velocities for step string characters
+const HIT_VELOCITY: u8 = 100;
+const ACCENT_VELOCITY: u8 = 120;
+const GHOST_VELOCITY: u8 = 50;
+
+/// One drum hit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImportedHit {
+    /// Position in ticks from the pattern start
+    pub tick: u64,
+    /// MIDI note
+    pub note: u8,
+    /// Velocity (1-127)
+    pub velocity: u8,
+}
+
+/// A drum pattern read from another program
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedPattern {
+    /// Pattern name
+    pub name: String,
+    /// Length in ticks
+    pub length_ticks: u64,
+    /// Resolution the ticks are in
+    pub ppqn: u32,
+    /// Voices as (name, note), in the order they first appear
+    pub voices: Vec<(String, u8)>,
+    /// Hits sorted by tick
+    pub hits: Vec<ImportedHit>,
+}
+
+impl ImportedPattern {
+    /// Create an empty pattern
+    pub fn new(name: impl Into<String>, length_ticks: u64, ppqn: u32) -> Self {
+        Self {
+            name: name.into(),
+            length_ticks,
+            ppqn,
+            voices: Vec::new(),
+            hits: Vec::new(),
+        }
+    }
+
+    /// Add a hit on a voice
+    pub fn add_hit(&mut self, voice: &str, note: u8, tick: u64, velocity: u8) {
+        if !self.voices.iter().any(|(_, n)| *n == note) {
+            self.voices.push((voice.to_string(), note));
+        }
+        let hit = ImportedHit {
+            tick,
+            note,
+            velocity: velocity.clamp(1, 127),
+        };
+        let index = self.hits.partition_point(|h| h.tick <= tick);
+        self.hits.insert(index, hit);
+    }
+
+    /// Name usable as a file name
+    pub fn file_stem(&self) -> String {
+        let stem: String = self
+            .name
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if stem.is_empty() {
+            "pattern".to_string()
+        } else {
+            stem
+        }
+    }
+
+    /// Length of each hit in a clip: a sixteenth
+    fn hit_length(&self) -> u64 {
+        (self.ppqn as u64 / 4).max(1)
+    }
+
+    /// Convert to a looping clip
+    pub fn to_clip(&self) -> Clip {
+        let mut clip = Clip::new(self.name.clone(), self.length_ticks);
+        clip.add_notes(
+            self.hits
+                .iter()
+                .map(|hit| ClipNote::new(hit.tick, self.hit_length(), hit.note, hit.velocity)),
+        );
+        clip
+    }
+
+    /// Convert to a MIDI file with one drum track
+    pub fn to_exporter(&self) -> MidiExporter {
+        let mut exporter = MidiExporter::new();
+        exporter.set_format(MidiFileFormat::Type1);
+        exporter.set_source_ppqn(self.ppqn);
+        let mut track = ExportTrack::new(self.name.clone(), DRUM_CHANNEL);
+        for hit in &self.hits {
+            track.add_note(ExportNote::new(hit.tick, hit.note, hit.velocity, self.hit_length()));
+        }
+        exporter.add_track(track);
+        exporter
+    }
+
+    /// Replace the drum generator's voices with the first bar of the
+    /// pattern, quantized to its steps. Hits between steps keep their
+    /// timing as step offsets. Returns the number of voices set.
+    pub fn apply_to_drums(&self, drums: &mut DrumGenerator) -> usize {
+        let steps = drums.steps_per_bar();
+        let step_ticks = (self.ppqn as f64 * 4.0 / steps.max(1) as f64).max(1.0);
+        for (name, note) in &self.voices {
+            let mut lane = vec![DrumStep::default(); steps];
+            for hit in self.hits.iter().filter(|h| h.note == *note) {
+                let position = hit.tick as f64 / step_ticks;
+                let step = position.round() as usize;
+                if step >= steps {
+                    continue;
+                }
+                let offset = ((position - step as f64) * 100.0).round() as i8;
+                lane[step] = DrumStep {
+                    hit: true,
+                    velocity: Some(hit.velocity),
+                    offset: offset.clamp(-MAX_STEP_OFFSET, MAX_STEP_OFFSET),
+                };
+            }
+            drums.set_voice(name, *note, &lane);
+        }
+        self.voices.len()
+    }
+}
+
+/// Read drum patterns from a file, picking the format by extension
+pub fn load_drum_patterns<P: AsRef<Path>>(path: P, ppqn: u32) -> Result<Vec<ImportedPattern>> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read pattern file: {:?}", path))?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    match extension.as_str() {
+        "h2song" | "h2pattern" => parse_hydrogen(&text, ppqn),
+        "csv" | "txt" => {
+            let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("pattern");
+            Ok(vec![parse_step_csv(&text, name, ppqn, 4)?])
+        }
+        _ => Err(anyhow!("Unknown drum pattern format: {:?} (expected .h2song, .h2pattern or .csv)", path)),
+    }
+}
+
+/// Parse the patterns of a Hydrogen song or pattern file
+pub fn parse_hydrogen(xml: &str, ppqn: u32) -> Result<Vec<ImportedPattern>> {
+    // Instrument id -> (voice name, note)
+    let mut instruments = Vec::new();
+    if let Some(list) = elements(xml, "instrumentList").into_iter().next() {
+        for instrument in elements(list, "instrument") {
+            let Some(id) = child(instrument, "id").and_then(|id| id.parse::<i64>().ok()) else {
+                continue;
+            };
+            let name = child(instrument, "name").map(unescape).unwrap_or_else(|| format!("drum {}", id));
+            let note = child(instrument, "midiOutNote")
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|&n| n <= 127)
+                .or_else(|| gm_drums::from_name(&name))
+                .unwrap_or_else(|| (gm_drums::KICK as i64 + id).clamp(0, 127) as u8);
+            instruments.push((id, voice_name(&name), note));
+        }
+    }
+
+    let patterns = match elements(xml, "patternList").into_iter().next() {
+        Some(list) => elements(list, "pattern"),
+        None => elements(xml, "pattern"),
+    };
+    if patterns.is_empty() {
+        return Err(anyhow!("No patterns found in Hydrogen file"));
+    }
+
+    let scale = |tick: u64| tick * ppqn as u64 / HYDROGEN_PPQN;
+    let mut imported = Vec::new();
+    for (index, pattern) in patterns.into_iter().enumerate() {
+        let name = child(pattern, "name").map(unescape).unwrap_or_else(|| format!("pattern {}", index + 1));
+        let size = child(pattern, "size").and_then(|s| s.parse::<u64>().ok()).unwrap_or(HYDROGEN_PPQN * 4);
+        let mut result = ImportedPattern::new(name, scale(size), ppqn);
+        for note in elements(pattern, "note") {
+            let (Some(position), Some(id)) = (
+                child(note, "position").and_then(|p| p.parse::<u64>().ok()),
+                child(note, "instrument").and_then(|i| i.parse::<i64>().ok()),
+            ) else {
+                continue;
+            };
+            let velocity = child(note, "velocity").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.8);
+            let (voice, midi_note) = match instruments.iter().find(|(i, _, _)| *i == id) {
+                Some((_, voice, note)) => (voice.clone(), *note),
+                None => (format!("drum_{}", id), (gm_drums::KICK as i64 + id).clamp(0, 127) as u8),
+            };
+            let velocity = (velocity.clamp(0.0, 1.0) * 127.0).round() as u8;
+            result.add_hit(&voice, midi_note, scale(position), velocity);
+        }
+        imported.push(result);
+    }
+    Ok(imported)
+}
+
+/// Parse a step CSV pattern with `steps_per_beat` steps to the beat
+pub fn parse_step_csv(text: &str, name: &str, ppqn: u32, steps_per_beat: u32) -> Result<ImportedPattern> {
+    let step_ticks = (ppqn / steps_per_beat.max(1)).max(1) as u64;
+    let mut pattern = ImportedPattern::new(name, 0, ppqn);
+    let mut longest = 0usize;
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let voice = fields[0];
+        let note = gm_drums::from_name(voice)
+            .ok_or_else(|| anyhow!("Line {}: unknown drum voice '{}'", number + 1, voice))?;
+
+        // A single step string, or one cell per step
+        let cells: Vec<String> = if fields.len() == 2 && fields[1].parse::<u8>().is_err() {
+            fields[1].chars().filter(|c| !matches!(c, '|' | ' ')).map(String::from).collect()
+        } else {
+            fields[1..].iter().map(|c| c.to_string()).collect()
+        };
+        for (step, cell) in cells.iter().enumerate() {
+            let velocity = step_velocity(cell)
+                .ok_or_else(|| anyhow!("Line {}: bad step '{}' for '{}'", number + 1, cell, voice))?;
+            if let Some(velocity) = velocity {
+                pattern.add_hit(&voice_name(voice), note, step as u64 * step_ticks, velocity);
+            }
+        }
+        longest = longest.max(cells.len());
+    }
+
+    if pattern.voices.is_empty() {
+        return Err(anyhow!("No drum voices in step pattern '{}'", name));
+    }
+    pattern.length_ticks = longest as u64 * step_ticks;
+    Ok(pattern)
+}
+
+/// Velocity of a step cell: Some(None) for a rest, None if unreadable
+fn step_velocity(cell: &str) -> Option<Option<u8>> {
+    match cell {
+        "" | "." | "-" | "0" => Some(None),
+        "x" => Some(Some(HIT_VELOCITY)),
+        "X" => Some(Some(ACCENT_VELOCITY)),
+        "o" | "g" => Some(Some(GHOST_VELOCITY)),
+        _ => cell.parse::<u8>().ok().filter(|&v| v <= 127).map(Some),
+    }
+}
+
+/// Voice name from an instrument name ("Open Hat" -> "open_hat")
+fn voice_name(name: &str) -> String {
+    name.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Inner text of each `<tag>...</tag>` element, in document order
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let body = &rest[start + open.len()..];
+        let Some(end) = body.find(&close) else {
+            break;
+        };
+        found.push(&body[..end]);
+        rest = &body[end + close.len()..];
+    }
+    found
+}
+
+/// Trimmed text of the first `<tag>` element
+fn child<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    elements(xml, tag).into_iter().next().map(str::trim)
+}
+
+/// Replace the predefined XML entities
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SONG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<song>
+  <bpm>120</bpm>
+  <instrumentList>
+    <instrument><id>0</id><name>Kick</name><midiOutNote>36</midiOutNote></instrument>
+    <instrument><id>1</id><name>Open Hat</name></instrument>
+  </instrumentList>
+  <patternList>
+    <pattern>
+      <name>Beat &amp; Fill</name>
+      <size>192</size>
+      <noteList>
+        <note><position>0</position><velocity>1</velocity><instrument>0</instrument></note>
+        <note><position>96</position><velocity>0.5</velocity><instrument>0</instrument></note>
+        <note><position>26</position><velocity>0.8</velocity><instrument>1</instrument></note>
+      </noteList>
+    </pattern>
+  </patternList>
+</song>"#;
+
+    #[test]
+    fn test_parse_hydrogen() {
+        let patterns = parse_hydrogen(SONG, 24).unwrap();
+        assert_eq!(patterns.len(), 1);
+        let pattern = &patterns[0];
+        assert_eq!(pattern.name, "Beat & Fill");
+        assert_eq!(pattern.file_stem(), "beat___fill");
+        assert_eq!(pattern.length_ticks, 96);
+        assert_eq!(pattern.voices, vec![("kick".to_string(), 36), ("open_hat".to_string(), 46)]);
+        let hits: Vec<(u64, u8, u8)> = pattern.hits.iter().map(|h| (h.tick, h.note, h.velocity)).collect();
+        assert_eq!(hits, vec![(0, 36, 127), (13, 46, 102), (48, 36, 64)]);
+
+        // The hat lands a little after the second sixteenth
+        let mut drums = DrumGenerator::new();
+        assert_eq!(pattern.apply_to_drums(&mut drums), 2);
+        let hat = drums.voice_steps("open_hat").unwrap();
+        assert_eq!(hat[2], DrumStep { hit: true, velocity: Some(102), offset: 17 });
+        assert!(drums.voice_steps("kick").unwrap()[8].hit);
+    }
+
+    #[test]
+    fn test_parse_step_csv() {
+        let text = "# two bars of hats\nkick, x...|x...|X...|x...\nsnare,0,0,0,0,90,0,0,0\nhat,o.x.\n";
+        let pattern = parse_step_csv(text, "groove", 24, 4).unwrap();
+        assert_eq!(pattern.length_ticks, 96);
+        assert_eq!(pattern.hits.iter().filter(|h| h.note == gm_drums::KICK).count(), 4);
+        assert!(pattern.hits.contains(&ImportedHit { tick: 24, note: gm_drums::SNARE, velocity: 90 }));
+        assert!(pattern.hits.contains(&ImportedHit { tick: 0, note: gm_drums::CLOSED_HAT, velocity: 50 }));
+
+        let clip = pattern.to_clip();
+        assert_eq!(clip.length(), 96);
+        assert_eq!(clip.notes().len(), pattern.hits.len());
+        assert_eq!(pattern.to_exporter().tracks()[0].channel, DRUM_CHANNEL);
+
+        assert!(parse_step_csv("tuba,x...", "bad", 24, 4).is_err());
+        assert!(parse_step_csv("kick,x.?.", "bad", 24, 4).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Crash-resistant recording journal.
+//!
+//! While a take is recording, every captured note and performance move is
+//! appended to a journal file as one line of text, and the file is synced
+//! to disk every few milliseconds. A finished take deletes its journal, so
+//! a journal found at startup belongs to a take that never finished: its
+//! lines are replayed into the notes and moves captured so far. A line cut
+//! short by the crash is ignored.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+
+use crate::arrangement::PerformanceEvent;
+
+use super::capture::RecordedNote;
+use super::export::{ExportNote, ExportTrack, MidiExporter, MidiFileFormat};
+
+/// Journal file name under `~/.seq`
+pub const JOURNAL_FILE: &str = "recording.journal";
+
+/// Longest time written lines may wait before being synced to disk
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Default journal path, `~/.seq/recording.journal`
+pub fn default_journal_path() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".seq").join(JOURNAL_FILE))
+}
+
+/// One journal line
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalEntry {
+    /// Take started at a song tick
+    Start(u64),
+    /// Note on at a song tick
+    NoteOn { tick: u64, channel: u8, note: u8, velocity: u8 },
+    /// Note off at a song tick
+    NoteOff { tick: u64, channel: u8, note: u8 },
+    /// Performance move at a song tick
+    Move(u64, PerformanceEvent),
+}
+
+impl JournalEntry {
+    /// Song tick of the entry
+    pub fn tick(&self) -> u64 {
+        match *self {
+            JournalEntry::Start(tick) | JournalEntry::Move(tick, _) => tick,
+            JournalEntry::NoteOn { tick, .. } | JournalEntry::NoteOff { tick, .. } => tick,
+        }
+    }
+
+    /// Write as a journal line (without the newline)
+    pub fn to_line(&self) -> String {
+        match self {
+            JournalEntry::Start(tick) => format!("start {}", tick),
+            JournalEntry::NoteOn { tick, channel, note, velocity } => {
+                format!("on {} {} {} {}", tick, channel, note, velocity)
+            }
+            JournalEntry::NoteOff { tick, channel, note } => format!("off {} {} {}", tick, channel, note),
+            JournalEntry::Move(tick, PerformanceEvent::Mute(track, on)) => format!("mute {} {} {}", tick, track, *on as u8),
+            JournalEntry::Move(tick, PerformanceEvent::Solo(track, on)) => format!("solo {} {} {}", tick, track, *on as u8),
+            JournalEntry::Move(tick, PerformanceEvent::Part(name)) => format!("part {} {}", tick, name),
+            JournalEntry::Move(tick, PerformanceEvent::Scene(index)) => format!("scene {} {}", tick, index),
+        }
+    }
+
+    /// Parse a journal line
+    pub fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(3, ' ');
+        let kind = fields.next()?;
+        let tick: u64 = fields.next()?.parse().ok()?;
+        let rest = fields.next().unwrap_or("");
+        let numbers: Vec<usize> = rest.split(' ').filter_map(|n| n.parse().ok()).collect();
+        let byte = |i: usize| numbers.get(i).and_then(|&n| u8::try_from(n).ok());
+        let entry = match kind {
+            "start" => JournalEntry::Start(tick),
+            "on" if numbers.len() == 3 => JournalEntry::NoteOn {
+                tick,
+                channel: byte(0)?,
+                note: byte(1)?,
+                velocity: byte(2)?,
+            },
+            "off" if numbers.len() == 2 => JournalEntry::NoteOff { tick, channel: byte(0)?, note: byte(1)? },
+            "mute" if numbers.len() == 2 => JournalEntry::Move(tick, PerformanceEvent::Mute(numbers[0], numbers[1] != 0)),
+            "solo" if numbers.len() == 2 => JournalEntry::Move(tick, PerformanceEvent::Solo(numbers[0], numbers[1] != 0)),
+            "part" if !rest.is_empty() => JournalEntry::Move(tick, PerformanceEvent::Part(rest.to_string())),
+            "scene" if numbers.len() == 1 => JournalEntry::Move(tick, PerformanceEvent::Scene(numbers[0])),
+            _ => return None,
+        };
+        Some(entry)
+    }
+}
+
+/// Append-only journal of the take being recorded
+#[derive(Debug)]
+pub struct RecordingJournal {
+    path: PathBuf,
+    file: File,
+    /// Longest time lines may wait before a sync
+    sync_interval: Duration,
+    /// Last sync to disk
+    last_sync: Instant,
+    /// Lines written since the last sync
+    unsynced: bool,
+}
+
+impl RecordingJournal {
+    /// Start a journal for a take beginning at a song tick, replacing any
+    /// journal already at the path
+    pub fn create(path: impl Into<PathBuf>, start_tick: u64) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)
+            .with_context(|| format!("Failed to create recording journal: {:?}", path))?;
+        let mut journal = Self {
+            path,
+            file,
+            sync_interval: DEFAULT_SYNC_INTERVAL,
+            last_sync: Instant::now(),
+            unsynced: false,
+        };
+        journal.append(&JournalEntry::Start(start_tick))?;
+        journal.sync()?;
+        Ok(journal)
+    }
+
+    /// Set the longest time lines may wait before a sync
+    pub fn with_sync_interval(mut self, interval: Duration) -> Self {
+        self.sync_interval = interval;
+        self
+    }
+
+    /// Get the journal path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write an entry. The line reaches the operating system at once, so
+    /// it survives a crash of the sequencer; it is synced to disk once the
+    /// sync interval has passed.
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<()> {
+        self.file
+            .write_all(format!("{}\n", entry.to_line()).as_bytes())
+            .with_context(|| format!("Failed to write recording journal: {:?}", self.path))?;
+        self.unsynced = true;
+        if self.last_sync.elapsed() >= self.sync_interval {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Sync written lines to disk. Call from the main loop so lines never
+    /// wait longer than the sync interval.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.unsynced && self.last_sync.elapsed() >= self.sync_interval {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        self.file.sync_data()?;
+        self.last_sync = Instant::now();
+        self.unsynced = false;
+        Ok(())
+    }
+
+    /// The take finished normally: delete the journal
+    pub fn finish(self) -> Result<()> {
+        let path = self.path.clone();
+        drop(self);
+        fs::remove_file(&path).with_context(|| format!("Failed to remove recording journal: {:?}", path))
+    }
+}
+
+/// What a journal left by an unfinished take holds
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecoveredTake {
+    /// Song tick the take started at
+    pub start_tick: u64,
+    /// Song tick of the last entry
+    pub end_tick: u64,
+    /// Captured notes, relative to the start; notes still held end at the
+    /// last entry
+    pub notes: Vec<RecordedNote>,
+    /// Performance moves, relative to the start
+    pub moves: Vec<(u64, PerformanceEvent)>,
+}
+
+impl RecoveredTake {
+    /// Rebuild a take from journal entries
+    pub fn from_entries(entries: &[JournalEntry]) -> Self {
+        let start_tick = match entries.first() {
+            Some(JournalEntry::Start(tick)) => *tick,
+            _ => entries.iter().map(JournalEntry::tick).min().unwrap_or(0),
+        };
+        let end_tick = entries.iter().map(JournalEntry::tick).max().unwrap_or(start_tick);
+        let mut take = Self { start_tick, end_tick, ..Self::default() };
+        let relative = |tick: u64| tick.saturating_sub(start_tick);
+
+        let mut held: HashMap<(u8, u8), (u64, u8)> = HashMap::new();
+        for entry in entries {
+            match entry {
+                JournalEntry::Start(_) => {}
+                JournalEntry::NoteOn { tick, channel, note, velocity } => {
+                    held.insert((*channel, *note), (*tick, *velocity));
+                }
+                JournalEntry::NoteOff { tick, channel, note } => {
+                    if let Some((start, velocity)) = held.remove(&(*channel, *note)) {
+                        take.push_note(*channel, *note, velocity, relative(start), tick.saturating_sub(start));
+                    }
+                }
+                JournalEntry::Move(tick, event) => take.moves.push((relative(*tick), event.clone())),
+            }
+        }
+        for ((channel, note), (start, velocity)) in held {
+            take.push_note(channel, note, velocity, relative(start), end_tick.saturating_sub(start));
+        }
+        take.notes.sort_by_key(|n| (n.start_tick, n.note));
+        take
+    }
+
+    fn push_note(&mut self, channel: u8, note: u8, velocity: u8, start: u64, duration: u64) {
+        if duration > 0 {
+            self.notes.push(RecordedNote::new(channel, note, velocity, start, duration));
+        }
+    }
+
+    /// Exporter with one track per recorded channel
+    pub fn to_exporter(&self, ppqn: u32) -> MidiExporter {
+        let mut exporter = MidiExporter::new();
+        exporter.set_format(MidiFileFormat::Type1);
+        exporter.set_source_ppqn(ppqn);
+        let mut channels: Vec<u8> = self.notes.iter().map(|n| n.channel).collect();
+        channels.sort_unstable();
+        channels.dedup();
+        for channel in channels {
+            let mut track = ExportTrack::new(format!("Recovered ch {}", channel + 1), channel);
+            for note in self.notes.iter().filter(|n| n.channel == channel) {
+                track.add_note(ExportNote::new(note.start_tick, note.note, note.velocity, note.duration));
+            }
+            exporter.add_track(track);
+        }
+        exporter
+    }
+
+    /// Read the journal an unfinished take left behind, if there is one
+    pub fn recover(path: &Path) -> Result<Option<Self>> {
+        let contents = match fs::read(path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read recording journal: {:?}", path)),
+        };
+        // Whole lines up to the first one the crash cut short
+        let entries: Vec<JournalEntry> = contents
+            .split_inclusive('\n')
+            .map_while(|line| line.strip_suffix('\n').and_then(JournalEntry::from_line))
+            .collect();
+        Ok(Some(Self::from_entries(&entries)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_lines() {
+        let entries = [
+            JournalEntry::Start(96),
+            JournalEntry::NoteOn { tick: 100, channel: 1, note: 60, velocity: 90 },
+            JournalEntry::NoteOff { tick: 120, channel: 1, note: 60 },
+            JournalEntry::Move(130, PerformanceEvent::Mute(2, true)),
+            JournalEntry::Move(140, PerformanceEvent::Part("big chorus".to_string())),
+            JournalEntry::Move(150, PerformanceEvent::Scene(3)),
+        ];
+        for entry in &entries {
+            assert_eq!(JournalEntry::from_line(&entry.to_line()).as_ref(), Some(entry));
+        }
+        assert_eq!(JournalEntry::from_line("on 100 1 60"), None);
+        assert_eq!(JournalEntry::from_line("on 100 1 300 9"), None);
+    }
+
+    #[test]
+    fn test_recover_unfinished_take() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("takes").join(JOURNAL_FILE);
+        assert_eq!(RecoveredTake::recover(&path).unwrap(), None);
+
+        let mut journal = RecordingJournal::create(&path, 96).unwrap();
+        for entry in [
+            JournalEntry::NoteOn { tick: 96, channel: 0, note: 60, velocity: 100 },
+            JournalEntry::Move(100, PerformanceEvent::Solo(1, true)),
+            JournalEntry::NoteOn { tick: 108, channel: 0, note: 64, velocity: 80 },
+            JournalEntry::NoteOff { tick: 120, channel: 0, note: 60 },
+            JournalEntry::NoteOff { tick: 130, channel: 0, note: 99 },
+            JournalEntry::NoteOff { tick: 144, channel: 0, note: 67 },
+        ] {
+            journal.append(&entry).unwrap();
+        }
+        journal.flush().unwrap();
+        // The crash cut the last line short
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"on 150 0 7").unwrap();
+        drop(journal);
+
+        let take = RecoveredTake::recover(&path).unwrap().unwrap();
+        assert_eq!(take.start_tick, 96);
+        assert_eq!(take.end_tick, 144);
+        assert_eq!(
+            take.notes,
+            vec![RecordedNote::new(0, 60, 100, 0, 24), RecordedNote::new(0, 64, 80, 12, 36)]
+        );
+        assert_eq!(take.moves, vec![(4, PerformanceEvent::Solo(1, true))]);
+        assert_eq!(take.to_exporter(24).tracks()[0].notes.len(), 2);
+
+        // A finished take leaves nothing to recover
+        let journal = RecordingJournal::create(&path, 0).unwrap();
+        journal.finish().unwrap();
+        assert_eq!(RecoveredTake::recover(&path).unwrap(), None);
+    }
+}
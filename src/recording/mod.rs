@@ -6,15 +6,30 @@
 //! This module provides:
 //! - MIDI recording to clips
 //! - Generator output freezing
-//! - Standard MIDI file export
+//! - Phrase library of captured generator output
+//! - Crash-resistant journal of the take being recorded
+//! - Standard MIDI file export (whole songs or per-section stems, as
+//!   played or as written, and whole arrangements rendered with a seed)
+//! - Drum pattern import from Hydrogen and step CSV files
+//! - MIDI file analysis that proposes generator settings
 
+pub mod analysis;
 pub mod capture;
 pub mod export;
 pub mod freeze;
+pub mod import;
+pub mod journal;
+pub mod phrase;
 
-pub use capture::{MidiRecorder, RecordMode, RecordedNote, RecordingState};
-pub use export::{MidiExporter, MidiFileFormat};
+pub use analysis::{GeneratorSuggestion, MidiAnalysis, MidiFileContents};
+pub use capture::{
+    LengthRounding, MidiRecorder, RecordInput, RecordMode, RecordedNote, RecordingState,
+};
+pub use export::{ExportFeel, MidiExporter, MidiFileFormat, StemRegion, StemSplit, TempoChange};
 pub use freeze::{ClipFreezer, FreezeOptions};
+pub use import::{load_drum_patterns, ImportedHit, ImportedPattern};
+pub use journal::{default_journal_path, JournalEntry, RecordingJournal, RecoveredTake};
+pub use phrase::{Phrase, PhraseLibrary};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,296 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Phrase library for keeping notable generator output.
+//!
+//! Every track's output goes into a short rolling history. Capturing a
+//! phrase copies the last N whole bars of a track into a named phrase, which
+//! stays in the library for the session and can be dropped onto any track
+//! as a static clip.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::generators::MidiEvent;
+use crate::sequencer::{Clip, ClipNote};
+
+/// Bars of history kept per track
+pub const DEFAULT_HISTORY_BARS: u32 = 16;
+
+/// A note heard at an absolute tick
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HeardNote {
+    /// Absolute start tick
+    start: u64,
+    /// Duration in ticks
+    duration: u64,
+    /// MIDI note number
+    note: u8,
+    /// Velocity
+    velocity: u8,
+}
+
+/// A captured phrase
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phrase {
+    /// Phrase name
+    name: String,
+    /// Name of the track it was captured from
+    source: Option<String>,
+    /// Length in ticks
+    length_ticks: u64,
+    /// Notes relative to the phrase start
+    notes: Vec<ClipNote>,
+}
+
+impl Phrase {
+    /// Create a phrase from notes
+    pub fn new(name: impl Into<String>, length_ticks: u64, notes: Vec<ClipNote>) -> Self {
+        Self {
+            name: name.into(),
+            source: None,
+            length_ticks,
+            notes,
+        }
+    }
+
+    /// Set the source track name
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Get phrase name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set phrase name
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = name.into();
+    }
+
+    /// Get source track name
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Get length in ticks
+    pub fn length(&self) -> u64 {
+        self.length_ticks
+    }
+
+    /// Get notes
+    pub fn notes(&self) -> &[ClipNote] {
+        &self.notes
+    }
+
+    /// Build a static clip from the phrase
+    pub fn to_clip(&self) -> Clip {
+        let mut clip = Clip::new(&self.name, self.length_ticks);
+        clip.add_notes(self.notes.iter().cloned());
+        clip
+    }
+}
+
+/// Rolling track history and captured phrases
+#[derive(Debug, Clone)]
+pub struct PhraseLibrary {
+    /// Captured phrases in capture order
+    phrases: Vec<Phrase>,
+    /// Recent notes per track index
+    history: HashMap<usize, VecDeque<HeardNote>>,
+    /// Ticks of history kept per track
+    history_ticks: u64,
+    /// Number for the next automatic name
+    next_number: u32,
+}
+
+impl PhraseLibrary {
+    /// Create a library keeping `history_ticks` of output per track
+    pub fn new(history_ticks: u64) -> Self {
+        Self {
+            phrases: Vec::new(),
+            history: HashMap::new(),
+            history_ticks,
+            next_number: 1,
+        }
+    }
+
+    /// Create a library keeping the default number of bars
+    pub fn with_bars(ticks_per_bar: u64) -> Self {
+        Self::new(DEFAULT_HISTORY_BARS as u64 * ticks_per_bar)
+    }
+
+    /// Remember a track's output for a buffer starting at `buffer_start`
+    pub fn record(&mut self, track: usize, events: &[MidiEvent], buffer_start: u64) {
+        let history = self.history.entry(track).or_default();
+        for event in events.iter().filter(|e| e.velocity > 0) {
+            history.push_back(HeardNote {
+                start: buffer_start + event.start_tick,
+                duration: event.duration_ticks,
+                note: event.note,
+                velocity: event.velocity,
+            });
+        }
+
+        // Forget notes that ended before the kept window
+        let latest = history.iter().map(|n| n.start).max().unwrap_or(0);
+        let oldest = latest.saturating_sub(self.history_ticks);
+        history.retain(|n| n.start + n.duration >= oldest);
+    }
+
+    /// Forget a track's history (e.g. after its generator changed)
+    pub fn clear_history(&mut self, track: usize) {
+        self.history.remove(&track);
+    }
+
+    /// Capture the last `bars` whole bars of a track before `now`.
+    /// Returns the index of the new phrase, or None when nothing played.
+    pub fn capture(
+        &mut self,
+        track: usize,
+        source: Option<&str>,
+        bars: u32,
+        ticks_per_bar: u64,
+        now: u64,
+    ) -> Option<usize> {
+        let length = bars.max(1) as u64 * ticks_per_bar.max(1);
+        let notes: Vec<ClipNote> = self
+            .recent(track, bars, ticks_per_bar, now)
+            .into_iter()
+            .map(|e| ClipNote::new(e.start_tick, e.duration_ticks, e.note, e.velocity))
+            .collect();
+        if notes.is_empty() {
+            return None;
+        }
+
+        let mut phrase = Phrase::new(format!("Phrase {}", self.next_number), length, notes);
+        if let Some(source) = source {
+            phrase = phrase.with_source(source);
+        }
+        self.next_number += 1;
+        self.phrases.push(phrase);
+        Some(self.phrases.len() - 1)
+    }
+
+    /// A track's notes in the last `bars` whole bars before `now`, with
+    /// start ticks relative to the first of those bars
+    pub fn recent(&self, track: usize, bars: u32, ticks_per_bar: u64, now: u64) -> Vec<MidiEvent> {
+        let ticks_per_bar = ticks_per_bar.max(1);
+        let end = now - now % ticks_per_bar;
+        let length = bars.max(1) as u64 * ticks_per_bar;
+        let (Some(start), Some(history)) = (end.checked_sub(length), self.history.get(&track)) else {
+            return Vec::new();
+        };
+
+        let mut events: Vec<MidiEvent> = history
+            .iter()
+            .filter(|n| n.start >= start && n.start < end)
+            .map(|n| {
+                let duration = n.duration.min(end - n.start).max(1);
+                MidiEvent::new(n.note, n.velocity, n.start - start, duration)
+            })
+            .collect();
+        events.sort_by_key(|e| (e.start_tick, e.note));
+        events
+    }
+
+    /// Add a phrase
+    pub fn add(&mut self, phrase: Phrase) {
+        self.phrases.push(phrase);
+    }
+
+    /// Get all phrases
+    pub fn phrases(&self) -> &[Phrase] {
+        &self.phrases
+    }
+
+    /// Get a phrase by index
+    pub fn get(&self, index: usize) -> Option<&Phrase> {
+        self.phrases.get(index)
+    }
+
+    /// Find a phrase by name
+    pub fn find(&self, name: &str) -> Option<&Phrase> {
+        self.phrases.iter().find(|p| p.name() == name)
+    }
+
+    /// Rename a phrase
+    pub fn rename(&mut self, index: usize, name: impl Into<String>) -> bool {
+        match self.phrases.get_mut(index) {
+            Some(phrase) => {
+                phrase.set_name(name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a phrase
+    pub fn remove(&mut self, index: usize) -> Option<Phrase> {
+        (index < self.phrases.len()).then(|| self.phrases.remove(index))
+    }
+
+    /// Build a clip from a phrase to drop onto a track
+    pub fn clip(&self, index: usize) -> Option<Clip> {
+        self.get(index).map(Phrase::to_clip)
+    }
+
+    /// Number of phrases
+    pub fn len(&self) -> usize {
+        self.phrases.len()
+    }
+
+    /// Check if the library is empty
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_last_bars() {
+        let mut library = PhraseLibrary::with_bars(96);
+        for bar in 0..4u64 {
+            let events = vec![
+                MidiEvent::new(60 + bar as u8, 100, 0, 24),
+                MidiEvent::new(72, 90, 90, 24),
+            ];
+            library.record(0, &events, bar * 96);
+        }
+
+        // Mid-way through bar 4: the last two whole bars are bars 2 and 3
+        let index = library.capture(0, Some("Lead"), 2, 96, 400).unwrap();
+        let phrase = library.get(index).unwrap();
+        assert_eq!(phrase.name(), "Phrase 1");
+        assert_eq!(phrase.source(), Some("Lead"));
+        assert_eq!(phrase.length(), 192);
+        assert_eq!(phrase.notes()[0], ClipNote::new(0, 24, 62, 100));
+        // The note hanging over the window end is cut at the boundary
+        assert_eq!(phrase.notes().last().unwrap(), &ClipNote::new(186, 6, 72, 90));
+
+        assert!(library.rename(index, "Hook"));
+        let clip = library.clip(index).unwrap();
+        assert_eq!(clip.name(), "Hook");
+        assert_eq!(clip.length(), 192);
+        assert_eq!(clip.note_count(), 4);
+
+        // Nothing played on another track
+        assert_eq!(library.capture(1, None, 2, 96, 400), None);
+        assert_eq!(library.len(), 1);
+    }
+
+    #[test]
+    fn test_history_window() {
+        let mut library = PhraseLibrary::new(96);
+        library.record(0, &[MidiEvent::new(60, 100, 0, 12)], 0);
+        library.record(0, &[MidiEvent::new(62, 100, 0, 12)], 960);
+
+        // The first note fell out of the history
+        assert_eq!(library.capture(0, None, 10, 96, 1056), Some(0));
+        assert_eq!(library.get(0).unwrap().notes().len(), 1);
+    }
+}
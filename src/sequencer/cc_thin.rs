@@ -0,0 +1,226 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Control change thinning for slow hardware.
+//!
+//! Texture generators and modulation can send a CC or channel pressure
+//! value every tick, more than older synths can take. A thinner passes a
+//! controller's value only when enough ticks have gone by since the last
+//! one it sent and the value has moved far enough. The newest held-back
+//! value is sent once the stream settles, so the synth still lands on
+//! where the movement stopped. Notes and other messages pass untouched.
+
+use std::collections::HashMap;
+
+use super::scheduler::{MidiMessageType, ScheduledEvent};
+
+/// Counts of thinned messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThinStats {
+    /// Messages sent
+    pub passed: u64,
+    /// Messages dropped
+    pub dropped: u64,
+}
+
+impl ThinStats {
+    /// Share of messages dropped (0.0 to 1.0)
+    pub fn drop_ratio(&self) -> f64 {
+        let total = self.passed + self.dropped;
+        if total == 0 {
+            0.0
+        } else {
+            self.dropped as f64 / total as f64
+        }
+    }
+}
+
+/// One controller stream: destination, channel, message type and CC number
+type StreamKey = (Option<usize>, u8, bool, u8);
+
+/// Last value sent on a stream and the newest one held back
+#[derive(Debug, Clone)]
+struct Stream {
+    sent_tick: u64,
+    sent_value: u8,
+    held: Option<ScheduledEvent>,
+}
+
+/// Rate limits CC and channel pressure streams
+#[derive(Debug, Clone)]
+pub struct CcThinner {
+    /// Fewest ticks between values on a stream
+    min_interval: u64,
+    /// Smallest change worth sending
+    min_delta: u8,
+    streams: HashMap<StreamKey, Stream>,
+    stats: ThinStats,
+}
+
+impl CcThinner {
+    /// Create a thinner with a minimum interval (ticks) and value change
+    pub fn new(min_interval: u64, min_delta: u8) -> Self {
+        Self {
+            min_interval,
+            min_delta: min_delta.clamp(1, 127),
+            streams: HashMap::new(),
+            stats: ThinStats::default(),
+        }
+    }
+
+    /// Get the minimum interval in ticks
+    pub fn min_interval(&self) -> u64 {
+        self.min_interval
+    }
+
+    /// Get the minimum value change
+    pub fn min_delta(&self) -> u8 {
+        self.min_delta
+    }
+
+    /// Messages passed and dropped so far
+    pub fn stats(&self) -> ThinStats {
+        self.stats
+    }
+
+    /// Thin a chunk of scheduled events starting at `now`. Held values
+    /// that have settled are sent at `now`.
+    pub fn process(&mut self, events: Vec<ScheduledEvent>, now: u64) -> Vec<ScheduledEvent> {
+        let mut output = self.settle(now);
+        for event in events {
+            let Some(key) = stream_key(&event) else {
+                output.push(event);
+                continue;
+            };
+            let value = event_value(&event);
+            let Some(stream) = self.streams.get_mut(&key) else {
+                self.streams.insert(
+                    key,
+                    Stream {
+                        sent_tick: event.time_ticks,
+                        sent_value: value,
+                        held: None,
+                    },
+                );
+                self.stats.passed += 1;
+                output.push(event);
+                continue;
+            };
+
+            let waited = event.time_ticks >= stream.sent_tick + self.min_interval;
+            let moved = value.abs_diff(stream.sent_value) >= self.min_delta;
+            if waited && moved {
+                if stream.held.take().is_some() {
+                    self.stats.dropped += 1;
+                }
+                stream.sent_tick = event.time_ticks;
+                stream.sent_value = value;
+                self.stats.passed += 1;
+                output.push(event);
+            } else {
+                // Keep only the newest value back
+                if stream.held.replace(event).is_some() {
+                    self.stats.dropped += 1;
+                }
+            }
+        }
+        output
+    }
+
+    /// Send held values whose stream has been quiet for the interval
+    fn settle(&mut self, now: u64) -> Vec<ScheduledEvent> {
+        let mut settled = Vec::new();
+        for stream in self.streams.values_mut() {
+            let quiet = stream.held.as_ref().is_some_and(|e| now >= e.time_ticks + self.min_interval);
+            if !quiet {
+                continue;
+            }
+            let Some(mut event) = stream.held.take() else {
+                continue;
+            };
+            let value = event_value(&event);
+            if value == stream.sent_value {
+                self.stats.dropped += 1;
+                continue;
+            }
+            event.time_ticks = now.max(stream.sent_tick + self.min_interval);
+            stream.sent_tick = event.time_ticks;
+            stream.sent_value = value;
+            self.stats.passed += 1;
+            settled.push(event);
+        }
+        settled
+    }
+
+    /// Forget stream state and statistics
+    pub fn reset(&mut self) {
+        self.streams.clear();
+        self.stats = ThinStats::default();
+    }
+}
+
+/// Stream an event belongs to, if it is thinned
+fn stream_key(event: &ScheduledEvent) -> Option<StreamKey> {
+    match event.message_type {
+        MidiMessageType::ControlChange => Some((event.destination, event.channel, false, event.data1)),
+        MidiMessageType::ChannelPressure => Some((event.destination, event.channel, true, 0)),
+        _ => None,
+    }
+}
+
+/// Value carried by a thinned event
+fn event_value(event: &ScheduledEvent) -> u8 {
+    match event.message_type {
+        MidiMessageType::ChannelPressure => event.data1,
+        _ => event.data2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thin_sweep() {
+        let mut thinner = CcThinner::new(4, 2);
+        let sweep: Vec<ScheduledEvent> = (0..12)
+            .map(|tick| ScheduledEvent::control_change(tick, 0, 74, tick as u8 * 3))
+            .chain([ScheduledEvent::note_on(5, 0, 60, 100)])
+            .collect();
+        let sent = thinner.process(sweep, 0);
+
+        // Every fourth value gets through, and the note always does
+        let values: Vec<(u64, u8)> = sent
+            .iter()
+            .filter(|e| e.message_type == MidiMessageType::ControlChange)
+            .map(|e| (e.time_ticks, e.data2))
+            .collect();
+        assert_eq!(values, vec![(0, 0), (4, 12), (8, 24)]);
+        assert!(sent.iter().any(|e| e.message_type == MidiMessageType::NoteOn));
+
+        // The last value (33 at tick 11) arrives once the stream settles
+        assert!(thinner.process(Vec::new(), 14).is_empty());
+        let settled = thinner.process(Vec::new(), 15);
+        assert_eq!((settled[0].time_ticks, settled[0].data2), (15, 33));
+        assert_eq!(thinner.stats(), ThinStats { passed: 4, dropped: 8 });
+    }
+
+    #[test]
+    fn test_small_moves_and_separate_streams() {
+        let mut thinner = CcThinner::new(0, 4);
+        let events = vec![
+            ScheduledEvent::channel_pressure(0, 1, 60),
+            ScheduledEvent::channel_pressure(1, 1, 62),
+            ScheduledEvent::channel_pressure(2, 2, 61),
+            ScheduledEvent::channel_pressure(3, 1, 64),
+            ScheduledEvent::control_change(3, 1, 1, 62),
+        ];
+        let sent = thinner.process(events, 0);
+        let pressures: Vec<(u8, u8)> = sent.iter().map(|e| (e.channel, e.data1)).collect();
+        assert_eq!(pressures, vec![(1, 60), (2, 61), (1, 64), (1, 1)]);
+        assert_eq!(thinner.stats().dropped, 1);
+
+        thinner.reset();
+        assert_eq!(thinner.stats().drop_ratio(), 0.0);
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Per-track pattern chains.
+//!
+//! Like the pattern chain on a hardware sequencer, a track can step through
+//! its own clips in a fixed order ("A A B C"), each entry playing a set
+//! number of loops before the next takes over. Every track runs its chain
+//! on its own, so a four-clip drum chain can run against a two-clip bass
+//! chain without building an arrangement around them.
+
+/// One step of a chain: a clip and how many times it loops
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainEntry {
+    /// Clip index on the track
+    pub clip: usize,
+    /// Loops before moving on (at least 1)
+    pub repeats: u32,
+}
+
+impl ChainEntry {
+    /// Create an entry
+    pub fn new(clip: usize, repeats: u32) -> Self {
+        Self {
+            clip,
+            repeats: repeats.max(1),
+        }
+    }
+}
+
+/// A track's clip sequence and where it has got to
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternChain {
+    /// Steps in order (the chain loops back to the first)
+    entries: Vec<ChainEntry>,
+    /// Current step
+    position: usize,
+    /// Loops of the current step already played
+    played: u32,
+    /// Absolute tick of the next switch (None = not started)
+    next_switch: Option<u64>,
+}
+
+impl PatternChain {
+    /// Create a chain from its steps
+    pub fn new(entries: Vec<ChainEntry>) -> Self {
+        Self {
+            entries,
+            position: 0,
+            played: 0,
+            next_switch: None,
+        }
+    }
+
+    /// Create a chain of clip indices, one loop each, merging runs of the
+    /// same clip ("A A B" plays A twice)
+    pub fn from_clips(clips: &[usize]) -> Self {
+        let mut entries: Vec<ChainEntry> = Vec::new();
+        for &clip in clips {
+            match entries.last_mut() {
+                Some(last) if last.clip == clip => last.repeats += 1,
+                _ => entries.push(ChainEntry::new(clip, 1)),
+            }
+        }
+        Self::new(entries)
+    }
+
+    /// Get the steps
+    pub fn entries(&self) -> &[ChainEntry] {
+        &self.entries
+    }
+
+    /// Get the current step index
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the current step
+    pub fn current(&self) -> Option<&ChainEntry> {
+        self.entries.get(self.position)
+    }
+
+    /// Total loops in one pass of the chain
+    pub fn total_loops(&self) -> u32 {
+        self.entries.iter().map(|e| e.repeats).sum()
+    }
+
+    /// Check if a switch is due at an absolute tick
+    pub fn is_due(&self, tick: u64) -> bool {
+        self.next_switch.map_or(true, |next| tick >= next)
+    }
+
+    /// Move on one loop and return the clip to play. The first call starts
+    /// the chain at its first step.
+    pub fn advance(&mut self) -> Option<usize> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        if self.next_switch.is_some() {
+            self.played += 1;
+            if self.played >= self.entries[self.position].repeats {
+                self.played = 0;
+                self.position = (self.position + 1) % self.entries.len();
+            }
+        }
+        Some(self.entries[self.position].clip)
+    }
+
+    /// Schedule the next switch after a loop of `clip_length` ticks
+    /// started at `tick`
+    pub fn started(&mut self, tick: u64, clip_length: u64) {
+        self.next_switch = Some(tick + clip_length.max(1));
+    }
+
+    /// Jump to a step (it starts on the next switch's boundary)
+    pub fn jump(&mut self, position: usize) {
+        if position < self.entries.len() {
+            self.position = position;
+            self.played = 0;
+        }
+    }
+
+    /// Go back to the first step (start again on the next generate)
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.played = 0;
+        self.next_switch = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_order_and_repeats() {
+        // A A B C
+        let mut chain = PatternChain::from_clips(&[0, 0, 1, 2]);
+        assert_eq!(chain.entries().len(), 3);
+        assert_eq!(chain.total_loops(), 4);
+
+        let mut played = Vec::new();
+        let mut tick = 0;
+        for _ in 0..6 {
+            assert!(chain.is_due(tick));
+            played.push(chain.advance().unwrap());
+            chain.started(tick, 96);
+            assert!(!chain.is_due(tick + 95));
+            tick += 96;
+        }
+        assert_eq!(played, vec![0, 0, 1, 2, 0, 0]);
+
+        chain.reset();
+        assert!(chain.is_due(0));
+        assert_eq!(chain.advance(), Some(0));
+        assert_eq!(PatternChain::new(Vec::new()).advance(), None);
+    }
+}
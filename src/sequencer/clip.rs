@@ -4,9 +4,12 @@
 //! Clip system for sequenced and generated content.
 //!
 //! Provides clips that can contain static sequences, generate content
-//! in real-time, or combine both approaches.
+//! in real-time, or combine both approaches. A clip can also carry a lane
+//! of control changes (filter or mod wheel moves for a hardware synth)
+//! that plays along with its notes.
 
-use crate::generators::{Generator, GeneratorContext, MidiEvent};
+use crate::generators::{ControlEvent, Generator, GeneratorContext, MidiEvent};
+use crate::timing::PPQN;
 
 /// Clip playback state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -70,6 +73,28 @@ impl ClipNote {
     }
 }
 
+/// A control change within a clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClipCc {
+    /// Position in ticks from clip start
+    pub tick: u64,
+    /// Controller number (0-127)
+    pub cc: u8,
+    /// Value (0-127)
+    pub value: u8,
+}
+
+impl ClipCc {
+    /// Create a new clip control change
+    pub fn new(tick: u64, cc: u8, value: u8) -> Self {
+        Self {
+            tick,
+            cc: cc.min(127),
+            value: value.min(127),
+        }
+    }
+}
+
 /// Clip type - static, generated, or hybrid
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClipType {
@@ -99,6 +124,10 @@ pub struct Clip {
     loop_end: u64,
     /// Static notes (for Sequenced and Hybrid)
     notes: Vec<ClipNote>,
+    /// Control change lane (for Sequenced and Hybrid)
+    ccs: Vec<ClipCc>,
+    /// Control changes from the last generate call
+    pending_controls: Vec<ControlEvent>,
     /// Generator (for Generated and Hybrid)
     generator: Option<Box<dyn Generator>>,
     /// Current position in ticks (relative to clip start)
@@ -109,6 +138,8 @@ pub struct Clip {
     variation: f64,
     /// Whether playing in reverse (for ping-pong)
     reverse: bool,
+    /// Exclusive group across tracks
+    group: Option<String>,
 }
 
 impl Clip {
@@ -123,11 +154,14 @@ impl Clip {
             loop_start: 0,
             loop_end: 0,
             notes: Vec::new(),
+            ccs: Vec::new(),
+            pending_controls: Vec::new(),
             generator: None,
             position: 0,
             loop_count: 0,
             variation: 0.0,
             reverse: false,
+            group: None,
         }
     }
 
@@ -142,11 +176,14 @@ impl Clip {
             loop_start: 0,
             loop_end: 0,
             notes: Vec::new(),
+            ccs: Vec::new(),
+            pending_controls: Vec::new(),
             generator: Some(generator),
             position: 0,
             loop_count: 0,
             variation: 0.0,
             reverse: false,
+            group: None,
         }
     }
 
@@ -166,19 +203,64 @@ impl Clip {
             loop_start: 0,
             loop_end: 0,
             notes: Vec::new(),
+            ccs: Vec::new(),
+            pending_controls: Vec::new(),
             generator: Some(generator),
             position: 0,
             loop_count: 0,
             variation: variation.clamp(0.0, 1.0),
             reverse: false,
+            group: None,
         }
     }
 
+    /// Render bars of a generator offline into a static clip
+    pub fn render(
+        name: impl Into<String>,
+        generator: &mut dyn Generator,
+        context: &GeneratorContext,
+        bars: u32,
+    ) -> Self {
+        let ticks_per_bar = context.ticks_per_bar().max(1);
+        let mut clip = Self::new(name, bars.max(1) as u64 * ticks_per_bar);
+        for bar in 0..bars.max(1) as u64 {
+            let bar_context = GeneratorContext {
+                bar,
+                beat: 0,
+                tick: 0,
+                ticks_to_generate: ticks_per_bar,
+                ..context.clone()
+            };
+            let notes = generator
+                .generate(&bar_context)
+                .into_iter()
+                .filter(|e| e.velocity > 0)
+                .map(|e| ClipNote::new(bar * ticks_per_bar + e.start_tick, e.duration_ticks.max(1), e.note, e.velocity));
+            clip.add_notes(notes);
+            let ccs = generator
+                .take_control_changes()
+                .into_iter()
+                .map(|c| ClipCc::new(bar * ticks_per_bar + c.start_tick, c.cc, c.value));
+            clip.add_ccs(ccs);
+        }
+        clip
+    }
+
     /// Get clip name
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// Get exclusive group
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Set exclusive group
+    pub fn set_group(&mut self, group: Option<String>) {
+        self.group = group;
+    }
+
     /// Get clip type
     pub fn clip_type(&self) -> ClipType {
         self.clip_type
@@ -244,6 +326,21 @@ impl Clip {
         &self.notes
     }
 
+    /// Get a mutable note (call `sort_notes` after moving it)
+    pub fn note_mut(&mut self, index: usize) -> Option<&mut ClipNote> {
+        self.notes.get_mut(index)
+    }
+
+    /// Remove a note
+    pub fn remove_note(&mut self, index: usize) -> Option<ClipNote> {
+        (index < self.notes.len()).then(|| self.notes.remove(index))
+    }
+
+    /// Restore start-time order after editing notes
+    pub fn sort_notes(&mut self) {
+        self.notes.sort_by_key(|n| n.start_tick);
+    }
+
     /// Clear all notes
     pub fn clear_notes(&mut self) {
         self.notes.clear();
@@ -254,6 +351,34 @@ impl Clip {
         self.notes.len()
     }
 
+    /// Add a control change to the lane
+    pub fn add_cc(&mut self, cc: ClipCc) {
+        self.ccs.push(cc);
+        self.ccs.sort_by_key(|c| c.tick);
+    }
+
+    /// Add several control changes
+    pub fn add_ccs(&mut self, ccs: impl IntoIterator<Item = ClipCc>) {
+        self.ccs.extend(ccs);
+        self.ccs.sort_by_key(|c| c.tick);
+    }
+
+    /// Get the control change lane
+    pub fn ccs(&self) -> &[ClipCc] {
+        &self.ccs
+    }
+
+    /// Clear the control change lane
+    pub fn clear_ccs(&mut self) {
+        self.ccs.clear();
+    }
+
+    /// Take the control changes from the last generate call, from the lane
+    /// and the generator, with start ticks relative to that call
+    pub fn take_control_changes(&mut self) -> Vec<ControlEvent> {
+        std::mem::take(&mut self.pending_controls)
+    }
+
     /// Set the generator
     pub fn set_generator(&mut self, generator: Box<dyn Generator>) {
         self.generator = Some(generator);
@@ -310,6 +435,7 @@ impl Clip {
         self.loop_count = 0;
         self.reverse = false;
         self.state = ClipState::Stopped;
+        self.pending_controls.clear();
         if let Some(ref mut gen) = self.generator {
             gen.reset();
         }
@@ -328,18 +454,24 @@ impl Clip {
         match self.clip_type {
             ClipType::Sequenced => {
                 events = self.generate_sequenced(ticks, loop_end);
+                let controls = self.sequenced_controls(ticks, loop_end);
+                self.pending_controls.extend(controls);
             }
             ClipType::Generated => {
                 if let Some(ref mut gen) = self.generator {
                     events = gen.generate(context);
+                    self.pending_controls.extend(gen.take_control_changes());
                 }
             }
             ClipType::Hybrid => {
                 // Mix sequenced and generated content
                 let sequenced = self.generate_sequenced(ticks, loop_end);
+                let controls = self.sequenced_controls(ticks, loop_end);
+                self.pending_controls.extend(controls);
 
                 if let Some(ref mut gen) = self.generator {
                     let generated = gen.generate(context);
+                    self.pending_controls.extend(gen.take_control_changes());
                     events = self.mix_events(sequenced, generated);
                 } else {
                     events = sequenced;
@@ -384,6 +516,19 @@ impl Clip {
         events
     }
 
+    /// Control changes from the lane that fall in the window
+    fn sequenced_controls(&self, ticks: u64, loop_end: u64) -> Vec<ControlEvent> {
+        let start = self.position;
+        self.ccs
+            .iter()
+            .filter_map(|cc| {
+                let tick = if self.reverse { loop_end.checked_sub(cc.tick + 1)? } else { cc.tick };
+                let playing = tick >= self.loop_start && tick < loop_end && tick >= start && tick < start + ticks;
+                playing.then(|| ControlEvent::new(cc.cc, cc.value, tick - start))
+            })
+            .collect()
+    }
+
     /// Mix sequenced and generated events for hybrid mode
     fn mix_events(&self, sequenced: Vec<MidiEvent>, generated: Vec<MidiEvent>) -> Vec<MidiEvent> {
         use rand::{Rng, SeedableRng};
@@ -476,11 +621,14 @@ impl Clone for Clip {
             loop_start: self.loop_start,
             loop_end: self.loop_end,
             notes: self.notes.clone(),
+            ccs: self.ccs.clone(),
+            pending_controls: Vec::new(),
             generator: None, // Generators are not cloneable
             position: self.position,
             loop_count: self.loop_count,
             variation: self.variation,
             reverse: self.reverse,
+            group: self.group.clone(),
         }
     }
 }
@@ -488,25 +636,36 @@ impl Clone for Clip {
 /// Builder for creating clips with a fluent API
 pub struct ClipBuilder {
     clip: Clip,
+    /// Ticks per quarter note for bar and beat lengths
+    ppqn: u32,
 }
 
 impl ClipBuilder {
     /// Start building a new clip
     pub fn new(name: impl Into<String>) -> Self {
         Self {
-            clip: Clip::new(name, 96), // Default 1 bar
+            clip: Clip::new(name, PPQN as u64 * 4), // Default 1 bar
+            ppqn: PPQN,
         }
     }
 
-    /// Set clip length in bars (assuming 4/4 time, 24 PPQN)
+    /// Set the resolution used by `bars` and `beats` (rescales the current length)
+    pub fn ppqn(mut self, ppqn: u32) -> Self {
+        let ppqn = ppqn.max(1);
+        self.clip.length_ticks = self.clip.length_ticks * ppqn as u64 / self.ppqn as u64;
+        self.ppqn = ppqn;
+        self
+    }
+
+    /// Set clip length in bars (assuming 4/4 time)
     pub fn bars(mut self, bars: u32) -> Self {
-        self.clip.length_ticks = bars as u64 * 96;
+        self.clip.length_ticks = bars as u64 * self.ppqn as u64 * 4;
         self
     }
 
     /// Set clip length in beats
     pub fn beats(mut self, beats: u32) -> Self {
-        self.clip.length_ticks = beats as u64 * 24;
+        self.clip.length_ticks = beats as u64 * self.ppqn as u64;
         self
     }
 
@@ -522,6 +681,12 @@ impl ClipBuilder {
         self
     }
 
+    /// Set exclusive group
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.clip.group = Some(group.into());
+        self
+    }
+
     /// Set as one-shot
     pub fn one_shot(mut self) -> Self {
         self.clip.mode = ClipMode::OneShot;
@@ -540,6 +705,12 @@ impl ClipBuilder {
         self
     }
 
+    /// Add a control change
+    pub fn cc(mut self, tick: u64, cc: u8, value: u8) -> Self {
+        self.clip.add_cc(ClipCc::new(tick, cc, value));
+        self
+    }
+
     /// Set generator
     pub fn generator(mut self, gen: Box<dyn Generator>) -> Self {
         self.clip.generator = Some(gen);
@@ -710,6 +881,41 @@ mod tests {
         assert_eq!(clip.length(), 192);
         assert_eq!(clip.mode(), ClipMode::OneShot);
         assert_eq!(clip.note_count(), 2);
+
+        // Lengths follow a finer resolution
+        assert_eq!(ClipBuilder::new("Fine").ppqn(480).build().length(), 1920);
+        assert_eq!(ClipBuilder::new("Fine").ppqn(96).beats(3).build().length(), 288);
+    }
+
+    #[test]
+    fn test_clip_cc_lane() {
+        let mut clip = ClipBuilder::new("Filter")
+            .ppqn(24)
+            .beats(2)
+            .cc(30, 74, 100)
+            .cc(0, 74, 20)
+            .cc(12, 1, 64)
+            .build();
+        assert_eq!(clip.ccs()[0], ClipCc::new(0, 74, 20));
+
+        clip.play();
+        let ctx = test_context(24);
+        clip.generate(&ctx);
+        let first = clip.take_control_changes();
+        assert_eq!(first, vec![ControlEvent::new(74, 20, 0), ControlEvent::new(1, 64, 12)]);
+        assert!(clip.take_control_changes().is_empty());
+
+        // Start ticks are relative to each call
+        clip.generate(&ctx);
+        assert_eq!(clip.take_control_changes(), vec![ControlEvent::new(74, 100, 6)]);
+    }
+
+    #[test]
+    fn test_render_keeps_generator_controls() {
+        let mut texture = crate::generators::texture::TextureGenerator::new();
+        let clip = Clip::render("Sweep", &mut texture, &test_context(96), 2);
+        assert!(clip.ccs().iter().all(|c| c.cc == 74));
+        assert!(clip.ccs().iter().any(|c| c.tick >= 96));
     }
 
     #[test]
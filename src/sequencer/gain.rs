@@ -0,0 +1,194 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Velocity gain staging.
+//!
+//! Measures the average note-on velocity each track sends over a few bars
+//! and works out the velocity scale that would bring it to the track's
+//! target level. The suggestions can be shown or applied straight away,
+//! which balances a rig of several synths in one pass.
+
+use std::collections::HashMap;
+
+use super::scheduler::{MidiMessageType, ScheduledEvent};
+use super::track::TrackManager;
+
+/// Target average velocity when a track sets none
+pub const DEFAULT_TARGET_VELOCITY: u8 = 90;
+
+/// Bars measured when none are given
+pub const DEFAULT_MEASURE_BARS: u32 = 4;
+
+/// Suggested velocity scale for one track
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GainSuggestion {
+    /// Track index
+    pub track: usize,
+    /// Average note-on velocity measured
+    pub average: f64,
+    /// Velocity scale while measuring
+    pub current_scale: f64,
+    /// Scale that reaches the target (0.0 to 2.0)
+    pub suggested_scale: f64,
+}
+
+/// Measures output velocity per track over a number of bars
+#[derive(Debug, Clone)]
+pub struct GainMeter {
+    /// Target average velocity per track
+    targets: HashMap<usize, u8>,
+    /// Velocity sum and note count per track
+    totals: HashMap<usize, (u64, u64)>,
+    /// Tick range being measured [start, end)
+    window: Option<(u64, u64)>,
+}
+
+impl GainMeter {
+    /// Create an idle meter
+    pub fn new() -> Self {
+        Self {
+            targets: HashMap::new(),
+            totals: HashMap::new(),
+            window: None,
+        }
+    }
+
+    /// Set a track's target average velocity
+    pub fn set_target(&mut self, track: usize, velocity: u8) {
+        self.targets.insert(track, velocity.clamp(1, 127));
+    }
+
+    /// Get a track's target average velocity
+    pub fn target(&self, track: usize) -> u8 {
+        self.targets.get(&track).copied().unwrap_or(DEFAULT_TARGET_VELOCITY)
+    }
+
+    /// Start measuring `bars` bars from `now`, forgetting any earlier run
+    pub fn start(&mut self, now: u64, bars: u32, ticks_per_bar: u64) {
+        self.totals.clear();
+        self.window = Some((now, now + bars.max(1) as u64 * ticks_per_bar));
+    }
+
+    /// Check if a measurement is running
+    pub fn is_measuring(&self) -> bool {
+        self.window.is_some()
+    }
+
+    /// Check if the measured window has passed
+    pub fn is_complete(&self, now: u64) -> bool {
+        self.window.is_some_and(|(_, end)| now >= end)
+    }
+
+    /// Count the note-ons of scheduled output inside the window
+    pub fn record(&mut self, events: &[ScheduledEvent]) {
+        let Some((start, end)) = self.window else {
+            return;
+        };
+        for event in events {
+            let Some(track) = event.track_index else {
+                continue;
+            };
+            if event.message_type != MidiMessageType::NoteOn || event.data2 == 0 {
+                continue;
+            }
+            if event.time_ticks < start || event.time_ticks >= end {
+                continue;
+            }
+            let total = self.totals.entry(track).or_default();
+            total.0 += event.data2 as u64;
+            total.1 += 1;
+        }
+    }
+
+    /// Average velocity measured on a track
+    pub fn average(&self, track: usize) -> Option<f64> {
+        let &(sum, count) = self.totals.get(&track)?;
+        (count > 0).then(|| sum as f64 / count as f64)
+    }
+
+    /// Suggested scales for every track that played, in track order
+    pub fn suggestions(&self, tracks: &TrackManager) -> Vec<GainSuggestion> {
+        let mut suggestions: Vec<GainSuggestion> = tracks
+            .iter()
+            .filter_map(|track| {
+                let index = track.index();
+                let average = self.average(index)?;
+                let current_scale = track.velocity_scale();
+                let suggested_scale = current_scale * self.target(index) as f64 / average;
+                Some(GainSuggestion {
+                    track: index,
+                    average,
+                    current_scale,
+                    suggested_scale: ((suggested_scale * 100.0).round() / 100.0).clamp(0.0, 2.0),
+                })
+            })
+            .collect();
+        suggestions.sort_by_key(|s| s.track);
+        suggestions
+    }
+
+    /// Apply the suggested scales and stop measuring.
+    /// Returns the suggestions that were applied.
+    pub fn apply(&mut self, tracks: &mut TrackManager) -> Vec<GainSuggestion> {
+        let suggestions = self.suggestions(tracks);
+        for suggestion in &suggestions {
+            if let Some(track) = tracks.track_mut(suggestion.track) {
+                track.set_velocity_scale(suggestion.suggested_scale);
+            }
+        }
+        self.window = None;
+        suggestions
+    }
+
+    /// Stop measuring without applying anything
+    pub fn cancel(&mut self) {
+        self.window = None;
+        self.totals.clear();
+    }
+}
+
+impl Default for GainMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer::track::TrackConfig;
+
+    fn note(track: usize, tick: u64, velocity: u8) -> ScheduledEvent {
+        ScheduledEvent::note_on(tick, 0, 60, velocity).with_track(track)
+    }
+
+    #[test]
+    fn test_gain_suggestions() {
+        let mut tracks = TrackManager::new();
+        tracks.add_track(TrackConfig::new("Loud"));
+        tracks.add_track(TrackConfig::new("Quiet"));
+        tracks.add_track(TrackConfig::new("Silent"));
+
+        let mut meter = GainMeter::new();
+        meter.set_target(1, 80);
+        meter.start(96, 2, 96);
+        meter.record(&[
+            note(0, 0, 10), // Before the window
+            note(0, 100, 120),
+            note(0, 200, 120),
+            note(1, 150, 20),
+            note(1, 160, 40),
+            note(0, 288, 1), // After the window
+        ]);
+        assert!(!meter.is_complete(200));
+        assert!(meter.is_complete(288));
+
+        let applied = meter.apply(&mut tracks);
+        assert_eq!(applied.len(), 2);
+        assert_eq!((applied[0].average, applied[0].suggested_scale), (120.0, 0.75));
+        // Quiet track needs more than the largest scale
+        assert_eq!(applied[1].suggested_scale, 2.0);
+        assert_eq!(tracks.track(0).unwrap().velocity_scale(), 0.75);
+        assert!(!meter.is_measuring());
+    }
+}
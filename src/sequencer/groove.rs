@@ -0,0 +1,122 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Groove templates.
+//!
+//! A groove gives each sixteenth of the beat its own timing offset and
+//! velocity scaling, for feels that plain swing can't express (a lazy
+//! backbeat, pushed off-beats, an accented shuffle). Offsets are fractions
+//! of a sixteenth, positive meaning late; both lists repeat when shorter
+//! than the bar. A track picks a groove by name, either a built-in one or
+//! one defined in the song file, and it is applied at the song position of
+//! each event, on top of the track's swing.
+
+/// Names of the built-in grooves
+pub const BUILTIN_GROOVES: &[&str] = &["straight", "swing", "shuffle", "push", "lazy"];
+
+/// Largest offset, in sixteenths
+pub const MAX_GROOVE_OFFSET: f64 = 0.5;
+
+/// Largest velocity scaling
+pub const MAX_GROOVE_VELOCITY: f64 = 2.0;
+
+/// Per-sixteenth timing and velocity template
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrooveTemplate {
+    /// Template name
+    name: String,
+    /// Offset of each sixteenth, in sixteenths (positive = late)
+    offsets: Vec<f64>,
+    /// Velocity scaling of each sixteenth
+    velocities: Vec<f64>,
+}
+
+impl GrooveTemplate {
+    /// Create a template, clamping offsets and velocity scalings
+    pub fn new(name: impl Into<String>, offsets: Vec<f64>, velocities: Vec<f64>) -> Self {
+        Self {
+            name: name.into(),
+            offsets: offsets
+                .into_iter()
+                .map(|o| o.clamp(-MAX_GROOVE_OFFSET, MAX_GROOVE_OFFSET))
+                .collect(),
+            velocities: velocities.into_iter().map(|v| v.clamp(0.0, MAX_GROOVE_VELOCITY)).collect(),
+        }
+    }
+
+    /// Look up a built-in groove by name
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (offsets, velocities): (&[f64], &[f64]) = match name.trim().to_lowercase().as_str() {
+            "straight" => (&[0.0], &[1.0]),
+            // Off-beat sixteenths a third late and a little softer
+            "swing" => (&[0.0, 0.33], &[1.0, 0.85]),
+            // Eighth-note triplet swing with accented beats
+            "shuffle" => (&[0.0, 0.0, 0.5, 0.0], &[1.1, 0.75, 0.9, 0.75]),
+            // Off-beats ahead of the grid
+            "push" => (&[0.0, -0.15, -0.1, -0.15], &[1.0, 0.9, 1.05, 0.9]),
+            // Everything behind the beat, backbeat latest
+            "lazy" => (&[0.05, 0.15, 0.1, 0.15, 0.2, 0.15, 0.1, 0.15], &[1.0, 0.8, 0.9, 0.8, 1.1, 0.8, 0.9, 0.8]),
+            _ => return None,
+        };
+        Some(Self::new(name.trim().to_lowercase(), offsets.to_vec(), velocities.to_vec()))
+    }
+
+    /// Get the name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the offsets
+    pub fn offsets(&self) -> &[f64] {
+        &self.offsets
+    }
+
+    /// Get the velocity scalings
+    pub fn velocities(&self) -> &[f64] {
+        &self.velocities
+    }
+
+    /// Timing shift in ticks and velocity scaling for an event at a song tick
+    pub fn apply(&self, tick: u64, ppqn: u32) -> (i64, f64) {
+        let step_ticks = (ppqn as u64 / 4).max(1);
+        let step = (tick / step_ticks) as usize;
+        let offset = match self.offsets.len() {
+            0 => 0.0,
+            len => self.offsets[step % len],
+        };
+        let velocity = match self.velocities.len() {
+            0 => 1.0,
+            len => self.velocities[step % len],
+        };
+        ((offset * step_ticks as f64).round() as i64, velocity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_steps() {
+        let groove = GrooveTemplate::new("test", vec![0.0, 0.5, -0.25, 9.0], vec![1.0, 0.5]);
+        assert_eq!(groove.offsets()[3], MAX_GROOVE_OFFSET);
+
+        // 24 ppqn: six ticks per sixteenth, and the template repeats each beat
+        assert_eq!(groove.apply(0, 24), (0, 1.0));
+        assert_eq!(groove.apply(7, 24), (3, 0.5));
+        assert_eq!(groove.apply(12, 24), (-2, 1.0));
+        assert_eq!(groove.apply(24 + 6, 24), (3, 0.5));
+
+        // An empty template leaves events alone
+        assert_eq!(GrooveTemplate::new("none", vec![], vec![]).apply(30, 24), (0, 1.0));
+    }
+
+    #[test]
+    fn test_builtin_grooves() {
+        for name in BUILTIN_GROOVES {
+            assert_eq!(GrooveTemplate::builtin(name).unwrap().name(), *name);
+        }
+        assert_eq!(GrooveTemplate::builtin(" Shuffle ").unwrap().apply(12, 24), (3, 0.9));
+        assert!(GrooveTemplate::builtin("polka").is_none());
+    }
+}
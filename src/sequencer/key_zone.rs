@@ -0,0 +1,264 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Steering the song key from a keyboard zone.
+//!
+//! Notes played in the zone (usually the bottom of a split keyboard) don't
+//! sound; they set the key the generators play in. A bass note moves the
+//! root and keeps the mode. In chord mode a held triad also picks major or
+//! minor, using the parallel mode when the chord's third disagrees with the
+//! current one. The change waits for the next bar line so the ensemble
+//! moves together.
+
+use crate::midi::MidiMessage;
+use crate::music::{Key, Note, ScaleType};
+
+/// How notes in the zone are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyZoneMode {
+    /// The lowest held note is the new root; the mode is kept
+    Bass,
+    /// A held chord sets the root and major/minor; a single note acts as a bass note
+    Chord,
+}
+
+impl Default for KeyZoneMode {
+    fn default() -> Self {
+        KeyZoneMode::Chord
+    }
+}
+
+impl KeyZoneMode {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "bass" | "root" => Some(KeyZoneMode::Bass),
+            "chord" => Some(KeyZoneMode::Chord),
+            _ => None,
+        }
+    }
+}
+
+/// A key change waiting for its bar line
+#[derive(Debug, Clone, PartialEq)]
+struct PendingKey {
+    key: Key,
+    /// Tick the zone was played
+    received: u64,
+}
+
+/// A note range on an input that steers the key
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyZone {
+    /// Lowest note of the zone
+    low: u8,
+    /// Highest note of the zone
+    high: u8,
+    /// Input channel (None = any)
+    channel: Option<u8>,
+    mode: KeyZoneMode,
+    /// Notes held in the zone
+    held: Vec<u8>,
+    pending: Option<PendingKey>,
+}
+
+impl KeyZone {
+    /// Create a zone over a note range
+    pub fn new(low: u8, high: u8) -> Self {
+        Self {
+            low: low.min(high),
+            high: low.max(high),
+            channel: None,
+            mode: KeyZoneMode::default(),
+            held: Vec::new(),
+            pending: None,
+        }
+    }
+
+    /// Only listen on one channel (0-15)
+    pub fn with_channel(mut self, channel: Option<u8>) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Set how notes are read
+    pub fn with_mode(mut self, mode: KeyZoneMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Get the note range
+    pub fn range(&self) -> (u8, u8) {
+        (self.low, self.high)
+    }
+
+    /// Get the mode
+    pub fn mode(&self) -> KeyZoneMode {
+        self.mode
+    }
+
+    /// Notes held in the zone
+    pub fn held(&self) -> &[u8] {
+        &self.held
+    }
+
+    /// Key waiting for the next bar line
+    pub fn pending(&self) -> Option<&Key> {
+        self.pending.as_ref().map(|p| &p.key)
+    }
+
+    /// Check if a note on a channel falls in the zone
+    pub fn contains(&self, channel: u8, note: u8) -> bool {
+        self.channel.map_or(true, |c| c == channel) && (self.low..=self.high).contains(&note)
+    }
+
+    /// Take an input message. Returns true if it was in the zone (and
+    /// shouldn't be played).
+    pub fn process(&mut self, message: &MidiMessage, current: &Key, tick: u64) -> bool {
+        match *message {
+            MidiMessage::NoteOn { channel, note, velocity } if velocity > 0 && self.contains(channel, note) => {
+                if !self.held.contains(&note) {
+                    self.held.push(note);
+                }
+                self.pending = self.key_for(current).map(|key| PendingKey { key, received: tick });
+                true
+            }
+            MidiMessage::NoteOn { channel, note, .. } | MidiMessage::NoteOff { channel, note, .. }
+                if self.contains(channel, note) =>
+            {
+                self.held.retain(|&n| n != note);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Key the held notes ask for (None if nothing is held)
+    pub fn key_for(&self, current: &Key) -> Option<Key> {
+        let bass = *self.held.iter().min()?;
+        let scale_type = current.scale().scale_type();
+        if self.mode == KeyZoneMode::Bass {
+            return Some(Key::new(Note::from_pitch_class(bass % 12), scale_type));
+        }
+
+        // Score each held pitch class as a root: a third counts double a
+        // fifth, and the bass wins ties
+        let classes: Vec<u8> = self.held.iter().map(|n| n % 12).collect();
+        let has = |pc: u8| classes.contains(&(pc % 12));
+        let mut roots = vec![bass % 12];
+        roots.extend(classes.iter().copied().filter(|&pc| pc != bass % 12));
+        let best = roots
+            .iter()
+            .map(|&root| {
+                let third = if has(root + 4) {
+                    Some(false)
+                } else if has(root + 3) {
+                    Some(true)
+                } else {
+                    None
+                };
+                let score = if third.is_some() { 2 } else { 0 } + if has(root + 7) { 1 } else { 0 };
+                (root, third, score)
+            })
+            .fold(None, |best: Option<(u8, Option<bool>, i32)>, candidate| match best {
+                Some(b) if b.2 >= candidate.2 => Some(b),
+                _ => Some(candidate),
+            })?;
+
+        let (root, minor, _) = best;
+        let scale_type = match minor {
+            Some(minor) => with_third(scale_type, minor),
+            None => scale_type,
+        };
+        Some(Key::new(Note::from_pitch_class(root), scale_type))
+    }
+
+    /// Called each tick: returns the new key once its bar line arrives
+    pub fn poll(&mut self, tick: u64, ticks_per_bar: u64) -> Option<Key> {
+        let bar = ticks_per_bar.max(1);
+        let due = tick >= self.pending.as_ref()?.received.div_ceil(bar) * bar;
+        if !due {
+            return None;
+        }
+        self.pending.take().map(|p| p.key)
+    }
+
+    /// Forget held notes and any waiting change
+    pub fn reset(&mut self) {
+        self.held.clear();
+        self.pending = None;
+    }
+}
+
+/// A mode with a minor or major third: the mode itself if it already has
+/// it, else its parallel (or plain major/minor)
+fn with_third(scale_type: ScaleType, minor: bool) -> ScaleType {
+    let intervals = scale_type.intervals();
+    let is_minor = intervals.contains(&3) && !intervals.contains(&4);
+    if is_minor == minor {
+        return scale_type;
+    }
+    match scale_type.parallel() {
+        Some(parallel) => parallel,
+        None if minor => ScaleType::NaturalMinor,
+        None => ScaleType::Major,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(note: u8) -> MidiMessage {
+        MidiMessage::NoteOn { channel: 0, note, velocity: 90 }
+    }
+
+    fn off(note: u8) -> MidiMessage {
+        MidiMessage::NoteOff { channel: 0, note, velocity: 0 }
+    }
+
+    #[test]
+    fn test_chord_sets_key_on_next_bar() {
+        let c_major = Key::new(Note::C, ScaleType::Major);
+        let mut zone = KeyZone::new(36, 59);
+
+        // Notes above the zone pass through
+        assert!(!zone.process(&on(72), &c_major, 10));
+
+        // A minor triad in first inversion: C E A
+        for note in [48, 52, 57] {
+            assert!(zone.process(&on(note), &c_major, 10));
+        }
+        assert_eq!(zone.pending(), Some(&Key::new(Note::A, ScaleType::NaturalMinor)));
+        assert_eq!(zone.poll(50, 96), None);
+        assert_eq!(zone.poll(96, 96), Some(Key::new(Note::A, ScaleType::NaturalMinor)));
+        assert_eq!(zone.poll(97, 96), None);
+
+        // Releasing the chord keeps the key; a lone bass note moves the root
+        for note in [48, 52, 57] {
+            zone.process(&off(note), &c_major, 120);
+        }
+        assert!(zone.held().is_empty());
+        let dorian = Key::new(Note::D, ScaleType::Dorian);
+        zone.process(&on(43), &dorian, 192);
+        assert_eq!(zone.poll(192, 96), Some(Key::new(Note::G, ScaleType::Dorian)));
+    }
+
+    #[test]
+    fn test_major_chord_over_minor_key() {
+        let a_minor = Key::new(Note::A, ScaleType::NaturalMinor);
+        let mut zone = KeyZone::new(0, 59).with_channel(Some(0));
+        // F major with the fifth in the bass
+        for note in [36, 41, 45] {
+            zone.process(&on(note), &a_minor, 0);
+        }
+        assert_eq!(zone.key_for(&a_minor), Some(Key::new(Note::F, ScaleType::Major)));
+
+        let mut bass = KeyZone::new(0, 59).with_mode(KeyZoneMode::Bass);
+        for note in [36, 41, 45] {
+            bass.process(&on(note), &a_minor, 0);
+        }
+        assert_eq!(bass.key_for(&a_minor), Some(Key::new(Note::C, ScaleType::NaturalMinor)));
+        assert!(!bass.process(&MidiMessage::NoteOn { channel: 3, note: 200, velocity: 1 }, &a_minor, 0));
+    }
+}
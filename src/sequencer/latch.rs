@@ -0,0 +1,153 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Latch/hold mode for incoming notes.
+//!
+//! A latched note keeps sounding after its key is released, until the same
+//! note is pressed again or the latch is cleared. Keys already down when
+//! the latch is turned on were never latched, so their releases go through.
+
+use std::collections::BTreeSet;
+
+use crate::midi::MidiMessage;
+
+/// Note latch for a track's live input
+#[derive(Debug, Clone, Default)]
+pub struct NoteLatch {
+    /// Whether latching is active
+    enabled: bool,
+    /// Latched notes: (channel, note)
+    latched: BTreeSet<(u8, u8)>,
+    /// Notes released by a second press, whose key is still down
+    releasing: BTreeSet<(u8, u8)>,
+}
+
+impl NoteLatch {
+    /// Create a new (disabled) latch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable. Disabling releases all latched notes.
+    pub fn set_enabled(&mut self, enabled: bool) -> Vec<MidiMessage> {
+        self.enabled = enabled;
+        self.releasing.clear();
+        if enabled {
+            Vec::new()
+        } else {
+            self.clear()
+        }
+    }
+
+    /// Check whether a note is latched
+    pub fn is_latched(&self, channel: u8, note: u8) -> bool {
+        self.latched.contains(&(channel, note))
+    }
+
+    /// Number of latched notes
+    pub fn latched_count(&self) -> usize {
+        self.latched.len()
+    }
+
+    /// Process an incoming message, returning the messages to send on
+    pub fn process(&mut self, message: &MidiMessage) -> Vec<MidiMessage> {
+        if !self.enabled {
+            return vec![message.clone()];
+        }
+
+        match *message {
+            MidiMessage::NoteOn { channel, note, .. } => {
+                if self.latched.remove(&(channel, note)) {
+                    self.releasing.insert((channel, note));
+                    vec![MidiMessage::NoteOff { channel, note, velocity: 0 }]
+                } else {
+                    self.releasing.remove(&(channel, note));
+                    self.latched.insert((channel, note));
+                    vec![message.clone()]
+                }
+            }
+            // Releases are held back for latched notes only
+            MidiMessage::NoteOff { channel, note, .. } => {
+                if self.latched.contains(&(channel, note)) || self.releasing.remove(&(channel, note)) {
+                    Vec::new()
+                } else {
+                    vec![message.clone()]
+                }
+            }
+            _ => vec![message.clone()],
+        }
+    }
+
+    /// Release all latched notes
+    pub fn clear(&mut self) -> Vec<MidiMessage> {
+        std::mem::take(&mut self.latched)
+            .into_iter()
+            .map(|(channel, note)| MidiMessage::NoteOff { channel, note, velocity: 0 })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note_on(note: u8) -> MidiMessage {
+        MidiMessage::NoteOn { channel: 0, note, velocity: 100 }
+    }
+
+    fn note_off(note: u8) -> MidiMessage {
+        MidiMessage::NoteOff { channel: 0, note, velocity: 0 }
+    }
+
+    #[test]
+    fn test_latch_disabled_passes_through() {
+        let mut latch = NoteLatch::new();
+        assert_eq!(latch.process(&note_off(60)), vec![note_off(60)]);
+    }
+
+    #[test]
+    fn test_latch_toggle() {
+        let mut latch = NoteLatch::new();
+        latch.set_enabled(true);
+
+        assert_eq!(latch.process(&note_on(60)), vec![note_on(60)]);
+        assert!(latch.process(&note_off(60)).is_empty());
+        assert!(latch.is_latched(0, 60));
+
+        // Pressing the same note again releases it
+        assert_eq!(latch.process(&note_on(60)), vec![note_off(60)]);
+        assert!(latch.process(&note_off(60)).is_empty());
+        assert_eq!(latch.latched_count(), 0);
+    }
+
+    #[test]
+    fn test_latch_clear() {
+        let mut latch = NoteLatch::new();
+        latch.set_enabled(true);
+        latch.process(&note_on(48));
+        latch.process(&note_on(55));
+
+        assert_eq!(latch.clear(), vec![note_off(48), note_off(55)]);
+        assert_eq!(latch.latched_count(), 0);
+
+        latch.process(&note_on(60));
+        assert_eq!(latch.set_enabled(false), vec![note_off(60)]);
+    }
+
+    #[test]
+    fn test_latch_enabled_while_key_held() {
+        let mut latch = NoteLatch::new();
+        assert_eq!(latch.process(&note_on(62)), vec![note_on(62)]);
+        latch.set_enabled(true);
+
+        // The key was down before the latch, so its release still goes out
+        assert_eq!(latch.process(&note_off(62)), vec![note_off(62)]);
+        assert_eq!(latch.latched_count(), 0);
+        assert!(latch.clear().is_empty());
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Metronome clicks that follow the groove.
+//!
+//! Beats click as usual; with swing set, the off-beat eighths click softly
+//! at their swung position so the feel can be heard without any tracks
+//! playing. Groove audition mode clicks every step of a finer grid and is
+//! meant to be played on its own while the swing is adjusted. Clicks go to
+//! any output, so sending them on the drum channel of the built-in
+//! FluidSynth plays them as internal drum hits. While a recorder counts in
+//! the metronome clicks even when switched off.
+
+use crate::generators::{GeneratorContext, MidiEvent};
+use crate::midi::gm::DRUM_CHANNEL;
+use crate::recording::{MidiRecorder, RecordingState};
+
+use super::track::swing_offset;
+use super::ScheduledEvent;
+
+/// Generates click events for the current swing
+#[derive(Debug, Clone)]
+pub struct Metronome {
+    /// Whether clicks are produced
+    enabled: bool,
+    /// MIDI channel (0-15)
+    channel: u8,
+    /// Note for the first beat of the bar
+    accent_note: u8,
+    /// Note for other beats and off-beat steps
+    note: u8,
+    /// Velocity of beat clicks
+    velocity: u8,
+    /// Swing amount applied to off-beat clicks (0.0 to 1.0)
+    swing: f64,
+    /// Click every step of the audition grid
+    audition: bool,
+    /// Steps per beat in audition mode
+    audition_division: u32,
+    /// Click level (0.0 to 1.0), scaling every velocity
+    volume: f64,
+    /// Output destination index (None = default output)
+    destination: Option<usize>,
+    /// A recorder is counting in, or recording with its click on
+    recording: bool,
+}
+
+impl Default for Metronome {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            channel: DRUM_CHANNEL,
+            accent_note: 76, // Hi Wood Block
+            note: 77,        // Low Wood Block
+            velocity: 100,
+            swing: 0.0,
+            audition: false,
+            audition_division: 4,
+            volume: 1.0,
+            destination: None,
+            recording: false,
+        }
+    }
+}
+
+impl Metronome {
+    /// Create a disabled metronome
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check if enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable clicks
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Toggle clicks, returning the new state
+    pub fn toggle(&mut self) -> bool {
+        self.enabled = !self.enabled;
+        self.enabled
+    }
+
+    /// Get click level
+    pub fn volume(&self) -> f64 {
+        self.volume
+    }
+
+    /// Set click level (0.0 = silent, 1.0 = full)
+    pub fn set_volume(&mut self, volume: f64) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    /// Get output destination
+    pub fn destination(&self) -> Option<usize> {
+        self.destination
+    }
+
+    /// Set output destination
+    pub fn set_destination(&mut self, destination: Option<usize>) {
+        self.destination = destination;
+    }
+
+    /// Click for a recorder: always during its count-in, and while it
+    /// records if its metronome option is on (call each tick)
+    pub fn follow_recorder(&mut self, recorder: &MidiRecorder) {
+        self.recording = match recorder.state() {
+            RecordingState::CountIn => true,
+            RecordingState::Recording => recorder.metronome(),
+            _ => false,
+        };
+    }
+
+    /// Get MIDI channel
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+
+    /// Set MIDI channel
+    pub fn set_channel(&mut self, channel: u8) {
+        self.channel = channel.min(15);
+    }
+
+    /// Set the accent and regular click notes
+    pub fn set_notes(&mut self, accent: u8, note: u8) {
+        self.accent_note = accent.min(127);
+        self.note = note.min(127);
+    }
+
+    /// Set beat click velocity
+    pub fn set_velocity(&mut self, velocity: u8) {
+        self.velocity = velocity.clamp(1, 127);
+    }
+
+    /// Get swing amount
+    pub fn swing(&self) -> f64 {
+        self.swing
+    }
+
+    /// Follow a swing amount (call whenever the swing is adjusted)
+    pub fn set_swing(&mut self, swing: f64) {
+        self.swing = swing.clamp(0.0, 1.0);
+    }
+
+    /// Check if groove audition is on
+    pub fn is_auditioning(&self) -> bool {
+        self.audition
+    }
+
+    /// Turn groove audition on or off. While auditioning the metronome
+    /// clicks even when disabled, and the caller should silence the tracks.
+    pub fn set_audition(&mut self, audition: bool) {
+        self.audition = audition;
+    }
+
+    /// Toggle groove audition, returning the new state
+    pub fn toggle_audition(&mut self) -> bool {
+        self.audition = !self.audition;
+        self.audition
+    }
+
+    /// Set steps per beat for audition mode (2 = eighths, 4 = sixteenths)
+    pub fn set_audition_division(&mut self, division: u32) {
+        self.audition_division = division.clamp(1, 8);
+    }
+
+    /// Steps per beat that currently click
+    fn division(&self) -> u32 {
+        if self.audition {
+            self.audition_division
+        } else if self.swing > 0.0 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Generate clicks for the context's window, relative to its start
+    pub fn generate(&self, context: &GeneratorContext) -> Vec<MidiEvent> {
+        if !self.enabled && !self.audition && !self.recording {
+            return Vec::new();
+        }
+
+        let ppqn = context.ppqn.max(1);
+        let ticks_per_beat = ppqn as u64;
+        let ticks_per_bar = context.ticks_per_bar().max(1);
+        let step = (ticks_per_beat / self.division() as u64).max(1);
+        let start = context.total_ticks();
+        let end = start + context.ticks_to_generate;
+
+        // Steps swung into the window from just before it count too
+        let max_offset = swing_offset(ticks_per_beat - 1, ppqn, self.swing);
+        let first = start.saturating_sub(max_offset) / step;
+
+        let mut events = Vec::new();
+        let mut grid = first * step;
+        while grid < end {
+            let tick = grid + swing_offset(grid, ppqn, self.swing);
+            if tick >= start && tick < end {
+                let (note, velocity) = if grid % ticks_per_bar == 0 {
+                    (self.accent_note, self.velocity.saturating_add(20).min(127))
+                } else if grid % ticks_per_beat == 0 {
+                    (self.note, self.velocity)
+                } else {
+                    (self.note, (self.velocity as f64 * 0.6) as u8)
+                };
+                let velocity = (velocity as f64 * self.volume).round() as u8;
+                let duration = (step / 2).max(1);
+                if velocity > 0 {
+                    events.push(
+                        MidiEvent::new(note, velocity, tick - start, duration).with_channel(self.channel),
+                    );
+                }
+            }
+            grid += step;
+        }
+        events
+    }
+
+    /// Clicks for the context's window as note on/off pairs on the
+    /// metronome's output
+    pub fn generate_scheduled(&self, context: &GeneratorContext, base_tick: u64) -> Vec<ScheduledEvent> {
+        self.generate(context)
+            .into_iter()
+            .flat_map(|click| {
+                let on = base_tick + click.start_tick;
+                [
+                    ScheduledEvent::note_on(on, click.channel, click.note, click.velocity),
+                    ScheduledEvent::note_off(on + click.duration_ticks, click.channel, click.note),
+                ]
+            })
+            .map(|event| event.with_destination(self.destination))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar() -> GeneratorContext {
+        GeneratorContext {
+            ticks_to_generate: 96,
+            ..Default::default()
+        }
+    }
+
+    fn ticks(events: &[MidiEvent]) -> Vec<u64> {
+        events.iter().map(|e| e.start_tick).collect()
+    }
+
+    #[test]
+    fn test_beat_clicks() {
+        let mut metronome = Metronome::new();
+        assert!(metronome.generate(&bar()).is_empty());
+
+        metronome.set_enabled(true);
+        let clicks = metronome.generate(&bar());
+        assert_eq!(ticks(&clicks), vec![0, 24, 48, 72]);
+        assert_eq!(clicks[0].note, 76);
+        assert_eq!(clicks[1].note, 77);
+        assert!(clicks[0].velocity > clicks[1].velocity);
+        assert_eq!(clicks[0].channel, DRUM_CHANNEL);
+    }
+
+    #[test]
+    fn test_volume_output_and_count_in() {
+        let mut metronome = Metronome::new();
+        metronome.set_enabled(true);
+        metronome.set_volume(0.5);
+        metronome.set_destination(Some(2));
+        let events = metronome.generate_scheduled(&bar(), 96);
+        assert_eq!(events.len(), 8);
+        assert_eq!(events[0].to_midi_bytes(), vec![0x99, 76, 60]);
+        assert_eq!(events[2].to_midi_bytes(), vec![0x99, 77, 50]);
+        assert_eq!(events[0].time_ticks, 96);
+        assert!(events.iter().all(|e| e.destination == Some(2)));
+        metronome.set_volume(0.0);
+        assert!(metronome.generate(&bar()).is_empty());
+
+        // Switched off, it still counts a recorder in
+        let mut metronome = Metronome::new();
+        let mut recorder = MidiRecorder::new(24);
+        recorder.set_count_in(1);
+        recorder.set_metronome(false);
+        recorder.start(0);
+        metronome.follow_recorder(&recorder);
+        assert_eq!(metronome.generate(&bar()).len(), 4);
+        recorder.tick(96);
+        metronome.follow_recorder(&recorder);
+        assert!(metronome.generate(&bar()).is_empty());
+    }
+
+    #[test]
+    fn test_clicks_follow_swing() {
+        let mut metronome = Metronome::new();
+        metronome.set_enabled(true);
+        metronome.set_swing(0.5);
+
+        // Off-beat eighths land where a swung track would put them
+        let clicks = metronome.generate(&bar());
+        assert_eq!(ticks(&clicks), vec![0, 15, 24, 39, 48, 63, 72, 87]);
+        assert!(clicks[1].velocity < clicks[2].velocity);
+    }
+
+    #[test]
+    fn test_groove_audition() {
+        let mut metronome = Metronome::new();
+        metronome.set_swing(0.5);
+
+        // Audition clicks even with the metronome off
+        assert!(metronome.toggle_audition());
+        let clicks = metronome.generate(&bar());
+        assert_eq!(clicks.len(), 16);
+        assert_eq!(ticks(&clicks)[..4], [0, 6, 15, 21]);
+
+        // A window that starts mid-beat still gets its swung clicks
+        let half = GeneratorContext {
+            tick: 12,
+            ticks_to_generate: 12,
+            ..Default::default()
+        };
+        assert_eq!(ticks(&metronome.generate(&half)), vec![3, 9]);
+    }
+}
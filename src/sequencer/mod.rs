@@ -8,16 +8,67 @@
 //! - Track system for multi-channel output
 //! - Clip system for sequenced and generated content
 //! - Pattern triggering with quantization
+//! - Random clip selection per track
+//! - Per-track pattern chains
+//! - Note repeat and latch for live input
+//! - Overlap resolution for identical notes from several sources
+//! - Metronome clicks that follow the swing
+//! - Groove templates with per-sixteenth timing and velocity
+//! - CC and channel pressure thinning for slow hardware
+//! - Per-track processor chains (velocity curve, humanize, range, channel,
+//!   echo, harmonizer, chordify)
+//! - Step-sequenced generator parameter lanes per track
+//! - Velocity gain staging against per-track target levels
+//! - Per-track sustain pedal modes
+//! - Round-robin note rotation across channels
+//! - MPE output with per-note pitch, pressure and slide
+//! - Key changes steered from a keyboard zone
+//! - Live input played thru armed tracks
 
+pub mod cc_thin;
+pub mod chain;
 pub mod clip;
+pub mod gain;
+pub mod groove;
+pub mod key_zone;
+pub mod latch;
+pub mod metronome;
+pub mod mpe;
+pub mod note_repeat;
+pub mod note_tracker;
+pub mod pedal;
+pub mod processor;
+pub mod round_robin;
 pub mod scheduler;
+pub mod shuffle;
+pub mod step_mod;
+pub mod thru;
 pub mod track;
 pub mod trigger;
 
-pub use clip::{Clip, ClipMode, ClipState};
+pub use cc_thin::{CcThinner, ThinStats};
+pub use chain::{ChainEntry, PatternChain};
+pub use clip::{Clip, ClipCc, ClipMode, ClipNote, ClipState};
+pub use gain::{GainMeter, GainSuggestion};
+pub use groove::{GrooveTemplate, BUILTIN_GROOVES};
+pub use key_zone::{KeyZone, KeyZoneMode};
+pub use latch::NoteLatch;
+pub use metronome::Metronome;
+pub use mpe::{MpeOutput, MpeZone};
+pub use note_repeat::{NoteRepeat, RepeatRate};
+pub use note_tracker::{NoteTracker, OverlapPolicy};
+pub use pedal::{PedalMode, SustainPedal};
+pub use processor::{Processor, ProcessorChain};
+pub use round_robin::{RoundRobin, RoundRobinVoice};
 pub use scheduler::{ScheduledEvent, Scheduler};
-pub use track::{Track, TrackState};
-pub use trigger::{FollowAction, QuantizeMode, TriggerQueue};
+pub use shuffle::ClipShuffle;
+pub use step_mod::StepLane;
+pub use thru::{ArmedInput, InputThru};
+pub use track::{
+    fold_into_range, OutputLayer, ProbabilityMode, Track, TrackState, TransposeMode, VoiceRoute, MAX_GATE_SCALE,
+    MIN_GATE_SCALE,
+};
+pub use trigger::{FollowAction, QuantizeMode, TriggerQueue, TriggerSource};
 
 /// Timing information for the sequencer
 #[derive(Debug, Clone, Copy)]
@@ -127,6 +178,51 @@ impl SequencerTiming {
     }
 }
 
+/// Pending and active fill requests ("fill now" performance action)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FillState {
+    /// Bar that will play the fill
+    fill_bar: Option<u64>,
+}
+
+impl FillState {
+    /// Create an idle fill state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request a fill. Requests in the last beat of a bar carry over to the next bar.
+    pub fn request(&mut self, timing: &SequencerTiming) {
+        let bar = timing.current_bar();
+        let in_last_beat = timing.current_beat() + 1 >= timing.beats_per_bar as u64;
+        self.fill_bar = Some(if in_last_beat { bar + 1 } else { bar });
+    }
+
+    /// Cancel a pending fill
+    pub fn cancel(&mut self) {
+        self.fill_bar = None;
+    }
+
+    /// Check whether a bar plays the fill
+    pub fn is_active(&self, bar: u64) -> bool {
+        self.fill_bar == Some(bar)
+    }
+
+    /// Check whether a fill is waiting or playing
+    pub fn is_pending(&self) -> bool {
+        self.fill_bar.is_some()
+    }
+
+    /// Clear the request once its bar has passed
+    pub fn update(&mut self, timing: &SequencerTiming) {
+        if let Some(bar) = self.fill_bar {
+            if timing.current_bar() > bar {
+                self.fill_bar = None;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,6 +272,25 @@ mod tests {
         assert_eq!(timing.ticks_to_next_bar(), 0);
     }
 
+    #[test]
+    fn test_fill_state() {
+        let mut timing = SequencerTiming::default();
+        let mut fill = FillState::new();
+
+        timing.position_ticks = 30; // Beat 2 of bar 1
+        fill.request(&timing);
+        assert!(fill.is_active(0));
+
+        timing.position_ticks = 96;
+        fill.update(&timing);
+        assert!(!fill.is_pending());
+
+        timing.position_ticks = 180; // Last beat of bar 2
+        fill.request(&timing);
+        assert!(!fill.is_active(1));
+        assert!(fill.is_active(2));
+    }
+
     #[test]
     fn test_advance_and_reset() {
         let mut timing = SequencerTiming::default();
@@ -0,0 +1,294 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! MPE (MIDI Polyphonic Expression) output.
+//!
+//! Each note gets a member channel of its own, so pitch bend, channel
+//! pressure and slide (CC74) act on that note alone. Channels are handed
+//! out like a round robin: the next free one in turn, stealing the next
+//! in turn when all are sounding. The zone is announced with the MPE
+//! Configuration Message and the member channels' bend range before the
+//! first note.
+
+use crate::generators::{MidiEvent, NoteExpression};
+
+use super::track::resolve_destination;
+use super::ScheduledEvent;
+
+/// Pitch bend range of MPE member channels in semitones (the MPE default)
+pub const DEFAULT_MPE_BEND_RANGE: u8 = 48;
+
+/// Slide controller
+pub const CC_SLIDE: u8 = 74;
+
+/// Ticks between expression updates
+pub const DEFAULT_EXPRESSION_STEP: u64 = 6;
+
+/// Which MPE zone the instrument listens on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MpeZone {
+    /// Manager on channel 1, members counting up from channel 2
+    Lower,
+    /// Manager on channel 16, members counting down from channel 15
+    Upper,
+}
+
+impl Default for MpeZone {
+    fn default() -> Self {
+        MpeZone::Lower
+    }
+}
+
+impl MpeZone {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "lower" | "low" => Some(MpeZone::Lower),
+            "upper" | "high" => Some(MpeZone::Upper),
+            _ => None,
+        }
+    }
+
+    /// Manager channel (0-15)
+    pub fn manager_channel(&self) -> u8 {
+        match self {
+            MpeZone::Lower => 0,
+            MpeZone::Upper => 15,
+        }
+    }
+}
+
+/// Plays notes on per-note channels with their expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct MpeOutput {
+    /// Zone the instrument listens on
+    zone: MpeZone,
+    /// Number of member channels (1-15)
+    members: u8,
+    /// Member channel bend range in semitones
+    bend_range: u8,
+    /// Ticks between expression updates
+    step: u64,
+    /// Output destination index (None = default output)
+    destination: Option<usize>,
+    /// Member to try first for the next note
+    next: usize,
+    /// Absolute tick each member's last note ends
+    busy_until: Vec<u64>,
+    /// Zone setup still to be sent
+    setup_pending: bool,
+}
+
+impl MpeOutput {
+    /// Create an output over some member channels
+    pub fn new(zone: MpeZone, members: u8) -> Self {
+        let members = members.clamp(1, 15);
+        Self {
+            zone,
+            members,
+            bend_range: DEFAULT_MPE_BEND_RANGE,
+            step: DEFAULT_EXPRESSION_STEP,
+            destination: None,
+            next: 0,
+            busy_until: vec![0; members as usize],
+            setup_pending: true,
+        }
+    }
+
+    /// Build from song config, playing on a named destination
+    pub fn from_config(config: &crate::config::MpeConfig, destination: Option<&str>, destinations: &[String]) -> Self {
+        Self::new(config.zone().unwrap_or_default(), config.members)
+            .with_bend_range(config.bend_range)
+            .with_destination(resolve_destination(destination, destinations))
+    }
+
+    /// Set the member channel bend range
+    pub fn with_bend_range(mut self, semitones: u8) -> Self {
+        self.bend_range = semitones.clamp(1, 96);
+        self
+    }
+
+    /// Set the ticks between expression updates
+    pub fn with_step(mut self, ticks: u64) -> Self {
+        self.step = ticks.max(1);
+        self
+    }
+
+    /// Set output destination
+    pub fn with_destination(mut self, destination: Option<usize>) -> Self {
+        self.destination = destination;
+        self
+    }
+
+    /// Get the zone
+    pub fn zone(&self) -> MpeZone {
+        self.zone
+    }
+
+    /// Get output destination
+    pub fn destination(&self) -> Option<usize> {
+        self.destination
+    }
+
+    /// Get the bend range
+    pub fn bend_range(&self) -> u8 {
+        self.bend_range
+    }
+
+    /// Member channels (0-15) in allocation order
+    pub fn member_channels(&self) -> Vec<u8> {
+        (1..=self.members)
+            .map(|i| match self.zone {
+                MpeZone::Lower => i,
+                MpeZone::Upper => 15 - i,
+            })
+            .collect()
+    }
+
+    /// Pitch bend value for a pitch offset in semitones
+    pub fn bend(&self, semitones: f64) -> i16 {
+        (semitones / self.bend_range as f64 * 8192.0).round().clamp(-8192.0, 8191.0) as i16
+    }
+
+    /// RPN messages setting a parameter on a channel
+    fn rpn(&self, tick: u64, channel: u8, rpn: u8, value: u8) -> Vec<ScheduledEvent> {
+        [(101, 0), (100, rpn), (6, value), (38, 0), (101, 127), (100, 127)]
+            .into_iter()
+            .map(|(cc, v)| ScheduledEvent::control_change(tick, channel, cc, v).with_destination(self.destination))
+            .collect()
+    }
+
+    /// Zone setup to send once before the first note: the MPE
+    /// Configuration Message, then the bend range of each member
+    pub fn take_setup(&mut self, tick: u64) -> Vec<ScheduledEvent> {
+        if !std::mem::take(&mut self.setup_pending) {
+            return Vec::new();
+        }
+        let mut events = self.rpn(tick, self.zone.manager_channel(), 6, self.members);
+        for channel in self.member_channels() {
+            events.extend(self.rpn(tick, channel, 0, self.bend_range));
+        }
+        events
+    }
+
+    /// Pick the member for a note sounding from `start` to `end` (absolute
+    /// ticks), returning its channel
+    pub fn assign(&mut self, start: u64, end: u64) -> u8 {
+        let count = self.busy_until.len();
+        let index = (0..count)
+            .map(|offset| (self.next + offset) % count)
+            .find(|&i| self.busy_until[i] <= start)
+            .unwrap_or(self.next % count);
+        self.busy_until[index] = end;
+        self.next = (index + 1) % count;
+        self.member_channels()[index]
+    }
+
+    /// Note on/off for an event on its own channel, with the expression
+    /// before, during and after the note
+    pub fn render(&mut self, event: &MidiEvent, base_tick: u64) -> Vec<ScheduledEvent> {
+        let start = base_tick + event.start_tick;
+        let end = start + event.duration_ticks;
+        let channel = self.assign(start, end);
+        let expression = &event.expression;
+
+        // Values at a point in the note, as MIDI data
+        let values = |tick: u64| {
+            let pitch = NoteExpression::at(&expression.pitch, tick).unwrap_or(0.0);
+            let pressure = NoteExpression::at(&expression.pressure, tick).unwrap_or(0.0);
+            let slide = NoteExpression::at(&expression.slide, tick).unwrap_or(0.5);
+            (
+                self.bend(pitch),
+                (pressure.clamp(0.0, 1.0) * 127.0).round() as u8,
+                (slide.clamp(0.0, 1.0) * 127.0).round() as u8,
+            )
+        };
+
+        // Every dimension is set before the note on, so nothing carries
+        // over from the channel's last note
+        let (mut bend, mut pressure, mut slide) = values(0);
+        let mut events = vec![
+            ScheduledEvent::pitch_bend(start, channel, bend),
+            ScheduledEvent::channel_pressure(start, channel, pressure),
+            ScheduledEvent::control_change(start, channel, CC_SLIDE, slide),
+            ScheduledEvent::note_on(start, channel, event.note, event.velocity),
+        ];
+
+        let mut offset = self.step;
+        while offset < event.duration_ticks {
+            let (b, p, s) = values(offset);
+            let tick = start + offset;
+            if b != bend {
+                events.push(ScheduledEvent::pitch_bend(tick, channel, b));
+                bend = b;
+            }
+            if p != pressure {
+                events.push(ScheduledEvent::channel_pressure(tick, channel, p));
+                pressure = p;
+            }
+            if s != slide {
+                events.push(ScheduledEvent::control_change(tick, channel, CC_SLIDE, s));
+                slide = s;
+            }
+            offset += self.step;
+        }
+
+        events.push(ScheduledEvent::note_off(end, channel, event.note));
+        events.into_iter().map(|e| e.with_destination(self.destination)).collect()
+    }
+
+    /// Start allocating from the first member again
+    pub fn reset(&mut self) {
+        self.next = 0;
+        self.busy_until.iter_mut().for_each(|tick| *tick = 0);
+        self.setup_pending = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer::scheduler::MidiMessageType;
+
+    #[test]
+    fn test_per_note_channels_and_setup() {
+        let mut mpe = MpeOutput::new(MpeZone::Lower, 3);
+        assert_eq!(mpe.member_channels(), vec![1, 2, 3]);
+        assert_eq!(MpeOutput::new(MpeZone::Upper, 2).member_channels(), vec![14, 13]);
+
+        let setup = mpe.take_setup(0);
+        let bytes: Vec<Vec<u8>> = setup.iter().take(3).map(|e| e.to_midi_bytes()).collect();
+        assert_eq!(bytes, vec![vec![0xB0, 101, 0], vec![0xB0, 100, 6], vec![0xB0, 6, 3]]);
+        assert_eq!(setup.len(), 4 * 6);
+        assert!(mpe.take_setup(0).is_empty());
+
+        // A chord spreads over the members; a fourth note steals the next
+        let chord: Vec<u8> = (0..4).map(|_| mpe.assign(0, 96)).collect();
+        assert_eq!(chord, vec![1, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_expression_is_rendered_on_the_note_channel() {
+        let mut mpe = MpeOutput::new(MpeZone::Lower, 15).with_step(12);
+        let expression = NoteExpression {
+            pitch: vec![(0, 0.0), (48, 0.5)],
+            pressure: vec![(0, 1.0)],
+            ..NoteExpression::default()
+        };
+        let note = MidiEvent::new(60, 100, 0, 48).with_expression(expression);
+        let events = mpe.render(&note, 96);
+
+        assert!(events.iter().all(|e| e.channel == 1));
+        assert_eq!(events[3].to_midi_bytes(), vec![0x91, 60, 100]);
+        assert_eq!(events[1].to_midi_bytes(), vec![0xD1, 127]);
+        assert_eq!(events[2].to_midi_bytes(), vec![0xB1, CC_SLIDE, 64]);
+        let bends: Vec<(u64, i16)> = events
+            .iter()
+            .filter(|e| e.message_type == MidiMessageType::PitchBend)
+            .map(|e| (e.time_ticks, ((e.data2 as i16) << 7 | e.data1 as i16) - 8192))
+            .collect();
+        assert_eq!(bends, vec![(96, 0), (108, 21), (120, 43), (132, 64)]);
+        assert_eq!(events.last().unwrap().to_midi_bytes(), vec![0x81, 60, 0]);
+        assert_eq!(events.last().unwrap().time_ticks, 144);
+    }
+}
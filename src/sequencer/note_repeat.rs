@@ -0,0 +1,266 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Note repeat for live input.
+//!
+//! While note repeat is enabled, held notes retrigger on a synced grid
+//! (1/8, 1/16 or 1/32 with optional swing). Aftertouch pressure can take
+//! over the repeat velocity, MPC-style.
+
+use std::collections::BTreeMap;
+
+use crate::midi::MidiMessage;
+
+use super::ScheduledEvent;
+
+/// Repeat grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatRate {
+    /// Eighth notes
+    Eighth,
+    /// Sixteenth notes
+    Sixteenth,
+    /// Thirty-second notes
+    ThirtySecond,
+}
+
+impl Default for RepeatRate {
+    fn default() -> Self {
+        RepeatRate::Sixteenth
+    }
+}
+
+impl RepeatRate {
+    /// Get ticks per repeat step
+    pub fn ticks(&self, ppqn: u32) -> u64 {
+        let ppqn = ppqn as u64;
+        match self {
+            RepeatRate::Eighth => ppqn / 2,
+            RepeatRate::Sixteenth => ppqn / 4,
+            RepeatRate::ThirtySecond => ppqn / 8,
+        }
+        .max(1)
+    }
+
+    /// Parse from a string like "1/16"
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim() {
+            "1/8" | "8" | "eighth" => Some(RepeatRate::Eighth),
+            "1/16" | "16" | "sixteenth" => Some(RepeatRate::Sixteenth),
+            "1/32" | "32" | "thirty-second" => Some(RepeatRate::ThirtySecond),
+            _ => None,
+        }
+    }
+}
+
+/// Note repeat processor for live input
+#[derive(Debug, Clone)]
+pub struct NoteRepeat {
+    /// Whether repeat is active
+    enabled: bool,
+    /// Repeat grid
+    rate: RepeatRate,
+    /// Swing amount (0.0 to 1.0)
+    swing: f64,
+    /// Gate length as a fraction of the step
+    gate: f64,
+    /// Let aftertouch pressure set repeat velocity
+    pressure_velocity: bool,
+    /// Held notes: (channel, note) -> velocity
+    held: BTreeMap<(u8, u8), u8>,
+}
+
+impl NoteRepeat {
+    /// Create a new (disabled) note repeat
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            rate: RepeatRate::default(),
+            swing: 0.0,
+            gate: 0.5,
+            pressure_velocity: true,
+            held: BTreeMap::new(),
+        }
+    }
+
+    /// Check if enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Enable or disable (disabling releases held notes)
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.held.clear();
+        }
+    }
+
+    /// Get repeat rate
+    pub fn rate(&self) -> RepeatRate {
+        self.rate
+    }
+
+    /// Set repeat rate
+    pub fn set_rate(&mut self, rate: RepeatRate) {
+        self.rate = rate;
+    }
+
+    /// Get swing
+    pub fn swing(&self) -> f64 {
+        self.swing
+    }
+
+    /// Set swing
+    pub fn set_swing(&mut self, swing: f64) {
+        self.swing = swing.clamp(0.0, 1.0);
+    }
+
+    /// Set gate length as a fraction of the step
+    pub fn set_gate(&mut self, gate: f64) {
+        self.gate = gate.clamp(0.05, 1.0);
+    }
+
+    /// Set whether pressure controls repeat velocity
+    pub fn set_pressure_velocity(&mut self, enabled: bool) {
+        self.pressure_velocity = enabled;
+    }
+
+    /// Get held notes as (channel, note, velocity)
+    pub fn held_notes(&self) -> Vec<(u8, u8, u8)> {
+        self.held.iter().map(|(&(ch, note), &vel)| (ch, note, vel)).collect()
+    }
+
+    /// Process an incoming message. Returns true if the message was consumed.
+    pub fn process(&mut self, message: &MidiMessage) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        match *message {
+            MidiMessage::NoteOn { channel, note, velocity } => {
+                self.held.insert((channel, note), velocity);
+                true
+            }
+            MidiMessage::NoteOff { channel, note, .. } => {
+                self.held.remove(&(channel, note)).is_some()
+            }
+            MidiMessage::PolyAftertouch { channel, note, pressure } if self.pressure_velocity => {
+                if let Some(vel) = self.held.get_mut(&(channel, note)) {
+                    *vel = pressure.max(1);
+                    true
+                } else {
+                    false
+                }
+            }
+            MidiMessage::ChannelAftertouch { channel, pressure } if self.pressure_velocity => {
+                let mut consumed = false;
+                for (_, vel) in self.held.range_mut((channel, 0)..=(channel, 127)) {
+                    *vel = pressure.max(1);
+                    consumed = true;
+                }
+                consumed
+            }
+            _ => false,
+        }
+    }
+
+    /// Generate repeats for held notes in the tick range [start, end)
+    pub fn generate(&self, start_tick: u64, end_tick: u64, ppqn: u32) -> Vec<ScheduledEvent> {
+        let mut events = Vec::new();
+        if !self.enabled || self.held.is_empty() || end_tick <= start_tick {
+            return events;
+        }
+
+        let step = self.rate.ticks(ppqn);
+        let swing_offset = (step as f64 * self.swing * 0.5) as u64;
+        let gate = ((step as f64 * self.gate) as u64).max(1);
+
+        // Start one step early so a swung off-beat from the previous step is not missed
+        let mut index = (start_tick / step).saturating_sub(1);
+        loop {
+            let mut tick = index * step;
+            if index % 2 == 1 {
+                tick += swing_offset;
+            }
+            if tick >= end_tick {
+                break;
+            }
+            if tick >= start_tick {
+                for (&(channel, note), &velocity) in &self.held {
+                    events.push(ScheduledEvent::note_on(tick, channel, note, velocity));
+                    events.push(ScheduledEvent::note_off(tick + gate, channel, note));
+                }
+            }
+            index += 1;
+        }
+
+        events
+    }
+}
+
+impl Default for NoteRepeat {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer::scheduler::MidiMessageType;
+
+    fn note_ons(events: &[ScheduledEvent]) -> Vec<u64> {
+        events
+            .iter()
+            .filter(|e| e.message_type == MidiMessageType::NoteOn)
+            .map(|e| e.time_ticks)
+            .collect()
+    }
+
+    #[test]
+    fn test_disabled_passes_through() {
+        let mut repeat = NoteRepeat::new();
+        let msg = MidiMessage::NoteOn { channel: 9, note: 36, velocity: 100 };
+        assert!(!repeat.process(&msg));
+        assert!(repeat.generate(0, 96, 24).is_empty());
+    }
+
+    #[test]
+    fn test_repeat_rates() {
+        let mut repeat = NoteRepeat::new();
+        repeat.set_enabled(true);
+        repeat.process(&MidiMessage::NoteOn { channel: 9, note: 36, velocity: 100 });
+
+        assert_eq!(note_ons(&repeat.generate(0, 24, 24)), vec![0, 6, 12, 18]);
+
+        repeat.set_rate(RepeatRate::Eighth);
+        assert_eq!(note_ons(&repeat.generate(0, 24, 24)), vec![0, 12]);
+
+        repeat.process(&MidiMessage::NoteOff { channel: 9, note: 36, velocity: 0 });
+        assert!(repeat.generate(24, 48, 24).is_empty());
+    }
+
+    #[test]
+    fn test_swing_and_pressure() {
+        let mut repeat = NoteRepeat::new();
+        repeat.set_enabled(true);
+        repeat.set_rate(RepeatRate::Eighth);
+        repeat.set_swing(1.0);
+        repeat.process(&MidiMessage::NoteOn { channel: 9, note: 38, velocity: 90 });
+        repeat.process(&MidiMessage::PolyAftertouch { channel: 9, note: 38, pressure: 40 });
+
+        let events = repeat.generate(0, 48, 24);
+        assert_eq!(note_ons(&events), vec![0, 18, 24, 42]);
+        assert_eq!(events[0].data2, 40);
+
+        // Swung step from the previous window is still emitted
+        assert_eq!(note_ons(&repeat.generate(15, 20, 24)), vec![18]);
+    }
+
+    #[test]
+    fn test_rate_from_str() {
+        assert_eq!(RepeatRate::from_str("1/32"), Some(RepeatRate::ThirtySecond));
+        assert_eq!(RepeatRate::from_str("1/5"), None);
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Sounding-note tracker with overlap resolution.
+//!
+//! Clips, generators and MIDI thru can all play the same note on the same
+//! channel at once. Sent as-is, the first note-off cuts every copy short and
+//! the rest arrive as dangling note-offs. The tracker sits between the
+//! scheduler and the outputs and resolves overlapping identical notes with a
+//! per-track policy, so each sounding note gets exactly one note-off.
+
+use std::collections::HashMap;
+
+use super::scheduler::{MidiMessageType, ScheduledEvent};
+
+/// What to do when a note starts while the same note is already sounding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// End the sounding note and start it again
+    Retrigger,
+    /// Keep the sounding note going until the last copy ends
+    Extend,
+    /// Drop the new note (and its note-off)
+    Ignore,
+}
+
+impl Default for OverlapPolicy {
+    fn default() -> Self {
+        OverlapPolicy::Retrigger
+    }
+}
+
+impl OverlapPolicy {
+    /// Parse from a string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "retrigger" => Some(OverlapPolicy::Retrigger),
+            "extend" | "legato" => Some(OverlapPolicy::Extend),
+            "ignore" => Some(OverlapPolicy::Ignore),
+            _ => None,
+        }
+    }
+}
+
+/// Output, channel and note of a sounding note
+type NoteKey = (Option<usize>, u8, u8);
+
+/// State of one sounding note
+#[derive(Debug, Clone, Copy, Default)]
+struct Voice {
+    /// Note-ons still waiting for their note-off
+    open: u32,
+    /// Dropped note-ons whose note-offs must be dropped too
+    ignored: u32,
+}
+
+/// Tracks sounding notes and resolves overlaps
+#[derive(Debug, Clone, Default)]
+pub struct NoteTracker {
+    /// Sounding notes
+    voices: HashMap<NoteKey, Voice>,
+    /// Policy per track index
+    policies: HashMap<usize, OverlapPolicy>,
+    /// Policy for tracks without their own (and for thru)
+    default_policy: OverlapPolicy,
+}
+
+impl NoteTracker {
+    /// Create a new tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the policy for a track
+    pub fn set_policy(&mut self, track_index: usize, policy: OverlapPolicy) {
+        self.policies.insert(track_index, policy);
+    }
+
+    /// Set the policy for events without a track policy
+    pub fn set_default_policy(&mut self, policy: OverlapPolicy) {
+        self.default_policy = policy;
+    }
+
+    /// Get the policy that applies to a track
+    pub fn policy(&self, track_index: Option<usize>) -> OverlapPolicy {
+        track_index
+            .and_then(|t| self.policies.get(&t).copied())
+            .unwrap_or(self.default_policy)
+    }
+
+    /// Number of distinct notes sounding
+    pub fn sounding(&self) -> usize {
+        self.voices.values().filter(|v| v.open > 0).count()
+    }
+
+    /// Pass an outgoing event through, returning the events to send instead
+    pub fn process(&mut self, event: ScheduledEvent) -> Vec<ScheduledEvent> {
+        let is_on = event.message_type == MidiMessageType::NoteOn && event.data2 > 0;
+        let is_off = event.message_type == MidiMessageType::NoteOff
+            || (event.message_type == MidiMessageType::NoteOn && event.data2 == 0);
+        if !is_on && !is_off {
+            return vec![event];
+        }
+
+        let key = (event.destination, event.channel, event.data1);
+        let policy = self.policy(event.track_index);
+        let voice = self.voices.entry(key).or_default();
+        let mut out = Vec::new();
+
+        if is_on {
+            if voice.open == 0 {
+                voice.open = 1;
+                out.push(event);
+            } else {
+                match policy {
+                    OverlapPolicy::Retrigger => {
+                        voice.open += 1;
+                        out.push(note_off_for(&event));
+                        out.push(event);
+                    }
+                    OverlapPolicy::Extend => voice.open += 1,
+                    OverlapPolicy::Ignore => voice.ignored += 1,
+                }
+            }
+        } else if voice.open > 0 {
+            // Only the last open copy's note-off reaches the output
+            voice.open -= 1;
+            if voice.open == 0 {
+                out.push(event);
+            }
+        } else if voice.ignored > 0 {
+            voice.ignored -= 1;
+        }
+        // Any other note-off is dangling: nothing is sounding to end
+
+        if voice.open == 0 && voice.ignored == 0 {
+            self.voices.remove(&key);
+        }
+        out
+    }
+
+    /// Note-offs for everything sounding, forgetting all notes
+    pub fn release_all(&mut self, time_ticks: u64) -> Vec<ScheduledEvent> {
+        let mut keys: Vec<NoteKey> = self
+            .voices
+            .drain()
+            .filter(|(_, voice)| voice.open > 0)
+            .map(|(key, _)| key)
+            .collect();
+        keys.sort();
+        keys.into_iter()
+            .map(|(destination, channel, note)| {
+                ScheduledEvent::note_off(time_ticks, channel, note).with_destination(destination)
+            })
+            .collect()
+    }
+}
+
+/// A note-off matching a note-on
+fn note_off_for(event: &ScheduledEvent) -> ScheduledEvent {
+    let mut off = ScheduledEvent::note_off(event.time_ticks, event.channel, event.data1)
+        .with_destination(event.destination);
+    off.time_micros = event.time_micros;
+    off.track_index = event.track_index;
+    off
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(track: usize) -> ScheduledEvent {
+        ScheduledEvent::note_on(0, 0, 60, 100).with_track(track)
+    }
+
+    fn off(track: usize) -> ScheduledEvent {
+        ScheduledEvent::note_off(0, 0, 60).with_track(track)
+    }
+
+    fn types(events: &[ScheduledEvent]) -> Vec<MidiMessageType> {
+        events.iter().map(|e| e.message_type).collect()
+    }
+
+    #[test]
+    fn test_overlap_policies() {
+        use MidiMessageType::{NoteOff, NoteOn};
+
+        let mut tracker = NoteTracker::new();
+        tracker.set_policy(1, OverlapPolicy::Extend);
+        tracker.set_policy(2, OverlapPolicy::Ignore);
+
+        // Retrigger: restart, then a single note-off at the end
+        assert_eq!(types(&tracker.process(on(0))), vec![NoteOn]);
+        assert_eq!(types(&tracker.process(on(0))), vec![NoteOff, NoteOn]);
+        assert!(tracker.process(off(0)).is_empty());
+        assert_eq!(types(&tracker.process(off(0))), vec![NoteOff]);
+        assert_eq!(tracker.sounding(), 0);
+
+        // Extend: the second copy only lengthens the first
+        assert_eq!(types(&tracker.process(on(1))), vec![NoteOn]);
+        assert!(tracker.process(on(1)).is_empty());
+        assert!(tracker.process(off(1)).is_empty());
+        assert_eq!(types(&tracker.process(off(1))), vec![NoteOff]);
+
+        // Ignore: the first note-off ends it, the dropped copy's is swallowed
+        assert_eq!(types(&tracker.process(on(2))), vec![NoteOn]);
+        assert!(tracker.process(on(2)).is_empty());
+        assert_eq!(types(&tracker.process(off(2))), vec![NoteOff]);
+        assert!(tracker.process(off(2)).is_empty());
+
+        // Dangling note-offs never reach the output
+        assert!(tracker.process(off(0)).is_empty());
+    }
+
+    #[test]
+    fn test_release_all() {
+        let mut tracker = NoteTracker::new();
+        tracker.process(ScheduledEvent::note_on(0, 1, 64, 100));
+        tracker.process(ScheduledEvent::note_on(0, 0, 60, 100).with_destination(Some(2)));
+        tracker.process(ScheduledEvent::control_change(0, 0, 7, 100));
+
+        let released = tracker.release_all(48);
+        assert_eq!(released.len(), 2);
+        assert_eq!(released[0].to_midi_bytes(), vec![0x81, 64, 0]);
+        assert_eq!(released[1].destination, Some(2));
+        assert_eq!(tracker.sounding(), 0);
+    }
+}
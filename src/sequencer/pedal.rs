@@ -0,0 +1,256 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Per-track sustain pedal behavior.
+//!
+//! The sustain pedal (CC 64) means different things for different parts:
+//! ordinary sustain for a keys track, sostenuto for a bass that should hold
+//! only the notes already down, a latch toggle for a pad, or a freeze that
+//! holds the generator's current notes until the pedal comes up.
+
+use std::collections::BTreeSet;
+
+use crate::generators::MidiEvent;
+use crate::midi::MidiMessage;
+
+use super::latch::NoteLatch;
+
+/// Sustain pedal controller number
+pub const CC_SUSTAIN: u8 = 64;
+
+/// What the sustain pedal does on a track
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PedalMode {
+    /// Pass CC 64 on to the instrument
+    Thru,
+    /// Hold released notes until the pedal comes up
+    Sustain,
+    /// Hold only the notes that were down when the pedal went down
+    Sostenuto,
+    /// Each press toggles the track's note latch
+    LatchToggle,
+    /// Freeze the generator's current notes while the pedal is down
+    HoldOutput,
+}
+
+impl Default for PedalMode {
+    fn default() -> Self {
+        PedalMode::Thru
+    }
+}
+
+impl PedalMode {
+    /// Parse from a string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "thru" | "through" | "off" => Some(PedalMode::Thru),
+            "sustain" => Some(PedalMode::Sustain),
+            "sostenuto" => Some(PedalMode::Sostenuto),
+            "latch" | "latch_toggle" => Some(PedalMode::LatchToggle),
+            "hold" | "hold_output" | "freeze" => Some(PedalMode::HoldOutput),
+            _ => None,
+        }
+    }
+}
+
+/// Sustain pedal state for one track
+#[derive(Debug, Clone, Default)]
+pub struct SustainPedal {
+    /// Pedal behavior
+    mode: PedalMode,
+    /// Pedal is down
+    down: bool,
+    /// Keys currently held: (channel, note)
+    keys: BTreeSet<(u8, u8)>,
+    /// Notes the pedal is allowed to hold (sostenuto; sustain holds any)
+    holdable: BTreeSet<(u8, u8)>,
+    /// Released notes waiting for the pedal to come up
+    held: BTreeSet<(u8, u8)>,
+    /// Generator notes frozen by hold mode
+    frozen: Vec<MidiEvent>,
+    /// Generator notes from the last buffer (frozen when the pedal goes down)
+    last_output: Vec<MidiEvent>,
+}
+
+impl SustainPedal {
+    /// Create a pedal with a mode
+    pub fn new(mode: PedalMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    /// Get pedal mode
+    pub fn mode(&self) -> PedalMode {
+        self.mode
+    }
+
+    /// Set pedal mode, releasing anything the old mode was holding
+    pub fn set_mode(&mut self, mode: PedalMode) -> Vec<MidiMessage> {
+        let released = self.release();
+        self.mode = mode;
+        self.down = false;
+        released
+    }
+
+    /// Check if the pedal is down
+    pub fn is_down(&self) -> bool {
+        self.down
+    }
+
+    /// Check if generator output is frozen
+    pub fn is_holding_output(&self) -> bool {
+        self.mode == PedalMode::HoldOutput && self.down
+    }
+
+    /// Process an incoming message, returning the messages to send on
+    pub fn process(&mut self, message: &MidiMessage, latch: &mut NoteLatch) -> Vec<MidiMessage> {
+        match *message {
+            MidiMessage::ControlChange { controller: CC_SUSTAIN, value, .. } if self.mode != PedalMode::Thru => {
+                self.pedal(value >= 64, latch)
+            }
+            MidiMessage::NoteOn { channel, note, velocity } if velocity > 0 => {
+                self.keys.insert((channel, note));
+                // Replaying a held note ends the held one first
+                if self.held.remove(&(channel, note)) {
+                    vec![MidiMessage::NoteOff { channel, note, velocity: 0 }, message.clone()]
+                } else {
+                    vec![message.clone()]
+                }
+            }
+            MidiMessage::NoteOn { channel, note, .. } | MidiMessage::NoteOff { channel, note, .. } => {
+                self.keys.remove(&(channel, note));
+                let holds = match self.mode {
+                    PedalMode::Sustain => self.down,
+                    PedalMode::Sostenuto => self.down && self.holdable.contains(&(channel, note)),
+                    _ => false,
+                };
+                if holds {
+                    self.held.insert((channel, note));
+                    Vec::new()
+                } else {
+                    vec![message.clone()]
+                }
+            }
+            _ => vec![message.clone()],
+        }
+    }
+
+    /// Pedal moved down or up
+    fn pedal(&mut self, down: bool, latch: &mut NoteLatch) -> Vec<MidiMessage> {
+        if down == self.down {
+            return Vec::new();
+        }
+        self.down = down;
+        match (self.mode, down) {
+            (PedalMode::Sostenuto, true) => {
+                self.holdable = self.keys.clone();
+                Vec::new()
+            }
+            (PedalMode::LatchToggle, true) => latch.set_enabled(!latch.is_enabled()),
+            (PedalMode::HoldOutput, true) => {
+                self.frozen = self.last_output.clone();
+                Vec::new()
+            }
+            (_, false) => self.release(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Release held notes and frozen output
+    fn release(&mut self) -> Vec<MidiMessage> {
+        self.holdable.clear();
+        self.frozen.clear();
+        std::mem::take(&mut self.held)
+            .into_iter()
+            .map(|(channel, note)| MidiMessage::NoteOff { channel, note, velocity: 0 })
+            .collect()
+    }
+
+    /// Pass a buffer of generator output through the pedal. While frozen the
+    /// notes sounding when the pedal went down play for the whole buffer
+    /// instead.
+    pub fn process_output(&mut self, events: Vec<MidiEvent>, buffer_ticks: u64) -> Vec<MidiEvent> {
+        if self.mode != PedalMode::HoldOutput {
+            return events;
+        }
+        if !self.down {
+            if !events.is_empty() {
+                self.last_output = events.clone();
+            }
+            return events;
+        }
+
+        let mut notes = BTreeSet::new();
+        self.frozen
+            .iter()
+            .filter(|e| e.velocity > 0 && notes.insert((e.channel, e.note)))
+            .map(|e| MidiEvent::new(e.note, e.velocity, 0, buffer_ticks.max(1)).with_channel(e.channel))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(note: u8) -> MidiMessage {
+        MidiMessage::NoteOn { channel: 0, note, velocity: 100 }
+    }
+
+    fn off(note: u8) -> MidiMessage {
+        MidiMessage::NoteOff { channel: 0, note, velocity: 0 }
+    }
+
+    fn pedal(value: u8) -> MidiMessage {
+        MidiMessage::ControlChange { channel: 0, controller: CC_SUSTAIN, value }
+    }
+
+    #[test]
+    fn test_sustain_and_sostenuto() {
+        let mut latch = NoteLatch::new();
+        let mut sustain = SustainPedal::new(PedalMode::Sustain);
+        assert!(sustain.process(&pedal(127), &mut latch).is_empty());
+        sustain.process(&on(60), &mut latch);
+        assert!(sustain.process(&off(60), &mut latch).is_empty());
+        assert_eq!(sustain.process(&pedal(0), &mut latch), vec![off(60)]);
+
+        // Sostenuto only holds the notes down when the pedal went down
+        let mut sostenuto = SustainPedal::new(PedalMode::Sostenuto);
+        sostenuto.process(&on(48), &mut latch);
+        sostenuto.process(&pedal(127), &mut latch);
+        sostenuto.process(&on(64), &mut latch);
+        assert!(sostenuto.process(&off(48), &mut latch).is_empty());
+        assert_eq!(sostenuto.process(&off(64), &mut latch), vec![off(64)]);
+        assert_eq!(sostenuto.process(&pedal(0), &mut latch), vec![off(48)]);
+
+        // Thru leaves the pedal to the instrument
+        let mut thru = SustainPedal::default();
+        assert_eq!(thru.process(&pedal(127), &mut latch), vec![pedal(127)]);
+    }
+
+    #[test]
+    fn test_latch_toggle_and_hold_output() {
+        let mut latch = NoteLatch::new();
+        let mut toggle = SustainPedal::new(PedalMode::LatchToggle);
+        toggle.process(&pedal(127), &mut latch);
+        assert!(latch.is_enabled());
+        toggle.process(&pedal(0), &mut latch);
+        assert!(latch.is_enabled());
+        toggle.process(&pedal(100), &mut latch);
+        assert!(!latch.is_enabled());
+
+        let mut hold = SustainPedal::new(PedalMode::HoldOutput);
+        let chord = vec![MidiEvent::new(60, 90, 0, 12), MidiEvent::new(64, 80, 12, 12)];
+        assert_eq!(hold.process_output(chord, 24).len(), 2);
+        hold.process(&pedal(127), &mut latch);
+        assert!(hold.is_holding_output());
+
+        let frozen = hold.process_output(vec![MidiEvent::new(72, 100, 0, 6)], 24);
+        assert_eq!(frozen, vec![MidiEvent::new(60, 90, 0, 24), MidiEvent::new(64, 80, 0, 24)]);
+
+        hold.process(&pedal(0), &mut latch);
+        assert_eq!(hold.process_output(vec![MidiEvent::new(72, 100, 0, 6)], 24).len(), 1);
+    }
+}
@@ -0,0 +1,453 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Per-track event processors.
+//!
+//! A processor chain shapes what a track plays after its generator and
+//! clips, so output can be tamed without touching each generator: a
+//! velocity curve, random humanization, a note range and a channel remap.
+//! Processors run in the order listed, after transpose, swing and groove.
+//! Output layers, round robin and MPE pick their own channels, so a remap
+//! only moves notes that go out on the track's channel.
+//!
+//! MIDI effects add notes rather than shaping them: an echo repeats each
+//! note with falling velocity, a harmonizer adds notes a number of scale
+//! degrees away in the song key, and chordify stacks a fixed chord shape
+//! on every note. The same chain runs on live notes played into a track;
+//! it remembers what each key press became so its note off releases all
+//! of it, even if the key changes in between.
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::generators::MidiEvent;
+use crate::midi::MidiMessage;
+use crate::music::Key;
+
+use super::track::fold_into_range;
+
+/// Largest humanize timing spread, in ticks
+pub const MAX_HUMANIZE_TICKS: u64 = 12;
+
+/// Most repeats from one echo
+pub const MAX_ECHO_REPEATS: u8 = 16;
+
+/// Semitones above the played note for a named chord shape ("minor",
+/// "maj7", "power", ...) or a list like "0,3,7"
+pub fn chord_shape(name: &str) -> Option<Vec<u8>> {
+    let shape: &[u8] = match name.trim().to_lowercase().as_str() {
+        "major" | "maj" => &[0, 4, 7],
+        "minor" | "min" | "m" => &[0, 3, 7],
+        "dim" => &[0, 3, 6],
+        "aug" => &[0, 4, 8],
+        "sus2" => &[0, 2, 7],
+        "sus4" => &[0, 5, 7],
+        "power" | "5" => &[0, 7, 12],
+        "octave" => &[0, 12],
+        "maj7" => &[0, 4, 7, 11],
+        "min7" | "m7" => &[0, 3, 7, 10],
+        "dom7" | "7" => &[0, 4, 7, 10],
+        list => {
+            let intervals: Vec<u8> = list
+                .split(',')
+                .map(|i| i.trim().parse::<u8>().ok().filter(|i| *i <= 48))
+                .collect::<Option<_>>()?;
+            return Some(intervals);
+        }
+    };
+    Some(shape.to_vec())
+}
+
+/// One step of a processor chain
+#[derive(Debug, Clone, PartialEq)]
+pub enum Processor {
+    /// Bend velocities along a power curve into a range: below 1.0 lifts
+    /// soft notes, above 1.0 pushes them down
+    VelocityCurve { curve: f64, min: u8, max: u8 },
+    /// Random timing (ticks) and velocity spread
+    Humanize { timing: u64, velocity: u8 },
+    /// Keep notes within a range, folding by octaves or dropping the rest
+    NoteRange { low: u8, high: u8, fold: bool },
+    /// Move notes from one channel (None = any) to another (0-15)
+    ChannelRemap { from: Option<u8>, to: u8 },
+    /// Repeat each note every `delay` ticks, each repeat's velocity
+    /// scaled by `decay`
+    Echo { delay: u64, repeats: u8, decay: f64 },
+    /// Add notes this many scale degrees from each note, in the song key
+    Harmonize { degrees: Vec<i32> },
+    /// Add notes this many semitones above each note
+    Chordify { intervals: Vec<u8> },
+}
+
+impl Processor {
+    /// Create a velocity curve, clamping its values
+    pub fn velocity_curve(curve: f64, min: u8, max: u8) -> Self {
+        let (min, max) = (min.clamp(1, 127), max.clamp(1, 127));
+        Processor::VelocityCurve {
+            curve: curve.clamp(0.1, 10.0),
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    /// Create a humanizer, clamping its spread
+    pub fn humanize(timing: u64, velocity: u8) -> Self {
+        Processor::Humanize {
+            timing: timing.min(MAX_HUMANIZE_TICKS),
+            velocity: velocity.min(127),
+        }
+    }
+
+    /// Create a note range
+    pub fn note_range(low: u8, high: u8, fold: bool) -> Self {
+        let (low, high) = (low.min(127), high.min(127));
+        Processor::NoteRange {
+            low: low.min(high),
+            high: low.max(high),
+            fold,
+        }
+    }
+
+    /// Create a channel remap
+    pub fn channel_remap(from: Option<u8>, to: u8) -> Self {
+        Processor::ChannelRemap {
+            from: from.map(|c| c.min(15)),
+            to: to.min(15),
+        }
+    }
+
+    /// Create an echo, clamping its values
+    pub fn echo(delay: u64, repeats: u8, decay: f64) -> Self {
+        Processor::Echo {
+            delay: delay.max(1),
+            repeats: repeats.clamp(1, MAX_ECHO_REPEATS),
+            decay: decay.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Create a harmonizer (degree 0, the played note, is left out)
+    pub fn harmonize(degrees: &[i32]) -> Self {
+        Processor::Harmonize {
+            degrees: degrees.iter().copied().filter(|d| *d != 0).collect(),
+        }
+    }
+
+    /// Create a chordifier (interval 0, the played note, is left out)
+    pub fn chordify(intervals: &[u8]) -> Self {
+        Processor::Chordify {
+            intervals: intervals.iter().copied().filter(|i| *i != 0).collect(),
+        }
+    }
+
+    /// Processor name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Processor::VelocityCurve { .. } => "velocity_curve",
+            Processor::Humanize { .. } => "humanize",
+            Processor::NoteRange { .. } => "note_range",
+            Processor::ChannelRemap { .. } => "channel_remap",
+            Processor::Echo { .. } => "echo",
+            Processor::Harmonize { .. } => "harmonize",
+            Processor::Chordify { .. } => "chordify",
+        }
+    }
+
+    /// Process one event into `out`, adding nothing to drop it
+    fn apply(&self, mut event: MidiEvent, key: &Key, rng: &mut StdRng, out: &mut Vec<MidiEvent>) {
+        match *self {
+            Processor::VelocityCurve { curve, min, max } => {
+                if event.velocity > 0 {
+                    let shaped = (event.velocity as f64 / 127.0).powf(curve);
+                    event.velocity = (min as f64 + (max - min) as f64 * shaped).round() as u8;
+                }
+            }
+            Processor::Humanize { timing, velocity } => {
+                let ticks = if timing > 0 { rng.gen_range(-(timing as i64)..=timing as i64) } else { 0 };
+                let velocity = if velocity > 0 {
+                    rng.gen_range(-(velocity as i16)..=velocity as i16)
+                } else {
+                    0
+                };
+                event.humanize(ticks, velocity);
+            }
+            Processor::NoteRange { low, high, fold } => {
+                if event.note < low || event.note > high {
+                    if !fold {
+                        return;
+                    }
+                    event.note = fold_into_range(event.note, low, high);
+                }
+            }
+            Processor::ChannelRemap { from, to } => {
+                if from.map_or(true, |from| from == event.channel) {
+                    event.channel = to;
+                }
+            }
+            Processor::Echo { delay, repeats, decay } => {
+                out.push(event.clone());
+                for repeat in 1..=repeats as u64 {
+                    let velocity = (event.velocity as f64 * decay.powi(repeat as i32)).round() as u8;
+                    if velocity == 0 {
+                        break;
+                    }
+                    out.push(MidiEvent {
+                        velocity,
+                        start_tick: event.start_tick + delay * repeat,
+                        ..event.clone()
+                    });
+                }
+                return;
+            }
+            Processor::Harmonize { ref degrees } => {
+                out.push(event.clone());
+                for &degree in degrees {
+                    let note = key.scale().transpose_in_scale(event.note, degree);
+                    if note != event.note {
+                        out.push(MidiEvent { note, ..event.clone() });
+                    }
+                }
+                return;
+            }
+            Processor::Chordify { ref intervals } => {
+                out.push(event.clone());
+                for &interval in intervals {
+                    if let Some(note) = event.note.checked_add(interval).filter(|n| *n <= 127) {
+                        out.push(MidiEvent { note, ..event.clone() });
+                    }
+                }
+                return;
+            }
+        }
+        out.push(event);
+    }
+}
+
+/// A note sent for a held live key: delay, channel and note
+type LiveNote = (u64, u8, u8);
+
+/// Ordered list of processors applied to a track's output
+#[derive(Debug, Clone)]
+pub struct ProcessorChain {
+    processors: Vec<Processor>,
+    rng: StdRng,
+    /// What each held live key (channel, note) was turned into
+    held: HashMap<(u8, u8), Vec<LiveNote>>,
+}
+
+impl Default for ProcessorChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for ProcessorChain {
+    fn eq(&self, other: &Self) -> bool {
+        self.processors == other.processors
+    }
+}
+
+impl ProcessorChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self {
+            processors: Vec::new(),
+            rng: StdRng::from_entropy(),
+            held: HashMap::new(),
+        }
+    }
+
+    /// Add a processor to the end of the chain
+    pub fn with(mut self, processor: Processor) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Add a processor to the end of the chain
+    pub fn push(&mut self, processor: Processor) {
+        self.processors.push(processor);
+    }
+
+    /// Get the processors in order
+    pub fn processors(&self) -> &[Processor] {
+        &self.processors
+    }
+
+    /// Check if the chain does nothing
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+
+    /// Seed the humanizers, for repeatable output
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    /// Run events through every processor in turn, harmonizing in `key`
+    pub fn process(&mut self, events: Vec<MidiEvent>, key: &Key) -> Vec<MidiEvent> {
+        let mut events = events;
+        for processor in &self.processors {
+            let mut next = Vec::with_capacity(events.len());
+            for event in events {
+                processor.apply(event, key, &mut self.rng, &mut next);
+            }
+            events = next;
+        }
+        events
+    }
+
+    /// Run a live input message through the chain, returning the messages
+    /// to send, each with the ticks to wait first. Note offs release
+    /// whatever their note on became; other messages pass straight on.
+    pub fn process_input(&mut self, message: &MidiMessage, key: &Key) -> Vec<(u64, MidiMessage)> {
+        let release = |notes: Vec<LiveNote>| {
+            notes
+                .into_iter()
+                .map(|(delay, channel, note)| (delay, MidiMessage::NoteOff { channel, note, velocity: 0 }))
+        };
+        match *message {
+            MidiMessage::NoteOn { channel, note, velocity } if velocity > 0 => {
+                // A retriggered key lets go of what it played before
+                let mut output: Vec<(u64, MidiMessage)> =
+                    self.held.remove(&(channel, note)).map(release).into_iter().flatten().collect();
+                let mut event = MidiEvent::new(note, velocity, 0, 0);
+                event.channel = channel;
+                let events = self.process(vec![event], key);
+                self.held.insert(
+                    (channel, note),
+                    events.iter().map(|e| (e.start_tick, e.channel, e.note)).collect(),
+                );
+                output.extend(events.into_iter().map(|e| {
+                    let message = MidiMessage::NoteOn {
+                        channel: e.channel,
+                        note: e.note,
+                        velocity: e.velocity,
+                    };
+                    (e.start_tick, message)
+                }));
+                output
+            }
+            MidiMessage::NoteOn { channel, note, .. } | MidiMessage::NoteOff { channel, note, .. } => {
+                match self.held.remove(&(channel, note)) {
+                    Some(notes) => release(notes).collect(),
+                    None => vec![(0, message.clone())],
+                }
+            }
+            _ => vec![(0, message.clone())],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::{Note, ScaleType};
+
+    fn c_major() -> Key {
+        Key::new(Note::C, ScaleType::Major)
+    }
+
+    #[test]
+    fn test_chain_in_order() {
+        let mut chain = ProcessorChain::new()
+            .with(Processor::note_range(48, 72, false))
+            .with(Processor::velocity_curve(0.5, 40, 120))
+            .with(Processor::channel_remap(Some(0), 9));
+        let mut high = MidiEvent::new(84, 100, 0, 6);
+        high.channel = 0;
+        let mut soft = MidiEvent::new(60, 32, 6, 6);
+        soft.channel = 0;
+        let mut other = MidiEvent::new(64, 127, 12, 6);
+        other.channel = 2;
+
+        let out = chain.process(vec![high, soft, other], &c_major());
+        let summary: Vec<(u8, u8, u8)> = out.iter().map(|e| (e.note, e.velocity, e.channel)).collect();
+        // 84 is out of range; 32 is lifted to 40 + 80 * sqrt(32/127)
+        assert_eq!(summary, vec![(60, 80, 9), (64, 120, 2)]);
+
+        let mut folding = ProcessorChain::new().with(Processor::note_range(48, 72, true));
+        assert_eq!(folding.process(vec![MidiEvent::new(84, 100, 0, 6)], &c_major())[0].note, 72);
+    }
+
+    #[test]
+    fn test_humanize_is_bounded_and_seeded() {
+        let run = |seed| {
+            let mut chain = ProcessorChain::new().with(Processor::humanize(99, 10));
+            chain.set_seed(seed);
+            chain.process((0..32).map(|i| MidiEvent::new(60, 100, 24 + i, 6)).collect(), &c_major())
+        };
+        let events = run(3);
+        assert_eq!(events, run(3));
+        for (i, event) in events.iter().enumerate() {
+            let shift = event.start_tick as i64 - (24 + i as i64);
+            assert!(shift.abs() <= MAX_HUMANIZE_TICKS as i64);
+            assert!((90..=110).contains(&event.velocity));
+            assert_eq!(event.feel.ticks, shift);
+        }
+        assert!(events.iter().any(|e| e.velocity != 100));
+    }
+
+    #[test]
+    fn test_midi_effects() {
+        let mut chain = ProcessorChain::new()
+            .with(Processor::harmonize(&[2]))
+            .with(Processor::echo(12, 4, 0.5));
+        let out = chain.process(vec![MidiEvent::new(64, 80, 6, 6)], &c_major());
+        let summary: Vec<(u8, u8, u64)> = out.iter().map(|e| (e.note, e.velocity, e.start_tick)).collect();
+        // E up a third in C major is G; repeats halve until they fade out
+        assert_eq!(
+            summary,
+            vec![
+                (64, 80, 6),
+                (64, 40, 18),
+                (64, 20, 30),
+                (64, 10, 42),
+                (64, 5, 54),
+                (67, 80, 6),
+                (67, 40, 18),
+                (67, 20, 30),
+                (67, 10, 42),
+                (67, 5, 54),
+            ]
+        );
+
+        let mut minor = ProcessorChain::new().with(Processor::chordify(&chord_shape("min7").unwrap()));
+        let chord = minor.process(vec![MidiEvent::new(57, 90, 0, 6)], &c_major());
+        assert_eq!(chord.iter().map(|e| e.note).collect::<Vec<_>>(), vec![57, 60, 64, 67]);
+        assert_eq!(chord_shape("0, 7, 12"), Some(vec![0, 7, 12]));
+        assert_eq!(chord_shape("wide"), None);
+    }
+
+    #[test]
+    fn test_live_input_releases_what_it_played() {
+        let mut chain = ProcessorChain::new()
+            .with(Processor::harmonize(&[2]))
+            .with(Processor::echo(6, 1, 0.5));
+        let on = chain.process_input(&MidiMessage::NoteOn { channel: 0, note: 60, velocity: 100 }, &c_major());
+        assert_eq!(
+            on,
+            vec![
+                (0, MidiMessage::NoteOn { channel: 0, note: 60, velocity: 100 }),
+                (6, MidiMessage::NoteOn { channel: 0, note: 60, velocity: 50 }),
+                (0, MidiMessage::NoteOn { channel: 0, note: 64, velocity: 100 }),
+                (6, MidiMessage::NoteOn { channel: 0, note: 64, velocity: 50 }),
+            ]
+        );
+
+        // A key change before the release still lets go of the E
+        let a_minor = Key::new(Note::A, ScaleType::NaturalMinor);
+        let off = chain.process_input(&MidiMessage::NoteOff { channel: 0, note: 60, velocity: 0 }, &a_minor);
+        let released: Vec<(u64, u8)> = off
+            .iter()
+            .map(|(delay, m)| match m {
+                MidiMessage::NoteOff { note, .. } => (*delay, *note),
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(released, vec![(0, 60), (6, 60), (0, 64), (6, 64)]);
+
+        let cc = MidiMessage::ControlChange { channel: 0, controller: 1, value: 5 };
+        assert_eq!(chain.process_input(&cc, &c_major()), vec![(0, cc.clone())]);
+    }
+}
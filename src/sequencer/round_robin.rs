@@ -0,0 +1,223 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Round-robin note rotation across channels.
+//!
+//! Successive notes go to successive channels, so a multitimbral synth (or a
+//! stack of mono synths) plays as one polyphonic instrument. A free channel
+//! is preferred over stealing a sounding one, and each channel can carry its
+//! own transpose and detune for unison-style spreads.
+
+use crate::generators::MidiEvent;
+
+use super::track::resolve_destination;
+
+/// Default pitch bend range in semitones, used to turn detune into bend
+pub const DEFAULT_BEND_RANGE: u8 = 2;
+
+/// One channel in the rotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundRobinVoice {
+    /// Output destination index (None = default output)
+    pub destination: Option<usize>,
+    /// MIDI channel (0-15)
+    pub channel: u8,
+    /// Transpose in semitones, applied after the track transpose
+    pub transpose: i8,
+    /// Detune in cents, sent as pitch bend
+    pub detune: i16,
+}
+
+impl RoundRobinVoice {
+    /// Create a voice on the default output
+    pub fn new(channel: u8) -> Self {
+        Self {
+            destination: None,
+            channel: channel.min(15),
+            transpose: 0,
+            detune: 0,
+        }
+    }
+
+    /// Set output destination
+    pub fn with_destination(mut self, destination: Option<usize>) -> Self {
+        self.destination = destination;
+        self
+    }
+
+    /// Set transpose
+    pub fn with_transpose(mut self, transpose: i8) -> Self {
+        self.transpose = transpose.clamp(-48, 48);
+        self
+    }
+
+    /// Set detune in cents
+    pub fn with_detune(mut self, cents: i16) -> Self {
+        self.detune = cents.clamp(-100, 100);
+        self
+    }
+
+    /// Pitch bend value for the detune at a bend range in semitones
+    pub fn bend(&self, bend_range: u8) -> i16 {
+        let range_cents = bend_range.max(1) as f64 * 100.0;
+        (self.detune as f64 / range_cents * 8192.0).round().clamp(-8192.0, 8191.0) as i16
+    }
+
+    /// Apply this voice to a processed event
+    fn apply(&self, event: &MidiEvent) -> Option<MidiEvent> {
+        let note = event.note as i16 + self.transpose as i16;
+        if !(0..=127).contains(&note) {
+            return None;
+        }
+        let mut voiced = event.clone();
+        voiced.note = note as u8;
+        voiced.channel = self.channel;
+        Some(voiced)
+    }
+}
+
+/// Rotates notes across a set of channels
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundRobin {
+    /// Channels in rotation order
+    voices: Vec<RoundRobinVoice>,
+    /// Pitch bend range of the receiving synths (semitones)
+    bend_range: u8,
+    /// Voice to try first for the next note
+    next: usize,
+    /// Absolute tick each voice's last note ends
+    busy_until: Vec<u64>,
+    /// Detune bends still to be sent
+    detune_pending: bool,
+}
+
+impl RoundRobin {
+    /// Create a rotation over some voices
+    pub fn new(voices: Vec<RoundRobinVoice>) -> Self {
+        let count = voices.len();
+        Self {
+            voices,
+            bend_range: DEFAULT_BEND_RANGE,
+            next: 0,
+            busy_until: vec![0; count],
+            detune_pending: true,
+        }
+    }
+
+    /// Create a rotation from song configuration, resolving destinations by name
+    pub fn from_config(config: &crate::config::RoundRobinConfig, destinations: &[String]) -> Self {
+        let voices = config
+            .voices
+            .iter()
+            .map(|voice| {
+                RoundRobinVoice::new(voice.channel.saturating_sub(1))
+                    .with_destination(resolve_destination(voice.destination.as_deref(), destinations))
+                    .with_transpose(voice.transpose)
+                    .with_detune(voice.detune)
+            })
+            .collect();
+        Self::new(voices).with_bend_range(config.bend_range)
+    }
+
+    /// Set the pitch bend range of the receiving synths
+    pub fn with_bend_range(mut self, semitones: u8) -> Self {
+        self.bend_range = semitones.clamp(1, 48);
+        self
+    }
+
+    /// Get the voices
+    pub fn voices(&self) -> &[RoundRobinVoice] {
+        &self.voices
+    }
+
+    /// Get the pitch bend range
+    pub fn bend_range(&self) -> u8 {
+        self.bend_range
+    }
+
+    /// Pick the voice for a note sounding from `start` to `end` (absolute
+    /// ticks): the next free voice in rotation, or the next voice if all are
+    /// sounding
+    pub fn assign(&mut self, start: u64, end: u64) -> Option<usize> {
+        let count = self.voices.len();
+        if count == 0 {
+            return None;
+        }
+        let index = (0..count)
+            .map(|offset| (self.next + offset) % count)
+            .find(|&i| self.busy_until[i] <= start)
+            .unwrap_or(self.next % count);
+        self.busy_until[index] = end;
+        self.next = (index + 1) % count;
+        Some(index)
+    }
+
+    /// Send a note to its voice, returning the voiced note and destination
+    pub fn route(&mut self, event: &MidiEvent, base_tick: u64) -> Option<(MidiEvent, Option<usize>)> {
+        let start = base_tick + event.start_tick;
+        let index = self.assign(start, start + event.duration_ticks)?;
+        let voice = &self.voices[index];
+        Some((voice.apply(event)?, voice.destination))
+    }
+
+    /// Detune bends to send once before the first note, as
+    /// (channel, destination, bend). Empty once sent.
+    pub fn take_detune(&mut self) -> Vec<(u8, Option<usize>, i16)> {
+        if !std::mem::take(&mut self.detune_pending) {
+            return Vec::new();
+        }
+        self.voices
+            .iter()
+            .filter(|voice| voice.detune != 0)
+            .map(|voice| (voice.channel, voice.destination, voice.bend(self.bend_range)))
+            .collect()
+    }
+
+    /// Start the rotation again from the first voice
+    pub fn reset(&mut self) {
+        self.next = 0;
+        self.busy_until.iter_mut().for_each(|tick| *tick = 0);
+        self.detune_pending = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_prefers_free_voices() {
+        let voices = (0..3).map(RoundRobinVoice::new).collect();
+        let mut rr = RoundRobin::new(voices);
+
+        // Short notes rotate through every channel
+        let picks: Vec<usize> = (0..4).map(|i| rr.assign(i * 24, i * 24 + 12).unwrap()).collect();
+        assert_eq!(picks, vec![0, 1, 2, 0]);
+
+        // A held note on voice 0 is skipped while it sounds
+        rr.reset();
+        assert_eq!(rr.assign(0, 96), Some(0));
+        assert_eq!(rr.assign(0, 12), Some(1));
+        assert_eq!(rr.assign(24, 36), Some(2));
+        assert_eq!(rr.assign(48, 60), Some(1));
+        assert_eq!(rr.assign(50, 100), Some(2));
+        // Everything busy: steal the next in turn
+        assert_eq!(rr.assign(52, 100), Some(0));
+    }
+
+    #[test]
+    fn test_voice_transpose_and_detune() {
+        let mut rr = RoundRobin::new(vec![
+            RoundRobinVoice::new(0).with_detune(-10),
+            RoundRobinVoice::new(1).with_transpose(12).with_detune(10),
+        ]);
+        let detune = rr.take_detune();
+        assert_eq!(detune, vec![(0, None, -410), (1, None, 410)]);
+        assert!(rr.take_detune().is_empty());
+
+        let note = MidiEvent::new(60, 100, 0, 12);
+        assert_eq!(rr.route(&note, 0).unwrap().0.channel, 0);
+        let (second, _) = rr.route(&note, 0).unwrap();
+        assert_eq!((second.channel, second.note), (1, 72));
+    }
+}
@@ -25,6 +25,8 @@ pub enum MidiMessageType {
     ProgramChange,
     /// Pitch bend
     PitchBend,
+    /// Channel pressure (aftertouch)
+    ChannelPressure,
 }
 
 /// A scheduled MIDI event
@@ -44,6 +46,8 @@ pub struct ScheduledEvent {
     pub data2: u8,
     /// Source track index (for tracking origin)
     pub track_index: Option<usize>,
+    /// Output destination index (None = default output)
+    pub destination: Option<usize>,
 }
 
 impl ScheduledEvent {
@@ -57,6 +61,7 @@ impl ScheduledEvent {
             data1: note,
             data2: velocity,
             track_index: None,
+            destination: None,
         }
     }
 
@@ -70,6 +75,7 @@ impl ScheduledEvent {
             data1: note,
             data2: 0,
             track_index: None,
+            destination: None,
         }
     }
 
@@ -83,6 +89,7 @@ impl ScheduledEvent {
             data1: cc,
             data2: value,
             track_index: None,
+            destination: None,
         }
     }
 
@@ -96,6 +103,36 @@ impl ScheduledEvent {
             data1: program,
             data2: 0,
             track_index: None,
+            destination: None,
+        }
+    }
+
+    /// Create a pitch bend event (value -8192 to 8191)
+    pub fn pitch_bend(time_ticks: u64, channel: u8, value: i16) -> Self {
+        let raw = (value.clamp(-8192, 8191) + 8192) as u16;
+        Self {
+            time_micros: 0,
+            time_ticks,
+            channel,
+            message_type: MidiMessageType::PitchBend,
+            data1: (raw & 0x7F) as u8,
+            data2: (raw >> 7) as u8,
+            track_index: None,
+            destination: None,
+        }
+    }
+
+    /// Create a channel pressure event
+    pub fn channel_pressure(time_ticks: u64, channel: u8, pressure: u8) -> Self {
+        Self {
+            time_micros: 0,
+            time_ticks,
+            channel,
+            message_type: MidiMessageType::ChannelPressure,
+            data1: pressure.min(127),
+            data2: 0,
+            track_index: None,
+            destination: None,
         }
     }
 
@@ -105,6 +142,12 @@ impl ScheduledEvent {
         self
     }
 
+    /// Set the output destination for this event
+    pub fn with_destination(mut self, destination: Option<usize>) -> Self {
+        self.destination = destination;
+        self
+    }
+
     /// Convert to MIDI bytes
     pub fn to_midi_bytes(&self) -> Vec<u8> {
         match self.message_type {
@@ -116,6 +159,7 @@ impl ScheduledEvent {
                 // Pitch bend uses two 7-bit values
                 vec![0xE0 | self.channel, self.data1, self.data2]
             }
+            MidiMessageType::ChannelPressure => vec![0xD0 | self.channel, self.data1],
         }
     }
 }
@@ -361,6 +405,29 @@ impl Scheduler {
         events
     }
 
+    /// Get events due at or before a tick, with the position following an
+    /// external clock instead of the wall clock
+    pub fn poll_ticks(&mut self, ticks: u64) -> Vec<ScheduledEvent> {
+        if !self.playing {
+            return Vec::new();
+        }
+
+        self.timing.position_ticks = ticks;
+        self.position_micros = self.timing.ticks_to_micros(ticks);
+
+        let mut events = Vec::new();
+
+        while let Some(event) = self.queue.peek() {
+            if event.time_ticks <= ticks {
+                events.push(self.queue.pop().unwrap());
+            } else {
+                break;
+            }
+        }
+
+        events
+    }
+
     /// Calculate delay until next event
     pub fn time_to_next_event(&self) -> Option<Duration> {
         if !self.playing {
@@ -510,6 +577,12 @@ mod tests {
 
         let cc = ScheduledEvent::control_change(0, 0, 1, 64);
         assert_eq!(cc.to_midi_bytes(), vec![0xB0, 1, 64]);
+
+        let bend = ScheduledEvent::pitch_bend(0, 3, 0);
+        assert_eq!(bend.to_midi_bytes(), vec![0xE3, 0x00, 0x40]);
+
+        let bend = ScheduledEvent::pitch_bend(0, 0, 8191);
+        assert_eq!(bend.to_midi_bytes(), vec![0xE0, 0x7F, 0x7F]);
     }
 
     #[test]
@@ -0,0 +1,122 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Bar-synced random clip selection.
+//!
+//! A track in shuffle mode picks one of its clips at random, by weight,
+//! every N bars or each time the playing clip loops. It gives pattern
+//! variation from a handful of clips without building parts around them.
+
+use rand::Rng;
+
+/// Random clip selection settings and timing
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipShuffle {
+    /// Bars between picks (0 = each time the clip loops)
+    every_bars: u32,
+    /// Relative weight of each clip (missing = 1.0)
+    weights: Vec<f64>,
+    /// Absolute tick of the next pick
+    next_pick: Option<u64>,
+}
+
+impl ClipShuffle {
+    /// Pick every `every_bars` bars (0 = each loop of the playing clip)
+    pub fn new(every_bars: u32) -> Self {
+        Self {
+            every_bars,
+            weights: Vec::new(),
+            next_pick: None,
+        }
+    }
+
+    /// Set clip weights
+    pub fn with_weights(mut self, weights: Vec<f64>) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Get bars between picks
+    pub fn every_bars(&self) -> u32 {
+        self.every_bars
+    }
+
+    /// Get the weight of a clip
+    pub fn weight(&self, clip: usize) -> f64 {
+        self.weights.get(clip).copied().unwrap_or(1.0).max(0.0)
+    }
+
+    /// Check if a pick is due at an absolute tick
+    pub fn is_due(&self, tick: u64) -> bool {
+        self.next_pick.map_or(true, |next| tick >= next)
+    }
+
+    /// Schedule the next pick after one made at `tick`. `clip_length` is
+    /// the picked clip's length, used when picking on each loop.
+    pub fn picked(&mut self, tick: u64, ticks_per_bar: u64, clip_length: u64) {
+        let period = if self.every_bars == 0 {
+            clip_length
+        } else {
+            self.every_bars as u64 * ticks_per_bar
+        };
+        self.next_pick = Some(tick + period.max(1));
+    }
+
+    /// Choose a clip by weight
+    pub fn choose(&self, clip_count: usize, rng: &mut impl Rng) -> Option<usize> {
+        let total: f64 = (0..clip_count).map(|i| self.weight(i)).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = rng.gen::<f64>() * total;
+        for i in 0..clip_count {
+            roll -= self.weight(i);
+            if roll < 0.0 {
+                return Some(i);
+            }
+        }
+        (0..clip_count).rev().find(|&i| self.weight(i) > 0.0)
+    }
+
+    /// Forget the pick schedule (pick again on the next generate)
+    pub fn reset(&mut self) {
+        self.next_pick = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_weighted_choice() {
+        let shuffle = ClipShuffle::new(2).with_weights(vec![3.0, 0.0, 1.0]);
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut counts = [0; 3];
+        for _ in 0..1000 {
+            counts[shuffle.choose(3, &mut rng).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        assert!(counts[0] > counts[2] * 2);
+
+        assert_eq!(ClipShuffle::new(1).with_weights(vec![0.0]).choose(1, &mut rng), None);
+    }
+
+    #[test]
+    fn test_pick_schedule() {
+        let mut bars = ClipShuffle::new(2);
+        assert!(bars.is_due(0));
+        bars.picked(0, 96, 48);
+        assert!(!bars.is_due(191));
+        assert!(bars.is_due(192));
+
+        // Each loop follows the clip length
+        let mut loops = ClipShuffle::new(0);
+        loops.picked(96, 96, 48);
+        assert!(loops.is_due(144));
+        loops.reset();
+        assert!(loops.is_due(0));
+    }
+}
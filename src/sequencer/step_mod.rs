@@ -0,0 +1,117 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Step-sequenced parameter lanes.
+//!
+//! A step lane works like a modular sequencer patched into a generator
+//! parameter: a row of values, a number of steps per bar, and the parameter
+//! they set. Each step lasts its share of a bar and the lane loops over its
+//! values, so `octaves` with 1, 1, 2, 3 at four steps per bar widens an
+//! arpeggio on the last two beats of every bar. Steps count from the song
+//! start, so a lane longer than a bar keeps its place across bars.
+
+/// Most values in one lane
+pub const MAX_LANE_STEPS: usize = 64;
+
+/// Finest lane resolution, in steps per bar
+pub const MAX_STEPS_PER_BAR: u32 = 32;
+
+/// A looping row of values for one generator parameter
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepLane {
+    /// Generator parameter to set
+    param: String,
+    /// Value for each step
+    values: Vec<f64>,
+    /// Steps in one bar
+    steps_per_bar: u32,
+    /// Last step whose value was set
+    last_step: Option<u64>,
+}
+
+impl StepLane {
+    /// Create a lane, keeping at most MAX_LANE_STEPS values
+    pub fn new(param: impl Into<String>, mut values: Vec<f64>, steps_per_bar: u32) -> Self {
+        values.truncate(MAX_LANE_STEPS);
+        Self {
+            param: param.into(),
+            values,
+            steps_per_bar: steps_per_bar.clamp(1, MAX_STEPS_PER_BAR),
+            last_step: None,
+        }
+    }
+
+    /// Get the parameter name
+    pub fn param(&self) -> &str {
+        &self.param
+    }
+
+    /// Get the step values
+    pub fn values(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Get the steps per bar
+    pub fn steps_per_bar(&self) -> u32 {
+        self.steps_per_bar
+    }
+
+    /// Step number (from the song start) playing at a tick
+    pub fn step_at(&self, tick: u64, ticks_per_bar: u64) -> u64 {
+        tick * self.steps_per_bar as u64 / ticks_per_bar.max(1)
+    }
+
+    /// Value of the step playing at a tick
+    pub fn value_at(&self, tick: u64, ticks_per_bar: u64) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let step = self.step_at(tick, ticks_per_bar) % self.values.len() as u64;
+        Some(self.values[step as usize])
+    }
+
+    /// Value to set at a tick, if a new step has begun since the last call
+    pub fn poll(&mut self, tick: u64, ticks_per_bar: u64) -> Option<f64> {
+        let step = self.step_at(tick, ticks_per_bar);
+        if self.last_step == Some(step) {
+            return None;
+        }
+        self.last_step = Some(step);
+        self.value_at(tick, ticks_per_bar)
+    }
+
+    /// Forget the last step, so the next poll sets the value again
+    pub fn reset(&mut self) {
+        self.last_step = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lane_steps_with_the_clock() {
+        let mut lane = StepLane::new("octaves", vec![1.0, 1.0, 2.0, 3.0], 4);
+        // 96 ticks per bar: a step per beat
+        assert_eq!(lane.poll(0, 96), Some(1.0));
+        assert_eq!(lane.poll(12, 96), None);
+        assert_eq!(lane.poll(24, 96), Some(1.0));
+        assert_eq!(lane.poll(48, 96), Some(2.0));
+        assert_eq!(lane.poll(72, 96), Some(3.0));
+        assert_eq!(lane.poll(96, 96), Some(1.0));
+
+        lane.reset();
+        assert_eq!(lane.poll(100, 96), Some(1.0));
+        assert_eq!(StepLane::new("density", Vec::new(), 4).poll(0, 96), None);
+    }
+
+    #[test]
+    fn test_long_lane_spans_bars() {
+        // Six eighth-note steps loop across bar lines
+        let lane = StepLane::new("density", vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6], 8);
+        assert_eq!(lane.value_at(96, 96), Some(0.3));
+        assert_eq!(lane.value_at(96 + 36, 96), Some(0.6));
+        assert_eq!(StepLane::new("p", vec![0.0], 99).steps_per_bar(), MAX_STEPS_PER_BAR);
+    }
+}
@@ -0,0 +1,286 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Live input played thru to tracks.
+//!
+//! Arming a track connects a MIDI source (and optionally one channel and a
+//! range of keys) to it. Incoming notes can be snapped into the song key,
+//! are transposed like the track's own output, pass through its pedal,
+//! latch and processors, and go straight out on its channel and
+//! destination. The same notes feed the recorder, taken before the
+//! transpose since the track transposes its clips again on playback. Each
+//! key press remembers what it became, so its release lets go of the right
+//! note even if the key or transpose changed in between.
+
+use std::collections::HashMap;
+
+use crate::midi::MidiMessage;
+use crate::music::Key;
+use crate::recording::{MidiRecorder, RecordInput};
+
+use super::scheduler::ScheduledEvent;
+use super::track::{Track, TrackManager};
+
+/// A MIDI input armed to a track
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArmedInput {
+    /// Track index
+    pub track: usize,
+    /// Source and channel played thru
+    pub input: RecordInput,
+    /// Lowest and highest key played thru
+    pub range: (u8, u8),
+    /// Snap notes into the song key
+    pub quantize: bool,
+}
+
+impl ArmedInput {
+    /// Arm every key of an input to a track
+    pub fn new(track: usize, input: RecordInput) -> Self {
+        Self {
+            track,
+            input,
+            range: (0, 127),
+            quantize: false,
+        }
+    }
+
+    /// Only play keys within a range
+    pub fn with_range(mut self, low: u8, high: u8) -> Self {
+        let (low, high) = (low.min(127), high.min(127));
+        self.range = (low.min(high), low.max(high));
+        self
+    }
+
+    /// Snap notes into the song key
+    pub fn with_quantize(mut self, quantize: bool) -> Self {
+        self.quantize = quantize;
+        self
+    }
+
+    /// Check if a message from a source is played thru
+    pub fn accepts(&self, source: &str, message: &MidiMessage) -> bool {
+        let (channel, note) = match *message {
+            MidiMessage::NoteOn { channel, note, .. }
+            | MidiMessage::NoteOff { channel, note, .. }
+            | MidiMessage::PolyAftertouch { channel, note, .. } => (channel, Some(note)),
+            MidiMessage::ControlChange { channel, .. }
+            | MidiMessage::PitchBend { channel, .. }
+            | MidiMessage::ChannelAftertouch { channel, .. } => (channel, None),
+            _ => return false,
+        };
+        self.input.accepts(source, channel) && note.map_or(true, |n| n >= self.range.0 && n <= self.range.1)
+    }
+}
+
+/// Routes armed inputs to their tracks
+#[derive(Debug, Clone, Default)]
+pub struct InputThru {
+    arms: Vec<ArmedInput>,
+    /// Held keys: (track, input channel, input note) -> (recorded note, sent note)
+    held: HashMap<(usize, u8, u8), (u8, u8)>,
+}
+
+impl InputThru {
+    /// Create with nothing armed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arm an input, replacing any already armed to the track
+    pub fn arm(&mut self, armed: ArmedInput) {
+        self.arms.retain(|a| a.track != armed.track);
+        self.arms.push(armed);
+    }
+
+    /// Disarm a track, releasing the notes it holds
+    pub fn disarm(&mut self, track: usize, tracks: &mut TrackManager, tick: u64) -> Vec<ScheduledEvent> {
+        self.arms.retain(|a| a.track != track);
+        let mut released = Vec::new();
+        self.held.retain(|&(held_track, _, _), &mut (_, sent)| {
+            if held_track == track {
+                released.push(sent);
+            }
+            held_track != track
+        });
+        let Some(engine_track) = tracks.track_mut(track) else {
+            return Vec::new();
+        };
+        let channel = engine_track.channel();
+        released
+            .into_iter()
+            .flat_map(|note| {
+                let off = MidiMessage::NoteOff { channel, note, velocity: 0 };
+                send(engine_track, &off, tick)
+            })
+            .collect()
+    }
+
+    /// Get the armed inputs
+    pub fn arms(&self) -> &[ArmedInput] {
+        &self.arms
+    }
+
+    /// Check if a track is armed
+    pub fn is_armed(&self, track: usize) -> bool {
+        self.arms.iter().any(|a| a.track == track)
+    }
+
+    /// Play an input message thru the armed tracks at a tick, recording its
+    /// notes. Returns the events to send.
+    pub fn process(
+        &mut self,
+        source: &str,
+        message: &MidiMessage,
+        key: &Key,
+        tracks: &mut TrackManager,
+        mut recorder: Option<&mut MidiRecorder>,
+        tick: u64,
+    ) -> Vec<ScheduledEvent> {
+        let mut events = Vec::new();
+        for armed in &self.arms {
+            let Some(track) = tracks.track_mut(armed.track) else {
+                continue;
+            };
+            let channel = track.channel();
+            match *message {
+                MidiMessage::NoteOn { channel: input, note, velocity } if velocity > 0 => {
+                    if !armed.accepts(source, message) {
+                        continue;
+                    }
+                    let recorded = if armed.quantize { key.scale().quantize(note) } else { note };
+                    if let Some(recorder) = recorder.as_deref_mut() {
+                        recorder.note_on_from(source, input, recorded, velocity);
+                    }
+                    let Some(sent) = track.transposed(recorded).filter(|_| !track.is_muted()) else {
+                        continue;
+                    };
+                    self.held.insert((armed.track, input, note), (recorded, sent));
+                    let on = MidiMessage::NoteOn { channel, note: sent, velocity };
+                    events.extend(send(track, &on, tick));
+                }
+                MidiMessage::NoteOn { channel: input, note, .. } | MidiMessage::NoteOff { channel: input, note, .. } => {
+                    // Releases follow the press, wherever the range now is
+                    if !armed.input.accepts(source, input) {
+                        continue;
+                    }
+                    let Some((recorded, sent)) = self.held.remove(&(armed.track, input, note)) else {
+                        if armed.accepts(source, message) {
+                            if let Some(recorder) = recorder.as_deref_mut() {
+                                recorder.note_off_from(source, input, note);
+                            }
+                        }
+                        continue;
+                    };
+                    if let Some(recorder) = recorder.as_deref_mut() {
+                        recorder.note_off_from(source, input, recorded);
+                    }
+                    let off = MidiMessage::NoteOff { channel, note: sent, velocity: 0 };
+                    events.extend(send(track, &off, tick));
+                }
+                _ if armed.accepts(source, message) && !track.is_muted() => {
+                    let event = match *message {
+                        MidiMessage::ControlChange { controller, value, .. } => {
+                            ScheduledEvent::control_change(tick, channel, controller, value)
+                        }
+                        MidiMessage::PitchBend { value, .. } => ScheduledEvent::pitch_bend(tick, channel, value),
+                        MidiMessage::ChannelAftertouch { pressure, .. } => {
+                            ScheduledEvent::channel_pressure(tick, channel, pressure)
+                        }
+                        _ => continue,
+                    };
+                    events.push(event.with_track(armed.track).with_destination(track.destination()));
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
+
+/// Pass a note through a track's pedal, latch and processors
+fn send(track: &mut Track, message: &MidiMessage, tick: u64) -> Vec<ScheduledEvent> {
+    let index = track.index();
+    let destination = track.destination();
+    track
+        .process_input(message)
+        .into_iter()
+        .filter_map(|(delay, message)| {
+            let event = match message {
+                MidiMessage::NoteOn { channel, note, velocity } => {
+                    ScheduledEvent::note_on(tick + delay, channel, note, velocity)
+                }
+                MidiMessage::NoteOff { channel, note, .. } => ScheduledEvent::note_off(tick + delay, channel, note),
+                _ => return None,
+            };
+            Some(event.with_track(index).with_destination(destination))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::music::{Note, ScaleType};
+    use crate::sequencer::scheduler::MidiMessageType;
+    use crate::sequencer::track::TrackConfig;
+
+    fn on(channel: u8, note: u8) -> MidiMessage {
+        MidiMessage::NoteOn { channel, note, velocity: 100 }
+    }
+
+    fn off(channel: u8, note: u8) -> MidiMessage {
+        MidiMessage::NoteOff { channel, note, velocity: 0 }
+    }
+
+    #[test]
+    fn test_thru_quantizes_transposes_and_records() {
+        let mut tracks = TrackManager::new();
+        tracks.add_track(TrackConfig::new("Keys").with_channel(3));
+        tracks.track_mut(0).unwrap().set_transpose(12);
+        let mut thru = InputThru::new();
+        let input = RecordInput::new(Some("keystep".to_string()), Some(0));
+        thru.arm(ArmedInput::new(0, input.clone()).with_range(48, 72).with_quantize(true));
+        let mut recorder = MidiRecorder::new(24);
+        recorder.set_input(input);
+        recorder.start(0);
+        let c_major = Key::new(Note::C, ScaleType::Major);
+
+        // C#3 snaps to C3, records there, and plays an octave up on channel 4
+        let events = thru.process("KeyStep Pro", &on(0, 49), &c_major, &mut tracks, Some(&mut recorder), 10);
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].time_ticks, events[0].channel, events[0].data1), (10, 3, 60));
+        assert_eq!(events[0].track_index, Some(0));
+
+        // Other channels, keys out of range and other sources are ignored
+        assert!(thru.process("KeyStep Pro", &on(1, 60), &c_major, &mut tracks, None, 12).is_empty());
+        assert!(thru.process("KeyStep Pro", &on(0, 30), &c_major, &mut tracks, None, 12).is_empty());
+        assert!(thru.process("Launchpad", &on(0, 60), &c_major, &mut tracks, None, 12).is_empty());
+
+        // The release lets go of what was sent, after the transpose changed
+        tracks.track_mut(0).unwrap().set_transpose(0);
+        recorder.tick(14);
+        let events = thru.process("KeyStep Pro", &off(0, 49), &c_major, &mut tracks, Some(&mut recorder), 14);
+        assert_eq!((events[0].message_type, events[0].data1), (MidiMessageType::NoteOff, 60));
+        assert_eq!(recorder.notes().len(), 1);
+        assert_eq!(recorder.notes()[0].note, 48);
+    }
+
+    #[test]
+    fn test_disarm_releases_held_notes() {
+        let mut tracks = TrackManager::new();
+        tracks.add_track(TrackConfig::new("Bass"));
+        let mut thru = InputThru::new();
+        thru.arm(ArmedInput::new(0, RecordInput::default()));
+        let cc = MidiMessage::ControlChange { channel: 5, controller: 1, value: 64 };
+        let key = Key::new(Note::C, ScaleType::Major);
+        assert_eq!(thru.process("any", &cc, &key, &mut tracks, None, 0)[0].channel, 0);
+        thru.process("any", &on(5, 40), &key, &mut tracks, None, 0);
+
+        let released = thru.disarm(0, &mut tracks, 24);
+        assert_eq!(released.len(), 1);
+        assert_eq!((released[0].time_ticks, released[0].data1), (24, 40));
+        assert!(!thru.is_armed(0));
+        assert!(thru.process("any", &on(5, 40), &key, &mut tracks, None, 30).is_empty());
+    }
+}
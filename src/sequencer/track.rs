@@ -4,11 +4,32 @@
 //! Track system for multi-channel MIDI output.
 //!
 //! Provides track state management with mute/solo, transpose,
-//! swing, and channel routing.
+//! swing, play probability, and channel routing.
+//!
+//! Swing and the track's groove template are applied at each event's song
+//! position, so the feel stays put however the output is chunked.
+
+use std::cell::Cell;
 
+use anyhow::{anyhow, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::cc_thin::{CcThinner, ThinStats};
+use super::chain::PatternChain;
 use super::clip::{Clip, ClipState};
+use super::groove::GrooveTemplate;
+use super::latch::NoteLatch;
+use super::mpe::MpeOutput;
+use super::pedal::{PedalMode, SustainPedal};
+use super::processor::{Processor, ProcessorChain};
+use super::round_robin::RoundRobin;
 use super::scheduler::ScheduledEvent;
+use super::shuffle::ClipShuffle;
+use super::step_mod::StepLane;
 use crate::generators::{Generator, GeneratorContext, MidiEvent};
+use crate::midi::MidiMessage;
+use crate::music::{Key, Note, ScaleType};
 
 /// Track state for mute/solo/active
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,6 +48,202 @@ impl Default for TrackState {
     }
 }
 
+/// How the track play probability is evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbabilityMode {
+    /// Roll once per note
+    PerEvent,
+    /// Roll once per bar; the whole bar plays or stays silent
+    PerBar,
+}
+
+impl Default for ProbabilityMode {
+    fn default() -> Self {
+        ProbabilityMode::PerEvent
+    }
+}
+
+impl ProbabilityMode {
+    /// Parse from a config string ("event" or "bar")
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "event" | "per_event" | "note" => Some(ProbabilityMode::PerEvent),
+            "bar" | "per_bar" => Some(ProbabilityMode::PerBar),
+            _ => None,
+        }
+    }
+}
+
+/// What the track transpose counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransposeMode {
+    /// Chromatic semitones
+    Semitones,
+    /// Scale degrees in the song key; notes stay in key and out-of-key
+    /// notes snap to the nearest degree
+    Degrees,
+}
+
+impl Default for TransposeMode {
+    fn default() -> Self {
+        TransposeMode::Semitones
+    }
+}
+
+impl TransposeMode {
+    /// Parse from a config string ("semitones" or "degrees")
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "semitones" | "semitone" | "chromatic" => Some(TransposeMode::Semitones),
+            "degrees" | "degree" | "scale" | "diatonic" => Some(TransposeMode::Degrees),
+            _ => None,
+        }
+    }
+}
+
+/// An additional output for a layered track
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputLayer {
+    /// Output destination index (None = default output)
+    pub destination: Option<usize>,
+    /// MIDI channel (0-15)
+    pub channel: u8,
+    /// Transpose in semitones, applied after the track transpose
+    pub transpose: i8,
+    /// Velocity scale, applied after the track velocity processing
+    pub velocity_scale: f64,
+}
+
+impl OutputLayer {
+    /// Create a layer on the default output
+    pub fn new(channel: u8) -> Self {
+        Self {
+            destination: None,
+            channel: channel.min(15),
+            transpose: 0,
+            velocity_scale: 1.0,
+        }
+    }
+
+    /// Create a layer from song configuration, resolving the destination by name.
+    /// Fails if a named destination matches none of the outputs.
+    pub fn from_config(config: &crate::config::OutputConfig, destinations: &[String]) -> Result<Self> {
+        let destination = config
+            .destination
+            .as_deref()
+            .map(|name| {
+                resolve_destination(Some(name), destinations)
+                    .ok_or_else(|| anyhow!("No MIDI output matching '{}'", name))
+            })
+            .transpose()?;
+        Ok(Self::new(config.channel.saturating_sub(1))
+            .with_destination(destination)
+            .with_transpose(config.transpose)
+            .with_velocity_scale(config.velocity_scale))
+    }
+
+    /// Set output destination
+    pub fn with_destination(mut self, destination: Option<usize>) -> Self {
+        self.destination = destination;
+        self
+    }
+
+    /// Set transpose
+    pub fn with_transpose(mut self, transpose: i8) -> Self {
+        self.transpose = transpose.clamp(-48, 48);
+        self
+    }
+
+    /// Set velocity scale
+    pub fn with_velocity_scale(mut self, scale: f64) -> Self {
+        self.velocity_scale = scale.clamp(0.0, 2.0);
+        self
+    }
+
+    /// Apply this layer to a processed event
+    fn apply(&self, event: &MidiEvent) -> Option<MidiEvent> {
+        let note = event.note as i16 + self.transpose as i16;
+        if !(0..=127).contains(&note) {
+            return None;
+        }
+        let velocity = (event.velocity as f64 * self.velocity_scale) as i16;
+        let mut layered = event.clone();
+        layered.note = note as u8;
+        layered.velocity = velocity.clamp(1, 127) as u8;
+        layered.channel = self.channel;
+        Some(layered)
+    }
+}
+
+/// Find a destination index by case-insensitive partial name match
+pub(crate) fn resolve_destination(name: Option<&str>, destinations: &[String]) -> Option<usize> {
+    let name = name?.to_lowercase();
+    destinations
+        .iter()
+        .position(|d| d.to_lowercase().contains(&name))
+}
+
+/// Sends one drum voice to its own channel and destination
+#[derive(Debug, Clone, PartialEq)]
+pub struct VoiceRoute {
+    /// Note played by the voice (after track processing)
+    pub note: u8,
+    /// Output destination index (None = default output)
+    pub destination: Option<usize>,
+    /// MIDI channel (0-15)
+    pub channel: u8,
+    /// Note sent to the destination (None = unchanged)
+    pub output_note: Option<u8>,
+}
+
+impl VoiceRoute {
+    /// Route a voice to a channel on the default output
+    pub fn new(note: u8, channel: u8) -> Self {
+        Self {
+            note: note.min(127),
+            destination: None,
+            channel: channel.min(15),
+            output_note: None,
+        }
+    }
+
+    /// Create a route from song configuration. Returns None for an unknown voice.
+    pub fn from_config(config: &crate::config::VoiceRouteConfig, destinations: &[String]) -> Option<Self> {
+        let note = crate::generators::drums::gm_drums::from_name(&config.voice)?;
+        Some(
+            Self::new(note, config.channel.saturating_sub(1))
+                .with_destination(resolve_destination(config.destination.as_deref(), destinations))
+                .with_output_note(config.note),
+        )
+    }
+
+    /// Set output destination
+    pub fn with_destination(mut self, destination: Option<usize>) -> Self {
+        self.destination = destination;
+        self
+    }
+
+    /// Set the note sent to the destination
+    pub fn with_output_note(mut self, note: Option<u8>) -> Self {
+        self.output_note = note.map(|n| n.min(127));
+        self
+    }
+
+    /// Apply this route to a processed event
+    fn apply(&self, event: &MidiEvent) -> MidiEvent {
+        let mut routed = event.clone();
+        routed.note = self.output_note.unwrap_or(event.note);
+        routed.channel = self.channel;
+        routed
+    }
+}
+
+/// Shortest gate scale (staccato)
+pub const MIN_GATE_SCALE: f64 = 0.1;
+
+/// Longest gate scale (legato)
+pub const MAX_GATE_SCALE: f64 = 2.0;
+
 /// Configuration for a track
 #[derive(Debug, Clone)]
 pub struct TrackConfig {
@@ -34,10 +251,16 @@ pub struct TrackConfig {
     pub name: String,
     /// MIDI channel (0-15)
     pub channel: u8,
-    /// Transpose in semitones (-48 to +48)
+    /// Output destination index for `channel` (None = default output)
+    pub destination: Option<usize>,
+    /// Transpose (-48 to +48), in semitones or scale degrees
     pub transpose: i8,
+    /// What `transpose` counts
+    pub transpose_mode: TransposeMode,
     /// Swing amount (0.0 to 1.0)
     pub swing: f64,
+    /// Per-sixteenth timing and velocity template (applied after swing)
+    pub groove: Option<GrooveTemplate>,
     /// Velocity scale (0.0 to 2.0)
     pub velocity_scale: f64,
     /// Velocity offset (-127 to +127)
@@ -46,6 +269,35 @@ pub struct TrackConfig {
     pub note_min: u8,
     /// Note range maximum (0-127)
     pub note_max: u8,
+    /// Fold notes outside the range back in by octaves instead of dropping them
+    pub fold_range: bool,
+    /// Output layers (empty = single output on `channel`)
+    pub outputs: Vec<OutputLayer>,
+    /// Latch incoming notes
+    pub latch: bool,
+    /// Per-voice routes (matched voices bypass the output layers)
+    pub voice_routes: Vec<VoiceRoute>,
+    /// Rotate notes across channels (bypasses the output layers)
+    pub round_robin: Option<RoundRobin>,
+    /// Play each note on its own MPE channel with its expression (bypasses
+    /// the output layers and round robin)
+    pub mpe: Option<MpeOutput>,
+    /// Rate limit on the track's CC and channel pressure output
+    pub cc_thin: Option<CcThinner>,
+    /// Post-processing of the generated notes, in order
+    pub processors: ProcessorChain,
+    /// Step-sequenced generator parameters
+    pub step_lanes: Vec<StepLane>,
+    /// Chance that output plays (0.0 to 1.0)
+    pub play_probability: f64,
+    /// Whether the probability is rolled per note or per bar
+    pub probability_mode: ProbabilityMode,
+    /// Mute group (launching a clip stops the other tracks in the group)
+    pub mute_group: Option<String>,
+    /// Note length multiplier (0.1 to 2.0)
+    pub gate_scale: f64,
+    /// What the sustain pedal does on this track
+    pub pedal_mode: PedalMode,
 }
 
 impl Default for TrackConfig {
@@ -53,12 +305,29 @@ impl Default for TrackConfig {
         Self {
             name: String::from("Track"),
             channel: 0,
+            destination: None,
             transpose: 0,
+            transpose_mode: TransposeMode::Semitones,
             swing: 0.0,
+            groove: None,
             velocity_scale: 1.0,
             velocity_offset: 0,
             note_min: 0,
             note_max: 127,
+            fold_range: false,
+            outputs: Vec::new(),
+            latch: false,
+            voice_routes: Vec::new(),
+            round_robin: None,
+            mpe: None,
+            cc_thin: None,
+            processors: ProcessorChain::new(),
+            step_lanes: Vec::new(),
+            play_probability: 1.0,
+            probability_mode: ProbabilityMode::PerEvent,
+            mute_group: None,
+            gate_scale: 1.0,
+            pedal_mode: PedalMode::Thru,
         }
     }
 }
@@ -78,17 +347,116 @@ impl TrackConfig {
         self
     }
 
+    /// Set output destination
+    pub fn with_destination(mut self, destination: Option<usize>) -> Self {
+        self.destination = destination;
+        self
+    }
+
     /// Set transpose
     pub fn with_transpose(mut self, transpose: i8) -> Self {
         self.transpose = transpose.clamp(-48, 48);
         self
     }
 
+    /// Set what the transpose counts
+    pub fn with_transpose_mode(mut self, mode: TransposeMode) -> Self {
+        self.transpose_mode = mode;
+        self
+    }
+
     /// Set swing amount
     pub fn with_swing(mut self, swing: f64) -> Self {
         self.swing = swing.clamp(0.0, 1.0);
         self
     }
+
+    /// Set the groove template
+    pub fn with_groove(mut self, groove: Option<GrooveTemplate>) -> Self {
+        self.groove = groove;
+        self
+    }
+
+    /// Set the instrument range, optionally folding stray notes into it
+    pub fn with_range(mut self, low: u8, high: u8, fold: bool) -> Self {
+        self.note_min = low.min(high).min(127);
+        self.note_max = low.max(high).min(127);
+        self.fold_range = fold;
+        self
+    }
+
+    /// Set play probability
+    pub fn with_play_probability(mut self, probability: f64, mode: ProbabilityMode) -> Self {
+        self.play_probability = probability.clamp(0.0, 1.0);
+        self.probability_mode = mode;
+        self
+    }
+
+    /// Add an output layer
+    pub fn with_output(mut self, layer: OutputLayer) -> Self {
+        self.outputs.push(layer);
+        self
+    }
+
+    /// Set latch mode for incoming notes
+    pub fn with_latch(mut self, latch: bool) -> Self {
+        self.latch = latch;
+        self
+    }
+
+    /// Add a voice route
+    pub fn with_voice_route(mut self, route: VoiceRoute) -> Self {
+        self.voice_routes.push(route);
+        self
+    }
+
+    /// Rotate notes across channels
+    pub fn with_round_robin(mut self, round_robin: RoundRobin) -> Self {
+        self.round_robin = Some(round_robin);
+        self
+    }
+
+    /// Play notes as MPE
+    pub fn with_mpe(mut self, mpe: MpeOutput) -> Self {
+        self.mpe = Some(mpe);
+        self
+    }
+
+    /// Thin the CC and channel pressure output
+    pub fn with_cc_thin(mut self, thinner: CcThinner) -> Self {
+        self.cc_thin = Some(thinner);
+        self
+    }
+
+    /// Add a processor to the end of the chain
+    pub fn with_processor(mut self, processor: Processor) -> Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Add a step-sequenced parameter lane
+    pub fn with_step_lane(mut self, lane: StepLane) -> Self {
+        self.step_lanes.push(lane);
+        self
+    }
+
+    /// Set mute group
+    pub fn with_mute_group(mut self, group: impl Into<String>) -> Self {
+        self.mute_group = Some(group.into());
+        self
+    }
+
+    /// Set note length multiplier
+    pub fn with_gate_scale(mut self, scale: f64) -> Self {
+        self.gate_scale = scale.clamp(MIN_GATE_SCALE, MAX_GATE_SCALE);
+        self
+    }
+
+    /// Set sustain pedal behavior
+    pub fn with_pedal_mode(mut self, mode: PedalMode) -> Self {
+        self.pedal_mode = mode;
+        self
+    }
 }
 
 /// A sequencer track
@@ -103,26 +471,60 @@ pub struct Track {
     clips: Vec<Clip>,
     /// Generator for this track (if any)
     generator: Option<Box<dyn Generator>>,
+    /// Generator queued for the next part switch
+    pending_generator: Option<Box<dyn Generator>>,
     /// Current clip state
     clip_state: ClipState,
     /// Track index (for identification)
     index: usize,
     /// Whether this track has pending solo
     pending_solo: bool,
+    /// Latch for incoming notes
+    latch: NoteLatch,
+    /// Sustain pedal handling
+    pedal: SustainPedal,
+    /// Random source for the play probability
+    rng: StdRng,
+    /// Last per-bar probability roll: (bar, plays)
+    bar_roll: Option<(u64, bool)>,
+    /// Notes produced outside the note range since the last reset
+    out_of_range: Cell<u64>,
+    /// Song key from the last generate, for scale-degree transpose and
+    /// the processors
+    key: Key,
+    /// Global gate scale from the track manager
+    master_gate: f64,
+    /// Random clip selection
+    shuffle: Option<ClipShuffle>,
+    /// Fixed clip sequence
+    chain: Option<PatternChain>,
 }
 
 impl Track {
     /// Create a new track
     pub fn new(index: usize, config: TrackConfig) -> Self {
+        let mut latch = NoteLatch::new();
+        latch.set_enabled(config.latch);
+        let pedal = SustainPedal::new(config.pedal_mode);
         Self {
             config,
             state: TrackState::Active,
             active_clip: None,
             clips: Vec::new(),
             generator: None,
+            pending_generator: None,
             clip_state: ClipState::Stopped,
             index,
             pending_solo: false,
+            latch,
+            pedal,
+            rng: StdRng::from_entropy(),
+            bar_roll: None,
+            out_of_range: Cell::new(0),
+            key: Key::new(Note::C, ScaleType::Major),
+            master_gate: 1.0,
+            shuffle: None,
+            chain: None,
         }
     }
 
@@ -151,6 +553,16 @@ impl Track {
         self.config.channel = channel.min(15);
     }
 
+    /// Get output destination
+    pub fn destination(&self) -> Option<usize> {
+        self.config.destination
+    }
+
+    /// Set output destination (None = default output)
+    pub fn set_destination(&mut self, destination: Option<usize>) {
+        self.config.destination = destination;
+    }
+
     /// Get transpose
     pub fn transpose(&self) -> i8 {
         self.config.transpose
@@ -161,6 +573,29 @@ impl Track {
         self.config.transpose = transpose.clamp(-48, 48);
     }
 
+    /// Get what the transpose counts
+    pub fn transpose_mode(&self) -> TransposeMode {
+        self.config.transpose_mode
+    }
+
+    /// Set what the transpose counts
+    pub fn set_transpose_mode(&mut self, mode: TransposeMode) {
+        self.config.transpose_mode = mode;
+    }
+
+    /// Transpose a note in the track's mode (None if it leaves the MIDI range)
+    pub fn transposed(&self, note: u8) -> Option<u8> {
+        let transpose = self.config.transpose;
+        match self.config.transpose_mode {
+            TransposeMode::Semitones => {
+                let transposed = note as i16 + transpose as i16;
+                (0..=127).contains(&transposed).then_some(transposed as u8)
+            }
+            TransposeMode::Degrees if transpose == 0 => Some(note),
+            TransposeMode::Degrees => Some(self.key.scale().transpose_in_scale(note, transpose as i32)),
+        }
+    }
+
     /// Get swing
     pub fn swing(&self) -> f64 {
         self.config.swing
@@ -171,6 +606,205 @@ impl Track {
         self.config.swing = swing.clamp(0.0, 1.0);
     }
 
+    /// Get the groove template
+    pub fn groove(&self) -> Option<&GrooveTemplate> {
+        self.config.groove.as_ref()
+    }
+
+    /// Set the groove template (None = straight)
+    pub fn set_groove(&mut self, groove: Option<GrooveTemplate>) {
+        self.config.groove = groove;
+    }
+
+    /// Get velocity scale
+    pub fn velocity_scale(&self) -> f64 {
+        self.config.velocity_scale
+    }
+
+    /// Set velocity scale
+    pub fn set_velocity_scale(&mut self, scale: f64) {
+        self.config.velocity_scale = scale.clamp(0.0, 2.0);
+    }
+
+    /// Get play probability
+    pub fn play_probability(&self) -> f64 {
+        self.config.play_probability
+    }
+
+    /// Set play probability
+    pub fn set_play_probability(&mut self, probability: f64) {
+        self.config.play_probability = probability.clamp(0.0, 1.0);
+    }
+
+    /// Get probability mode
+    pub fn probability_mode(&self) -> ProbabilityMode {
+        self.config.probability_mode
+    }
+
+    /// Set probability mode
+    pub fn set_probability_mode(&mut self, mode: ProbabilityMode) {
+        self.config.probability_mode = mode;
+        self.bar_roll = None;
+    }
+
+    /// Roll the play probability for an event starting at an absolute tick
+    fn roll_probability(&mut self, tick: u64, ticks_per_bar: u64) -> bool {
+        let probability = self.config.play_probability;
+        if probability >= 1.0 {
+            return true;
+        }
+        if probability <= 0.0 {
+            return false;
+        }
+
+        match self.config.probability_mode {
+            ProbabilityMode::PerEvent => self.rng.gen::<f64>() < probability,
+            ProbabilityMode::PerBar => {
+                let bar = tick / ticks_per_bar.max(1);
+                match self.bar_roll {
+                    Some((rolled, plays)) if rolled == bar => plays,
+                    _ => {
+                        let plays = self.rng.gen::<f64>() < probability;
+                        self.bar_roll = Some((bar, plays));
+                        plays
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get the note range (low, high)
+    pub fn note_range(&self) -> (u8, u8) {
+        (self.config.note_min, self.config.note_max)
+    }
+
+    /// Set the note range (swapped if given high to low)
+    pub fn set_note_range(&mut self, low: u8, high: u8) {
+        self.config.note_min = low.min(high).min(127);
+        self.config.note_max = low.max(high).min(127);
+    }
+
+    /// Check if stray notes are folded into the range
+    pub fn fold_range(&self) -> bool {
+        self.config.fold_range
+    }
+
+    /// Fold stray notes into the range instead of dropping them
+    pub fn set_fold_range(&mut self, fold: bool) {
+        self.config.fold_range = fold;
+    }
+
+    /// Notes produced outside the range since the last reset
+    pub fn out_of_range_count(&self) -> u64 {
+        self.out_of_range.get()
+    }
+
+    /// Clear the out-of-range count
+    pub fn clear_out_of_range(&self) {
+        self.out_of_range.set(0);
+    }
+
+    /// Clip notes that land outside the range after transpose, as (clip, count)
+    pub fn clip_range_violations(&self) -> Vec<(usize, usize)> {
+        self.clips
+            .iter()
+            .enumerate()
+            .map(|(i, clip)| {
+                let count = clip
+                    .notes()
+                    .iter()
+                    .filter(|n| {
+                        self.transposed(n.note)
+                            .map_or(true, |n| n < self.config.note_min || n > self.config.note_max)
+                    })
+                    .count();
+                (i, count)
+            })
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+
+    /// Get note length multiplier
+    pub fn gate_scale(&self) -> f64 {
+        self.config.gate_scale
+    }
+
+    /// Set note length multiplier
+    pub fn set_gate_scale(&mut self, scale: f64) {
+        self.config.gate_scale = scale.clamp(MIN_GATE_SCALE, MAX_GATE_SCALE);
+    }
+
+    /// Set the global gate scale applied on top of the track's own
+    pub fn set_master_gate(&mut self, scale: f64) {
+        self.master_gate = scale.clamp(MIN_GATE_SCALE, MAX_GATE_SCALE);
+    }
+
+    /// Get mute group
+    pub fn mute_group(&self) -> Option<&str> {
+        self.config.mute_group.as_deref()
+    }
+
+    /// Set mute group
+    pub fn set_mute_group(&mut self, group: Option<String>) {
+        self.config.mute_group = group;
+    }
+
+    /// Get output layers
+    pub fn outputs(&self) -> &[OutputLayer] {
+        &self.config.outputs
+    }
+
+    /// Add an output layer
+    pub fn add_output(&mut self, layer: OutputLayer) {
+        self.config.outputs.push(layer);
+    }
+
+    /// Remove all output layers (back to the single track channel)
+    pub fn clear_outputs(&mut self) {
+        self.config.outputs.clear();
+    }
+
+    /// Get voice routes
+    pub fn voice_routes(&self) -> &[VoiceRoute] {
+        &self.config.voice_routes
+    }
+
+    /// Route a voice, replacing any existing route for the same note
+    pub fn set_voice_route(&mut self, route: VoiceRoute) {
+        self.config.voice_routes.retain(|r| r.note != route.note);
+        self.config.voice_routes.push(route);
+    }
+
+    /// Remove all voice routes
+    pub fn clear_voice_routes(&mut self) {
+        self.config.voice_routes.clear();
+    }
+
+    /// Get the round-robin rotation
+    pub fn round_robin(&self) -> Option<&RoundRobin> {
+        self.config.round_robin.as_ref()
+    }
+
+    /// Turn round-robin output on (Some) or off (None)
+    pub fn set_round_robin(&mut self, round_robin: Option<RoundRobin>) {
+        self.config.round_robin = round_robin;
+    }
+
+    /// CC thinning statistics (None when the track isn't thinned)
+    pub fn cc_thin_stats(&self) -> Option<ThinStats> {
+        self.config.cc_thin.as_ref().map(CcThinner::stats)
+    }
+
+    /// Get the MPE output
+    pub fn mpe(&self) -> Option<&MpeOutput> {
+        self.config.mpe.as_ref()
+    }
+
+    /// Turn MPE output on (Some) or off (None)
+    pub fn set_mpe(&mut self, mpe: Option<MpeOutput>) {
+        self.config.mpe = mpe;
+    }
+
     /// Get current state
     pub fn state(&self) -> TrackState {
         self.state
@@ -234,6 +868,63 @@ impl Track {
         self.generator = None;
     }
 
+    /// Seed the track's random choices and its generators, so the same
+    /// seed plays the same notes
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.bar_roll = None;
+        self.config.processors.set_seed(seed);
+        if let Some(ref mut generator) = self.generator {
+            generator.set_seed(seed);
+        }
+        if let Some(ref mut generator) = self.pending_generator {
+            generator.set_seed(seed);
+        }
+    }
+
+    /// Queue a generator to replace the current one at the next part switch
+    pub fn queue_generator(&mut self, generator: Box<dyn Generator>) {
+        self.pending_generator = Some(generator);
+    }
+
+    /// Check if a generator is queued
+    pub fn has_pending_generator(&self) -> bool {
+        self.pending_generator.is_some()
+    }
+
+    /// Render one bar of the queued generator and discard it, so generators
+    /// that build patterns as they play are fully formed at the downbeat.
+    /// Returns true if a generator was pre-rolled.
+    pub fn preroll_generator(&mut self, context: &GeneratorContext) -> bool {
+        let Some(ref mut generator) = self.pending_generator else {
+            return false;
+        };
+        let bar = GeneratorContext {
+            ticks_to_generate: context.ticks_per_bar(),
+            ..context.clone()
+        };
+        generator.generate(&bar);
+        generator.take_pitch_bends();
+        generator.take_control_changes();
+        true
+    }
+
+    /// Swap in the queued generator. Returns true if one was waiting.
+    pub fn commit_generator(&mut self) -> bool {
+        match self.pending_generator.take() {
+            Some(generator) => {
+                self.generator = Some(generator);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop the queued generator
+    pub fn cancel_pending_generator(&mut self) {
+        self.pending_generator = None;
+    }
+
     /// Add a clip to this track
     pub fn add_clip(&mut self, clip: Clip) -> usize {
         self.clips.push(clip);
@@ -281,18 +972,79 @@ impl Track {
         self.active_clip.and_then(|idx| self.clips.get_mut(idx))
     }
 
+    /// Get clip shuffle settings
+    pub fn clip_shuffle(&self) -> Option<&ClipShuffle> {
+        self.shuffle.as_ref()
+    }
+
+    /// Turn clip shuffle on (Some) or off (None)
+    pub fn set_clip_shuffle(&mut self, shuffle: Option<ClipShuffle>) {
+        self.shuffle = shuffle;
+    }
+
+    /// Get the pattern chain
+    pub fn pattern_chain(&self) -> Option<&PatternChain> {
+        self.chain.as_ref()
+    }
+
+    /// Get mutable pattern chain
+    pub fn pattern_chain_mut(&mut self) -> Option<&mut PatternChain> {
+        self.chain.as_mut()
+    }
+
+    /// Turn the pattern chain on (Some) or off (None)
+    pub fn set_pattern_chain(&mut self, chain: Option<PatternChain>) {
+        self.chain = chain;
+    }
+
+    /// Move the chain on a loop, restarting its clip from the top
+    fn chain_clip(&mut self, context: &GeneratorContext) {
+        let Some(chain) = self.chain.as_mut() else {
+            return;
+        };
+        let Some(index) = chain.advance().filter(|&i| i < self.clips.len()) else {
+            return;
+        };
+        if let Some(previous) = self.active_clip.filter(|&i| i != index) {
+            self.clips[previous].stop();
+        }
+        self.active_clip = Some(index);
+        let clip = &mut self.clips[index];
+        clip.reset();
+        clip.play();
+        chain.started(context.total_ticks(), clip.length());
+    }
+
+    /// Pick the next clip at random and start it from the top
+    fn shuffle_clip(&mut self, context: &GeneratorContext) {
+        let Some(shuffle) = self.shuffle.as_mut() else {
+            return;
+        };
+        let Some(index) = shuffle.choose(self.clips.len(), &mut self.rng) else {
+            return;
+        };
+        if let Some(previous) = self.active_clip.filter(|&i| i != index) {
+            self.clips[previous].stop();
+        }
+        self.active_clip = Some(index);
+        let clip = &mut self.clips[index];
+        clip.reset();
+        clip.play();
+        shuffle.picked(context.total_ticks(), context.ticks_per_bar(), clip.length());
+    }
+
     /// Process MIDI events - apply transpose and velocity scaling
     fn process_event(&self, mut event: MidiEvent) -> Option<MidiEvent> {
         // Apply transpose
-        let transposed = event.note as i16 + self.config.transpose as i16;
-        if transposed < 0 || transposed > 127 {
-            return None; // Note out of range
-        }
-        event.note = transposed as u8;
+        event.note = self.transposed(event.note)?;
 
         // Apply note range filter
         if event.note < self.config.note_min || event.note > self.config.note_max {
-            return None;
+            self.out_of_range.set(self.out_of_range.get() + 1);
+            if !self.config.fold_range {
+                return None;
+            }
+            event.note = fold_into_range(event.note, self.config.note_min, self.config.note_max);
         }
 
         // Apply velocity scaling and offset
@@ -300,6 +1052,12 @@ impl Track {
             + self.config.velocity_offset as i16;
         event.velocity = scaled.clamp(1, 127) as u8;
 
+        // Apply gate scaling
+        let gate = self.config.gate_scale * self.master_gate;
+        if gate != 1.0 {
+            event.duration_ticks = ((event.duration_ticks as f64 * gate).round() as u64).max(1);
+        }
+
         // Set channel
         event.channel = self.config.channel;
 
@@ -308,21 +1066,7 @@ impl Track {
 
     /// Apply swing to tick position
     fn apply_swing(&self, tick: u64, ppqn: u32) -> u64 {
-        if self.config.swing == 0.0 {
-            return tick;
-        }
-
-        let beat_ticks = ppqn as u64;
-        let half_beat = beat_ticks / 2;
-        let tick_in_beat = tick % beat_ticks;
-
-        // Apply swing to off-beat notes (second half of beat)
-        if tick_in_beat >= half_beat {
-            let swing_offset = (half_beat as f64 * self.config.swing * 0.5) as u64;
-            tick + swing_offset
-        } else {
-            tick
-        }
+        tick + swing_offset(tick, ppqn, self.config.swing)
     }
 
     /// Generate events for this track
@@ -333,9 +1077,16 @@ impl Track {
         }
 
         let mut events = Vec::new();
+        self.key = context.key.clone();
 
-        // Generate from generator if present
+        // Generate from generator if present, after the step lanes set
+        // their parameters
         if let Some(ref mut generator) = self.generator {
+            for lane in &mut self.config.step_lanes {
+                if let Some(value) = lane.poll(context.total_ticks(), context.ticks_per_bar()) {
+                    generator.set_param(lane.param(), value);
+                }
+            }
             let generated = generator.generate(context);
             for event in generated {
                 if let Some(processed) = self.process_event(event) {
@@ -344,6 +1095,14 @@ impl Track {
             }
         }
 
+        // A pattern chain moves on at the end of each loop; shuffle mode
+        // picks a clip on its bar or loop boundary
+        if self.chain.as_ref().is_some_and(|c| c.is_due(context.total_ticks())) {
+            self.chain_clip(context);
+        } else if self.chain.is_none() && self.shuffle.as_ref().is_some_and(|s| s.is_due(context.total_ticks())) {
+            self.shuffle_clip(context);
+        }
+
         // Generate from active clip if present
         if let Some(clip_idx) = self.active_clip {
             if let Some(clip) = self.clips.get_mut(clip_idx) {
@@ -356,12 +1115,33 @@ impl Track {
             }
         }
 
-        // Apply swing
+        // A held pedal can freeze the output
+        events = self.pedal.process_output(events, context.ticks_to_generate);
+
+        // Apply play probability
+        if self.config.play_probability < 1.0 {
+            let origin = context.total_ticks();
+            let ticks_per_bar = context.ticks_per_bar();
+            events.retain(|event| self.roll_probability(origin + event.start_tick, ticks_per_bar));
+        }
+
+        // Apply swing and groove at the song position
+        let origin = context.total_ticks();
         for event in &mut events {
-            event.start_tick = self.apply_swing(event.start_tick, context.ppqn);
+            let tick = origin + event.start_tick;
+            let mut shift = self.apply_swing(tick, context.ppqn) as i64 - tick as i64;
+            let mut velocity = 0;
+            if let Some(groove) = &self.config.groove {
+                let (ticks, scale) = groove.apply(tick, context.ppqn);
+                shift += ticks;
+                if event.velocity > 0 {
+                    velocity = (event.velocity as f64 * scale).round() as i16 - event.velocity as i16;
+                }
+            }
+            event.humanize(shift, velocity);
         }
 
-        events
+        self.config.processors.process(events, &self.key)
     }
 
     /// Convert generated events to scheduled events
@@ -373,26 +1153,206 @@ impl Track {
         let events = self.generate(context);
         let mut scheduled = Vec::new();
 
+        // Round-robin channels are detuned before their first note
+        if let Some(ref mut round_robin) = self.config.round_robin {
+            for (channel, destination, bend) in round_robin.take_detune() {
+                scheduled.push(
+                    ScheduledEvent::pitch_bend(base_tick, channel, bend)
+                        .with_track(self.index)
+                        .with_destination(destination),
+                );
+            }
+        }
+
+        // The MPE zone is set up before its first note
+        if let Some(ref mut mpe) = self.config.mpe {
+            scheduled.extend(mpe.take_setup(base_tick).into_iter().map(|e| e.with_track(self.index)));
+        }
+
+        // Pitch bends go to every output channel
+        let bends = match self.generator {
+            Some(ref mut generator) => generator.take_pitch_bends(),
+            None => Vec::new(),
+        };
+        for bend in bends {
+            let tick = base_tick + bend.start_tick;
+            if let Some(ref mpe) = self.config.mpe {
+                // Bends on the manager channel move every note in the zone
+                scheduled.push(
+                    ScheduledEvent::pitch_bend(tick, mpe.zone().manager_channel(), bend.value)
+                        .with_track(self.index)
+                        .with_destination(mpe.destination()),
+                );
+                continue;
+            }
+            if let Some(ref round_robin) = self.config.round_robin {
+                // Keep each channel's detune under the bend
+                let range = round_robin.bend_range();
+                for voice in round_robin.voices() {
+                    scheduled.push(
+                        ScheduledEvent::pitch_bend(tick, voice.channel, bend.value.saturating_add(voice.bend(range)))
+                            .with_track(self.index)
+                            .with_destination(voice.destination),
+                    );
+                }
+                continue;
+            }
+            if self.config.outputs.is_empty() {
+                scheduled.push(
+                    ScheduledEvent::pitch_bend(tick, self.config.channel, bend.value)
+                        .with_track(self.index)
+                        .with_destination(self.config.destination),
+                );
+            }
+            for layer in &self.config.outputs {
+                scheduled.push(
+                    ScheduledEvent::pitch_bend(tick, layer.channel, bend.value)
+                        .with_track(self.index)
+                        .with_destination(layer.destination),
+                );
+            }
+        }
+
+        // Generated and clip control changes go to the channels the notes play on
+        let mut controls = match self.generator {
+            Some(ref mut generator) => generator.take_control_changes(),
+            None => Vec::new(),
+        };
+        if let Some(clip) = self.active_clip.and_then(|i| self.clips.get_mut(i)) {
+            controls.extend(clip.take_control_changes());
+        }
+        if !controls.is_empty() {
+            let targets: Vec<(u8, Option<usize>)> = if let Some(ref mpe) = self.config.mpe {
+                vec![(mpe.zone().manager_channel(), mpe.destination())]
+            } else if let Some(ref round_robin) = self.config.round_robin {
+                round_robin.voices().iter().map(|v| (v.channel, v.destination)).collect()
+            } else if self.config.outputs.is_empty() {
+                vec![(self.config.channel, self.config.destination)]
+            } else {
+                self.config.outputs.iter().map(|l| (l.channel, l.destination)).collect()
+            };
+            for control in controls {
+                for &(channel, destination) in &targets {
+                    scheduled.push(
+                        ScheduledEvent::control_change(base_tick + control.start_tick, channel, control.cc, control.value)
+                            .with_track(self.index)
+                            .with_destination(destination),
+                    );
+                }
+            }
+        }
+
         for event in events {
-            let start_tick = base_tick + event.start_tick;
-            let end_tick = start_tick + event.duration_ticks;
+            // Routed drum voices go only to their own channel/destination
+            if let Some(route) = self.config.voice_routes.iter().find(|r| r.note == event.note) {
+                let routed = route.apply(&event);
+                self.push_scheduled(&mut scheduled, &routed, base_tick, route.destination);
+                continue;
+            }
 
-            // Note on
-            scheduled.push(
-                ScheduledEvent::note_on(start_tick, event.channel, event.note, event.velocity)
-                    .with_track(self.index),
-            );
+            // MPE: each note on its own channel with its expression
+            if let Some(ref mut mpe) = self.config.mpe {
+                scheduled.extend(mpe.render(&event, base_tick).into_iter().map(|e| e.with_track(self.index)));
+                continue;
+            }
 
-            // Note off
-            scheduled.push(
-                ScheduledEvent::note_off(end_tick, event.channel, event.note)
-                    .with_track(self.index),
-            );
+            // Round-robin: each note to the next channel in rotation
+            if let Some(ref mut round_robin) = self.config.round_robin {
+                if let Some((voiced, destination)) = round_robin.route(&event, base_tick) {
+                    self.push_scheduled(&mut scheduled, &voiced, base_tick, destination);
+                }
+                continue;
+            }
+
+            if self.config.outputs.is_empty() {
+                self.push_scheduled(&mut scheduled, &event, base_tick, self.config.destination);
+                continue;
+            }
+
+            // Layered output: one copy per destination
+            for layer in &self.config.outputs {
+                if let Some(layered) = layer.apply(&event) {
+                    self.push_scheduled(&mut scheduled, &layered, base_tick, layer.destination);
+                }
+            }
+        }
+
+        // Dense controller streams are thinned for slow hardware
+        if let Some(ref mut thinner) = self.config.cc_thin {
+            scheduled = thinner.process(scheduled, base_tick);
         }
 
         scheduled
     }
 
+    /// Push a note on/off pair for an event
+    fn push_scheduled(
+        &self,
+        scheduled: &mut Vec<ScheduledEvent>,
+        event: &MidiEvent,
+        base_tick: u64,
+        destination: Option<usize>,
+    ) {
+        let start_tick = base_tick + event.start_tick;
+        let end_tick = start_tick + event.duration_ticks;
+
+        // Note on
+        scheduled.push(
+            ScheduledEvent::note_on(start_tick, event.channel, event.note, event.velocity)
+                .with_track(self.index)
+                .with_destination(destination),
+        );
+
+        // Note off
+        scheduled.push(
+            ScheduledEvent::note_off(end_tick, event.channel, event.note)
+                .with_track(self.index)
+                .with_destination(destination),
+        );
+    }
+
+    /// Get the input latch
+    pub fn latch(&self) -> &NoteLatch {
+        &self.latch
+    }
+
+    /// Get the mutable input latch
+    pub fn latch_mut(&mut self) -> &mut NoteLatch {
+        &mut self.latch
+    }
+
+    /// Get the sustain pedal
+    pub fn pedal(&self) -> &SustainPedal {
+        &self.pedal
+    }
+
+    /// Set sustain pedal behavior, returning note offs for held notes
+    pub fn set_pedal_mode(&mut self, mode: PedalMode) -> Vec<MidiMessage> {
+        self.config.pedal_mode = mode;
+        self.pedal.set_mode(mode)
+    }
+
+    /// Pass a live input message through the pedal, latch and processors,
+    /// returning the messages to send on, each with the ticks to wait first
+    pub fn process_input(&mut self, message: &MidiMessage) -> Vec<(u64, MidiMessage)> {
+        let latched: Vec<MidiMessage> = self
+            .pedal
+            .process(message, &mut self.latch)
+            .iter()
+            .flat_map(|m| self.latch.process(m))
+            .collect();
+        latched
+            .iter()
+            .flat_map(|m| self.config.processors.process_input(m, &self.key))
+            .collect()
+    }
+
+    /// Toggle latch mode, returning note offs for released notes
+    pub fn toggle_latch(&mut self) -> Vec<MidiMessage> {
+        self.config.latch = !self.latch.is_enabled();
+        self.latch.set_enabled(self.config.latch)
+    }
+
     /// Reset the track
     pub fn reset(&mut self) {
         if let Some(ref mut generator) = self.generator {
@@ -401,15 +1361,64 @@ impl Track {
         for clip in &mut self.clips {
             clip.reset();
         }
+        if let Some(ref mut shuffle) = self.shuffle {
+            shuffle.reset();
+        }
+        if let Some(ref mut chain) = self.chain {
+            chain.reset();
+        }
+        if let Some(ref mut round_robin) = self.config.round_robin {
+            round_robin.reset();
+        }
+        if let Some(ref mut mpe) = self.config.mpe {
+            mpe.reset();
+        }
+        if let Some(ref mut thinner) = self.config.cc_thin {
+            thinner.reset();
+        }
+        for lane in &mut self.config.step_lanes {
+            lane.reset();
+        }
         self.clip_state = ClipState::Stopped;
     }
 }
 
+/// Delay applied by swing to a tick: off-beat notes (second half of the
+/// beat) move later by up to a quarter of the beat
+pub fn swing_offset(tick: u64, ppqn: u32, swing: f64) -> u64 {
+    if swing == 0.0 {
+        return 0;
+    }
+
+    let beat_ticks = (ppqn as u64).max(1);
+    let half_beat = beat_ticks / 2;
+    if tick % beat_ticks >= half_beat {
+        (half_beat as f64 * swing * 0.5) as u64
+    } else {
+        0
+    }
+}
+
+/// Move a note by octaves until it lies in [low, high], clamping if the
+/// range is narrower than an octave
+pub fn fold_into_range(note: u8, low: u8, high: u8) -> u8 {
+    let mut folded = note as i16;
+    while folded < low as i16 {
+        folded += 12;
+    }
+    while folded > high as i16 {
+        folded -= 12;
+    }
+    folded.clamp(low as i16, high as i16) as u8
+}
+
 /// Manager for multiple tracks with solo handling
 pub struct TrackManager {
     tracks: Vec<Track>,
     /// Whether any track is soloed
     has_solo: bool,
+    /// Global note length multiplier
+    gate_scale: f64,
 }
 
 impl TrackManager {
@@ -418,16 +1427,32 @@ impl TrackManager {
         Self {
             tracks: Vec::new(),
             has_solo: false,
+            gate_scale: 1.0,
         }
     }
 
     /// Add a track
     pub fn add_track(&mut self, config: TrackConfig) -> usize {
         let index = self.tracks.len();
-        self.tracks.push(Track::new(index, config));
+        let mut track = Track::new(index, config);
+        track.set_master_gate(self.gate_scale);
+        self.tracks.push(track);
         index
     }
 
+    /// Get global note length multiplier
+    pub fn gate_scale(&self) -> f64 {
+        self.gate_scale
+    }
+
+    /// Set global note length multiplier (applies on top of each track's)
+    pub fn set_gate_scale(&mut self, scale: f64) {
+        self.gate_scale = scale.clamp(MIN_GATE_SCALE, MAX_GATE_SCALE);
+        for track in &mut self.tracks {
+            track.set_master_gate(self.gate_scale);
+        }
+    }
+
     /// Get a track by index
     pub fn track(&self, index: usize) -> Option<&Track> {
         self.tracks.get(index)
@@ -501,6 +1526,50 @@ impl TrackManager {
         all_events
     }
 
+    /// Tracks that must stop when a clip is launched on a track: the other
+    /// tracks in its mute group, and tracks playing a clip in the launched
+    /// clip's group. Only tracks with an active clip are returned.
+    pub fn exclusive_tracks(&self, track_index: usize, clip_index: Option<usize>) -> Vec<usize> {
+        let Some(track) = self.tracks.get(track_index) else {
+            return Vec::new();
+        };
+        let track_group = track.mute_group();
+        let clip_group = clip_index.and_then(|i| track.clip(i)).and_then(Clip::group);
+
+        self.tracks
+            .iter()
+            .enumerate()
+            .filter(|&(i, other)| {
+                if i == track_index || other.active_clip().is_none() {
+                    return false;
+                }
+                let same_track_group = track_group.is_some() && other.mute_group() == track_group;
+                let same_clip_group =
+                    clip_group.is_some() && other.active_clip().and_then(Clip::group) == clip_group;
+                same_track_group || same_clip_group
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Pre-roll queued generators on all tracks
+    pub fn preroll_pending(&mut self, context: &GeneratorContext) -> usize {
+        self.tracks
+            .iter_mut()
+            .map(|track| track.preroll_generator(context))
+            .filter(|&prerolled| prerolled)
+            .count()
+    }
+
+    /// Swap in queued generators on all tracks
+    pub fn commit_pending(&mut self) -> usize {
+        self.tracks
+            .iter_mut()
+            .map(|track| track.commit_generator())
+            .filter(|&committed| committed)
+            .count()
+    }
+
     /// Reset all tracks
     pub fn reset_all(&mut self) {
         for track in &mut self.tracks {
@@ -529,6 +1598,7 @@ impl Default for TrackManager {
 mod tests {
     use super::*;
     use crate::music::scale::{Key, Note, ScaleType};
+    use crate::sequencer::ClipNote;
 
     fn test_context() -> GeneratorContext {
         GeneratorContext {
@@ -540,6 +1610,53 @@ mod tests {
         }
     }
 
+    /// Generator playing the same notes on every call
+    struct FixedNotes {
+        notes: Vec<MidiEvent>,
+        /// Calls that stay silent after a reset (a generator building its pattern)
+        warmup: usize,
+        silent: usize,
+    }
+
+    impl FixedNotes {
+        fn new(notes: Vec<MidiEvent>) -> Box<Self> {
+            Box::new(Self {
+                notes,
+                warmup: 0,
+                silent: 0,
+            })
+        }
+
+        fn with_warmup(mut self: Box<Self>, calls: usize) -> Box<Self> {
+            self.warmup = calls;
+            self.silent = calls;
+            self
+        }
+    }
+
+    impl Generator for FixedNotes {
+        fn generate(&mut self, _context: &GeneratorContext) -> Vec<MidiEvent> {
+            if self.silent > 0 {
+                self.silent -= 1;
+                return Vec::new();
+            }
+            self.notes.clone()
+        }
+        fn set_param(&mut self, _name: &str, _value: f64) {}
+        fn get_param(&self, _name: &str) -> Option<f64> {
+            None
+        }
+        fn reset(&mut self) {
+            self.silent = self.warmup;
+        }
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+        fn params(&self) -> std::collections::HashMap<String, f64> {
+            std::collections::HashMap::new()
+        }
+    }
+
     #[test]
     fn test_track_creation() {
         let track = Track::with_index(0);
@@ -587,6 +1704,27 @@ mod tests {
         assert_eq!(processed.note, 72); // 60 + 12
     }
 
+    #[test]
+    fn test_transpose_by_scale_degrees() {
+        let mut track = Track::with_index(0);
+        track.set_transpose(2);
+        track.set_transpose_mode(TransposeMode::Degrees);
+
+        // C major: up a third in key, wrapping past the octave
+        let notes: Vec<u8> = [60, 64, 71, 61]
+            .iter()
+            .map(|&n| track.process_event(MidiEvent::new(n, 100, 0, 24)).unwrap().note)
+            .collect();
+        assert_eq!(notes, vec![64, 67, 74, 64]);
+
+        // The song key comes from the generator context
+        let mut context = GeneratorContext::default();
+        context.key = Key::new(Note::A, ScaleType::NaturalMinor);
+        track.generate(&context);
+        assert_eq!(track.process_event(MidiEvent::new(60, 100, 0, 24)).unwrap().note, 64);
+        assert_eq!(track.process_event(MidiEvent::new(67, 100, 0, 24)).unwrap().note, 71);
+    }
+
     #[test]
     fn test_transpose_out_of_range() {
         let mut track = Track::with_index(0);
@@ -599,6 +1737,54 @@ mod tests {
         assert!(processed.is_none());
     }
 
+    #[test]
+    fn test_instrument_range() {
+        assert_eq!(fold_into_range(28, 28, 64), 28);
+        assert_eq!(fold_into_range(24, 28, 64), 36);
+        assert_eq!(fold_into_range(72, 28, 64), 60);
+        assert_eq!(fold_into_range(61, 64, 66), 64);
+
+        // Bass synth E1-E4: stray notes are dropped and counted
+        let mut track = Track::new(0, TrackConfig::new("Bass").with_range(64, 28, false));
+        assert_eq!(track.note_range(), (28, 64));
+        assert!(track.process_event(MidiEvent::new(72, 100, 0, 24)).is_none());
+        assert_eq!(track.process_event(MidiEvent::new(40, 100, 0, 24)).unwrap().note, 40);
+        assert_eq!(track.out_of_range_count(), 1);
+
+        // With folding they come back an octave down
+        track.set_fold_range(true);
+        assert_eq!(track.process_event(MidiEvent::new(72, 100, 0, 24)).unwrap().note, 60);
+        assert_eq!(track.out_of_range_count(), 2);
+        track.clear_out_of_range();
+        assert_eq!(track.out_of_range_count(), 0);
+
+        let mut clip = Clip::new("riff", 96);
+        clip.add_notes([ClipNote::new(0, 6, 40, 100), ClipNote::new(24, 6, 76, 100)]);
+        track.add_clip(Clip::new("empty", 96));
+        track.add_clip(clip);
+        assert_eq!(track.clip_range_violations(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_gate_scale() {
+        let mut manager = TrackManager::new();
+        manager.add_track(TrackConfig::new("Lead").with_gate_scale(0.5));
+        let event = MidiEvent::new(60, 100, 0, 24);
+        let track = manager.track(0).unwrap();
+        assert_eq!(track.process_event(event.clone()).unwrap().duration_ticks, 12);
+
+        // The global scale multiplies the track's
+        manager.set_gate_scale(3.0);
+        assert_eq!(manager.gate_scale(), MAX_GATE_SCALE);
+        assert_eq!(manager.track(0).unwrap().process_event(event.clone()).unwrap().duration_ticks, 24);
+
+        // Never shorter than a tick
+        manager.set_gate_scale(0.1);
+        manager.track_mut(0).unwrap().set_gate_scale(0.1);
+        let short = MidiEvent::new(60, 100, 0, 2);
+        assert_eq!(manager.track(0).unwrap().process_event(short).unwrap().duration_ticks, 1);
+    }
+
     #[test]
     fn test_velocity_scaling() {
         let config = TrackConfig {
@@ -649,6 +1835,35 @@ mod tests {
         assert!(manager.should_output(1));
     }
 
+    #[test]
+    fn test_play_probability() {
+        let mut track = Track::with_index(0);
+        track.set_generator(FixedNotes::new((0..16).map(|i| MidiEvent::new(60, 100, i * 6, 3)).collect()));
+        track.rng = StdRng::seed_from_u64(3);
+        let bar = |n: u64| GeneratorContext {
+            bar: n,
+            ticks_to_generate: 96,
+            ..test_context()
+        };
+
+        track.set_play_probability(0.0);
+        assert!(track.generate(&bar(0)).is_empty());
+
+        // Per event: some notes of the bar play, some don't
+        track.set_play_probability(0.5);
+        let played = track.generate(&bar(0)).len();
+        assert!(played > 0 && played < 16);
+
+        // Per bar: each bar plays whole or not at all
+        track.set_probability_mode(ProbabilityMode::PerBar);
+        let counts: Vec<usize> = (0..12).map(|n| track.generate(&bar(n)).len()).collect();
+        assert!(counts.iter().all(|&c| c == 0 || c == 16));
+        assert!(counts.contains(&0) && counts.contains(&16));
+
+        track.set_play_probability(1.5);
+        assert_eq!(track.play_probability(), 1.0);
+    }
+
     #[test]
     fn test_swing_application() {
         let config = TrackConfig {
@@ -663,5 +1878,407 @@ mod tests {
         // Tick 12 (off-beat) should be delayed
         let swung = track.apply_swing(12, 24);
         assert!(swung > 12);
+
+        // Generated notes keep the swing as feel, so it can be taken out
+        let mut clip = Clip::new("Offbeat", 24);
+        clip.add_note(ClipNote::new(12, 6, 60, 100));
+        clip.play();
+        let mut track = Track::new(
+            0,
+            TrackConfig {
+                swing: 0.5,
+                ..Default::default()
+            },
+        );
+        track.add_clip(clip);
+        track.set_active_clip(Some(0));
+        let events = track.generate(&test_context());
+        assert_eq!(events[0].start_tick, swung);
+        assert_eq!(events[0].feel.ticks, (swung - 12) as i64);
+        assert_eq!(events[0].score().start_tick, 12);
+    }
+
+    #[test]
+    fn test_groove_at_song_position() {
+        let groove = GrooveTemplate::new("late", vec![0.0, 0.5], vec![1.0, 0.5]);
+        let mut clip = Clip::new("Sixteenths", 24);
+        clip.add_note(ClipNote::new(6, 3, 60, 100));
+        clip.play();
+        let mut track = Track::new(0, TrackConfig::default().with_groove(Some(groove)));
+        track.add_clip(clip);
+        track.set_active_clip(Some(0));
+
+        // Generated one tick at a time, the second sixteenth still moves
+        let mut events = Vec::new();
+        for tick in 0..24 {
+            let context = GeneratorContext {
+                tick,
+                ticks_to_generate: 1,
+                ..test_context()
+            };
+            events.extend(track.generate(&context));
+        }
+        let note = events.iter().find(|e| e.velocity > 0).unwrap();
+        assert_eq!(note.feel, crate::generators::Feel { ticks: 3, velocity: -50 });
+        assert_eq!(note.score().velocity, 100);
+    }
+
+    #[test]
+    fn test_track_latch() {
+        let mut track = Track::new(0, TrackConfig::new("Pad").with_latch(true));
+        assert!(track.latch().is_enabled());
+
+        let on = MidiMessage::NoteOn { channel: 0, note: 60, velocity: 90 };
+        track.latch_mut().process(&on);
+        assert_eq!(track.latch().latched_count(), 1);
+
+        let released = track.toggle_latch();
+        assert_eq!(released.len(), 1);
+        assert!(!track.latch().is_enabled());
+    }
+
+    #[test]
+    fn test_output_layers() {
+        let one_note = || FixedNotes::new(vec![MidiEvent::new(60, 100, 0, 12)]);
+        let config = TrackConfig::new("Layered")
+            .with_output(OutputLayer::new(0))
+            .with_output(
+                OutputLayer::new(9)
+                    .with_destination(Some(1))
+                    .with_transpose(12)
+                    .with_velocity_scale(0.5),
+            );
+        let mut track = Track::new(0, config);
+        track.set_generator(one_note());
+
+        let scheduled = track.generate_scheduled(&test_context(), 0);
+        assert_eq!(scheduled.len(), 4);
+
+        let layer_on = &scheduled[2];
+        assert_eq!(layer_on.channel, 9);
+        assert_eq!(layer_on.data1, 72);
+        assert_eq!(layer_on.data2, 50);
+        assert_eq!(layer_on.destination, Some(1));
+        assert_eq!(scheduled[0].destination, None);
+
+        // Without layers the track plays on its own destination
+        let mut track = Track::new(0, TrackConfig::new("Synth").with_destination(Some(2)));
+        track.set_generator(one_note());
+        let scheduled = track.generate_scheduled(&test_context(), 0);
+        assert!(scheduled.iter().all(|e| e.destination == Some(2)));
+    }
+
+    #[test]
+    fn test_generator_control_changes() {
+        let config = TrackConfig::new("Texture")
+            .with_output(OutputLayer::new(2))
+            .with_output(OutputLayer::new(5).with_destination(Some(1)));
+        let mut track = Track::new(0, config);
+        track.set_generator(Box::new(crate::generators::texture::TextureGenerator::new()));
+
+        // A sweep value every 6 ticks, on both layers
+        let scheduled = track.generate_scheduled(&test_context(), 96);
+        assert_eq!(scheduled.len(), 8);
+        assert!(scheduled.iter().all(|e| e.message_type == crate::sequencer::scheduler::MidiMessageType::ControlChange));
+        assert_eq!(scheduled[0].to_midi_bytes(), vec![0xB2, 74, 20]);
+        assert_eq!(scheduled[1].destination, Some(1));
+        assert_eq!(scheduled[7].time_ticks, 96 + 18);
+    }
+
+    #[test]
+    fn test_clip_control_changes() {
+        let mut track = Track::new(0, TrackConfig::new("Bass").with_channel(3));
+        let mut clip = Clip::new("Filter", 96);
+        clip.add_note(ClipNote::new(0, 12, 36, 100));
+        clip.add_cc(crate::sequencer::ClipCc::new(6, 74, 90));
+        let index = track.add_clip(clip);
+        track.set_active_clip(Some(index));
+        track.active_clip_mut().unwrap().play();
+
+        let scheduled = track.generate_scheduled(&test_context(), 48);
+        let control_change = crate::sequencer::scheduler::MidiMessageType::ControlChange;
+        let cc = scheduled.iter().find(|e| e.message_type == control_change).unwrap();
+        assert_eq!((cc.time_ticks, cc.to_midi_bytes()), (54, vec![0xB3, 74, 90]));
+    }
+
+    #[test]
+    fn test_cc_thinning() {
+        let mut track = Track::new(0, TrackConfig::new("Texture").with_cc_thin(CcThinner::new(12, 1)));
+        let mut texture = crate::generators::texture::TextureGenerator::new();
+        texture.set_param("resolution", 1.0);
+        track.set_generator(Box::new(texture));
+        assert_eq!(track.cc_thin_stats(), Some(ThinStats::default()));
+
+        // A value per tick becomes at most one every 12 ticks
+        let scheduled = track.generate_scheduled(&test_context(), 0);
+        assert!(scheduled.len() <= 2);
+        let stats = track.cc_thin_stats().unwrap();
+        assert_eq!(stats.passed as usize, scheduled.len());
+        assert!(stats.dropped > 10);
+        assert!(Track::with_index(1).cc_thin_stats().is_none());
+    }
+
+    #[test]
+    fn test_processor_chain() {
+        let config = TrackConfig::new("Drums")
+            .with_channel(2)
+            .with_processor(Processor::velocity_curve(1.0, 64, 64))
+            .with_processor(Processor::channel_remap(None, 9));
+        let mut track = Track::new(0, config);
+        let mut clip = Clip::new("Hits", 96);
+        clip.add_note(crate::sequencer::ClipNote::new(0, 6, 38, 127));
+        let index = track.add_clip(clip);
+        track.set_active_clip(Some(index));
+        track.active_clip_mut().unwrap().play();
+
+        let events = track.generate(&test_context());
+        assert_eq!(events.len(), 1);
+        assert_eq!((events[0].velocity, events[0].channel), (64, 9));
+    }
+
+    #[test]
+    fn test_live_input_through_processors() {
+        let config = TrackConfig::new("Lead").with_processor(Processor::chordify(&[0, 7]));
+        let mut track = Track::new(0, config);
+        let played = track.process_input(&MidiMessage::NoteOn { channel: 0, note: 48, velocity: 90 });
+        let notes: Vec<u8> = played
+            .iter()
+            .filter_map(|(_, m)| match m {
+                MidiMessage::NoteOn { note, .. } => Some(*note),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(notes, vec![48, 55]);
+        assert_eq!(track.process_input(&MidiMessage::NoteOff { channel: 0, note: 48, velocity: 0 }).len(), 2);
+    }
+
+    #[test]
+    fn test_step_lane_sets_generator_param() {
+        let lane = StepLane::new("octaves", vec![1.0, 1.0, 2.0, 3.0], 4);
+        let mut track = Track::new(0, TrackConfig::new("Arp").with_step_lane(lane));
+        track.set_generator(crate::generators::arpeggio::ArpeggioGenerator::create());
+
+        let mut context = test_context();
+        let mut octaves = Vec::new();
+        for beat in 0..4 {
+            context.beat = beat;
+            track.generate(&context);
+            octaves.push(track.generator().unwrap().get_param("octaves").unwrap());
+        }
+        assert_eq!(octaves, vec![1.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_output_layer_from_config() {
+        let config = crate::config::OutputConfig {
+            destination: Some("fluid".to_string()),
+            channel: 3,
+            transpose: -12,
+            velocity_scale: 0.8,
+        };
+        let destinations = vec!["IAC Bus 1".to_string(), "FluidSynth".to_string()];
+        let layer = OutputLayer::from_config(&config, &destinations).unwrap();
+
+        assert_eq!(layer.destination, Some(1));
+        assert_eq!(layer.channel, 2);
+        assert_eq!(layer.transpose, -12);
+
+        let unknown = crate::config::OutputConfig {
+            destination: Some("prophet".to_string()),
+            ..config
+        };
+        assert!(OutputLayer::from_config(&unknown, &destinations).is_err());
+    }
+
+    #[test]
+    fn test_round_robin_output() {
+        let config: crate::config::RoundRobinConfig = serde_yaml::from_str(
+            r#"
+voices:
+  - channel: 1
+  - channel: 2
+    detune: 5
+  - channel: 3
+    destination: "volca"
+    transpose: -12
+"#,
+        )
+        .unwrap();
+        let destinations = vec!["IAC Bus 1".to_string(), "Volca Keys".to_string()];
+        let round_robin = RoundRobin::from_config(&config, &destinations);
+        let mut track = Track::new(0, TrackConfig::new("Poly").with_round_robin(round_robin));
+        track.set_generator(FixedNotes::new(
+            [60, 64, 67].into_iter().map(|note| MidiEvent::new(note, 100, 0, 12)).collect(),
+        ));
+
+        let scheduled = track.generate_scheduled(&test_context(), 0);
+        // One detune bend, then a note on/off pair per channel
+        assert_eq!(scheduled.len(), 7);
+        assert_eq!(scheduled[0].channel, 1);
+        let note_ons: Vec<(u8, u8, Option<usize>)> = scheduled[1..]
+            .iter()
+            .step_by(2)
+            .map(|e| (e.channel, e.data1, e.destination))
+            .collect();
+        assert_eq!(note_ons, vec![(0, 60, None), (1, 64, None), (2, 55, Some(1))]);
+    }
+
+    #[test]
+    fn test_voice_routes() {
+        let config = TrackConfig::new("Drums")
+            .with_channel(9)
+            .with_voice_route(
+                VoiceRoute::new(36, 0)
+                    .with_destination(Some(2))
+                    .with_output_note(Some(60)),
+            );
+        let mut track = Track::new(0, config);
+        track.set_generator(FixedNotes::new(vec![
+            MidiEvent::new(36, 100, 0, 6),
+            MidiEvent::new(38, 100, 12, 6),
+        ]));
+
+        let scheduled = track.generate_scheduled(&test_context(), 0);
+        assert_eq!(scheduled.len(), 4);
+
+        // Kick goes to its own instrument
+        assert_eq!(scheduled[0].channel, 0);
+        assert_eq!(scheduled[0].data1, 60);
+        assert_eq!(scheduled[0].destination, Some(2));
+
+        // Snare stays on the track channel
+        assert_eq!(scheduled[2].channel, 9);
+        assert_eq!(scheduled[2].data1, 38);
+        assert_eq!(scheduled[2].destination, None);
+    }
+
+    #[test]
+    fn test_generator_preroll() {
+        let mut manager = TrackManager::new();
+        manager.add_track(TrackConfig::new("Lead"));
+        manager.add_track(TrackConfig::new("Bass"));
+        manager
+            .track_mut(0)
+            .unwrap()
+            // Spends its first bar building a pattern
+            .queue_generator(FixedNotes::new(vec![MidiEvent::new(60, 100, 0, 6)]).with_warmup(1));
+
+        // Queued generators don't play until committed
+        assert!(manager.track(0).unwrap().has_pending_generator());
+        assert!(manager.track_mut(0).unwrap().generate(&test_context()).is_empty());
+
+        assert_eq!(manager.preroll_pending(&test_context()), 1);
+        assert_eq!(manager.commit_pending(), 1);
+        assert!(!manager.track(0).unwrap().has_pending_generator());
+
+        // Plays from the first bar after the switch
+        assert_eq!(manager.track_mut(0).unwrap().generate(&test_context()).len(), 1);
+        assert!(!manager.track_mut(1).unwrap().preroll_generator(&test_context()));
+    }
+
+    #[test]
+    fn test_exclusive_tracks() {
+        let mut manager = TrackManager::new();
+        manager.add_track(TrackConfig::new("Bass A").with_mute_group("bass"));
+        manager.add_track(TrackConfig::new("Bass B").with_mute_group("bass"));
+        manager.add_track(TrackConfig::new("Keys"));
+        manager.add_track(TrackConfig::new("Pad"));
+        for (i, group) in [(0, None), (1, None), (2, Some("chords")), (3, Some("chords"))] {
+            let track = manager.track_mut(i).unwrap();
+            let mut clip = Clip::new("Clip", 96);
+            clip.set_group(group.map(String::from));
+            track.add_clip(clip);
+        }
+
+        // Nothing playing yet: nothing to stop
+        assert!(manager.exclusive_tracks(0, Some(0)).is_empty());
+
+        for i in 0..4 {
+            manager.track_mut(i).unwrap().set_active_clip(Some(0));
+        }
+        assert_eq!(manager.exclusive_tracks(0, Some(0)), vec![1]);
+        assert_eq!(manager.exclusive_tracks(2, Some(0)), vec![3]);
+        assert!(manager.exclusive_tracks(2, None).is_empty());
+    }
+
+    #[test]
+    fn test_clip_shuffle() {
+        let mut track = Track::with_index(0);
+        track.add_clip(Clip::new("A", 48));
+        track.add_clip(Clip::new("B", 48));
+        track.add_clip(Clip::new("C", 48));
+        track.rng = StdRng::seed_from_u64(5);
+        track.set_clip_shuffle(Some(ClipShuffle::new(1).with_weights(vec![1.0, 1.0, 0.0])));
+
+        let mut picks = Vec::new();
+        for bar in 0..16 {
+            for half in 0..2 {
+                let context = GeneratorContext {
+                    bar,
+                    beat: half * 2,
+                    ticks_to_generate: 48,
+                    ..test_context()
+                };
+                track.generate(&context);
+                if half == 0 {
+                    picks.push(track.active_clip_index().unwrap());
+                } else {
+                    // No pick mid-bar
+                    assert_eq!(track.active_clip_index(), picks.last().copied());
+                }
+            }
+        }
+        assert!(picks.contains(&0) && picks.contains(&1));
+        assert!(!picks.contains(&2));
+        assert!(track.active_clip().unwrap().is_playing());
+    }
+
+    #[test]
+    fn test_pattern_chain() {
+        let mut track = Track::with_index(0);
+        track.add_clip(Clip::new("A", 48));
+        track.add_clip(Clip::new("B", 96));
+        track.add_clip(Clip::new("C", 48));
+        track.set_pattern_chain(Some(PatternChain::from_clips(&[0, 0, 1, 2])));
+
+        // Half-bar buffers: A twice, B for a whole bar, then C
+        let mut played = Vec::new();
+        for bar in 0..3 {
+            for half in 0..2 {
+                let context = GeneratorContext {
+                    bar,
+                    beat: half * 2,
+                    ticks_to_generate: 48,
+                    ..test_context()
+                };
+                track.generate(&context);
+                played.push(track.active_clip_index().unwrap());
+            }
+        }
+        assert_eq!(played, vec![0, 0, 1, 1, 2, 0]);
+
+        track.reset();
+        assert_eq!(track.pattern_chain().unwrap().position(), 0);
+    }
+
+    #[test]
+    fn test_voice_route_from_config() {
+        let config = crate::config::VoiceRouteConfig {
+            voice: "snare".to_string(),
+            channel: 11,
+            destination: Some("volca".to_string()),
+            note: None,
+        };
+        let destinations = vec!["IAC Bus 1".to_string(), "Volca Beats".to_string()];
+        let route = VoiceRoute::from_config(&config, &destinations).unwrap();
+        assert_eq!(route.note, 38);
+        assert_eq!(route.channel, 10);
+        assert_eq!(route.destination, Some(1));
+
+        let unknown = crate::config::VoiceRouteConfig {
+            voice: "theremin".to_string(),
+            ..config
+        };
+        assert!(VoiceRoute::from_config(&unknown, &destinations).is_none());
     }
 }
@@ -5,8 +5,16 @@
 //!
 //! Provides instant and quantized triggering of clips and patterns,
 //! with a queue system and follow actions.
+//!
+//! Each control source (keyboard, MIDI controller, grid, OSC, REPL) can
+//! launch on its own quantization, so a grid can wait for the bar while
+//! OSC fires at once. Sources without a setting use the queue default.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
 
-use std::collections::VecDeque;
+use crate::timing::Grid;
 
 use super::SequencerTiming;
 
@@ -27,6 +35,8 @@ pub enum QuantizeMode {
     Bars(u8),
     /// Quantize to next phrase (typically 4 or 8 bars)
     Phrase,
+    /// Quantize to the next line of a note-value grid (counted from the bar)
+    Grid(Grid),
 }
 
 impl Default for QuantizeMode {
@@ -36,6 +46,31 @@ impl Default for QuantizeMode {
 }
 
 impl QuantizeMode {
+    /// Parse from a string like "bar", "beat", "2 bars", "phrase" or a
+    /// note-value grid ("1/8T", "1/8.")
+    pub fn from_str(s: &str) -> Option<Self> {
+        if let Some(grid) = Grid::from_str(s) {
+            return Some(QuantizeMode::Grid(grid));
+        }
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "immediate" | "none" | "off" => return Some(QuantizeMode::Immediate),
+            "tick" => return Some(QuantizeMode::Tick),
+            "beat" => return Some(QuantizeMode::Beat),
+            "bar" => return Some(QuantizeMode::Bar),
+            "phrase" => return Some(QuantizeMode::Phrase),
+            _ => {}
+        }
+
+        let (count, unit) = s.split_once(' ')?;
+        let count: u8 = count.parse().ok()?;
+        match unit.trim() {
+            "beat" | "beats" => Some(QuantizeMode::Beats(count)),
+            "bar" | "bars" => Some(QuantizeMode::Bars(count)),
+            _ => None,
+        }
+    }
+
     /// Calculate ticks until trigger based on current timing
     pub fn ticks_until(&self, timing: &SequencerTiming) -> u64 {
         match self {
@@ -64,12 +99,49 @@ impl QuantizeMode {
                     phrase_length - position_in_phrase
                 }
             }
+            QuantizeMode::Grid(grid) => {
+                // Dotted grids don't divide the bar, so restart at each bar
+                let step = grid.ticks(timing.ppqn) as u64;
+                let into_step = (timing.position_ticks % timing.ticks_per_bar()) % step;
+                let to_step = if into_step == 0 { 0 } else { step - into_step };
+                to_step.min(timing.ticks_to_next_bar())
+            }
+        }
+    }
+}
+
+/// Where a launch came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriggerSource {
+    /// Computer keyboard shortcut
+    Keyboard,
+    /// MIDI controller mapping
+    Midi,
+    /// Grid controller pad
+    Grid,
+    /// OSC message
+    Osc,
+    /// Live-coding REPL command
+    Repl,
+}
+
+impl TriggerSource {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "keyboard" | "keys" => Some(TriggerSource::Keyboard),
+            "midi" | "controller" => Some(TriggerSource::Midi),
+            "grid" | "pads" => Some(TriggerSource::Grid),
+            "osc" => Some(TriggerSource::Osc),
+            "repl" => Some(TriggerSource::Repl),
+            _ => None,
         }
     }
 }
 
 /// Follow action - what to do when a clip finishes
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FollowAction {
     /// Do nothing, let clip handle looping
     None,
@@ -221,6 +293,8 @@ pub struct TriggerQueue {
     default_quantize: QuantizeMode,
     /// Phrase length in bars (for phrase quantization)
     phrase_bars: u8,
+    /// Quantization per control source (overrides the default)
+    source_quantize: HashMap<TriggerSource, QuantizeMode>,
 }
 
 impl TriggerQueue {
@@ -230,9 +304,39 @@ impl TriggerQueue {
             queue: VecDeque::new(),
             default_quantize: QuantizeMode::Bar,
             phrase_bars: 4,
+            source_quantize: HashMap::new(),
         }
     }
 
+    /// Set the quantization for launches from a source (None = default)
+    pub fn set_source_quantize(&mut self, source: TriggerSource, mode: Option<QuantizeMode>) {
+        match mode {
+            Some(mode) => self.source_quantize.insert(source, mode),
+            None => self.source_quantize.remove(&source),
+        };
+    }
+
+    /// Quantization set for a source, if any
+    pub fn source_quantize(&self, source: TriggerSource) -> Option<QuantizeMode> {
+        self.source_quantize.get(&source).copied()
+    }
+
+    /// Quantization used for launches from a source
+    pub fn quantize_for(&self, source: TriggerSource) -> QuantizeMode {
+        self.source_quantize(source).unwrap_or(self.default_quantize)
+    }
+
+    /// Queue a trigger from a control source, on that source's quantization
+    pub fn queue_from(
+        &mut self,
+        source: TriggerSource,
+        track_index: usize,
+        clip_index: Option<usize>,
+        timing: &SequencerTiming,
+    ) {
+        self.queue_with_quantize(track_index, clip_index, timing, self.quantize_for(source));
+    }
+
     /// Set default quantization mode
     pub fn set_default_quantize(&mut self, mode: QuantizeMode) {
         self.default_quantize = mode;
@@ -295,6 +399,24 @@ impl TriggerQueue {
         self.insert_sorted(trigger);
     }
 
+    /// Queue a launch that stops other tracks at the same boundary.
+    /// Pending launches on those tracks are cancelled, so the last launch
+    /// in a group wins.
+    pub fn queue_exclusive(
+        &mut self,
+        track_index: usize,
+        clip_index: Option<usize>,
+        timing: &SequencerTiming,
+        exclusive: &[usize],
+    ) {
+        let trigger_tick = timing.position_ticks + self.default_quantize.ticks_until(timing);
+        for &other in exclusive.iter().filter(|&&t| t != track_index) {
+            self.cancel_for_track(other);
+            self.insert_sorted(QueuedTrigger::new(other, None, trigger_tick));
+        }
+        self.insert_sorted(QueuedTrigger::new(track_index, clip_index, trigger_tick));
+    }
+
     /// Insert trigger maintaining time order
     fn insert_sorted(&mut self, trigger: QueuedTrigger) {
         // Find insertion point to maintain sorted order
@@ -404,8 +526,19 @@ impl Scene {
 
     /// Queue this scene's clips
     pub fn queue_all(&self, queue: &mut TriggerQueue, timing: &SequencerTiming) {
+        self.queue_all_with_quantize(queue, timing, self.quantize);
+    }
+
+    /// Queue this scene's clips from a control source: the source's
+    /// quantization, if set, replaces the scene's
+    pub fn queue_all_from(&self, source: TriggerSource, queue: &mut TriggerQueue, timing: &SequencerTiming) {
+        let quantize = queue.source_quantize(source).unwrap_or(self.quantize);
+        self.queue_all_with_quantize(queue, timing, quantize);
+    }
+
+    fn queue_all_with_quantize(&self, queue: &mut TriggerQueue, timing: &SequencerTiming, quantize: QuantizeMode) {
         for (track_index, clip) in self.clips.iter().enumerate() {
-            queue.queue_with_quantize(track_index, *clip, timing, self.quantize);
+            queue.queue_with_quantize(track_index, *clip, timing, quantize);
         }
     }
 }
@@ -464,6 +597,20 @@ impl SceneManager {
         }
     }
 
+    /// Trigger a scene from a control source, on that source's quantization
+    pub fn trigger_scene_from(
+        &mut self,
+        source: TriggerSource,
+        index: usize,
+        queue: &mut TriggerQueue,
+        timing: &SequencerTiming,
+    ) {
+        if let Some(scene) = self.scenes.get(index) {
+            scene.queue_all_from(source, queue, timing);
+            self.current_scene = Some(index);
+        }
+    }
+
     /// Trigger next scene
     pub fn trigger_next(&mut self, queue: &mut TriggerQueue, timing: &SequencerTiming) {
         let next = match self.current_scene {
@@ -508,6 +655,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_quantize_from_str() {
+        assert_eq!(QuantizeMode::from_str("Bar"), Some(QuantizeMode::Bar));
+        assert_eq!(QuantizeMode::from_str("2 bars"), Some(QuantizeMode::Bars(2)));
+        assert_eq!(QuantizeMode::from_str("3 beats"), Some(QuantizeMode::Beats(3)));
+        assert_eq!(QuantizeMode::from_str("sometime"), None);
+        assert_eq!(QuantizeMode::from_str("1/8T"), Some(QuantizeMode::Grid(Grid::triplet(8))));
+    }
+
+    #[test]
+    fn test_quantize_grid() {
+        let mut timing = test_timing();
+        timing.position_ticks = 10;
+        assert_eq!(QuantizeMode::Grid(Grid::triplet(8)).ticks_until(&timing), 6);
+
+        // Dotted eighths: 0, 18, 36, 54, 72, 90, then the next bar
+        timing.position_ticks = 91;
+        assert_eq!(QuantizeMode::Grid(Grid::dotted(8)).ticks_until(&timing), 5);
+        timing.position_ticks = 96 + 20;
+        assert_eq!(QuantizeMode::Grid(Grid::dotted(8)).ticks_until(&timing), 16);
+    }
+
     #[test]
     fn test_quantize_immediate() {
         let timing = test_timing();
@@ -620,6 +789,24 @@ mod tests {
         assert_eq!(queue.peek().unwrap().track_index, 1);
     }
 
+    #[test]
+    fn test_queue_exclusive() {
+        let mut queue = TriggerQueue::new();
+        let mut timing = test_timing();
+        timing.position_ticks = 10;
+
+        queue.queue(2, Some(1), &timing);
+        queue.queue_exclusive(1, Some(0), &timing, &[2, 3]);
+        assert_eq!(queue.len(), 3);
+
+        // Everything lands on the same bar; the launch on track 2 was replaced by a stop
+        let fired = queue.poll(96);
+        assert!(fired.iter().all(|t| t.trigger_tick == 96));
+        let stops: Vec<usize> = fired.iter().filter(|t| t.clip_index.is_none()).map(|t| t.track_index).collect();
+        assert_eq!(stops, vec![2, 3]);
+        assert_eq!(fired.last().unwrap().clip_index, Some(0));
+    }
+
     #[test]
     fn test_scene() {
         let mut scene = Scene::new("Intro", 4);
@@ -656,6 +843,33 @@ mod tests {
         assert_eq!(manager.current_scene(), Some(1));
     }
 
+    #[test]
+    fn test_source_quantize() {
+        let mut queue = TriggerQueue::new();
+        let mut timing = test_timing();
+        timing.position_ticks = 30;
+        queue.set_source_quantize(TriggerSource::Keyboard, Some(QuantizeMode::Beat));
+        queue.set_source_quantize(TriggerSource::Osc, Some(QuantizeMode::Immediate));
+        assert_eq!(TriggerSource::from_str(" Grid "), Some(TriggerSource::Grid));
+
+        queue.queue_from(TriggerSource::Grid, 0, Some(0), &timing);
+        queue.queue_from(TriggerSource::Keyboard, 1, Some(0), &timing);
+        queue.queue_from(TriggerSource::Osc, 2, Some(0), &timing);
+        let ticks: Vec<(usize, u64)> = queue.iter().map(|t| (t.track_index, t.trigger_tick)).collect();
+        assert_eq!(ticks, vec![(2, 30), (1, 48), (0, 96)]);
+
+        // A source setting replaces the scene's own quantization
+        queue.clear();
+        let mut manager = SceneManager::new();
+        manager.add_scene(Scene::new("Drop", 2));
+        manager.trigger_scene_from(TriggerSource::Osc, 0, &mut queue, &timing);
+        assert!(queue.iter().all(|t| t.trigger_tick == 30));
+        queue.clear();
+        queue.set_source_quantize(TriggerSource::Osc, None);
+        manager.trigger_scene_from(TriggerSource::Osc, 0, &mut queue, &timing);
+        assert!(queue.iter().all(|t| t.trigger_tick == 96));
+    }
+
     #[test]
     fn test_quantize_phrase() {
         let mut timing = test_timing();
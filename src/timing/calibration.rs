@@ -0,0 +1,306 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! A/V sync calibration.
+//!
+//! Hardware synths, the software synth and the lights each answer a beat
+//! with their own delay. The calibration tone plays a steady pattern on all
+//! of them at once: a MIDI note, an audio click and an optional OSC flash,
+//! with the bar downbeat accented. Record the room (or watch the lights)
+//! and enter how late each one lands; the offsets then hold back the faster
+//! outputs so everything arrives together with the slowest.
+
+use crate::control::osc::{OscArg, OscMessage};
+
+/// Default OSC address for calibration flashes
+pub const DEFAULT_FLASH_ADDRESS: &str = "/seq/flash";
+
+/// Largest offset accepted for one output, in milliseconds
+pub const MAX_SYNC_OFFSET_MS: f64 = 500.0;
+
+/// Length of the audio click in milliseconds
+const CLICK_MS: f64 = 15.0;
+
+/// An output whose delay is calibrated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncOutput {
+    /// External MIDI synths
+    Midi,
+    /// The software synth's audio
+    Audio,
+    /// Lighting (OSC flashes and beat pulses)
+    Lighting,
+}
+
+impl SyncOutput {
+    /// Parse from string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "midi" => Some(SyncOutput::Midi),
+            "audio" | "synth" => Some(SyncOutput::Audio),
+            "lighting" | "lights" | "osc" => Some(SyncOutput::Lighting),
+            _ => None,
+        }
+    }
+}
+
+/// Measured delay of each output, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SyncOffsets {
+    /// Delay of external MIDI synths
+    pub midi_ms: f64,
+    /// Delay of the software synth
+    pub audio_ms: f64,
+    /// Delay of the lights
+    pub lighting_ms: f64,
+}
+
+impl SyncOffsets {
+    /// Create offsets, clamping each to 0..=MAX_SYNC_OFFSET_MS
+    pub fn new(midi_ms: f64, audio_ms: f64, lighting_ms: f64) -> Self {
+        let mut offsets = Self::default();
+        offsets.set(SyncOutput::Midi, midi_ms);
+        offsets.set(SyncOutput::Audio, audio_ms);
+        offsets.set(SyncOutput::Lighting, lighting_ms);
+        offsets
+    }
+
+    /// Get the delay of an output
+    pub fn get(&self, output: SyncOutput) -> f64 {
+        match output {
+            SyncOutput::Midi => self.midi_ms,
+            SyncOutput::Audio => self.audio_ms,
+            SyncOutput::Lighting => self.lighting_ms,
+        }
+    }
+
+    /// Set the delay of an output
+    pub fn set(&mut self, output: SyncOutput, ms: f64) {
+        let ms = ms.clamp(0.0, MAX_SYNC_OFFSET_MS);
+        match output {
+            SyncOutput::Midi => self.midi_ms = ms,
+            SyncOutput::Audio => self.audio_ms = ms,
+            SyncOutput::Lighting => self.lighting_ms = ms,
+        }
+    }
+
+    /// Change the delay of an output by a step
+    pub fn adjust(&mut self, output: SyncOutput, delta_ms: f64) {
+        self.set(output, self.get(output) + delta_ms);
+    }
+
+    /// How long to hold an output back so it lands with the slowest one
+    pub fn delay_ms(&self, output: SyncOutput) -> f64 {
+        let slowest = self.midi_ms.max(self.audio_ms).max(self.lighting_ms);
+        slowest - self.get(output)
+    }
+
+    /// Hold-back for an output in ticks at a tempo
+    pub fn delay_ticks(&self, output: SyncOutput, tempo: f64, ppqn: u32) -> u64 {
+        let ms_per_tick = 60_000.0 / tempo.max(1.0) / ppqn.max(1) as f64;
+        (self.delay_ms(output) / ms_per_tick).round() as u64
+    }
+}
+
+/// One beat of the calibration pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationPulse {
+    /// Beat number from the start
+    pub beat: u64,
+    /// Send time in milliseconds from the start, after compensation
+    pub time_ms: f64,
+    /// Bar downbeat
+    pub accent: bool,
+}
+
+/// Repeating synced pattern for measuring output delays
+#[derive(Debug, Clone)]
+pub struct CalibrationTone {
+    /// Tempo in BPM
+    tempo: f64,
+    /// Beats per bar (the first is accented)
+    beats_per_bar: u32,
+    /// MIDI channel (0-15)
+    channel: u8,
+    /// Note on the downbeat
+    accent_note: u8,
+    /// Note on other beats
+    note: u8,
+    /// Click pitch in Hz on other beats (the downbeat is an octave up)
+    click_hz: f64,
+    /// OSC address for flashes
+    address: String,
+    /// Output delays to compensate
+    offsets: SyncOffsets,
+}
+
+impl Default for CalibrationTone {
+    fn default() -> Self {
+        Self {
+            tempo: 120.0,
+            beats_per_bar: 4,
+            channel: 9,
+            accent_note: 76,
+            note: 77,
+            click_hz: 1000.0,
+            address: DEFAULT_FLASH_ADDRESS.to_string(),
+            offsets: SyncOffsets::default(),
+        }
+    }
+}
+
+impl CalibrationTone {
+    /// Create a tone at a tempo
+    pub fn new(tempo: f64) -> Self {
+        Self {
+            tempo: tempo.clamp(20.0, 300.0),
+            ..Self::default()
+        }
+    }
+
+    /// Set the MIDI channel and notes
+    pub fn with_notes(mut self, channel: u8, accent: u8, note: u8) -> Self {
+        self.channel = channel.min(15);
+        self.accent_note = accent.min(127);
+        self.note = note.min(127);
+        self
+    }
+
+    /// Set the OSC address for flashes
+    pub fn with_address(mut self, address: impl Into<String>) -> Self {
+        self.address = address.into();
+        self
+    }
+
+    /// Set the output delays to compensate
+    pub fn with_offsets(mut self, offsets: SyncOffsets) -> Self {
+        self.offsets = offsets;
+        self
+    }
+
+    /// Get the output delays
+    pub fn offsets(&self) -> &SyncOffsets {
+        &self.offsets
+    }
+
+    /// Get mutable output delays, for adjusting while the tone plays
+    pub fn offsets_mut(&mut self) -> &mut SyncOffsets {
+        &mut self.offsets
+    }
+
+    /// Time between beats in milliseconds
+    pub fn beat_ms(&self) -> f64 {
+        60_000.0 / self.tempo
+    }
+
+    /// Beats due for an output in the window [start_ms, end_ms)
+    pub fn pulses(&self, output: SyncOutput, start_ms: f64, end_ms: f64) -> Vec<CalibrationPulse> {
+        let delay = self.offsets.delay_ms(output);
+        let beat_ms = self.beat_ms();
+        let first = ((start_ms - delay) / beat_ms).ceil().max(0.0) as u64;
+        (first..)
+            .map(|beat| CalibrationPulse {
+                beat,
+                time_ms: beat as f64 * beat_ms + delay,
+                accent: beat % self.beats_per_bar as u64 == 0,
+            })
+            .take_while(|pulse| pulse.time_ms < end_ms)
+            .collect()
+    }
+
+    /// Note on for a beat (the note off goes out half a beat later)
+    pub fn note_on(&self, pulse: &CalibrationPulse) -> [u8; 3] {
+        let note = if pulse.accent { self.accent_note } else { self.note };
+        [0x90 | self.channel, note, if pulse.accent { 127 } else { 100 }]
+    }
+
+    /// Note off for a beat
+    pub fn note_off(&self, pulse: &CalibrationPulse) -> [u8; 3] {
+        let note = if pulse.accent { self.accent_note } else { self.note };
+        [0x80 | self.channel, note, 0]
+    }
+
+    /// OSC flash for a beat: `<address> ,ii beat accent`
+    pub fn flash(&self, pulse: &CalibrationPulse) -> OscMessage {
+        OscMessage::new(
+            self.address.clone(),
+            vec![OscArg::Int(pulse.beat as i64 + 1), OscArg::Int(pulse.accent as i64)],
+        )
+    }
+
+    /// Mix the clicks into an interleaved buffer whose first frame is
+    /// `first_frame` frames from the start
+    pub fn render_click(&self, buffer: &mut [f32], channels: usize, sample_rate: u32, first_frame: u64) {
+        let channels = channels.max(1);
+        let rate = sample_rate.max(1) as f64;
+        let frames = buffer.len() / channels;
+        let start_ms = first_frame as f64 * 1000.0 / rate;
+        let end_ms = (first_frame + frames as u64) as f64 * 1000.0 / rate;
+
+        // Clicks that started before this buffer may still be ringing
+        for pulse in self.pulses(SyncOutput::Audio, start_ms - CLICK_MS, end_ms) {
+            let hz = if pulse.accent { self.click_hz * 2.0 } else { self.click_hz };
+            let onset = (pulse.time_ms * rate / 1000.0).round() as u64;
+            let length = (CLICK_MS * rate / 1000.0) as u64;
+            for frame in onset.max(first_frame)..(onset + length).min(first_frame + frames as u64) {
+                let t = (frame - onset) as f64 / rate;
+                let envelope = 1.0 - (frame - onset) as f64 / length as f64;
+                let sample = (std::f64::consts::TAU * hz * t).sin() * envelope * envelope * 0.8;
+                let index = (frame - first_frame) as usize * channels;
+                for out in &mut buffer[index..index + channels] {
+                    *out += sample as f32;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_delay_faster_outputs() {
+        let mut offsets = SyncOffsets::new(12.0, 40.0, 900.0);
+        assert_eq!(offsets.lighting_ms, MAX_SYNC_OFFSET_MS);
+        offsets.set(SyncOutput::from_str("lights").unwrap(), 25.0);
+        offsets.adjust(SyncOutput::Midi, -2.0);
+
+        // The software synth is slowest, so it goes out first
+        assert_eq!(offsets.delay_ms(SyncOutput::Audio), 0.0);
+        assert_eq!(offsets.delay_ms(SyncOutput::Midi), 30.0);
+        assert_eq!(offsets.delay_ms(SyncOutput::Lighting), 15.0);
+        // 120 BPM at 24 PPQN is about 20.8 ms per tick
+        assert_eq!(offsets.delay_ticks(SyncOutput::Midi, 120.0, 24), 1);
+    }
+
+    #[test]
+    fn test_pattern_lines_up_after_offsets() {
+        let tone = CalibrationTone::new(120.0).with_offsets(SyncOffsets::new(0.0, 30.0, 10.0));
+        let midi = tone.pulses(SyncOutput::Midi, 0.0, 2000.0);
+        assert_eq!(midi.len(), 4);
+        assert_eq!(midi[1].time_ms, 530.0);
+        assert!(midi[0].accent && !midi[1].accent);
+        assert_eq!(tone.note_on(&midi[0]), [0x99, 76, 127]);
+        assert_eq!(tone.flash(&midi[1]).args, vec![OscArg::Int(2), OscArg::Int(0)]);
+        assert_eq!(tone.pulses(SyncOutput::Audio, 0.0, 2000.0)[1].time_ms, 500.0);
+        assert_eq!(tone.pulses(SyncOutput::Lighting, 510.0, 1000.0)[0].time_ms, 520.0);
+    }
+
+    #[test]
+    fn test_click_spans_buffers() {
+        let tone = CalibrationTone::new(120.0);
+        // At 8 kHz a click is 120 frames and beat 2 starts at frame 4000
+        let mut first = vec![0.0f32; 2 * 4060];
+        tone.render_click(&mut first, 2, 8000, 0);
+        assert!(first[..2 * 120].iter().any(|s| *s != 0.0));
+        assert!(first[2 * 120..2 * 4000].iter().all(|s| *s == 0.0));
+        assert!(first[2 * 4000..].iter().any(|s| *s != 0.0));
+
+        // The rest of the click lands in the next buffer
+        let mut next = vec![0.0f32; 2 * 100];
+        tone.render_click(&mut next, 2, 8000, 4060);
+        assert!(next[..2 * 60].iter().any(|s| *s != 0.0));
+        assert!(next[2 * 60..].iter().all(|s| *s == 0.0));
+    }
+}
@@ -5,14 +5,31 @@
 //!
 //! This module provides a BPM-based MIDI clock that generates timing messages
 //! at 24 PPQN (Pulses Per Quarter Note) as per the MIDI specification.
+//!
+//! The clock can run at a finer internal resolution (any multiple of 24 up
+//! to 960 ticks per quarter note) for micro-timing. Clock messages are still
+//! sent every 24th of a beat, on every `ticks_per_pulse()`-th internal tick.
 
 use std::time::{Duration, Instant};
 
 use crate::midi::messages;
 
+use super::humanize::TempoHumanizer;
+
 /// Pulses Per Quarter Note - MIDI standard is 24
 pub const PPQN: u32 = 24;
 
+/// Finest supported internal resolution (ticks per quarter note)
+pub const MAX_PPQN: u32 = 960;
+
+/// Check if an internal resolution is supported (a multiple of 24, up to 960)
+pub fn is_valid_ppqn(ppqn: u32) -> bool {
+    ppqn >= PPQN && ppqn <= MAX_PPQN && ppqn % PPQN == 0
+}
+
+/// How long a nudge stays active without being re-triggered (key repeat)
+const NUDGE_HOLD: Duration = Duration::from_millis(250);
+
 /// MIDI Clock state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClockState {
@@ -128,7 +145,9 @@ pub struct MidiClock {
     bpm: f64,
     /// Current clock state
     state: ClockState,
-    /// Current pulse count within the beat (0-23)
+    /// Internal ticks per quarter note
+    ppqn: u32,
+    /// Current internal tick within the beat (0 to ppqn-1)
     pulse: u32,
     /// Current beat count
     beat: u64,
@@ -138,6 +157,16 @@ pub struct MidiClock {
     tempo_ramp: Option<TempoRamp>,
     /// Tap tempo calculator
     tap_tempo: TapTempo,
+    /// Nudge depth as a fraction of the tempo
+    nudge_depth: f64,
+    /// Active temporary nudge (-1.0 to 1.0 of depth)
+    nudge: f64,
+    /// When the active nudge releases unless re-triggered
+    nudge_until: Option<Instant>,
+    /// Internal ticks to insert (positive) or drop (negative) for phase alignment
+    phase_pulses: i64,
+    /// Tempo drift and bar profile
+    humanizer: Option<TempoHumanizer>,
 }
 
 impl MidiClock {
@@ -146,16 +175,54 @@ impl MidiClock {
         Self {
             bpm: bpm.clamp(20.0, 300.0),
             state: ClockState::Stopped,
+            ppqn: PPQN,
             pulse: 0,
             beat: 0,
             last_tick: None,
             tempo_ramp: None,
             tap_tempo: TapTempo::default(),
+            nudge_depth: 0.04,
+            nudge: 0.0,
+            nudge_until: None,
+            phase_pulses: 0,
+            humanizer: None,
         }
     }
 
-    /// Get the current tempo in BPM
+    /// Set the internal resolution (rounded down to a multiple of 24)
+    pub fn with_ppqn(mut self, ppqn: u32) -> Self {
+        self.set_ppqn(ppqn);
+        self
+    }
+
+    /// Get the internal resolution in ticks per quarter note
+    pub fn ppqn(&self) -> u32 {
+        self.ppqn
+    }
+
+    /// Set the internal resolution (rounded down to a multiple of 24).
+    /// The position within the current beat is kept.
+    pub fn set_ppqn(&mut self, ppqn: u32) {
+        let ppqn = (ppqn.clamp(PPQN, MAX_PPQN) / PPQN) * PPQN;
+        self.pulse = (self.pulse as u64 * ppqn as u64 / self.ppqn as u64) as u32;
+        let pulses = self.phase_pulses / self.ticks_per_pulse() as i64;
+        self.phase_pulses = pulses * (ppqn / PPQN) as i64;
+        self.ppqn = ppqn;
+    }
+
+    /// Internal ticks per MIDI clock pulse
+    pub fn ticks_per_pulse(&self) -> u32 {
+        self.ppqn / PPQN
+    }
+
+    /// Get the current tempo in BPM (including any active nudge and drift)
     pub fn bpm(&self) -> f64 {
+        let drift = self.humanizer.as_ref().map_or(1.0, TempoHumanizer::factor);
+        self.base_bpm() * (1.0 + self.nudge * self.nudge_depth) * drift
+    }
+
+    /// Get the tempo without the temporary nudge
+    pub fn base_bpm(&self) -> f64 {
         if let Some(ref ramp) = self.tempo_ramp {
             ramp.current_tempo()
         } else {
@@ -171,7 +238,61 @@ impl MidiClock {
 
     /// Nudge tempo by a delta
     pub fn nudge_bpm(&mut self, delta: f64) {
-        self.set_bpm(self.bpm() + delta);
+        self.set_bpm(self.base_bpm() + delta);
+    }
+
+    /// Get nudge depth as a fraction of the tempo
+    pub fn nudge_depth(&self) -> f64 {
+        self.nudge_depth
+    }
+
+    /// Set nudge depth as a fraction of the tempo
+    pub fn set_nudge_depth(&mut self, depth: f64) {
+        self.nudge_depth = depth.clamp(0.0, 0.5);
+    }
+
+    /// Start (or hold) a temporary nudge, like a DJ pitch bend.
+    /// `direction` is -1.0 to 1.0 of the nudge depth; the nudge releases
+    /// on `release_nudge` or when it is not re-triggered for a moment.
+    pub fn start_nudge(&mut self, direction: f64) {
+        self.nudge = direction.clamp(-1.0, 1.0);
+        self.nudge_until = Some(Instant::now() + NUDGE_HOLD);
+    }
+
+    /// Return to the base tempo
+    pub fn release_nudge(&mut self) {
+        self.nudge = 0.0;
+        self.nudge_until = None;
+    }
+
+    /// Check if a nudge is active
+    pub fn is_nudging(&self) -> bool {
+        self.nudge != 0.0
+    }
+
+    /// Get the tempo humanizer
+    pub fn humanizer(&self) -> Option<&TempoHumanizer> {
+        self.humanizer.as_ref()
+    }
+
+    /// Get the tempo humanizer mutably
+    pub fn humanizer_mut(&mut self) -> Option<&mut TempoHumanizer> {
+        self.humanizer.as_mut()
+    }
+
+    /// Set or remove the tempo humanizer
+    pub fn set_humanizer(&mut self, humanizer: Option<TempoHumanizer>) {
+        self.humanizer = humanizer;
+    }
+
+    /// Shift phase by whole MIDI clock pulses without changing tempo (positive = advance)
+    pub fn shift_phase(&mut self, pulses: i64) {
+        self.phase_pulses += pulses * self.ticks_per_pulse() as i64;
+    }
+
+    /// Get MIDI clock pulses still waiting to be inserted or dropped
+    pub fn pending_phase(&self) -> i64 {
+        self.phase_pulses / self.ticks_per_pulse() as i64
     }
 
     /// Start a tempo ramp to the target BPM over the specified duration
@@ -199,11 +320,21 @@ impl MidiClock {
         self.state
     }
 
-    /// Get the current pulse within the beat (0-23)
+    /// Get the current MIDI clock pulse within the beat (0-23)
     pub fn pulse(&self) -> u32 {
+        self.pulse / self.ticks_per_pulse()
+    }
+
+    /// Get the current internal tick within the beat
+    pub fn tick_in_beat(&self) -> u32 {
         self.pulse
     }
 
+    /// Get the position in internal ticks since start
+    pub fn position_ticks(&self) -> u64 {
+        self.beat * self.ppqn as u64 + self.pulse as u64
+    }
+
     /// Get the current beat count
     pub fn beat(&self) -> u64 {
         self.beat
@@ -217,11 +348,19 @@ impl MidiClock {
         Duration::from_secs_f64(seconds)
     }
 
+    /// Calculate the interval between internal ticks
+    pub fn tick_interval(&self) -> Duration {
+        Duration::from_secs_f64(60.0 / (self.bpm() * self.ppqn as f64))
+    }
+
     /// Start the clock - returns MIDI Start message
     pub fn start(&mut self) -> [u8; 1] {
         self.state = ClockState::Running;
         self.pulse = 0;
         self.beat = 0;
+        if let Some(humanizer) = self.humanizer.as_mut() {
+            humanizer.reset();
+        }
         self.last_tick = Some(Instant::now());
         [messages::START]
     }
@@ -250,8 +389,9 @@ impl MidiClock {
         [messages::CONTINUE]
     }
 
-    /// Check if it's time for the next clock pulse
+    /// Check if it's time for the next internal tick.
     /// Returns Some with the clock message if a pulse should be sent
+    /// (every tick at 24 PPQN, every `ticks_per_pulse()` ticks above it).
     pub fn tick(&mut self) -> Option<[u8; 1]> {
         if self.state != ClockState::Running {
             return None;
@@ -266,30 +406,60 @@ impl MidiClock {
         }
 
         let now = Instant::now();
-        let interval = self.pulse_interval();
+
+        // Release a nudge that is no longer being held
+        if self.nudge_until.is_some_and(|until| now >= until) {
+            self.release_nudge();
+        }
+
+        // Phase advance: insert a tick right away
+        if self.phase_pulses > 0 {
+            self.phase_pulses -= 1;
+            self.last_tick = Some(now);
+            return self.advance_pulse();
+        }
+
+        let interval = self.tick_interval();
 
         if let Some(last) = self.last_tick {
             if now.duration_since(last) >= interval {
                 self.last_tick = Some(now);
-                self.pulse += 1;
-                if self.pulse >= PPQN {
-                    self.pulse = 0;
-                    self.beat += 1;
+
+                // Phase retard: swallow this tick
+                if self.phase_pulses < 0 {
+                    self.phase_pulses += 1;
+                    return None;
                 }
-                return Some([messages::TIMING_CLOCK]);
+
+                return self.advance_pulse();
             }
         }
 
         None
     }
 
-    /// Get the time until the next clock pulse
+    /// Move the tick/beat counters forward by one internal tick,
+    /// returning the clock message when it lands on a MIDI clock pulse
+    fn advance_pulse(&mut self) -> Option<[u8; 1]> {
+        self.pulse += 1;
+        if self.pulse >= self.ppqn {
+            self.pulse = 0;
+            self.beat += 1;
+            // Drift only moves on the beat, keeping pulses even within it
+            if let Some(humanizer) = self.humanizer.as_mut() {
+                humanizer.next_beat();
+            }
+        }
+        (self.pulse % self.ticks_per_pulse() == 0).then_some([messages::TIMING_CLOCK])
+    }
+
+    /// Get the time until the next internal tick
     pub fn time_until_next_pulse(&self) -> Duration {
         if self.state != ClockState::Running {
             return Duration::from_secs(0);
         }
 
-        let interval = self.pulse_interval();
+        let interval = self.tick_interval();
         if let Some(last) = self.last_tick {
             let elapsed = last.elapsed();
             if elapsed < interval {
@@ -310,6 +480,7 @@ impl Default for MidiClock {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::timing::TempoProfile;
     use std::thread;
 
     #[test]
@@ -423,4 +594,103 @@ mod tests {
         clock.nudge_bpm(-10.0);
         assert_eq!(clock.bpm(), 115.0);
     }
+
+    #[test]
+    fn test_temporary_nudge() {
+        let mut clock = MidiClock::new(100.0);
+        clock.set_nudge_depth(0.05);
+
+        clock.start_nudge(1.0);
+        assert!(clock.is_nudging());
+        assert!((clock.bpm() - 105.0).abs() < 1e-9);
+        assert_eq!(clock.base_bpm(), 100.0);
+
+        // A permanent nudge while bent keeps the bend separate
+        clock.nudge_bpm(10.0);
+        assert_eq!(clock.base_bpm(), 110.0);
+
+        clock.release_nudge();
+        assert_eq!(clock.bpm(), 110.0);
+
+        // Nudge releases on its own when not held
+        clock.start();
+        clock.start_nudge(-1.0);
+        thread::sleep(Duration::from_millis(300));
+        clock.tick();
+        assert!(!clock.is_nudging());
+    }
+
+    #[test]
+    fn test_humanized_tempo() {
+        let mut clock = MidiClock::new(100.0);
+        clock.set_humanizer(Some(
+            TempoHumanizer::new(0.0).with_profile(TempoProfile::Drag, 0.04),
+        ));
+        clock.start();
+        let first = clock.bpm();
+        assert!(first > 100.0);
+        assert_eq!(clock.base_bpm(), 100.0);
+
+        // Steady within the beat, slower on the next
+        clock.shift_phase(23);
+        for _ in 0..23 {
+            clock.tick();
+        }
+        assert_eq!(clock.bpm(), first);
+        clock.shift_phase(1);
+        clock.tick();
+        assert_eq!(clock.beat(), 1);
+        assert!(clock.bpm() < first);
+
+        // Restarting returns to the start of the bar
+        clock.start();
+        assert_eq!(clock.bpm(), first);
+    }
+
+    #[test]
+    fn test_high_resolution() {
+        let mut clock = MidiClock::new(120.0).with_ppqn(96);
+        assert_eq!(clock.ticks_per_pulse(), 4);
+        let ratio = clock.pulse_interval().as_secs_f64() / clock.tick_interval().as_secs_f64();
+        assert!((ratio - 4.0).abs() < 1e-9);
+        clock.start();
+
+        // One clock message per four internal ticks
+        clock.shift_phase(2);
+        assert_eq!(clock.pending_phase(), 2);
+        let sent = (0..8).filter(|_| clock.tick().is_some()).count();
+        assert_eq!(sent, 2);
+        assert_eq!((clock.pulse(), clock.tick_in_beat()), (2, 8));
+
+        // Switching resolution keeps the place in the beat
+        clock.set_ppqn(480);
+        assert_eq!((clock.pulse(), clock.tick_in_beat()), (2, 40));
+        clock.set_ppqn(100);
+        assert_eq!(clock.ppqn(), 96);
+        assert_eq!(clock.position_ticks(), 8);
+
+        assert!(is_valid_ppqn(480));
+        assert!(!is_valid_ppqn(100));
+        assert!(!is_valid_ppqn(1920));
+    }
+
+    #[test]
+    fn test_phase_shift() {
+        let mut clock = MidiClock::new(120.0);
+        clock.start();
+
+        // Advance inserts pulses immediately
+        clock.shift_phase(2);
+        assert!(clock.tick().is_some());
+        assert!(clock.tick().is_some());
+        assert_eq!(clock.pulse(), 2);
+        assert_eq!(clock.pending_phase(), 0);
+
+        // Retard drops the next due pulse
+        clock.shift_phase(-1);
+        thread::sleep(clock.pulse_interval());
+        assert!(clock.tick().is_none());
+        assert_eq!(clock.pulse(), 2);
+        assert_eq!(clock.pending_phase(), 0);
+    }
 }
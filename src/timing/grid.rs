@@ -0,0 +1,126 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Note-value grids: straight, triplet and dotted.
+//!
+//! Grids are written as note values ("1/8", "1/16T", "1/8.") and turned
+//! into ticks at whatever PPQN is in use. Triplets are two thirds of the
+//! straight value and dotted values one and a half times it.
+
+use std::fmt;
+
+/// Feel of a grid division
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridFeel {
+    /// Plain note value
+    Straight,
+    /// Three in the time of two
+    Triplet,
+    /// One and a half times the note value
+    Dotted,
+}
+
+impl Default for GridFeel {
+    fn default() -> Self {
+        GridFeel::Straight
+    }
+}
+
+/// A note-value grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grid {
+    /// Note value denominator (4 = quarter, 8 = eighth, ...)
+    pub division: u32,
+    /// Straight, triplet or dotted
+    pub feel: GridFeel,
+}
+
+impl Grid {
+    /// Straight grid
+    pub fn straight(division: u32) -> Self {
+        Self {
+            division: division.max(1),
+            feel: GridFeel::Straight,
+        }
+    }
+
+    /// Triplet grid
+    pub fn triplet(division: u32) -> Self {
+        Self {
+            division: division.max(1),
+            feel: GridFeel::Triplet,
+        }
+    }
+
+    /// Dotted grid
+    pub fn dotted(division: u32) -> Self {
+        Self {
+            division: division.max(1),
+            feel: GridFeel::Dotted,
+        }
+    }
+
+    /// Parse from a string like "1/8", "1/16T", "1/8t", "1/8." or "1/8D"
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        let (value, feel) = if let Some(value) = s.strip_suffix('t') {
+            (value, GridFeel::Triplet)
+        } else if let Some(value) = s.strip_suffix('.').or_else(|| s.strip_suffix('d')) {
+            (value, GridFeel::Dotted)
+        } else {
+            (s.as_str(), GridFeel::Straight)
+        };
+        let division = value.trim().strip_prefix("1/")?.parse::<u32>().ok()?;
+        (division > 0 && division <= 128).then_some(Self { division, feel })
+    }
+
+    /// Grid length in ticks at a PPQN (at least one tick)
+    pub fn ticks(&self, ppqn: u32) -> u32 {
+        // A whole note is four quarters; scale by 2/3 or 3/2 for the feel
+        let (num, den) = match self.feel {
+            GridFeel::Straight => (1, 1),
+            GridFeel::Triplet => (2, 3),
+            GridFeel::Dotted => (3, 2),
+        };
+        let exact = 4 * ppqn as u64 * num;
+        let divisor = self.division as u64 * den;
+        (((exact + divisor / 2) / divisor) as u32).max(1)
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suffix = match self.feel {
+            GridFeel::Straight => "",
+            GridFeel::Triplet => "T",
+            GridFeel::Dotted => ".",
+        };
+        write!(f, "1/{}{}", self.division, suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_parse_and_ticks() {
+        assert_eq!(Grid::from_str("1/8T"), Some(Grid::triplet(8)));
+        assert_eq!(Grid::from_str("1/16t"), Some(Grid::triplet(16)));
+        assert_eq!(Grid::from_str("1/8."), Some(Grid::dotted(8)));
+        assert_eq!(Grid::from_str("1/4d"), Some(Grid::dotted(4)));
+        assert_eq!(Grid::from_str("1/16"), Some(Grid::straight(16)));
+        assert_eq!(Grid::from_str("eighth"), None);
+        assert_eq!(Grid::from_str("1/0"), None);
+
+        assert_eq!(Grid::triplet(8).ticks(24), 8);
+        assert_eq!(Grid::triplet(16).ticks(24), 4);
+        assert_eq!(Grid::dotted(8).ticks(24), 18);
+        assert_eq!(Grid::triplet(16).ticks(960), 160);
+        assert_eq!(Grid::dotted(16).ticks(96), 36);
+        assert_eq!(Grid::triplet(64).ticks(24), 1);
+
+        assert_eq!(Grid::dotted(8).to_string(), "1/8.");
+        assert_eq!(Grid::from_str(&Grid::triplet(16).to_string()), Some(Grid::triplet(16)));
+    }
+}
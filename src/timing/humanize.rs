@@ -0,0 +1,224 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Tempo humanizer (drift and rubato).
+//!
+//! Varies the effective tempo by a small bounded random walk plus an
+//! optional bar-level profile that pushes or drags through each bar. The
+//! variation only changes on beat boundaries, so clock pulses stay evenly
+//! spaced within a beat and receiving gear follows smoothly. The walk is
+//! pulled back toward the set tempo, so the average tempo does not wander.
+
+use std::f64::consts::PI;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// How far the walk is pulled back toward zero each beat
+const WALK_RETURN: f64 = 0.9;
+
+/// Default profile depth when a spec gives none
+const DEFAULT_PROFILE_DEPTH: f64 = 0.02;
+
+/// Tempo shape applied across each bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempoProfile {
+    /// No bar-level shape
+    Flat,
+    /// Start the bar slow and speed up through it
+    Push,
+    /// Start the bar fast and slow down through it
+    Drag,
+    /// Ease off around the barline, move forward mid-bar
+    Breathe,
+}
+
+impl Default for TempoProfile {
+    fn default() -> Self {
+        TempoProfile::Flat
+    }
+}
+
+impl TempoProfile {
+    /// Parse a profile name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "flat" | "none" => Some(TempoProfile::Flat),
+            "push" => Some(TempoProfile::Push),
+            "drag" => Some(TempoProfile::Drag),
+            "breathe" | "rubato" => Some(TempoProfile::Breathe),
+            _ => None,
+        }
+    }
+
+    /// Parse "name" or "name:depth" (depth as a fraction of the tempo)
+    pub fn parse(spec: &str) -> Option<(Self, f64)> {
+        let (name, depth) = match spec.split_once(':') {
+            Some((name, depth)) => (name, depth.trim().parse::<f64>().ok()?),
+            None => (spec, DEFAULT_PROFILE_DEPTH),
+        };
+        Some((Self::from_name(name)?, depth))
+    }
+
+    /// Tempo offset (-1.0 to 1.0 of depth) at a position in the bar (0.0 to 1.0).
+    /// Every shape averages to zero over a bar.
+    pub fn offset(&self, position: f64) -> f64 {
+        match self {
+            TempoProfile::Flat => 0.0,
+            TempoProfile::Push => position * 2.0 - 1.0,
+            TempoProfile::Drag => 1.0 - position * 2.0,
+            TempoProfile::Breathe => -(position * 2.0 * PI).cos(),
+        }
+    }
+}
+
+/// Bounded random-walk tempo variation with a bar profile
+#[derive(Debug, Clone)]
+pub struct TempoHumanizer {
+    /// Largest walk offset as a fraction of the tempo
+    drift: f64,
+    /// Largest change per beat as a fraction of the tempo
+    step: f64,
+    /// Bar-level shape
+    profile: TempoProfile,
+    /// Profile depth as a fraction of the tempo
+    profile_depth: f64,
+    /// Beats per bar (for the profile)
+    beats_per_bar: u32,
+    /// Beats since start
+    beat: u64,
+    /// Current walk offset
+    walk: f64,
+    /// Random source
+    rng: StdRng,
+}
+
+impl TempoHumanizer {
+    /// Create a humanizer drifting up to `drift` (fraction of the tempo)
+    pub fn new(drift: f64) -> Self {
+        let drift = drift.clamp(0.0, 0.1);
+        Self {
+            drift,
+            step: drift / 4.0,
+            profile: TempoProfile::Flat,
+            profile_depth: 0.0,
+            beats_per_bar: 4,
+            beat: 0,
+            walk: 0.0,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Set the largest change per beat
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.set_step(step);
+        self
+    }
+
+    /// Set the bar profile
+    pub fn with_profile(mut self, profile: TempoProfile, depth: f64) -> Self {
+        self.set_profile(profile, depth);
+        self
+    }
+
+    /// Use a fixed seed (repeatable drift)
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Get drift amount
+    pub fn drift(&self) -> f64 {
+        self.drift
+    }
+
+    /// Set drift amount
+    pub fn set_drift(&mut self, drift: f64) {
+        self.drift = drift.clamp(0.0, 0.1);
+        self.walk = self.walk.clamp(-self.drift, self.drift);
+    }
+
+    /// Set the largest change per beat
+    pub fn set_step(&mut self, step: f64) {
+        self.step = step.clamp(0.0, 0.05);
+    }
+
+    /// Get the bar profile
+    pub fn profile(&self) -> TempoProfile {
+        self.profile
+    }
+
+    /// Set the bar profile and its depth
+    pub fn set_profile(&mut self, profile: TempoProfile, depth: f64) {
+        self.profile = profile;
+        self.profile_depth = depth.clamp(0.0, 0.1);
+    }
+
+    /// Set beats per bar
+    pub fn set_beats_per_bar(&mut self, beats: u32) {
+        self.beats_per_bar = beats.max(1);
+    }
+
+    /// Tempo multiplier for the current beat
+    pub fn factor(&self) -> f64 {
+        let beats = self.beats_per_bar as u64;
+        let position = ((self.beat % beats) as f64 + 0.5) / beats as f64;
+        1.0 + self.walk + self.profile.offset(position) * self.profile_depth
+    }
+
+    /// Move to the next beat, taking one step of the walk
+    pub fn next_beat(&mut self) {
+        self.beat += 1;
+        let step = if self.step > 0.0 {
+            self.rng.gen_range(-self.step..=self.step)
+        } else {
+            0.0
+        };
+        self.walk = (self.walk * WALK_RETURN + step).clamp(-self.drift, self.drift);
+    }
+
+    /// Return to the start of the first bar with no drift
+    pub fn reset(&mut self) {
+        self.beat = 0;
+        self.walk = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_is_bounded() {
+        let mut humanizer = TempoHumanizer::new(0.02).with_step(0.01).with_seed(7);
+        let mut varied = false;
+        for _ in 0..1000 {
+            humanizer.next_beat();
+            let factor = humanizer.factor();
+            assert!((0.98..=1.02).contains(&factor));
+            varied |= factor != 1.0;
+        }
+        assert!(varied);
+
+        humanizer.reset();
+        assert_eq!(humanizer.factor(), 1.0);
+    }
+
+    #[test]
+    fn test_bar_profiles() {
+        let mut humanizer = TempoHumanizer::new(0.0).with_profile(TempoProfile::Push, 0.04);
+        let bar: Vec<f64> = (0..4)
+            .map(|_| {
+                let factor = humanizer.factor();
+                humanizer.next_beat();
+                factor
+            })
+            .collect();
+        assert!(bar.windows(2).all(|w| w[0] < w[1]));
+        assert!((bar.iter().sum::<f64>() / 4.0 - 1.0).abs() < 1e-9);
+
+        assert_eq!(TempoProfile::parse("drag"), Some((TempoProfile::Drag, 0.02)));
+        assert_eq!(TempoProfile::parse("rubato:0.05"), Some((TempoProfile::Breathe, 0.05)));
+        assert_eq!(TempoProfile::parse("wobble"), None);
+    }
+}
@@ -6,6 +6,14 @@
 //! This module provides MIDI clock generation and timing utilities
 //! for the sequencer.
 
+pub mod calibration;
 pub mod clock;
+pub mod grid;
+pub mod humanize;
+pub mod position;
 
-pub use clock::{ClockState, MidiClock, TapTempo, TempoRamp, PPQN};
+pub use calibration::{CalibrationTone, SyncOffsets, SyncOutput};
+pub use clock::{is_valid_ppqn, ClockState, MidiClock, TapTempo, TempoRamp, MAX_PPQN, PPQN};
+pub use grid::{Grid, GridFeel};
+pub use humanize::{TempoHumanizer, TempoProfile};
+pub use position::{PositionMode, PositionReadout};
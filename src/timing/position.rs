@@ -0,0 +1,204 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Position readouts.
+//!
+//! A song position can be read as bars:beats:ticks, as elapsed time, as the
+//! time left in the current section, or as SMPTE timecode while MTC is
+//! running. The transport, exports and logs all format positions through
+//! [`PositionReadout`] so they agree on the selected mode.
+
+use crate::midi::mmc::MmcTime;
+
+/// How positions are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionMode {
+    /// Bars:beats:ticks
+    BarsBeats,
+    /// Elapsed minutes and seconds
+    Clock,
+    /// Time left in the current section
+    Remaining,
+    /// SMPTE timecode (while MTC is active)
+    Smpte,
+}
+
+impl Default for PositionMode {
+    fn default() -> Self {
+        PositionMode::BarsBeats
+    }
+}
+
+impl PositionMode {
+    /// Parse from a string
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "bars" | "bbt" | "bars_beats" => Some(PositionMode::BarsBeats),
+            "time" | "clock" => Some(PositionMode::Clock),
+            "remaining" | "left" => Some(PositionMode::Remaining),
+            "smpte" | "timecode" | "mtc" => Some(PositionMode::Smpte),
+            _ => None,
+        }
+    }
+
+    /// Next mode in display order (wrapping)
+    pub fn next(self) -> Self {
+        match self {
+            PositionMode::BarsBeats => PositionMode::Clock,
+            PositionMode::Clock => PositionMode::Remaining,
+            PositionMode::Remaining => PositionMode::Smpte,
+            PositionMode::Smpte => PositionMode::BarsBeats,
+        }
+    }
+
+    /// Short label for the mode
+    pub fn label(&self) -> &'static str {
+        match self {
+            PositionMode::BarsBeats => "BBT",
+            PositionMode::Clock => "TIME",
+            PositionMode::Remaining => "REM",
+            PositionMode::Smpte => "SMPTE",
+        }
+    }
+}
+
+/// Formats tick positions in a position mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionReadout {
+    /// Selected mode
+    pub mode: PositionMode,
+    /// Ticks per quarter note
+    pub ppqn: u32,
+    /// Beats per bar
+    pub beats_per_bar: u8,
+    /// Tempo used to turn ticks into time
+    pub tempo: f64,
+    /// End tick of the current section, if known
+    pub section_end: Option<u64>,
+    /// MTC frame rate while timecode is active
+    pub smpte_fps: Option<u8>,
+}
+
+impl PositionReadout {
+    /// Create a bars:beats:ticks readout
+    pub fn new(ppqn: u32, beats_per_bar: u8, tempo: f64) -> Self {
+        Self {
+            mode: PositionMode::BarsBeats,
+            ppqn: ppqn.max(1),
+            beats_per_bar: beats_per_bar.max(1),
+            tempo,
+            section_end: None,
+            smpte_fps: None,
+        }
+    }
+
+    /// Set the mode
+    pub fn with_mode(mut self, mode: PositionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the end tick of the current section
+    pub fn with_section_end(mut self, tick: Option<u64>) -> Self {
+        self.section_end = tick;
+        self
+    }
+
+    /// Set the MTC frame rate (None = no timecode)
+    pub fn with_smpte(mut self, fps: Option<u8>) -> Self {
+        self.smpte_fps = fps;
+        self
+    }
+
+    /// Check if a mode can be shown with what is known
+    pub fn is_available(&self, mode: PositionMode) -> bool {
+        match mode {
+            PositionMode::Remaining => self.section_end.is_some(),
+            PositionMode::Smpte => self.smpte_fps.is_some(),
+            _ => true,
+        }
+    }
+
+    /// Mode actually shown: unavailable modes fall back to bars:beats:ticks
+    pub fn effective_mode(&self) -> PositionMode {
+        if self.is_available(self.mode) {
+            self.mode
+        } else {
+            PositionMode::BarsBeats
+        }
+    }
+
+    /// Seconds from the start at the readout tempo
+    pub fn seconds(&self, ticks: u64) -> f64 {
+        if self.tempo <= 0.0 {
+            return 0.0;
+        }
+        ticks as f64 / self.ppqn as f64 * 60.0 / self.tempo
+    }
+
+    /// Format a tick position
+    pub fn format(&self, ticks: u64) -> String {
+        match self.effective_mode() {
+            PositionMode::BarsBeats => format_bars(ticks, self.ppqn, self.beats_per_bar),
+            PositionMode::Clock => format_clock(self.seconds(ticks)),
+            PositionMode::Remaining => {
+                let end = self.section_end.unwrap_or(ticks);
+                format!("-{}", format_clock(self.seconds(end.saturating_sub(ticks))))
+            }
+            PositionMode::Smpte => {
+                let time = MmcTime::from_seconds(self.seconds(ticks), self.smpte_fps.unwrap_or(30));
+                format!("{:02}:{:02}:{:02}:{:02}", time.hours, time.minutes, time.seconds, time.frames)
+            }
+        }
+    }
+}
+
+/// Bars:beats:ticks, 1-indexed ("002:01:00")
+pub fn format_bars(ticks: u64, ppqn: u32, beats_per_bar: u8) -> String {
+    let ticks_per_beat = ppqn.max(1) as u64;
+    let ticks_per_bar = ticks_per_beat * beats_per_bar.max(1) as u64;
+    format!(
+        "{:03}:{:02}:{:02}",
+        ticks / ticks_per_bar + 1,
+        (ticks % ticks_per_bar) / ticks_per_beat + 1,
+        ticks % ticks_per_beat
+    )
+}
+
+/// Minutes, seconds and tenths ("1:05.2")
+pub fn format_clock(seconds: f64) -> String {
+    let tenths = (seconds.max(0.0) * 10.0).floor() as u64;
+    format!("{}:{:02}.{}", tenths / 600, (tenths / 10) % 60, tenths % 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_modes() {
+        // 120 BPM: a 4/4 bar is two seconds
+        let readout = PositionReadout::new(24, 4, 120.0);
+        assert_eq!(readout.format(96 * 32 + 30), "033:02:06");
+        assert_eq!(readout.clone().with_mode(PositionMode::Clock).format(96 * 32 + 24), "1:04.5");
+
+        let remaining = readout
+            .clone()
+            .with_mode(PositionMode::Remaining)
+            .with_section_end(Some(96 * 8));
+        assert_eq!(remaining.format(96 * 6), "-0:04.0");
+
+        let smpte = readout.clone().with_mode(PositionMode::Smpte).with_smpte(Some(25));
+        assert_eq!(smpte.format(96 * 30 + 12), "00:01:00:06");
+    }
+
+    #[test]
+    fn test_unavailable_modes_fall_back() {
+        let readout = PositionReadout::new(24, 4, 120.0).with_mode(PositionMode::Smpte);
+        assert_eq!(readout.effective_mode(), PositionMode::BarsBeats);
+        assert_eq!(readout.format(0), "001:01:00");
+
+        assert_eq!(PositionMode::from_str("timecode"), Some(PositionMode::Smpte));
+        assert_eq!(PositionMode::Smpte.next(), PositionMode::BarsBeats);
+    }
+}
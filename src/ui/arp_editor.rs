@@ -0,0 +1,232 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Arpeggio step-order editor widget.
+//!
+//! The editor works on a copy of a track's step order. Each edit hands the
+//! new order back to be played, and saving keeps it in the song file's
+//! `arp_presets` under the preset name it was loaded from (or one made up
+//! from the track).
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::generators::arpeggio::ArpStep;
+
+/// Maximum number of steps in an edited order
+const MAX_STEPS: usize = 32;
+
+/// Editing state for a custom arpeggio step order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArpEditorState {
+    /// Track being edited
+    pub track: usize,
+    /// Steps being edited
+    pub steps: Vec<ArpStep>,
+    /// Cursor position
+    pub cursor: usize,
+    /// Preset name the steps were loaded from (if any)
+    pub preset: Option<String>,
+}
+
+impl Default for ArpEditorState {
+    fn default() -> Self {
+        Self {
+            track: 0,
+            steps: vec![ArpStep::note(1)],
+            cursor: 0,
+            preset: None,
+        }
+    }
+}
+
+impl ArpEditorState {
+    /// Create an editor for existing steps
+    pub fn new(steps: Vec<ArpStep>) -> Self {
+        let steps = if steps.is_empty() { vec![ArpStep::note(1)] } else { steps };
+        Self {
+            track: 0,
+            steps,
+            cursor: 0,
+            preset: None,
+        }
+    }
+
+    /// Set the track being edited
+    pub fn with_track(mut self, track: usize) -> Self {
+        self.track = track;
+        self
+    }
+
+    /// Set the preset the steps were loaded from
+    pub fn with_preset(mut self, name: impl Into<String>) -> Self {
+        self.preset = Some(name.into());
+        self
+    }
+
+    /// Name to save the steps under: the loaded preset, else one for the track
+    pub fn preset_name(&self) -> String {
+        self.preset.clone().unwrap_or_else(|| format!("track-{}", self.track + 1))
+    }
+
+    /// Move cursor left
+    pub fn cursor_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Move cursor right
+    pub fn cursor_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.steps.len() - 1);
+    }
+
+    /// Raise or lower the degree under the cursor (a rest becomes degree 1)
+    pub fn adjust_degree(&mut self, delta: i32) {
+        let step = &mut self.steps[self.cursor];
+        *step = match *step {
+            ArpStep::Rest => ArpStep::note(1),
+            ArpStep::Note { degree, octave } => ArpStep::Note {
+                degree: (degree as i32 + delta).clamp(1, 15) as usize,
+                octave,
+            },
+        };
+    }
+
+    /// Shift the octave of the step under the cursor
+    pub fn adjust_octave(&mut self, delta: i8) {
+        if let ArpStep::Note { degree, octave } = self.steps[self.cursor] {
+            self.steps[self.cursor] = ArpStep::Note {
+                degree,
+                octave: (octave + delta).clamp(-4, 4),
+            };
+        }
+    }
+
+    /// Toggle the step under the cursor between a rest and a note
+    pub fn toggle_rest(&mut self) {
+        let step = &mut self.steps[self.cursor];
+        *step = match *step {
+            ArpStep::Rest => ArpStep::note(1),
+            ArpStep::Note { .. } => ArpStep::Rest,
+        };
+    }
+
+    /// Insert a copy of the current step after the cursor
+    pub fn insert_step(&mut self) {
+        if self.steps.len() >= MAX_STEPS {
+            return;
+        }
+        let step = self.steps[self.cursor];
+        self.steps.insert(self.cursor + 1, step);
+        self.cursor += 1;
+    }
+
+    /// Delete the step under the cursor (at least one step remains)
+    pub fn delete_step(&mut self) {
+        if self.steps.len() <= 1 {
+            return;
+        }
+        self.steps.remove(self.cursor);
+        self.cursor = self.cursor.min(self.steps.len() - 1);
+    }
+
+    /// Text form of the edited order
+    pub fn order_string(&self) -> String {
+        ArpStep::format_order(&self.steps)
+    }
+}
+
+/// Widget for editing a custom arpeggio step order
+pub struct ArpEditorWidget<'a> {
+    state: &'a ArpEditorState,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> ArpEditorWidget<'a> {
+    /// Create a new arpeggio editor widget
+    pub fn new(state: &'a ArpEditorState) -> Self {
+        Self { state, block: None }
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for ArpEditorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        let title = match self.state.preset {
+            Some(ref name) => format!("Preset: {}", name),
+            None => "Custom".to_string(),
+        };
+
+        let mut spans = Vec::new();
+        for (i, step) in self.state.steps.iter().enumerate() {
+            let base = match step {
+                ArpStep::Rest => Style::default().fg(Color::DarkGray),
+                ArpStep::Note { .. } => Style::default().fg(Color::Green),
+            };
+            let style = if i == self.state.cursor {
+                base.add_modifier(Modifier::REVERSED | Modifier::BOLD)
+            } else {
+                base
+            };
+            spans.push(Span::styled(format!("{:^4}", step.to_string()), style));
+        }
+
+        let lines = vec![
+            Line::from(Span::styled(title, Style::default().fg(Color::Cyan))),
+            Line::from(spans),
+        ];
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editor_edits() {
+        let mut editor = ArpEditorState::new(ArpStep::parse_order("1-3").unwrap());
+        editor.cursor_right();
+        editor.adjust_degree(2);
+        editor.adjust_octave(1);
+        assert_eq!(editor.order_string(), "1-5^");
+
+        editor.insert_step();
+        editor.toggle_rest();
+        assert_eq!(editor.order_string(), "1-5^-r");
+
+        editor.cursor_left();
+        editor.delete_step();
+        assert_eq!(editor.order_string(), "1-r");
+        assert_eq!(editor.cursor, 1);
+    }
+
+    #[test]
+    fn test_editor_keeps_one_step() {
+        let mut editor = ArpEditorState::default();
+        editor.delete_step();
+        assert_eq!(editor.steps.len(), 1);
+        assert_eq!(editor.preset_name(), "track-1");
+        assert_eq!(ArpEditorState::default().with_track(2).with_preset("alberti").preset_name(), "alberti");
+
+        editor.cursor_right();
+        assert_eq!(editor.cursor, 0);
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Stage beat indicator.
+//!
+//! Makes the tempo readable from across a stage: the terminal title can
+//! show the beat (and the bell ring on each bar), and a full-screen view
+//! shows the beat as large block digits that flash on each beat.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+use super::TransportState;
+
+/// Glyph rows for the digits 0-9 (3 wide, 5 tall)
+const DIGITS: [[&str; 5]; 10] = [
+    ["###", "# #", "# #", "# #", "###"],
+    [" # ", "## ", " # ", " # ", "###"],
+    ["###", "  #", "###", "#  ", "###"],
+    ["###", "  #", "###", "  #", "###"],
+    ["# #", "# #", "###", "  #", "  #"],
+    ["###", "#  ", "###", "  #", "###"],
+    ["###", "#  ", "###", "# #", "###"],
+    ["###", "  #", "  #", "  #", "  #"],
+    ["###", "# #", "###", "# #", "###"],
+    ["###", "# #", "###", "  #", "###"],
+];
+
+/// Where each beat is signalled outside the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BeatFlash {
+    /// Show the beat in the terminal title
+    pub title: bool,
+    /// Ring the terminal bell on each bar
+    pub bell: bool,
+}
+
+impl BeatFlash {
+    /// Parse from string ("title", "bell", "title+bell", "off")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let mut flash = BeatFlash::default();
+        for word in s.split(|c: char| c == '+' || c == ',' || c.is_whitespace()).filter(|w| !w.is_empty()) {
+            match word.to_lowercase().as_str() {
+                "title" => flash.title = true,
+                "bell" => flash.bell = true,
+                "off" | "none" => {}
+                _ => return None,
+            }
+        }
+        Some(flash)
+    }
+
+    /// Check if anything is signalled
+    pub fn is_enabled(&self) -> bool {
+        self.title || self.bell
+    }
+}
+
+/// A beat that just started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BeatCue {
+    /// Bar (1-indexed)
+    pub bar: u64,
+    /// Beat in the bar (1-indexed)
+    pub beat: u64,
+}
+
+impl BeatCue {
+    /// Check if this is the first beat of a bar
+    pub fn is_downbeat(&self) -> bool {
+        self.beat == 1
+    }
+}
+
+/// Notices when the transport moves onto a new beat
+#[derive(Debug, Clone, Default)]
+pub struct BeatWatch {
+    /// Last beat seen while playing
+    last: Option<(u64, u64)>,
+}
+
+impl BeatWatch {
+    /// Create a watch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check the transport; returns the beat if a new one started
+    pub fn update(&mut self, transport: &TransportState) -> Option<BeatCue> {
+        if !transport.playing {
+            self.last = None;
+            return None;
+        }
+        let position = (transport.bar, transport.beat);
+        if self.last == Some(position) {
+            return None;
+        }
+        self.last = Some(position);
+        Some(BeatCue {
+            bar: transport.bar,
+            beat: transport.beat,
+        })
+    }
+}
+
+/// Terminal title for the transport: the beat, marked on the downbeat
+pub fn title_text(transport: &TransportState) -> String {
+    if !transport.playing {
+        return format!("SEQ - stopped - {:.0} BPM", transport.tempo);
+    }
+    let marker = if transport.beat == 1 { "■" } else { "□" };
+    format!(
+        "SEQ {} {}.{} - {:.0} BPM",
+        marker, transport.bar, transport.beat, transport.tempo
+    )
+}
+
+/// Full-screen beat number that flashes on each beat
+pub struct BeatIndicatorWidget<'a> {
+    transport: &'a TransportState,
+}
+
+impl<'a> BeatIndicatorWidget<'a> {
+    /// Create a new beat indicator widget
+    pub fn new(transport: &'a TransportState) -> Self {
+        Self { transport }
+    }
+
+    /// Check if the flash is lit (the first quarter of each beat)
+    fn is_lit(&self) -> bool {
+        self.transport.playing && self.transport.tick < (self.transport.ppqn as u64 / 4).max(1)
+    }
+}
+
+impl Widget for BeatIndicatorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let transport = self.transport;
+        let color = if transport.beat == 1 { Color::Red } else { Color::Green };
+        let (fg, bg) = if self.is_lit() { (Color::Black, color) } else { (color, Color::Reset) };
+        buf.set_style(area, Style::default().bg(bg));
+
+        // Scale the digits to the largest size that fits, keeping a margin
+        let digits: Vec<usize> = transport.beat.to_string().bytes().map(|b| (b - b'0') as usize).collect();
+        let glyph_width = digits.len() as u16 * 4 - 1;
+        let scale_x = (area.width.saturating_sub(2) / glyph_width).max(1);
+        let scale_y = (area.height.saturating_sub(2) / 5).max(1);
+        let scale = scale_x.min(scale_y * 2);
+        let (cell_w, cell_h) = (scale, (scale / 2).max(1));
+        let left = area.x + area.width.saturating_sub(glyph_width * cell_w) / 2;
+        let top = area.y + area.height.saturating_sub(5 * cell_h) / 2;
+
+        for (i, &digit) in digits.iter().enumerate() {
+            for (row, line) in DIGITS[digit].iter().enumerate() {
+                for (col, ch) in line.chars().enumerate() {
+                    if ch != '#' {
+                        continue;
+                    }
+                    let x0 = left + (i as u16 * 4 + col as u16) * cell_w;
+                    let y0 = top + row as u16 * cell_h;
+                    for y in y0..(y0 + cell_h).min(area.bottom()) {
+                        for x in x0..(x0 + cell_w).min(area.right()) {
+                            buf[(x, y)].set_symbol("█").set_style(Style::default().fg(fg).bg(bg));
+                        }
+                    }
+                }
+            }
+        }
+
+        // Bar and tempo along the bottom
+        if area.height > 1 {
+            let text = format!("bar {}  {:.0} BPM", transport.bar, transport.tempo);
+            let x = area.x + area.width.saturating_sub(text.chars().count() as u16) / 2;
+            buf.set_stringn(x, area.bottom() - 1, &text, area.width as usize, Style::default().fg(fg).bg(bg));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_beat_watch_and_title() {
+        assert_eq!(BeatFlash::from_str("title+bell"), Some(BeatFlash { title: true, bell: true }));
+        assert!(!BeatFlash::from_str("off").unwrap().is_enabled());
+        assert_eq!(BeatFlash::from_str("strobe"), None);
+
+        let mut transport = TransportState::default();
+        let mut watch = BeatWatch::new();
+        assert_eq!(watch.update(&transport), None);
+
+        transport.playing = true;
+        let cue = watch.update(&transport).unwrap();
+        assert!(cue.is_downbeat());
+        assert_eq!(watch.update(&transport), None);
+        transport.beat = 2;
+        assert_eq!(watch.update(&transport), Some(BeatCue { bar: 1, beat: 2 }));
+        assert_eq!(title_text(&transport), "SEQ □ 1.2 - 120 BPM");
+    }
+
+    #[test]
+    fn test_big_digits_flash() {
+        let mut transport = TransportState::default();
+        transport.playing = true;
+        transport.beat = 3;
+        let area = Rect::new(0, 0, 20, 12);
+        let mut buf = Buffer::empty(area);
+        BeatIndicatorWidget::new(&transport).render(area, &mut buf);
+
+        // Lit at the start of the beat: the background takes the beat color
+        assert_eq!(buf[(0, 0)].bg, Color::Green);
+        let blocks = buf.content().iter().filter(|c| c.symbol() == "█").count();
+        assert!(blocks > 20);
+    }
+}
@@ -0,0 +1,397 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Piano-roll clip editor.
+//!
+//! Shows a clip's notes on a pitch-by-step grid with a cursor. Notes are
+//! added, deleted, moved and resized from the keyboard in grid steps; the
+//! edited notes are written back to the clip with `apply`.
+//!
+//! Zooming changes the grid from thirty-second notes out to whole bars, so
+//! long clips fit on screen. While following, the view pages along with the
+//! playhead instead of the cursor.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::sequencer::{Clip, ClipNote};
+
+use super::{follow_offset, note_name, scroll_offset};
+
+/// Velocity of notes added in the editor
+const DEFAULT_VELOCITY: u8 = 100;
+
+/// Grid steps for each zoom level, in eighths of a beat (1/32 note to a bar)
+const ZOOM_LEVELS: [u64; 6] = [1, 2, 4, 8, 16, 32];
+
+/// Zoom level of the sixteenth-note grid
+const DEFAULT_ZOOM: usize = 1;
+
+/// Editing state for a clip's notes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClipEditorState {
+    /// Track the clip belongs to
+    pub track: usize,
+    /// Clip index on the track
+    pub clip: usize,
+    /// Clip name
+    pub name: String,
+    /// Clip length in ticks
+    pub length: u64,
+    /// Notes being edited
+    pub notes: Vec<ClipNote>,
+    /// Ticks per grid step
+    pub step_ticks: u64,
+    /// Ticks per beat (for grid markings)
+    pub ppqn: u32,
+    /// Cursor position in ticks (on the grid)
+    pub cursor_tick: u64,
+    /// Cursor pitch
+    pub cursor_note: u8,
+    /// Zoom level (index into the grid sizes)
+    pub zoom: usize,
+    /// Scroll with the playhead instead of the cursor
+    pub follow: bool,
+    /// Playback position within the clip (None = not playing)
+    pub playhead: Option<u64>,
+}
+
+impl ClipEditorState {
+    /// Load a clip's notes on a sixteenth-note grid
+    pub fn from_clip(track: usize, index: usize, clip: &Clip, ppqn: u32) -> Self {
+        Self {
+            track,
+            clip: index,
+            name: clip.name().to_string(),
+            length: clip.length(),
+            notes: clip.notes().to_vec(),
+            step_ticks: (ppqn as u64 / 4).max(1),
+            ppqn: ppqn.max(1),
+            cursor_tick: 0,
+            cursor_note: clip.notes().first().map_or(60, |n| n.note),
+            zoom: DEFAULT_ZOOM,
+            follow: true,
+            playhead: None,
+        }
+    }
+
+    /// Write the edited notes back to the clip
+    pub fn apply(&self, clip: &mut Clip) {
+        clip.clear_notes();
+        clip.add_notes(self.notes.iter().cloned());
+    }
+
+    /// Number of grid steps in the clip
+    pub fn steps(&self) -> u64 {
+        self.length.div_ceil(self.step_ticks.max(1)).max(1)
+    }
+
+    /// Step under the cursor
+    pub fn cursor_step(&self) -> u64 {
+        self.cursor_tick / self.step_ticks.max(1)
+    }
+
+    /// Move the cursor by grid steps
+    pub fn move_cursor(&mut self, steps: i32) {
+        let last = self.steps() as i64 - 1;
+        let step = (self.cursor_step() as i64 + steps as i64).clamp(0, last);
+        self.cursor_tick = step as u64 * self.step_ticks;
+    }
+
+    /// Zoom out (positive) or in by levels. The grid changes size and the
+    /// cursor snaps to it.
+    pub fn zoom_by(&mut self, levels: i32) {
+        let zoom = (self.zoom as i64 + levels as i64).clamp(0, ZOOM_LEVELS.len() as i64 - 1) as usize;
+        self.zoom = zoom;
+        self.step_ticks = (self.ppqn as u64 * ZOOM_LEVELS[zoom] / 8).max(1);
+        self.cursor_tick = self.cursor_tick / self.step_ticks * self.step_ticks;
+    }
+
+    /// Toggle following the playhead. Returns the new state.
+    pub fn toggle_follow(&mut self) -> bool {
+        self.follow = !self.follow;
+        self.follow
+    }
+
+    /// Step the playhead is in
+    pub fn playhead_step(&self) -> Option<u64> {
+        self.playhead.map(|tick| (tick % self.length.max(1)) / self.step_ticks.max(1))
+    }
+
+    /// Move the cursor by semitones
+    pub fn move_pitch(&mut self, semitones: i32) {
+        self.cursor_note = (self.cursor_note as i32 + semitones).clamp(0, 127) as u8;
+    }
+
+    /// Index of the note under the cursor
+    pub fn note_at_cursor(&self) -> Option<usize> {
+        let (tick, pitch) = (self.cursor_tick, self.cursor_note);
+        self.notes
+            .iter()
+            .position(|n| n.note == pitch && n.start_tick <= tick && tick < n.start_tick + n.duration.max(1))
+    }
+
+    /// Add a one-step note at the cursor. Returns false if one is there.
+    pub fn add_note(&mut self) -> bool {
+        if self.note_at_cursor().is_some() {
+            return false;
+        }
+        let duration = self.step_ticks.min(self.length.saturating_sub(self.cursor_tick)).max(1);
+        self.notes
+            .push(ClipNote::new(self.cursor_tick, duration, self.cursor_note, DEFAULT_VELOCITY));
+        self.notes.sort_by_key(|n| n.start_tick);
+        true
+    }
+
+    /// Delete the note under the cursor
+    pub fn delete_note(&mut self) -> bool {
+        match self.note_at_cursor() {
+            Some(index) => {
+                self.notes.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move the note under the cursor by grid steps and semitones; the
+    /// cursor follows it
+    pub fn move_note(&mut self, steps: i32, semitones: i32) -> bool {
+        let Some(index) = self.note_at_cursor() else {
+            return false;
+        };
+        let step_ticks = self.step_ticks as i64;
+        let note = &mut self.notes[index];
+        let latest = self.length.saturating_sub(note.duration.max(1)) as i64;
+        let start = (note.start_tick as i64 + steps as i64 * step_ticks).clamp(0, latest.max(0));
+        let moved_by = start - note.start_tick as i64;
+        note.start_tick = start as u64;
+        note.note = (note.note as i32 + semitones).clamp(0, 127) as u8;
+        self.cursor_note = note.note;
+        self.cursor_tick = (self.cursor_tick as i64 + moved_by).max(0) as u64;
+        self.notes.sort_by_key(|n| n.start_tick);
+        true
+    }
+
+    /// Lengthen (positive) or shorten the note under the cursor by grid
+    /// steps, keeping at least one step and inside the clip
+    pub fn resize_note(&mut self, steps: i32) -> bool {
+        let Some(index) = self.note_at_cursor() else {
+            return false;
+        };
+        let step_ticks = self.step_ticks as i64;
+        let note = &mut self.notes[index];
+        let longest = self.length.saturating_sub(note.start_tick).max(1) as i64;
+        let duration = (note.duration as i64 + steps as i64 * step_ticks).clamp(step_ticks.min(longest), longest);
+        note.duration = duration as u64;
+        // Keep the cursor on the note when shortening past it
+        let end = note.start_tick + note.duration;
+        if self.cursor_tick >= end {
+            self.cursor_tick = (end - 1) / self.step_ticks * self.step_ticks;
+        }
+        true
+    }
+
+    /// What the grid shows for a pitch at a step
+    fn cell(&self, pitch: u8, step: u64) -> Cell {
+        let start = step * self.step_ticks;
+        let end = start + self.step_ticks;
+        for note in self.notes.iter().filter(|n| n.note == pitch) {
+            if note.start_tick >= start && note.start_tick < end {
+                return Cell::Start;
+            }
+            if note.start_tick < start && start < note.start_tick + note.duration {
+                return Cell::Held;
+            }
+        }
+        Cell::Empty
+    }
+}
+
+/// One grid cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    /// A note starts in the step
+    Start,
+    /// A note sounds through the step
+    Held,
+    /// Nothing plays
+    Empty,
+}
+
+/// Widget showing a clip on a piano-roll grid
+pub struct ClipEditorWidget<'a> {
+    state: &'a ClipEditorState,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> ClipEditorWidget<'a> {
+    /// Create a new clip editor widget
+    pub fn new(state: &'a ClipEditorState) -> Self {
+        Self { state, block: None }
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for ClipEditorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+        let state = self.state;
+
+        // Pitches run from high (top) to low, scrolled to keep the cursor in view
+        let rows = area.height.saturating_sub(1) as usize;
+        let top = 127 - scroll_offset(128, 127 - state.cursor_note as usize, rows) as u8;
+        let columns = area.width.saturating_sub(5) as usize;
+        let steps = state.steps() as usize;
+        let playhead = state.playhead_step();
+        let first_step = follow_offset(
+            steps,
+            state.cursor_step() as usize,
+            playhead.map(|s| s as usize),
+            state.follow,
+            columns,
+        ) as u64;
+        let steps_per_beat = (state.ppqn as u64 / state.step_ticks.max(1)).max(1);
+
+        let mut lines = vec![Line::from(Span::styled(
+            format!(
+                "{}  step {}/{}  {}  grid {}{}",
+                state.name,
+                state.cursor_step() + 1,
+                steps,
+                note_name(state.cursor_note),
+                grid_label(state.step_ticks, state.ppqn),
+                if state.follow { "  follow" } else { "" }
+            ),
+            Style::default().fg(Color::DarkGray),
+        ))];
+        for pitch in (0..=top).rev().take(rows) {
+            let label_style = if pitch % 12 == 0 {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let mut spans = vec![Span::styled(format!("{:<5}", note_name(pitch)), label_style)];
+            for step in first_step..(first_step + columns as u64).min(steps as u64) {
+                let (text, style) = match state.cell(pitch, step) {
+                    Cell::Start => ("█", Style::default().fg(Color::Green)),
+                    Cell::Held => ("▒", Style::default().fg(Color::Green)),
+                    Cell::Empty if step % steps_per_beat == 0 => (":", Style::default().fg(Color::DarkGray)),
+                    Cell::Empty => (".", Style::default().fg(Color::DarkGray)),
+                };
+                let style = if playhead == Some(step) { style.bg(Color::DarkGray) } else { style };
+                let style = if pitch == state.cursor_note && step == state.cursor_step() {
+                    style.add_modifier(Modifier::REVERSED)
+                } else {
+                    style
+                };
+                spans.push(Span::styled(text, style));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+/// Grid size as a note value ("1/16") or in beats ("2 beats")
+fn grid_label(step_ticks: u64, ppqn: u32) -> String {
+    let ppqn = ppqn.max(1) as u64;
+    if step_ticks >= ppqn {
+        let beats = step_ticks / ppqn;
+        format!("{} beat{}", beats, if beats == 1 { "" } else { "s" })
+    } else {
+        format!("1/{}", 4 * ppqn / step_ticks.max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_notes() {
+        let mut clip = Clip::new("Riff", 96);
+        clip.add_note(ClipNote::new(0, 6, 60, 90));
+        let mut editor = ClipEditorState::from_clip(2, 0, &clip, 24);
+        assert_eq!(editor.steps(), 16);
+        assert_eq!(editor.cursor_note, 60);
+        assert_eq!(editor.cell(60, 0), Cell::Start);
+
+        // Add a note two steps in, stretch it, then move it up a fifth
+        editor.move_cursor(2);
+        assert!(editor.add_note());
+        assert!(!editor.add_note());
+        assert!(editor.resize_note(2));
+        assert_eq!(editor.cell(60, 4), Cell::Held);
+        assert!(editor.move_note(1, 7));
+        assert_eq!(editor.cursor_note, 67);
+        assert_eq!(editor.notes[1], ClipNote::new(18, 18, 67, DEFAULT_VELOCITY));
+
+        // Delete the first note
+        editor.move_cursor(-10);
+        editor.move_pitch(-7);
+        assert!(editor.delete_note());
+        assert!(!editor.delete_note());
+
+        editor.apply(&mut clip);
+        assert_eq!(clip.notes(), &[ClipNote::new(18, 18, 67, DEFAULT_VELOCITY)]);
+    }
+
+    #[test]
+    fn test_edits_stay_inside_clip() {
+        let clip = Clip::new("Short", 24);
+        let mut editor = ClipEditorState::from_clip(0, 0, &clip, 24);
+        editor.move_cursor(100);
+        assert_eq!(editor.cursor_step(), 3);
+        assert!(editor.add_note());
+        assert!(editor.resize_note(4));
+        assert_eq!(editor.notes[0].duration, 6);
+        assert!(editor.move_note(2, 0));
+        assert_eq!(editor.notes[0].start_tick, 18);
+        assert!(editor.resize_note(-1));
+        assert_eq!(editor.notes[0].duration, 6);
+    }
+
+    #[test]
+    fn test_zoom_and_follow() {
+        let clip = Clip::new("Long", 24 * 64);
+        let mut editor = ClipEditorState::from_clip(0, 0, &clip, 24);
+        assert_eq!(grid_label(editor.step_ticks, 24), "1/16");
+        editor.move_cursor(7);
+
+        // Zooming out to beats snaps the cursor back to beat two
+        editor.zoom_by(2);
+        assert_eq!(editor.step_ticks, 24);
+        assert_eq!(editor.cursor_tick, 24);
+        assert_eq!(editor.steps(), 64);
+        editor.zoom_by(10);
+        assert_eq!(grid_label(editor.step_ticks, 24), "4 beats");
+        editor.zoom_by(-10);
+        assert_eq!(grid_label(editor.step_ticks, 24), "1/32");
+
+        // Following pages with the playhead; off, the cursor stays in view
+        editor.zoom_by(3);
+        editor.playhead = Some(24 * 40);
+        assert_eq!(editor.playhead_step(), Some(40));
+        assert_eq!(follow_offset(64, 1, Some(40), editor.follow, 16), 32);
+        assert!(!editor.toggle_follow());
+        assert_eq!(follow_offset(64, 1, Some(40), editor.follow, 16), 0);
+    }
+}
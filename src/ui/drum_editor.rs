@@ -0,0 +1,298 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Drum step editor with velocity and timing offset lanes.
+//!
+//! Zooming in widens each step; long patterns scroll with the cursor, or
+//! page along with the playing step while following.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::generators::drums::{DrumGenerator, DrumStep, MAX_STEP_OFFSET};
+
+use super::follow_offset;
+
+/// Bar heights for the velocity lane
+const VELOCITY_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Widest step, in characters
+const MAX_STEP_WIDTH: usize = 3;
+
+/// Width of the voice name column
+const NAME_WIDTH: usize = 9;
+
+/// Editing state for a drum pattern
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrumEditorState {
+    /// Voice names and their steps
+    pub voices: Vec<(String, Vec<DrumStep>)>,
+    /// Selected voice
+    pub voice: usize,
+    /// Cursor step
+    pub cursor: usize,
+    /// Characters per step (1-3)
+    pub step_width: usize,
+    /// Scroll with the playing step instead of the cursor
+    pub follow: bool,
+    /// Playing step (None = not playing)
+    pub playhead: Option<usize>,
+}
+
+impl DrumEditorState {
+    /// Load the pattern of a drum generator
+    pub fn from_generator(drums: &DrumGenerator) -> Self {
+        let voices = drums
+            .voice_names()
+            .into_iter()
+            .filter_map(|name| {
+                let steps = drums.voice_steps(&name)?;
+                Some((name, steps))
+            })
+            .collect();
+        Self {
+            voices,
+            voice: 0,
+            cursor: 0,
+            step_width: 1,
+            follow: true,
+            playhead: None,
+        }
+    }
+
+    /// Write the edited pattern back to the generator
+    pub fn apply(&self, drums: &mut DrumGenerator) {
+        for (name, steps) in &self.voices {
+            drums.set_voice_steps(name, steps);
+        }
+    }
+
+    /// Move the cursor between steps
+    pub fn move_cursor(&mut self, delta: i32) {
+        let last = self.steps().map_or(0, |s| s.len().saturating_sub(1)) as i64;
+        self.cursor = (self.cursor as i64 + delta as i64).clamp(0, last) as usize;
+    }
+
+    /// Move between voices
+    pub fn move_voice(&mut self, delta: i32) {
+        let last = self.voices.len().saturating_sub(1) as i64;
+        self.voice = (self.voice as i64 + delta as i64).clamp(0, last) as usize;
+    }
+
+    /// Widen (positive) or narrow the steps
+    pub fn zoom_by(&mut self, delta: i32) {
+        self.step_width = (self.step_width as i64 + delta as i64).clamp(1, MAX_STEP_WIDTH as i64) as usize;
+    }
+
+    /// Toggle following the playing step. Returns the new state.
+    pub fn toggle_follow(&mut self) -> bool {
+        self.follow = !self.follow;
+        self.follow
+    }
+
+    /// First step shown in a lane of a given width
+    pub fn first_visible(&self, width: usize) -> usize {
+        let count = self.steps().map_or(0, |s| s.len());
+        let columns = width.saturating_sub(NAME_WIDTH) / self.step_width.max(1);
+        follow_offset(count, self.cursor, self.playhead, self.follow, columns)
+    }
+
+    /// Steps of the selected voice
+    fn steps(&self) -> Option<&Vec<DrumStep>> {
+        self.voices.get(self.voice).map(|(_, steps)| steps)
+    }
+
+    /// Step under the cursor
+    fn current(&mut self) -> Option<&mut DrumStep> {
+        let cursor = self.cursor;
+        self.voices.get_mut(self.voice)?.1.get_mut(cursor)
+    }
+
+    /// Toggle the hit under the cursor
+    pub fn toggle_hit(&mut self) {
+        if let Some(step) = self.current() {
+            step.hit = !step.hit;
+        }
+    }
+
+    /// Raise or lower the step velocity (starting from 100 when unset)
+    pub fn adjust_velocity(&mut self, delta: i32) {
+        if let Some(step) = self.current() {
+            let velocity = step.velocity.unwrap_or(100) as i32 + delta;
+            step.velocity = Some(velocity.clamp(1, 127) as u8);
+        }
+    }
+
+    /// Return the step to the voice's own velocity
+    pub fn clear_velocity(&mut self) {
+        if let Some(step) = self.current() {
+            step.velocity = None;
+        }
+    }
+
+    /// Push (negative) or drag (positive) the step, in percent of a step
+    pub fn adjust_offset(&mut self, delta: i32) {
+        if let Some(step) = self.current() {
+            let max = MAX_STEP_OFFSET as i32;
+            step.offset = (step.offset as i32 + delta).clamp(-max, max) as i8;
+        }
+    }
+}
+
+/// Widget for editing drum steps
+pub struct DrumEditorWidget<'a> {
+    state: &'a DrumEditorState,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> DrumEditorWidget<'a> {
+    /// Create a new drum editor widget
+    pub fn new(state: &'a DrumEditorState) -> Self {
+        Self { state, block: None }
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+/// Velocity lane character for a step
+fn velocity_char(step: &DrumStep) -> char {
+    match (step.hit, step.velocity) {
+        (false, _) => ' ',
+        (true, None) => '·',
+        (true, Some(v)) => VELOCITY_BARS[(v as usize * VELOCITY_BARS.len() / 128).min(7)],
+    }
+}
+
+/// Offset lane character for a step
+fn offset_char(step: &DrumStep) -> char {
+    match step.offset {
+        o if !step.hit || o == 0 => ' ',
+        o if o < 0 => '<',
+        _ => '>',
+    }
+}
+
+impl Widget for DrumEditorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        let first = self.state.first_visible(area.width as usize);
+        let columns = (area.width as usize).saturating_sub(NAME_WIDTH) / self.state.step_width.max(1);
+        let pad = |c: char| format!("{:<width$}", c, width = self.state.step_width.max(1));
+        let cursor_style = |style: Style, selected: bool| {
+            if selected {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            }
+        };
+
+        let mut lines = Vec::new();
+        for (v, (name, steps)) in self.state.voices.iter().enumerate() {
+            let selected_voice = v == self.state.voice;
+            let name_style = if selected_voice {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+            let mut spans = vec![Span::styled(format!("{:<9}", name), name_style)];
+            for (i, step) in steps.iter().enumerate().skip(first).take(columns) {
+                let (text, style) = if step.hit {
+                    ('x', Style::default().fg(Color::Green))
+                } else {
+                    ('.', Style::default().fg(Color::DarkGray))
+                };
+                let style = if self.state.playhead == Some(i) { style.bg(Color::DarkGray) } else { style };
+                let selected = selected_voice && i == self.state.cursor;
+                spans.push(Span::styled(pad(text), cursor_style(style, selected)));
+            }
+            lines.push(Line::from(spans));
+
+            if selected_voice {
+                let lane = |label: &str, to_char: fn(&DrumStep) -> char| {
+                    let text: String = steps.iter().skip(first).take(columns).map(|s| pad(to_char(s))).collect();
+                    Line::from(vec![
+                        Span::styled(format!("{:<9}", label), Style::default().fg(Color::DarkGray)),
+                        Span::styled(text, Style::default().fg(Color::Magenta)),
+                    ])
+                };
+                lines.push(lane("  vel", velocity_char));
+                lines.push(lane("  time", offset_char));
+            }
+        }
+
+        if let Some(step) = self.state.steps().and_then(|s| s.get(self.state.cursor)) {
+            let velocity = step.velocity.map_or("-".to_string(), |v| v.to_string());
+            lines.push(Line::from(Span::styled(
+                format!("Step {}  vel {}  offset {:+}%", self.state.cursor + 1, velocity, step.offset),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::drums::gm_drums;
+
+    #[test]
+    fn test_edit_lanes() {
+        let mut drums = DrumGenerator::new();
+        let mut editor = DrumEditorState::from_generator(&drums);
+        assert_eq!(editor.voices[0].0, "hat");
+
+        // Kick is the second voice; step 2 starts empty
+        editor.move_voice(1);
+        editor.move_cursor(1);
+        editor.toggle_hit();
+        editor.adjust_velocity(-40);
+        editor.adjust_offset(-80);
+        editor.apply(&mut drums);
+
+        let kick = drums.voice_steps("kick").unwrap();
+        assert_eq!(kick[1], DrumStep { hit: true, velocity: Some(60), offset: -MAX_STEP_OFFSET });
+        assert_eq!(velocity_char(&kick[1]), '▄');
+        assert_eq!(offset_char(&kick[1]), '<');
+        assert_eq!(gm_drums::from_name(&editor.voices[1].0), Some(gm_drums::KICK));
+
+        editor.move_cursor(100);
+        assert_eq!(editor.cursor, 15);
+    }
+
+    #[test]
+    fn test_zoom_and_follow() {
+        let mut editor = DrumEditorState::from_generator(&DrumGenerator::new());
+        editor.move_cursor(12);
+
+        // 16 steps fit in 25 columns; three-wide steps scroll to the cursor
+        assert_eq!(editor.first_visible(25), 0);
+        editor.zoom_by(1);
+        editor.zoom_by(5);
+        assert_eq!(editor.step_width, MAX_STEP_WIDTH);
+        assert_eq!(editor.first_visible(33), 5);
+
+        // Following pages with the playing step
+        editor.playhead = Some(9);
+        assert_eq!(editor.first_visible(33), 8);
+        editor.toggle_follow();
+        assert_eq!(editor.first_visible(33), 5);
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Event list editor for clip notes.
+//!
+//! Shows the selected clip as a table of tick, note, velocity, duration and
+//! channel, one row per note, for precise fixes the piano roll makes fiddly.
+//! The list can be filtered down to a single pitch (one drum voice, say).
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::sequencer::{Clip, ClipNote};
+use crate::timing::PPQN;
+
+use super::channel_note_name;
+
+/// Beats per bar used for position display
+const BEATS_PER_BAR: u64 = 4;
+
+/// Editable column of the event list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventField {
+    /// Start tick
+    Tick,
+    /// Note number
+    Note,
+    /// Velocity
+    Velocity,
+    /// Duration in ticks
+    Duration,
+}
+
+impl Default for EventField {
+    fn default() -> Self {
+        EventField::Tick
+    }
+}
+
+impl EventField {
+    /// Column to the right (wrapping)
+    pub fn next(self) -> Self {
+        match self {
+            EventField::Tick => EventField::Note,
+            EventField::Note => EventField::Velocity,
+            EventField::Velocity => EventField::Duration,
+            EventField::Duration => EventField::Tick,
+        }
+    }
+
+    /// Column to the left (wrapping)
+    pub fn prev(self) -> Self {
+        match self {
+            EventField::Tick => EventField::Duration,
+            EventField::Note => EventField::Tick,
+            EventField::Velocity => EventField::Note,
+            EventField::Duration => EventField::Velocity,
+        }
+    }
+}
+
+/// Which events the list shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFilter {
+    /// Every note
+    All,
+    /// Only notes of one pitch
+    Pitch(u8),
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        EventFilter::All
+    }
+}
+
+impl EventFilter {
+    /// Check if a note passes the filter
+    pub fn matches(&self, note: &ClipNote) -> bool {
+        match self {
+            EventFilter::All => true,
+            EventFilter::Pitch(pitch) => note.note == *pitch,
+        }
+    }
+}
+
+/// Cursor and filter state for the event list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventListState {
+    /// Selected row (among filtered rows)
+    pub cursor: usize,
+    /// Selected column
+    pub field: EventField,
+    /// Row filter
+    pub filter: EventFilter,
+    /// Ticks moved per tick/duration adjustment
+    pub step: u64,
+    /// Ticks per quarter note of the clip
+    pub ppqn: u32,
+}
+
+impl Default for EventListState {
+    fn default() -> Self {
+        Self {
+            cursor: 0,
+            field: EventField::Tick,
+            filter: EventFilter::All,
+            step: PPQN as u64 / 4,
+            ppqn: PPQN,
+        }
+    }
+}
+
+impl EventListState {
+    /// Set the clip resolution (the step becomes a sixteenth)
+    pub fn with_ppqn(mut self, ppqn: u32) -> Self {
+        self.ppqn = ppqn.max(1);
+        self.step = (self.ppqn as u64 / 4).max(1);
+        self
+    }
+
+    /// Indices into the clip's notes of the rows shown
+    pub fn rows(&self, clip: &Clip) -> Vec<usize> {
+        clip.notes()
+            .iter()
+            .enumerate()
+            .filter(|(_, note)| self.filter.matches(note))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Index into the clip's notes of the row under the cursor
+    pub fn selected(&self, clip: &Clip) -> Option<usize> {
+        self.rows(clip).get(self.cursor).copied()
+    }
+
+    /// Move the cursor, keeping it inside the list
+    pub fn move_cursor(&mut self, delta: i32, clip: &Clip) {
+        let last = self.rows(clip).len().saturating_sub(1) as i64;
+        self.cursor = (self.cursor as i64 + delta as i64).clamp(0, last) as usize;
+    }
+
+    /// Select the next column
+    pub fn next_field(&mut self) {
+        self.field = self.field.next();
+    }
+
+    /// Select the previous column
+    pub fn prev_field(&mut self) {
+        self.field = self.field.prev();
+    }
+
+    /// Change the selected field of the selected note
+    pub fn adjust(&mut self, clip: &mut Clip, delta: i32) {
+        let Some(index) = self.selected(clip) else {
+            return;
+        };
+        let length = clip.length().max(1);
+        let step = self.step.max(1) as i64 * delta as i64;
+        let Some(note) = clip.note_mut(index) else {
+            return;
+        };
+        match self.field {
+            EventField::Tick => {
+                note.start_tick = (note.start_tick as i64 + step).clamp(0, length as i64 - 1) as u64;
+            }
+            EventField::Note => note.note = (note.note as i32 + delta).clamp(0, 127) as u8,
+            EventField::Velocity => note.velocity = (note.velocity as i32 + delta).clamp(1, 127) as u8,
+            EventField::Duration => note.duration = (note.duration as i64 + step).max(1) as u64,
+        }
+        let edited = note.clone();
+        self.resort(clip, &edited);
+    }
+
+    /// Insert a copy of the selected note one step later (or a default note)
+    pub fn insert(&mut self, clip: &mut Clip) {
+        let note = match self.selected(clip).and_then(|i| clip.notes().get(i)) {
+            Some(note) => ClipNote {
+                start_tick: (note.start_tick + self.step).min(clip.length().saturating_sub(1)),
+                ..note.clone()
+            },
+            None => {
+                let pitch = match self.filter {
+                    EventFilter::Pitch(pitch) => pitch,
+                    EventFilter::All => 60,
+                };
+                ClipNote::new(0, self.step.max(1), pitch, 100)
+            }
+        };
+        clip.add_note(note.clone());
+        self.resort(clip, &note);
+    }
+
+    /// Delete the selected note
+    pub fn delete(&mut self, clip: &mut Clip) {
+        if let Some(index) = self.selected(clip) {
+            clip.remove_note(index);
+            self.move_cursor(0, clip);
+        }
+    }
+
+    /// Toggle between all notes and only the selected note's pitch
+    pub fn toggle_pitch_filter(&mut self, clip: &Clip) {
+        let selected = self.selected(clip).and_then(|i| clip.notes().get(i)).cloned();
+        self.filter = match (self.filter, &selected) {
+            (EventFilter::All, Some(note)) => EventFilter::Pitch(note.note),
+            _ => EventFilter::All,
+        };
+        // Keep the same note under the cursor when it is still shown
+        match selected {
+            Some(note) => self.follow(clip, &note),
+            None => self.cursor = 0,
+        }
+    }
+
+    /// Restore note order and put the cursor back on the edited note
+    fn resort(&mut self, clip: &mut Clip, note: &ClipNote) {
+        clip.sort_notes();
+        self.follow(clip, note);
+    }
+
+    /// Put the cursor on a note, if it is shown
+    fn follow(&mut self, clip: &Clip, note: &ClipNote) {
+        let rows = self.rows(clip);
+        match rows.iter().position(|&i| clip.notes()[i] == *note) {
+            Some(row) => self.cursor = row,
+            None => self.move_cursor(0, clip),
+        }
+    }
+}
+
+/// Format a tick as bar.beat.tick (1-based bar and beat)
+fn format_position(tick: u64, ppqn: u32) -> String {
+    let ticks_per_beat = ppqn.max(1) as u64;
+    let beat = tick / ticks_per_beat;
+    format!(
+        "{}.{}.{:02}",
+        beat / BEATS_PER_BAR + 1,
+        beat % BEATS_PER_BAR + 1,
+        tick % ticks_per_beat
+    )
+}
+
+/// Widget showing a clip as an editable event list
+pub struct EventListWidget<'a> {
+    clip: &'a Clip,
+    state: &'a EventListState,
+    channel: u8,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> EventListWidget<'a> {
+    /// Create a new event list widget
+    pub fn new(clip: &'a Clip, state: &'a EventListState) -> Self {
+        Self {
+            clip,
+            state,
+            channel: 0,
+            block: None,
+        }
+    }
+
+    /// Set the channel shown for the clip's notes (0-15)
+    pub fn channel(mut self, channel: u8) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for EventListWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        let filter = match self.state.filter {
+            EventFilter::All => "All notes".to_string(),
+            EventFilter::Pitch(pitch) => format!("Only {}", channel_note_name(self.channel + 1, pitch)),
+        };
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(self.clip.name().to_string(), Style::default().fg(Color::Cyan)),
+                Span::styled(format!("  {}", filter), Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(Span::styled(
+                format!("{:<10}{:<16}{:>4}{:>6}{:>4}", "Position", "Note", "Vel", "Dur", "Ch"),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+        ];
+
+        // Scroll so the cursor stays visible
+        let rows = self.state.rows(self.clip);
+        let visible = (area.height as usize).saturating_sub(lines.len()).max(1);
+        let first = self.state.cursor.saturating_sub(visible - 1);
+
+        for (row, &index) in rows.iter().enumerate().skip(first).take(visible) {
+            let note = &self.clip.notes()[index];
+            let name: String = channel_note_name(self.channel + 1, note.note).chars().take(15).collect();
+            let cells = [
+                (EventField::Tick, format!("{:<10}", format_position(note.start_tick, self.state.ppqn))),
+                (EventField::Note, format!("{:<16}", name)),
+                (EventField::Velocity, format!("{:>4}", note.velocity)),
+                (EventField::Duration, format!("{:>6}", note.duration)),
+            ];
+
+            let mut spans: Vec<Span> = cells
+                .into_iter()
+                .map(|(field, text)| {
+                    let style = if row == self.state.cursor && field == self.state.field {
+                        Style::default().add_modifier(Modifier::REVERSED)
+                    } else if row == self.state.cursor {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    Span::styled(text, style)
+                })
+                .collect();
+            spans.push(Span::styled(
+                format!("{:>4}", self.channel + 1),
+                Style::default().fg(Color::DarkGray),
+            ));
+            lines.push(Line::from(spans));
+        }
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_clip() -> Clip {
+        let mut clip = Clip::new("Beat", 96);
+        clip.add_notes([
+            ClipNote::new(0, 6, 36, 100),
+            ClipNote::new(24, 6, 38, 90),
+            ClipNote::new(48, 6, 36, 100),
+            ClipNote::new(72, 6, 38, 90),
+        ]);
+        clip
+    }
+
+    #[test]
+    fn test_adjust_keeps_order() {
+        let mut clip = test_clip();
+        let mut state = EventListState::default();
+
+        // Move the first kick past the snare
+        for _ in 0..5 {
+            state.adjust(&mut clip, 1);
+        }
+        assert_eq!(clip.notes()[1].start_tick, 30);
+        assert_eq!(state.cursor, 1);
+
+        state.next_field();
+        state.next_field();
+        state.adjust(&mut clip, -200);
+        assert_eq!(clip.notes()[1].velocity, 1);
+    }
+
+    #[test]
+    fn test_pitch_filter() {
+        let mut clip = test_clip();
+        let mut state = EventListState::default();
+        state.move_cursor(1, &clip);
+        state.toggle_pitch_filter(&clip);
+        assert_eq!(state.filter, EventFilter::Pitch(38));
+        assert_eq!(state.rows(&clip), vec![1, 3]);
+        assert_eq!(state.cursor, 0);
+
+        // Inserting while filtered adds the same voice
+        state.insert(&mut clip);
+        assert_eq!(clip.note_count(), 5);
+        assert_eq!(state.rows(&clip).len(), 3);
+        assert_eq!(clip.notes()[state.selected(&clip).unwrap()].start_tick, 30);
+
+        state.delete(&mut clip);
+        state.delete(&mut clip);
+        assert_eq!(state.rows(&clip), vec![1]);
+
+        state.toggle_pitch_filter(&clip);
+        assert_eq!(state.filter, EventFilter::All);
+        assert_eq!(clip.notes()[state.selected(&clip).unwrap()].start_tick, 24);
+    }
+
+    #[test]
+    fn test_format_position() {
+        assert_eq!(format_position(0, 24), "1.1.00");
+        assert_eq!(format_position(30, 24), "1.2.06");
+        assert_eq!(format_position(96 + 48, 24), "2.3.00");
+        assert_eq!(format_position(480 * 5 + 120, 480), "2.2.120");
+
+        let state = EventListState::default().with_ppqn(96);
+        assert_eq!(state.step, 24);
+    }
+}
@@ -5,14 +5,42 @@
 //!
 //! Provides a ratatui-based terminal interface with transport controls,
 //! track status view, and MIDI activity display.
-
+//!
+//! Small terminals get simpler layouts instead of a broken full view: a
+//! compact transport line over a one-line-per-track list, and below that a
+//! single status line.
+//!
+//! Views are kept on a mode stack: the main view sits at the bottom and
+//! editors such as the piano-roll clip editor and the arpeggio step editor
+//! are pushed over it and popped to return. A full-screen beat view can be
+//! pushed the same way for the stage, and the beat can also be shown in
+//! the terminal title.
+
+mod arp_editor;
+mod beat;
+mod clip_editor;
+mod drum_editor;
+mod event_list;
 mod transport;
 mod tracks;
 mod midi_activity;
-
+mod params;
+mod phrases;
+mod router;
+mod scenes;
+
+pub use arp_editor::{ArpEditorState, ArpEditorWidget};
+pub use beat::{title_text, BeatCue, BeatFlash, BeatIndicatorWidget, BeatWatch};
+pub use clip_editor::{ClipEditorState, ClipEditorWidget};
+pub use drum_editor::{DrumEditorState, DrumEditorWidget};
+pub use event_list::{EventField, EventFilter, EventListState, EventListWidget};
 pub use transport::TransportWidget;
 pub use tracks::TracksWidget;
 pub use midi_activity::MidiActivityWidget;
+pub use params::{ParamEditorState, ParamEditorWidget};
+pub use phrases::{PhraseBrowserState, PhraseBrowserWidget};
+pub use router::{RouterMatrixState, RouterMatrixWidget};
+pub use scenes::{SceneCell, SceneStripWidget};
 
 use std::io::{self, Stdout};
 use std::sync::{Arc, Mutex};
@@ -21,18 +49,112 @@ use std::time::{Duration, Instant};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    style::Print,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
 };
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, Paragraph, Wrap},
     Frame, Terminal,
 };
 
-use crate::sequencer::{SequencerTiming, TrackState};
+use crate::arrangement::{PartManager, TrackOverride};
+use crate::control::TRACKS_PER_PAGE;
+use crate::midi::gm;
+use crate::sequencer::{SequencerTiming, ThinStats, TrackState};
+use crate::timing::{PositionMode, PositionReadout};
+
+/// Smallest terminal (columns, rows) for the full layout
+const FULL_LAYOUT_SIZE: (u16, u16) = (80, 20);
+
+/// Smallest terminal (columns, rows) for the compact layout
+const COMPACT_LAYOUT_SIZE: (u16, u16) = (30, 4);
+
+/// Screen layout chosen for the terminal size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutMode {
+    /// Transport, track rows with meters, MIDI activity and status bar
+    Full,
+    /// Transport line, one line per track and status bar
+    Compact,
+    /// A single status line
+    Minimal,
+}
+
+impl Default for LayoutMode {
+    fn default() -> Self {
+        LayoutMode::Full
+    }
+}
+
+impl LayoutMode {
+    /// Choose the layout for a terminal size
+    pub fn for_size(width: u16, height: u16) -> Self {
+        if width >= FULL_LAYOUT_SIZE.0 && height >= FULL_LAYOUT_SIZE.1 {
+            LayoutMode::Full
+        } else if width >= COMPACT_LAYOUT_SIZE.0 && height >= COMPACT_LAYOUT_SIZE.1 {
+            LayoutMode::Compact
+        } else {
+            LayoutMode::Minimal
+        }
+    }
+}
+
+/// A view of the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UiMode {
+    /// Transport, tracks and MIDI activity
+    Main,
+    /// Piano-roll editor for the selected track's clip
+    ClipEditor,
+    /// Step-order editor for the selected track's arpeggio
+    ArpEditor,
+    /// Full-screen beat indicator
+    Beat,
+}
+
+/// Stack of views; the top one gets the keys and the screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeStack {
+    modes: Vec<UiMode>,
+}
+
+impl Default for ModeStack {
+    fn default() -> Self {
+        Self { modes: vec![UiMode::Main] }
+    }
+}
+
+impl ModeStack {
+    /// Current view
+    pub fn current(&self) -> UiMode {
+        self.modes.last().copied().unwrap_or(UiMode::Main)
+    }
+
+    /// Open a view over the current one (no-op if it is already on top)
+    pub fn push(&mut self, mode: UiMode) {
+        if self.current() != mode {
+            self.modes.push(mode);
+        }
+    }
+
+    /// Return to the previous view. The main view is never popped.
+    pub fn pop(&mut self) -> Option<UiMode> {
+        if self.modes.len() > 1 {
+            self.modes.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Number of views open over the main view
+    pub fn depth(&self) -> usize {
+        self.modes.len() - 1
+    }
+}
 
 /// UI state shared between components
 #[derive(Debug, Clone)]
@@ -41,6 +163,10 @@ pub struct UiState {
     pub transport: TransportState,
     /// Track states
     pub tracks: Vec<TrackUiState>,
+    /// Track bank page for mute/solo keys
+    pub track_page: usize,
+    /// Selected track index
+    pub selected_track: usize,
     /// MIDI activity
     pub midi_activity: MidiActivityState,
     /// Help text visible
@@ -49,6 +175,14 @@ pub struct UiState {
     pub status_message: Option<String>,
     /// Status message timestamp
     pub status_time: Option<Instant>,
+    /// Config load errors (running in safe mode when not empty)
+    pub config_errors: Vec<String>,
+    /// Open views
+    pub modes: ModeStack,
+    /// Clip being edited (loaded when the clip editor opens)
+    pub clip_editor: Option<ClipEditorState>,
+    /// Arpeggio being edited (loaded when the arpeggio editor opens)
+    pub arp_editor: Option<ArpEditorState>,
 }
 
 impl Default for UiState {
@@ -56,10 +190,16 @@ impl Default for UiState {
         Self {
             transport: TransportState::default(),
             tracks: Vec::new(),
+            track_page: 0,
+            selected_track: 0,
             midi_activity: MidiActivityState::default(),
             show_help: false,
             status_message: None,
             status_time: None,
+            config_errors: Vec::new(),
+            modes: ModeStack::default(),
+            clip_editor: None,
+            arp_editor: None,
         }
     }
 }
@@ -71,6 +211,23 @@ impl UiState {
         self.status_time = Some(Instant::now());
     }
 
+    /// Number of track pages
+    pub fn page_count(&self) -> usize {
+        self.tracks.len().max(1).div_ceil(TRACKS_PER_PAGE)
+    }
+
+    /// Move the track page, keeping it in range
+    pub fn shift_page(&mut self, delta: i32) {
+        let page = (self.track_page as i64 + delta as i64).max(0) as usize;
+        self.track_page = page.min(self.page_count() - 1);
+    }
+
+    /// Move the track selection, keeping it in range
+    pub fn select_track(&mut self, delta: i32) {
+        let last = self.tracks.len().saturating_sub(1) as i64;
+        self.selected_track = (self.selected_track as i64 + delta as i64).clamp(0, last) as usize;
+    }
+
     /// Clear expired status message
     pub fn clear_expired_status(&mut self) {
         if let Some(time) = self.status_time {
@@ -103,6 +260,59 @@ pub struct TransportState {
     pub tick: u64,
     /// Total ticks elapsed
     pub total_ticks: u64,
+    /// Currently playing part
+    pub part: Option<PartIndicator>,
+    /// Queued or upcoming part
+    pub next_part: Option<PartIndicator>,
+    /// Whether a quantized part change is waiting
+    pub part_pending: bool,
+    /// Ticks per quarter note
+    pub ppqn: u32,
+    /// How the position is shown
+    pub position_mode: PositionMode,
+    /// End tick of the current section, if known
+    pub section_end: Option<u64>,
+    /// MTC frame rate while timecode is active
+    pub smpte_fps: Option<u8>,
+    /// Whether the metronome is clicking
+    pub metronome: bool,
+}
+
+/// Part name and color shown in the transport bar
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartIndicator {
+    /// Part name
+    pub name: String,
+    /// Part color (RGB)
+    pub color: (u8, u8, u8),
+}
+
+impl PartIndicator {
+    /// Create a new part indicator
+    pub fn new(name: impl Into<String>, color: (u8, u8, u8)) -> Self {
+        Self {
+            name: name.into(),
+            color,
+        }
+    }
+
+    /// Get the display style (part color as background)
+    pub fn style(&self) -> Style {
+        cell_style(self.color)
+    }
+}
+
+/// Convert an RGB tuple to a terminal color
+pub fn rgb_color(color: (u8, u8, u8)) -> Color {
+    Color::Rgb(color.0, color.1, color.2)
+}
+
+/// Style for a cell tinted with a part/scene color, with readable text
+pub fn cell_style(color: (u8, u8, u8)) -> Style {
+    let (r, g, b) = color;
+    let luma = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+    let fg = if luma > 140.0 { Color::Black } else { Color::White };
+    Style::default().fg(fg).bg(rgb_color(color))
 }
 
 impl Default for TransportState {
@@ -117,6 +327,14 @@ impl Default for TransportState {
             beat: 1,
             tick: 0,
             total_ticks: 0,
+            part: None,
+            next_part: None,
+            part_pending: false,
+            ppqn: 24,
+            position_mode: PositionMode::BarsBeats,
+            section_end: None,
+            smpte_fps: None,
+            metronome: false,
         }
     }
 }
@@ -131,6 +349,72 @@ impl TransportState {
         self.beat = timing.current_beat() + 1;
         self.tick = timing.current_tick();
         self.total_ticks = timing.position_ticks;
+        self.ppqn = timing.ppqn;
+    }
+
+    /// Readout for the current position settings
+    pub fn position_readout(&self) -> PositionReadout {
+        PositionReadout::new(self.ppqn, self.time_sig_num, self.tempo)
+            .with_mode(self.position_mode)
+            .with_section_end(self.section_end)
+            .with_smpte(self.smpte_fps)
+    }
+
+    /// Current position in the selected mode
+    pub fn position_text(&self) -> String {
+        self.position_readout().format(self.total_ticks)
+    }
+
+    /// Switch to the next position mode that can be shown
+    pub fn cycle_position_mode(&mut self) {
+        let readout = self.position_readout();
+        let mut mode = self.position_mode.next();
+        while !readout.is_available(mode) {
+            mode = mode.next();
+        }
+        self.position_mode = mode;
+    }
+
+    /// Update current/next part from the part manager
+    pub fn update_from_parts(&mut self, parts: &PartManager) {
+        let indicator = |name: &str| {
+            parts
+                .get_part(name)
+                .map(|part| PartIndicator::new(name, part.color()))
+        };
+
+        self.part = parts.current_part().and_then(indicator);
+        self.part_pending = parts.pending_transition().is_some();
+        self.section_end = parts.pending_transition().map(|pending| pending.scheduled_tick);
+        self.next_part = match parts.pending_transition() {
+            Some(pending) => indicator(&pending.target),
+            None => parts.next_part().and_then(indicator),
+        };
+    }
+
+    /// Whether the next part indicator is lit (flashes each beat while pending)
+    pub fn part_flash(&self) -> bool {
+        self.part_pending && self.beat % 2 == 1
+    }
+
+    /// Spans for the current → next part indicator
+    pub fn part_spans(&self) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+        if let Some(part) = &self.part {
+            spans.push(Span::styled(format!(" {} ", part.name), part.style()));
+        }
+        if let Some(next) = &self.next_part {
+            let style = if !self.part_pending {
+                Style::default().fg(rgb_color(next.color))
+            } else if self.part_flash() {
+                next.style().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(rgb_color(next.color)).add_modifier(Modifier::BOLD)
+            };
+            spans.push(Span::styled(" → ", Style::default().fg(Color::DarkGray)));
+            spans.push(Span::styled(format!(" {} ", next.name), style));
+        }
+        spans
     }
 }
 
@@ -139,6 +423,10 @@ impl TransportState {
 pub struct TrackUiState {
     /// Track name
     pub name: String,
+    /// Short display name for narrow layouts
+    pub short_name: Option<String>,
+    /// Icon shown before the name
+    pub icon: Option<String>,
     /// Track index
     pub index: usize,
     /// MIDI channel (1-16 for display)
@@ -153,13 +441,41 @@ pub struct TrackUiState {
     pub playing_notes: Vec<u8>,
     /// Velocity meter (0-127)
     pub velocity_meter: u8,
+    /// Selected program (if any)
+    pub program: Option<u8>,
+    /// Instrument range (low, high), if declared
+    pub range: Option<(u8, u8)>,
+    /// Notes fell outside the instrument range recently
+    pub out_of_range: bool,
+    /// CC thinning counts (None when the track isn't thinned)
+    pub cc_thin: Option<ThinStats>,
+    /// Overrides that end with the current part
+    pub overrides: Vec<TrackOverride>,
+    /// Recent and upcoming notes for the note lane, oldest first
+    pub lane: Vec<LaneNote>,
+}
+
+/// A note shown in a track's note lane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LaneNote {
+    /// Song tick the note starts on
+    pub start_tick: u64,
+    /// Length in ticks
+    pub duration: u64,
+    /// MIDI note
+    pub note: u8,
 }
 
+/// Most notes kept for a track's note lane
+const MAX_LANE_NOTES: usize = 256;
+
 impl TrackUiState {
     /// Create a new track UI state
     pub fn new(index: usize, name: impl Into<String>) -> Self {
         Self {
             name: name.into(),
+            short_name: None,
+            icon: None,
             index,
             channel: 1,
             state: TrackState::Active,
@@ -167,8 +483,43 @@ impl TrackUiState {
             generator: None,
             playing_notes: Vec::new(),
             velocity_meter: 0,
+            program: None,
+            range: None,
+            out_of_range: false,
+            cc_thin: None,
+            overrides: Vec::new(),
+            lane: Vec::new(),
+        }
+    }
+
+    /// Add a played or scheduled note to the note lane
+    pub fn add_lane_note(&mut self, start_tick: u64, duration: u64, note: u8) {
+        let index = self.lane.partition_point(|n| n.start_tick <= start_tick);
+        self.lane.insert(index, LaneNote { start_tick, duration: duration.max(1), note });
+        if self.lane.len() > MAX_LANE_NOTES {
+            let excess = self.lane.len() - MAX_LANE_NOTES;
+            self.lane.drain(..excess);
         }
     }
+
+    /// Drop lane notes that ended before a tick
+    pub fn prune_lane(&mut self, before_tick: u64) {
+        self.lane.retain(|n| n.start_tick + n.duration >= before_tick);
+    }
+
+    /// Get the name to display (icon plus short name if set)
+    pub fn display_name(&self) -> String {
+        let name = self.short_name.as_deref().unwrap_or(&self.name);
+        match &self.icon {
+            Some(icon) => format!("{} {}", icon, name),
+            None => name.to_string(),
+        }
+    }
+
+    /// Pick up the part-scoped overrides on this track
+    pub fn update_from_parts(&mut self, parts: &PartManager) {
+        self.overrides = parts.track_overrides(self.index);
+    }
 }
 
 /// MIDI activity state
@@ -237,7 +588,7 @@ impl MidiActivityMessage {
         Self {
             message_type: "Note On".to_string(),
             channel,
-            data: format!("{} vel:{}", note_name(note), velocity),
+            data: format!("{} vel:{}", channel_note_name(channel, note), velocity),
             time: Instant::now(),
         }
     }
@@ -247,7 +598,7 @@ impl MidiActivityMessage {
         Self {
             message_type: "Note Off".to_string(),
             channel,
-            data: note_name(note),
+            data: channel_note_name(channel, note),
             time: Instant::now(),
         }
     }
@@ -261,6 +612,40 @@ impl MidiActivityMessage {
             time: Instant::now(),
         }
     }
+
+    /// Create a program change message
+    pub fn program_change(channel: u8, program: u8) -> Self {
+        Self {
+            message_type: "Program".to_string(),
+            channel,
+            data: program_label(program),
+            time: Instant::now(),
+        }
+    }
+}
+
+/// Note name, using GM percussion names on channel 10
+pub(crate) fn channel_note_name(channel: u8, note: u8) -> String {
+    if channel == gm::DRUM_CHANNEL + 1 {
+        if let Some(name) = gm::drum_name(note) {
+            return name.to_string();
+        }
+    }
+    note_name(note)
+}
+
+/// Program number with its GM name
+pub(crate) fn program_label(program: u8) -> String {
+    match gm::program_name(program) {
+        Some(name) => format!("{} {}", program, name),
+        None => program.to_string(),
+    }
+}
+
+/// Level meter text `width` cells wide (levels above 127 read as full)
+pub(crate) fn meter_bar(level: u8, width: usize) -> String {
+    let filled = (level.min(127) as usize * width) / 127;
+    "█".repeat(filled) + &"░".repeat(width - filled)
 }
 
 /// Convert MIDI note number to name
@@ -292,16 +677,57 @@ pub enum KeyAction {
     NudgeUp,
     /// Nudge tempo down
     NudgeDown,
+    /// Shift phase forward one pulse
+    PhaseAdvance,
+    /// Shift phase back one pulse
+    PhaseRetard,
     /// Toggle track mute
     ToggleMute(usize),
     /// Toggle track solo
     ToggleSolo(usize),
+    /// Track page changed
+    TrackPage(usize),
+    /// Track selection changed
+    SelectTrack(usize),
     /// Trigger scene
     TriggerScene(usize),
+    /// Play a fill for the next bar
+    Fill,
     /// Toggle help
     ToggleHelp,
     /// Toggle MIDI learn
     ToggleLearn,
+    /// Position readout switched to the next mode
+    CyclePositionDisplay,
+    /// Clip editor opened on a track (load its clip into `clip_editor`)
+    OpenClipEditor(usize),
+    /// Clip editor closed
+    CloseClipEditor,
+    /// Notes edited: write `clip_editor` back to the clip
+    ClipEdited {
+        /// Track index
+        track: usize,
+        /// Clip index
+        clip: usize,
+    },
+    /// Arpeggio editor opened on a track (load its steps into `arp_editor`)
+    OpenArpEditor(usize),
+    /// Arpeggio editor closed
+    CloseArpEditor,
+    /// Steps edited: play the new order on the track
+    ArpEdited {
+        /// Track index
+        track: usize,
+        /// Step order, e.g. "1-3-r-5^"
+        order: String,
+    },
+    /// Steps saved: store the order in the song's `arp_presets`
+    ArpPresetSaved {
+        /// Preset name
+        name: String,
+        /// Step order
+        order: String,
+    },
 }
 
 /// Terminal UI application
@@ -314,6 +740,10 @@ pub struct App {
     frame_rate: u32,
     /// Whether to continue running
     running: bool,
+    /// Where the beat is signalled outside the TUI
+    beat_flash: BeatFlash,
+    /// Detects new beats for the flash
+    beat_watch: BeatWatch,
 }
 
 impl App {
@@ -331,6 +761,8 @@ impl App {
             terminal,
             frame_rate: 60,
             running: true,
+            beat_flash: BeatFlash::default(),
+            beat_watch: BeatWatch::new(),
         })
     }
 
@@ -349,6 +781,11 @@ impl App {
         self.frame_rate = fps.clamp(1, 120);
     }
 
+    /// Set where the beat is signalled outside the TUI
+    pub fn set_beat_flash(&mut self, flash: BeatFlash) {
+        self.beat_flash = flash;
+    }
+
     /// Check if running
     pub fn is_running(&self) -> bool {
         self.running
@@ -359,8 +796,30 @@ impl App {
         self.running = false;
     }
 
+    /// Current view
+    pub fn mode(&self) -> UiMode {
+        self.state.lock().map(|state| state.modes.current()).unwrap_or(UiMode::Main)
+    }
+
     /// Handle a key event
     pub fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> KeyAction {
+        if self.mode() == UiMode::ClipEditor {
+            if let Some(action) = self.handle_clip_editor_key(code, modifiers) {
+                return action;
+            }
+        }
+        if self.mode() == UiMode::ArpEditor {
+            if let Some(action) = self.handle_arp_editor_key(code, modifiers) {
+                return action;
+            }
+        }
+        if self.mode() == UiMode::Beat && matches!(code, KeyCode::Esc | KeyCode::Char('b')) {
+            if let Ok(mut state) = self.state.lock() {
+                state.modes.pop();
+            }
+            return KeyAction::None;
+        }
+
         match (code, modifiers) {
             // Quit
             (KeyCode::Char('q'), KeyModifiers::NONE)
@@ -379,14 +838,16 @@ impl App {
             (KeyCode::Down, KeyModifiers::NONE) => KeyAction::TempoDown,
             (KeyCode::Up, KeyModifiers::SHIFT) => KeyAction::NudgeUp,
             (KeyCode::Down, KeyModifiers::SHIFT) => KeyAction::NudgeDown,
+            (KeyCode::Right, KeyModifiers::SHIFT) => KeyAction::PhaseAdvance,
+            (KeyCode::Left, KeyModifiers::SHIFT) => KeyAction::PhaseRetard,
 
-            // Track mute (1-8)
+            // Track mute (1-8, offset by the track page)
             (KeyCode::Char(c @ '1'..='8'), KeyModifiers::NONE) => {
                 let index = (c as usize) - ('1' as usize);
-                KeyAction::ToggleMute(index)
+                KeyAction::ToggleMute(self.page_offset() + index)
             }
 
-            // Track solo (Shift + 1-8)
+            // Track solo (Shift + 1-8, offset by the track page)
             (KeyCode::Char(c @ '!'..='*'), KeyModifiers::SHIFT) => {
                 // Shift+1-8 produces !@#$%^&*
                 let index = match c {
@@ -400,7 +861,31 @@ impl App {
                     '*' => 7,
                     _ => return KeyAction::None,
                 };
-                KeyAction::ToggleSolo(index)
+                KeyAction::ToggleSolo(self.page_offset() + index)
+            }
+
+            // Track pages
+            (KeyCode::PageDown, _) | (KeyCode::PageUp, _) => {
+                let delta = if code == KeyCode::PageDown { 1 } else { -1 };
+                match self.state.lock() {
+                    Ok(mut state) => {
+                        state.shift_page(delta);
+                        KeyAction::TrackPage(state.track_page)
+                    }
+                    Err(_) => KeyAction::None,
+                }
+            }
+
+            // Track selection
+            (KeyCode::Char('['), KeyModifiers::NONE) | (KeyCode::Char(']'), KeyModifiers::NONE) => {
+                let delta = if code == KeyCode::Char(']') { 1 } else { -1 };
+                match self.state.lock() {
+                    Ok(mut state) => {
+                        state.select_track(delta);
+                        KeyAction::SelectTrack(state.selected_track)
+                    }
+                    Err(_) => KeyAction::None,
+                }
             }
 
             // Scene triggers (F1-F8)
@@ -408,6 +893,9 @@ impl App {
                 KeyAction::TriggerScene((n - 1) as usize)
             }
 
+            // Fill
+            (KeyCode::Char('f'), KeyModifiers::NONE) => KeyAction::Fill,
+
             // Help
             (KeyCode::Char('?'), _) | (KeyCode::Char('h'), KeyModifiers::NONE) => {
                 if let Ok(mut state) = self.state.lock() {
@@ -419,10 +907,157 @@ impl App {
             // MIDI learn
             (KeyCode::Char('l'), KeyModifiers::NONE) => KeyAction::ToggleLearn,
 
+            // Position readout
+            (KeyCode::Char('p'), KeyModifiers::NONE) => {
+                if let Ok(mut state) = self.state.lock() {
+                    state.transport.cycle_position_mode();
+                }
+                KeyAction::CyclePositionDisplay
+            }
+
+            // Full-screen beat view
+            (KeyCode::Char('b'), KeyModifiers::NONE) => {
+                if let Ok(mut state) = self.state.lock() {
+                    state.modes.push(UiMode::Beat);
+                }
+                KeyAction::None
+            }
+
+            // Clip editor
+            (KeyCode::Char('e'), KeyModifiers::NONE) => match self.state.lock() {
+                Ok(mut state) => {
+                    state.modes.push(UiMode::ClipEditor);
+                    state.clip_editor = None;
+                    KeyAction::OpenClipEditor(state.selected_track)
+                }
+                Err(_) => KeyAction::None,
+            },
+
+            // Arpeggio editor
+            (KeyCode::Char('A'), _) => match self.state.lock() {
+                Ok(mut state) => {
+                    state.modes.push(UiMode::ArpEditor);
+                    state.arp_editor = None;
+                    KeyAction::OpenArpEditor(state.selected_track)
+                }
+                Err(_) => KeyAction::None,
+            },
+
             _ => KeyAction::None,
         }
     }
 
+    /// Handle a key in the clip editor. Returns None for keys the editor
+    /// leaves to the main view (transport, quit).
+    fn handle_clip_editor_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<KeyAction> {
+        let Ok(mut state) = self.state.lock() else {
+            return Some(KeyAction::None);
+        };
+        if matches!(code, KeyCode::Esc | KeyCode::Char('e')) {
+            state.modes.pop();
+            return Some(KeyAction::CloseClipEditor);
+        }
+        let editor = state.clip_editor.as_mut()?;
+        let edited = match (code, modifiers) {
+            (KeyCode::Left, KeyModifiers::NONE) => {
+                editor.move_cursor(-1);
+                false
+            }
+            (KeyCode::Right, KeyModifiers::NONE) => {
+                editor.move_cursor(1);
+                false
+            }
+            (KeyCode::Up, KeyModifiers::NONE) => {
+                editor.move_pitch(1);
+                false
+            }
+            (KeyCode::Down, KeyModifiers::NONE) => {
+                editor.move_pitch(-1);
+                false
+            }
+            (KeyCode::Left, KeyModifiers::SHIFT) => editor.move_note(-1, 0),
+            (KeyCode::Right, KeyModifiers::SHIFT) => editor.move_note(1, 0),
+            (KeyCode::Up, KeyModifiers::SHIFT) => editor.move_note(0, 1),
+            (KeyCode::Down, KeyModifiers::SHIFT) => editor.move_note(0, -1),
+            (KeyCode::Enter, _) | (KeyCode::Char('a'), KeyModifiers::NONE) => editor.add_note(),
+            (KeyCode::Delete, _) | (KeyCode::Backspace, _) | (KeyCode::Char('x'), KeyModifiers::NONE) => {
+                editor.delete_note()
+            }
+            (KeyCode::Char('.'), KeyModifiers::NONE) => editor.resize_note(1),
+            (KeyCode::Char(','), KeyModifiers::NONE) => editor.resize_note(-1),
+            (KeyCode::Char('+'), _) | (KeyCode::Char('='), KeyModifiers::NONE) => {
+                editor.zoom_by(-1);
+                false
+            }
+            (KeyCode::Char('-'), KeyModifiers::NONE) => {
+                editor.zoom_by(1);
+                false
+            }
+            (KeyCode::Char('F'), _) => {
+                editor.toggle_follow();
+                false
+            }
+            _ => return None,
+        };
+        Some(if edited {
+            KeyAction::ClipEdited {
+                track: editor.track,
+                clip: editor.clip,
+            }
+        } else {
+            KeyAction::None
+        })
+    }
+
+    /// Handle a key in the arpeggio editor. Returns None for keys the editor
+    /// leaves to the main view.
+    fn handle_arp_editor_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Option<KeyAction> {
+        let Ok(mut state) = self.state.lock() else {
+            return Some(KeyAction::None);
+        };
+        if matches!(code, KeyCode::Esc | KeyCode::Char('A')) {
+            state.modes.pop();
+            return Some(KeyAction::CloseArpEditor);
+        }
+        let editor = state.arp_editor.as_mut()?;
+        match (code, modifiers) {
+            (KeyCode::Left, KeyModifiers::NONE) => editor.cursor_left(),
+            (KeyCode::Right, KeyModifiers::NONE) => editor.cursor_right(),
+            (KeyCode::Up, KeyModifiers::NONE) => editor.adjust_degree(1),
+            (KeyCode::Down, KeyModifiers::NONE) => editor.adjust_degree(-1),
+            (KeyCode::Up, KeyModifiers::SHIFT) => editor.adjust_octave(1),
+            (KeyCode::Down, KeyModifiers::SHIFT) => editor.adjust_octave(-1),
+            (KeyCode::Enter, _) | (KeyCode::Char('a'), KeyModifiers::NONE) => editor.insert_step(),
+            (KeyCode::Delete, _) | (KeyCode::Backspace, _) | (KeyCode::Char('x'), KeyModifiers::NONE) => {
+                editor.delete_step()
+            }
+            (KeyCode::Char('.'), KeyModifiers::NONE) => editor.toggle_rest(),
+            (KeyCode::Char('s'), KeyModifiers::NONE) => {
+                return Some(KeyAction::ArpPresetSaved {
+                    name: editor.preset_name(),
+                    order: editor.order_string(),
+                });
+            }
+            _ => return None,
+        }
+        Some(if matches!(code, KeyCode::Left | KeyCode::Right) {
+            KeyAction::None
+        } else {
+            KeyAction::ArpEdited {
+                track: editor.track,
+                order: editor.order_string(),
+            }
+        })
+    }
+
+    /// First track index on the current page
+    fn page_offset(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.track_page * TRACKS_PER_PAGE)
+            .unwrap_or(0)
+    }
+
     /// Poll for events with timeout
     pub fn poll_event(&self) -> io::Result<Option<Event>> {
         let timeout = Duration::from_millis(1000 / self.frame_rate as u64);
@@ -440,6 +1075,23 @@ impl App {
         self.terminal.draw(|frame| {
             let area = frame.area();
 
+            if state.modes.current() == UiMode::Beat {
+                frame.render_widget(BeatIndicatorWidget::new(&state.transport), area);
+                return;
+            }
+
+            match LayoutMode::for_size(area.width, area.height) {
+                LayoutMode::Full => {}
+                LayoutMode::Compact => {
+                    render_compact(frame, area, &state);
+                    return;
+                }
+                LayoutMode::Minimal => {
+                    frame.render_widget(Paragraph::new(status_line(&state)), area);
+                    return;
+                }
+            }
+
             // Main layout: header, content, footer
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
@@ -454,8 +1106,12 @@ impl App {
             // Transport
             render_transport(frame, chunks[0], &state.transport);
 
-            // Tracks
-            render_tracks(frame, chunks[1], &state.tracks);
+            // Tracks, or the clip editor over them
+            match state.modes.current() {
+                UiMode::ClipEditor => render_clip_editor(frame, chunks[1], &state),
+                UiMode::ArpEditor => render_arp_editor(frame, chunks[1], &state),
+                _ => render_tracks(frame, chunks[1], &state),
+            }
 
             // MIDI Activity
             render_midi_activity(frame, chunks[2], &state.midi_activity);
@@ -466,9 +1122,29 @@ impl App {
             // Help overlay
             if state.show_help {
                 render_help_overlay(frame, area);
+            } else if !state.config_errors.is_empty() {
+                render_safe_mode_overlay(frame, area, &state.config_errors);
             }
         })?;
 
+        self.flash_beat(&state.transport)
+    }
+
+    /// Show a new beat in the terminal title and ring the bell on the bar
+    fn flash_beat(&mut self, transport: &TransportState) -> io::Result<()> {
+        if !self.beat_flash.is_enabled() {
+            return Ok(());
+        }
+        let Some(cue) = self.beat_watch.update(transport) else {
+            return Ok(());
+        };
+        let backend = self.terminal.backend_mut();
+        if self.beat_flash.title {
+            execute!(backend, SetTitle(title_text(transport)))?;
+        }
+        if self.beat_flash.bell && cue.is_downbeat() {
+            execute!(backend, Print("\x07"))?;
+        }
         Ok(())
     }
 
@@ -508,7 +1184,7 @@ fn render_transport(frame: &mut Frame, area: Rect, state: &TransportState) {
             Constraint::Length(15), // Position
             Constraint::Length(12), // Tempo
             Constraint::Length(10), // Time Sig
-            Constraint::Min(0),     // Padding
+            Constraint::Min(0),     // Part
         ])
         .split(inner);
 
@@ -525,7 +1201,7 @@ fn render_transport(frame: &mut Frame, area: Rect, state: &TransportState) {
     frame.render_widget(Paragraph::new(play_text), chunks[0]);
 
     // Position
-    let position = format!("{:03}:{:02}:{:02}", state.bar, state.beat, state.tick);
+    let position = state.position_text();
     let pos_widget = Paragraph::new(position)
         .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD));
     frame.render_widget(pos_widget, chunks[1]);
@@ -541,13 +1217,39 @@ fn render_transport(frame: &mut Frame, area: Rect, state: &TransportState) {
     let sig_widget = Paragraph::new(time_sig)
         .style(Style::default().fg(Color::White));
     frame.render_widget(sig_widget, chunks[3]);
+
+    // Current/next part
+    frame.render_widget(Paragraph::new(Line::from(state.part_spans())), chunks[4]);
+}
+
+/// First visible row so that the selected row stays in view
+pub(crate) fn scroll_offset(count: usize, selected: usize, rows: usize) -> usize {
+    if rows == 0 || count <= rows {
+        return 0;
+    }
+    selected.saturating_sub(rows - 1).min(count - rows)
+}
+
+/// First visible column: while following, pages along with the playhead;
+/// otherwise keeps the cursor in view
+pub(crate) fn follow_offset(count: usize, cursor: usize, playhead: Option<usize>, follow: bool, columns: usize) -> usize {
+    match playhead {
+        Some(playhead) if follow && columns > 0 && count > columns => (playhead / columns * columns).min(count - columns),
+        _ => scroll_offset(count, cursor, columns),
+    }
 }
 
 /// Render tracks section
-fn render_tracks(frame: &mut Frame, area: Rect, tracks: &[TrackUiState]) {
+fn render_tracks(frame: &mut Frame, area: Rect, state: &UiState) {
+    let tracks = &state.tracks;
+    let title = if state.page_count() > 1 {
+        format!(" Tracks (bank {}/{}) ", state.track_page + 1, state.page_count())
+    } else {
+        " Tracks ".to_string()
+    };
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(" Tracks ");
+        .title(title);
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
@@ -559,9 +1261,13 @@ fn render_tracks(frame: &mut Frame, area: Rect, tracks: &[TrackUiState]) {
         return;
     }
 
-    // Calculate track row height
+    // Calculate track row height and scroll to keep the selection visible
     let track_height = 2;
-    let constraints: Vec<Constraint> = tracks
+    let rows = (inner.height / track_height).max(1) as usize;
+    let offset = scroll_offset(tracks.len(), state.selected_track, rows);
+    let visible = &tracks[offset..tracks.len().min(offset + rows)];
+
+    let constraints: Vec<Constraint> = visible
         .iter()
         .map(|_| Constraint::Length(track_height))
         .collect();
@@ -571,16 +1277,46 @@ fn render_tracks(frame: &mut Frame, area: Rect, tracks: &[TrackUiState]) {
         .constraints(constraints)
         .split(inner);
 
-    for (i, track) in tracks.iter().enumerate() {
+    for (i, track) in visible.iter().enumerate() {
         if i >= track_chunks.len() {
             break;
         }
-        render_track_row(frame, track_chunks[i], track);
+        let selected = offset + i == state.selected_track;
+        render_track_row(frame, track_chunks[i], track, selected, &state.transport);
+    }
+}
+
+/// Render the clip editor in place of the tracks
+fn render_clip_editor(frame: &mut Frame, area: Rect, state: &UiState) {
+    let block = Block::default().borders(Borders::ALL).title(" Clip Editor ");
+    match &state.clip_editor {
+        Some(editor) => frame.render_widget(ClipEditorWidget::new(editor).block(block), area),
+        None => {
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            let empty = Paragraph::new("No clip on the selected track").style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(empty, inner);
+        }
+    }
+}
+
+/// Render the arpeggio editor in place of the tracks
+fn render_arp_editor(frame: &mut Frame, area: Rect, state: &UiState) {
+    let block = Block::default().borders(Borders::ALL).title(" Arpeggio Steps ");
+    match &state.arp_editor {
+        Some(editor) => frame.render_widget(ArpEditorWidget::new(editor).block(block), area),
+        None => {
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            let empty = Paragraph::new("No arpeggio on the selected track");
+            let empty = empty.style(Style::default().fg(Color::DarkGray));
+            frame.render_widget(empty, inner);
+        }
     }
 }
 
 /// Render a single track row
-fn render_track_row(frame: &mut Frame, area: Rect, track: &TrackUiState) {
+fn render_track_row(frame: &mut Frame, area: Rect, track: &TrackUiState, selected: bool, transport: &TransportState) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -594,8 +1330,17 @@ fn render_track_row(frame: &mut Frame, area: Rect, track: &TrackUiState) {
         .split(area);
 
     // Index
-    let idx = Paragraph::new(format!("{}", track.index + 1))
-        .style(Style::default().fg(Color::DarkGray));
+    let idx_style = if selected {
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let idx_text = if selected {
+        format!(">{}", track.index + 1)
+    } else {
+        format!(" {}", track.index + 1)
+    };
+    let idx = Paragraph::new(idx_text).style(idx_style);
     frame.render_widget(idx, chunks[0]);
 
     // Name
@@ -604,7 +1349,7 @@ fn render_track_row(frame: &mut Frame, area: Rect, track: &TrackUiState) {
         TrackState::Soloed => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         TrackState::Active => Style::default().fg(Color::White),
     };
-    let name = Paragraph::new(track.name.clone()).style(name_style);
+    let name = Paragraph::new(track.display_name()).style(name_style);
     frame.render_widget(name, chunks[1]);
 
     // Channel
@@ -631,15 +1376,135 @@ fn render_track_row(frame: &mut Frame, area: Rect, track: &TrackUiState) {
         .style(Style::default().fg(Color::Green));
     frame.render_widget(source_widget, chunks[4]);
 
-    // Velocity meter
+    // Note lane when there are notes to show, otherwise the velocity meter
     let meter_width = chunks[5].width.saturating_sub(2) as usize;
-    let filled = (track.velocity_meter as usize * meter_width) / 127;
-    let meter: String = "█".repeat(filled) + &"░".repeat(meter_width - filled);
-    let meter_widget = Paragraph::new(meter)
+    if !track.lane.is_empty() {
+        let lines = tracks::note_lane_lines(
+            &track.lane,
+            transport.total_ticks,
+            transport.ppqn,
+            meter_width,
+            chunks[5].height as usize,
+            track.state == TrackState::Muted,
+        );
+        frame.render_widget(Paragraph::new(lines), chunks[5]);
+        return;
+    }
+    let meter_widget = Paragraph::new(meter_bar(track.velocity_meter, meter_width))
         .style(Style::default().fg(Color::Green));
     frame.render_widget(meter_widget, chunks[5]);
 }
 
+/// Play/stop symbol and color
+fn play_indicator(state: &TransportState) -> Span<'static> {
+    if state.recording && state.playing {
+        Span::styled("●", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+    } else if state.playing {
+        Span::styled("▶", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
+    } else {
+        Span::styled("■", Style::default().fg(Color::Yellow))
+    }
+}
+
+/// Transport, part and status on one line (minimal layout)
+fn status_line(state: &UiState) -> Line<'static> {
+    let transport = &state.transport;
+    let mut spans = vec![
+        play_indicator(transport),
+        Span::styled(
+            format!(" {}:{} ", transport.bar, transport.beat),
+            Style::default().fg(Color::Cyan),
+        ),
+        Span::styled(format!("{:.0} ", transport.tempo), Style::default().fg(Color::Magenta)),
+    ];
+    spans.extend(transport.part_spans());
+    if let Some(msg) = &state.status_message {
+        spans.push(Span::styled(format!(" {}", msg), Style::default().fg(Color::Yellow)));
+    }
+    Line::from(spans)
+}
+
+/// Render the compact layout: transport line, track lines, status bar
+fn render_compact(frame: &mut Frame, area: Rect, state: &UiState) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1), // Transport
+            Constraint::Min(1),    // Tracks
+            Constraint::Length(1), // Status bar
+        ])
+        .split(area);
+
+    let transport = &state.transport;
+    let mut spans = vec![
+        play_indicator(transport),
+        Span::styled(
+            format!(" {} ", transport.position_text()),
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{:.1} BPM ", transport.tempo), Style::default().fg(Color::Magenta)),
+    ];
+    spans.extend(transport.part_spans());
+    frame.render_widget(Paragraph::new(Line::from(spans)), chunks[0]);
+
+    let rows = chunks[1].height as usize;
+    let offset = scroll_offset(state.tracks.len(), state.selected_track, rows);
+    let lines: Vec<Line> = state
+        .tracks
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(rows)
+        .map(|(i, track)| compact_track_line(track, i == state.selected_track, chunks[1].width))
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[1]);
+
+    match &state.status_message {
+        Some(msg) => frame.render_widget(
+            Paragraph::new(Span::styled(msg.clone(), Style::default().fg(Color::Yellow))),
+            chunks[2],
+        ),
+        None => frame.render_widget(
+            Paragraph::new(Span::styled(" h: Help | q: Quit", Style::default().fg(Color::DarkGray))),
+            chunks[2],
+        ),
+    }
+}
+
+/// One track as a single line, with a meter when there is room
+fn compact_track_line(track: &TrackUiState, selected: bool, width: u16) -> Line<'static> {
+    let marker = if selected { ">" } else { " " };
+    let (state, state_style) = match track.state {
+        TrackState::Muted => ("M", Style::default().fg(Color::Red)),
+        TrackState::Soloed => ("S", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        TrackState::Active => ("-", Style::default().fg(Color::DarkGray)),
+    };
+    let name: String = track.display_name().chars().take(10).collect();
+    let source = track
+        .active_clip
+        .as_ref()
+        .or(track.generator.as_ref())
+        .map(|s| s.as_str())
+        .unwrap_or("-");
+    let source: String = source.chars().take(10).collect();
+
+    let mut spans = vec![
+        Span::styled(format!("{}{:<2} ", marker, track.index + 1), Style::default().fg(Color::DarkGray)),
+        Span::styled(format!("{:<10} ", name), Style::default().fg(Color::White)),
+        Span::styled(format!("{} ", state), state_style),
+        Span::styled(format!("{:<10} ", source), Style::default().fg(Color::Green)),
+    ];
+    // Prefix is 4 + 11 + 2 + 11 columns
+    let meter_width = (width as usize).saturating_sub(28).min(16);
+    if meter_width >= 4 {
+        spans.push(Span::styled(
+            meter_bar(track.velocity_meter, meter_width),
+            Style::default().fg(Color::Green),
+        ));
+    }
+    Line::from(spans)
+}
+
 /// Render MIDI activity section
 fn render_midi_activity(frame: &mut Frame, area: Rect, state: &MidiActivityState) {
     let title = if state.learn_mode {
@@ -708,9 +1573,14 @@ fn render_midi_messages(frame: &mut Frame, area: Rect, label: &str, messages: &[
 fn render_status_bar(frame: &mut Frame, area: Rect, state: &UiState) {
     let text = if let Some(ref msg) = state.status_message {
         Span::styled(msg, Style::default().fg(Color::Yellow))
+    } else if !state.config_errors.is_empty() {
+        Span::styled(
+            format!(" SAFE MODE: {} config error(s) - fix the file to reload", state.config_errors.len()),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )
     } else {
         Span::styled(
-            " Space: Play/Pause | Esc: Stop | 1-8: Mute | Shift+1-8: Solo | h: Help | q: Quit",
+            " Space: Play/Pause | Esc: Stop | 1-8: Mute | Shift+1-8: Solo | PgUp/PgDn: Bank | h: Help | q: Quit",
             Style::default().fg(Color::DarkGray),
         )
     };
@@ -718,11 +1588,45 @@ fn render_status_bar(frame: &mut Frame, area: Rect, state: &UiState) {
     frame.render_widget(Paragraph::new(text), area);
 }
 
+/// Render the safe mode error report
+fn render_safe_mode_overlay(frame: &mut Frame, area: Rect, errors: &[String]) {
+    let width = 70.min(area.width.saturating_sub(4));
+    let height = (errors.len() as u16 * 3 + 4).min(area.height.saturating_sub(4));
+    let x = (area.width - width) / 2;
+    let y = (area.height - height) / 2;
+    let error_area = Rect::new(x, y, width, height);
+
+    // Clear background
+    frame.render_widget(
+        Block::default().style(Style::default().bg(Color::Black)),
+        error_area,
+    );
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Safe Mode ")
+        .border_style(Style::default().fg(Color::Red))
+        .style(Style::default().bg(Color::Black));
+
+    let inner = block.inner(error_area);
+    frame.render_widget(block, error_area);
+
+    let mut lines = vec![
+        Line::from("Configuration failed to load; running with defaults."),
+        Line::from(""),
+    ];
+    for error in errors {
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))));
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
 /// Render help overlay
 fn render_help_overlay(frame: &mut Frame, area: Rect) {
     // Calculate centered area
     let width = 50.min(area.width.saturating_sub(4));
-    let height = 16.min(area.height.saturating_sub(4));
+    let height = 24.min(area.height.saturating_sub(4));
     let x = (area.width - width) / 2;
     let y = (area.height - height) / 2;
     let help_area = Rect::new(x, y, width, height);
@@ -747,12 +1651,19 @@ fn render_help_overlay(frame: &mut Frame, area: Rect) {
         Line::from("  Esc         Stop"),
         Line::from("  r           Toggle Record"),
         Line::from("  Up/Down     Tempo +/- 1 BPM"),
-        Line::from("  Shift+Up/Dn Nudge tempo"),
+        Line::from("  Shift+Up/Dn Nudge tempo (hold)"),
+        Line::from("  Shift+L/R   Shift phase"),
         Line::from(""),
         Line::from(Span::styled("Tracks", Style::default().add_modifier(Modifier::BOLD))),
         Line::from("  1-8         Toggle mute"),
         Line::from("  Shift+1-8   Toggle solo"),
+        Line::from("  PgUp/PgDn   Previous/next track bank"),
+        Line::from("  [ / ]       Select previous/next track"),
         Line::from("  F1-F8       Trigger scene"),
+        Line::from("  f           Fill next bar"),
+        Line::from("  e           Clip editor (arrows, a/x, ,/., +/- zoom, F follow)"),
+        Line::from("  A           Arpeggio steps (arrows, a/x, . rest, s save preset)"),
+        Line::from("  b           Full-screen beat (Esc to close)"),
         Line::from(""),
         Line::from(Span::styled("Other", Style::default().add_modifier(Modifier::BOLD))),
         Line::from("  l           MIDI learn"),
@@ -775,12 +1686,84 @@ mod tests {
         assert_eq!(note_name(127), "G9");
     }
 
+    #[test]
+    fn test_mode_stack() {
+        let mut modes = ModeStack::default();
+        assert_eq!(modes.current(), UiMode::Main);
+        modes.push(UiMode::ClipEditor);
+        modes.push(UiMode::ClipEditor);
+        assert_eq!(modes.depth(), 1);
+        assert_eq!(modes.current(), UiMode::ClipEditor);
+        assert_eq!(modes.pop(), Some(UiMode::ClipEditor));
+        assert_eq!(modes.pop(), None);
+        assert_eq!(modes.current(), UiMode::Main);
+    }
+
     #[test]
     fn test_transport_state_default() {
         let state = TransportState::default();
         assert!(!state.playing);
         assert_eq!(state.tempo, 120.0);
         assert_eq!(state.bar, 1);
+        assert!(state.part.is_none());
+    }
+
+    #[test]
+    fn test_transport_part_indicator() {
+        use crate::arrangement::{Part, PartTransition};
+
+        let mut parts = PartManager::new(4);
+        let mut intro = Part::new("Intro").with_transition(PartTransition::Immediate);
+        intro.set_color(200, 40, 40);
+        let mut verse = Part::new("Verse");
+        verse.set_color(40, 40, 200);
+        parts.add_part(intro);
+        parts.add_part(verse);
+        parts.trigger_part("Intro", 0, 24, 4);
+
+        let mut state = TransportState::default();
+        state.update_from_parts(&parts);
+        assert_eq!(state.part, Some(PartIndicator::new("Intro", (200, 40, 40))));
+        assert_eq!(state.next_part, Some(PartIndicator::new("Verse", (40, 40, 200))));
+        assert!(!state.part_pending);
+
+        // Queue the verse for the next bar
+        parts.trigger_part("Verse", 10, 24, 4);
+        state.update_from_parts(&parts);
+        assert!(state.part_pending);
+        state.beat = 1;
+        assert!(state.part_flash());
+        state.beat = 2;
+        assert!(!state.part_flash());
+    }
+
+    #[test]
+    fn test_transport_position_modes() {
+        let mut state = TransportState {
+            total_ticks: 96 * 4,
+            ..Default::default()
+        };
+        assert_eq!(state.position_text(), "005:01:00");
+
+        // Remaining and SMPTE are skipped until they have something to show
+        state.cycle_position_mode();
+        assert_eq!(state.position_text(), "0:08.0");
+        state.cycle_position_mode();
+        assert_eq!(state.position_mode, PositionMode::BarsBeats);
+
+        state.section_end = Some(96 * 6);
+        state.smpte_fps = Some(30);
+        state.cycle_position_mode();
+        state.cycle_position_mode();
+        assert_eq!(state.position_text(), "-0:04.0");
+        state.cycle_position_mode();
+        assert_eq!(state.position_text(), "00:00:08:00");
+    }
+
+    #[test]
+    fn test_cell_style_contrast() {
+        assert_eq!(cell_style((250, 250, 200)).fg, Some(Color::Black));
+        assert_eq!(cell_style((20, 20, 120)).fg, Some(Color::White));
     }
 
     #[test]
@@ -798,6 +1781,13 @@ mod tests {
         assert_eq!(msg.message_type, "Note On");
         assert_eq!(msg.channel, 1);
         assert!(msg.data.contains("C4"));
+
+        // Channel 10 shows GM percussion names
+        let msg = MidiActivityMessage::note_off(10, 38);
+        assert_eq!(msg.data, "Acoustic Snare");
+
+        let msg = MidiActivityMessage::program_change(1, 89);
+        assert_eq!(msg.data, "89 Pad 2 (warm)");
     }
 
     #[test]
@@ -819,4 +1809,80 @@ mod tests {
         assert_eq!(track.index, 0);
         assert_eq!(track.state, TrackState::Active);
     }
+
+    #[test]
+    fn test_track_display_name() {
+        let mut track = TrackUiState::new(0, "Bass Synth");
+        assert_eq!(track.display_name(), "Bass Synth");
+
+        track.short_name = Some("Bs".to_string());
+        track.icon = Some("♪".to_string());
+        assert_eq!(track.display_name(), "♪ Bs");
+    }
+
+    #[test]
+    fn test_track_lane_notes() {
+        let mut track = TrackUiState::new(0, "Lead");
+        track.add_lane_note(24, 12, 64);
+        track.add_lane_note(0, 6, 60);
+        track.add_lane_note(48, 0, 67);
+        let starts: Vec<u64> = track.lane.iter().map(|n| n.start_tick).collect();
+        assert_eq!(starts, vec![0, 24, 48]);
+        assert_eq!(track.lane[2].duration, 1);
+
+        track.prune_lane(10);
+        assert_eq!(track.lane.len(), 2);
+        for tick in 0..300 {
+            track.add_lane_note(tick, 1, 60);
+        }
+        assert_eq!(track.lane.len(), MAX_LANE_NOTES);
+    }
+
+    #[test]
+    fn test_layout_modes() {
+        assert_eq!(LayoutMode::for_size(120, 40), LayoutMode::Full);
+        assert_eq!(LayoutMode::for_size(80, 20), LayoutMode::Full);
+        assert_eq!(LayoutMode::for_size(79, 40), LayoutMode::Compact);
+        assert_eq!(LayoutMode::for_size(120, 8), LayoutMode::Compact);
+        assert_eq!(LayoutMode::for_size(29, 40), LayoutMode::Minimal);
+        assert_eq!(LayoutMode::for_size(120, 3), LayoutMode::Minimal);
+
+        // Meters never overrun their width, even for out-of-range levels
+        assert_eq!(meter_bar(127, 4), "████");
+        assert_eq!(meter_bar(255, 4), "████");
+        assert_eq!(meter_bar(0, 0), "");
+
+        let mut track = TrackUiState::new(0, "Bass");
+        track.velocity_meter = 200;
+        let line = compact_track_line(&track, true, 40);
+        assert_eq!(line.spans.len(), 5);
+        assert_eq!(compact_track_line(&track, false, 30).spans.len(), 4);
+
+        let mut state = UiState::default();
+        state.set_status("Saved");
+        let text: String = status_line(&state).spans.iter().map(|s| s.content.to_string()).collect();
+        assert_eq!(text, "■ 1:1 120  Saved");
+    }
+
+    #[test]
+    fn test_track_paging_and_scroll() {
+        let mut state = UiState::default();
+        state.tracks = (0..20).map(|i| TrackUiState::new(i, format!("T{}", i))).collect();
+        assert_eq!(state.page_count(), 3);
+
+        state.shift_page(5);
+        assert_eq!(state.track_page, 2);
+        state.shift_page(-1);
+        assert_eq!(state.track_page, 1);
+
+        state.select_track(15);
+        assert_eq!(state.selected_track, 15);
+        state.select_track(10);
+        assert_eq!(state.selected_track, 19);
+
+        assert_eq!(scroll_offset(20, 3, 8), 0);
+        assert_eq!(scroll_offset(20, 12, 8), 5);
+        assert_eq!(scroll_offset(20, 19, 8), 12);
+        assert_eq!(scroll_offset(4, 3, 8), 0);
+    }
 }
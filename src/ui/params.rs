@@ -0,0 +1,140 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Parameter editor page with randomization locks.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::control::ParameterRegistry;
+
+/// Width of the parameter name column
+const NAME_WIDTH: usize = 16;
+
+/// Width of the value bar
+const BAR_WIDTH: usize = 12;
+
+/// Cursor state for the parameter editor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParamEditorState {
+    /// Selected parameter (in registry order)
+    pub cursor: usize,
+}
+
+impl ParamEditorState {
+    /// Move the cursor, keeping it inside the parameter list
+    pub fn move_cursor(&mut self, delta: i32, registry: &ParameterRegistry) {
+        let last = registry.len().saturating_sub(1) as i64;
+        self.cursor = (self.cursor as i64 + delta as i64).clamp(0, last) as usize;
+    }
+
+    /// Name of the parameter under the cursor
+    pub fn selected<'a>(&self, registry: &'a ParameterRegistry) -> Option<&'a str> {
+        registry.iter().nth(self.cursor).map(|p| p.name.as_str())
+    }
+
+    /// Toggle the lock on the parameter under the cursor, returning the new state
+    pub fn toggle_lock(&self, registry: &mut ParameterRegistry) -> Option<bool> {
+        let name = self.selected(registry)?.to_string();
+        registry.toggle_lock(&name)
+    }
+}
+
+/// Widget listing parameters with their values and lock flags
+pub struct ParamEditorWidget<'a> {
+    registry: &'a ParameterRegistry,
+    state: &'a ParamEditorState,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> ParamEditorWidget<'a> {
+    /// Create a new parameter editor widget
+    pub fn new(registry: &'a ParameterRegistry, state: &'a ParamEditorState) -> Self {
+        Self {
+            registry,
+            state,
+            block: None,
+        }
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for ParamEditorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        let lines: Vec<Line> = self
+            .registry
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let filled = (param.get_normalized() * BAR_WIDTH as f64).round() as usize;
+                let filled = filled.min(BAR_WIDTH);
+                let name: String = param.display_name.chars().take(NAME_WIDTH - 1).collect();
+
+                let (lock, lock_style) = if param.locked {
+                    ("L ", Style::default().fg(Color::Yellow))
+                } else {
+                    ("  ", Style::default())
+                };
+                let name_style = if i == self.state.cursor {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else if param.locked {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default()
+                };
+
+                Line::from(vec![
+                    Span::styled(lock, lock_style),
+                    Span::styled(format!("{:<width$}", name, width = NAME_WIDTH), name_style),
+                    Span::styled("█".repeat(filled), Style::default().fg(Color::Cyan)),
+                    Span::styled(
+                        "░".repeat(BAR_WIDTH - filled),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::raw(format!(" {}", param.format())),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::Parameter;
+
+    #[test]
+    fn test_editor_toggle_lock() {
+        let mut registry = ParameterRegistry::new();
+        registry.register(Parameter::new("base_octave", 1.0, 7.0, 4.0));
+        registry.register(Parameter::new("rate", 0.0, 1.0, 0.5));
+
+        let mut state = ParamEditorState::default();
+        state.move_cursor(5, &registry);
+        assert_eq!(state.selected(&registry), Some("rate"));
+
+        assert_eq!(state.toggle_lock(&mut registry), Some(true));
+        assert!(registry.is_locked("rate"));
+        assert!(!registry.is_locked("base_octave"));
+    }
+}
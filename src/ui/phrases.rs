@@ -0,0 +1,133 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Phrase library browser.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::recording::PhraseLibrary;
+
+use super::scroll_offset;
+
+/// Selection state for the phrase browser
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhraseBrowserState {
+    /// Selected phrase index
+    pub selected: usize,
+}
+
+impl PhraseBrowserState {
+    /// Move the selection, keeping it inside the library
+    pub fn move_selection(&mut self, delta: i32, library: &PhraseLibrary) {
+        let last = library.len().saturating_sub(1) as i64;
+        self.selected = (self.selected as i64 + delta as i64).clamp(0, last) as usize;
+    }
+}
+
+/// Widget listing captured phrases
+pub struct PhraseBrowserWidget<'a> {
+    library: &'a PhraseLibrary,
+    state: &'a PhraseBrowserState,
+    ticks_per_bar: u64,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> PhraseBrowserWidget<'a> {
+    /// Create a new phrase browser
+    pub fn new(library: &'a PhraseLibrary, state: &'a PhraseBrowserState, ticks_per_bar: u64) -> Self {
+        Self {
+            library,
+            state,
+            ticks_per_bar: ticks_per_bar.max(1),
+            block: None,
+        }
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for PhraseBrowserWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        if self.library.is_empty() {
+            Paragraph::new(Span::styled(
+                "No phrases captured",
+                Style::default().fg(Color::DarkGray),
+            ))
+            .render(area, buf);
+            return;
+        }
+
+        let rows = area.height as usize;
+        let offset = scroll_offset(self.library.len(), self.state.selected, rows);
+        let lines: Vec<Line> = self
+            .library
+            .phrases()
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(rows)
+            .map(|(i, phrase)| {
+                let bars = phrase.length().div_ceil(self.ticks_per_bar);
+                let style = if i == self.state.selected {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:<16}", phrase.name()), style),
+                    Span::styled(
+                        format!(" {:<10}", phrase.source().unwrap_or("-")),
+                        Style::default().fg(Color::Cyan),
+                    ),
+                    Span::styled(
+                        format!(" {:>2} bars {:>3} notes", bars, phrase.notes().len()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ])
+            })
+            .collect();
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::Phrase;
+    use crate::sequencer::ClipNote;
+
+    #[test]
+    fn test_browser_selection() {
+        let mut library = PhraseLibrary::new(384);
+        library.add(Phrase::new("Hook", 192, vec![ClipNote::new(0, 24, 60, 100)]));
+        library.add(Phrase::new("Fill", 96, Vec::new()));
+
+        let mut state = PhraseBrowserState::default();
+        state.move_selection(5, &library);
+        assert_eq!(state.selected, 1);
+        state.move_selection(-3, &library);
+        assert_eq!(state.selected, 0);
+
+        state.move_selection(1, &PhraseLibrary::new(384));
+        assert_eq!(state.selected, 0);
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! MIDI routing matrix page.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::midi::MidiRouter;
+
+/// Width of a port name column
+const COLUMN_WIDTH: usize = 10;
+
+/// Cursor state for the routing matrix (rows = inputs, columns = outputs)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouterMatrixState {
+    /// Selected input row
+    pub input: usize,
+    /// Selected output column
+    pub output: usize,
+}
+
+impl RouterMatrixState {
+    /// Move the cursor, keeping it inside the matrix
+    pub fn move_cursor(&mut self, d_input: i32, d_output: i32, router: &MidiRouter) {
+        let clamp = |value: usize, delta: i32, len: usize| {
+            (value as i64 + delta as i64).clamp(0, len.saturating_sub(1) as i64) as usize
+        };
+        self.input = clamp(self.input, d_input, router.inputs().len());
+        self.output = clamp(self.output, d_output, router.outputs().len());
+    }
+
+    /// Toggle the thru route under the cursor
+    pub fn toggle(&self, router: &mut MidiRouter) -> bool {
+        router.toggle_route(self.input, self.output).unwrap_or(false)
+    }
+}
+
+/// Widget showing the routing matrix
+pub struct RouterMatrixWidget<'a> {
+    router: &'a MidiRouter,
+    state: &'a RouterMatrixState,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> RouterMatrixWidget<'a> {
+    /// Create a new routing matrix widget
+    pub fn new(router: &'a MidiRouter, state: &'a RouterMatrixState) -> Self {
+        Self {
+            router,
+            state,
+            block: None,
+        }
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+/// Shorten a port name to fit a column
+fn column_label(name: &str) -> String {
+    let short: String = name.chars().take(COLUMN_WIDTH - 1).collect();
+    format!("{:<width$}", short, width = COLUMN_WIDTH)
+}
+
+impl Widget for RouterMatrixWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        let header_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+        let mut header = vec![Span::raw(column_label(""))];
+        for output in self.router.outputs() {
+            header.push(Span::styled(column_label(output), header_style));
+        }
+
+        let mut lines = vec![Line::from(header)];
+        for (i, input) in self.router.inputs().iter().enumerate() {
+            let mut spans = vec![Span::styled(column_label(input), header_style)];
+            for o in 0..self.router.outputs().len() {
+                let (symbol, base) = match self.router.route(i, o) {
+                    Some(route) if !route.enabled => ("○", Style::default().fg(Color::DarkGray)),
+                    Some(route) if !route.processors.is_empty() => {
+                        ("◆", Style::default().fg(Color::Yellow))
+                    }
+                    Some(_) => ("●", Style::default().fg(Color::Green)),
+                    None => ("·", Style::default().fg(Color::DarkGray)),
+                };
+                let style = if i == self.state.input && o == self.state.output {
+                    base.add_modifier(Modifier::REVERSED)
+                } else {
+                    base
+                };
+                spans.push(Span::styled(format!("{:<width$}", symbol, width = COLUMN_WIDTH), style));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        Paragraph::new(lines).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix_cursor_and_toggle() {
+        let mut router = MidiRouter::new(
+            vec!["Keys".to_string(), "Pads".to_string()],
+            vec!["Synth".to_string()],
+        );
+        let mut state = RouterMatrixState::default();
+
+        state.move_cursor(5, 3, &router);
+        assert_eq!(state, RouterMatrixState { input: 1, output: 0 });
+
+        assert!(state.toggle(&mut router));
+        assert!(router.route(1, 0).is_some());
+        assert!(!state.toggle(&mut router));
+    }
+}
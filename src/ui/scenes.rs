@@ -0,0 +1,218 @@
+// Copyright (c) 2026 Robert L. Snyder, Sierra Vista, AZ
+// Licensed under the MIT License. See LICENSE file in the project root for details.
+
+//! Scene and arrangement strip tinted with part colors.
+//!
+//! Zoom levels widen or narrow the cells. When there are more cells than
+//! fit, the strip pages along with the playing cell while following, or
+//! keeps the selected cell in view.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::arrangement::{SceneManager, SongPlayer};
+
+use super::{cell_style, follow_offset};
+
+/// Cell widths for each zoom level
+const CELL_WIDTHS: [usize; 4] = [4, 6, 10, 16];
+
+/// Zoom level of the default cell width
+const DEFAULT_STRIP_ZOOM: usize = 2;
+
+/// A single colored scene/section cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneCell {
+    /// Cell label
+    pub name: String,
+    /// Cell color (RGB)
+    pub color: (u8, u8, u8),
+    /// Currently playing
+    pub playing: bool,
+    /// Queued to start at the next quantize point
+    pub pending: bool,
+}
+
+impl SceneCell {
+    /// Create a new cell
+    pub fn new(name: impl Into<String>, color: (u8, u8, u8)) -> Self {
+        Self {
+            name: name.into(),
+            color,
+            playing: false,
+            pending: false,
+        }
+    }
+
+    /// Build cells for all scenes
+    pub fn from_scenes(scenes: &SceneManager) -> Vec<SceneCell> {
+        let pending = scenes.pending_launch().map(|p| p.scene_index);
+        scenes
+            .scenes()
+            .iter()
+            .enumerate()
+            .map(|(i, scene)| SceneCell {
+                name: scene.name().to_string(),
+                color: scene.color(),
+                playing: scenes.current_scene() == Some(i),
+                pending: pending == Some(i),
+            })
+            .collect()
+    }
+
+    /// Build cells for the sections of the loaded song
+    pub fn from_song(player: &SongPlayer) -> Vec<SceneCell> {
+        let Some(song) = player.song() else {
+            return Vec::new();
+        };
+        song.sections()
+            .iter()
+            .enumerate()
+            .map(|(i, section)| SceneCell {
+                name: section.part_name().to_string(),
+                color: section.color(),
+                playing: player.current_section() == i,
+                pending: false,
+            })
+            .collect()
+    }
+}
+
+/// Widget drawing a row of colored cells
+pub struct SceneStripWidget<'a> {
+    cells: &'a [SceneCell],
+    flash: bool,
+    zoom: usize,
+    follow: bool,
+    selected: usize,
+    block: Option<Block<'a>>,
+}
+
+impl<'a> SceneStripWidget<'a> {
+    /// Create a new strip widget
+    pub fn new(cells: &'a [SceneCell]) -> Self {
+        Self {
+            cells,
+            flash: false,
+            zoom: DEFAULT_STRIP_ZOOM,
+            follow: true,
+            selected: 0,
+            block: None,
+        }
+    }
+
+    /// Set the zoom level (0 = narrowest cells)
+    pub fn zoom(mut self, zoom: usize) -> Self {
+        self.zoom = zoom.min(CELL_WIDTHS.len() - 1);
+        self
+    }
+
+    /// Page along with the playing cell
+    pub fn follow(mut self, follow: bool) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    /// Cell kept in view when not following
+    pub fn selected(mut self, selected: usize) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// First cell shown in a strip of a given width
+    fn first_visible(&self, width: usize) -> usize {
+        let playing = self.cells.iter().position(|cell| cell.playing);
+        let columns = width / CELL_WIDTHS[self.zoom];
+        follow_offset(self.cells.len(), self.selected, playing, self.follow, columns)
+    }
+
+    /// Set flash phase for pending cells
+    pub fn flash(mut self, flash: bool) -> Self {
+        self.flash = flash;
+        self
+    }
+
+    /// Set the block wrapper
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+}
+
+impl Widget for SceneStripWidget<'_> {
+    fn render(mut self, area: Rect, buf: &mut Buffer) {
+        let area = if let Some(block) = self.block.take() {
+            let inner = block.inner(area);
+            block.render(area, buf);
+            inner
+        } else {
+            area
+        };
+
+        let cell_width = CELL_WIDTHS[self.zoom];
+        let spans: Vec<Span> = self
+            .cells
+            .iter()
+            .skip(self.first_visible(area.width as usize))
+            .map(|cell| {
+                let label: String = cell.name.chars().take(cell_width - 2).collect();
+                let style = if cell.playing {
+                    cell_style(cell.color).add_modifier(Modifier::BOLD)
+                } else if cell.pending && self.flash {
+                    cell_style(cell.color)
+                } else {
+                    Style::default().fg(Color::Rgb(cell.color.0, cell.color.1, cell.color.2))
+                };
+                Span::styled(format!(" {:<width$}", label, width = cell_width - 1), style)
+            })
+            .collect();
+
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrangement::Scene;
+
+    #[test]
+    fn test_cells_from_scenes() {
+        let mut scenes = SceneManager::new(2);
+        let mut verse = Scene::new("Verse");
+        verse.set_color(40, 200, 40);
+        scenes.add_scene(Scene::new("Intro"));
+        scenes.add_scene(verse);
+
+        scenes.launch_scene(0, 0, 24, 4);
+        scenes.launch_scene(1, 10, 24, 4);
+
+        let cells = SceneCell::from_scenes(&scenes);
+        assert_eq!(cells.len(), 2);
+        assert!(cells[0].playing);
+        assert!(cells[1].pending);
+        assert_eq!(cells[1].color, (40, 200, 40));
+    }
+
+    #[test]
+    fn test_strip_zoom_and_follow() {
+        let mut cells: Vec<SceneCell> = (0..12).map(|i| SceneCell::new(format!("S{}", i), (0, 0, 0))).collect();
+        cells[9].playing = true;
+
+        // Four 10-wide cells fit in 40 columns: page with the playing cell
+        let strip = SceneStripWidget::new(&cells);
+        assert_eq!(strip.first_visible(40), 8);
+        let strip = SceneStripWidget::new(&cells).follow(false).selected(5);
+        assert_eq!(strip.first_visible(40), 2);
+
+        // Zoomed all the way out, every cell fits
+        let strip = SceneStripWidget::new(&cells).zoom(0);
+        assert_eq!(strip.first_visible(48), 0);
+        assert_eq!(SceneStripWidget::new(&cells).zoom(9).zoom, CELL_WIDTHS.len() - 1);
+    }
+}
@@ -2,6 +2,11 @@
 // Licensed under the MIT License. See LICENSE file in the project root for details.
 
 //! Track status display widgets.
+//!
+//! Tracks with notes in their lane show a small scrolling piano roll in
+//! place of the level meter: time runs left to right a sixteenth per
+//! column, with the last quarter of the lane already played, and each
+//! column's block height is the highest note in it.
 
 use ratatui::{
     buffer::Buffer,
@@ -11,13 +16,22 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
-use crate::sequencer::TrackState;
-use super::TrackUiState;
+use crate::arrangement::TrackOverride;
+use crate::sequencer::{ThinStats, TrackState};
+use super::{meter_bar, scroll_offset, LaneNote, TrackUiState};
+
+/// Block characters from lowest to highest
+const LANE_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Smallest pitch span the lane height is scaled to, in semitones
+const LANE_MIN_SPAN: u8 = 12;
 
 /// Widget for displaying all tracks
 pub struct TracksWidget<'a> {
     tracks: &'a [TrackUiState],
     selected: Option<usize>,
+    /// Song position and resolution for the note lanes
+    position: (u64, u32),
     block: Option<Block<'a>>,
 }
 
@@ -27,6 +41,7 @@ impl<'a> TracksWidget<'a> {
         Self {
             tracks,
             selected: None,
+            position: (0, 24),
             block: None,
         }
     }
@@ -37,6 +52,12 @@ impl<'a> TracksWidget<'a> {
         self
     }
 
+    /// Set the song position (in ticks) the note lanes scroll with
+    pub fn position(mut self, tick: u64, ppqn: u32) -> Self {
+        self.position = (tick, ppqn);
+        self
+    }
+
     /// Set the block wrapper
     pub fn block(mut self, block: Block<'a>) -> Self {
         self.block = Some(block);
@@ -66,11 +87,16 @@ impl Widget for TracksWidget<'_> {
         let track_height = 1;
         let total_height = header_height + self.tracks.len() as u16 * track_height;
 
+        // Scroll so the selected track stays visible
+        let rows = (area.height.saturating_sub(header_height) / track_height).max(1) as usize;
+        let offset = scroll_offset(self.tracks.len(), self.selected.unwrap_or(0), rows);
+        let visible = &self.tracks[offset..self.tracks.len().min(offset + rows)];
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
                 std::iter::once(Constraint::Length(header_height))
-                    .chain(self.tracks.iter().map(|_| Constraint::Length(track_height)))
+                    .chain(visible.iter().map(|_| Constraint::Length(track_height)))
                     .collect::<Vec<_>>(),
             )
             .split(area);
@@ -79,9 +105,9 @@ impl Widget for TracksWidget<'_> {
         render_track_header(chunks[0], buf);
 
         // Render each track
-        for (i, track) in self.tracks.iter().enumerate() {
-            let is_selected = self.selected == Some(i);
-            render_track_row(chunks[i + 1], buf, track, is_selected);
+        for (i, track) in visible.iter().enumerate() {
+            let is_selected = self.selected == Some(offset + i);
+            render_track_row(chunks[i + 1], buf, track, is_selected, self.position);
         }
     }
 }
@@ -115,7 +141,7 @@ fn render_track_header(area: Rect, buf: &mut Buffer) {
 }
 
 /// Render a single track row
-fn render_track_row(area: Rect, buf: &mut Buffer, track: &TrackUiState, selected: bool) {
+fn render_track_row(area: Rect, buf: &mut Buffer, track: &TrackUiState, selected: bool, position: (u64, u32)) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -142,13 +168,14 @@ fn render_track_row(area: Rect, buf: &mut Buffer, track: &TrackUiState, selected
     };
     Paragraph::new(idx_text).style(idx_style).render(chunks[0], buf);
 
-    // Name
+    // Name (red while notes fall outside the instrument range)
     let name_style = match track.state {
         TrackState::Muted => Style::default().fg(Color::DarkGray),
+        _ if track.out_of_range => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         TrackState::Soloed => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
         TrackState::Active => Style::default().fg(Color::White),
     };
-    Paragraph::new(track.name.clone())
+    Paragraph::new(track.display_name())
         .style(name_style)
         .render(chunks[1], buf);
 
@@ -157,8 +184,11 @@ fn render_track_row(area: Rect, buf: &mut Buffer, track: &TrackUiState, selected
         .style(Style::default().fg(Color::Cyan))
         .render(chunks[2], buf);
 
-    // Mute indicator
-    let mute_style = if track.state == TrackState::Muted {
+    // Mute indicator (magenta when it only lasts for this part)
+    let scoped = |kind| track.overrides.contains(&kind);
+    let mute_style = if track.state == TrackState::Muted && scoped(TrackOverride::Mute) {
+        Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+    } else if track.state == TrackState::Muted {
         Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
     } else {
         Style::default().fg(Color::DarkGray)
@@ -167,7 +197,9 @@ fn render_track_row(area: Rect, buf: &mut Buffer, track: &TrackUiState, selected
     Paragraph::new(mute_text).style(mute_style).render(chunks[3], buf);
 
     // Solo indicator
-    let solo_style = if track.state == TrackState::Soloed {
+    let solo_style = if track.state == TrackState::Soloed && scoped(TrackOverride::Solo) {
+        Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)
+    } else if track.state == TrackState::Soloed {
         Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
     } else {
         Style::default().fg(Color::DarkGray)
@@ -182,6 +214,11 @@ fn render_track_row(area: Rect, buf: &mut Buffer, track: &TrackUiState, selected
         .or(track.generator.as_ref())
         .map(|s| s.as_str())
         .unwrap_or("-");
+    let source = if scoped(TrackOverride::DoubleDensity) {
+        format!("{} x2", source)
+    } else {
+        source.to_string()
+    };
     let source_style = if track.state == TrackState::Muted {
         Style::default().fg(Color::DarkGray)
     } else {
@@ -189,8 +226,85 @@ fn render_track_row(area: Rect, buf: &mut Buffer, track: &TrackUiState, selected
     };
     Paragraph::new(source).style(source_style).render(chunks[5], buf);
 
-    // Level meter
-    render_level_meter(chunks[6], buf, track.velocity_meter, track.state);
+    // Note lane, or the level meter when nothing is playing
+    if track.lane.is_empty() {
+        render_level_meter(chunks[6], buf, track.velocity_meter, track.state);
+    } else {
+        let width = chunks[6].width.saturating_sub(1) as usize;
+        let muted = track.state == TrackState::Muted;
+        Paragraph::new(note_lane_lines(&track.lane, position.0, position.1, width, 1, muted)).render(chunks[6], buf);
+    }
+}
+
+/// Render a note lane `height` rows tall around the tick `now`
+pub(crate) fn note_lane_lines(
+    notes: &[LaneNote],
+    now: u64,
+    ppqn: u32,
+    width: usize,
+    height: usize,
+    muted: bool,
+) -> Vec<Line<'static>> {
+    let height = height.max(1);
+    let column_ticks = (ppqn as i64 / 4).max(1);
+    let start = now as i64 - (width / 4) as i64 * column_ticks;
+    let playhead = (width / 4).min(width.saturating_sub(1));
+
+    // Scale heights to the notes in view
+    let visible = |n: &&LaneNote| {
+        let end = (n.start_tick + n.duration) as i64;
+        end > start && (n.start_tick as i64) < start + width as i64 * column_ticks
+    };
+    let low = notes.iter().filter(visible).map(|n| n.note).min().unwrap_or(60);
+    let high = notes.iter().filter(visible).map(|n| n.note).max().unwrap_or(60);
+    let span = (high - low).max(LANE_MIN_SPAN) as usize;
+    let levels = height * LANE_BLOCKS.len();
+
+    // Filled eighths of the lane height per column (0 = empty)
+    let fills: Vec<usize> = (0..width)
+        .map(|column| {
+            let from = start + column as i64 * column_ticks;
+            let to = from + column_ticks;
+            notes
+                .iter()
+                .filter(|n| (n.start_tick as i64) < to && ((n.start_tick + n.duration) as i64) > from)
+                .map(|n| 1 + (n.note - low) as usize * (levels - 1) / span)
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    (0..height)
+        .rev()
+        .map(|row| {
+            let mut spans: Vec<Span<'static>> = Vec::new();
+            let mut run = String::new();
+            let mut run_style = Style::default();
+            for (column, &fill) in fills.iter().enumerate() {
+                let part = fill.saturating_sub(row * LANE_BLOCKS.len()).min(LANE_BLOCKS.len());
+                let symbol = match part {
+                    0 if column == playhead => '│',
+                    0 => ' ',
+                    part => LANE_BLOCKS[part - 1],
+                };
+                let style = match column.cmp(&playhead) {
+                    _ if muted => Style::default().fg(Color::DarkGray),
+                    std::cmp::Ordering::Less => Style::default().fg(Color::DarkGray),
+                    std::cmp::Ordering::Equal => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                    std::cmp::Ordering::Greater => Style::default().fg(Color::Cyan),
+                };
+                if style != run_style && !run.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut run), run_style));
+                }
+                run_style = style;
+                run.push(symbol);
+            }
+            if !run.is_empty() {
+                spans.push(Span::styled(run, run_style));
+            }
+            Line::from(spans)
+        })
+        .collect()
 }
 
 /// Render a level meter
@@ -200,7 +314,6 @@ fn render_level_meter(area: Rect, buf: &mut Buffer, level: u8, state: TrackState
         return;
     }
 
-    let filled = (level as usize * width) / 127;
     let color = match state {
         TrackState::Muted => Color::DarkGray,
         _ => {
@@ -214,8 +327,7 @@ fn render_level_meter(area: Rect, buf: &mut Buffer, level: u8, state: TrackState
         }
     };
 
-    let meter: String = "█".repeat(filled) + &"░".repeat(width - filled);
-    Paragraph::new(meter)
+    Paragraph::new(meter_bar(level, width))
         .style(Style::default().fg(color))
         .render(area, buf);
 }
@@ -278,13 +390,42 @@ impl Widget for TrackDetailWidget<'_> {
             .or(self.track.generator.as_ref())
             .map(|s| s.as_str())
             .unwrap_or("None");
-        let info_line = Line::from(vec![
+        let mut info = vec![
             Span::styled("Ch: ", Style::default().fg(Color::DarkGray)),
             Span::styled(format!("{}", self.track.channel), Style::default().fg(Color::Cyan)),
             Span::raw("  "),
             Span::styled("Source: ", Style::default().fg(Color::DarkGray)),
             Span::styled(source, Style::default().fg(Color::Green)),
-        ]);
+        ];
+        if let Some(program) = self.track.program {
+            info.push(Span::raw("  "));
+            info.push(Span::styled("Prog: ", Style::default().fg(Color::DarkGray)));
+            info.push(Span::styled(super::program_label(program), Style::default().fg(Color::Cyan)));
+        }
+        if let Some((low, high)) = self.track.range {
+            let (text, style) = if self.track.out_of_range {
+                ("! ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            } else {
+                ("", Style::default().fg(Color::Cyan))
+            };
+            info.push(Span::raw("  "));
+            info.push(Span::styled("Range: ", Style::default().fg(Color::DarkGray)));
+            info.push(Span::styled(
+                format!("{}{}-{}", text, super::note_name(low), super::note_name(high)),
+                style,
+            ));
+        }
+        if let Some(stats) = self.track.cc_thin {
+            info.push(Span::raw("  "));
+            info.push(Span::styled("CC: ", Style::default().fg(Color::DarkGray)));
+            info.push(Span::styled(thin_label(&stats), Style::default().fg(Color::Cyan)));
+        }
+        if !self.track.overrides.is_empty() {
+            info.push(Span::raw("  "));
+            info.push(Span::styled("Until next part: ", Style::default().fg(Color::DarkGray)));
+            info.push(Span::styled(override_label(&self.track.overrides), Style::default().fg(Color::Magenta)));
+        }
+        let info_line = Line::from(info);
         Paragraph::new(info_line).render(chunks[1], buf);
 
         // Playing notes
@@ -301,10 +442,21 @@ impl Widget for TrackDetailWidget<'_> {
     }
 }
 
+/// CC thinning summary: messages sent and the share dropped
+fn thin_label(stats: &ThinStats) -> String {
+    format!("{} sent, {:.0}% thinned", stats.passed, stats.drop_ratio() * 100.0)
+}
+
+/// Overrides in force, e.g. "solo, x2"
+fn override_label(overrides: &[TrackOverride]) -> String {
+    overrides.iter().map(|o| o.label()).collect::<Vec<_>>().join(", ")
+}
+
 /// Widget for displaying playing notes as a piano roll snippet
 pub struct NoteDisplayWidget {
     notes: Vec<u8>,
     range: (u8, u8),
+    instrument: Option<(u8, u8)>,
 }
 
 impl NoteDisplayWidget {
@@ -313,6 +465,7 @@ impl NoteDisplayWidget {
         Self {
             notes,
             range: (36, 96), // C2 to C7
+            instrument: None,
         }
     }
 
@@ -321,6 +474,17 @@ impl NoteDisplayWidget {
         self.range = (low, high);
         self
     }
+
+    /// Shade keys outside the instrument range and flag notes played there
+    pub fn instrument_range(mut self, low: u8, high: u8) -> Self {
+        self.instrument = Some((low, high));
+        self
+    }
+
+    /// Check if a note lies outside the instrument range
+    fn outside(&self, note: u8) -> bool {
+        matches!(self.instrument, Some((low, high)) if note < low || note > high)
+    }
 }
 
 impl Widget for NoteDisplayWidget {
@@ -333,22 +497,30 @@ impl Widget for NoteDisplayWidget {
             return;
         }
 
-        // Create display string
-        let mut display: Vec<char> = vec!['·'; width.min(range_size)];
+        // Create display cells, shading keys the instrument can't play
+        let cells = width.min(range_size);
+        let mut display: Vec<Span> = (0..cells)
+            .map(|pos| {
+                let note = low as usize + pos * range_size / cells;
+                if self.outside(note as u8) {
+                    Span::styled("░", Style::default().fg(Color::DarkGray))
+                } else {
+                    Span::styled("·", Style::default().fg(Color::Cyan))
+                }
+            })
+            .collect();
 
         for &note in &self.notes {
             if note >= low && note < high {
-                let pos = ((note - low) as usize * display.len()) / range_size;
-                if pos < display.len() {
-                    display[pos] = '█';
+                let pos = ((note - low) as usize * cells) / range_size;
+                if pos < cells {
+                    let color = if self.outside(note) { Color::Red } else { Color::Cyan };
+                    display[pos] = Span::styled("█", Style::default().fg(color));
                 }
             }
         }
 
-        let text: String = display.into_iter().collect();
-        Paragraph::new(text)
-            .style(Style::default().fg(Color::Cyan))
-            .render(area, buf);
+        Paragraph::new(Line::from(display)).render(area, buf);
     }
 }
 
@@ -380,6 +552,34 @@ mod tests {
         let track = TrackUiState::new(0, "Lead");
         let widget = TrackDetailWidget::new(&track);
         assert_eq!(widget.track.name, "Lead");
+
+        let stats = ThinStats { passed: 30, dropped: 90 };
+        assert_eq!(thin_label(&stats), "30 sent, 75% thinned");
+        assert_eq!(override_label(&[TrackOverride::Mute, TrackOverride::DoubleDensity]), "mute, x2");
+    }
+
+    #[test]
+    fn test_note_lane() {
+        let notes = [
+            LaneNote { start_tick: 0, duration: 6, note: 60 },
+            LaneNote { start_tick: 12, duration: 6, note: 72 },
+        ];
+        let text = |lines: &[Line]| -> Vec<String> {
+            lines
+                .iter()
+                .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+                .collect()
+        };
+
+        // Two sixteenths of history, the playhead, then what is coming
+        let lines = note_lane_lines(&notes, 6, 24, 8, 1, false);
+        assert_eq!(text(&lines), vec![" ▁│█    "]);
+        assert_eq!(lines[0].spans.last().unwrap().style.fg, Some(Color::Cyan));
+
+        // Two rows double the resolution
+        let lines = note_lane_lines(&notes, 6, 24, 8, 2, true);
+        assert_eq!(text(&lines), vec!["  │█    ", " ▁│█    "]);
+        assert!(lines[1].spans.iter().all(|s| s.style.fg == Some(Color::DarkGray)));
     }
 
     #[test]
@@ -390,5 +590,9 @@ mod tests {
 
         let widget = widget.range(48, 72);
         assert_eq!(widget.range, (48, 72));
+
+        let widget = widget.instrument_range(28, 64);
+        assert!(widget.outside(67));
+        assert!(!widget.outside(60));
     }
 }
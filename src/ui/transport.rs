@@ -7,7 +7,7 @@ use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::Span,
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Widget},
 };
 
@@ -53,7 +53,7 @@ impl Widget for TransportWidget<'_> {
                 Constraint::Length(12), // Tempo
                 Constraint::Length(2),  // Spacer
                 Constraint::Length(8),  // Time signature
-                Constraint::Min(0),     // Remaining
+                Constraint::Min(0),     // Current/next part
             ])
             .split(area);
 
@@ -69,12 +69,8 @@ impl Widget for TransportWidget<'_> {
         };
         Paragraph::new(indicator).style(style).render(chunks[0], buf);
 
-        // Position: Bar:Beat:Tick
-        let position = format!(
-            "{:03}:{:02}:{:02}",
-            self.state.bar, self.state.beat, self.state.tick
-        );
-        Paragraph::new(position)
+        // Position in the selected mode
+        Paragraph::new(self.state.position_text())
             .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
             .render(chunks[2], buf);
 
@@ -84,11 +80,15 @@ impl Widget for TransportWidget<'_> {
             .style(Style::default().fg(Color::Magenta))
             .render(chunks[4], buf);
 
-        // Time signature
-        let time_sig = format!("{}/{}", self.state.time_sig_num, self.state.time_sig_denom);
+        // Time signature, with a note when the metronome clicks
+        let click = if self.state.metronome { " ♩" } else { "" };
+        let time_sig = format!("{}/{}{}", self.state.time_sig_num, self.state.time_sig_denom, click);
         Paragraph::new(time_sig)
             .style(Style::default().fg(Color::White))
             .render(chunks[6], buf);
+
+        // Current/next part
+        Paragraph::new(Line::from(self.state.part_spans())).render(chunks[7], buf);
     }
 }
 